@@ -36,6 +36,8 @@ impl CompactJson for AccessTokenClaims {}
 pub struct ValidatedAccessToken {
     pub access_token: AccessTokenClaims,
     pub permissions: Vec<String>,
+    /// The tenant of the user, if the token carries one
+    pub tenant: Option<String>,
 }
 
 impl From<ValidatedAccessToken> for UserDetails {
@@ -43,6 +45,7 @@ impl From<ValidatedAccessToken> for UserDetails {
         Self {
             id: token.access_token.sub,
             permissions: token.permissions,
+            tenant: token.tenant,
         }
     }
 }
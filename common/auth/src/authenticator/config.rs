@@ -45,6 +45,7 @@ impl AuthenticatorConfig {
                     required_audience: None,
                     group_selector: None,
                     group_mappings: Default::default(),
+                    tenant_selector: None,
                     tls_insecure: false,
                     tls_ca_certificates: Default::default(),
                 })
@@ -99,6 +100,13 @@ pub struct SingleAuthenticatorClientConfig {
     /// Enable additional TLS certificates for communication with the SSO server
     #[arg(env = "AUTHENTICATOR_OIDC_TLS_CA_CERTIFICATES", long = "authentication-tls-certificate", action = ArgAction::Append)]
     pub tls_ca_certificates: Vec<PathBuf>,
+
+    /// JSON path extracting the tenant from the access token, like `$.org` or `$.iss`
+    #[arg(
+        env = "AUTHENTICATOR_OIDC_TENANT_SELECTOR",
+        long = "authentication-tenant-selector"
+    )]
+    pub tenant_selector: Option<String>,
 }
 
 /// Configuration for OIDC client used to authenticate on the server side
@@ -136,6 +144,12 @@ pub struct AuthenticatorClientConfig {
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub group_mappings: HashMap<String, Vec<String>>,
 
+    /// JSON path extracting the tenant from the access token, using the first string it finds.
+    ///
+    /// Users of different tenants are isolated from each other, if tenant isolation is enabled.
+    #[serde(default)]
+    pub tenant_selector: Option<String>,
+
     /// Ignore TLS checks when contacting the issuer
     #[serde(default)]
     pub tls_insecure: bool,
@@ -158,6 +172,7 @@ impl SingleAuthenticatorClientConfig {
                 scope_mappings: default_scope_mappings(),
                 group_selector: None,
                 group_mappings: Default::default(),
+                tenant_selector: self.tenant_selector.clone(),
                 additional_permissions: Default::default(),
             })
     }
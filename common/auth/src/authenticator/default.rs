@@ -11,8 +11,12 @@ pub const DEFAULT_SCOPE_MAPPINGS: &[(&str, &[&str])] = &[
         "create:document",
         &[
             "create.advisory",
+            "create.annotation",
+            "create.assignment",
+            "create.comment",
             "create.importer",
             "create.metadata",
+            "create.policy",
             "create.sbom",
             "create.weakness",
             "upload.dataset",
@@ -23,9 +27,16 @@ pub const DEFAULT_SCOPE_MAPPINGS: &[(&str, &[&str])] = &[
         &[
             "ai",
             "read.advisory",
+            "read.assignment",
+            "read.audit",
             "read.importer",
+            "read.job",
             "read.metadata",
+            "read.policy",
             "read.sbom",
+            "read.settings",
+            "read.telemetry",
+            "read.triage",
             "read.weakness",
         ],
     ),
@@ -33,9 +44,15 @@ pub const DEFAULT_SCOPE_MAPPINGS: &[(&str, &[&str])] = &[
         "update:document",
         &[
             "update.advisory",
+            "update.annotation",
+            "update.assignment",
+            "update.comment",
             "update.importer",
             "update.metadata",
+            "update.policy",
             "update.sbom",
+            "update.settings",
+            "update.triage",
             "update.weakness",
         ],
     ),
@@ -43,8 +60,11 @@ pub const DEFAULT_SCOPE_MAPPINGS: &[(&str, &[&str])] = &[
         "delete:document",
         &[
             "delete.advisory",
+            "delete.annotation",
+            "delete.comment",
             "delete.importer",
             "delete.metadata",
+            "delete.policy",
             "delete.sbom",
             "delete.vulnerability",
             "delete.weakness",
@@ -192,6 +192,19 @@ async fn create_client(config: AuthenticatorClientConfig) -> anyhow::Result<Auth
         })
         .transpose()?;
 
+    let tenant_selector = config
+        .tenant_selector
+        .map(|selector| {
+            parse_json_path(&selector).map_err(|err| {
+                anyhow!(
+                    "Unable to parse JSON path tenant selector for client '{}' / '{}': {err}",
+                    config.issuer_url,
+                    client.client_id,
+                )
+            })
+        })
+        .transpose()?;
+
     Ok(AuthenticatorClient {
         client,
         audience: config.required_audience,
@@ -199,6 +212,7 @@ async fn create_client(config: AuthenticatorClientConfig) -> anyhow::Result<Auth
         additional_permissions: config.additional_permissions,
         group_selector,
         group_mappings: config.group_mappings,
+        tenant_selector,
     })
 }
 
@@ -210,6 +224,7 @@ pub struct AuthenticatorClient {
     additional_permissions: Vec<String>,
     group_selector: Option<JpQuery>,
     group_mappings: HashMap<String, Vec<String>>,
+    tenant_selector: Option<JpQuery>,
 }
 
 impl AuthenticatorClient {
@@ -225,12 +240,24 @@ impl AuthenticatorClient {
 
         permissions.extend(Self::map_groups(groups, &self.group_mappings));
 
+        let tenant = self
+            .tenant_selector
+            .as_ref()
+            .and_then(|selector| Self::extract_tenant(&access_token, selector));
+
         ValidatedAccessToken {
             access_token,
             permissions,
+            tenant,
         }
     }
 
+    /// Extract the tenant from the access token, including its standard claims
+    fn extract_tenant(access_token: &AccessTokenClaims, selector: &JpQuery) -> Option<String> {
+        let value = serde_json::to_value(access_token).ok()?;
+        Self::extract_groups(&value, selector).into_iter().next()
+    }
+
     /// Extract the groups from the value/access token
     fn extract_groups(value: &Value, selector: &JpQuery) -> Vec<String> {
         js_path_process(selector, value)
@@ -337,4 +364,26 @@ mod test {
         let groups = AuthenticatorClient::extract_groups(&value, &selector);
         assert_eq!(&groups, &["manager", "reader"]);
     }
+
+    #[test]
+    fn test_tenant() {
+        let token: AccessTokenClaims = serde_json::from_str(
+            r#"{
+  "sub": "dfbf2b67-eb5e-44e5-8d70-581bf6fa4eff",
+  "iss": "https://foo/bar",
+  "org": "acme",
+  "exp": 1707314932,
+  "iat": 1707311332
+}"#,
+        )
+        .unwrap();
+
+        let tenant =
+            |path| AuthenticatorClient::extract_tenant(&token, &parse_json_path(path).unwrap());
+
+        assert_eq!(tenant("$.org").as_deref(), Some("acme"));
+        // standard claims can be used as well
+        assert_eq!(tenant("$.iss").as_deref(), Some("https://foo/bar"));
+        assert_eq!(tenant("$.team"), None);
+    }
 }
@@ -31,6 +31,8 @@ use crate::authenticator::error::AuthorizationError;
 pub struct UserDetails {
     pub id: String,
     pub permissions: Vec<String>,
+    /// The tenant of the user, extracted using the tenant selector of the client
+    pub tenant: Option<String>,
 }
 
 impl UserDetails {
@@ -6,6 +6,7 @@ pub mod authenticator;
 pub mod authorizer;
 pub mod client;
 pub mod devmode;
+pub mod tenant;
 
 #[cfg(feature = "swagger")]
 pub mod swagger_ui;
@@ -100,9 +100,58 @@ permission! {
         #[strum(serialize = "delete.metadata")]
         DeleteMetadata,
 
+        #[strum(serialize = "create.assignment")]
+        CreateAssignment,
+        #[strum(serialize = "read.assignment")]
+        ReadAssignment,
+        #[strum(serialize = "update.assignment")]
+        UpdateAssignment,
+
+        #[strum(serialize = "create.annotation")]
+        CreateAnnotation,
+        #[strum(serialize = "update.annotation")]
+        UpdateAnnotation,
+        #[strum(serialize = "delete.annotation")]
+        DeleteAnnotation,
+
+        #[strum(serialize = "create.comment")]
+        CreateComment,
+        #[strum(serialize = "update.comment")]
+        UpdateComment,
+        #[strum(serialize = "delete.comment")]
+        DeleteComment,
+
+        #[strum(serialize = "read.triage")]
+        ReadTriage,
+        #[strum(serialize = "update.triage")]
+        UpdateTriage,
+
+        #[strum(serialize = "create.policy")]
+        CreatePolicy,
+        #[strum(serialize = "read.policy")]
+        ReadPolicy,
+        #[strum(serialize = "update.policy")]
+        UpdatePolicy,
+        #[strum(serialize = "delete.policy")]
+        DeletePolicy,
+
+        #[strum(serialize = "read.audit")]
+        ReadAudit,
+
         #[strum(serialize = "upload.dataset")]
         UploadDataset,
 
+        #[strum(serialize = "read.job")]
+        ReadJob,
+
+        #[strum(serialize = "read.telemetry")]
+        ReadTelemetry,
+
+        #[strum(serialize = "read.settings")]
+        ReadSettings,
+        #[strum(serialize = "update.settings")]
+        UpdateSettings,
+
         #[strum(serialize = "ai")]
         Ai,
 
@@ -0,0 +1,38 @@
+//! The tenant of a user, when isolating tenants sharing one deployment.
+
+#[cfg(feature = "actix")]
+use crate::authenticator::{error::AuthorizationError, user::UserInformation};
+
+/// The label carrying the tenant of a document.
+pub const TENANT_LABEL: &str = "tenant";
+
+/// Whether tenants are isolated from each other.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TenantIsolation(pub bool);
+
+/// The tenant of the user performing a request, `None` if tenants are not isolated.
+///
+/// Fails if tenants are isolated, but the user is anonymous or has no tenant.
+#[cfg(feature = "actix")]
+pub fn tenant_of(req: &actix_web::HttpRequest) -> Result<Option<String>, AuthorizationError> {
+    use actix_web::{HttpMessage, web};
+
+    let isolation = req
+        .app_data::<web::Data<TenantIsolation>>()
+        .map(|isolation| **isolation)
+        .unwrap_or_default();
+    if !isolation.0 {
+        return Ok(None);
+    }
+
+    match req.extensions().get::<UserInformation>() {
+        Some(UserInformation::Authenticated(details)) => match &details.tenant {
+            Some(tenant) => Ok(Some(tenant.clone())),
+            None => {
+                log::debug!("User {} has no tenant", details.id);
+                Err(AuthorizationError::Failed)
+            }
+        },
+        _ => Err(AuthorizationError::Failed),
+    }
+}
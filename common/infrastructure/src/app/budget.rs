@@ -0,0 +1,146 @@
+use actix_web::{
+    Error, HttpResponse, ResponseError,
+    body::MessageBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform, forward_ready},
+};
+use futures::future::{LocalBoxFuture, Ready, ready};
+use std::sync::Arc;
+use trustify_common::{
+    db::budget::{QueryBudget, QueryStats},
+    error::ErrorInformation,
+};
+
+/// The request exceeded its budget of SQL statements and was aborted.
+#[derive(Debug, thiserror::Error)]
+#[error("Request exceeded the query budget: {0}")]
+pub struct QueryBudgetExceeded(String);
+
+impl ResponseError for QueryBudgetExceeded {
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::InternalServerError().json(ErrorInformation::new("QueryBudgetExceeded", self))
+    }
+}
+
+/// Account the SQL statements issued while handling a request.
+///
+/// The statistics get logged for every request. A request exceeding the budget gets aborted.
+#[derive(Clone, Debug, Default)]
+pub struct QueryAccounting {
+    budget: QueryBudget,
+}
+
+impl QueryAccounting {
+    pub fn new(budget: QueryBudget) -> Self {
+        Self { budget }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for QueryAccounting
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = QueryAccountingMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(QueryAccountingMiddleware {
+            service,
+            budget: self.budget,
+        }))
+    }
+}
+
+pub struct QueryAccountingMiddleware<S> {
+    service: S,
+    budget: QueryBudget,
+}
+
+impl<S, B> Service<ServiceRequest> for QueryAccountingMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let stats = Arc::new(QueryStats::new(self.budget));
+        let request = format!("{} {}", req.method(), req.path());
+        let response = stats.clone().scope(self.service.call(req));
+
+        Box::pin(async move {
+            tokio::select! {
+                biased;
+
+                _ = stats.exceeded() => {
+                    // dropping the response future aborts handling the request
+                    log::warn!("{request}: aborted, exceeding the query budget: {stats}");
+                    Err(QueryBudgetExceeded(stats.to_string()).into())
+                }
+                response = response => {
+                    log::debug!("{request}: {stats}");
+                    response
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::{App, HttpResponse, http::StatusCode, test, web};
+    use std::time::Duration;
+
+    #[actix_web::test]
+    async fn within_budget() {
+        let app = test::init_service(
+            App::new()
+                .wrap(QueryAccounting::new(QueryBudget {
+                    max_statements: 1,
+                    max_duration: None,
+                }))
+                .route("/", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let response = test::call_service(&app, test::TestRequest::get().to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn exceeding_budget() {
+        let app = test::init_service(
+            App::new()
+                .wrap(QueryAccounting::new(QueryBudget {
+                    max_statements: 0,
+                    max_duration: Some(Duration::from_millis(1)),
+                }))
+                .route(
+                    "/",
+                    web::get().to(|| async {
+                        // simulate a slow statement, never finishing the request
+                        trustify_common::db::budget::record(Duration::from_millis(2));
+                        std::future::pending::<HttpResponse>().await
+                    }),
+                ),
+        )
+        .await;
+
+        let response = test::try_call_service(&app, test::TestRequest::get().to_request()).await;
+        let err = response.expect_err("must be aborted");
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
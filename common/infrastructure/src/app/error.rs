@@ -0,0 +1,81 @@
+use actix_web::{
+    HttpResponse,
+    body::{BodySize, MessageBody},
+    dev::ServiceResponse,
+    http::header,
+    middleware::{ErrorHandlerResponse, ErrorHandlers},
+};
+use trustify_common::error::ErrorInformation;
+
+/// Create error handlers, ensuring every error response carries an [`ErrorInformation`] body.
+///
+/// Error responses which already have a body are passed on unchanged.
+pub fn error_handlers<B: MessageBody + 'static>() -> ErrorHandlers<B> {
+    ErrorHandlers::new().default_handler(error_information)
+}
+
+fn error_information<B: MessageBody + 'static>(
+    res: ServiceResponse<B>,
+) -> actix_web::Result<ErrorHandlerResponse<B>> {
+    if !matches!(
+        res.response().body().size(),
+        BodySize::None | BodySize::Sized(0)
+    ) {
+        return Ok(ErrorHandlerResponse::Response(res.map_into_left_body()));
+    }
+
+    let status = res.status();
+    let (req, original) = res.into_parts();
+
+    let mut response = HttpResponse::build(status).json(ErrorInformation::new(
+        status.canonical_reason().unwrap_or("Error"),
+        "",
+    ));
+
+    // keep headers, like the authentication challenge or the range of the content
+    for (name, value) in original.headers() {
+        if name != header::CONTENT_TYPE && name != header::CONTENT_LENGTH {
+            response.headers_mut().append(name.clone(), value.clone());
+        }
+    }
+
+    Ok(ErrorHandlerResponse::Response(
+        ServiceResponse::new(req, response).map_into_right_body(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::{App, http::StatusCode, test, web};
+
+    #[actix_web::test]
+    async fn empty_body() {
+        let app = test::init_service(App::new().wrap(error_handlers()).route(
+            "/",
+            web::get().to(|| async { HttpResponse::NotFound().finish() }),
+        ))
+        .await;
+
+        let response = test::call_service(&app, test::TestRequest::get().to_request()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let error: ErrorInformation = test::read_body_json(response).await;
+        assert_eq!(error, ErrorInformation::new("Not Found", ""));
+    }
+
+    #[actix_web::test]
+    async fn existing_body() {
+        let app = test::init_service(App::new().wrap(error_handlers()).route(
+            "/",
+            web::get().to(|| async {
+                HttpResponse::Conflict().json(ErrorInformation::new("Conflict", "Already exists"))
+            }),
+        ))
+        .await;
+
+        let response = test::call_service(&app, test::TestRequest::get().to_request()).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let error: ErrorInformation = test::read_body_json(response).await;
+        assert_eq!(error, ErrorInformation::new("Conflict", "Already exists"));
+    }
+}
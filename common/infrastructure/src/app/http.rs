@@ -1,5 +1,5 @@
 use crate::{
-    app::{AppOptions, new_app},
+    app::{AppOptions, budget::QueryAccounting, new_app},
     endpoint::Endpoint,
     otel::{Metrics, Tracing},
 };
@@ -8,6 +8,11 @@ use actix_tls::{accept::openssl::reexports::SslAcceptor, connect::openssl::reexp
 use actix_web::{
     App, HttpResponse, HttpServer,
     dev::{ServiceFactory, ServiceRequest},
+    http::{
+        Uri,
+        header::{self, HeaderName, HeaderValue},
+    },
+    middleware::DefaultHeaders,
     web::{self, JsonConfig},
 };
 use actix_web_opentelemetry::{RequestMetrics, RequestTracing};
@@ -23,13 +28,14 @@ use std::{
     path::PathBuf,
     str::FromStr,
     sync::Arc,
+    time::Duration,
 };
 use trustify_auth::{
     authenticator::Authenticator,
     authorizer::Authorizer,
     swagger_ui::{SwaggerUiOidc, swagger_ui_with_auth},
 };
-use trustify_common::model::BinaryByteSize;
+use trustify_common::{db::budget::QueryBudget, model::BinaryByteSize};
 use utoipa::openapi::Info;
 use utoipa_actix_web::AppExt;
 use utoipa_rapidoc::RapiDoc;
@@ -177,6 +183,72 @@ where
     #[arg(id = "http-disable-log", long, env = "HTTP_SERVER_DISABLE_LOG")]
     pub disable_log: bool,
 
+    /// The maximum number of SQL statements a single request may issue, before it gets aborted.
+    /// Zero meaning "unlimited".
+    #[arg(
+        id = "http-server-query-limit",
+        long,
+        env = "HTTP_SERVER_QUERY_LIMIT",
+        default_value_t = 0
+    )]
+    pub query_limit: u64,
+
+    /// The maximum time a single request may spend executing SQL statements, before it gets
+    /// aborted (humantime).
+    #[arg(
+        id = "http-server-query-time-limit",
+        long,
+        env = "HTTP_SERVER_QUERY_TIME_LIMIT"
+    )]
+    pub query_time_limit: Option<humantime::Duration>,
+
+    /// The origins allowed to make cross-origin requests, like `https://example.com`, or `*` for
+    /// any origin. Defaults to any origin.
+    #[arg(
+        id = "http-server-cors-allowed-origins",
+        long,
+        env = "HTTP_SERVER_CORS_ALLOWED_ORIGINS",
+        value_delimiter = ','
+    )]
+    pub cors_allowed_origins: Vec<String>,
+
+    /// The headers allowed in cross-origin requests. Defaults to any header.
+    #[arg(
+        id = "http-server-cors-allowed-headers",
+        long,
+        env = "HTTP_SERVER_CORS_ALLOWED_HEADERS",
+        value_delimiter = ','
+    )]
+    pub cors_allowed_headers: Vec<String>,
+
+    /// Send the standard security headers: `X-Content-Type-Options`, `X-Frame-Options`, and
+    /// `Referrer-Policy`
+    #[arg(
+        id = "http-server-security-headers",
+        long,
+        env = "HTTP_SERVER_SECURITY_HEADERS",
+        default_value_t = false,
+        action = clap::ArgAction::Set
+    )]
+    pub security_headers: bool,
+
+    /// The max age of HTTP Strict Transport Security (humantime), sending the
+    /// `Strict-Transport-Security` header if set
+    #[arg(
+        id = "http-server-hsts-max-age",
+        long,
+        env = "HTTP_SERVER_HSTS_MAX_AGE"
+    )]
+    pub hsts_max_age: Option<humantime::Duration>,
+
+    /// The value of the `Content-Security-Policy` header, not sent if unset
+    #[arg(
+        id = "http-server-content-security-policy",
+        long,
+        env = "HTTP_SERVER_CONTENT_SECURITY_POLICY"
+    )]
+    pub content_security_policy: Option<String>,
+
     #[arg(skip)]
     _marker: Marker<E>,
 }
@@ -212,6 +284,13 @@ where
             tls_key_file: None,
             tls_certificate_file: None,
             disable_log: false,
+            query_limit: 0,
+            query_time_limit: None,
+            cors_allowed_origins: vec![],
+            cors_allowed_headers: vec![],
+            security_headers: false,
+            hsts_max_age: None,
+            content_security_policy: None,
             _marker: Default::default(),
         }
     }
@@ -248,7 +327,25 @@ where
             .workers(value.workers)
             .bind(addr)
             .request_limit(value.request_limit.0.0 as _)
-            .json_limit(value.json_limit.0.0 as _);
+            .json_limit(value.json_limit.0.0 as _)
+            .query_budget(QueryBudget {
+                max_statements: value.query_limit,
+                max_duration: value.query_time_limit.map(Into::into),
+            })
+            .security_headers(SecurityHeaders {
+                standard: value.security_headers,
+                hsts_max_age: value.hsts_max_age.map(Into::into),
+                content_security_policy: value
+                    .content_security_policy
+                    .map(|csp| HeaderValue::from_str(&csp))
+                    .transpose()
+                    .context("parse content security policy")?,
+            });
+
+        if !value.cors_allowed_origins.is_empty() || !value.cors_allowed_headers.is_empty() {
+            let cors = CorsConfig::new(value.cors_allowed_origins, value.cors_allowed_headers)?;
+            result = result.cors(move || cors.build());
+        }
 
         if value.tls_enabled {
             result = result.tls(TlsConfiguration {
@@ -265,6 +362,119 @@ where
     }
 }
 
+/// The restrictions of cross-origin requests, allowing anything that isn't restricted.
+#[derive(Clone, Debug, Default)]
+pub struct CorsConfig {
+    /// The allowed origins, any origin if empty.
+    allowed_origins: Vec<String>,
+    /// The allowed request headers, any header if empty.
+    allowed_headers: Vec<HeaderName>,
+}
+
+impl CorsConfig {
+    /// Create a new configuration, failing on invalid origins or headers.
+    ///
+    /// An origin of `*` allows any origin.
+    pub fn new(allowed_origins: Vec<String>, allowed_headers: Vec<String>) -> anyhow::Result<Self> {
+        let allowed_origins = match allowed_origins.iter().any(|origin| origin == "*") {
+            true => vec![],
+            false => allowed_origins,
+        };
+
+        for origin in &allowed_origins {
+            Uri::from_str(origin).with_context(|| format!("parse CORS origin '{origin}'"))?;
+        }
+
+        let allowed_headers = allowed_headers
+            .iter()
+            .map(|header| {
+                HeaderName::from_str(header)
+                    .with_context(|| format!("parse CORS header '{header}'"))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            allowed_origins,
+            allowed_headers,
+        })
+    }
+
+    /// Build the middleware, handling CORS requests.
+    pub fn build(&self) -> Cors {
+        let mut cors = Cors::default()
+            .allow_any_method()
+            .expose_any_header()
+            .max_age(3600)
+            // leave rejecting the response to the browser, like for same-origin requests
+            .block_on_origin_mismatch(false);
+
+        if self.allowed_origins.is_empty() {
+            cors = cors.allow_any_origin();
+        }
+        for origin in &self.allowed_origins {
+            cors = cors.allowed_origin(origin);
+        }
+
+        match self.allowed_headers.is_empty() {
+            true => cors.allow_any_header(),
+            false => cors.allowed_headers(self.allowed_headers.clone()),
+        }
+    }
+}
+
+/// Headers, hardening the use of responses by browsers.
+#[derive(Clone, Debug, Default)]
+pub struct SecurityHeaders {
+    /// Send the standard headers: `X-Content-Type-Options`, `X-Frame-Options`, and
+    /// `Referrer-Policy`.
+    pub standard: bool,
+    /// The max age of HTTP Strict Transport Security, not sent if unset.
+    pub hsts_max_age: Option<Duration>,
+    /// The value of the `Content-Security-Policy` header, not sent if unset.
+    pub content_security_policy: Option<HeaderValue>,
+}
+
+impl SecurityHeaders {
+    /// Create the middleware adding the headers, `None` if there are none to add.
+    fn middleware(&self) -> Option<DefaultHeaders> {
+        let mut headers = vec![];
+
+        if self.standard {
+            headers.extend([
+                (
+                    header::X_CONTENT_TYPE_OPTIONS,
+                    HeaderValue::from_static("nosniff"),
+                ),
+                (header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY")),
+                (
+                    header::REFERRER_POLICY,
+                    HeaderValue::from_static("strict-origin-when-cross-origin"),
+                ),
+            ]);
+        }
+
+        if let Some(max_age) = self.hsts_max_age {
+            let value = format!("max-age={}; includeSubDomains", max_age.as_secs());
+            headers.push((
+                header::STRICT_TRANSPORT_SECURITY,
+                HeaderValue::try_from(value).expect("must be a valid header value"),
+            ));
+        }
+
+        if let Some(csp) = &self.content_security_policy {
+            headers.push((header::CONTENT_SECURITY_POLICY, csp.clone()));
+        }
+
+        (!headers.is_empty()).then(|| {
+            headers
+                .into_iter()
+                .fold(DefaultHeaders::new(), |middleware, header| {
+                    middleware.add(header)
+                })
+        })
+    }
+}
+
 pub type ConfiguratorFn =
     dyn Fn(&mut utoipa_actix_web::service_config::ServiceConfig) + Send + Sync;
 
@@ -278,6 +488,7 @@ pub struct HttpServerBuilder {
     tls: Option<TlsConfiguration>,
 
     cors_factory: Option<Arc<dyn Fn() -> Cors + Send + Sync>>,
+    security_headers: SecurityHeaders,
     authenticator: Option<Arc<Authenticator>>,
     authorizer: Option<Authorizer>,
     swagger_ui_oidc: Option<Arc<SwaggerUiOidc>>,
@@ -287,6 +498,7 @@ pub struct HttpServerBuilder {
     request_limit: Option<usize>,
     tracing: Tracing,
     metrics: Metrics,
+    query_budget: QueryBudget,
 
     disable_log: bool,
 
@@ -319,6 +531,7 @@ impl HttpServerBuilder {
             bind: Bind::Address(DEFAULT_ADDR),
             tls: None,
             cors_factory: Some(Arc::new(Cors::permissive)),
+            security_headers: SecurityHeaders::default(),
             authenticator: None,
             authorizer: None,
             swagger_ui_oidc: None,
@@ -327,6 +540,7 @@ impl HttpServerBuilder {
             request_limit: None,
             tracing: Tracing::default(),
             metrics: Metrics::default(),
+            query_budget: QueryBudget::default(),
             openapi_info: None,
             disable_log: false,
         }
@@ -348,6 +562,14 @@ impl HttpServerBuilder {
         self
     }
 
+    /// Set the security headers, added to all responses.
+    ///
+    /// The default is to not add any.
+    pub fn security_headers(mut self, security_headers: SecurityHeaders) -> Self {
+        self.security_headers = security_headers;
+        self
+    }
+
     pub fn default_authenticator(mut self, authenticator: Option<Arc<Authenticator>>) -> Self {
         self.authenticator = authenticator;
         self
@@ -424,6 +646,12 @@ impl HttpServerBuilder {
         self
     }
 
+    /// Set the budget of SQL statements a single request may issue.
+    pub fn query_budget(mut self, query_budget: QueryBudget) -> Self {
+        self.query_budget = query_budget;
+        self
+    }
+
     pub fn disable_log(mut self, disable_log: bool) -> Self {
         self.disable_log = disable_log;
         self
@@ -462,7 +690,7 @@ impl HttpServerBuilder {
 
             let metrics = match self.metrics {
                 Metrics::Disabled => None,
-                Metrics::Enabled => Some(RequestMetrics::default()),
+                Metrics::Enabled | Metrics::Prometheus => Some(RequestMetrics::default()),
             };
 
             log::debug!(
@@ -473,6 +701,7 @@ impl HttpServerBuilder {
 
             let mut app = new_app(AppOptions {
                 cors,
+                default_headers: self.security_headers.middleware(),
                 authenticator: self.authenticator.clone(),
                 authorizer: self
                     .authorizer
@@ -481,6 +710,7 @@ impl HttpServerBuilder {
                 logger,
                 tracing_logger,
                 metrics,
+                query_accounting: Some(QueryAccounting::new(self.query_budget)),
             })
             .app_data(json)
             .into_utoipa_app();
@@ -610,6 +840,7 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
+    use actix_web::{dev::ServiceResponse, test};
 
     #[derive(Debug)]
     pub struct MockEndpoint;
@@ -623,4 +854,71 @@ mod test {
     fn default_config_converts() {
         HttpServerBuilder::try_from(HttpServerConfig::<MockEndpoint>::default()).unwrap();
     }
+
+    #[actix_web::test]
+    async fn cors_and_security_headers() {
+        let builder = HttpServerBuilder::try_from(HttpServerConfig::<MockEndpoint> {
+            cors_allowed_origins: vec!["https://example.com".into()],
+            security_headers: true,
+            hsts_max_age: Some(Duration::from_secs(3600).into()),
+            content_security_policy: Some("default-src 'self'".into()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let app = test::init_service(
+            new_app(AppOptions {
+                cors: builder.cors_factory.as_ref().map(|factory| factory()),
+                default_headers: builder.security_headers.middleware(),
+                ..Default::default()
+            })
+            .route("/", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let get = |response: &ServiceResponse<_>, name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToString::to_string)
+        };
+
+        // an allowed origin
+
+        let request = test::TestRequest::get()
+            .insert_header((header::ORIGIN, "https://example.com"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(
+            get(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN).as_deref(),
+            Some("https://example.com")
+        );
+        assert_eq!(
+            get(&response, header::X_CONTENT_TYPE_OPTIONS).as_deref(),
+            Some("nosniff")
+        );
+        assert_eq!(
+            get(&response, header::STRICT_TRANSPORT_SECURITY).as_deref(),
+            Some("max-age=3600; includeSubDomains")
+        );
+        assert_eq!(
+            get(&response, header::CONTENT_SECURITY_POLICY).as_deref(),
+            Some("default-src 'self'")
+        );
+
+        // any other origin
+
+        let request = test::TestRequest::get()
+            .insert_header((header::ORIGIN, "https://example.org"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(get(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN), None);
+    }
+
+    #[test]
+    fn invalid_cors_config() {
+        assert!(CorsConfig::new(vec!["not an origin".into()], vec![]).is_err());
+        assert!(CorsConfig::new(vec![], vec!["not a header".into()]).is_err());
+    }
 }
@@ -1,3 +1,5 @@
+pub mod budget;
+pub mod error;
 pub mod http;
 
 use actix_cors::Cors;
@@ -5,11 +7,12 @@ use actix_web::{
     App, Error,
     body::MessageBody,
     dev::{ServiceFactory, ServiceRequest, ServiceResponse},
-    middleware::{Compress, Logger},
+    middleware::{Compress, DefaultHeaders, Logger},
 };
 use actix_web_extras::middleware::Condition;
 use actix_web_httpauth::{extractors::bearer::BearerAuth, middleware::HttpAuthentication};
 use actix_web_opentelemetry::{RequestMetrics, RequestTracing};
+use budget::QueryAccounting;
 use futures::{FutureExt, future::LocalBoxFuture};
 use std::sync::Arc;
 use trustify_auth::{authenticator::Authenticator, authorizer::Authorizer};
@@ -17,11 +20,13 @@ use trustify_auth::{authenticator::Authenticator, authorizer::Authorizer};
 #[derive(Default)]
 pub struct AppOptions {
     pub cors: Option<Cors>,
+    pub default_headers: Option<DefaultHeaders>,
     pub authenticator: Option<Arc<Authenticator>>,
     pub authorizer: Authorizer,
     pub logger: Option<Logger>,
     pub tracing_logger: Option<RequestTracing>,
     pub metrics: Option<RequestMetrics>,
+    pub query_accounting: Option<QueryAccounting>,
 }
 
 /// create a new authenticator
@@ -69,12 +74,18 @@ pub fn new_app(
     // following lines, read them from end to start! Middleware for services will be executed after
     // the middleware here.
     App::new()
+        // Give error responses without a body a structured one
+        .wrap(error::error_handlers())
+        // Account the SQL statements issued by the request, might abort it
+        .wrap(Condition::from_option(options.query_accounting))
         // Handle authentication, might fail and return early
         .wrap(new_auth(options.authenticator))
         // Handle authorization
         .app_data(actix_web::web::Data::new(options.authorizer))
         // Handle CORS requests, this might finish early and not pass requests to the next entry
         .wrap(Condition::from_option(options.cors))
+        // Add headers to all responses, including those of CORS requests
+        .wrap(Condition::from_option(options.default_headers))
         // Next, record metrics for the request (should never fail)
         .wrap(Condition::from_option(options.metrics))
         // Compress everything
@@ -1,6 +1,6 @@
 use crate::{
     health::{Checks, HealthChecks},
-    otel::{Metrics as OtelMetrics, Tracing, init_metrics, init_tracing},
+    otel::{Metrics as OtelMetrics, Tracing, init_metrics, init_tracing, prometheus_registry},
 };
 use actix_web::{
     App, HttpRequest, HttpResponse, HttpServer, Responder, http::uri::Builder, middleware::Logger,
@@ -9,6 +9,7 @@ use actix_web::{
 use anyhow::Context;
 use futures::future::select_all;
 use opentelemetry::metrics::Meter;
+use prometheus::{Encoder, TextEncoder};
 use std::{future::Future, pin::Pin, sync::Arc};
 use tokio::signal;
 
@@ -40,6 +41,9 @@ pub struct InfrastructureConfig {
     /// Enable metrics
     #[arg(long, env, default_value_t = OtelMetrics::Disabled)]
     pub metrics: OtelMetrics,
+    /// Endpoint of the OTLP collector, receiving traces and metrics
+    #[arg(long, env = "OTEL_EXPORTER_OTLP_ENDPOINT")]
+    pub otlp_endpoint: Option<String>,
 }
 
 impl Default for InfrastructureConfig {
@@ -50,6 +54,7 @@ impl Default for InfrastructureConfig {
             infrastructure_workers: 1,
             tracing: Tracing::Disabled,
             metrics: OtelMetrics::Disabled,
+            otlp_endpoint: None,
         }
     }
 }
@@ -68,8 +73,11 @@ pub struct MainContext<T> {
 pub async fn index(req: HttpRequest) -> HttpResponse {
     let conn = req.connection_info();
 
+    let metrics = prometheus_registry().map(|_| "/metrics");
+
     let apis = ["/health/live", "/health/ready", "/health/startup"]
         .into_iter()
+        .chain(metrics)
         .filter_map(|api| {
             Builder::new()
                 .authority(conn.host())
@@ -96,6 +104,24 @@ async fn readiness(health: web::Data<HealthChecks>) -> impl Responder + use<> {
     run_checks(&health.readiness).await
 }
 
+/// Render the metrics in the Prometheus text format.
+async fn metrics() -> HttpResponse {
+    let Some(registry) = prometheus_registry() else {
+        return HttpResponse::NotFound().finish();
+    };
+
+    let encoder = TextEncoder::new();
+    match encoder.encode_to_string(&registry.gather()) {
+        Ok(body) => HttpResponse::Ok()
+            .content_type(encoder.format_type())
+            .body(body),
+        Err(err) => {
+            log::warn!("Failed to encode metrics: {err}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
 async fn run_checks(checks: &Checks) -> impl Responder + use<> {
     let checks = checks.run().await;
 
@@ -158,6 +184,7 @@ impl Infrastructure {
                         .service(web::resource("/ready").to(readiness))
                         .service(web::resource("/startup").to(startup)),
                 )
+                .service(web::resource("/metrics").to(metrics))
                 .configure(|c| configurator(c))
         });
 
@@ -194,8 +221,9 @@ impl Infrastructure {
         M: FnOnce(MainContext<D>) -> MFut,
         MFut: Future<Output = anyhow::Result<()>>,
     {
-        init_tracing(id, self.config.tracing);
-        init_metrics(id, self.config.metrics);
+        let endpoint = self.config.otlp_endpoint.as_deref();
+        init_tracing(id, self.config.tracing, endpoint);
+        init_metrics(id, self.config.metrics, endpoint);
 
         let init_data = init(InitContext {
             health: self.health.clone(),
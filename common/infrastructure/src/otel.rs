@@ -7,15 +7,16 @@ use opentelemetry::{
     propagation::Injector,
     trace::TracerProvider as _,
 };
-use opentelemetry_otlp::{MetricExporter, SpanExporter};
+use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig};
 use opentelemetry_sdk::{
     Resource,
     metrics::{PeriodicReader, SdkMeterProvider},
     propagation::TraceContextPropagator,
     trace::{Sampler, Sampler::ParentBased, SdkTracerProvider},
 };
+use prometheus::Registry;
 use reqwest::RequestBuilder;
-use std::sync::Once;
+use std::sync::{Once, OnceLock};
 use tracing_opentelemetry::OpenTelemetryLayer;
 use tracing_subscriber::{
     EnvFilter, field::MakeExt, layer::SubscriberExt, util::SubscriberInitExt,
@@ -28,6 +29,9 @@ pub enum Metrics {
     Disabled,
     #[clap(name = "enabled")]
     Enabled,
+    /// Expose metrics in the Prometheus format, on the infrastructure endpoint
+    #[clap(name = "prometheus")]
+    Prometheus,
 }
 
 #[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Default)]
@@ -44,6 +48,7 @@ impl fmt::Display for Metrics {
         match self {
             Metrics::Disabled => write!(f, "disabled"),
             Metrics::Enabled => write!(f, "enabled"),
+            Metrics::Prometheus => write!(f, "prometheus"),
         }
     }
 }
@@ -122,29 +127,70 @@ fn sampler() -> Sampler {
 }
 
 static INIT: Once = Once::new();
-pub fn init_tracing(name: &str, tracing: Tracing) {
+
+/// Initialize tracing.
+///
+/// If no OTLP endpoint is provided, the exporter falls back to the `OTEL_EXPORTER_OTLP_*`
+/// environment variables.
+pub fn init_tracing(name: &str, tracing: Tracing, endpoint: Option<&str>) {
     match tracing {
         Tracing::Disabled => {
             INIT.call_once(init_no_tracing);
         }
         Tracing::Enabled => {
-            init_otlp_tracing(name);
+            init_otlp_tracing(name, endpoint);
         }
     }
 }
 
-pub fn init_metrics(name: &'static str, metrics: Metrics) {
-    if let Metrics::Enabled = metrics {
-        init_otlp_metrics(name);
+/// Initialize metrics.
+///
+/// If no OTLP endpoint is provided, the exporter falls back to the `OTEL_EXPORTER_OTLP_*`
+/// environment variables.
+pub fn init_metrics(name: &'static str, metrics: Metrics, endpoint: Option<&str>) {
+    match metrics {
+        Metrics::Disabled => {}
+        Metrics::Enabled => init_otlp_metrics(name, endpoint),
+        Metrics::Prometheus => init_prometheus_metrics(name),
     }
 }
 
-fn init_otlp_metrics(name: &str) {
+static PROMETHEUS: OnceLock<Registry> = OnceLock::new();
+
+/// The Prometheus registry, if metrics are exposed in the Prometheus format.
+pub fn prometheus_registry() -> Option<&'static Registry> {
+    PROMETHEUS.get()
+}
+
+fn init_prometheus_metrics(name: &str) {
+    let registry = PROMETHEUS.get_or_init(Registry::new);
+
     #[allow(clippy::expect_used)]
-    let exporter = MetricExporter::builder()
-        .with_tonic()
+    let exporter = opentelemetry_prometheus::exporter()
+        .with_registry(registry.clone())
         .build()
-        .expect("Unable to build metrics exporter.");
+        .expect("Unable to build Prometheus exporter.");
+
+    let resource = Resource::builder()
+        .with_service_name(name.to_string())
+        .build();
+
+    let provider = SdkMeterProvider::builder()
+        .with_reader(exporter)
+        .with_resource(resource)
+        .build();
+
+    set_meter_provider(provider);
+}
+
+fn init_otlp_metrics(name: &str, endpoint: Option<&str>) {
+    let mut exporter = MetricExporter::builder().with_tonic();
+    if let Some(endpoint) = endpoint {
+        exporter = exporter.with_endpoint(endpoint);
+    }
+
+    #[allow(clippy::expect_used)]
+    let exporter = exporter.build().expect("Unable to build metrics exporter.");
 
     let reader = PeriodicReader::builder(exporter).build();
 
@@ -163,14 +209,16 @@ fn init_otlp_metrics(name: &str) {
     set_meter_provider(provider);
 }
 
-fn init_otlp_tracing(name: &str) {
+fn init_otlp_tracing(name: &str, endpoint: Option<&str>) {
     set_text_map_propagator(TraceContextPropagator::new());
 
+    let mut exporter = SpanExporter::builder().with_tonic();
+    if let Some(endpoint) = endpoint {
+        exporter = exporter.with_endpoint(endpoint);
+    }
+
     #[allow(clippy::expect_used)]
-    let exporter = SpanExporter::builder()
-        .with_tonic()
-        .build()
-        .expect("Unable to build tracing exporter");
+    let exporter = exporter.build().expect("Unable to build tracing exporter");
 
     let resource = Resource::builder()
         .with_service_name(name.to_string())
@@ -0,0 +1,65 @@
+//! Handling of CWE identifiers.
+
+/// Normalize a CWE identifier into the form used by the CWE catalog, like `CWE-79`.
+///
+/// Accepts identifiers with or without the `CWE-` prefix, in any case, and with leading zeros.
+/// Returns `None` for anything not referencing a CWE entry, like `NVD-CWE-noinfo`.
+pub fn normalize(id: &str) -> Option<String> {
+    let id = id.trim();
+    let number = match id.get(..4) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("cwe-") => &id[4..],
+        _ => id,
+    };
+
+    if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    number
+        .parse::<u32>()
+        .ok()
+        .map(|number| format!("CWE-{number}"))
+}
+
+/// Normalize a list of CWE identifiers, dropping invalid ones and duplicates.
+///
+/// Returns `None` if no valid identifier remains.
+pub fn normalize_all<'a>(ids: impl IntoIterator<Item = &'a str>) -> Option<Vec<String>> {
+    let mut result = Vec::new();
+    for id in ids.into_iter().filter_map(normalize) {
+        if !result.contains(&id) {
+            result.push(id);
+        }
+    }
+
+    if result.is_empty() {
+        None
+    } else {
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn normalize_ids() {
+        assert_eq!(normalize("CWE-79").as_deref(), Some("CWE-79"));
+        assert_eq!(normalize("cwe-079").as_deref(), Some("CWE-79"));
+        assert_eq!(normalize(" 20 ").as_deref(), Some("CWE-20"));
+        assert_eq!(normalize("NVD-CWE-noinfo"), None);
+        assert_eq!(normalize("NVD-CWE-Other"), None);
+        assert_eq!(normalize("CWE-"), None);
+        assert_eq!(normalize("CWE-+1"), None);
+    }
+
+    #[test]
+    fn normalize_lists() {
+        assert_eq!(
+            normalize_all(["CWE-79", "cwe-79", "NVD-CWE-Other", "CWE-20"]),
+            Some(vec!["CWE-79".to_string(), "CWE-20".to_string()])
+        );
+        assert_eq!(normalize_all(["NVD-CWE-noinfo"]), None);
+    }
+}
@@ -0,0 +1,53 @@
+//! Inserting many rows, using a single parameter.
+//!
+//! Using `insert_many` creates a statement with one parameter per value. Batches are limited by
+//! the maximum number of parameters, and each batch results in a new statement, with thousands of
+//! placeholders, which needs to be parsed by the database.
+//!
+//! Instead, all rows of a batch can be passed as a single JSON array, turning it into rows using
+//! `jsonb_to_recordset`:
+//!
+//! ```sql
+//! INSERT INTO base_purl (id, "type", namespace, name)
+//! SELECT * FROM jsonb_to_recordset($1) AS t(id uuid, "type" text, namespace text, name text)
+//! ON CONFLICT (id) DO NOTHING
+//! ```
+//!
+//! The statement doesn't depend on the number of rows. So it gets prepared once per connection,
+//! and re-used for all following batches.
+
+use sea_orm::{ConnectionTrait, DbErr, Statement};
+use serde::Serialize;
+
+/// The maximum number of rows inserted by a single statement.
+///
+/// Not limited by the number of parameters, but keeps the size of a single request reasonable.
+pub const BATCH_SIZE: usize = 10_000;
+
+/// Insert rows in batches of [`BATCH_SIZE`].
+///
+/// The statement must take the rows, serialized as JSON array, as its only parameter (`$1`). The
+/// rows are inserted in the order they are provided, which should be stable to avoid deadlocks.
+pub async fn insert_rows<C, T>(
+    db: &C,
+    sql: &str,
+    rows: impl IntoIterator<Item = T>,
+) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+    T: Serialize,
+{
+    let rows = rows.into_iter().collect::<Vec<_>>();
+
+    for batch in rows.chunks(BATCH_SIZE) {
+        let batch = serde_json::to_value(batch).map_err(|err| DbErr::Json(err.to_string()))?;
+        db.execute(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            sql,
+            [batch.into()],
+        ))
+        .await?;
+    }
+
+    Ok(())
+}
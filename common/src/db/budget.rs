@@ -0,0 +1,196 @@
+//! Accounting of SQL statements, issued within a scope.
+//!
+//! Statements executed through a [`Database`](super::Database), or a transaction started from
+//! it, get recorded in the [`QueryStats`] of the current scope, if there is one. Once the
+//! [`QueryBudget`] of the scope is exceeded, waiters of [`QueryStats::exceeded`] get notified,
+//! allowing them to abort the operation.
+
+use std::{
+    fmt::{Display, Formatter},
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+use tokio::sync::Notify;
+
+tokio::task_local! {
+    static CURRENT: Arc<QueryStats>;
+}
+
+/// Limits of the SQL statements issued within a scope.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueryBudget {
+    /// Maximum number of statements. Zero meaning "unlimited".
+    pub max_statements: u64,
+    /// Maximum accumulated duration of all statements.
+    pub max_duration: Option<Duration>,
+}
+
+impl QueryBudget {
+    /// Check if the provided numbers exceed the budget.
+    fn is_exceeded_by(&self, statements: u64, duration: Duration) -> bool {
+        (self.max_statements > 0 && statements > self.max_statements)
+            || self.max_duration.is_some_and(|max| duration > max)
+    }
+}
+
+/// Statistics of the SQL statements issued within a scope.
+#[derive(Debug, Default)]
+pub struct QueryStats {
+    budget: QueryBudget,
+    statements: AtomicU64,
+    /// The accumulated duration, in microseconds.
+    duration: AtomicU64,
+    exceeded: AtomicBool,
+    notify: Notify,
+}
+
+impl QueryStats {
+    pub fn new(budget: QueryBudget) -> Self {
+        Self {
+            budget,
+            ..Default::default()
+        }
+    }
+
+    /// Run a future, recording all statements it issues.
+    ///
+    /// Statements issued by tasks spawned from the future are not recorded.
+    pub fn scope<F: Future>(self: Arc<Self>, f: F) -> impl Future<Output = F::Output> {
+        CURRENT.scope(self, f)
+    }
+
+    /// The number of issued statements.
+    pub fn statements(&self) -> u64 {
+        self.statements.load(Ordering::Relaxed)
+    }
+
+    /// The accumulated duration of all issued statements.
+    pub fn duration(&self) -> Duration {
+        Duration::from_micros(self.duration.load(Ordering::Relaxed))
+    }
+
+    /// Check if the budget was exceeded.
+    pub fn is_exceeded(&self) -> bool {
+        self.exceeded.load(Ordering::Relaxed)
+    }
+
+    /// Wait until the budget gets exceeded.
+    pub async fn exceeded(&self) {
+        loop {
+            // create the future before checking, so that we don't miss a notification
+            let notified = self.notify.notified();
+            if self.is_exceeded() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    fn record(&self, elapsed: Duration) {
+        let elapsed = elapsed.as_micros() as u64;
+        let statements = self.statements.fetch_add(1, Ordering::Relaxed) + 1;
+        let duration = self.duration.fetch_add(elapsed, Ordering::Relaxed) + elapsed;
+
+        if self
+            .budget
+            .is_exceeded_by(statements, Duration::from_micros(duration))
+            && !self.exceeded.swap(true, Ordering::Relaxed)
+        {
+            self.notify.notify_waiters();
+        }
+    }
+}
+
+impl Display for QueryStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} statement(s) in {}",
+            self.statements(),
+            humantime::Duration::from(self.duration())
+        )
+    }
+}
+
+/// Record an executed statement in the statistics of the current scope, if there is one.
+pub fn record(elapsed: Duration) {
+    let _ = CURRENT.try_with(|stats| stats.record(elapsed));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+
+    #[test(tokio::test)]
+    async fn record_in_scope() {
+        let stats = Arc::new(QueryStats::new(QueryBudget::default()));
+
+        // outside the scope, nothing gets recorded
+        record(Duration::from_millis(1));
+
+        stats
+            .clone()
+            .scope(async {
+                record(Duration::from_millis(1));
+                record(Duration::from_millis(2));
+            })
+            .await;
+
+        assert_eq!(stats.statements(), 2);
+        assert_eq!(stats.duration(), Duration::from_millis(3));
+        assert!(!stats.is_exceeded());
+    }
+
+    #[test(tokio::test)]
+    async fn exceed_statements() {
+        let stats = Arc::new(QueryStats::new(QueryBudget {
+            max_statements: 2,
+            max_duration: None,
+        }));
+
+        stats
+            .clone()
+            .scope(async {
+                record(Duration::from_millis(1));
+                record(Duration::from_millis(1));
+            })
+            .await;
+        assert!(!stats.is_exceeded());
+
+        let result = tokio::select! {
+            _ = stats.exceeded() => true,
+            _ = stats.clone().scope(async {
+                record(Duration::from_millis(1));
+                std::future::pending::<()>().await;
+            }) => false,
+        };
+
+        assert!(result);
+        assert!(stats.is_exceeded());
+    }
+
+    #[test(tokio::test)]
+    async fn exceed_duration() {
+        let stats = Arc::new(QueryStats::new(QueryBudget {
+            max_statements: 0,
+            max_duration: Some(Duration::from_millis(10)),
+        }));
+
+        stats
+            .clone()
+            .scope(async {
+                record(Duration::from_millis(5));
+                record(Duration::from_millis(6));
+            })
+            .await;
+
+        assert!(stats.is_exceeded());
+        // must return immediately
+        stats.exceeded().await;
+    }
+}
@@ -1,6 +1,6 @@
 use migration::Iden;
-use sea_orm::{ConnectionTrait, DbErr, ExecResult};
-use sea_query::{Func, SelectStatement};
+use sea_orm::{ConnectionTrait, DbErr, ExecResult, prelude::Uuid};
+use sea_query::{Alias, Expr, Func, SelectStatement};
 use std::fmt::Write;
 
 /// PostgreSQL's `array_agg` function.
@@ -55,6 +55,15 @@ impl Iden for VersionMatches {
     }
 }
 
+/// The function deriving the ecosystem of a package from the type of its purl.
+pub struct PurlEcosystem;
+
+impl Iden for PurlEcosystem {
+    fn unquoted(&self, s: &mut dyn Write) {
+        write!(s, "purl_ecosystem").unwrap()
+    }
+}
+
 /// The function updating the deprecated state of a consistent set of advisories.
 pub struct UpdateDeprecatedAdvisory;
 
@@ -73,3 +82,31 @@ impl UpdateDeprecatedAdvisory {
         db.execute(stmt).await
     }
 }
+
+/// The function recomputing the cached effective statuses of products.
+pub struct RecomputeProductStatusSummary;
+
+impl Iden for RecomputeProductStatusSummary {
+    fn unquoted(&self, s: &mut dyn Write) {
+        write!(s, "recompute_product_status_summary").unwrap()
+    }
+}
+
+impl RecomputeProductStatusSummary {
+    /// Recompute the statuses, limited to a vulnerability and a product if provided.
+    pub async fn execute(
+        db: &impl ConnectionTrait,
+        vulnerability: Option<&str>,
+        product: Option<Uuid>,
+    ) -> Result<ExecResult, DbErr> {
+        let stmt = db.get_database_backend().build(
+            SelectStatement::new().expr(
+                Func::cust(Self)
+                    .arg(Expr::val(vulnerability).cast_as(Alias::new("text")))
+                    .arg(Expr::val(product).cast_as(Alias::new("uuid"))),
+            ),
+        );
+
+        db.execute(stmt).await
+    }
+}
@@ -1,9 +1,27 @@
 use crate::db::multi_model::{FromQueryResultMultiModel, SelectIntoMultiModel};
 use sea_orm::{
     ConnectionTrait, DbErr, EntityTrait, FromQueryResult, Paginator, PaginatorTrait, QuerySelect,
-    Select, SelectModel, SelectTwo, SelectTwoModel, Selector, SelectorTrait,
+    QueryTrait, Select, SelectModel, SelectTwo, SelectTwoModel, Selector, SelectorTrait, Statement,
 };
 use std::num::NonZeroU64;
+use utoipa::ToSchema;
+
+/// How to count the total number of items of a paginated query.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, ToSchema, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum Count {
+    /// Count all matching items
+    #[default]
+    Exact,
+    /// Use the estimate of the query planner, based on the table statistics
+    Estimated,
+    /// Don't count, only report the items up to the end of the requested page
+    ///
+    /// Requesting a page past the end of the items still counts them all.
+    None,
+}
 
 pub struct Limiter<'db, C, S1, S2>
 where
@@ -14,6 +32,12 @@ where
     db: &'db C,
     selector: Selector<S1>,
     paginator: Paginator<'db, C, S2>,
+    count: Count,
+    /// the query, without limit and offset
+    query: Statement,
+    /// the query of the requested page
+    page: Statement,
+    offset: u64,
 }
 
 impl<'db, C, S1, S2> Limiter<'db, C, S1, S2>
@@ -22,15 +46,79 @@ where
     S1: SelectorTrait + 'db,
     S2: SelectorTrait + 'db,
 {
+    /// Set how to count the total number of items, defaults to [`Count::Exact`].
+    pub fn counting(mut self, count: Count) -> Self {
+        self.count = count;
+        self
+    }
+
     pub async fn fetch(self) -> Result<Vec<S1::Item>, DbErr> {
         self.selector.all(self.db).await
     }
 
     pub async fn total(&self) -> Result<u64, DbErr> {
-        self.paginator.num_items().await
+        match self.count {
+            Count::Exact => self.paginator.num_items().await,
+            Count::Estimated => self.estimate().await,
+            Count::None => match self.count_page().await? {
+                // past the end of the items, which requires counting to find it
+                0 if self.offset > 0 => self.paginator.num_items().await,
+                num => Ok(self.offset + num),
+            },
+        }
+    }
+
+    /// Take the number of rows the query planner expects the query to return.
+    async fn estimate(&self) -> Result<u64, DbErr> {
+        let statement = Statement {
+            sql: format!("EXPLAIN (FORMAT JSON) {}", self.query.sql),
+            ..self.query.clone()
+        };
+
+        let Some(row) = self.db.query_one(statement).await? else {
+            return Ok(0);
+        };
+        let plan: serde_json::Value = row.try_get("", "QUERY PLAN")?;
+
+        Ok(plan[0]["Plan"]["Plan Rows"].as_f64().unwrap_or_default() as u64)
+    }
+
+    /// Count the rows of the requested page, which is cheap compared to counting all rows.
+    async fn count_page(&self) -> Result<u64, DbErr> {
+        let statement = Statement {
+            sql: format!(
+                r#"SELECT COUNT(*) AS "num_items" FROM ({}) AS "page""#,
+                self.page.sql
+            ),
+            ..self.page.clone()
+        };
+
+        let Some(row) = self.db.query_one(statement).await? else {
+            return Ok(0);
+        };
+        let num: i64 = row.try_get("", "num_items")?;
+
+        Ok(num as u64)
     }
 }
 
+/// Limit a query, keeping the statements required for counting its items.
+fn limit_query<C, Q>(db: &C, query: Q, offset: u64, limit: u64) -> (Q, Statement, Statement)
+where
+    C: ConnectionTrait,
+    Q: QuerySelect + QueryTrait,
+{
+    let backend = db.get_database_backend();
+    let full = query.build(backend);
+
+    let query = query
+        .limit(NonZeroU64::new(limit).map(|limit| limit.get()))
+        .offset(NonZeroU64::new(offset).map(|offset| offset.get()));
+    let page = query.build(backend);
+
+    (query, full, page)
+}
+
 pub trait LimiterTrait<'db, C>
 where
     C: ConnectionTrait,
@@ -61,16 +149,16 @@ where
         offset: u64,
         limit: u64,
     ) -> Limiter<'db, C, Self::FetchSelector, Self::CountSelector> {
-        let selector = self
-            .clone()
-            .limit(NonZeroU64::new(limit).map(|limit| limit.get()))
-            .offset(NonZeroU64::new(offset).map(|offset| offset.get()))
-            .into_model();
+        let (limited, query, page) = limit_query(db, self.clone(), offset, limit);
 
         Limiter {
             db,
             paginator: self.paginate(db, 1),
-            selector,
+            selector: limited.into_model(),
+            count: Count::default(),
+            query,
+            page,
+            offset,
         }
     }
 }
@@ -105,16 +193,16 @@ where
         offset: u64,
         limit: u64,
     ) -> Limiter<'db, C, SelectModel<M>, SelectModel<M>> {
-        let selector = self
-            .clone()
-            .limit(NonZeroU64::new(limit).map(|limit| limit.get()))
-            .offset(NonZeroU64::new(offset).map(|offset| offset.get()))
-            .into_model::<M>();
+        let (limited, query, page) = limit_query(db, self.clone(), offset, limit);
 
         Limiter {
             db,
             paginator: self.into_model::<M>().paginate(db, 1),
-            selector,
+            selector: limited.into_model::<M>(),
+            count: Count::default(),
+            query,
+            page,
+            offset,
         }
     }
 
@@ -124,16 +212,16 @@ where
         offset: u64,
         limit: u64,
     ) -> Result<Limiter<'db, C, SelectModel<M>, SelectModel<M>>, DbErr> {
-        let selector = self
-            .clone()
-            .limit(NonZeroU64::new(limit).map(|limit| limit.get()))
-            .offset(NonZeroU64::new(offset).map(|offset| offset.get()))
-            .try_into_multi_model::<M>()?;
+        let (limited, query, page) = limit_query(db, self.clone(), offset, limit);
 
         Ok(Limiter {
             db,
             paginator: self.into_model::<M>().paginate(db, 1),
-            selector,
+            selector: limited.try_into_multi_model::<M>()?,
+            count: Count::default(),
+            query,
+            page,
+            offset,
         })
     }
 }
@@ -150,16 +238,16 @@ where
     M: FromQueryResult + Sized + Send + Sync + 'db,
     EM: FromQueryResult + Sized + Send + Sync + 'db,
 {
-    let selector = select
-        .clone()
-        .limit(NonZeroU64::new(limit).map(|limit| limit.get()))
-        .offset(NonZeroU64::new(offset).map(|offset| offset.get()))
-        .into_model();
+    let (limited, query, page) = limit_query(db, select.clone(), offset, limit);
 
     Limiter {
         db,
         paginator: select.paginate(db, 1),
-        selector,
+        selector: limited.into_model(),
+        count: Count::default(),
+        query,
+        page,
+        offset,
     }
 }
 
@@ -180,16 +268,16 @@ where
         offset: u64,
         limit: u64,
     ) -> Limiter<'db, C, Self::FetchSelector, Self::CountSelector> {
-        let selector = self
-            .clone()
-            .limit(NonZeroU64::new(limit).map(|limit| limit.get()))
-            .offset(NonZeroU64::new(offset).map(|offset| offset.get()))
-            .into_model();
+        let (limited, query, page) = limit_query(db, self.clone(), offset, limit);
 
         Limiter {
             db,
             paginator: self.paginate(db, 1),
-            selector,
+            selector: limited.into_model(),
+            count: Count::default(),
+            query,
+            page,
+            offset,
         }
     }
 }
@@ -1,15 +1,20 @@
 mod func;
 
+pub mod batch;
+pub mod budget;
 pub mod chunk;
+pub mod copy;
 pub mod embedded;
 pub mod limiter;
 pub mod multi_model;
 pub mod query;
+pub mod slow;
 
 pub use func::*;
 
 use anyhow::{Context, ensure};
 use migration::{Migrator, MigratorTrait};
+use opentelemetry::{KeyValue, global};
 use reqwest::Url;
 use sea_orm::{
     AccessMode, ConnectOptions, ConnectionTrait, DatabaseConnection, DatabaseTransaction,
@@ -29,6 +34,8 @@ use tracing::instrument;
 pub struct Database {
     /// the database connection
     db: DatabaseConnection,
+    /// the connection to a read-only replica, if configured
+    read: Option<Box<Database>>,
     /// the database name
     name: String,
 }
@@ -36,10 +43,31 @@ pub struct Database {
 impl Database {
     #[instrument(err)]
     pub async fn new(database: &crate::config::Database) -> Result<Self, anyhow::Error> {
-        let url = database.to_url();
-
+        let db = Self::connect(database.to_url(), database, "primary").await?;
+
+        let read = match &database.read_url {
+            Some(url) => Some(Box::new(Self {
+                db: Self::connect(url.clone(), database, "read").await?,
+                read: None,
+                name: database.name.clone(),
+            })),
+            None => None,
+        };
+
+        Ok(Self {
+            db,
+            read,
+            name: database.name.clone(),
+        })
+    }
+
+    async fn connect(
+        url: String,
+        database: &crate::config::Database,
+        pool: &'static str,
+    ) -> Result<DatabaseConnection, anyhow::Error> {
         if log::log_enabled!(log::Level::Debug) {
-            log::debug!("connect to {}", strip_password(url.clone()));
+            log::debug!("connect to {} ({pool})", strip_password(url.clone()));
         }
 
         let mut opt = ConnectOptions::new(url);
@@ -52,10 +80,67 @@ impl Database {
         opt.max_lifetime(Duration::from_secs(database.max_lifetime));
         opt.idle_timeout(Duration::from_secs(database.idle_timeout));
 
-        let db = sea_orm::Database::connect(opt).await?;
-        let name = database.name.clone();
+        let mut db = sea_orm::Database::connect(opt).await?;
+
+        Self::register_metrics(&db, database.max_conn, pool);
+
+        let threshold: Option<Duration> = database.slow_query_threshold.map(Into::into);
+        let attributes = [KeyValue::new("pool", pool)];
+        let durations = global::meter("Database")
+            .f64_histogram("db_statement_duration")
+            .with_description("Duration of SQL statements")
+            .with_unit("s")
+            .build();
+        let slow = global::meter("Database")
+            .u64_counter("db_slow_statements")
+            .with_description("Number of statements exceeding the slow query threshold")
+            .build();
+
+        db.set_metric_callback(move |info| {
+            budget::record(info.elapsed);
+            durations.record(info.elapsed.as_secs_f64(), &attributes);
+            if threshold.is_some_and(|threshold| slow::record(info, threshold)) {
+                slow.add(1, &attributes);
+            }
+        });
 
-        Ok(Self { db, name })
+        Ok(db)
+    }
+
+    /// Register gauges, observing the utilization of the connection pool.
+    fn register_metrics(db: &DatabaseConnection, max_conn: u32, pool: &'static str) {
+        let meter = global::meter("Database");
+        let attributes = [KeyValue::new("pool", pool)];
+        {
+            let db = db.clone();
+            let attributes = attributes.clone();
+            meter
+                .u64_observable_gauge("db_pool_connections")
+                .with_description("Number of open connections")
+                .with_callback(move |inst| {
+                    inst.observe(db.get_postgres_connection_pool().size() as _, &attributes)
+                })
+                .build();
+        }
+        {
+            let db = db.clone();
+            let attributes = attributes.clone();
+            meter
+                .u64_observable_gauge("db_pool_idle_connections")
+                .with_description("Number of idle connections")
+                .with_callback(move |inst| {
+                    inst.observe(
+                        db.get_postgres_connection_pool().num_idle() as _,
+                        &attributes,
+                    )
+                })
+                .build();
+        }
+        meter
+            .u64_observable_gauge("db_pool_max_connections")
+            .with_description("Maximum number of connections")
+            .with_callback(move |inst| inst.observe(max_conn as _, &attributes))
+            .build();
     }
 
     #[instrument(skip(self), err)]
@@ -119,10 +204,13 @@ impl Database {
 
     #[instrument(skip(self), err)]
     pub async fn close(self) -> anyhow::Result<()> {
+        if let Some(read) = self.read {
+            read.db.close().await?;
+        }
         Ok(self.db.close().await?)
     }
 
-    /// Ping the database.
+    /// Ping the database, and the read-only replica if configured.
     ///
     /// Intended to be used for health checks.
     #[instrument(skip(self), err)]
@@ -131,6 +219,12 @@ impl Database {
             .ping()
             .await
             .context("failed to ping the database")?;
+        if let Some(read) = &self.read {
+            read.db
+                .ping()
+                .await
+                .context("failed to ping the read-only replica")?;
+        }
         Ok(())
     }
 
@@ -138,6 +232,31 @@ impl Database {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Get the database to use for read-only operations.
+    ///
+    /// This is the read-only replica, if configured, or the primary database otherwise. As the
+    /// replica may lag behind the primary, operations which must observe their own writes must
+    /// keep using the primary.
+    pub fn read(&self) -> &Database {
+        self.read.as_deref().unwrap_or(self)
+    }
+
+    /// Begin a read-only transaction, providing a consistent snapshot of the database.
+    ///
+    /// Intended for read paths running multiple queries, which must not observe changes
+    /// committed in between them. The transaction doesn't need to be committed, dropping it
+    /// is sufficient. It runs on the read-only replica, if configured.
+    #[instrument(skip(self), err)]
+    pub async fn begin_read(&self) -> Result<DatabaseTransaction, DbErr> {
+        self.read()
+            .db
+            .begin_with_config(
+                Some(IsolationLevel::RepeatableRead),
+                Some(AccessMode::ReadOnly),
+            )
+            .await
+    }
 }
 
 impl Deref for Database {
@@ -295,8 +414,17 @@ impl<'b> StreamTrait for &'b Database {
 pub trait DatabaseErrors {
     /// return `true` if the error is a duplicate key error
     fn is_duplicate(&self) -> bool;
+
+    /// return `true` if the error is a serialization failure or a deadlock, which goes away when
+    /// running the transaction again
+    fn is_transient(&self) -> bool;
 }
 
+/// SQLSTATE of a serialization failure
+const SERIALIZATION_FAILURE: &str = "40001";
+/// SQLSTATE of a detected deadlock
+const DEADLOCK_DETECTED: &str = "40P01";
+
 impl DatabaseErrors for DbErr {
     fn is_duplicate(&self) -> bool {
         match self {
@@ -306,6 +434,17 @@ impl DatabaseErrors for DbErr {
             _ => false,
         }
     }
+
+    fn is_transient(&self) -> bool {
+        match self {
+            DbErr::Query(RuntimeErr::SqlxError(sqlx::error::Error::Database(err)))
+            | DbErr::Exec(RuntimeErr::SqlxError(sqlx::error::Error::Database(err))) => matches!(
+                err.code().as_deref(),
+                Some(SERIALIZATION_FAILURE | DEADLOCK_DETECTED)
+            ),
+            _ => false,
+        }
+    }
 }
 
 /// Remove the password from the URL and replace it with `***`, if present.
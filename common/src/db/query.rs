@@ -1,11 +1,13 @@
 mod columns;
 mod filter;
 mod filtering;
+mod labels;
 mod sort;
 mod value;
 
 pub use columns::{Columns, IntoColumns};
 pub use filtering::Filtering;
+pub use labels::{LabelFilter, LabelFilters, LabelQuery};
 pub use value::{Valuable, Value};
 
 use filter::{Filter, Operator};
@@ -50,6 +52,9 @@ impl Query {
     /// `{op}` should be one of `=`, `!=`, `~`, `!~, `>=`, `>`, `<=`,
     /// or `<`.
     ///
+    /// For array-valued fields, only `=` and `!=` are supported,
+    /// matching whether the array contains the value, or not.
+    ///
     pub fn q(s: &str) -> Self {
         Self {
             q: s.into(),
@@ -63,10 +68,12 @@ impl Query {
     /// optional `order` should be one of `asc` or `desc`. If omitted,
     /// the order defaults to `asc`.
     ///
-    /// Multiple sorts should be `,`-delimited
+    /// Multiple sorts should be `,`-delimited, each with its own
+    /// optional order, e.g. `average_severity:desc,modified:asc`.
     ///
     /// Each `{field}` name must correspond to one of the selected
-    /// Columns.
+    /// Columns. If the Columns contain an `id` field, it's used as a
+    /// final tiebreaker.
     ///
     pub fn sort(self, s: &str) -> Self {
         Self {
@@ -214,7 +221,7 @@ pub(crate) mod tests {
             .to_string();
         assert_eq!(
             stmt,
-            r#"SELECT "advisory"."id" FROM "advisory" WHERE (("advisory"."location" ILIKE '%foo%') OR ("advisory"."title" ILIKE '%foo%')) AND "advisory"."published" > '2024-04-20' ORDER BY "advisory"."location" ASC, "advisory"."title" DESC, "advisory"."id" DESC"#
+            r#"SELECT "advisory"."id" FROM "advisory" WHERE (("advisory"."location" ILIKE '%foo%') OR ("advisory"."title" ILIKE '%foo%')) AND "advisory"."published" > '2024-04-20' ORDER BY "advisory"."location" ASC, "advisory"."title" DESC, "advisory"."id" ASC, "advisory"."id" DESC"#
         );
         Ok(())
     }
@@ -201,6 +201,25 @@ impl Columns {
         }
     }
 
+    /// Return the names of all fields known to the context, sorted and deduplicated.
+    pub(crate) fn names(&self) -> Vec<String> {
+        let mut names = self
+            .columns
+            .iter()
+            .filter_map(|(col, _)| match col {
+                ColumnRef::Column(name)
+                | ColumnRef::TableColumn(_, name)
+                | ColumnRef::SchemaTableColumn(_, _, name) => Some(name.to_string()),
+                _ => None,
+            })
+            .chain(self.exprs.keys().map(|k| k.to_string()))
+            .chain(self.json_keys.keys().map(|k| k.to_string()))
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        names
+    }
+
     pub(crate) fn translate(&self, field: &str, op: &str, value: &str) -> Option<String> {
         match self.translator {
             None => None,
@@ -315,7 +334,7 @@ mod tests {
 
         assert_eq!(
             clause(q("severity>medium").sort("severity:desc"))?,
-            r#""advisory"."score" > 6 ORDER BY "advisory"."score" DESC"#,
+            r#""advisory"."score" > 6 ORDER BY "advisory"."score" DESC, "advisory"."id" ASC"#,
         );
         assert_eq!(
             clause(q("severity=medium"))?,
@@ -376,7 +395,7 @@ mod tests {
         );
         assert_eq!(
             clause(q("name=log4j").sort("name"))?,
-            r#"("advisory"."purl" ->> 'name') = 'log4j' ORDER BY "advisory"."purl" ->> 'name' ASC"#
+            r#"("advisory"."purl" ->> 'name') = 'log4j' ORDER BY "advisory"."purl" ->> 'name' ASC, "advisory"."id" ASC"#
         );
         assert_eq!(
             clause(q("foo"))?,
@@ -3,7 +3,10 @@ use chrono::Local;
 use human_date_parser::{ParseResult, from_human_time};
 use sea_orm::{
     ColumnType, Condition, IntoSimpleExpr, Value as SeaValue, sea_query,
-    sea_query::{ConditionExpression, IntoCondition, extension::postgres::PgExpr},
+    sea_query::{
+        ConditionExpression, IntoCondition,
+        extension::postgres::{PgExpr, PgFunc},
+    },
 };
 use sea_query::{BinOper, Expr, Keyword, SimpleExpr};
 use std::{
@@ -28,6 +31,33 @@ impl Filter {
             operands: Operand::Composite(filters),
         }
     }
+
+    /// Filter an array by its elements: it contains the value (`=`), or it doesn't (`!=`)
+    fn element(array: Expr, arg: Arg, operator: Operator) -> Result<Self, Error> {
+        if let Arg::Null = arg {
+            // whether the array itself is null
+            return Ok(Filter {
+                operands: Operand::Simple(array, arg),
+                operator,
+            });
+        }
+        let array = match operator {
+            Operator::Equal => PgFunc::any(array),
+            Operator::NotEqual => PgFunc::all(array),
+            _ => {
+                return Err(Error::SearchSyntax(format!(
+                    "Invalid operator for an array: '{operator}'"
+                )));
+            }
+        };
+        Ok(Filter {
+            operands: Operand::Simple(
+                Expr::expr(arg.into_simple_expr()),
+                Arg::SimpleExpr(array.into()),
+            ),
+            operator,
+        })
+    }
 }
 
 // From a filter string of the form {field}{op}{value}
@@ -46,12 +76,16 @@ impl TryFrom<(&str, Operator, &Vec<String>, &Columns)> for Filter {
                     .map(
                         |s| match columns.translate(field, &operator.to_string(), s) {
                             Some(x) => q(&x).filter_for(columns),
-                            None => columns.for_field(field).and_then(|(expr, ref ty)| {
-                                Arg::parse(s, ty).map(|v| Filter {
-                                    operands: Operand::Simple(expr, v),
-                                    operator,
-                                })
-                            }),
+                            None => columns
+                                .for_field(field)
+                                .and_then(|(expr, ref ty)| match ty {
+                                    ColumnType::Array(ty) => Arg::parse(s, ty)
+                                        .and_then(|v| Filter::element(expr, v, operator)),
+                                    _ => Arg::parse(s, ty).map(|v| Filter {
+                                        operands: Operand::Simple(expr, v),
+                                        operator,
+                                    }),
+                                }),
                         },
                     )
                     .collect::<Result<Vec<_>, _>>()?,
@@ -168,6 +202,7 @@ impl Arg {
         Ok(match ct {
             ColumnType::Uuid => Arg::Value(SeaValue::from(s.parse::<Uuid>().map_err(err)?)),
             ColumnType::Integer => Arg::Value(SeaValue::from(s.parse::<i32>().map_err(err)?)),
+            ColumnType::Boolean => Arg::Value(SeaValue::from(s.parse::<bool>().map_err(err)?)),
             ColumnType::Decimal(_) | ColumnType::Float | ColumnType::Double => {
                 Arg::Value(SeaValue::from(s.parse::<f64>().map_err(err)?))
             }
@@ -413,6 +448,42 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[test(tokio::test)]
+    async fn arrays() -> Result<(), anyhow::Error> {
+        use crate::db::query::Filtering;
+        use sea_orm::{EntityTrait, sea_query::RcOrArc};
+
+        let clause = |query: &str| -> Result<String, anyhow::Error> {
+            Ok(advisory::Entity::find()
+                .select_only()
+                .column(advisory::Column::Id)
+                .filtering_with(
+                    q(query),
+                    advisory::Entity.columns().add_expr(
+                        "tags",
+                        SimpleExpr::Custom("ARRAY['a', 'b']".into()),
+                        ColumnType::Array(RcOrArc::new(ColumnType::Text)),
+                    ),
+                )?
+                .build(sea_orm::DatabaseBackend::Postgres)
+                .to_string()
+                .split("WHERE ")
+                .last()
+                .unwrap()
+                .to_string())
+        };
+
+        assert_eq!(clause("tags=a")?, "'a' = ANY(ARRAY['a', 'b'])");
+        assert_eq!(
+            clause("tags!=a|c")?,
+            "'a' <> ALL(ARRAY['a', 'b']) AND 'c' <> ALL(ARRAY['a', 'b'])"
+        );
+        assert_eq!(clause("tags=null")?, "ARRAY['a', 'b'] IS NULL");
+        assert!(clause("tags~a").is_err());
+
+        Ok(())
+    }
+
     #[test(tokio::test)]
     async fn complex_ilikes() -> Result<(), anyhow::Error> {
         //
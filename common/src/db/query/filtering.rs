@@ -26,10 +26,7 @@ pub trait Filtering<T: EntityTrait> {
             self.filter(search.filter_for(&columns)?)
         };
 
-        Ok(sort
-            .split_terminator(',')
-            .map(|s| Sort::parse(s, &columns))
-            .collect::<Result<Vec<_>, _>>()?
+        Ok(Sort::parse(sort, &columns)?
             .into_iter()
             .fold(stmt, |select, s| s.order_by(select)))
     }
@@ -0,0 +1,228 @@
+use super::Error;
+use sea_orm::Condition;
+use sea_query::{Expr, IntoColumnRef, extension::postgres::PgExpr};
+use serde::Deserialize;
+use serde_json::json;
+use std::{collections::HashMap, str::FromStr};
+use utoipa::IntoParams;
+
+/// A filter on the labels of a document, stored as a JSON object of strings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LabelFilter {
+    /// The label has exactly this value
+    Equal { key: String, value: String },
+    /// The value of the label matches a pattern, with `*` matching any number of characters
+    Like { key: String, pattern: String },
+    /// The label is present, with any value
+    Exists(String),
+    /// The inner filter does not match
+    Not(Box<LabelFilter>),
+}
+
+impl LabelFilter {
+    pub fn equal(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::Equal {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Translate the filter into a condition on a JSONB column.
+    ///
+    /// Documents lacking a label are matched by negated filters on that label.
+    pub fn into_condition(self, column: impl IntoColumnRef) -> Condition {
+        let column = column.into_column_ref();
+        match self {
+            Self::Equal { key, value } => {
+                Condition::all().add(Expr::col(column).contains(json!({ key: value })))
+            }
+            Self::Like { key, pattern } => {
+                let pattern = pattern
+                    .replace('\\', r"\\")
+                    .replace('%', r"\%")
+                    .replace('_', r"\_")
+                    .replace('*', "%");
+                Condition::all()
+                    .add(
+                        Expr::expr(Expr::col(column.clone()).get_json_field(key.as_str()))
+                            .is_not_null(),
+                    )
+                    .add(Expr::expr(Expr::col(column).cast_json_field(key.as_str())).like(pattern))
+            }
+            Self::Exists(key) => Condition::all()
+                .add(Expr::expr(Expr::col(column).get_json_field(key.as_str())).is_not_null()),
+            Self::Not(inner) => inner.into_condition(column).not(),
+        }
+    }
+}
+
+/// Parse a filter of the form `key=value`, `key=pattern*`, or `has:key`, each optionally negated
+/// by a leading `!`.
+impl FromStr for LabelFilter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if let Some(inner) = s.strip_prefix('!') {
+            return Ok(Self::Not(Box::new(inner.parse()?)));
+        }
+
+        if let Some(key) = s.strip_prefix("has:") {
+            return match key.trim() {
+                "" => Err(Error::SearchSyntax(format!("Missing label key: '{s}'"))),
+                key => Ok(Self::Exists(key.into())),
+            };
+        }
+
+        match s.split_once('=') {
+            Some((key, _)) if key.trim().is_empty() => {
+                Err(Error::SearchSyntax(format!("Missing label key: '{s}'")))
+            }
+            Some((key, value)) if value.contains('*') => Ok(Self::Like {
+                key: key.trim().into(),
+                pattern: value.into(),
+            }),
+            Some((key, value)) => Ok(Self::equal(key.trim(), value)),
+            None => Err(Error::SearchSyntax(format!(
+                "Invalid label filter: '{s}', expected 'key=value' or 'has:key'"
+            ))),
+        }
+    }
+}
+
+/// Filters on the labels of a document, all of which must match.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LabelFilters(pub Vec<LabelFilter>);
+
+impl LabelFilters {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Translate the filters into a condition on a JSONB column.
+    pub fn into_condition(self, column: impl IntoColumnRef) -> Condition {
+        let column = column.into_column_ref();
+        self.0.into_iter().fold(Condition::all(), |and, filter| {
+            and.add(filter.into_condition(column.clone()))
+        })
+    }
+}
+
+/// Parse a `,`-delimited list of filters. An empty string results in no filters.
+impl FromStr for LabelFilters {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(
+            s.split(',')
+                .filter(|filter| !filter.trim().is_empty())
+                .map(LabelFilter::from_str)
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+}
+
+impl From<()> for LabelFilters {
+    fn from(_: ()) -> Self {
+        Self::default()
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> From<(K, V)> for LabelFilters {
+    fn from((key, value): (K, V)) -> Self {
+        Self(vec![LabelFilter::equal(key, value)])
+    }
+}
+
+impl<const N: usize> From<[(&str, &str); N]> for LabelFilters {
+    fn from(value: [(&str, &str); N]) -> Self {
+        Self(
+            value
+                .into_iter()
+                .map(|(key, value)| LabelFilter::equal(key, value))
+                .collect(),
+        )
+    }
+}
+
+impl From<HashMap<String, String>> for LabelFilters {
+    fn from(value: HashMap<String, String>) -> Self {
+        Self(
+            value
+                .into_iter()
+                .map(|(key, value)| LabelFilter::Equal { key, value })
+                .collect(),
+        )
+    }
+}
+
+impl From<Vec<LabelFilter>> for LabelFilters {
+    fn from(value: Vec<LabelFilter>) -> Self {
+        Self(value)
+    }
+}
+
+/// A query parameter filtering by labels.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, IntoParams)]
+pub struct LabelQuery {
+    /// Filters on the labels, `,`-delimited: `key=value` for an exact value, `key=value*` for a
+    /// value with a wildcard, or `has:key` for the presence of a label. Each can be negated with
+    /// a leading `!`.
+    #[serde(default)]
+    pub labels: String,
+}
+
+impl TryFrom<LabelQuery> for LabelFilters {
+    type Error = Error;
+
+    fn try_from(value: LabelQuery) -> Result<Self, Self::Error> {
+        value.labels.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    fn like(key: &str, pattern: &str) -> LabelFilter {
+        LabelFilter::Like {
+            key: key.into(),
+            pattern: pattern.into(),
+        }
+    }
+
+    fn not(filter: LabelFilter) -> LabelFilter {
+        LabelFilter::Not(Box::new(filter))
+    }
+
+    #[test]
+    fn parse() {
+        let parse = |s: &str| s.parse::<LabelFilters>().map(|filters| filters.0);
+
+        assert_eq!(parse(""), Ok(vec![]));
+        assert_eq!(parse("team=a"), Ok(vec![LabelFilter::equal("team", "a")]));
+        assert_eq!(parse("team=plat*"), Ok(vec![like("team", "plat*")]));
+        assert_eq!(parse("has:ci"), Ok(vec![LabelFilter::Exists("ci".into())]));
+        assert_eq!(
+            parse("!team=b, !has:ci"),
+            Ok(vec![
+                not(LabelFilter::equal("team", "b")),
+                not(LabelFilter::Exists("ci".into()))
+            ])
+        );
+        // only the first '=' separates the key from the value
+        assert_eq!(
+            parse("source=a=b"),
+            Ok(vec![LabelFilter::equal("source", "a=b")])
+        );
+        // an empty value is still a value
+        assert_eq!(parse("team="), Ok(vec![LabelFilter::equal("team", "")]));
+
+        assert!(parse("team").is_err());
+        assert!(parse("=a").is_err());
+        assert!(parse("has:").is_err());
+        assert!(parse("!").is_err());
+    }
+}
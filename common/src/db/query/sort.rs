@@ -11,29 +11,96 @@ impl Sort {
     pub(crate) fn order_by<T: QueryOrder>(self, stmt: T) -> T {
         stmt.order_by(self.field, self.order)
     }
-    pub(crate) fn parse(s: &str, columns: &Columns) -> Result<Self, Error> {
-        let (field, order) = match s.split(':').collect::<Vec<_>>()[..] {
-            [f] => (f, String::from("asc")),
-            [f, dir] => (f, dir.to_lowercase()),
-            _ => {
-                return Err(Error::SearchSyntax(format!("Invalid sort: '{s}'")));
-            }
-        };
-        match columns.translate(field, &order, "") {
-            Some(s) => Sort::parse(&s, columns),
-            None => Ok(Self {
-                field: columns.for_field(field)?.0,
-                order: match order.as_str() {
-                    "asc" => Order::Asc,
-                    "desc" => Order::Desc,
-                    dir => {
-                        return Err(Error::SearchSyntax(format!(
-                            "Invalid sort direction: '{dir}'"
-                        )));
+
+    /// Parse a `,`-delimited list of `{field}[:order]` sorts.
+    ///
+    /// Each field is only sorted by once, the first occurrence wins. If
+    /// the columns contain an `id` field, it's appended as a final
+    /// tiebreaker, so that pagination over equal values is stable.
+    pub(crate) fn parse(s: &str, columns: &Columns) -> Result<Vec<Self>, Error> {
+        let mut sorts = Sorts::default();
+        for s in s.split_terminator(',').map(str::trim) {
+            let (field, order) = split(s)?;
+            match columns.translate(field, direction(&order), "") {
+                Some(translated) => {
+                    // translations are not translated again
+                    for s in translated.split_terminator(',').map(str::trim) {
+                        let (field, order) = split(s)?;
+                        sorts.push(field, order, columns)?;
                     }
-                },
-            }),
+                }
+                None => sorts.push(field, order, columns)?,
+            }
+        }
+        Ok(sorts.finish(columns))
+    }
+}
+
+#[derive(Default)]
+struct Sorts {
+    fields: Vec<String>,
+    sorts: Vec<Sort>,
+}
+
+impl Sorts {
+    fn push(&mut self, field: &str, order: Order, columns: &Columns) -> Result<(), Error> {
+        let name = field.to_lowercase();
+        if self.fields.contains(&name) {
+            return Ok(());
         }
+        let (field, _) = columns.for_field(field).map_err(|_| {
+            Error::SearchSyntax(format!(
+                "Invalid sort field: '{field}', expected one of: {}",
+                columns.names().join(", ")
+            ))
+        })?;
+        self.fields.push(name);
+        self.sorts.push(Sort { field, order });
+        Ok(())
+    }
+
+    fn finish(mut self, columns: &Columns) -> Vec<Sort> {
+        if !self.sorts.is_empty() && !self.fields.iter().any(|f| f == "id") {
+            if let Ok((field, _)) = columns.for_field("id") {
+                self.sorts.push(Sort {
+                    field,
+                    order: Order::Asc,
+                });
+            }
+        }
+        self.sorts
+    }
+}
+
+fn split(s: &str) -> Result<(&str, Order), Error> {
+    let (field, order) = match s.split(':').collect::<Vec<_>>()[..] {
+        [f] => (f, Order::Asc),
+        [f, dir] => (
+            f,
+            match dir.to_lowercase().as_str() {
+                "asc" => Order::Asc,
+                "desc" => Order::Desc,
+                _ => {
+                    return Err(Error::SearchSyntax(format!(
+                        "Invalid sort direction: '{dir}'"
+                    )));
+                }
+            },
+        ),
+        _ => {
+            return Err(Error::SearchSyntax(format!("Invalid sort: '{s}'")));
+        }
+    };
+    if field.is_empty() {
+        return Err(Error::SearchSyntax(format!("Invalid sort: '{s}'")));
+    }
+    Ok((field, order))
+}
+
+fn direction(order: &Order) -> &'static str {
+    match order {
+        Order::Desc => "desc",
+        _ => "asc",
     }
 }
 
@@ -43,7 +110,7 @@ pub(crate) mod tests {
     use super::super::*;
     use super::*;
 
-    use sea_orm::{ColumnType, ColumnTypeTrait};
+    use sea_orm::{ColumnType, ColumnTypeTrait, EntityTrait, QuerySelect, QueryTrait};
     use sea_query::StringLen;
     use test_log::test;
 
@@ -88,4 +155,60 @@ pub(crate) mod tests {
 
         Ok(())
     }
+
+    #[test(tokio::test)]
+    async fn multiple_sorts() -> Result<(), anyhow::Error> {
+        let clause = |sort: &str| -> Result<String, Error> {
+            Ok(advisory::Entity::find()
+                .select_only()
+                .column(advisory::Column::Id)
+                .filtering_with(
+                    q("").sort(sort),
+                    advisory::Entity
+                        .columns()
+                        .translator(|f, op, v| match (f, v) {
+                            ("severity", "") => Some(format!("score:{op},published:{op}")),
+                            _ => None,
+                        }),
+                )?
+                .build(sea_orm::DatabaseBackend::Postgres)
+                .to_string()
+                .split("ORDER BY ")
+                .last()
+                .unwrap()
+                .to_string())
+        };
+
+        assert_eq!(
+            clause("severity:DESC, location:asc")?,
+            r#""advisory"."score" DESC, "advisory"."published" DESC, "advisory"."location" ASC, "advisory"."id" ASC"#
+        );
+        assert_eq!(
+            clause("severity,title:desc")?,
+            r#""advisory"."score" ASC, "advisory"."published" ASC, "advisory"."title" DESC, "advisory"."id" ASC"#
+        );
+        // the first occurrence of a field wins, the id tiebreaker isn't repeated
+        assert_eq!(
+            clause("id:desc,title,id,Title:desc")?,
+            r#""advisory"."id" DESC, "advisory"."title" ASC"#
+        );
+        assert!(clause("severity:up").is_err());
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn invalid_field_lists_sortable_fields() -> Result<(), anyhow::Error> {
+        let Err(Error::SearchSyntax(message)) =
+            Sort::parse("location,foo:desc", &advisory::Entity.columns())
+        else {
+            panic!("expected a syntax error");
+        };
+        assert_eq!(
+            message,
+            "Invalid sort field: 'foo', expected one of: id, location, published, purl, score, severity, title"
+        );
+
+        Ok(())
+    }
 }
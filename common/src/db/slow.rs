@@ -0,0 +1,100 @@
+//! Logging of slow SQL statements.
+//!
+//! Statements taking longer than a configured threshold get logged, together with a summary of
+//! their bind parameters. This helps in tracking down pathological statements, generated from
+//! user provided queries.
+
+use sea_orm::{Statement, metric::Info};
+use sea_query::Values;
+use std::{fmt::Write, time::Duration};
+
+/// Maximum number of characters shown for a single bind parameter.
+const MAX_VALUE_LEN: usize = 64;
+
+/// Log the statement, in case it exceeded the threshold.
+///
+/// Returns `true` if the statement was considered slow.
+pub fn record(info: &Info<'_>, threshold: Duration) -> bool {
+    if info.elapsed <= threshold {
+        return false;
+    }
+
+    log::warn!(
+        "slow statement ({:?}, failed: {}): {}; parameters: {}",
+        info.elapsed,
+        info.failed,
+        info.statement.sql,
+        parameters(info.statement),
+    );
+
+    true
+}
+
+/// Summarize the bind parameters of a statement, truncating lengthy values.
+fn parameters(statement: &Statement) -> String {
+    let Some(Values(values)) = &statement.values else {
+        return "none".into();
+    };
+    if values.is_empty() {
+        return "none".into();
+    }
+
+    let mut result = format!("{} total", values.len());
+    for (n, value) in values.iter().enumerate() {
+        let value = format!("{value:?}");
+        let _ = write!(result, ", ${}={}", n + 1, truncate(&value, MAX_VALUE_LEN));
+    }
+
+    result
+}
+
+fn truncate(value: &str, max: usize) -> String {
+    match value.char_indices().nth(max) {
+        Some((idx, _)) => format!("{}… ({} chars)", &value[..idx], value.chars().count()),
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sea_orm::{DbBackend, Value};
+
+    fn info(statement: &Statement, elapsed: Duration) -> Info<'_> {
+        Info {
+            elapsed,
+            statement,
+            failed: false,
+        }
+    }
+
+    #[test]
+    fn threshold() {
+        let statement = Statement::from_string(DbBackend::Postgres, "SELECT 1");
+
+        assert!(!record(
+            &info(&statement, Duration::from_millis(10)),
+            Duration::from_millis(100)
+        ));
+        assert!(record(
+            &info(&statement, Duration::from_millis(200)),
+            Duration::from_millis(100)
+        ));
+    }
+
+    #[test]
+    fn summarize_parameters() {
+        let statement = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT * FROM advisory WHERE id = $1 AND title = $2",
+            [Value::from(42i32), Value::from("x".repeat(100))],
+        );
+
+        let summary = parameters(&statement);
+        assert!(summary.starts_with("2 total, $1=Int(Some(42)), $2="));
+        assert!(summary.ends_with("… (116 chars)"));
+
+        let statement = Statement::from_string(DbBackend::Postgres, "SELECT 1");
+        assert_eq!(parameters(&statement), "none");
+    }
+}
@@ -1,6 +1,7 @@
 use actix_web::http::header;
 use anyhow::anyhow;
 use bytes::Bytes;
+use std::fmt::Debug;
 use tokio::{runtime::Handle, task::JoinError};
 use tracing::instrument;
 use walker_common::compression::{Compression, DecompressionOptions, Detector};
@@ -13,8 +14,46 @@ pub enum Error {
     Detector(anyhow::Error),
     #[error(transparent)]
     Io(#[from] std::io::Error),
-    #[error("payload too large")]
-    PayloadTooLarge,
+    #[error("payload too large, exceeding the limit of {limit} bytes")]
+    PayloadTooLarge { limit: usize },
+}
+
+/// Limits applied when decompressing a payload.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum size of the decompressed payload, zero meaning unlimited.
+    pub size: usize,
+    /// The maximum ratio between the decompressed and the compressed size, zero meaning unlimited.
+    pub ratio: usize,
+}
+
+impl From<usize> for Limits {
+    fn from(size: usize) -> Self {
+        Self { size, ratio: 0 }
+    }
+}
+
+impl Limits {
+    pub const fn new(size: usize, ratio: usize) -> Self {
+        Self { size, ratio }
+    }
+
+    /// The effective size limit for a compressed payload of the provided length.
+    ///
+    /// This is the smaller one of the size limit and the limit derived from the ratio, zero
+    /// meaning unlimited.
+    pub fn effective(&self, compressed: usize) -> usize {
+        let ratio = match self.ratio {
+            0 => 0,
+            ratio => compressed.saturating_mul(ratio).max(1),
+        };
+
+        match (self.size, ratio) {
+            (0, ratio) => ratio,
+            (size, 0) => size,
+            (size, ratio) => size.min(ratio),
+        }
+    }
 }
 
 /// Take some bytes, and an optional content-type header and decompress, if required.
@@ -25,6 +64,9 @@ pub enum Error {
 ///
 /// If no magic bytes could be detected, it will assume the content is not compressed.
 ///
+/// The limits are enforced while decompressing, so that the payload is never fully inflated
+/// when exceeding them.
+///
 /// **NOTE:** Depending on the size of the payload, this method might take some time. In an async
 /// context, it might be necessary to run this as a blocking function, or use [`decompress_async`]
 /// instead.
@@ -32,7 +74,7 @@ pub enum Error {
 pub fn decompress(
     bytes: Bytes,
     content_type: Option<header::ContentType>,
-    limit: usize,
+    limits: impl Into<Limits> + Debug,
 ) -> Result<Bytes, Error> {
     let content_type = content_type.as_ref().map(|ct| ct.as_ref());
 
@@ -66,10 +108,12 @@ pub fn decompress(
 
     // decompress (or not)
 
+    let limit = limits.into().effective(bytes.len());
+
     compression
         .decompress_with(bytes, &DecompressionOptions::default().limit(limit))
         .map_err(|err| match err.kind() {
-            std::io::ErrorKind::WriteZero => Error::PayloadTooLarge,
+            std::io::ErrorKind::WriteZero => Error::PayloadTooLarge { limit },
             _ => Error::from(err),
         })
 }
@@ -79,16 +123,17 @@ pub fn decompress(
 pub async fn decompress_async(
     bytes: Bytes,
     content_type: Option<header::ContentType>,
-    limit: usize,
+    limits: impl Into<Limits> + Debug,
 ) -> Result<Result<Bytes, Error>, JoinError> {
+    let limits = limits.into();
     Handle::current()
-        .spawn_blocking(move || decompress(bytes, content_type, limit))
+        .spawn_blocking(move || decompress(bytes, content_type, limits))
         .await
 }
 
 #[cfg(test)]
 mod test {
-    use crate::decompress::decompress_async;
+    use crate::decompress::{Error, Limits, decompress_async};
     use actix_web::http::header::ContentType;
     use test_log::test;
     use trustify_test_context::document_bytes_raw;
@@ -186,4 +231,37 @@ mod test {
 
         Ok(())
     }
+
+    #[test(tokio::test)]
+    async fn decompress_xz_with_ratio() -> anyhow::Result<()> {
+        let bytes = document_bytes_raw("openshift-container-storage-4.8.z.json.xz").await?;
+
+        let result = decompress_async(bytes.clone(), None, Limits::new(0, 2)).await?;
+
+        // must be an error, as the ratio is exceeded
+
+        assert!(
+            matches!(result, Err(Error::PayloadTooLarge { limit }) if limit == bytes.len() * 2),
+            "{result:?}"
+        );
+
+        // a sufficient ratio must work
+
+        let bytes = decompress_async(bytes, None, Limits::new(0, 1000)).await??;
+        let _json: serde_json::Value = serde_json::from_slice(&bytes)?;
+
+        // done
+
+        Ok(())
+    }
+
+    #[test]
+    fn effective_limit() {
+        assert_eq!(Limits::new(0, 0).effective(100), 0);
+        assert_eq!(Limits::new(1000, 0).effective(100), 1000);
+        assert_eq!(Limits::new(0, 5).effective(100), 500);
+        assert_eq!(Limits::new(1000, 5).effective(100), 500);
+        assert_eq!(Limits::new(400, 5).effective(100), 400);
+        assert_eq!(Limits::new(0, 5).effective(0), 1);
+    }
 }
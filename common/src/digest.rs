@@ -0,0 +1,167 @@
+//! Verifying uploaded content against digests provided by the client.
+//!
+//! Clients may provide digests of the request body using the `Digest` header (RFC 3230), like
+//! `Digest: sha-256=X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=`. Supported algorithms are
+//! `sha-256` and `sha-512`, others are ignored. If present, the body must match all supported
+//! digests, otherwise the request gets rejected.
+
+use crate::error::ErrorInformation;
+use actix_web::{
+    FromRequest, HttpRequest, HttpResponse, ResponseError,
+    dev::Payload,
+    http::header::{HeaderMap, HeaderName},
+    web,
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use bytes::Bytes;
+use ring::digest::{Algorithm, SHA256, SHA512, digest};
+use std::{future::Future, pin::Pin};
+
+/// The name of the header, carrying the digests.
+pub const DIGEST: HeaderName = HeaderName::from_static("digest");
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("invalid digest header: {0}")]
+    Invalid(String),
+    #[error("content doesn't match the provided {0} digest")]
+    Mismatch(&'static str),
+}
+
+impl ResponseError for Error {
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::BadRequest().json(ErrorInformation {
+            error: match self {
+                Self::Invalid(_) => "InvalidDigest",
+                Self::Mismatch(_) => "DigestMismatch",
+            }
+            .into(),
+            message: self.to_string(),
+            details: None,
+        })
+    }
+}
+
+/// A digest, expected for the content.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExpectedDigest {
+    /// The name of the algorithm, as used in the header
+    pub algorithm: &'static str,
+    pub value: Vec<u8>,
+}
+
+impl ExpectedDigest {
+    /// Parse the supported digests from the headers of a request.
+    pub fn from_headers(headers: &HeaderMap) -> Result<Vec<Self>, Error> {
+        let mut result = vec![];
+
+        for header in headers.get_all(DIGEST) {
+            let header = header
+                .to_str()
+                .map_err(|err| Error::Invalid(err.to_string()))?;
+
+            for entry in header.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                let (algorithm, value) = entry
+                    .split_once('=')
+                    .ok_or_else(|| Error::Invalid(format!("missing value: {entry}")))?;
+
+                let (algorithm, len) = match algorithm.trim().to_ascii_lowercase().as_str() {
+                    "sha-256" => ("sha-256", SHA256.output_len()),
+                    "sha-512" => ("sha-512", SHA512.output_len()),
+                    _ => continue,
+                };
+
+                let value = STANDARD
+                    .decode(value.trim())
+                    .map_err(|err| Error::Invalid(format!("{algorithm}: {err}")))?;
+                if value.len() != len {
+                    return Err(Error::Invalid(format!("{algorithm}: invalid length")));
+                }
+
+                result.push(Self { algorithm, value });
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn ring_algorithm(&self) -> &'static Algorithm {
+        match self.algorithm {
+            "sha-512" => &SHA512,
+            _ => &SHA256,
+        }
+    }
+
+    /// Verify the content matches all expected digests.
+    pub fn verify(expected: &[Self], content: &[u8]) -> Result<(), Error> {
+        for expected in expected {
+            if digest(expected.ring_algorithm(), content).as_ref() != expected.value {
+                return Err(Error::Mismatch(expected.algorithm));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The body of a request, verified against the digests provided using the `Digest` header.
+///
+/// The digests are verified against the body as received, before decompressing it.
+#[derive(Clone, Debug)]
+pub struct VerifiedBytes(pub Bytes);
+
+impl FromRequest for VerifiedBytes {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let expected = ExpectedDigest::from_headers(req.headers());
+        let bytes = web::Bytes::from_request(req, payload);
+
+        Box::pin(async move {
+            let expected = expected?;
+            let bytes = bytes.await?;
+            ExpectedDigest::verify(&expected, &bytes)?;
+            Ok(Self(bytes))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::http::header::HeaderValue;
+
+    fn headers(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(DIGEST, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn verify() {
+        let sha256 = STANDARD.encode(digest(&SHA256, b"content"));
+
+        let expected =
+            ExpectedDigest::from_headers(&headers(&format!("MD5=ignored, SHA-256={sha256}")))
+                .unwrap();
+        assert_eq!(expected.len(), 1);
+        assert!(ExpectedDigest::verify(&expected, b"content").is_ok());
+        assert!(matches!(
+            ExpectedDigest::verify(&expected, b"corrupted"),
+            Err(Error::Mismatch("sha-256"))
+        ));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(ExpectedDigest::from_headers(&headers("sha-256")).is_err());
+        assert!(ExpectedDigest::from_headers(&headers("sha-256=!!!")).is_err());
+        assert!(ExpectedDigest::from_headers(&headers("sha-256=YWJj")).is_err());
+        assert!(
+            ExpectedDigest::from_headers(&HeaderMap::new())
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -1,9 +1,11 @@
 use std::borrow::Cow;
 use std::fmt::Display;
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+/// The body of error responses
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct ErrorInformation {
     /// A machine-readable error type
+    #[schema(value_type = String)]
     pub error: Cow<'static, str>,
     /// A human-readable error message
     #[serde(default, skip_serializing_if = "String::is_empty")]
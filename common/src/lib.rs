@@ -1,8 +1,10 @@
 pub mod advisory;
 pub mod config;
 pub mod cpe;
+pub mod cwe;
 pub mod db;
 pub mod decompress;
+pub mod digest;
 pub mod error;
 pub mod hashing;
 pub mod id;
@@ -13,6 +15,7 @@ pub mod purl;
 pub mod reqwest;
 pub mod sbom;
 pub mod serde;
+pub mod signing;
 pub mod time;
 pub mod tls;
 pub mod uuid;
@@ -1,7 +1,7 @@
 mod bytesize;
 pub use bytesize::*;
 
-use crate::db::limiter::Limiter;
+use crate::db::limiter::{Count, Limiter};
 use sea_orm::{ConnectionTrait, DbErr, SelectorTrait};
 use std::cmp::min;
 use std::marker::PhantomData;
@@ -37,6 +37,12 @@ pub struct Paginated {
     /// Zero means: no limit
     #[serde(default = "default::limit")]
     pub limit: u64,
+    /// How to count the total number of items.
+    ///
+    /// Counting all items can be expensive for large result sets.
+    #[serde(default)]
+    #[param(inline)]
+    pub count: Count,
 }
 
 impl Paginated {
@@ -121,6 +127,7 @@ mod test {
         let paginated = Paginated {
             offset: 0,
             limit: 0,
+            ..Default::default()
         }
         .paginate_array(&data);
 
@@ -130,6 +137,7 @@ mod test {
         let paginated = Paginated {
             offset: 0,
             limit: 5,
+            ..Default::default()
         }
         .paginate_array(&data);
 
@@ -139,6 +147,7 @@ mod test {
         let paginated = Paginated {
             offset: 5,
             limit: 0,
+            ..Default::default()
         }
         .paginate_array(&data);
 
@@ -148,6 +157,7 @@ mod test {
         let paginated = Paginated {
             offset: 12,
             limit: 0,
+            ..Default::default()
         }
         .paginate_array(&data);
 
@@ -81,6 +81,9 @@ const NAMESPACE: Uuid = Uuid::from_bytes([
     0x37, 0x38, 0xb4, 0x3d, 0xfd, 0x03, 0x4a, 0x9d, 0x84, 0x9c, 0x48, 0x9b, 0xec, 0x61, 0x0f, 0x06,
 ]);
 
+/// Types, for which the purl-spec requires namespace and name to be lowercased.
+const CASE_INSENSITIVE_TYPES: &[&str] = &["bitbucket", "composer", "github", "hex", "npm", "pypi"];
+
 impl Purl {
     // clone from self with the passed version
     pub fn with_version<T: ToString>(&self, version: T) -> Self {
@@ -89,7 +92,51 @@ impl Purl {
         result
     }
 
+    /// Create the canonical form of the PURL, as defined by the purl-spec.
+    ///
+    /// The type and the qualifier keys are lowercased, and qualifiers without a value are
+    /// dropped. Namespace and name are lowercased for types which treat them case-insensitive.
+    /// For `pypi`, underscores in the name are replaced with dashes.
+    ///
+    /// PURLs which only differ in their non-canonical parts share the same canonical form, and
+    /// so the same IDs.
+    pub fn canonical(&self) -> Self {
+        let ty = self.ty.to_lowercase();
+
+        let (namespace, mut name) = if CASE_INSENSITIVE_TYPES.contains(&ty.as_str()) {
+            (
+                self.namespace.as_ref().map(|ns| ns.to_lowercase()),
+                self.name.to_lowercase(),
+            )
+        } else {
+            (self.namespace.clone(), self.name.clone())
+        };
+        if ty == "pypi" {
+            name = name.replace('_', "-");
+        }
+
+        let qualifiers = self
+            .qualifiers
+            .iter()
+            .filter(|(_, v)| !v.is_empty())
+            .map(|(k, v)| (k.to_lowercase(), v.clone()))
+            .collect();
+
+        Self {
+            ty,
+            namespace,
+            name,
+            version: self.version.clone(),
+            qualifiers,
+        }
+    }
+
     pub fn package_uuid(&self) -> Uuid {
+        self.canonical().then_package_uuid()
+    }
+
+    /// The package ID, assuming `self` is canonical
+    fn then_package_uuid(&self) -> Uuid {
         let mut result = Uuid::new_v5(&NAMESPACE, self.ty.as_bytes());
         if let Some(namespace) = &self.namespace {
             result = Uuid::new_v5(&result, namespace.as_bytes());
@@ -108,7 +155,8 @@ impl Purl {
     }
 
     pub fn version_uuid(&self) -> Uuid {
-        self.then_version_uuid(&self.package_uuid())
+        let purl = self.canonical();
+        purl.then_version_uuid(&purl.then_package_uuid())
     }
 
     fn then_qualifier_uuid(&self, version: &Uuid) -> Uuid {
@@ -122,13 +170,15 @@ impl Purl {
     }
 
     pub fn qualifier_uuid(&self) -> Uuid {
-        self.then_qualifier_uuid(&self.version_uuid())
+        self.uuids().2
     }
 
+    /// The IDs of the package, version, and qualified PURL, derived from the canonical form.
     pub fn uuids(&self) -> (Uuid, Uuid, Uuid) {
-        let package = self.package_uuid();
-        let version = self.then_version_uuid(&package);
-        let qualified = self.then_qualifier_uuid(&version);
+        let purl = self.canonical();
+        let package = purl.then_package_uuid();
+        let version = purl.then_version_uuid(&package);
+        let qualified = purl.then_qualifier_uuid(&version);
         (package, version, qualified)
     }
 
@@ -398,6 +448,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn canonical() -> Result<(), anyhow::Error> {
+        let purl = Purl::from_str("pkg:npm/Lodash@1.0")?;
+        assert_eq!(purl.canonical(), Purl::from_str("pkg:npm/lodash@1.0")?);
+        assert_eq!(purl.uuids(), Purl::from_str("pkg:npm/lodash@1.0")?.uuids());
+
+        let purl = Purl::from_str("pkg:github/Package-URL/Purl-Spec@244fd47e07d1004")?;
+        assert_eq!(
+            purl.canonical().to_string(),
+            "pkg:github/package-url/purl-spec@244fd47e07d1004"
+        );
+
+        let purl = Purl::from_str("pkg:pypi/Django_Allauth@0.1")?;
+        assert_eq!(purl.canonical().to_string(), "pkg:pypi/django-allauth@0.1");
+
+        // qualifiers without a value are the same as no qualifier
+        let purl = Purl::from_str("pkg:rpm/redhat/filesystem@3.8-6.el8?arch=aarch64&distro=")?;
+        assert_eq!(
+            purl.canonical(),
+            Purl::from_str("pkg:rpm/redhat/filesystem@3.8-6.el8?arch=aarch64")?
+        );
+
+        // case-sensitive types keep their case
+        let purl = Purl::from_str("pkg:maven/org.Apache/Commons@1.0")?;
+        assert_eq!(purl.canonical(), purl);
+
+        Ok(())
+    }
+
     #[test(tokio::test)]
     async fn purl_encoding() -> Result<(), anyhow::Error> {
         let purl = Purl::from_str("pkg:npm/@fastify/this@that@3.8-%236.el8")?;
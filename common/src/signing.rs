@@ -0,0 +1,306 @@
+//! Signing of API responses.
+//!
+//! When a signing key is configured, selected endpoints respond with a canonical JSON body and a
+//! detached JWS ([RFC 7515, Appendix F](https://www.rfc-editor.org/rfc/rfc7515#appendix-F)) over
+//! that body, in the [`SIGNATURE_HEADER`] header. Downstream systems can keep both, proving what
+//! was reported at the time of the request.
+//!
+//! The body is canonicalized by sorting the keys of all objects, and serializing without any
+//! whitespace. As the signature is created over the exact bytes of the body, verifying it does
+//! not require canonicalizing the body again:
+//!
+//! 1. Split the header value into the protected header and the signature (`<header>..<signature>`)
+//! 2. Verify the signature over `<header>.<base64url(body)>`, using the key from the JWK set at
+//!    `/.well-known/trustify/jwks.json`, matching the `kid` of the protected header.
+//!
+//! The protected header also carries the time of signing, as `iat`.
+
+use actix_web::{HttpResponse, HttpResponseBuilder, http::header::ContentType};
+use anyhow::{Context, anyhow};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use ring::{
+    digest::{SHA256, digest},
+    rand::SystemRandom,
+    signature::{Ed25519KeyPair, KeyPair},
+};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::{fmt::Debug, path::Path, sync::Arc};
+use time::OffsetDateTime;
+
+/// The name of the header, carrying the detached JWS.
+pub const SIGNATURE_HEADER: &str = "x-jws-signature";
+
+/// An Ed25519 key, used for signing responses.
+pub struct SigningKey {
+    key: Ed25519KeyPair,
+    kid: String,
+}
+
+impl Debug for SigningKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // never show the private key
+        f.debug_struct("SigningKey")
+            .field("kid", &self.kid)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SigningKey {
+    /// Create a key from a PEM encoded PKCS#8 document.
+    pub fn from_pem(pem: &[u8]) -> anyhow::Result<Self> {
+        let pem = pem::parse(pem)?;
+        if pem.tag() != "PRIVATE KEY" {
+            anyhow::bail!("expected a PKCS#8 private key, found: {}", pem.tag());
+        }
+
+        Self::from_pkcs8(pem.contents())
+    }
+
+    /// Create a key from a DER encoded PKCS#8 document.
+    pub fn from_pkcs8(der: &[u8]) -> anyhow::Result<Self> {
+        let key = Ed25519KeyPair::from_pkcs8_maybe_unchecked(der)
+            .map_err(|err| anyhow!("invalid Ed25519 key: {err}"))?;
+        let kid = thumbprint(&public_key(&key));
+
+        Ok(Self { key, kid })
+    }
+
+    /// Generate a new, ephemeral key.
+    pub fn generate() -> anyhow::Result<Self> {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|err| anyhow!("failed to generate key: {err}"))?;
+        Self::from_pkcs8(pkcs8.as_ref())
+    }
+
+    /// Load a key from a PEM encoded PKCS#8 file.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let pem = std::fs::read(path)
+            .with_context(|| format!("failed to read signing key: {}", path.display()))?;
+        Self::from_pem(&pem).with_context(|| format!("invalid signing key: {}", path.display()))
+    }
+
+    /// The ID of the key, its JWK thumbprint (RFC 7638).
+    pub fn kid(&self) -> &str {
+        &self.kid
+    }
+
+    /// The public key, as JWK.
+    pub fn jwk(&self) -> Value {
+        json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": public_key(&self.key),
+            "kid": self.kid,
+            "alg": "EdDSA",
+            "use": "sig",
+        })
+    }
+
+    /// Create a detached JWS over the payload.
+    pub fn sign(&self, payload: &[u8], iat: OffsetDateTime) -> String {
+        let header = json!({
+            "alg": "EdDSA",
+            "kid": self.kid,
+            "iat": iat.unix_timestamp(),
+        });
+        let header = URL_SAFE_NO_PAD.encode(header.to_string());
+        let input = format!("{header}.{}", URL_SAFE_NO_PAD.encode(payload));
+        let signature = self.key.sign(input.as_bytes());
+
+        format!("{header}..{}", URL_SAFE_NO_PAD.encode(signature))
+    }
+}
+
+fn public_key(key: &Ed25519KeyPair) -> String {
+    URL_SAFE_NO_PAD.encode(key.public_key())
+}
+
+fn thumbprint(x: &str) -> String {
+    // the required members, in lexicographic order, without whitespace
+    let jwk = format!(r#"{{"crv":"Ed25519","kty":"OKP","x":"{x}"}}"#);
+    URL_SAFE_NO_PAD.encode(digest(&SHA256, jwk.as_bytes()))
+}
+
+/// Serialize a value to canonical JSON.
+///
+/// The members of objects are sorted by their keys (comparing UTF-16 code units, like RFC 8785)
+/// and no whitespace is emitted. Strings and numbers are serialized as by `serde_json`.
+pub fn canonical_json<T: Serialize>(value: &T) -> Result<Vec<u8>, serde_json::Error> {
+    fn write(value: &Value, out: &mut Vec<u8>) -> Result<(), serde_json::Error> {
+        match value {
+            Value::Object(members) => {
+                let mut members = members.iter().collect::<Vec<_>>();
+                members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+                out.push(b'{');
+                for (i, (key, value)) in members.into_iter().enumerate() {
+                    if i > 0 {
+                        out.push(b',');
+                    }
+                    serde_json::to_writer(&mut *out, key)?;
+                    out.push(b':');
+                    write(value, out)?;
+                }
+                out.push(b'}');
+            }
+            Value::Array(items) => {
+                out.push(b'[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(b',');
+                    }
+                    write(item, out)?;
+                }
+                out.push(b']');
+            }
+            value => serde_json::to_writer(&mut *out, value)?,
+        }
+
+        Ok(())
+    }
+
+    let mut out = Vec::new();
+    write(&serde_json::to_value(value)?, &mut out)?;
+    Ok(out)
+}
+
+/// Creates JSON responses, signing them if a key is configured.
+#[derive(Clone, Debug, Default)]
+pub struct ResponseSigner {
+    key: Option<Arc<SigningKey>>,
+}
+
+// signers are considered equal when they use the same key
+impl PartialEq for ResponseSigner {
+    fn eq(&self, other: &Self) -> bool {
+        self.kid() == other.kid()
+    }
+}
+
+impl Eq for ResponseSigner {}
+
+impl ResponseSigner {
+    pub fn new(key: Option<SigningKey>) -> Self {
+        Self {
+            key: key.map(Arc::new),
+        }
+    }
+
+    /// The ID of the signing key, if signing is enabled.
+    pub fn kid(&self) -> Option<&str> {
+        self.key.as_deref().map(SigningKey::kid)
+    }
+
+    /// The public keys, as JWK set. Empty if signing is disabled.
+    pub fn jwks(&self) -> Value {
+        json!({
+            "keys": self.key.iter().map(|key| key.jwk()).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Complete the response with a JSON body.
+    ///
+    /// If signing is enabled, the body is serialized as canonical JSON, and the signature is
+    /// added as header. Otherwise, it's the same as calling [`HttpResponseBuilder::json`].
+    pub fn json<T: Serialize>(&self, mut response: HttpResponseBuilder, value: T) -> HttpResponse {
+        let Some(key) = &self.key else {
+            return response.json(value);
+        };
+
+        match canonical_json(&value) {
+            Ok(body) => response
+                .content_type(ContentType::json())
+                .insert_header((SIGNATURE_HEADER, key.sign(&body, OffsetDateTime::now_utc())))
+                .body(body),
+            Err(err) => HttpResponse::from_error(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::body::to_bytes;
+    use ring::signature::UnparsedPublicKey;
+    use test_log::test;
+
+    #[test]
+    fn canonical() -> anyhow::Result<()> {
+        let value = json!({
+            "b": [3, {"z": null, "a": "ä"}],
+            "a": 1.5,
+            "c": {"y": true, "x": "line\nbreak"},
+        });
+
+        assert_eq!(
+            String::from_utf8(canonical_json(&value)?)?,
+            r#"{"a":1.5,"b":[3,{"a":"ä","z":null}],"c":{"x":"line\nbreak","y":true}}"#
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sign_and_verify() -> anyhow::Result<()> {
+        let key = SigningKey::generate()?;
+        let payload = br#"{"a":1}"#;
+
+        let jws = key.sign(payload, OffsetDateTime::from_unix_timestamp(1_700_000_000)?);
+        let (header, signature) = jws.split_once("..").expect("must be detached");
+
+        let protected: Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header)?)?;
+        assert_eq!(protected["alg"], "EdDSA");
+        assert_eq!(protected["kid"], key.kid());
+        assert_eq!(protected["iat"], 1_700_000_000);
+
+        let jwk = key.jwk();
+        let public_key = URL_SAFE_NO_PAD.decode(jwk["x"].as_str().expect("must be a string"))?;
+        let public_key = UnparsedPublicKey::new(&ring::signature::ED25519, public_key);
+
+        let input = format!("{header}.{}", URL_SAFE_NO_PAD.encode(payload));
+        let signature = URL_SAFE_NO_PAD.decode(signature)?;
+        assert!(public_key.verify(input.as_bytes(), &signature).is_ok());
+        // a different payload must not verify
+        let input = format!("{header}.{}", URL_SAFE_NO_PAD.encode(br#"{"a":2}"#));
+        assert!(public_key.verify(input.as_bytes(), &signature).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn pem() -> anyhow::Result<()> {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).expect("must generate");
+        let pem = pem::encode(&pem::Pem::new("PRIVATE KEY", pkcs8.as_ref()));
+        let key = SigningKey::from_pem(pem.as_bytes())?;
+        assert_eq!(key.kid().len(), 43);
+
+        let pem = pem::encode(&pem::Pem::new("PUBLIC KEY", pkcs8.as_ref()));
+        assert!(SigningKey::from_pem(pem.as_bytes()).is_err());
+
+        Ok(())
+    }
+
+    #[test(actix_web::test)]
+    async fn respond() -> anyhow::Result<()> {
+        let value = json!({"b": 1, "a": 2});
+
+        let response = ResponseSigner::default().json(HttpResponse::Ok(), &value);
+        assert!(response.headers().get(SIGNATURE_HEADER).is_none());
+
+        let signer = ResponseSigner::new(Some(SigningKey::generate()?));
+        let response = signer.json(HttpResponse::Ok(), &value);
+        assert!(response.headers().get(SIGNATURE_HEADER).is_some());
+        let body = to_bytes(response.into_body()).await.expect("must read");
+        assert_eq!(body.as_ref(), br#"{"a":2,"b":1}"#);
+
+        assert_eq!(
+            signer.jwks()["keys"][0]["kid"],
+            signer.kid().expect("must be set")
+        );
+        assert_eq!(ResponseSigner::default().jwks(), json!({"keys": []}));
+
+        Ok(())
+    }
+}
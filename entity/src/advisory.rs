@@ -30,6 +30,10 @@ pub struct Model {
     pub title: Option<String>,
     pub labels: Labels,
     pub source_document_id: Option<Uuid>,
+    /// An ID provided by the client, for correlating the advisory with external systems
+    pub external_id: Option<String>,
+    /// When the advisory got marked as deleted, keeping it restorable
+    pub deleted_at: Option<OffsetDateTime>,
 }
 
 #[ComplexObject]
@@ -75,6 +79,9 @@ pub enum Relation {
 
     #[sea_orm(has_many = "super::advisory_vulnerability::Entity")]
     AdvisoryVulnerability,
+
+    #[sea_orm(has_many = "super::ingestion_warning::Entity")]
+    Warnings,
 }
 
 impl Related<super::source_document::Entity> for Entity {
@@ -111,6 +118,12 @@ impl Related<cvss3::Entity> for Entity {
     }
 }
 
+impl Related<super::ingestion_warning::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Warnings.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
 
 impl TryFilterForId for Entity {
@@ -0,0 +1,42 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A run of deleting all advisories matching a query
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "advisory_deletion")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub state: State,
+
+    /// the number of advisories to delete
+    pub total: i64,
+    /// the number of advisories deleted so far
+    pub deleted: i64,
+
+    /// the tenant of the user starting the deletion, if tenants are isolated
+    pub tenant: Option<String>,
+    /// the user starting the deletion, absent if authentication is disabled
+    pub user_id: Option<String>,
+
+    pub started: OffsetDateTime,
+    pub finished: Option<OffsetDateTime>,
+    /// the last time the running deletion made progress, detecting interrupted deletions
+    pub heartbeat: OffsetDateTime,
+
+    /// the error message, in case of a failure
+    pub error: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "i32", db_type = "Integer")]
+pub enum State {
+    Running = 0,
+    Succeeded = 1,
+    Failed = 2,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
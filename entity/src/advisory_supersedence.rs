@@ -0,0 +1,34 @@
+use sea_orm::entity::prelude::*;
+
+/// An advisory superseding another one.
+///
+/// The superseded advisory is referenced by its identifier, as it might not be ingested yet, and
+/// covers all of its versions.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "advisory_supersedence")]
+pub struct Model {
+    /// The superseding advisory
+    #[sea_orm(primary_key)]
+    pub advisory_id: Uuid,
+    /// The identifier of the superseded advisory
+    #[sea_orm(primary_key)]
+    pub supersedes: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::advisory::Entity",
+        from = "Column::AdvisoryId",
+        to = "super::advisory::Column::Id"
+    )]
+    Advisory,
+}
+
+impl Related<super::advisory::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Advisory.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,83 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A vulnerability, assigned to a user or team for triage
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "assignment")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub vulnerability_id: String,
+    /// the SBOM, in case the assignment is about a finding in an SBOM
+    pub sbom_id: Option<Uuid>,
+    /// the user or team the vulnerability is assigned to
+    pub assignee: String,
+    pub state: State,
+    pub due: Option<OffsetDateTime>,
+    /// the tenant owning the assignment, if tenants are isolated
+    pub tenant: Option<String>,
+    pub created: OffsetDateTime,
+    pub modified: OffsetDateTime,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "assignment_state")]
+#[serde(rename_all = "snake_case")]
+#[schema(as = AssignmentState)]
+pub enum State {
+    #[sea_orm(string_value = "new")]
+    New,
+    #[sea_orm(string_value = "in_progress")]
+    InProgress,
+    #[sea_orm(string_value = "resolved")]
+    Resolved,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::vulnerability::Entity",
+        from = "Column::VulnerabilityId",
+        to = "super::vulnerability::Column::Id"
+    )]
+    Vulnerability,
+    #[sea_orm(
+        belongs_to = "super::sbom::Entity",
+        from = "Column::SbomId",
+        to = "super::sbom::Column::SbomId"
+    )]
+    Sbom,
+    #[sea_orm(has_many = "super::assignment_event::Entity")]
+    Events,
+}
+
+impl Related<super::vulnerability::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Vulnerability.def()
+    }
+}
+
+impl Related<super::sbom::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Sbom.def()
+    }
+}
+
+impl Related<super::assignment_event::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Events.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,36 @@
+use crate::assignment::State;
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A change of an assignment, recording the state after the change
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "assignment_event")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub assignment_id: Uuid,
+    pub timestamp: OffsetDateTime,
+    /// the user performing the change, `None` if authentication is disabled
+    pub user_id: Option<String>,
+    pub assignee: String,
+    pub state: State,
+    pub due: Option<OffsetDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::assignment::Entity",
+        from = "Column::AssignmentId",
+        to = "super::assignment::Column::Id"
+    )]
+    Assignment,
+}
+
+impl Related<super::assignment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Assignment.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,53 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// An auxiliary file, like a scan report or a waiver, attached to an SBOM or an advisory
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "attachment")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// the SBOM, in case the file is attached to an SBOM
+    pub sbom_id: Option<Uuid>,
+    /// the advisory, in case the file is attached to an advisory
+    pub advisory_id: Option<Uuid>,
+    /// the file name
+    pub name: String,
+    pub content_type: String,
+    pub size: i64,
+    /// the digest of the content, identifying it in the storage
+    pub sha256: String,
+    /// the user uploading the file, `None` if authentication is disabled
+    pub uploader: Option<String>,
+    pub created: OffsetDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::sbom::Entity",
+        from = "Column::SbomId",
+        to = "super::sbom::Column::SbomId"
+    )]
+    Sbom,
+    #[sea_orm(
+        belongs_to = "super::advisory::Entity",
+        from = "Column::AdvisoryId",
+        to = "super::advisory::Column::Id"
+    )]
+    Advisory,
+}
+
+impl Related<super::sbom::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Sbom.def()
+    }
+}
+
+impl Related<super::advisory::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Advisory.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
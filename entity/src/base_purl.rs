@@ -8,6 +8,8 @@ pub struct Model {
     pub r#type: String,
     pub namespace: Option<String>,
     pub name: String,
+    /// The ecosystem of the package, derived from its type by the database
+    pub ecosystem: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
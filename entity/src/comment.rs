@@ -0,0 +1,50 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A note of an analyst on an advisory or a vulnerability
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "comment")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// the advisory, in case the comment is about an advisory
+    pub advisory_id: Option<Uuid>,
+    /// the vulnerability, in case the comment is about a vulnerability
+    pub vulnerability_id: Option<String>,
+    /// the user writing the comment, `None` if authentication is disabled
+    pub author: Option<String>,
+    /// the text of the comment, as Markdown
+    pub body: String,
+    pub created: OffsetDateTime,
+    pub modified: OffsetDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::advisory::Entity",
+        from = "Column::AdvisoryId",
+        to = "super::advisory::Column::Id"
+    )]
+    Advisory,
+    #[sea_orm(
+        belongs_to = "super::vulnerability::Entity",
+        from = "Column::VulnerabilityId",
+        to = "super::vulnerability::Column::Id"
+    )]
+    Vulnerability,
+}
+
+impl Related<super::advisory::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Advisory.def()
+    }
+}
+
+impl Related<super::vulnerability::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Vulnerability.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -14,6 +14,23 @@ pub struct Model {
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_many = "super::conversation_message::Entity")]
+    Messages,
+    #[sea_orm(has_many = "super::conversation_tool_invocation::Entity")]
+    ToolInvocations,
+}
+
+impl Related<super::conversation_message::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Messages.def()
+    }
+}
+
+impl Related<super::conversation_tool_invocation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ToolInvocations.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
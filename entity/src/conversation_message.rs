@@ -0,0 +1,34 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A message of a conversation shown to the user
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "conversation_message")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub conversation_id: Uuid,
+    /// the position of the message in the state of the conversation
+    #[sea_orm(primary_key)]
+    pub idx: i32,
+    pub message_type: String,
+    pub content: String,
+    pub timestamp: OffsetDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::conversation::Entity",
+        from = "Column::ConversationId",
+        to = "super::conversation::Column::Id"
+    )]
+    Conversation,
+}
+
+impl Related<super::conversation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Conversation.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A tool called by the LLM while generating the response of a conversation
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "conversation_tool_invocation")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub conversation_id: Uuid,
+    /// the position of the message requesting the call in the state of the conversation
+    pub idx: i32,
+    pub call_id: Option<String>,
+    pub name: String,
+    pub input: String,
+    /// the output of the tool, `None` if the tool did not reply
+    pub output: Option<String>,
+    pub timestamp: OffsetDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::conversation::Entity",
+        from = "Column::ConversationId",
+        to = "super::conversation::Column::Id"
+    )]
+    Conversation,
+}
+
+impl Related<super::conversation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Conversation.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
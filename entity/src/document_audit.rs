@@ -0,0 +1,73 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// An action on a document, recorded along with the acting user and client
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "document_audit")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub timestamp: OffsetDateTime,
+    /// the acting user, absent if authentication is disabled
+    pub user_id: Option<String>,
+    /// the address of the client, as seen by the server
+    pub client_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub action: Action,
+    pub document_kind: DocumentKind,
+    /// the ID of the SBOM or advisory, which might be deleted by now
+    pub document_id: Uuid,
+    /// details of the action, like the new labels
+    pub details: Option<serde_json::Value>,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "audit_action")]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    #[sea_orm(string_value = "uploaded")]
+    Uploaded,
+    #[sea_orm(string_value = "labels_changed")]
+    LabelsChanged,
+    #[sea_orm(string_value = "deleted")]
+    Deleted,
+    #[sea_orm(string_value = "restored")]
+    Restored,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "document_kind")]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentKind {
+    #[sea_orm(string_value = "sbom")]
+    Sbom,
+    #[sea_orm(string_value = "advisory")]
+    Advisory,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,54 @@
+use crate::{download_audit::DocumentKind, labels::Labels};
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A change of an SBOM or advisory, recorded by database triggers
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "document_change")]
+pub struct Model {
+    /// the order the changes got recorded in
+    #[sea_orm(primary_key)]
+    pub seq: i64,
+    /// the ID of the transaction performing the change
+    pub tx: i64,
+    pub timestamp: OffsetDateTime,
+    pub document_kind: DocumentKind,
+    /// the ID of the SBOM or advisory, which might be deleted by now
+    pub document_id: Uuid,
+    pub change: Change,
+    /// the labels of the document at the time of the change
+    pub labels: Labels,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "document_change_kind"
+)]
+#[serde(rename_all = "snake_case")]
+#[schema(as = ChangeKind)]
+pub enum Change {
+    #[sea_orm(string_value = "created")]
+    Created,
+    #[sea_orm(string_value = "updated")]
+    Updated,
+    #[sea_orm(string_value = "deleted")]
+    Deleted,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
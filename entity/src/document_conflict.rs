@@ -0,0 +1,28 @@
+use crate::download_audit::DocumentKind;
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// Two documents with the same identity, but different content
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "document_conflict")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub detected: OffsetDateTime,
+    pub document_kind: DocumentKind,
+    /// the format of the documents, like `csaf`
+    pub format: String,
+    /// the format specific ID, shared by both documents
+    pub identifier: String,
+    /// the revision, shared by both documents
+    pub revision: Option<String>,
+    /// the ID of the SBOM or advisory ingested first, which might be deleted by now
+    pub existing_id: Uuid,
+    /// the ID of the SBOM or advisory ingested later, which might be deleted by now
+    pub conflicting_id: Uuid,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
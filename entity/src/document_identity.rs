@@ -0,0 +1,28 @@
+use crate::download_audit::DocumentKind;
+use sea_orm::entity::prelude::*;
+
+/// The canonical identity of an ingested SBOM or advisory
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "document_identity")]
+pub struct Model {
+    /// the ID of the SBOM or advisory
+    #[sea_orm(primary_key)]
+    pub document_id: Uuid,
+    pub document_kind: DocumentKind,
+    /// the format of the document, like `csaf`
+    pub format: String,
+    /// the format specific ID of the document, like the publisher namespace and tracking ID of
+    /// a CSAF document
+    pub identifier: String,
+    /// the revision of the document, like the tracking version of a CSAF document
+    pub revision: Option<String>,
+    /// the SHA-256 digest of the canonical JSON representation of the document
+    pub canonical_sha256: String,
+    /// the tenant of the document, empty without tenant isolation
+    pub tenant: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+pub use crate::document_audit::DocumentKind;
+
+/// A download of a source document, accounting for the bytes served
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "download_audit")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub timestamp: OffsetDateTime,
+    /// the user downloading the document, absent if authentication is disabled
+    pub user_id: Option<String>,
+    pub document_kind: DocumentKind,
+    /// the ID of the SBOM or advisory, which might be deleted by now
+    pub document_id: Uuid,
+    pub bytes: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
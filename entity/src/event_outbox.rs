@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// An event about an ingested or deleted document, waiting to be published by the relay
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "event_outbox")]
+pub struct Model {
+    /// the order the events got recorded in
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub created: OffsetDateTime,
+    /// the key of the message, the ID of the document
+    pub key: String,
+    pub payload: serde_json::Value,
+    /// the number of failed attempts of publishing the event
+    pub attempts: i32,
+    /// the error of the last failed attempt
+    pub last_error: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
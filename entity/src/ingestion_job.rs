@@ -0,0 +1,50 @@
+use crate::labels::Labels;
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "ingestion_job")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub state: State,
+
+    /// the requested format of the document
+    pub format: String,
+    pub labels: Labels,
+    /// the SHA256 digest of the document, in the storage
+    pub document_key: String,
+    /// the external ID, applied to the ingested document
+    pub external_id: Option<String>,
+    /// the upload of the document, recorded in the audit trail of the ingested document
+    pub upload: Option<serde_json::Value>,
+    /// the tenant which submitted the job, if tenants are isolated
+    pub tenant: Option<String>,
+
+    pub submitted: OffsetDateTime,
+    pub started: Option<OffsetDateTime>,
+    /// updated while running, for detecting jobs of a crashed worker
+    pub heartbeat: Option<OffsetDateTime>,
+    /// the number of times processing the job was started
+    pub attempts: i32,
+    pub finished: Option<OffsetDateTime>,
+
+    /// the ingestion result, in case of success
+    pub result: Option<serde_json::Value>,
+    /// the error message, in case of a failure
+    pub error: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "i32", db_type = "Integer")]
+pub enum State {
+    Pending = 0,
+    Running = 1,
+    Succeeded = 2,
+    Failed = 3,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
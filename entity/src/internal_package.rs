@@ -0,0 +1,47 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A package published in a repository of an internal registry, as synced by an importer
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "internal_package")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// the name of the importer syncing the registry
+    pub importer: String,
+    /// the repository of the registry the package is published in
+    pub repository: String,
+    pub qualified_purl_id: Uuid,
+    /// when the package was last seen in the registry
+    pub last_seen: OffsetDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::importer::Entity",
+        from = "Column::Importer",
+        to = "super::importer::Column::Name"
+    )]
+    Importer,
+    #[sea_orm(
+        belongs_to = "super::qualified_purl::Entity",
+        from = "Column::QualifiedPurlId",
+        to = "super::qualified_purl::Column::Id"
+    )]
+    Purl,
+}
+
+impl Related<super::importer::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Importer.def()
+    }
+}
+
+impl Related<super::qualified_purl::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Purl.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
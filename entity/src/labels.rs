@@ -4,6 +4,7 @@ use std::{
     collections::HashMap,
     ops::{Deref, DerefMut},
 };
+use trustify_common::db::query::LabelFilters;
 use utoipa::{
     PartialSchema, ToSchema,
     openapi::{Object, ObjectBuilder, RefOr, Schema, Type, schema::AdditionalProperties},
@@ -141,6 +142,13 @@ impl DerefMut for Labels {
     }
 }
 
+/// Filter for documents having all the labels, with exactly these values.
+impl From<Labels> for LabelFilters {
+    fn from(value: Labels) -> Self {
+        value.0.into()
+    }
+}
+
 /// A module to serialize/deserialize labels with a prefix of `.labels`.
 ///
 /// This can be embedded in a struct like this:
@@ -1,40 +1,73 @@
 pub mod advisory;
+pub mod advisory_deletion;
+pub mod advisory_supersedence;
 pub mod advisory_vulnerability;
+pub mod assignment;
+pub mod assignment_event;
+pub mod attachment;
 pub mod base_purl;
+pub mod comment;
 pub mod conversation;
+pub mod conversation_message;
+pub mod conversation_tool_invocation;
 pub mod cpe;
 pub mod cvss3;
 pub mod cvss4;
+pub mod document_audit;
+pub mod document_change;
+pub mod document_conflict;
+pub mod document_identity;
+pub mod download_audit;
+pub mod event_outbox;
 pub mod importer;
 pub mod importer_report;
+pub mod ingestion_job;
+pub mod ingestion_warning;
+pub mod internal_package;
 pub mod labels;
 pub mod license;
 pub mod licensing_infos;
+pub mod notification;
 pub mod organization;
 pub mod package_relates_to_package;
 pub mod package_version_range;
+pub mod policy;
 pub mod product;
 pub mod product_status;
+pub mod product_status_summary;
 pub mod product_version;
 pub mod product_version_range;
 pub mod purl_status;
 pub mod qualified_purl;
+pub mod rebuild;
 pub mod relationship;
+pub mod saved_search;
 pub mod sbom;
 pub mod sbom_external_node;
 pub mod sbom_file;
+pub mod sbom_file_license;
 pub mod sbom_node;
 pub mod sbom_node_checksum;
 pub mod sbom_package;
 pub mod sbom_package_cpe_ref;
 pub mod sbom_package_license;
 pub mod sbom_package_purl_ref;
+pub mod sbom_predecessor;
+pub mod settings;
+pub mod settings_history;
+pub mod severity_override;
 pub mod source_document;
 pub mod status;
+pub mod status_recomputation;
+pub mod subscription;
+pub mod triage;
+pub mod triage_event;
 pub mod user_preferences;
 pub mod version_range;
 pub mod version_scheme;
 pub mod versioned_purl;
 pub mod vulnerability;
+pub mod vulnerability_alias;
+pub mod vulnerability_annotation;
 pub mod vulnerability_description;
 pub mod weakness;
@@ -0,0 +1,41 @@
+use crate::saved_search::Kind;
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A notification about an ingested document, matching the search of a subscription
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "notification")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    /// the user being notified
+    pub user_id: String,
+    /// the kind of the document
+    pub kind: Kind,
+    /// the ID of the SBOM or advisory
+    pub document_id: Uuid,
+    pub created: OffsetDateTime,
+    /// when the notification got delivered to the webhook of the subscription
+    pub delivered: Option<OffsetDateTime>,
+    /// the error of delivering the notification to the webhook
+    pub error: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::subscription::Entity",
+        from = "Column::SubscriptionId",
+        to = "super::subscription::Column::Id"
+    )]
+    Subscription,
+}
+
+impl Related<super::subscription::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Subscription.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -15,6 +15,8 @@ pub struct Model {
     // TODO: allow for external sbom namespace
     #[sea_orm(primary_key)]
     pub right_node_id: String,
+    /// The relationship type, as it was named in the original SBOM
+    pub original_relationship: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -49,6 +51,18 @@ pub enum Relation {
         to = "(super::sbom_package::Column::SbomId, super::sbom_package::Column::NodeId)"
     )]
     RightPackage,
+    #[sea_orm(
+        belongs_to = "super::sbom_external_node::Entity",
+        from = "(Column::SbomId, Column::LeftNodeId)",
+        to = "(super::sbom_external_node::Column::SbomId, super::sbom_external_node::Column::NodeId)"
+    )]
+    LeftExternal,
+    #[sea_orm(
+        belongs_to = "super::sbom_external_node::Entity",
+        from = "(Column::SbomId, Column::RightNodeId)",
+        to = "(super::sbom_external_node::Column::SbomId, super::sbom_external_node::Column::NodeId)"
+    )]
+    RightExternal,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -10,6 +10,8 @@ pub struct Model {
     pub name: String,
     pub vendor_id: Option<Uuid>,
     pub cpe_key: Option<String>,
+    /// the product this one is a stream or variant of
+    pub parent_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -26,6 +28,13 @@ pub enum Relation {
         from = "Column::CpeKey"
         to = "super::cpe::Column::Product")]
     Cpe,
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::ParentId",
+        to = "Column::Id",
+        on_delete = "SetNull"
+    )]
+    Parent,
 }
 
 impl Related<organization::Entity> for Entity {
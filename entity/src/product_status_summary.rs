@@ -0,0 +1,49 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// The effective status of a product regarding a vulnerability, derived from the product statuses
+/// of all advisories
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "product_status_summary")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub vulnerability_id: String,
+    #[sea_orm(primary_key)]
+    pub product_id: Uuid,
+
+    /// the slug of the status with the highest precedence
+    pub status: String,
+    /// the number of advisories stating a status
+    pub advisories: i32,
+    pub computed: OffsetDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::vulnerability::Entity",
+        from = "Column::VulnerabilityId",
+        to = "super::vulnerability::Column::Id"
+    )]
+    Vulnerability,
+    #[sea_orm(
+        belongs_to = "super::product::Entity",
+        from = "Column::ProductId",
+        to = "super::product::Column::Id"
+    )]
+    Product,
+}
+
+impl Related<super::vulnerability::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Vulnerability.def()
+    }
+}
+
+impl Related<super::product::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Product.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,4 +1,5 @@
 use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
 #[sea_orm(table_name = "product_version")]
@@ -8,6 +9,41 @@ pub struct Model {
     pub product_id: Uuid,
     pub sbom_id: Option<Uuid>,
     pub version: String,
+    /// the date the version became generally available
+    pub ga_date: Option<OffsetDateTime>,
+    /// the date the support of the version ends
+    pub eol_date: Option<OffsetDateTime>,
+    pub support_status: Option<SupportStatus>,
+}
+
+/// The support status of a product version
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "product_support_status"
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SupportStatus {
+    #[sea_orm(string_value = "full")]
+    Full,
+    #[sea_orm(string_value = "maintenance")]
+    Maintenance,
+    #[sea_orm(string_value = "extended")]
+    Extended,
+    #[sea_orm(string_value = "end_of_life")]
+    EndOfLife,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -37,4 +73,12 @@ impl Related<super::sbom::Entity> for Entity {
     }
 }
 
+impl Model {
+    /// Check if the version reached its end of life, by status or at the point in time `now`.
+    pub fn is_eol(&self, now: OffsetDateTime) -> bool {
+        self.support_status == Some(SupportStatus::EndOfLife)
+            || self.eol_date.is_some_and(|eol| eol <= now)
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
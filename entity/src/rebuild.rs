@@ -0,0 +1,38 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A run of rebuilding derived data, like after a migration
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "rebuild")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub state: State,
+
+    /// the tasks to run, in order
+    pub tasks: Vec<String>,
+    /// the tasks which already completed
+    pub completed: Vec<String>,
+
+    /// the tenant of the user starting the rebuild, if tenants are isolated
+    pub tenant: Option<String>,
+
+    pub started: OffsetDateTime,
+    pub finished: Option<OffsetDateTime>,
+
+    /// the error message, in case of a failure
+    pub error: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "i32", db_type = "Integer")]
+pub enum State {
+    Running = 0,
+    Succeeded = 1,
+    Failed = 2,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,48 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A search, saved by a user for running it again
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "saved_search")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// the user owning the search
+    pub user_id: String,
+    pub name: String,
+    pub kind: Kind,
+    pub q: String,
+    pub sort: String,
+    /// the label filters, as accepted by the `labels` query parameter
+    pub labels: String,
+    pub created: OffsetDateTime,
+    pub modified: OffsetDateTime,
+}
+
+/// The kind of documents searched for
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "saved_search_kind")]
+#[serde(rename_all = "snake_case")]
+#[schema(as = SavedSearchKind)]
+pub enum Kind {
+    #[sea_orm(string_value = "sbom")]
+    Sbom,
+    #[sea_orm(string_value = "advisory")]
+    Advisory,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -20,6 +20,10 @@ pub struct Model {
     pub data_licenses: Vec<String>,
 
     pub source_document_id: Option<Uuid>,
+    /// An ID provided by the client, for correlating the SBOM with external systems
+    pub external_id: Option<String>,
+    /// When the SBOM got marked as deleted, keeping it restorable
+    pub deleted_at: Option<OffsetDateTime>,
 
     #[graphql(derived(owned, into = "HashMap<String,String>", with = "Labels::from"))]
     pub labels: Labels,
@@ -49,6 +53,8 @@ pub enum Relation {
         to = "(super::sbom_node::Column::NodeId, super::sbom_node::Column::SbomId)"
     )]
     SbomNode,
+    #[sea_orm(has_many = "super::ingestion_warning::Entity")]
+    Warnings,
 }
 
 pub struct SbomPurlsLink;
@@ -129,6 +135,12 @@ impl Related<super::product_version::Entity> for Entity {
     }
 }
 
+impl Related<super::ingestion_warning::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Warnings.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
 
 impl TryFilterForId for Entity {
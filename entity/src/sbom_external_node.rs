@@ -77,6 +77,12 @@ pub enum Relation {
         to = "super::sbom::Column::SbomId"
     )]
     Sbom,
+    #[sea_orm(
+        belongs_to = "super::sbom::Entity",
+        from = "Column::TargetSbomId",
+        to = "super::sbom::Column::SbomId"
+    )]
+    TargetSbom,
     #[sea_orm(
         belongs_to = "super::package_relates_to_package::Entity",
         from = "Column::SbomId",
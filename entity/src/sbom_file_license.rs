@@ -0,0 +1,58 @@
+use crate::sbom_package_license::LicenseCategory;
+use sea_orm::entity::prelude::*;
+
+/// Licenses found for a file of an SBOM
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "sbom_file_license")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub sbom_id: Uuid,
+    #[sea_orm(primary_key)]
+    pub node_id: String,
+    #[sea_orm(primary_key)]
+    pub license_id: Uuid,
+    #[sea_orm(primary_key)]
+    pub license_type: LicenseCategory,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::sbom::Entity",
+        from = "Column::SbomId",
+        to = "super::sbom::Column::SbomId"
+    )]
+    Sbom,
+    #[sea_orm(
+        belongs_to = "super::sbom_file::Entity",
+        from = "(Column::SbomId, Column::NodeId)",
+        to = "(super::sbom_file::Column::SbomId, super::sbom_file::Column::NodeId)"
+    )]
+    File,
+    #[sea_orm(
+        belongs_to = "super::license::Entity",
+        from = "Column::LicenseId",
+        to = "super::license::Column::Id"
+    )]
+    License,
+}
+
+impl Related<super::sbom::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Sbom.def()
+    }
+}
+
+impl Related<super::sbom_file::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::File.def()
+    }
+}
+
+impl Related<super::license::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::License.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
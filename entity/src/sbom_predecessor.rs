@@ -0,0 +1,36 @@
+use sea_orm::entity::prelude::*;
+
+/// An SBOM, uploaded as an update of a previous SBOM of the same component.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "sbom_predecessor")]
+pub struct Model {
+    /// The updated SBOM
+    #[sea_orm(primary_key)]
+    pub sbom_id: Uuid,
+    /// The SBOM it superseded
+    pub predecessor_id: Uuid,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::sbom::Entity",
+        from = "Column::SbomId",
+        to = "super::sbom::Column::SbomId"
+    )]
+    Sbom,
+    #[sea_orm(
+        belongs_to = "super::sbom::Entity",
+        from = "Column::PredecessorId",
+        to = "super::sbom::Column::SbomId"
+    )]
+    Predecessor,
+}
+
+impl Related<super::sbom::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Sbom.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
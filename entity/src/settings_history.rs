@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "settings_history")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    /// the key of the settings which got changed
+    pub key: String,
+    /// the revision the change resulted in
+    pub revision: Uuid,
+    pub modified: OffsetDateTime,
+    /// the ID of the user performing the change, if authentication is enabled
+    pub user_id: Option<String>,
+    /// the settings, as stored by the change
+    pub data: serde_json::Value,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,40 @@
+use crate::cvss3::Severity;
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// The severity of a vulnerability as defined by an admin, overriding the severity of the
+/// advisories
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "severity_override")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub vulnerability_id: String,
+    /// the tenant the override applies to, empty without tenant isolation
+    #[sea_orm(primary_key)]
+    pub tenant: String,
+    pub severity: Severity,
+    /// the reasoning behind the override, like why a vulnerability is irrelevant for the org
+    pub reason: Option<String>,
+    /// the user defining the override
+    pub user_id: Option<String>,
+    pub created: OffsetDateTime,
+    pub modified: OffsetDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::vulnerability::Entity",
+        from = "Column::VulnerabilityId",
+        to = "super::vulnerability::Column::Id"
+    )]
+    Vulnerability,
+}
+
+impl Related<super::vulnerability::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Vulnerability.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,40 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A run of recomputing the cached product statuses, like after changing the status precedence
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "status_recomputation")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub state: State,
+
+    /// the vulnerability the recomputation is limited to
+    pub vulnerability_id: Option<String>,
+    /// the product the recomputation is limited to
+    pub product_id: Option<Uuid>,
+
+    /// the number of vulnerabilities to process
+    pub total: i32,
+    /// the number of vulnerabilities already processed
+    pub processed: i32,
+
+    pub started: OffsetDateTime,
+    pub finished: Option<OffsetDateTime>,
+
+    /// the error message, in case of a failure
+    pub error: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "i32", db_type = "Integer")]
+pub enum State {
+    Running = 0,
+    Succeeded = 1,
+    Failed = 2,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
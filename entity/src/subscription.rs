@@ -0,0 +1,36 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A subscription of a user to a saved search, notifying about newly ingested matches
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "subscription")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    /// the user owning the subscription
+    pub user_id: String,
+    pub saved_search_id: Uuid,
+    /// the URL notifications get posted to, in addition to storing them
+    pub webhook: Option<String>,
+    /// the tenant of the user, limiting the documents the subscription gets notified about
+    pub tenant: Option<String>,
+    pub created: OffsetDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::saved_search::Entity",
+        from = "Column::SavedSearchId",
+        to = "super::saved_search::Column::Id"
+    )]
+    SavedSearch,
+}
+
+impl Related<super::saved_search::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SavedSearch.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
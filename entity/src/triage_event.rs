@@ -0,0 +1,36 @@
+use crate::triage::State;
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A change of a triage, recording the assessment after the change
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "triage_event")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub triage_id: Uuid,
+    pub timestamp: OffsetDateTime,
+    /// the user performing the change, `None` if authentication is disabled
+    pub user_id: Option<String>,
+    pub state: State,
+    pub assignee: Option<String>,
+    pub justification: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::triage::Entity",
+        from = "Column::TriageId",
+        to = "super::triage::Column::Id"
+    )]
+    Triage,
+}
+
+impl Related<super::triage::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Triage.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
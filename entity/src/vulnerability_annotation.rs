@@ -0,0 +1,69 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// Threat intelligence about a vulnerability, supplied by a user or a feed
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "vulnerability_annotation")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub vulnerability_id: String,
+    pub kind: Kind,
+    /// a link to further information, like a proof of concept
+    pub url: Option<String>,
+    pub comment: Option<String>,
+    /// the feed supplying the annotation, if not supplied by a user
+    pub source: Option<String>,
+    /// the user creating the annotation
+    pub user_id: Option<String>,
+    /// the tenant of the user creating the annotation, absent for feeds and without tenant
+    /// isolation
+    pub tenant: Option<String>,
+    pub created: OffsetDateTime,
+    pub modified: OffsetDateTime,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "annotation_kind")]
+#[serde(rename_all = "snake_case")]
+#[schema(as = AnnotationKind)]
+pub enum Kind {
+    /// An exploit is publicly available
+    #[sea_orm(string_value = "exploit_available")]
+    ExploitAvailable,
+    /// A proof of concept exists
+    #[sea_orm(string_value = "proof_of_concept")]
+    ProofOfConcept,
+    /// The vulnerability is being exploited in the wild
+    #[sea_orm(string_value = "actively_exploited")]
+    ActivelyExploited,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::vulnerability::Entity",
+        from = "Column::VulnerabilityId",
+        to = "super::vulnerability::Column::Id"
+    )]
+    Vulnerability,
+}
+
+impl Related<super::vulnerability::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Vulnerability.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -5,6 +5,7 @@ use sea_orm::entity::prelude::*;
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: String,
+    pub name: Option<String>,
     pub description: Option<String>,
     pub extended_description: Option<String>,
     pub child_of: Option<Vec<String>>,
@@ -0,0 +1,179 @@
+use crate::UuidV4;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ConversationMessage::Table)
+                    .col(
+                        ColumnDef::new(ConversationMessage::ConversationId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationMessage::Idx)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationMessage::MessageType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationMessage::Content)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationMessage::Timestamp)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(ConversationMessage::ConversationId)
+                            .col(ConversationMessage::Idx),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(ConversationMessage::ConversationId)
+                            .to(Conversation::Table, Conversation::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ConversationToolInvocation::Table)
+                    .col(
+                        ColumnDef::new(ConversationToolInvocation::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationToolInvocation::ConversationId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationToolInvocation::Idx)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ConversationToolInvocation::CallId).string())
+                    .col(
+                        ColumnDef::new(ConversationToolInvocation::Name)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ConversationToolInvocation::Input)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ConversationToolInvocation::Output).text())
+                    .col(
+                        ColumnDef::new(ConversationToolInvocation::Timestamp)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(ConversationToolInvocation::ConversationId)
+                            .to(Conversation::Table, Conversation::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(ConversationToolInvocation::Table)
+                    .name(Indexes::ConversationToolInvocationConversationIdIdxIdx.to_string())
+                    .col(ConversationToolInvocation::ConversationId)
+                    .col(ConversationToolInvocation::Idx)
+                    .to_owned(),
+            )
+            .await?;
+
+        // existing conversations only have their state, extract their messages from it
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!(
+                "ai_m0000020_create_conversation_message/backfill_up.sql"
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(ConversationToolInvocation::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(ConversationMessage::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    ConversationToolInvocationConversationIdIdxIdx,
+}
+
+#[derive(DeriveIden)]
+enum ConversationMessage {
+    Table,
+    ConversationId,
+    Idx,
+    MessageType,
+    Content,
+    Timestamp,
+}
+
+#[derive(DeriveIden)]
+enum ConversationToolInvocation {
+    Table,
+    Id,
+    ConversationId,
+    Idx,
+    CallId,
+    Name,
+    Input,
+    Output,
+    Timestamp,
+}
+
+#[derive(DeriveIden)]
+enum Conversation {
+    Table,
+    Id,
+}
@@ -0,0 +1,35 @@
+-- the messages shown to the user: human messages, and AI messages not calling tools
+INSERT INTO conversation_message (conversation_id, idx, message_type, content, timestamp)
+SELECT
+    conversation.id,
+    message.ordinality - 1,
+    message.value ->> 'message_type',
+    coalesce(message.value ->> 'content', ''),
+    to_timestamp((conversation.state -> 'timestamps' ->> (message.ordinality - 1)::int)::bigint)
+FROM conversation,
+     jsonb_array_elements(conversation.state -> 'messages') WITH ORDINALITY AS message
+WHERE
+    message.value ->> 'message_type' IN ('human', 'ai')
+    AND jsonb_typeof(message.value -> 'tool_calls') IS DISTINCT FROM 'array';
+
+-- the tools called by AI messages, with the output of the matching tool message
+INSERT INTO conversation_tool_invocation (conversation_id, idx, call_id, name, input, output, timestamp)
+SELECT
+    conversation.id,
+    message.ordinality - 1,
+    call.value ->> 'id',
+    call.value -> 'function' ->> 'name',
+    coalesce(call.value -> 'function' ->> 'arguments', ''),
+    (
+        SELECT output.value ->> 'content'
+        FROM jsonb_array_elements(conversation.state -> 'messages') AS output
+        WHERE output.value ->> 'message_type' = 'tool' AND output.value ->> 'id' = call.value ->> 'id'
+        LIMIT 1
+    ),
+    to_timestamp((conversation.state -> 'timestamps' ->> (message.ordinality - 1)::int)::bigint)
+FROM conversation,
+     jsonb_array_elements(conversation.state -> 'messages') WITH ORDINALITY AS message,
+     jsonb_array_elements(message.value -> 'tool_calls') AS call
+WHERE
+    message.value ->> 'message_type' = 'ai'
+    AND jsonb_typeof(message.value -> 'tool_calls') = 'array';
@@ -4,6 +4,8 @@ pub use sea_orm_migration::prelude::*;
 pub mod ai;
 #[cfg(feature = "ai")]
 mod ai_m0000010_create_conversation;
+#[cfg(feature = "ai")]
+mod ai_m0000020_create_conversation_message;
 mod m0000010_init;
 mod m0000020_add_sbom_group;
 mod m0000030_perf_adv_vuln;
@@ -21,6 +23,50 @@ mod m0001000_sbom_non_null_suppliers;
 mod m0001010_alter_mavenver_cmp;
 mod m0001020_alter_pythonver_cmp;
 mod m0001030_perf_adv_gin_index;
+mod m0001040_sbom_external_node_target;
+mod m0001050_create_ingestion_job;
+mod m0001060_create_settings;
+mod m0001065_create_bulk_load;
+mod m0001070_add_external_id;
+mod m0001080_create_ingestion_warning;
+mod m0001085_create_document_audit;
+mod m0001090_create_assignment;
+mod m0001100_soft_delete;
+mod m0001110_create_internal_package;
+mod m0001120_create_download_audit;
+mod m0001130_create_summary_stats;
+mod m0001135_create_product_status_summary;
+mod m0001140_create_vulnerability_alias;
+mod m0001150_add_purl_ecosystem;
+mod m0001160_add_weakness_name;
+mod m0001170_create_policy;
+mod m0001180_create_saved_search;
+mod m0001190_create_subscription;
+mod m0001200_add_subscription_tenant;
+mod m0001210_canonicalize_purls;
+mod m0001220_add_product_version_lifecycle;
+mod m0001230_add_product_parent;
+mod m0001240_create_sbom_file_license;
+mod m0001250_add_sbom_node_checksum_value_index;
+mod m0001260_add_relationship_original;
+mod m0001270_create_vulnerability_annotation;
+mod m0001280_create_triage;
+mod m0001290_create_attachment;
+mod m0001300_create_comment;
+mod m0001310_create_document_change;
+mod m0001320_create_event_outbox;
+mod m0001330_create_document_identity;
+mod m0001340_create_advisory_supersedence;
+mod m0001350_create_severity_override;
+mod m0001360_create_sbom_predecessor;
+mod m0001370_create_rebuild;
+mod m0001380_add_ingestion_job_attempts;
+mod m0001390_add_tenant_columns;
+mod m0001400_add_summary_stats_tenant;
+mod m0001410_add_document_identity_tenant;
+mod m0001420_create_advisory_deletion;
+mod m0001430_add_ingestion_job_tenant;
+mod m0001440_add_advisory_deletion_heartbeat;
 
 pub struct Migrator;
 
@@ -45,6 +91,50 @@ impl MigratorTrait for Migrator {
             Box::new(m0001010_alter_mavenver_cmp::Migration),
             Box::new(m0001020_alter_pythonver_cmp::Migration),
             Box::new(m0001030_perf_adv_gin_index::Migration),
+            Box::new(m0001040_sbom_external_node_target::Migration),
+            Box::new(m0001050_create_ingestion_job::Migration),
+            Box::new(m0001060_create_settings::Migration),
+            Box::new(m0001065_create_bulk_load::Migration),
+            Box::new(m0001070_add_external_id::Migration),
+            Box::new(m0001080_create_ingestion_warning::Migration),
+            Box::new(m0001085_create_document_audit::Migration),
+            Box::new(m0001090_create_assignment::Migration),
+            Box::new(m0001100_soft_delete::Migration),
+            Box::new(m0001110_create_internal_package::Migration),
+            Box::new(m0001120_create_download_audit::Migration),
+            Box::new(m0001130_create_summary_stats::Migration),
+            Box::new(m0001135_create_product_status_summary::Migration),
+            Box::new(m0001140_create_vulnerability_alias::Migration),
+            Box::new(m0001150_add_purl_ecosystem::Migration),
+            Box::new(m0001160_add_weakness_name::Migration),
+            Box::new(m0001170_create_policy::Migration),
+            Box::new(m0001180_create_saved_search::Migration),
+            Box::new(m0001190_create_subscription::Migration),
+            Box::new(m0001200_add_subscription_tenant::Migration),
+            Box::new(m0001210_canonicalize_purls::Migration),
+            Box::new(m0001220_add_product_version_lifecycle::Migration),
+            Box::new(m0001230_add_product_parent::Migration),
+            Box::new(m0001240_create_sbom_file_license::Migration),
+            Box::new(m0001250_add_sbom_node_checksum_value_index::Migration),
+            Box::new(m0001260_add_relationship_original::Migration),
+            Box::new(m0001270_create_vulnerability_annotation::Migration),
+            Box::new(m0001280_create_triage::Migration),
+            Box::new(m0001290_create_attachment::Migration),
+            Box::new(m0001300_create_comment::Migration),
+            Box::new(m0001310_create_document_change::Migration),
+            Box::new(m0001320_create_event_outbox::Migration),
+            Box::new(m0001330_create_document_identity::Migration),
+            Box::new(m0001340_create_advisory_supersedence::Migration),
+            Box::new(m0001350_create_severity_override::Migration),
+            Box::new(m0001360_create_sbom_predecessor::Migration),
+            Box::new(m0001370_create_rebuild::Migration),
+            Box::new(m0001380_add_ingestion_job_attempts::Migration),
+            Box::new(m0001390_add_tenant_columns::Migration),
+            Box::new(m0001400_add_summary_stats_tenant::Migration),
+            Box::new(m0001410_add_document_identity_tenant::Migration),
+            Box::new(m0001420_create_advisory_deletion::Migration),
+            Box::new(m0001430_add_ingestion_job_tenant::Migration),
+            Box::new(m0001440_add_advisory_deletion_heartbeat::Migration),
         ]
     }
 }
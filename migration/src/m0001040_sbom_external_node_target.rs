@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // drop resolved targets when the referenced SBOM gets deleted
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name(Indexes::SbomExternalNodeTargetSbomIdFkey.to_string())
+                    .from(SbomExternalNode::Table, SbomExternalNode::TargetSbomId)
+                    .to(Sbom::Table, Sbom::SbomId)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(SbomExternalNode::Table)
+                    .name(Indexes::SbomExternalNodeTargetSbomIdIdx.to_string())
+                    .col(SbomExternalNode::TargetSbomId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(SbomExternalNode::Table)
+                    .name(Indexes::SbomExternalNodeTargetSbomIdIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .table(SbomExternalNode::Table)
+                    .name(Indexes::SbomExternalNodeTargetSbomIdFkey.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(DeriveIden)]
+enum Indexes {
+    SbomExternalNodeTargetSbomIdFkey,
+    SbomExternalNodeTargetSbomIdIdx,
+}
+
+#[derive(DeriveIden)]
+enum SbomExternalNode {
+    Table,
+    TargetSbomId,
+}
+
+#[derive(DeriveIden)]
+enum Sbom {
+    Table,
+    SbomId,
+}
@@ -0,0 +1,99 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(IngestionJob::Table)
+                    .col(
+                        ColumnDef::new(IngestionJob::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(IngestionJob::State).integer().not_null())
+                    .col(ColumnDef::new(IngestionJob::Format).string().not_null())
+                    .col(
+                        ColumnDef::new(IngestionJob::Labels)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IngestionJob::DocumentKey)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IngestionJob::Submitted)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(IngestionJob::Started).timestamp_with_time_zone())
+                    .col(ColumnDef::new(IngestionJob::Heartbeat).timestamp_with_time_zone())
+                    .col(ColumnDef::new(IngestionJob::Finished).timestamp_with_time_zone())
+                    .col(ColumnDef::new(IngestionJob::Result).json_binary())
+                    .col(ColumnDef::new(IngestionJob::Error).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        // workers look up the oldest job which still needs processing
+        manager
+            .create_index(
+                Index::create()
+                    .table(IngestionJob::Table)
+                    .name(Indexes::IngestionJobStateSubmittedIdx.to_string())
+                    .col(IngestionJob::State)
+                    .col(IngestionJob::Submitted)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(IngestionJob::Table)
+                    .name(Indexes::IngestionJobStateSubmittedIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(IngestionJob::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    IngestionJobStateSubmittedIdx,
+}
+
+#[derive(DeriveIden)]
+enum IngestionJob {
+    Table,
+    Id,
+    State,
+    Format,
+    Labels,
+    DocumentKey,
+    Submitted,
+    Started,
+    Heartbeat,
+    Finished,
+    Result,
+    Error,
+}
@@ -0,0 +1,120 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Settings::Table)
+                    .col(
+                        ColumnDef::new(Settings::Key)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Settings::Revision).uuid().not_null())
+                    .col(
+                        ColumnDef::new(Settings::Modified)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(Settings::Data).json_binary().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SettingsHistory::Table)
+                    .col(
+                        ColumnDef::new(SettingsHistory::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SettingsHistory::Key).string().not_null())
+                    .col(ColumnDef::new(SettingsHistory::Revision).uuid().not_null())
+                    .col(
+                        ColumnDef::new(SettingsHistory::Modified)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(SettingsHistory::UserId).string())
+                    .col(
+                        ColumnDef::new(SettingsHistory::Data)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // the history gets listed, newest first
+        manager
+            .create_index(
+                Index::create()
+                    .table(SettingsHistory::Table)
+                    .name(Indexes::SettingsHistoryKeyModifiedIdx.to_string())
+                    .col(SettingsHistory::Key)
+                    .col(SettingsHistory::Modified)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(SettingsHistory::Table)
+                    .name(Indexes::SettingsHistoryKeyModifiedIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(SettingsHistory::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Settings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    SettingsHistoryKeyModifiedIdx,
+}
+
+#[derive(DeriveIden)]
+enum Settings {
+    Table,
+    Key,
+    Revision,
+    Modified,
+    Data,
+}
+
+#[derive(DeriveIden)]
+enum SettingsHistory {
+    Table,
+    Id,
+    Key,
+    Revision,
+    Modified,
+    UserId,
+    Data,
+}
@@ -0,0 +1,37 @@
+-- Staging tables for bulk loading large SBOMs using "COPY FROM STDIN".
+--
+-- Rows are copied in outside the ingesting transaction, tagged with the ID of the load, and moved
+-- to the actual tables by that transaction. Being unlogged, they don't add to the WAL, and their
+-- content doesn't survive a crash, which is fine for data in transit.
+
+CREATE UNLOGGED TABLE IF NOT EXISTS sbom_node_load
+(
+    load_id UUID NOT NULL,
+    sbom_id UUID NOT NULL,
+    node_id TEXT NOT NULL,
+    name    TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS sbom_node_load_idx ON sbom_node_load (load_id);
+
+CREATE UNLOGGED TABLE IF NOT EXISTS sbom_package_load
+(
+    load_id UUID NOT NULL,
+    sbom_id UUID NOT NULL,
+    node_id TEXT NOT NULL,
+    "group" TEXT,
+    version TEXT
+);
+
+CREATE INDEX IF NOT EXISTS sbom_package_load_idx ON sbom_package_load (load_id);
+
+CREATE UNLOGGED TABLE IF NOT EXISTS package_relates_to_package_load
+(
+    load_id       UUID    NOT NULL,
+    sbom_id       UUID    NOT NULL,
+    left_node_id  TEXT    NOT NULL,
+    relationship  INTEGER NOT NULL,
+    right_node_id TEXT    NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS package_relates_to_package_load_idx ON package_relates_to_package_load (load_id);
@@ -0,0 +1,134 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sbom::Table)
+                    .add_column(ColumnDef::new(Sbom::ExternalId).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Sbom::Table)
+                    .name(Indexes::SbomExternalIdIdx.to_string())
+                    .col(Sbom::ExternalId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Advisory::Table)
+                    .add_column(ColumnDef::new(Advisory::ExternalId).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Advisory::Table)
+                    .name(Indexes::AdvisoryExternalIdIdx.to_string())
+                    .col(Advisory::ExternalId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // keep the external ID of documents submitted for asynchronous ingestion
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(IngestionJob::Table)
+                    .add_column(ColumnDef::new(IngestionJob::ExternalId).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(IngestionJob::Table)
+                    .drop_column(IngestionJob::ExternalId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(Advisory::Table)
+                    .name(Indexes::AdvisoryExternalIdIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Advisory::Table)
+                    .drop_column(Advisory::ExternalId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(Sbom::Table)
+                    .name(Indexes::SbomExternalIdIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sbom::Table)
+                    .drop_column(Sbom::ExternalId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    SbomExternalIdIdx,
+    AdvisoryExternalIdIdx,
+}
+
+#[derive(DeriveIden)]
+enum Sbom {
+    Table,
+    ExternalId,
+}
+
+#[derive(DeriveIden)]
+enum Advisory {
+    Table,
+    ExternalId,
+}
+
+#[derive(DeriveIden)]
+enum IngestionJob {
+    Table,
+    ExternalId,
+}
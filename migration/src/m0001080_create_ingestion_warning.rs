@@ -0,0 +1,106 @@
+use crate::UuidV4;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(IngestionWarning::Table)
+                    .col(
+                        ColumnDef::new(IngestionWarning::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(IngestionWarning::SbomId).uuid())
+                    .col(ColumnDef::new(IngestionWarning::AdvisoryId).uuid())
+                    .col(
+                        ColumnDef::new(IngestionWarning::Message)
+                            .string()
+                            .not_null(),
+                    )
+                    // warnings go away together with their document
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(IngestionWarning::SbomId)
+                            .to(Sbom::Table, Sbom::SbomId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(IngestionWarning::AdvisoryId)
+                            .to(Advisory::Table, Advisory::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(IngestionWarning::Table)
+                    .name(Indexes::IngestionWarningSbomIdIdx.to_string())
+                    .col(IngestionWarning::SbomId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(IngestionWarning::Table)
+                    .name(Indexes::IngestionWarningAdvisoryIdIdx.to_string())
+                    .col(IngestionWarning::AdvisoryId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(IngestionWarning::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(DeriveIden)]
+enum Indexes {
+    IngestionWarningSbomIdIdx,
+    IngestionWarningAdvisoryIdIdx,
+}
+
+#[derive(DeriveIden)]
+enum IngestionWarning {
+    Table,
+    Id,
+    SbomId,
+    AdvisoryId,
+    Message,
+}
+
+#[derive(DeriveIden)]
+enum Sbom {
+    Table,
+    SbomId,
+}
+
+#[derive(DeriveIden)]
+enum Advisory {
+    Table,
+    Id,
+}
@@ -0,0 +1,167 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(DocumentKind::Enum)
+                    .values([DocumentKind::Sbom, DocumentKind::Advisory])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(AuditAction::Enum)
+                    .values([
+                        AuditAction::Uploaded,
+                        AuditAction::LabelsChanged,
+                        AuditAction::Deleted,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // no foreign keys, the record must outlive the document
+        manager
+            .create_table(
+                Table::create()
+                    .table(DocumentAudit::Table)
+                    .col(
+                        ColumnDef::new(DocumentAudit::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentAudit::Timestamp)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(DocumentAudit::UserId).string())
+                    .col(ColumnDef::new(DocumentAudit::ClientAddress).string())
+                    .col(ColumnDef::new(DocumentAudit::UserAgent).string())
+                    .col(
+                        ColumnDef::new(DocumentAudit::Action)
+                            .custom(AuditAction::Enum)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentAudit::DocumentKind)
+                            .custom(DocumentKind::Enum)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DocumentAudit::DocumentId).uuid().not_null())
+                    .col(ColumnDef::new(DocumentAudit::Details).json_binary())
+                    .to_owned(),
+            )
+            .await?;
+
+        // the trail of a document
+        manager
+            .create_index(
+                Index::create()
+                    .table(DocumentAudit::Table)
+                    .name(Indexes::DocumentAuditDocumentIdx.to_string())
+                    .col(DocumentAudit::DocumentKind)
+                    .col(DocumentAudit::DocumentId)
+                    .col(DocumentAudit::Timestamp)
+                    .to_owned(),
+            )
+            .await?;
+
+        // keep the uploader of documents submitted for asynchronous ingestion
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(IngestionJob::Table)
+                    .add_column(ColumnDef::new(IngestionJob::Upload).json_binary())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(IngestionJob::Table)
+                    .drop_column(IngestionJob::Upload)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(DocumentAudit::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(Type::drop().if_exists().name(AuditAction::Enum).to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().if_exists().name(DocumentKind::Enum).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(DeriveIden)]
+enum Indexes {
+    DocumentAuditDocumentIdx,
+}
+
+#[derive(DeriveIden)]
+enum DocumentKind {
+    #[sea_orm(iden = "document_kind")]
+    Enum,
+    Sbom,
+    Advisory,
+}
+
+#[derive(DeriveIden)]
+enum AuditAction {
+    #[sea_orm(iden = "audit_action")]
+    Enum,
+    Uploaded,
+    LabelsChanged,
+    Deleted,
+}
+
+#[derive(DeriveIden)]
+enum DocumentAudit {
+    Table,
+    Id,
+    Timestamp,
+    UserId,
+    ClientAddress,
+    UserAgent,
+    Action,
+    DocumentKind,
+    DocumentId,
+    Details,
+}
+
+#[derive(DeriveIden)]
+enum IngestionJob {
+    Table,
+    Upload,
+}
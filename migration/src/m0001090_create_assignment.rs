@@ -0,0 +1,239 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(AssignmentState::Enum)
+                    .values([
+                        AssignmentState::New,
+                        AssignmentState::InProgress,
+                        AssignmentState::Resolved,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Assignment::Table)
+                    .col(
+                        ColumnDef::new(Assignment::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Assignment::VulnerabilityId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Assignment::SbomId).uuid())
+                    .col(ColumnDef::new(Assignment::Assignee).string().not_null())
+                    .col(
+                        ColumnDef::new(Assignment::State)
+                            .custom(AssignmentState::Enum)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Assignment::Due).timestamp_with_time_zone())
+                    .col(
+                        ColumnDef::new(Assignment::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(
+                        ColumnDef::new(Assignment::Modified)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(Assignment::VulnerabilityId)
+                            .to(Vulnerability::Table, Vulnerability::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(Assignment::SbomId)
+                            .to(Sbom::Table, Sbom::SbomId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // the work queue of an assignee: their open assignments
+        manager
+            .create_index(
+                Index::create()
+                    .table(Assignment::Table)
+                    .name(Indexes::AssignmentAssigneeStateIdx.to_string())
+                    .col(Assignment::Assignee)
+                    .col(Assignment::State)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Assignment::Table)
+                    .name(Indexes::AssignmentVulnerabilityIdIdx.to_string())
+                    .col(Assignment::VulnerabilityId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(AssignmentEvent::Table)
+                    .col(
+                        ColumnDef::new(AssignmentEvent::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AssignmentEvent::AssignmentId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AssignmentEvent::Timestamp)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(AssignmentEvent::UserId).string())
+                    .col(
+                        ColumnDef::new(AssignmentEvent::Assignee)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AssignmentEvent::State)
+                            .custom(AssignmentState::Enum)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AssignmentEvent::Due).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(AssignmentEvent::AssignmentId)
+                            .to(Assignment::Table, Assignment::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(AssignmentEvent::Table)
+                    .name(Indexes::AssignmentEventAssignmentIdTimestampIdx.to_string())
+                    .col(AssignmentEvent::AssignmentId)
+                    .col(AssignmentEvent::Timestamp)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(AssignmentEvent::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(Assignment::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .if_exists()
+                    .name(AssignmentState::Enum)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(DeriveIden)]
+enum Indexes {
+    AssignmentAssigneeStateIdx,
+    AssignmentVulnerabilityIdIdx,
+    AssignmentEventAssignmentIdTimestampIdx,
+}
+
+#[derive(DeriveIden)]
+enum AssignmentState {
+    #[sea_orm(iden = "assignment_state")]
+    Enum,
+    New,
+    InProgress,
+    Resolved,
+}
+
+#[derive(DeriveIden)]
+enum Assignment {
+    Table,
+    Id,
+    VulnerabilityId,
+    SbomId,
+    Assignee,
+    State,
+    Due,
+    Created,
+    Modified,
+}
+
+#[derive(DeriveIden)]
+enum AssignmentEvent {
+    Table,
+    Id,
+    AssignmentId,
+    Timestamp,
+    UserId,
+    Assignee,
+    State,
+    Due,
+}
+
+#[derive(DeriveIden)]
+enum Vulnerability {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Sbom {
+    Table,
+    SbomId,
+}
@@ -0,0 +1,16 @@
+CREATE OR REPLACE FUNCTION public.update_deprecated_advisory(identifier_input text DEFAULT NULL::text) RETURNS void
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    WITH MostRecent AS (SELECT DISTINCT ON (identifier) id
+                        FROM advisory
+                        WHERE identifier = COALESCE(identifier_input, identifier)
+                        ORDER BY identifier, modified DESC)
+    UPDATE advisory
+    SET deprecated = CASE
+                         WHEN id IN (SELECT id FROM MostRecent) THEN FALSE
+                         ELSE TRUE
+        END
+    WHERE identifier = COALESCE(identifier_input, identifier);
+END;
+$$;
@@ -0,0 +1,126 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(InternalPackage::Table)
+                    .col(
+                        ColumnDef::new(InternalPackage::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(InternalPackage::Importer)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(InternalPackage::Repository)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(InternalPackage::QualifiedPurlId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(InternalPackage::LastSeen)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(InternalPackage::Importer)
+                            .to(Importer::Table, Importer::Name)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(InternalPackage::QualifiedPurlId)
+                            .to(QualifiedPurl::Table, QualifiedPurl::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // one entry per package and repository of a registry, updated on every sync
+        manager
+            .create_index(
+                Index::create()
+                    .table(InternalPackage::Table)
+                    .name(Indexes::InternalPackageImporterRepositoryPurlIdx.to_string())
+                    .col(InternalPackage::Importer)
+                    .col(InternalPackage::Repository)
+                    .col(InternalPackage::QualifiedPurlId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(InternalPackage::Table)
+                    .name(Indexes::InternalPackageQualifiedPurlIdIdx.to_string())
+                    .col(InternalPackage::QualifiedPurlId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(InternalPackage::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(DeriveIden)]
+enum Indexes {
+    InternalPackageImporterRepositoryPurlIdx,
+    InternalPackageQualifiedPurlIdIdx,
+}
+
+#[derive(DeriveIden)]
+enum InternalPackage {
+    Table,
+    Id,
+    Importer,
+    Repository,
+    QualifiedPurlId,
+    LastSeen,
+}
+
+#[derive(DeriveIden)]
+enum Importer {
+    Table,
+    Name,
+}
+
+#[derive(DeriveIden)]
+enum QualifiedPurl {
+    Table,
+    Id,
+}
@@ -0,0 +1,105 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // no foreign keys, the record must outlive the downloaded document
+        manager
+            .create_table(
+                Table::create()
+                    .table(DownloadAudit::Table)
+                    .col(
+                        ColumnDef::new(DownloadAudit::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DownloadAudit::Timestamp)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(DownloadAudit::UserId).string())
+                    .col(
+                        ColumnDef::new(DownloadAudit::DocumentKind)
+                            .custom(DocumentKind::Enum)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DownloadAudit::DocumentId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(DownloadAudit::Bytes)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // the egress of a principal, per month
+        manager
+            .create_index(
+                Index::create()
+                    .table(DownloadAudit::Table)
+                    .name(Indexes::DownloadAuditTimestampUserIdIdx.to_string())
+                    .col(DownloadAudit::Timestamp)
+                    .col(DownloadAudit::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(DownloadAudit::Table)
+                    .name(Indexes::DownloadAuditDocumentIdIdx.to_string())
+                    .col(DownloadAudit::DocumentId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(DownloadAudit::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(DeriveIden)]
+enum Indexes {
+    DownloadAuditTimestampUserIdIdx,
+    DownloadAuditDocumentIdIdx,
+}
+
+#[derive(DeriveIden)]
+enum DocumentKind {
+    #[sea_orm(iden = "document_kind")]
+    Enum,
+}
+
+#[derive(DeriveIden)]
+enum DownloadAudit {
+    Table,
+    Id,
+    Timestamp,
+    UserId,
+    DocumentKind,
+    DocumentId,
+    Bytes,
+}
@@ -0,0 +1,4 @@
+DROP FUNCTION IF EXISTS recompute_product_status_summary(text, uuid);
+DROP FUNCTION IF EXISTS status_precedence();
+DROP TABLE IF EXISTS status_recomputation;
+DROP TABLE IF EXISTS product_status_summary;
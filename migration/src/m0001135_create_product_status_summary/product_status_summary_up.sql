@@ -0,0 +1,86 @@
+-- the effective status of each product, per vulnerability, cached as evaluating it requires
+-- considering the statements of all advisories
+CREATE TABLE product_status_summary (
+    vulnerability_id TEXT NOT NULL REFERENCES vulnerability (id) ON DELETE CASCADE,
+    product_id UUID NOT NULL REFERENCES product (id) ON DELETE CASCADE,
+    status TEXT NOT NULL,
+    advisories INTEGER NOT NULL,
+    computed TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    PRIMARY KEY (vulnerability_id, product_id)
+);
+
+CREATE INDEX product_status_summary_product_id_idx ON product_status_summary (product_id);
+
+-- runs of recomputing the cached statuses, reporting their progress
+CREATE TABLE status_recomputation (
+    id UUID PRIMARY KEY,
+    state INTEGER NOT NULL,
+    vulnerability_id TEXT,
+    product_id UUID,
+    total INTEGER NOT NULL,
+    processed INTEGER NOT NULL,
+    started TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    finished TIMESTAMP WITH TIME ZONE,
+    error TEXT
+);
+
+CREATE INDEX status_recomputation_started_idx ON status_recomputation (started);
+
+-- the precedence of statuses, highest first, as configured by the runtime settings
+CREATE OR REPLACE FUNCTION status_precedence() RETURNS text[]
+    LANGUAGE sql STABLE
+    AS $$
+    SELECT COALESCE(
+        (
+            SELECT ARRAY(SELECT jsonb_array_elements_text(data -> 'statusPrecedence'))
+            FROM settings
+            WHERE key = 'runtime' AND jsonb_typeof(data -> 'statusPrecedence') = 'array'
+        ),
+        ARRAY['affected', 'under_investigation', 'fixed', 'recommended', 'not_affected']
+    )
+$$;
+
+-- recompute the cached statuses, limited to a vulnerability and a product if provided, returning
+-- the number of cached statuses
+--
+-- deprecated and deleted advisories are not considered. Statuses missing from the precedence
+-- rank lowest.
+CREATE OR REPLACE FUNCTION recompute_product_status_summary(
+    vulnerability_input text DEFAULT NULL,
+    product_input uuid DEFAULT NULL
+) RETURNS bigint
+    LANGUAGE plpgsql
+    AS $$
+DECLARE
+    precedence text[] := status_precedence();
+    result bigint;
+BEGIN
+    DELETE FROM product_status_summary
+    WHERE vulnerability_id = COALESCE(vulnerability_input, vulnerability_id)
+      AND product_id = COALESCE(product_input, product_id);
+
+    INSERT INTO product_status_summary (vulnerability_id, product_id, status, advisories)
+    SELECT
+        product_status.vulnerability_id,
+        product_version_range.product_id,
+        (array_agg(
+            status.slug
+            ORDER BY COALESCE(array_position(precedence, status.slug), cardinality(precedence) + 1)
+        ))[1],
+        count(DISTINCT product_status.advisory_id)
+    FROM product_status
+        JOIN advisory ON advisory.id = product_status.advisory_id
+        JOIN status ON status.id = product_status.status_id
+        JOIN product_version_range ON product_version_range.id = product_status.product_version_range_id
+    WHERE NOT advisory.deprecated
+      AND advisory.deleted_at IS NULL
+      AND product_status.vulnerability_id = COALESCE(vulnerability_input, product_status.vulnerability_id)
+      AND product_version_range.product_id = COALESCE(product_input, product_version_range.product_id)
+    GROUP BY 1, 2;
+
+    GET DIAGNOSTICS result = ROW_COUNT;
+    RETURN result;
+END;
+$$;
+
+SELECT recompute_product_status_summary();
@@ -0,0 +1,111 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(VulnerabilityAlias::Table)
+                    .col(
+                        ColumnDef::new(VulnerabilityAlias::AdvisoryId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VulnerabilityAlias::VulnerabilityId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VulnerabilityAlias::Alias)
+                            .string()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(VulnerabilityAlias::AdvisoryId)
+                            .col(VulnerabilityAlias::VulnerabilityId)
+                            .col(VulnerabilityAlias::Alias),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(VulnerabilityAlias::AdvisoryId)
+                            .to(Advisory::Table, Advisory::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(VulnerabilityAlias::VulnerabilityId)
+                            .to(Vulnerability::Table, Vulnerability::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(VulnerabilityAlias::Table)
+                    .name(Indexes::VulnerabilityAliasVulnerabilityIdIdx.to_string())
+                    .col(VulnerabilityAlias::VulnerabilityId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // aliases are being looked up case-insensitively
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                r#"CREATE INDEX "{}" ON "vulnerability_alias" (lower("alias"))"#,
+                Indexes::VulnerabilityAliasLowerAliasIdx.to_string()
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(VulnerabilityAlias::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(DeriveIden)]
+enum Indexes {
+    VulnerabilityAliasVulnerabilityIdIdx,
+    VulnerabilityAliasLowerAliasIdx,
+}
+
+#[derive(DeriveIden)]
+enum VulnerabilityAlias {
+    Table,
+    AdvisoryId,
+    VulnerabilityId,
+    Alias,
+}
+
+#[derive(DeriveIden)]
+enum Advisory {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Vulnerability {
+    Table,
+    Id,
+}
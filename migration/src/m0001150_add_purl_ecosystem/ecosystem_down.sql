@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS base_purl_ecosystem_idx;
+
+ALTER TABLE base_purl DROP COLUMN IF EXISTS ecosystem;
+
+DROP FUNCTION IF EXISTS purl_ecosystem(text);
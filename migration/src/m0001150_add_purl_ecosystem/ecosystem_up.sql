@@ -0,0 +1,12 @@
+-- the ecosystem of a package, derived from the type of its purl
+CREATE OR REPLACE FUNCTION purl_ecosystem(purl_type text) RETURNS text AS $$
+    SELECT CASE lower(purl_type)
+        -- container images are referenced by either type
+        WHEN 'docker' THEN 'oci'
+        ELSE lower(purl_type)
+    END
+$$ LANGUAGE SQL IMMUTABLE PARALLEL SAFE;
+
+ALTER TABLE base_purl ADD COLUMN ecosystem text NOT NULL GENERATED ALWAYS AS (purl_ecosystem("type")) STORED;
+
+CREATE INDEX base_purl_ecosystem_idx ON base_purl (ecosystem);
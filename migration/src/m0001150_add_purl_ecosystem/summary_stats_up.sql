@@ -0,0 +1,81 @@
+DROP MATERIALIZED VIEW IF EXISTS summary_stats;
+
+-- counts for dashboards, including the counts per ecosystem
+CREATE MATERIALIZED VIEW summary_stats AS
+WITH live_advisory AS (
+    SELECT * FROM advisory WHERE deleted_at IS NULL
+),
+live_sbom AS (
+    SELECT * FROM sbom WHERE deleted_at IS NULL
+),
+-- the highest CVSS v3 severity of each advisory
+advisory_severity AS (
+    SELECT DISTINCT ON (advisory_id) advisory_id, severity
+    FROM cvss3
+    ORDER BY advisory_id, score DESC
+),
+period (key, since) AS (
+    VALUES ('day', interval '1 day'), ('week', interval '7 days')
+)
+SELECT category, key, count, now() AS refreshed
+FROM (
+    SELECT 'advisory_type' AS category, COALESCE(labels ->> 'type', 'unknown') AS key, count(*) AS count
+    FROM live_advisory
+    GROUP BY 2
+
+    UNION ALL
+
+    SELECT 'advisory_severity', COALESCE(advisory_severity.severity::text, 'unknown'), count(*)
+    FROM live_advisory
+    LEFT JOIN advisory_severity ON advisory_severity.advisory_id = live_advisory.id
+    GROUP BY 2
+
+    UNION ALL
+
+    SELECT 'sbom', '', count(*) FROM live_sbom
+
+    UNION ALL
+
+    SELECT 'vulnerability', '', count(*) FROM vulnerability
+
+    UNION ALL
+
+    SELECT 'base_purl', '', count(*) FROM base_purl
+
+    UNION ALL
+
+    SELECT 'qualified_purl', '', count(*) FROM qualified_purl
+
+    UNION ALL
+
+    SELECT 'ecosystem_base_purl', ecosystem, count(*)
+    FROM base_purl
+    GROUP BY 2
+
+    UNION ALL
+
+    SELECT 'ecosystem_vulnerability', base_purl.ecosystem, count(DISTINCT purl_status.vulnerability_id)
+    FROM purl_status
+    JOIN live_advisory ON live_advisory.id = purl_status.advisory_id
+    JOIN base_purl ON base_purl.id = purl_status.base_purl_id
+    GROUP BY 2
+
+    UNION ALL
+
+    SELECT 'ingested_advisory', period.key, count(source_document.id)
+    FROM period
+    LEFT JOIN (live_advisory JOIN source_document ON source_document.id = live_advisory.source_document_id)
+        ON source_document.ingested > now() - period.since
+    GROUP BY 2
+
+    UNION ALL
+
+    SELECT 'ingested_sbom', period.key, count(source_document.id)
+    FROM period
+    LEFT JOIN (live_sbom JOIN source_document ON source_document.id = live_sbom.source_document_id)
+        ON source_document.ingested > now() - period.since
+    GROUP BY 2
+) AS stats;
+
+-- required for refreshing the view concurrently
+CREATE UNIQUE INDEX summary_stats_category_key_idx ON summary_stats (category, key);
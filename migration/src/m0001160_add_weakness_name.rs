@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Weakness::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Weakness::Name).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!(
+                "m0001160_add_weakness_name/normalize_cwes.sql"
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // normalizing the CWE identifiers can't be reverted, but is compatible with the previous state
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Weakness::Table)
+                    .drop_column(Weakness::Name)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Weakness {
+    Table,
+    Name,
+}
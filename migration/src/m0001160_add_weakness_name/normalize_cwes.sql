@@ -0,0 +1,21 @@
+-- the same normalization as applied when ingesting: `CWE-{number}`, dropping anything else, like `NVD-CWE-noinfo`
+CREATE OR REPLACE FUNCTION normalize_cwes(cwes text[]) RETURNS text[] AS $$
+    SELECT array_agg(id ORDER BY position)
+    FROM (
+        SELECT 'CWE-' || (match[1]::bigint)::text AS id, min(position) AS position
+        FROM unnest(cwes) WITH ORDINALITY AS cwe(value, position),
+            regexp_match(trim(value), '^(?:[Cc][Ww][Ee]-)?([0-9]+)$') AS match
+        WHERE match IS NOT NULL
+        GROUP BY 1
+    ) AS normalized
+$$ LANGUAGE SQL IMMUTABLE;
+
+UPDATE vulnerability
+SET cwes = normalize_cwes(cwes)
+WHERE cwes IS DISTINCT FROM normalize_cwes(cwes);
+
+UPDATE advisory_vulnerability
+SET cwes = normalize_cwes(cwes)
+WHERE cwes IS DISTINCT FROM normalize_cwes(cwes);
+
+DROP FUNCTION normalize_cwes(text[]);
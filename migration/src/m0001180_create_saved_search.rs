@@ -0,0 +1,135 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(SavedSearchKind::Enum)
+                    .values([SavedSearchKind::Sbom, SavedSearchKind::Advisory])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SavedSearch::Table)
+                    .col(
+                        ColumnDef::new(SavedSearch::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SavedSearch::UserId).string().not_null())
+                    .col(ColumnDef::new(SavedSearch::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(SavedSearch::Kind)
+                            .custom(SavedSearchKind::Enum)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SavedSearch::Q)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(SavedSearch::Sort)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(SavedSearch::Labels)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(SavedSearch::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(
+                        ColumnDef::new(SavedSearch::Modified)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // the names of the searches of a user are unique
+        manager
+            .create_index(
+                Index::create()
+                    .table(SavedSearch::Table)
+                    .name(Indexes::SavedSearchUserIdNameIdx.to_string())
+                    .col(SavedSearch::UserId)
+                    .col(SavedSearch::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(SavedSearch::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .if_exists()
+                    .name(SavedSearchKind::Enum)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    SavedSearchUserIdNameIdx,
+}
+
+#[derive(DeriveIden)]
+enum SavedSearchKind {
+    #[sea_orm(iden = "saved_search_kind")]
+    Enum,
+    Sbom,
+    Advisory,
+}
+
+#[derive(DeriveIden)]
+enum SavedSearch {
+    Table,
+    Id,
+    UserId,
+    Name,
+    Kind,
+    Q,
+    Sort,
+    Labels,
+    Created,
+    Modified,
+}
@@ -0,0 +1,176 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Subscription::Table)
+                    .col(
+                        ColumnDef::new(Subscription::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Subscription::UserId).string().not_null())
+                    .col(
+                        ColumnDef::new(Subscription::SavedSearchId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Subscription::Webhook).string())
+                    .col(
+                        ColumnDef::new(Subscription::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    // subscriptions go away together with their search
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(Subscription::SavedSearchId)
+                            .to(SavedSearch::Table, SavedSearch::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // a search can only be subscribed to once
+        manager
+            .create_index(
+                Index::create()
+                    .table(Subscription::Table)
+                    .name(Indexes::SubscriptionSavedSearchIdIdx.to_string())
+                    .col(Subscription::SavedSearchId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Notification::Table)
+                    .col(
+                        ColumnDef::new(Notification::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Notification::SubscriptionId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Notification::UserId).string().not_null())
+                    .col(
+                        ColumnDef::new(Notification::Kind)
+                            .custom(SavedSearchKind::Enum)
+                            .not_null(),
+                    )
+                    // not a foreign key, the notification outlives the document
+                    .col(ColumnDef::new(Notification::DocumentId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(Notification::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(Notification::Delivered).timestamp_with_time_zone())
+                    .col(ColumnDef::new(Notification::Error).string())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(Notification::SubscriptionId)
+                            .to(Subscription::Table, Subscription::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Notification::Table)
+                    .name(Indexes::NotificationUserIdCreatedIdx.to_string())
+                    .col(Notification::UserId)
+                    .col(Notification::Created)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(Notification::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(Subscription::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(DeriveIden)]
+enum Indexes {
+    SubscriptionSavedSearchIdIdx,
+    NotificationUserIdCreatedIdx,
+}
+
+#[derive(DeriveIden)]
+enum SavedSearchKind {
+    #[sea_orm(iden = "saved_search_kind")]
+    Enum,
+}
+
+#[derive(DeriveIden)]
+enum SavedSearch {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Subscription {
+    Table,
+    Id,
+    UserId,
+    SavedSearchId,
+    Webhook,
+    Created,
+}
+
+#[derive(DeriveIden)]
+enum Notification {
+    Table,
+    Id,
+    SubscriptionId,
+    UserId,
+    Kind,
+    DocumentId,
+    Created,
+    Delivered,
+    Error,
+}
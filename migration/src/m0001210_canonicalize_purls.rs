@@ -0,0 +1,336 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ConnectionTrait, DbBackend, Statement, Value},
+};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Merge PURLs which only differ in their non-canonical parts.
+///
+/// PURLs are stored in their canonical form now, as defined by the purl-spec. Existing entries
+/// in a non-canonical form get replaced by their canonical counterpart, moving all references
+/// over to it.
+///
+/// **NOTE:** The rules and IDs must match those of `Purl::canonical` and `Purl::uuids`. They are
+/// copied here, as the migration must not change when those evolve.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Namespace of the PURL IDs.
+const NAMESPACE: Uuid = Uuid::from_bytes([
+    0x37, 0x38, 0xb4, 0x3d, 0xfd, 0x03, 0x4a, 0x9d, 0x84, 0x9c, 0x48, 0x9b, 0xec, 0x61, 0x0f, 0x06,
+]);
+
+/// Types, for which the purl-spec requires namespace and name to be lowercased.
+const CASE_INSENSITIVE_TYPES: &[&str] = &["bitbucket", "composer", "github", "hex", "npm", "pypi"];
+
+/// Condition, selecting base PURLs (as `b`) which are not in their canonical form.
+const NON_CANONICAL_BASE: &str = r#"
+    b."type" <> lower(b."type")
+    OR (
+        lower(b."type") IN ('bitbucket', 'composer', 'github', 'hex', 'npm', 'pypi')
+        AND (b.namespace <> lower(b.namespace) OR b.name <> lower(b.name))
+    )
+    OR (lower(b."type") = 'pypi' AND strpos(b.name, '_') > 0)
+"#;
+
+/// Condition, selecting qualified PURLs (as `qp`) with non-canonical qualifiers.
+const NON_CANONICAL_QUALIFIERS: &str = r#"
+    EXISTS (
+        SELECT 1 FROM jsonb_each_text(qp.qualifiers) q
+        WHERE q.value = '' OR q.key <> lower(q.key)
+    )
+"#;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // qualified PURLs, moving their SBOM and registry references
+
+        let rows = db
+            .query_all(Statement::from_string(
+                DbBackend::Postgres,
+                format!(
+                    r#"
+SELECT qp.id, b."type", b.namespace, b.name, v.version, qp.qualifiers::text AS qualifiers
+FROM qualified_purl qp
+    JOIN versioned_purl v ON v.id = qp.versioned_purl_id
+    JOIN base_purl b ON b.id = v.base_purl_id
+WHERE ({NON_CANONICAL_BASE}) OR ({NON_CANONICAL_QUALIFIERS})
+"#
+                ),
+            ))
+            .await?;
+
+        for row in rows {
+            let old: Uuid = row.try_get("", "id")?;
+            let qualifiers: String = row.try_get("", "qualifiers")?;
+            let qualifiers: BTreeMap<String, String> = serde_json::from_str(&qualifiers)
+                .map_err(|err| DbErr::Custom(format!("invalid qualifiers: {err}")))?;
+
+            let purl = Canonical::new(
+                row.try_get("", "type")?,
+                row.try_get("", "namespace")?,
+                row.try_get("", "name")?,
+                Some(row.try_get("", "version")?),
+                qualifiers,
+            );
+            let (_, _, new) = purl.create(db).await?;
+            if new == old {
+                continue;
+            }
+
+            execute(
+                db,
+                r#"
+INSERT INTO sbom_package_purl_ref (sbom_id, node_id, qualified_purl_id)
+SELECT sbom_id, node_id, $2 FROM sbom_package_purl_ref WHERE qualified_purl_id = $1
+ON CONFLICT DO NOTHING
+"#,
+                [old.into(), new.into()],
+            )
+            .await?;
+            execute(
+                db,
+                "DELETE FROM sbom_package_purl_ref WHERE qualified_purl_id = $1",
+                [old.into()],
+            )
+            .await?;
+            execute(
+                db,
+                r#"
+UPDATE internal_package i SET qualified_purl_id = $2
+WHERE i.qualified_purl_id = $1 AND NOT EXISTS (
+    SELECT 1 FROM internal_package o
+    WHERE o.importer = i.importer AND o.repository = i.repository AND o.qualified_purl_id = $2
+)
+"#,
+                [old.into(), new.into()],
+            )
+            .await?;
+            // remaining registry entries are duplicates, and get removed by the cascade
+            execute(db, "DELETE FROM qualified_purl WHERE id = $1", [old.into()]).await?;
+        }
+
+        // versioned PURLs of non-canonical base PURLs, moving their license assertions
+
+        let rows = db
+            .query_all(Statement::from_string(
+                DbBackend::Postgres,
+                format!(
+                    r#"
+SELECT v.id, b."type", b.namespace, b.name, v.version
+FROM versioned_purl v
+    JOIN base_purl b ON b.id = v.base_purl_id
+WHERE {NON_CANONICAL_BASE}
+"#
+                ),
+            ))
+            .await?;
+
+        for row in rows {
+            let old: Uuid = row.try_get("", "id")?;
+            let purl = Canonical::new(
+                row.try_get("", "type")?,
+                row.try_get("", "namespace")?,
+                row.try_get("", "name")?,
+                Some(row.try_get("", "version")?),
+                Default::default(),
+            );
+            let (_, new, _) = purl.create(db).await?;
+
+            execute(
+                db,
+                "UPDATE purl_license_assertion SET versioned_purl_id = $2 WHERE versioned_purl_id = $1",
+                [old.into(), new.into()],
+            )
+            .await?;
+            execute(db, "DELETE FROM versioned_purl WHERE id = $1", [old.into()]).await?;
+        }
+
+        // base PURLs, moving their advisory statuses
+
+        let rows = db
+            .query_all(Statement::from_string(
+                DbBackend::Postgres,
+                format!(
+                    r#"
+SELECT b.id, b."type", b.namespace, b.name
+FROM base_purl b
+WHERE {NON_CANONICAL_BASE}
+"#
+                ),
+            ))
+            .await?;
+
+        for row in rows {
+            let old: Uuid = row.try_get("", "id")?;
+            let purl = Canonical::new(
+                row.try_get("", "type")?,
+                row.try_get("", "namespace")?,
+                row.try_get("", "name")?,
+                None,
+                Default::default(),
+            );
+            let (new, _, _) = purl.create(db).await?;
+
+            execute(
+                db,
+                "UPDATE purl_status SET base_purl_id = $2 WHERE base_purl_id = $1",
+                [old.into(), new.into()],
+            )
+            .await?;
+            execute(db, "DELETE FROM base_purl WHERE id = $1", [old.into()]).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // merged PURLs can't be split up again
+        Ok(())
+    }
+}
+
+async fn execute<C, I>(db: &C, sql: &str, values: I) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+    I: IntoIterator<Item = Value>,
+{
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        sql,
+        values,
+    ))
+    .await?;
+    Ok(())
+}
+
+/// The canonical form of a PURL.
+struct Canonical {
+    ty: String,
+    namespace: Option<String>,
+    name: String,
+    version: Option<String>,
+    qualifiers: BTreeMap<String, String>,
+}
+
+impl Canonical {
+    fn new(
+        ty: String,
+        namespace: Option<String>,
+        name: String,
+        version: Option<String>,
+        qualifiers: BTreeMap<String, String>,
+    ) -> Self {
+        let ty = ty.to_lowercase();
+
+        let (namespace, mut name) = if CASE_INSENSITIVE_TYPES.contains(&ty.as_str()) {
+            (namespace.map(|ns| ns.to_lowercase()), name.to_lowercase())
+        } else {
+            (namespace, name)
+        };
+        if ty == "pypi" {
+            name = name.replace('_', "-");
+        }
+
+        let qualifiers = qualifiers
+            .into_iter()
+            .filter(|(_, v)| !v.is_empty())
+            .map(|(k, v)| (k.to_lowercase(), v))
+            .collect();
+
+        Self {
+            ty,
+            namespace,
+            name,
+            version,
+            qualifiers,
+        }
+    }
+
+    /// The IDs of the base, versioned, and qualified PURL.
+    fn uuids(&self) -> (Uuid, Uuid, Uuid) {
+        let mut base = Uuid::new_v5(&NAMESPACE, self.ty.as_bytes());
+        if let Some(namespace) = &self.namespace {
+            base = Uuid::new_v5(&base, namespace.as_bytes());
+        }
+        let base = Uuid::new_v5(&base, self.name.as_bytes());
+
+        let version = Uuid::new_v5(
+            &base,
+            self.version
+                .as_ref()
+                .map(|v| v.as_bytes())
+                .unwrap_or_default(),
+        );
+
+        let mut qualified = version;
+        for (k, v) in &self.qualifiers {
+            qualified = Uuid::new_v5(&qualified, k.as_bytes());
+            qualified = Uuid::new_v5(&qualified, v.as_bytes());
+        }
+
+        (base, version, qualified)
+    }
+
+    /// Ensure the entries of the PURL exist, as far as they are known, returning their IDs.
+    async fn create<C: ConnectionTrait>(&self, db: &C) -> Result<(Uuid, Uuid, Uuid), DbErr> {
+        let (base, version, qualified) = self.uuids();
+
+        execute(
+            db,
+            r#"INSERT INTO base_purl (id, "type", namespace, name) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING"#,
+            [
+                base.into(),
+                self.ty.clone().into(),
+                self.namespace.clone().into(),
+                self.name.clone().into(),
+            ],
+        )
+        .await?;
+
+        let Some(v) = &self.version else {
+            return Ok((base, version, qualified));
+        };
+
+        execute(
+            db,
+            "INSERT INTO versioned_purl (id, base_purl_id, version) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+            [version.into(), base.into(), v.clone().into()],
+        )
+        .await?;
+
+        let qualifiers = serde_json::to_string(&self.qualifiers)
+            .map_err(|err| DbErr::Custom(format!("failed to encode qualifiers: {err}")))?;
+        // same layout as the `CanonicalPurl` entity type
+        let purl = serde_json::json!({
+            "ty": self.ty,
+            "namespace": self.namespace,
+            "name": self.name,
+            "version": self.version,
+            "qualifiers": self.qualifiers,
+        })
+        .to_string();
+
+        execute(
+            db,
+            r#"
+INSERT INTO qualified_purl (id, versioned_purl_id, qualifiers, purl)
+VALUES ($1, $2, $3::jsonb, $4::jsonb)
+ON CONFLICT DO NOTHING
+"#,
+            [
+                qualified.into(),
+                version.into(),
+                qualifiers.into(),
+                purl.into(),
+            ],
+        )
+        .await?;
+
+        Ok((base, version, qualified))
+    }
+}
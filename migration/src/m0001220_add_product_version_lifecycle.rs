@@ -0,0 +1,84 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(SupportStatus::Enum)
+                    .values([
+                        SupportStatus::Full,
+                        SupportStatus::Maintenance,
+                        SupportStatus::Extended,
+                        SupportStatus::EndOfLife,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProductVersion::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(ProductVersion::GaDate).timestamp_with_time_zone(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(ProductVersion::EolDate).timestamp_with_time_zone(),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(ProductVersion::SupportStatus).custom(SupportStatus::Enum),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProductVersion::Table)
+                    .drop_column(ProductVersion::SupportStatus)
+                    .drop_column(ProductVersion::EolDate)
+                    .drop_column(ProductVersion::GaDate)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .if_exists()
+                    .name(SupportStatus::Enum)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SupportStatus {
+    #[sea_orm(iden = "product_support_status")]
+    Enum,
+    Full,
+    Maintenance,
+    Extended,
+    EndOfLife,
+}
+
+#[derive(DeriveIden)]
+enum ProductVersion {
+    Table,
+    GaDate,
+    EolDate,
+    SupportStatus,
+}
@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Product::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Product::ParentId).uuid())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name(Indexes::ProductParentIdFkey.to_string())
+                            .from_tbl(Product::Table)
+                            .from_col(Product::ParentId)
+                            .to_tbl(Product::Table)
+                            .to_col(Product::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // walking down the hierarchy looks up the children of a product
+        manager
+            .create_index(
+                Index::create()
+                    .table(Product::Table)
+                    .name(Indexes::ProductParentIdIdx.to_string())
+                    .col(Product::ParentId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(Product::Table)
+                    .name(Indexes::ProductParentIdIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Product::Table)
+                    .drop_foreign_key(Indexes::ProductParentIdFkey)
+                    .drop_column(Product::ParentId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    ProductParentIdFkey,
+    ProductParentIdIdx,
+}
+
+#[derive(DeriveIden)]
+enum Product {
+    Table,
+    Id,
+    ParentId,
+}
@@ -0,0 +1,79 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SbomFileLicense::Table)
+                    .col(ColumnDef::new(SbomFileLicense::SbomId).uuid().not_null())
+                    .col(ColumnDef::new(SbomFileLicense::NodeId).string().not_null())
+                    .col(ColumnDef::new(SbomFileLicense::LicenseId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(SbomFileLicense::LicenseType)
+                            .integer()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(SbomFileLicense::SbomId)
+                            .col(SbomFileLicense::NodeId)
+                            .col(SbomFileLicense::LicenseId)
+                            .col(SbomFileLicense::LicenseType),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(SbomFileLicense::SbomId)
+                            .to(Sbom::Table, Sbom::SbomId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(SbomFileLicense::LicenseId)
+                            .to(License::Table, License::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(SbomFileLicense::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SbomFileLicense {
+    Table,
+    SbomId,
+    NodeId,
+    LicenseId,
+    LicenseType,
+}
+
+#[derive(DeriveIden)]
+enum Sbom {
+    Table,
+    SbomId,
+}
+
+#[derive(DeriveIden)]
+enum License {
+    Table,
+    Id,
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // checksums are being looked up case-insensitively
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                r#"CREATE INDEX IF NOT EXISTS "{}" ON "sbom_node_checksum" (lower("value"))"#,
+                Indexes::SbomNodeChecksumLowerValueIdx.to_string()
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(SbomNodeChecksum::Table)
+                    .name(Indexes::SbomNodeChecksumLowerValueIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    SbomNodeChecksumLowerValueIdx,
+}
+
+#[derive(DeriveIden)]
+enum SbomNodeChecksum {
+    Table,
+}
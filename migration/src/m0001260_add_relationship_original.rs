@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PackageRelatesToPackage::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(PackageRelatesToPackage::OriginalRelationship).string(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // the staging table of bulk loads needs to carry it as well
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PackageRelatesToPackageLoad::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(PackageRelatesToPackageLoad::OriginalRelationship).string(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PackageRelatesToPackageLoad::Table)
+                    .drop_column(PackageRelatesToPackageLoad::OriginalRelationship)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PackageRelatesToPackage::Table)
+                    .drop_column(PackageRelatesToPackage::OriginalRelationship)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum PackageRelatesToPackage {
+    Table,
+    OriginalRelationship,
+}
+
+#[derive(DeriveIden)]
+enum PackageRelatesToPackageLoad {
+    Table,
+    OriginalRelationship,
+}
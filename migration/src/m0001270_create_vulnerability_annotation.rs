@@ -0,0 +1,138 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(AnnotationKind::Enum)
+                    .values([
+                        AnnotationKind::ExploitAvailable,
+                        AnnotationKind::ProofOfConcept,
+                        AnnotationKind::ActivelyExploited,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(VulnerabilityAnnotation::Table)
+                    .col(
+                        ColumnDef::new(VulnerabilityAnnotation::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(VulnerabilityAnnotation::VulnerabilityId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VulnerabilityAnnotation::Kind)
+                            .custom(AnnotationKind::Enum)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(VulnerabilityAnnotation::Url).string())
+                    .col(ColumnDef::new(VulnerabilityAnnotation::Comment).string())
+                    .col(ColumnDef::new(VulnerabilityAnnotation::Source).string())
+                    .col(ColumnDef::new(VulnerabilityAnnotation::UserId).string())
+                    .col(
+                        ColumnDef::new(VulnerabilityAnnotation::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(
+                        ColumnDef::new(VulnerabilityAnnotation::Modified)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(VulnerabilityAnnotation::VulnerabilityId)
+                            .to(Vulnerability::Table, Vulnerability::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(VulnerabilityAnnotation::Table)
+                    .name(Indexes::VulnerabilityAnnotationVulnerabilityIdIdx.to_string())
+                    .col(VulnerabilityAnnotation::VulnerabilityId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(VulnerabilityAnnotation::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .if_exists()
+                    .name(AnnotationKind::Enum)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    VulnerabilityAnnotationVulnerabilityIdIdx,
+}
+
+#[derive(DeriveIden)]
+enum AnnotationKind {
+    #[sea_orm(iden = "annotation_kind")]
+    Enum,
+    ExploitAvailable,
+    ProofOfConcept,
+    ActivelyExploited,
+}
+
+#[derive(DeriveIden)]
+enum VulnerabilityAnnotation {
+    Table,
+    Id,
+    VulnerabilityId,
+    Kind,
+    Url,
+    Comment,
+    Source,
+    UserId,
+    Created,
+    Modified,
+}
+
+#[derive(DeriveIden)]
+enum Vulnerability {
+    Table,
+    Id,
+}
@@ -0,0 +1,209 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(TriageState::Enum)
+                    .values([
+                        TriageState::Open,
+                        TriageState::InReview,
+                        TriageState::Mitigated,
+                        TriageState::AcceptedRisk,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Triage::Table)
+                    .col(
+                        ColumnDef::new(Triage::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Triage::SbomId).uuid().not_null())
+                    .col(ColumnDef::new(Triage::VulnerabilityId).string().not_null())
+                    .col(
+                        ColumnDef::new(Triage::State)
+                            .custom(TriageState::Enum)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Triage::Assignee).string())
+                    .col(ColumnDef::new(Triage::Justification).string())
+                    .col(
+                        ColumnDef::new(Triage::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(
+                        ColumnDef::new(Triage::Modified)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(Triage::SbomId)
+                            .to(Sbom::Table, Sbom::SbomId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(Triage::VulnerabilityId)
+                            .to(Vulnerability::Table, Vulnerability::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // a finding is triaged only once per SBOM, which also serves the lookups by SBOM
+        manager
+            .create_index(
+                Index::create()
+                    .table(Triage::Table)
+                    .name(Indexes::TriageSbomIdVulnerabilityIdIdx.to_string())
+                    .col(Triage::SbomId)
+                    .col(Triage::VulnerabilityId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TriageEvent::Table)
+                    .col(
+                        ColumnDef::new(TriageEvent::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TriageEvent::TriageId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(TriageEvent::Timestamp)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(TriageEvent::UserId).string())
+                    .col(
+                        ColumnDef::new(TriageEvent::State)
+                            .custom(TriageState::Enum)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TriageEvent::Assignee).string())
+                    .col(ColumnDef::new(TriageEvent::Justification).string())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(TriageEvent::TriageId)
+                            .to(Triage::Table, Triage::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(TriageEvent::Table)
+                    .name(Indexes::TriageEventTriageIdTimestampIdx.to_string())
+                    .col(TriageEvent::TriageId)
+                    .col(TriageEvent::Timestamp)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(TriageEvent::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().if_exists().table(Triage::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().if_exists().name(TriageState::Enum).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(DeriveIden)]
+enum Indexes {
+    TriageSbomIdVulnerabilityIdIdx,
+    TriageEventTriageIdTimestampIdx,
+}
+
+#[derive(DeriveIden)]
+enum TriageState {
+    #[sea_orm(iden = "triage_state")]
+    Enum,
+    Open,
+    InReview,
+    Mitigated,
+    AcceptedRisk,
+}
+
+#[derive(DeriveIden)]
+enum Triage {
+    Table,
+    Id,
+    SbomId,
+    VulnerabilityId,
+    State,
+    Assignee,
+    Justification,
+    Created,
+    Modified,
+}
+
+#[derive(DeriveIden)]
+enum TriageEvent {
+    Table,
+    Id,
+    TriageId,
+    Timestamp,
+    UserId,
+    State,
+    Assignee,
+    Justification,
+}
+
+#[derive(DeriveIden)]
+enum Vulnerability {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Sbom {
+    Table,
+    SbomId,
+}
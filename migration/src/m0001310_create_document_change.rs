@@ -0,0 +1,153 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(DocumentChangeKind::Enum)
+                    .values([
+                        DocumentChangeKind::Created,
+                        DocumentChangeKind::Updated,
+                        DocumentChangeKind::Deleted,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(DocumentChange::Table)
+                    .col(
+                        ColumnDef::new(DocumentChange::Seq)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(DocumentChange::Tx).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(DocumentChange::Timestamp)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentChange::DocumentKind)
+                            .custom(DocumentKind::Enum)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DocumentChange::DocumentId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(DocumentChange::Change)
+                            .custom(DocumentChangeKind::Enum)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentChange::Labels)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(DocumentChange::Table)
+                    .name(Indexes::DocumentChangeTimestampIdx.to_string())
+                    .col(DocumentChange::Timestamp)
+                    .to_owned(),
+            )
+            .await?;
+
+        // looking up the changes of a transaction, for reporting them as one
+        manager
+            .create_index(
+                Index::create()
+                    .table(DocumentChange::Table)
+                    .name(Indexes::DocumentChangeTxDocumentIdIdx.to_string())
+                    .col(DocumentChange::Tx)
+                    .col(DocumentChange::DocumentId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!(
+                "m0001310_create_document_change_fns/record_document_change_up.sql"
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!(
+                "m0001310_create_document_change_fns/record_document_change_down.sql"
+            ))
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(DocumentChange::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .if_exists()
+                    .name(DocumentChangeKind::Enum)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(DeriveIden)]
+enum Indexes {
+    DocumentChangeTimestampIdx,
+    DocumentChangeTxDocumentIdIdx,
+}
+
+#[derive(DeriveIden)]
+enum DocumentChangeKind {
+    #[sea_orm(iden = "document_change_kind")]
+    Enum,
+    Created,
+    Updated,
+    Deleted,
+}
+
+#[derive(DeriveIden)]
+enum DocumentKind {
+    #[sea_orm(iden = "document_kind")]
+    Enum,
+}
+
+#[derive(DeriveIden)]
+enum DocumentChange {
+    Table,
+    Seq,
+    Tx,
+    Timestamp,
+    DocumentKind,
+    DocumentId,
+    Change,
+    Labels,
+}
@@ -0,0 +1,6 @@
+DROP TRIGGER IF EXISTS advisory_document_update ON advisory;
+DROP TRIGGER IF EXISTS advisory_document_change ON advisory;
+DROP TRIGGER IF EXISTS sbom_document_update ON sbom;
+DROP TRIGGER IF EXISTS sbom_document_change ON sbom;
+
+DROP FUNCTION IF EXISTS public.record_document_change();
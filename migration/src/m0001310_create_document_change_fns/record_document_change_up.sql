@@ -0,0 +1,75 @@
+-- record the changes of SBOMs and advisories, for mirroring them incrementally
+--
+-- The first argument is the kind of the document, the second one the name of its ID column.
+-- Several changes of a document within one transaction are reported as one, changes of soft
+-- deleted documents are not reported at all.
+CREATE OR REPLACE FUNCTION public.record_document_change() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+DECLARE
+    _document jsonb;
+    _id uuid;
+    _change document_change_kind;
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        _document := to_jsonb(NEW);
+        _change := 'created';
+    ELSIF TG_OP = 'DELETE' THEN
+        IF OLD.deleted_at IS NOT NULL THEN
+            -- already reported when it got soft deleted
+            RETURN NULL;
+        END IF;
+        _document := to_jsonb(OLD);
+        _change := 'deleted';
+    ELSE
+        _document := to_jsonb(NEW);
+        IF OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL THEN
+            _change := 'deleted';
+        ELSIF OLD.deleted_at IS NOT NULL AND NEW.deleted_at IS NULL THEN
+            -- restored, which appears as a new document
+            _change := 'created';
+        ELSIF NEW.deleted_at IS NOT NULL THEN
+            RETURN NULL;
+        ELSE
+            _change := 'updated';
+        END IF;
+    END IF;
+
+    _id := (_document ->> TG_ARGV[1])::uuid;
+
+    IF _change = 'updated' THEN
+        UPDATE document_change
+        SET labels = COALESCE(_document -> 'labels', '{}'::jsonb)
+        WHERE tx = txid_current()
+          AND document_id = _id
+          AND change <> 'deleted';
+        IF FOUND THEN
+            RETURN NULL;
+        END IF;
+    END IF;
+
+    INSERT INTO document_change (tx, timestamp, document_kind, document_id, change, labels)
+    VALUES (txid_current(), clock_timestamp(), TG_ARGV[0]::document_kind, _id, _change,
+            COALESCE(_document -> 'labels', '{}'::jsonb));
+
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER sbom_document_change
+    AFTER INSERT OR DELETE ON sbom
+    FOR EACH ROW EXECUTE FUNCTION record_document_change('sbom', 'sbom_id');
+
+CREATE TRIGGER sbom_document_update
+    AFTER UPDATE ON sbom
+    FOR EACH ROW WHEN (OLD.* IS DISTINCT FROM NEW.*)
+    EXECUTE FUNCTION record_document_change('sbom', 'sbom_id');
+
+CREATE TRIGGER advisory_document_change
+    AFTER INSERT OR DELETE ON advisory
+    FOR EACH ROW EXECUTE FUNCTION record_document_change('advisory', 'id');
+
+CREATE TRIGGER advisory_document_update
+    AFTER UPDATE ON advisory
+    FOR EACH ROW WHEN (OLD.* IS DISTINCT FROM NEW.*)
+    EXECUTE FUNCTION record_document_change('advisory', 'id');
@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS document_change_event ON document_change;
+
+DROP FUNCTION IF EXISTS public.record_event();
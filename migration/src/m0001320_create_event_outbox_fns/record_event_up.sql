@@ -0,0 +1,28 @@
+-- queue an event for each document getting ingested or deleted, to be published by the relay
+--
+-- The event is written by the same transaction as the change itself, so no event gets lost, and
+-- no event gets published for a change which got rolled back. Updates are not published.
+CREATE OR REPLACE FUNCTION public.record_event() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    IF NEW.change = 'updated' THEN
+        RETURN NULL;
+    END IF;
+
+    INSERT INTO event_outbox (created, key, payload)
+    VALUES (NEW.timestamp, NEW.document_id::text, jsonb_build_object(
+        'timestamp', to_char(NEW.timestamp AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS.US"Z"'),
+        'type', NEW.document_kind,
+        'id', NEW.document_id,
+        'event', CASE NEW.change WHEN 'created' THEN 'ingested' ELSE 'deleted' END,
+        'labels', NEW.labels
+    ));
+
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER document_change_event
+    AFTER INSERT ON document_change
+    FOR EACH ROW EXECUTE FUNCTION record_event();
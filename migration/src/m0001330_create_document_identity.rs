@@ -0,0 +1,163 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DocumentIdentity::Table)
+                    .col(
+                        ColumnDef::new(DocumentIdentity::DocumentId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentIdentity::DocumentKind)
+                            .custom(DocumentKind::Enum)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DocumentIdentity::Format).string().not_null())
+                    .col(
+                        ColumnDef::new(DocumentIdentity::Identifier)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DocumentIdentity::Revision).string())
+                    .col(
+                        ColumnDef::new(DocumentIdentity::CanonicalSha256)
+                            .string()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(DocumentIdentity::Table)
+                    .name(Indexes::DocumentIdentityIdentifierIdx.to_string())
+                    .col(DocumentIdentity::Format)
+                    .col(DocumentIdentity::Identifier)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(DocumentConflict::Table)
+                    .col(
+                        ColumnDef::new(DocumentConflict::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentConflict::Detected)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentConflict::DocumentKind)
+                            .custom(DocumentKind::Enum)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DocumentConflict::Format).string().not_null())
+                    .col(
+                        ColumnDef::new(DocumentConflict::Identifier)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DocumentConflict::Revision).string())
+                    .col(
+                        ColumnDef::new(DocumentConflict::ExistingId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DocumentConflict::ConflictingId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(DocumentConflict::Table)
+                    .name(Indexes::DocumentConflictDetectedIdx.to_string())
+                    .col(DocumentConflict::Detected)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(DocumentConflict::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(DocumentIdentity::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(DeriveIden)]
+enum Indexes {
+    DocumentIdentityIdentifierIdx,
+    DocumentConflictDetectedIdx,
+}
+
+#[derive(DeriveIden)]
+enum DocumentKind {
+    #[sea_orm(iden = "document_kind")]
+    Enum,
+}
+
+#[derive(DeriveIden)]
+enum DocumentIdentity {
+    Table,
+    DocumentId,
+    DocumentKind,
+    Format,
+    Identifier,
+    Revision,
+    CanonicalSha256,
+}
+
+#[derive(DeriveIden)]
+enum DocumentConflict {
+    Table,
+    Id,
+    Detected,
+    DocumentKind,
+    Format,
+    Identifier,
+    Revision,
+    ExistingId,
+    ConflictingId,
+}
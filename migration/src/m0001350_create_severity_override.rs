@@ -0,0 +1,87 @@
+use crate::Now;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SeverityOverride::Table)
+                    .col(
+                        ColumnDef::new(SeverityOverride::VulnerabilityId)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SeverityOverride::Severity)
+                            .custom(Cvss3Severity::Enum)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SeverityOverride::Reason).string())
+                    .col(ColumnDef::new(SeverityOverride::UserId).string())
+                    .col(
+                        ColumnDef::new(SeverityOverride::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(
+                        ColumnDef::new(SeverityOverride::Modified)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(SeverityOverride::VulnerabilityId)
+                            .to(Vulnerability::Table, Vulnerability::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(SeverityOverride::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Cvss3Severity {
+    #[sea_orm(iden = "cvss3_severity")]
+    Enum,
+}
+
+#[derive(DeriveIden)]
+enum SeverityOverride {
+    Table,
+    VulnerabilityId,
+    Severity,
+    Reason,
+    UserId,
+    Created,
+    Modified,
+}
+
+#[derive(DeriveIden)]
+enum Vulnerability {
+    Table,
+    Id,
+}
@@ -0,0 +1,75 @@
+use crate::Now;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Rebuild::Table)
+                    .col(ColumnDef::new(Rebuild::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Rebuild::State).integer().not_null())
+                    .col(
+                        ColumnDef::new(Rebuild::Tasks)
+                            .array(ColumnType::Text)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Rebuild::Completed)
+                            .array(ColumnType::Text)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Rebuild::Started)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(Rebuild::Finished).timestamp_with_time_zone())
+                    .col(ColumnDef::new(Rebuild::Error).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Rebuild::Table)
+                    .name(Indexes::RebuildStartedIdx.to_string())
+                    .col(Rebuild::Started)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().if_exists().table(Rebuild::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    RebuildStartedIdx,
+}
+
+#[derive(DeriveIden)]
+enum Rebuild {
+    Table,
+    Id,
+    State,
+    Tasks,
+    Completed,
+    Started,
+    Finished,
+    Error,
+}
@@ -0,0 +1,86 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [
+            Tables::Policy,
+            Tables::VulnerabilityAnnotation,
+            Tables::Assignment,
+            Tables::Rebuild,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .add_column_if_not_exists(ColumnDef::new(Columns::Tenant).string())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tables::SeverityOverride)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Columns::Tenant)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("m0001390_add_tenant_columns/keys_up.sql"))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("m0001390_add_tenant_columns/keys_down.sql"))
+            .await?;
+
+        for table in [
+            Tables::SeverityOverride,
+            Tables::Policy,
+            Tables::VulnerabilityAnnotation,
+            Tables::Assignment,
+            Tables::Rebuild,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_column(Columns::Tenant)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden, Clone, Copy)]
+enum Tables {
+    Policy,
+    VulnerabilityAnnotation,
+    Assignment,
+    Rebuild,
+    SeverityOverride,
+}
+
+#[derive(DeriveIden)]
+enum Columns {
+    Tenant,
+}
@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS policy_name_tenant_idx;
+DELETE FROM policy WHERE tenant IS NOT NULL;
+ALTER TABLE policy ADD CONSTRAINT policy_name_key UNIQUE (name);
+
+DELETE FROM severity_override WHERE tenant != '';
+ALTER TABLE severity_override
+    DROP CONSTRAINT severity_override_pkey,
+    ADD PRIMARY KEY (vulnerability_id);
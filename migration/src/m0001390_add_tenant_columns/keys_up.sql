@@ -0,0 +1,9 @@
+-- each tenant overrides the severity of a vulnerability on its own, the empty tenant being the one
+-- used without tenant isolation
+ALTER TABLE severity_override
+    DROP CONSTRAINT severity_override_pkey,
+    ADD PRIMARY KEY (vulnerability_id, tenant);
+
+-- the names of policies are unique per tenant
+ALTER TABLE policy DROP CONSTRAINT IF EXISTS policy_name_key;
+CREATE UNIQUE INDEX IF NOT EXISTS policy_name_tenant_idx ON policy (name, COALESCE(tenant, ''));
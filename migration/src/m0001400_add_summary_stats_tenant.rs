@@ -0,0 +1,30 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!(
+                "m0001400_add_summary_stats_tenant/summary_stats_up.sql"
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // restore the view without tenants
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!(
+                "m0001150_add_purl_ecosystem/summary_stats_up.sql"
+            ))
+            .await?;
+
+        Ok(())
+    }
+}
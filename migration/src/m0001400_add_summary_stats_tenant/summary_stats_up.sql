@@ -0,0 +1,126 @@
+DROP MATERIALIZED VIEW IF EXISTS summary_stats;
+
+-- counts for dashboards, in total and per tenant
+--
+-- the total is counted for the empty tenant. documents count for their tenant as well, packages
+-- for the tenants of the SBOMs they are part of, and vulnerabilities for the tenants of the
+-- advisories referring to them.
+CREATE MATERIALIZED VIEW summary_stats AS
+WITH live_advisory AS (
+    SELECT advisory.*, scope.tenant
+    FROM advisory
+    CROSS JOIN LATERAL (VALUES (''), (advisory.labels ->> 'tenant')) AS scope (tenant)
+    WHERE advisory.deleted_at IS NULL AND scope.tenant IS NOT NULL
+),
+live_sbom AS (
+    SELECT sbom.*, scope.tenant
+    FROM sbom
+    CROSS JOIN LATERAL (VALUES (''), (sbom.labels ->> 'tenant')) AS scope (tenant)
+    WHERE sbom.deleted_at IS NULL AND scope.tenant IS NOT NULL
+),
+scoped_qualified_purl AS (
+    SELECT '' AS tenant, qualified_purl.id, qualified_purl.versioned_purl_id
+    FROM qualified_purl
+
+    UNION
+
+    SELECT live_sbom.tenant, qualified_purl.id, qualified_purl.versioned_purl_id
+    FROM live_sbom
+    JOIN sbom_package_purl_ref ON sbom_package_purl_ref.sbom_id = live_sbom.sbom_id
+    JOIN qualified_purl ON qualified_purl.id = sbom_package_purl_ref.qualified_purl_id
+    WHERE live_sbom.tenant != ''
+),
+scoped_base_purl AS (
+    SELECT '' AS tenant, base_purl.id, base_purl.ecosystem
+    FROM base_purl
+
+    UNION
+
+    SELECT scoped_qualified_purl.tenant, base_purl.id, base_purl.ecosystem
+    FROM scoped_qualified_purl
+    JOIN versioned_purl ON versioned_purl.id = scoped_qualified_purl.versioned_purl_id
+    JOIN base_purl ON base_purl.id = versioned_purl.base_purl_id
+    WHERE scoped_qualified_purl.tenant != ''
+),
+scoped_vulnerability AS (
+    SELECT '' AS tenant, vulnerability.id
+    FROM vulnerability
+
+    UNION
+
+    SELECT live_advisory.tenant, advisory_vulnerability.vulnerability_id
+    FROM live_advisory
+    JOIN advisory_vulnerability ON advisory_vulnerability.advisory_id = live_advisory.id
+    WHERE live_advisory.tenant != ''
+),
+-- the highest CVSS v3 severity of each advisory
+advisory_severity AS (
+    SELECT DISTINCT ON (advisory_id) advisory_id, severity
+    FROM cvss3
+    ORDER BY advisory_id, score DESC
+),
+period (key, since) AS (
+    VALUES ('day', interval '1 day'), ('week', interval '7 days')
+)
+SELECT tenant, category, key, count, now() AS refreshed
+FROM (
+    SELECT tenant, 'advisory_type' AS category, COALESCE(labels ->> 'type', 'unknown') AS key, count(*) AS count
+    FROM live_advisory
+    GROUP BY 1, 3
+
+    UNION ALL
+
+    SELECT tenant, 'advisory_severity', COALESCE(advisory_severity.severity::text, 'unknown'), count(*)
+    FROM live_advisory
+    LEFT JOIN advisory_severity ON advisory_severity.advisory_id = live_advisory.id
+    GROUP BY 1, 3
+
+    UNION ALL
+
+    SELECT tenant, 'sbom', '', count(*) FROM live_sbom GROUP BY 1
+
+    UNION ALL
+
+    SELECT tenant, 'vulnerability', '', count(*) FROM scoped_vulnerability GROUP BY 1
+
+    UNION ALL
+
+    SELECT tenant, 'base_purl', '', count(*) FROM scoped_base_purl GROUP BY 1
+
+    UNION ALL
+
+    SELECT tenant, 'qualified_purl', '', count(*) FROM scoped_qualified_purl GROUP BY 1
+
+    UNION ALL
+
+    SELECT tenant, 'ecosystem_base_purl', ecosystem, count(*)
+    FROM scoped_base_purl
+    GROUP BY 1, 3
+
+    UNION ALL
+
+    SELECT live_advisory.tenant, 'ecosystem_vulnerability', base_purl.ecosystem, count(DISTINCT purl_status.vulnerability_id)
+    FROM purl_status
+    JOIN live_advisory ON live_advisory.id = purl_status.advisory_id
+    JOIN base_purl ON base_purl.id = purl_status.base_purl_id
+    GROUP BY 1, 3
+
+    UNION ALL
+
+    SELECT live_advisory.tenant, 'ingested_advisory', period.key, count(*)
+    FROM period
+    JOIN source_document ON source_document.ingested > now() - period.since
+    JOIN live_advisory ON live_advisory.source_document_id = source_document.id
+    GROUP BY 1, 3
+
+    UNION ALL
+
+    SELECT live_sbom.tenant, 'ingested_sbom', period.key, count(*)
+    FROM period
+    JOIN source_document ON source_document.ingested > now() - period.since
+    JOIN live_sbom ON live_sbom.source_document_id = source_document.id
+    GROUP BY 1, 3
+) AS stats;
+
+-- required for refreshing the view concurrently
+CREATE UNIQUE INDEX summary_stats_tenant_category_key_idx ON summary_stats (tenant, category, key);
@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DocumentIdentity::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(DocumentIdentity::Tenant)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // identities of documents, ingested before identities were tracked, are backfilled by
+        // the `identities` rebuild task, as it requires the content of the documents
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!("m0001410_add_document_identity_tenant/up.sql"))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(include_str!(
+                "m0001410_add_document_identity_tenant/down.sql"
+            ))
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DocumentIdentity::Table)
+                    .drop_column(DocumentIdentity::Tenant)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum DocumentIdentity {
+    Table,
+    Tenant,
+}
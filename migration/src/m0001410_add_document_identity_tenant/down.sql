@@ -0,0 +1 @@
+DROP INDEX IF EXISTS document_identity_content_idx;
@@ -0,0 +1,27 @@
+-- identities are tracked per tenant, like their documents
+UPDATE document_identity
+SET tenant = COALESCE(sbom.labels ->> 'tenant', '')
+FROM sbom
+WHERE document_identity.document_kind = 'sbom' AND sbom.sbom_id = document_identity.document_id;
+
+UPDATE document_identity
+SET tenant = COALESCE(advisory.labels ->> 'tenant', '')
+FROM advisory
+WHERE document_identity.document_kind = 'advisory' AND advisory.id = document_identity.document_id;
+
+-- equivalent documents, which got ingested concurrently, are identified by the first one only
+DELETE FROM document_identity AS duplicate
+USING document_identity AS first
+WHERE
+    duplicate.document_kind = first.document_kind
+    AND duplicate.format = first.format
+    AND duplicate.identifier = first.identifier
+    AND duplicate.revision IS NOT DISTINCT FROM first.revision
+    AND duplicate.canonical_sha256 = first.canonical_sha256
+    AND duplicate.tenant = first.tenant
+    AND duplicate.document_id > first.document_id;
+
+-- an identity, with the same content, belongs to a single document of a tenant
+CREATE UNIQUE INDEX IF NOT EXISTS document_identity_content_idx ON document_identity (
+    document_kind, format, identifier, revision, canonical_sha256, tenant
+) NULLS NOT DISTINCT;
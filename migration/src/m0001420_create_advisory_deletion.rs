@@ -0,0 +1,75 @@
+use crate::Now;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AdvisoryDeletion::Table)
+                    .col(
+                        ColumnDef::new(AdvisoryDeletion::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AdvisoryDeletion::State).integer().not_null())
+                    .col(
+                        ColumnDef::new(AdvisoryDeletion::Total)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AdvisoryDeletion::Deleted)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(AdvisoryDeletion::Tenant).string())
+                    .col(ColumnDef::new(AdvisoryDeletion::UserId).string())
+                    .col(
+                        ColumnDef::new(AdvisoryDeletion::Started)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(AdvisoryDeletion::Finished).timestamp_with_time_zone())
+                    .col(ColumnDef::new(AdvisoryDeletion::Error).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .if_exists()
+                    .table(AdvisoryDeletion::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AdvisoryDeletion {
+    Table,
+    Id,
+    State,
+    Total,
+    Deleted,
+    Tenant,
+    UserId,
+    Started,
+    Finished,
+    Error,
+}
@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(IngestionJob::Table)
+                    .add_column(ColumnDef::new(IngestionJob::Tenant).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // jobs submitted so far carry the tenant in their stamped labels
+        manager
+            .get_connection()
+            .execute_unprepared(r#"UPDATE ingestion_job SET tenant = labels ->> 'tenant'"#)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(IngestionJob::Table)
+                    .drop_column(IngestionJob::Tenant)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum IngestionJob {
+    Table,
+    Tenant,
+}
@@ -0,0 +1,68 @@
+use migration::sea_orm::EntityTrait;
+use migration::{ConnectionTrait, Migrator, MigratorTrait};
+use std::str::FromStr;
+use test_context::test_context;
+use test_log::test;
+use trustify_common::purl::Purl;
+use trustify_entity::{base_purl, qualified_purl, versioned_purl};
+use trustify_test_context::TrustifyContext;
+
+/// Insert a PURL, with the IDs it got before PURLs were canonicalized.
+async fn insert_legacy(
+    ctx: &TrustifyContext,
+    name: &str,
+    qualifiers: &str,
+) -> Result<(), anyhow::Error> {
+    ctx.db
+        .execute_unprepared(&format!(
+            r#"
+WITH b AS (
+    INSERT INTO base_purl (id, "type", name) VALUES (gen_random_uuid(), 'npm', '{name}') RETURNING id
+), v AS (
+    INSERT INTO versioned_purl (id, base_purl_id, version) SELECT gen_random_uuid(), b.id, '1.0' FROM b RETURNING id
+)
+INSERT INTO qualified_purl (id, versioned_purl_id, qualifiers, purl)
+SELECT gen_random_uuid(), v.id, '{qualifiers}'::jsonb,
+    jsonb_build_object('ty', 'npm', 'namespace', null, 'name', '{name}', 'version', '1.0', 'qualifiers', '{qualifiers}'::jsonb)
+FROM v
+"#
+        ))
+        .await?;
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn merge_non_canonical(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let purl = Purl::from_str("pkg:npm/lodash@1.0")?;
+    ctx.graph.ingest_qualified_package(&purl, &ctx.db).await?;
+
+    insert_legacy(ctx, "Lodash", "{}").await?;
+    insert_legacy(ctx, "LODASH", r#"{"foo": ""}"#).await?;
+
+    assert_eq!(base_purl::Entity::find().all(&ctx.db).await?.len(), 3);
+
+    // run the migration again
+
+    Migrator::down(&*ctx.db, Some(1)).await?;
+    Migrator::up(&*ctx.db, None).await?;
+
+    // all of them got merged into the canonical one
+
+    let base = base_purl::Entity::find().all(&ctx.db).await?;
+    assert_eq!(base.len(), 1);
+    assert_eq!(base[0].id, purl.package_uuid());
+    assert_eq!(base[0].name, "lodash");
+
+    let versioned = versioned_purl::Entity::find().all(&ctx.db).await?;
+    assert_eq!(versioned.len(), 1);
+    assert_eq!(versioned[0].id, purl.version_uuid());
+
+    let qualified = qualified_purl::Entity::find().all(&ctx.db).await?;
+    assert_eq!(qualified.len(), 1);
+    assert_eq!(qualified[0].id, purl.qualifier_uuid());
+    assert_eq!(Purl::from(qualified[0].purl.clone()), purl);
+
+    Ok(())
+}
@@ -7,21 +7,24 @@ use super::service::{AnalysisService, QueryOptions};
 use crate::{
     endpoints::query::OwnedComponentReference,
     model::{AnalysisStatus, Node},
-    service::render::Renderer,
+    service::{render::Renderer, visible},
 };
-use actix_web::{HttpResponse, Responder, get, web};
+use actix_web::{HttpRequest, HttpResponse, Responder, get, web};
 use serde_json::json;
 use trustify_auth::{
     Permission, ReadSbom,
     authenticator::user::UserInformation,
     authorizer::{Authorizer, Require},
+    tenant::tenant_of,
     utoipa::AuthResponse,
 };
 use trustify_common::{
     db::{Database, query::Query},
+    error::ErrorInformation,
     model::{Paginated, PaginatedResults},
 };
 use utoipa_actix_web::service_config::ServiceConfig;
+use uuid::Uuid;
 
 pub fn configure(config: &mut ServiceConfig, db: Database, analysis: AnalysisService) {
     config
@@ -51,10 +54,12 @@ pub async fn analysis_status(
     user: UserInformation,
     authorizer: web::Data<Authorizer>,
     _: Require<ReadSbom>,
+    req: HttpRequest,
 ) -> actix_web::Result<impl Responder> {
     // TODO: Replace with a more "admin" style permission when revisiting the permission system
     authorizer.require(&user, Permission::CreateSbom)?;
-    Ok(HttpResponse::Ok().json(service.status(db.as_ref()).await?))
+    let tenant = tenant_of(&req)?;
+    Ok(HttpResponse::Ok().json(service.status(tenant.as_deref(), db.as_ref()).await?))
 }
 
 #[utoipa::path(
@@ -80,12 +85,14 @@ pub async fn get_component(
     web::Query(options): web::Query<QueryOptions>,
     web::Query(paginated): web::Query<Paginated>,
     _: Require<ReadSbom>,
+    req: HttpRequest,
 ) -> actix_web::Result<impl Responder> {
+    let tenant = tenant_of(&req)?;
     let query = OwnedComponentReference::try_from(key.as_str())?;
 
     Ok(HttpResponse::Ok().json(
         service
-            .retrieve(&query, options, paginated, db.as_ref())
+            .retrieve(&query, options, paginated, tenant.as_deref(), db.as_ref())
             .await?,
     ))
 }
@@ -112,10 +119,12 @@ pub async fn search_component(
     web::Query(options): web::Query<QueryOptions>,
     web::Query(paginated): web::Query<Paginated>,
     _: Require<ReadSbom>,
+    req: HttpRequest,
 ) -> actix_web::Result<impl Responder> {
+    let tenant = tenant_of(&req)?;
     Ok(HttpResponse::Ok().json(
         service
-            .retrieve(&search, options, paginated, db.as_ref())
+            .retrieve(&search, options, paginated, tenant.as_deref(), db.as_ref())
             .await?,
     ))
 }
@@ -130,8 +139,8 @@ pub async fn search_component(
     responses(
         AuthResponse,
         (status = 200, description = "A rendered version of the SBOM graph in the format requested", body = String),
-        (status = 404, description = "The SBOM was not found"),
-        (status = 415, description = "Unsupported rendering format"),
+        (status = 404, description = "The SBOM was not found", body = ErrorInformation),
+        (status = 415, description = "Unsupported rendering format", body = ErrorInformation),
     ),
 )]
 #[get("/v2/analysis/sbom/{sbom}/render.{ext}")]
@@ -141,13 +150,22 @@ pub async fn render_sbom_graph(
     db: web::Data<Database>,
     path: web::Path<(String, String)>,
     _: Require<ReadSbom>,
+    req: HttpRequest,
 ) -> actix_web::Result<impl Responder> {
+    let tenant = tenant_of(&req)?;
     let (sbom, ext) = path.into_inner();
 
     let Ok(ext) = serde_json::from_value::<Renderer>(json!(ext)) else {
         return Ok(HttpResponse::UnsupportedMediaType().finish());
     };
 
+    // the SBOMs of other tenants don't exist for the user
+    if let Ok(id) = Uuid::parse_str(&sbom) {
+        if !visible(tenant.as_deref(), id, db.as_ref()).await? {
+            return Ok(HttpResponse::NotFound().finish());
+        }
+    }
+
     let graph = service.load_graph(db.as_ref(), &sbom).await?;
 
     if let Some((data, content_type)) = service.render(graph.as_ref(), ext) {
@@ -179,10 +197,12 @@ pub async fn search_latest_component(
     web::Query(options): web::Query<QueryOptions>,
     web::Query(paginated): web::Query<Paginated>,
     _: Require<ReadSbom>,
+    req: HttpRequest,
 ) -> actix_web::Result<impl Responder> {
+    let tenant = tenant_of(&req)?;
     Ok(HttpResponse::Ok().json(
         service
-            .retrieve_latest(&search, options, paginated, db.as_ref())
+            .retrieve_latest(&search, options, paginated, tenant.as_deref(), db.as_ref())
             .await?,
     ))
 }
@@ -210,12 +230,14 @@ pub async fn get_latest_component(
     web::Query(options): web::Query<QueryOptions>,
     web::Query(paginated): web::Query<Paginated>,
     _: Require<ReadSbom>,
+    req: HttpRequest,
 ) -> actix_web::Result<impl Responder> {
+    let tenant = tenant_of(&req)?;
     let query = OwnedComponentReference::try_from(key.as_str())?;
 
     Ok(HttpResponse::Ok().json(
         service
-            .retrieve_latest(&query, options, paginated, db.as_ref())
+            .retrieve_latest(&query, options, paginated, tenant.as_deref(), db.as_ref())
             .await?,
     ))
 }
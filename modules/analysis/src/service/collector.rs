@@ -32,6 +32,8 @@ pub struct Collector<'a, C: ConnectionTrait> {
     depth: u64,
     discovered: DiscoveredTracker,
     relationships: &'a HashSet<Relationship>,
+    /// the tenant whose SBOMs may be followed into, any SBOM without a tenant
+    tenant: Option<&'a str>,
     connection: &'a C,
 }
 
@@ -46,6 +48,7 @@ impl<'a, C: ConnectionTrait> Collector<'a, C> {
             depth: self.depth,
             discovered: self.discovered.clone(),
             relationships: self.relationships,
+            tenant: self.tenant,
             connection: self.connection,
         }
     }
@@ -60,6 +63,7 @@ impl<'a, C: ConnectionTrait> Collector<'a, C> {
         direction: Direction,
         depth: u64,
         relationships: &'a HashSet<Relationship>,
+        tenant: Option<&'a str>,
         connection: &'a C,
     ) -> Self {
         Self {
@@ -71,6 +75,7 @@ impl<'a, C: ConnectionTrait> Collector<'a, C> {
             depth,
             discovered: Default::default(),
             relationships,
+            tenant,
             connection,
         }
     }
@@ -99,6 +104,7 @@ impl<'a, C: ConnectionTrait> Collector<'a, C> {
             depth: self.depth - 1,
             discovered: self.discovered.clone(),
             relationships: self.relationships,
+            tenant: self.tenant,
             connection: self.connection,
         }
     }
@@ -129,6 +135,10 @@ impl<'a, C: ConnectionTrait> Collector<'a, C> {
                     node_id: external_node_id,
                 } = resolve_external_sbom(external_node.node_id.clone(), self.connection).await?;
 
+                if !self.visible(external_sbom_id).await {
+                    return None;
+                }
+
                 // retrieve external sbom graph from graph_cache
                 let Some(external_graph) = self.graph_cache.get(&external_sbom_id.to_string())
                 else {
@@ -190,6 +200,9 @@ impl<'a, C: ConnectionTrait> Collector<'a, C> {
                         .await
                     {
                         Ok(Some(matched)) => {
+                            if !self.visible(matched.sbom_id).await {
+                                continue;
+                            }
                             // get the external sbom graph
                             let Some(external_graph) =
                                 self.graph_cache.clone().get(&matched.sbom_id.to_string())
@@ -245,6 +258,17 @@ impl<'a, C: ConnectionTrait> Collector<'a, C> {
         }
     }
 
+    /// Check if the collector may follow into an external SBOM.
+    async fn visible(&self, sbom_id: Uuid) -> bool {
+        match visible(self.tenant, sbom_id, self.connection).await {
+            Ok(visible) => visible,
+            Err(err) => {
+                log::warn!("Problem checking the tenant of sbom {sbom_id}: {err}");
+                false
+            }
+        }
+    }
+
     pub async fn collect_graph(&self) -> Vec<Node> {
         let mut result = vec![];
         log::debug!("Collecting graph for {:?}", self.node);
@@ -1,4 +1,4 @@
-use crate::service::resolve_external_sbom;
+use crate::service::{resolve_external_sbom, tenant_condition, visible};
 use crate::{
     Error,
     model::{PackageGraph, graph},
@@ -214,6 +214,7 @@ impl AnalysisService {
         &self,
         connection: &C,
         query: GraphQuery<'_>,
+        tenant: Option<&str>,
     ) -> Result<Vec<(String, Arc<PackageGraph>)>, Error> {
         let search_sbom_subquery = match query {
             GraphQuery::Component(ComponentReference::Id(name)) => sbom_node::Entity::find()
@@ -302,18 +303,22 @@ impl AnalysisService {
                 .into_query(),
         };
 
-        self.load_graphs_subquery(connection, search_sbom_subquery)
+        self.load_graphs_subquery(connection, search_sbom_subquery, tenant)
             .await
     }
 
     /// Take a select for sboms, and ensure they are loaded and return their IDs.
+    ///
+    /// Only SBOMs visible to the tenant are loaded.
     async fn load_graphs_subquery<C: ConnectionTrait>(
         &self,
         connection: &C,
         subquery: SelectStatement,
+        tenant: Option<&str>,
     ) -> Result<Vec<(String, Arc<PackageGraph>)>, Error> {
         let distinct_sbom_ids: Vec<String> = sbom::Entity::find()
             .filter(sbom::Column::SbomId.in_subquery(subquery))
+            .filter(tenant_condition(tenant))
             .select()
             .order_by(sbom::Column::DocumentId, Order::Asc)
             .order_by(sbom::Column::Published, Order::Desc)
@@ -323,7 +328,8 @@ impl AnalysisService {
             .map(|record| record.sbom_id.to_string()) // Assuming sbom_id is of type String
             .collect();
 
-        self.load_graphs(connection, &distinct_sbom_ids).await
+        self.load_graphs(connection, &distinct_sbom_ids, tenant)
+            .await
     }
 
     /// Load the SBOM matching the provided ID
@@ -436,11 +442,12 @@ impl AnalysisService {
         Ok(g)
     }
 
-    /// Load all SBOMs by the provided IDs
+    /// Load all SBOMs by the provided IDs, along with the external SBOMs visible to the tenant
     pub async fn load_graphs<C: ConnectionTrait>(
         &self,
         connection: &C,
         distinct_sbom_ids: &Vec<String>,
+        tenant: Option<&str>,
     ) -> Result<Vec<(String, Arc<PackageGraph>)>, Error> {
         let mut results = Vec::new();
         for distinct_sbom_id in distinct_sbom_ids {
@@ -453,6 +460,12 @@ impl AnalysisService {
                     log::debug!("resolved external sbom: {:?}", resolved_external_sbom);
                     if let Some(resolved_external_sbom) = resolved_external_sbom {
                         let resolved_external_sbom_id = resolved_external_sbom.clone().sbom_id;
+                        if !visible(tenant, resolved_external_sbom_id, connection).await? {
+                            log::debug!(
+                                "External sbom {resolved_external_sbom_id} belongs to another tenant"
+                            );
+                            continue;
+                        }
                         results.push((
                             resolved_external_sbom_id.clone().to_string(),
                             self.load_graph(connection, &resolved_external_sbom_id.to_string())
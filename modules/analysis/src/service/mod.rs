@@ -26,8 +26,8 @@ use petgraph::{
     visit::{IntoNodeIdentifiers, VisitMap, Visitable},
 };
 use sea_orm::{
-    ColumnTrait, DatabaseBackend, EntityOrSelect, EntityTrait, QueryFilter, QuerySelect,
-    RelationTrait, Statement, prelude::ConnectionTrait,
+    ColumnTrait, Condition, DatabaseBackend, EntityOrSelect, EntityTrait, PaginatorTrait,
+    QueryFilter, QuerySelect, RelationTrait, Statement, prelude::ConnectionTrait,
 };
 use sea_query::JoinType;
 use std::{
@@ -36,8 +36,9 @@ use std::{
     sync::Arc,
 };
 use tracing::instrument;
+use trustify_auth::tenant::TENANT_LABEL;
 use trustify_common::{
-    db::query::Value,
+    db::query::{LabelFilter, Value},
     model::{Paginated, PaginatedResults},
 };
 use trustify_entity::{
@@ -276,7 +277,7 @@ impl AnalysisService {
             .map(|record| record.sbom_id.to_string()) // Assuming sbom_id is of type String
             .collect();
 
-        self.load_graphs(connection, &distinct_sbom_ids).await
+        self.load_graphs(connection, &distinct_sbom_ids, None).await
     }
 
     pub fn clear_all_graphs(&self) -> Result<(), Error> {
@@ -286,12 +287,16 @@ impl AnalysisService {
 
     pub async fn status<C: ConnectionTrait>(
         &self,
+        tenant: Option<&str>,
         connection: &C,
     ) -> Result<AnalysisStatus, Error> {
-        let distinct_sbom_ids = sbom::Entity::find().select().all(connection).await?;
+        let sbom_count = sbom::Entity::find()
+            .filter(tenant_condition(tenant))
+            .count(connection)
+            .await?;
 
         Ok(AnalysisStatus {
-            sbom_count: distinct_sbom_ids.len() as u32,
+            sbom_count: sbom_count as u32,
             graph_count: self.graph_cache.len() as u32,
         })
     }
@@ -333,6 +338,7 @@ impl AnalysisService {
         query: impl Into<GraphQuery<'a>> + Debug,
         options: QueryOptions,
         graphs: &[(String, Arc<PackageGraph>)],
+        tenant: Option<&str>,
         connection: &C,
         graph_cache: Arc<GraphMap>,
     ) -> Vec<Node> {
@@ -356,6 +362,7 @@ impl AnalysisService {
                         Direction::Incoming,
                         options.ancestors,
                         &relationships,
+                        tenant,
                         connection,
                     )
                     .collect(),
@@ -370,6 +377,7 @@ impl AnalysisService {
                         Direction::Outgoing,
                         options.descendants,
                         &relationships,
+                        tenant,
                         connection,
                     )
                     .collect(),
@@ -396,12 +404,15 @@ impl AnalysisService {
         let query = query.into();
         let options = options.into();
 
-        let graphs = self.load_graphs(connection, &distinct_sbom_ids).await?;
+        let graphs = self
+            .load_graphs(connection, &distinct_sbom_ids, None)
+            .await?;
         let components = self
             .run_graph_query(
                 query,
                 options,
                 &graphs,
+                None,
                 connection,
                 self.graph_cache.clone(),
             )
@@ -417,18 +428,20 @@ impl AnalysisService {
         query: impl Into<GraphQuery<'_>> + Debug,
         options: impl Into<QueryOptions> + Debug,
         paginated: Paginated,
+        tenant: Option<&str>,
         connection: &C,
     ) -> Result<PaginatedResults<Node>, Error> {
         let query = query.into();
         let options = options.into();
 
-        let graphs = self.load_graphs_query(connection, query).await?;
+        let graphs = self.load_graphs_query(connection, query, tenant).await?;
 
         let components = self
             .run_graph_query(
                 query,
                 options,
                 &graphs,
+                tenant,
                 connection,
                 self.graph_cache.clone(),
             )
@@ -443,18 +456,20 @@ impl AnalysisService {
         query: impl Into<GraphQuery<'_>> + Debug,
         options: impl Into<QueryOptions> + Debug,
         paginated: Paginated,
+        tenant: Option<&str>,
         connection: &C,
     ) -> Result<PaginatedResults<Node>, Error> {
         let query = query.into();
         let options = options.into();
 
-        let graphs = self.load_graphs_query(connection, query).await?;
+        let graphs = self.load_graphs_query(connection, query, tenant).await?;
 
         let components = self
             .run_graph_query(
                 query,
                 options,
                 &graphs,
+                tenant,
                 connection,
                 self.graph_cache.clone(),
             )
@@ -666,6 +681,33 @@ impl AnalysisService {
     }
 }
 
+/// A condition limiting SBOMs to the ones of a tenant, all of them without a tenant.
+fn tenant_condition(tenant: Option<&str>) -> Condition {
+    match tenant {
+        None => Condition::all(),
+        Some(tenant) => {
+            LabelFilter::equal(TENANT_LABEL, tenant).into_condition(sbom::Column::Labels)
+        }
+    }
+}
+
+/// Check if an SBOM is visible to a tenant. Without a tenant, all SBOMs are.
+pub(crate) async fn visible<C: ConnectionTrait>(
+    tenant: Option<&str>,
+    sbom_id: Uuid,
+    connection: &C,
+) -> Result<bool, Error> {
+    if tenant.is_none() {
+        return Ok(true);
+    }
+
+    Ok(sbom::Entity::find_by_id(sbom_id)
+        .filter(tenant_condition(tenant))
+        .count(connection)
+        .await?
+        > 0)
+}
+
 fn acyclic(id: &str, graph: &Arc<PackageGraph>) -> bool {
     use petgraph::visit::{DfsEvent, depth_first_search};
     let g = graph.as_ref();
@@ -28,6 +28,7 @@ async fn test_simple_analysis_service(ctx: &TrustifyContext) -> Result<(), anyho
             &Query::q("DD"),
             QueryOptions::ancestors(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -60,6 +61,7 @@ async fn test_simple_analysis_service(ctx: &TrustifyContext) -> Result<(), anyho
             &Query::q("EE"),
             QueryOptions::ancestors(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -88,6 +90,7 @@ async fn test_simple_analysis_cyclonedx_service(
             &Query::q("DD"),
             QueryOptions::ancestors(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -121,6 +124,7 @@ async fn test_simple_analysis_cyclonedx_service(
             &Query::q("EE"),
             QueryOptions::ancestors(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -146,6 +150,7 @@ async fn test_simple_by_name_analysis_service(ctx: &TrustifyContext) -> Result<(
             ComponentReference::Name("B"),
             QueryOptions::ancestors(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -198,6 +203,7 @@ async fn simple_by_name_analysis_service_filter_rel(
                 ..QueryOptions::ancestors()
             },
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -238,6 +244,7 @@ async fn test_simple_by_purl_analysis_service(ctx: &TrustifyContext) -> Result<(
             &component_purl,
             QueryOptions::ancestors(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -288,6 +295,7 @@ async fn test_quarkus_analysis_service(ctx: &TrustifyContext) -> Result<(), anyh
             &Query::q("spymemcached"),
             QueryOptions::ancestors(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -330,6 +338,38 @@ async fn test_quarkus_analysis_service(ctx: &TrustifyContext) -> Result<(), anyh
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn other_tenants(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    ctx.ingest_documents(["spdx/simple.json"]).await?;
+    ctx.db
+        .execute_unprepared(r#"UPDATE sbom SET labels = labels || '{"tenant": "a"}'"#)
+        .await?;
+
+    let service = AnalysisService::new(AnalysisConfig::default());
+
+    for (tenant, expected) in [(Some("a"), 1), (Some("b"), 0), (None, 1)] {
+        let analysis_graph = service
+            .retrieve(
+                ComponentReference::Name("B"),
+                QueryOptions::ancestors(),
+                Paginated::default(),
+                tenant,
+                &ctx.db,
+            )
+            .await?;
+        assert_eq!(analysis_graph.total, expected, "tenant: {tenant:?}");
+
+        let analysis_status = service.status(tenant, &ctx.db).await?;
+        assert_eq!(
+            analysis_status.sbom_count as u64, expected,
+            "tenant: {tenant:?}"
+        );
+    }
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(tokio::test)]
 async fn test_status_service(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
@@ -339,7 +379,7 @@ async fn test_status_service(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
     let all_graphs = service.load_all_graphs(&ctx.db).await?;
     assert_eq!(all_graphs.len(), 1);
 
-    let analysis_status = service.status(&ctx.db).await?;
+    let analysis_status = service.status(None, &ctx.db).await?;
     assert_eq!(analysis_status.sbom_count, 1);
     assert_eq!(analysis_status.graph_count, 1);
 
@@ -351,7 +391,7 @@ async fn test_status_service(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
     ])
     .await?;
 
-    let analysis_status = service.status(&ctx.db).await?;
+    let analysis_status = service.status(None, &ctx.db).await?;
 
     assert_eq!(analysis_status.sbom_count, 3);
     assert_eq!(analysis_status.graph_count, 0);
@@ -412,6 +452,7 @@ async fn test_simple_deps_service(ctx: &TrustifyContext) -> Result<(), anyhow::E
             &Query::q("AA"),
             QueryOptions::descendants(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -424,6 +465,7 @@ async fn test_simple_deps_service(ctx: &TrustifyContext) -> Result<(), anyhow::E
             &Query::q("EE"),
             QueryOptions::ancestors(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -449,6 +491,7 @@ async fn test_simple_deps_cyclonedx_service(ctx: &TrustifyContext) -> Result<(),
             &Query::q("AA"),
             QueryOptions::descendants(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -461,6 +504,7 @@ async fn test_simple_deps_cyclonedx_service(ctx: &TrustifyContext) -> Result<(),
             &Query::q("EE"),
             QueryOptions::ancestors(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?
@@ -482,6 +526,7 @@ async fn test_simple_by_name_deps_service(ctx: &TrustifyContext) -> Result<(), a
             ComponentReference::Name("A"),
             QueryOptions::descendants(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -516,6 +561,7 @@ async fn test_simple_by_purl_deps_service(ctx: &TrustifyContext) -> Result<(), a
             &component_purl,
             QueryOptions::descendants(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -546,6 +592,7 @@ async fn test_quarkus_deps_service(ctx: &TrustifyContext) -> Result<(), anyhow::
             &Query::q("spymemcached"),
             QueryOptions::descendants(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -568,6 +615,7 @@ async fn test_circular_deps_cyclonedx_service(ctx: &TrustifyContext) -> Result<(
             ComponentReference::Name("junit-bom"),
             QueryOptions::descendants(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -590,6 +638,7 @@ async fn test_circular_deps_spdx_service(ctx: &TrustifyContext) -> Result<(), an
             ComponentReference::Name("A"),
             QueryOptions::descendants(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
@@ -614,6 +663,7 @@ async fn test_retrieve_all_sbom_roots_by_name(ctx: &TrustifyContext) -> Result<(
             &Query::q(&component_name),
             QueryOptions::ancestors(),
             Paginated::default(),
+            None,
             &ctx.db,
         )
         .await?;
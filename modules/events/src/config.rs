@@ -0,0 +1,88 @@
+use std::time::Duration;
+use trustify_module_settings::model::Settings;
+
+/// The kind of broker, receiving the events.
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Broker {
+    Kafka,
+    Nats,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+#[command(next_help_heading = "Events")]
+pub struct EventsConfig {
+    /// The broker to publish events about ingested and deleted documents to. Disabled by default.
+    #[arg(id = "events-broker", long, env = "TRUSTD_EVENTS_BROKER")]
+    pub broker: Option<Broker>,
+
+    /// The servers of the broker: the bootstrap servers for Kafka, or the server URLs for NATS.
+    #[arg(
+        id = "events-servers",
+        long,
+        env = "TRUSTD_EVENTS_SERVERS",
+        value_delimiter = ','
+    )]
+    pub servers: Vec<String>,
+
+    /// The Kafka topic, or the NATS subject, to publish the events to.
+    #[arg(
+        id = "events-topic",
+        long,
+        env = "TRUSTD_EVENTS_TOPIC",
+        default_value = "trustify.documents"
+    )]
+    pub topic: String,
+
+    /// The interval of checking for new events, when there was nothing to publish.
+    #[arg(
+        id = "events-interval",
+        long,
+        env = "TRUSTD_EVENTS_INTERVAL",
+        default_value = "1s"
+    )]
+    pub interval: humantime::Duration,
+
+    /// The maximum number of events, published at once.
+    #[arg(
+        id = "events-batch-size",
+        long,
+        env = "TRUSTD_EVENTS_BATCH_SIZE",
+        default_value_t = 100
+    )]
+    pub batch_size: u64,
+
+    /// The time after which events, which could not be published, get dropped.
+    #[arg(
+        id = "events-retention",
+        long,
+        env = "TRUSTD_EVENTS_RETENTION",
+        default_value = "7d"
+    )]
+    pub retention: humantime::Duration,
+}
+
+impl Default for EventsConfig {
+    fn default() -> Self {
+        Self {
+            broker: None,
+            servers: vec![],
+            topic: "trustify.documents".into(),
+            interval: Duration::from_secs(1).into(),
+            batch_size: 100,
+            retention: Duration::from_secs(7 * 24 * 60 * 60).into(),
+        }
+    }
+}
+
+impl EventsConfig {
+    /// The configuration, with the runtime settings applied.
+    pub fn apply(&self, settings: &Settings) -> Self {
+        Self {
+            retention: settings
+                .retention_days
+                .map(|days| Duration::from_secs(u64::from(days) * 24 * 60 * 60).into())
+                .unwrap_or(self.retention),
+            ..self.clone()
+        }
+    }
+}
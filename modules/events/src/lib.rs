@@ -0,0 +1,11 @@
+//! Publishing events about ingested and deleted documents to a message broker.
+//!
+//! The database records an event in the outbox, in the same transaction as ingesting or deleting
+//! the document. A background relay then publishes the events to a Kafka topic or a NATS subject,
+//! and removes them from the outbox once the broker confirmed receiving them. This way, no event
+//! gets lost when the process gets terminated.
+
+pub mod config;
+pub mod model;
+pub mod publisher;
+pub mod service;
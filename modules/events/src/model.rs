@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::labels::Labels;
+use uuid::Uuid;
+
+pub use trustify_entity::download_audit::DocumentKind;
+
+/// An event about an ingested or deleted document, as published to the broker
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    /// The time of the change
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    #[serde(rename = "type")]
+    pub kind: DocumentKind,
+    /// The ID of the SBOM or advisory
+    pub id: Uuid,
+    pub event: EventKind,
+    /// The labels of the document at the time of the change
+    pub labels: Labels,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// The document got ingested, or restored after being soft deleted
+    Ingested,
+    /// The document got deleted
+    Deleted,
+}
+
+/// A message, ready to be sent to the broker
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    /// The ID of the event in the outbox, stable across attempts of publishing it
+    pub id: i64,
+    /// The key, the ID of the document
+    pub key: String,
+    /// The event, serialized as JSON
+    pub payload: Vec<u8>,
+}
@@ -0,0 +1,51 @@
+use super::Publisher;
+use crate::model::Message;
+use async_trait::async_trait;
+use rdkafka::{
+    ClientConfig,
+    producer::{FutureProducer, FutureRecord},
+    util::Timeout,
+};
+use std::time::Duration;
+
+/// The time to wait for the broker to acknowledge a message.
+const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Publish events to a Kafka topic, using the ID of the document as key.
+pub struct KafkaPublisher {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl KafkaPublisher {
+    pub fn new(servers: &[String], topic: &str) -> anyhow::Result<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", servers.join(","))
+            // wait for all in-sync replicas, and don't duplicate messages on retries
+            .set("acks", "all")
+            .set("enable.idempotence", "true")
+            .create()?;
+
+        Ok(Self {
+            producer,
+            topic: topic.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl Publisher for KafkaPublisher {
+    async fn publish(&self, message: &Message) -> anyhow::Result<()> {
+        self.producer
+            .send(
+                FutureRecord::to(&self.topic)
+                    .key(&message.key)
+                    .payload(&message.payload),
+                Timeout::After(SEND_TIMEOUT),
+            )
+            .await
+            .map_err(|(err, _)| err)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+//! Publishing events to a broker.
+
+#[cfg(feature = "kafka")]
+mod kafka;
+#[cfg(feature = "nats")]
+mod nats;
+
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaPublisher;
+#[cfg(feature = "nats")]
+pub use nats::NatsPublisher;
+
+use crate::{
+    config::{Broker, EventsConfig},
+    model::Message,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// A broker, receiving the events.
+#[async_trait]
+pub trait Publisher: Send + Sync {
+    /// Publish a message, returning once the broker confirmed receiving it.
+    ///
+    /// The same message might be published more than once, if the relay got interrupted before
+    /// recording that it got published.
+    async fn publish(&self, message: &Message) -> anyhow::Result<()>;
+}
+
+/// Create the publisher of the configured broker, `None` if publishing events is disabled.
+pub async fn from_config(config: &EventsConfig) -> anyhow::Result<Option<Arc<dyn Publisher>>> {
+    let Some(broker) = config.broker else {
+        return Ok(None);
+    };
+
+    if config.servers.is_empty() {
+        anyhow::bail!("Publishing events is enabled, but no servers are configured");
+    }
+
+    Ok(Some(match broker {
+        #[cfg(feature = "kafka")]
+        Broker::Kafka => Arc::new(KafkaPublisher::new(&config.servers, &config.topic)?),
+        #[cfg(not(feature = "kafka"))]
+        Broker::Kafka => anyhow::bail!("Support for Kafka is not enabled in this build"),
+        #[cfg(feature = "nats")]
+        Broker::Nats => Arc::new(NatsPublisher::connect(&config.servers, &config.topic).await?),
+        #[cfg(not(feature = "nats"))]
+        Broker::Nats => anyhow::bail!("Support for NATS is not enabled in this build"),
+    }))
+}
@@ -0,0 +1,44 @@
+use super::Publisher;
+use crate::model::Message;
+use async_nats::{HeaderMap, jetstream};
+use async_trait::async_trait;
+
+/// Publish events to a NATS JetStream subject.
+///
+/// The ID of the event is sent as message ID, allowing the stream to drop duplicates.
+pub struct NatsPublisher {
+    context: jetstream::Context,
+    subject: String,
+}
+
+impl NatsPublisher {
+    pub async fn connect(servers: &[String], subject: &str) -> anyhow::Result<Self> {
+        let client = async_nats::connect(servers.join(",").as_str()).await?;
+
+        Ok(Self {
+            context: jetstream::new(client),
+            subject: subject.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl Publisher for NatsPublisher {
+    async fn publish(&self, message: &Message) -> anyhow::Result<()> {
+        let mut headers = HeaderMap::new();
+        headers.insert("Nats-Msg-Id", message.id.to_string().as_str());
+        headers.insert("Trustify-Key", message.key.as_str());
+
+        // the first future sends the message, the second one waits for the acknowledgement
+        self.context
+            .publish_with_headers(
+                self.subject.clone(),
+                headers,
+                message.payload.clone().into(),
+            )
+            .await?
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{config::EventsConfig, model::Message, publisher::Publisher};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseTransaction, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Statement, TransactionTrait,
+    sea_query::{Expr, LockType},
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use time::OffsetDateTime;
+use trustify_common::db::Database;
+use trustify_entity::event_outbox;
+use trustify_module_settings::service::SettingsService;
+
+/// The interval of dropping expired events.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("failed to publish event {id}: {err}")]
+    Publish { id: i64, err: anyhow::Error },
+}
+
+/// Publishes the events of the outbox, recorded by the database along with the changes of
+/// documents.
+///
+/// Events are published in the order they got recorded, and only removed from the outbox once the
+/// broker confirmed receiving them. This means that an event might be published more than once,
+/// but none gets lost. Only one instance publishes events at a time.
+pub struct EventRelay {
+    db: Database,
+    publisher: Option<Arc<dyn Publisher>>,
+    config: EventsConfig,
+    settings: SettingsService,
+}
+
+impl EventRelay {
+    /// Create a new relay. Without a publisher, expired events only get dropped.
+    pub fn new(db: Database, publisher: Option<Arc<dyn Publisher>>, config: EventsConfig) -> Self {
+        Self {
+            settings: SettingsService::new(db.clone()),
+            db,
+            publisher,
+            config,
+        }
+    }
+
+    /// Publish the next batch of events, returns the number of published events.
+    pub async fn next(&self) -> Result<usize, Error> {
+        let Some(publisher) = &self.publisher else {
+            return Ok(0);
+        };
+
+        let tx = self.db.begin().await?;
+
+        if !Self::lock(&tx).await? {
+            // another instance is publishing
+            return Ok(0);
+        }
+
+        let events = event_outbox::Entity::find()
+            .order_by_asc(event_outbox::Column::Id)
+            .limit(self.config.batch_size)
+            .lock(LockType::Update)
+            .all(&tx)
+            .await?;
+
+        let mut published = vec![];
+        let mut failure = None;
+
+        for event in events {
+            let message = Message {
+                id: event.id,
+                key: event.key,
+                payload: serde_json::to_vec(&event.payload)?,
+            };
+
+            if let Err(err) = publisher.publish(&message).await {
+                // keep the order, by not publishing any later event
+                event_outbox::Entity::update_many()
+                    .col_expr(
+                        event_outbox::Column::Attempts,
+                        Expr::col(event_outbox::Column::Attempts).add(1),
+                    )
+                    .col_expr(
+                        event_outbox::Column::LastError,
+                        Expr::value(err.to_string()),
+                    )
+                    .filter(event_outbox::Column::Id.eq(event.id))
+                    .exec(&tx)
+                    .await?;
+
+                failure = Some(Error::Publish { id: event.id, err });
+                break;
+            }
+
+            published.push(event.id);
+        }
+
+        event_outbox::Entity::delete_many()
+            .filter(event_outbox::Column::Id.is_in(published.clone()))
+            .exec(&tx)
+            .await?;
+
+        tx.commit().await?;
+
+        match failure {
+            Some(err) => Err(err),
+            None => Ok(published.len()),
+        }
+    }
+
+    /// Drop events older than the retention period, returns the number of dropped events.
+    ///
+    /// The retention period can be changed at runtime, using the settings.
+    pub async fn prune(&self) -> Result<u64, Error> {
+        let config = self.config.apply(&self.settings.current().await?);
+        let expired = OffsetDateTime::now_utc() - *config.retention;
+
+        let result = event_outbox::Entity::delete_many()
+            .filter(event_outbox::Column::Created.lt(expired))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// Keep publishing events, never returns.
+    pub async fn run(self) -> anyhow::Result<()> {
+        match (&self.publisher, self.config.broker) {
+            (Some(_), Some(broker)) => log::info!(
+                "Publishing events to {broker:?}, using topic '{}'",
+                self.config.topic
+            ),
+            _ => log::info!("Publishing events is disabled"),
+        }
+
+        let mut pruned: Option<Instant> = None;
+
+        loop {
+            if pruned.is_none_or(|pruned| pruned.elapsed() >= PRUNE_INTERVAL) {
+                match self.prune().await {
+                    Ok(0) => {}
+                    Ok(dropped) => log::warn!("Dropped {dropped} expired event(s)"),
+                    Err(err) => log::warn!("Failed to drop expired events: {err}"),
+                }
+                pruned = Some(Instant::now());
+            }
+
+            match self.next().await {
+                // there might be more
+                Ok(published) if published > 0 && published as u64 >= self.config.batch_size => {
+                    continue;
+                }
+                Ok(published) => {
+                    if published > 0 {
+                        log::debug!("Published {published} event(s)");
+                    }
+                }
+                Err(err) => log::warn!("Failed to publish events: {err}"),
+            }
+
+            tokio::time::sleep(*self.config.interval).await;
+        }
+    }
+
+    /// Acquire the lock for publishing, for the duration of the transaction.
+    async fn lock(tx: &DatabaseTransaction) -> Result<bool, Error> {
+        let row = tx
+            .query_one(Statement::from_string(
+                tx.get_database_backend(),
+                "SELECT pg_try_advisory_xact_lock(hashtext('event_outbox')) AS locked",
+            ))
+            .await?;
+
+        Ok(match row {
+            Some(row) => row.try_get("", "locked")?,
+            None => false,
+        })
+    }
+}
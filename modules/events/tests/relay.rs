@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use sea_orm::{EntityTrait, PaginatorTrait, sea_query::Expr};
+use std::sync::Arc;
+use test_context::test_context;
+use test_log::test;
+use time::{Duration, OffsetDateTime};
+use trustify_common::id::Id;
+use trustify_entity::{advisory, event_outbox};
+use trustify_module_events::{
+    model::{DocumentKind, Event, EventKind, Message},
+    publisher::Publisher,
+    service::EventRelay,
+};
+use trustify_module_settings::{model::Settings, service::SettingsService};
+use trustify_test_context::TrustifyContext;
+
+/// Records the published messages, or fails if requested.
+#[derive(Default)]
+struct Recorder {
+    messages: Mutex<Vec<Message>>,
+    fail: Mutex<bool>,
+}
+
+#[async_trait]
+impl Publisher for Recorder {
+    async fn publish(&self, message: &Message) -> anyhow::Result<()> {
+        if *self.fail.lock() {
+            anyhow::bail!("broker unavailable");
+        }
+        self.messages.lock().push(message.clone());
+        Ok(())
+    }
+}
+
+impl Recorder {
+    fn events(&self) -> anyhow::Result<Vec<(DocumentKind, EventKind, Id)>> {
+        self.messages
+            .lock()
+            .iter()
+            .map(|message| {
+                let event: Event = serde_json::from_slice(&message.payload)?;
+                assert_eq!(message.key, event.id.to_string());
+                Ok((event.kind, event.event, Id::Uuid(event.id)))
+            })
+            .collect()
+    }
+}
+
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn publish_in_order(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let sbom = ctx
+        .ingest_document("zookeeper-3.9.2-cyclonedx.json")
+        .await?
+        .id;
+    let advisory = ctx.ingest_document("csaf/cve-2023-0044.json").await?.id;
+
+    let uuid = advisory.try_as_uid().expect("must be a UUID");
+    advisory::Entity::delete_by_id(uuid).exec(&ctx.db).await?;
+
+    let recorder = Arc::new(Recorder::default());
+    let relay = EventRelay::new(ctx.db.clone(), Some(recorder.clone()), Default::default());
+
+    assert_eq!(relay.next().await?, 3);
+    assert_eq!(
+        recorder.events()?,
+        [
+            (DocumentKind::Sbom, EventKind::Ingested, sbom),
+            (
+                DocumentKind::Advisory,
+                EventKind::Ingested,
+                advisory.clone()
+            ),
+            (DocumentKind::Advisory, EventKind::Deleted, advisory),
+        ]
+    );
+
+    // nothing left to publish
+
+    assert_eq!(relay.next().await?, 0);
+    assert_eq!(event_outbox::Entity::find().count(&ctx.db).await?, 0);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn keep_unpublished(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let sbom = ctx
+        .ingest_document("zookeeper-3.9.2-cyclonedx.json")
+        .await?
+        .id;
+
+    let recorder = Arc::new(Recorder::default());
+    *recorder.fail.lock() = true;
+    let relay = EventRelay::new(ctx.db.clone(), Some(recorder.clone()), Default::default());
+
+    assert!(relay.next().await.is_err());
+
+    let events = event_outbox::Entity::find().all(&ctx.db).await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].attempts, 1);
+    assert_eq!(events[0].last_error.as_deref(), Some("broker unavailable"));
+
+    // the broker is back
+
+    *recorder.fail.lock() = false;
+    assert_eq!(relay.next().await?, 1);
+    assert_eq!(
+        recorder.events()?,
+        [(DocumentKind::Sbom, EventKind::Ingested, sbom)]
+    );
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn prune_by_retention(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_document("zookeeper-3.9.2-cyclonedx.json")
+        .await?;
+
+    event_outbox::Entity::update_many()
+        .col_expr(
+            event_outbox::Column::Created,
+            Expr::value(OffsetDateTime::now_utc() - Duration::days(3)),
+        )
+        .exec(&ctx.db)
+        .await?;
+
+    // kept, as long as the configured retention applies
+
+    let relay = EventRelay::new(ctx.db.clone(), None, Default::default());
+    assert_eq!(relay.prune().await?, 0);
+
+    // dropped, once the settings shorten the retention
+
+    SettingsService::new(ctx.db.clone())
+        .set(
+            Settings {
+                retention_days: Some(2),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .await?;
+    assert_eq!(relay.prune().await?, 1);
+    assert_eq!(event_outbox::Entity::find().count(&ctx.db).await?, 0);
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    admin::{
+        model::{Rebuild, RebuildRequest},
+        service::RebuildService,
+    },
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, get, http::header, post, web};
+use trustify_auth::{ReadSettings, UpdateSettings, authorizer::Require};
+use trustify_common::{
+    db::Database,
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults},
+};
+use trustify_module_ingestor::service::IngestorService;
+use uuid::Uuid;
+
+pub fn configure(
+    config: &mut utoipa_actix_web::service_config::ServiceConfig,
+    db: Database,
+    ingestor: IngestorService,
+) {
+    let service = RebuildService::new(db, ingestor);
+
+    config
+        .app_data(web::Data::new(service))
+        .service(rebuild)
+        .service(list_rebuilds)
+        .service(get_rebuild);
+}
+
+#[utoipa::path(
+    tag = "admin",
+    operation_id = "rebuild",
+    request_body = RebuildRequest,
+    responses(
+        (status = 202, description = "The rebuild was started", body = Rebuild),
+    ),
+)]
+#[post("/v2/admin/rebuild")]
+/// Rebuild derived data, like after a migration, in the background
+pub async fn rebuild(
+    service: web::Data<RebuildService>,
+    web::Json(request): web::Json<RebuildRequest>,
+    _: Require<UpdateSettings>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let result = service.start(request.tasks, &tenant).await?;
+    Ok(HttpResponse::Accepted()
+        .insert_header((
+            header::LOCATION,
+            format!("/api/v2/admin/rebuild/{}", result.id),
+        ))
+        .json(result))
+}
+
+#[utoipa::path(
+    tag = "admin",
+    operation_id = "listRebuilds",
+    params(
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Rebuilds, most recently started first", body = PaginatedResults<Rebuild>),
+    ),
+)]
+#[get("/v2/admin/rebuild")]
+/// List the rebuilds of derived data
+pub async fn list_rebuilds(
+    service: web::Data<RebuildService>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadSettings>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(service.list(paginated, &tenant).await?))
+}
+
+#[utoipa::path(
+    tag = "admin",
+    operation_id = "getRebuild",
+    params(
+        ("id" = Uuid, Path, description = "The ID of the rebuild"),
+    ),
+    responses(
+        (status = 200, description = "The state of the rebuild", body = Rebuild),
+        (status = 404, description = "The rebuild could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/admin/rebuild/{id}")]
+/// Get the state of a rebuild of derived data
+pub async fn get_rebuild(
+    service: web::Data<RebuildService>,
+    id: web::Path<Uuid>,
+    _: Require<ReadSettings>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(match service.fetch(id.into_inner(), &tenant).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
@@ -0,0 +1,145 @@
+use crate::{
+    admin::model::{Rebuild, RebuildState, RebuildTask},
+    test::{caller, caller_with, tenant_isolation, tenant_user},
+};
+use actix_http::StatusCode;
+use actix_web::test::TestRequest;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use serde_json::json;
+use std::time::Duration;
+use test_context::test_context;
+use test_log::test;
+use trustify_common::model::PaginatedResults;
+use trustify_entity::{cvss3, document_identity, qualified_purl};
+use trustify_test_context::{TrustifyContext, auth::TestAuthentication, call::CallService};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn rebuild(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_documents(["zookeeper-3.9.2-cyclonedx.json", "csaf/cve-2023-0044.json"])
+        .await?;
+    let app = caller(ctx).await?;
+
+    // break the derived data
+
+    ctx.db
+        .execute_unprepared("UPDATE cvss3 SET severity = 'none'")
+        .await?;
+    ctx.db
+        .execute_unprepared("UPDATE qualified_purl SET purl = NULL")
+        .await?;
+    ctx.db
+        .execute_unprepared("DELETE FROM document_identity")
+        .await?;
+
+    // rebuilding all of it
+
+    let request = TestRequest::post()
+        .uri("/api/v2/admin/rebuild")
+        .set_json(json!({}))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let result: Rebuild = actix_web::test::read_body_json(response).await;
+    assert_eq!(result.tasks, RebuildTask::ALL);
+
+    let mut result = result;
+    for _ in 0..100 {
+        if result.state != RebuildState::Running {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let request = TestRequest::get()
+            .uri(&format!("/api/v2/admin/rebuild/{}", result.id))
+            .to_request();
+        result = app.call_and_read_body_json(request).await;
+    }
+
+    assert_eq!(result.state, RebuildState::Succeeded);
+    assert_eq!(result.completed, RebuildTask::ALL);
+    assert!(result.finished.is_some());
+    assert_eq!(result.error, None);
+
+    // the derived data is back
+
+    assert!(qualified_purl::Entity::find().count(&ctx.db).await? > 0);
+    assert_eq!(
+        cvss3::Entity::find()
+            .filter(cvss3::Column::Severity.eq(cvss3::Severity::None))
+            .count(&ctx.db)
+            .await?,
+        0
+    );
+    assert_eq!(
+        qualified_purl::Entity::find()
+            .filter(qualified_purl::Column::Purl.is_null())
+            .count(&ctx.db)
+            .await?,
+        0
+    );
+    // the SBOM doesn't carry an identity, lacking a serial number
+    assert_eq!(document_identity::Entity::find().count(&ctx.db).await?, 1);
+
+    // the rebuild is listed
+
+    let request = TestRequest::get().uri("/api/v2/admin/rebuild").to_request();
+    let list: PaginatedResults<Rebuild> = app.call_and_read_body_json(request).await;
+    assert_eq!(list.total, 1);
+    assert_eq!(list.items[0].id, result.id);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn rebuild_unknown(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/admin/rebuild/{}", uuid::Uuid::now_v7()))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn rebuild_other_tenants(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_with(ctx, tenant_isolation()).await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/admin/rebuild")
+        .set_json(json!({"tasks": ["deprecation"]}))
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let result: Rebuild = actix_web::test::read_body_json(response).await;
+
+    // tenant b doesn't see the rebuild of tenant a
+
+    let request = TestRequest::get()
+        .uri("/api/v2/admin/rebuild")
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let list: PaginatedResults<Rebuild> = app.call_and_read_body_json(request).await;
+    assert_eq!(list.total, 0);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/admin/rebuild/{}", result.id))
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/admin/rebuild/{}", result.id))
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    Ok(())
+}
@@ -0,0 +1,5 @@
+pub mod endpoints;
+
+pub mod model;
+
+pub mod service;
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use time::OffsetDateTime;
+use trustify_entity::rebuild;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Derived data, which can be rebuilt from the ingested documents.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    strum::EnumString,
+    strum::Display,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum RebuildTask {
+    /// The deprecation of advisories, which are superseded by a more recent one with the same
+    /// identifier.
+    Deprecation,
+    /// The severities of CVSS scores.
+    Severity,
+    /// The coordinates of PURLs, denormalized for searching.
+    Purls,
+    /// The summary statistics.
+    Stats,
+    /// The canonical identities of documents, which were ingested before identities were
+    /// tracked.
+    Identities,
+}
+
+impl RebuildTask {
+    /// All tasks, in the order they should run.
+    pub const ALL: [Self; 5] = [
+        Self::Deprecation,
+        Self::Severity,
+        Self::Purls,
+        Self::Stats,
+        Self::Identities,
+    ];
+}
+
+/// A request for rebuilding derived data.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RebuildRequest {
+    /// The tasks to run, in order. Defaults to all tasks.
+    #[serde(default)]
+    pub tasks: Vec<RebuildTask>,
+}
+
+/// The state of rebuilding derived data, running in the background.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Rebuild {
+    /// The ID of the rebuild
+    pub id: Uuid,
+    /// The current state of the rebuild
+    pub state: RebuildState,
+    /// The tasks to run, in order
+    pub tasks: Vec<RebuildTask>,
+    /// The tasks which already completed
+    pub completed: Vec<RebuildTask>,
+    /// The time the rebuild started
+    #[serde(with = "time::serde::rfc3339")]
+    pub started: OffsetDateTime,
+    /// The time the rebuild finished
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub finished: Option<OffsetDateTime>,
+    /// The error of the rebuild, if it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The state of a rebuild
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RebuildState {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl From<rebuild::State> for RebuildState {
+    fn from(value: rebuild::State) -> Self {
+        match value {
+            rebuild::State::Running => Self::Running,
+            rebuild::State::Succeeded => Self::Succeeded,
+            rebuild::State::Failed => Self::Failed,
+        }
+    }
+}
+
+impl From<rebuild::Model> for Rebuild {
+    fn from(value: rebuild::Model) -> Self {
+        // the tasks were stored by us, skip those a newer version might have added
+        let tasks = |tasks: Vec<String>| {
+            tasks
+                .iter()
+                .filter_map(|task| RebuildTask::from_str(task).ok())
+                .collect()
+        };
+
+        Self {
+            id: value.id,
+            state: value.state.into(),
+            tasks: tasks(value.tasks),
+            completed: tasks(value.completed),
+            started: value.started,
+            finished: value.finished,
+            error: value.error,
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{
+    Error,
+    admin::model::{Rebuild, RebuildTask},
+    tenant::Tenant,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+use sea_query::Expr;
+use time::OffsetDateTime;
+use tracing::instrument;
+use trustify_common::{
+    db::{Database, limiter::LimiterTrait},
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::rebuild;
+use trustify_module_ingestor::service::IngestorService;
+use uuid::Uuid;
+
+/// Rebuilds derived data, like the denormalized columns of a table, which might be outdated after
+/// a migration.
+#[derive(Clone)]
+pub struct RebuildService {
+    db: Database,
+    ingestor: IngestorService,
+}
+
+impl RebuildService {
+    pub fn new(db: Database, ingestor: IngestorService) -> Self {
+        Self { db, ingestor }
+    }
+
+    /// Start rebuilding in the background, returning its initial state.
+    ///
+    /// Without any tasks, all tasks are run. Each task runs in its own transaction, so that
+    /// completed tasks are kept if a later one fails.
+    #[instrument(skip(self), err(level=tracing::Level::INFO))]
+    pub async fn start(&self, tasks: Vec<RebuildTask>, tenant: &Tenant) -> Result<Rebuild, Error> {
+        let tasks = match tasks.is_empty() {
+            true => RebuildTask::ALL.to_vec(),
+            false => tasks,
+        };
+
+        let model = rebuild::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            state: Set(rebuild::State::Running),
+            tasks: Set(tasks.iter().map(ToString::to_string).collect()),
+            completed: Set(vec![]),
+            tenant: Set(tenant.name().map(ToString::to_string)),
+            started: Set(OffsetDateTime::now_utc()),
+            finished: Set(None),
+            error: Set(None),
+        }
+        .insert(&self.db)
+        .await?;
+
+        log::info!("Starting rebuild {}: {tasks:?}", model.id);
+
+        let service = self.clone();
+        let id = model.id;
+        tokio::spawn(async move {
+            let result = service.run(id, &tasks).await;
+            if let Err(err) = service.complete(id, result).await {
+                log::warn!("Failed to record the outcome of rebuild {id}: {err}");
+            }
+        });
+
+        Ok(model.into())
+    }
+
+    /// Get the state of a rebuild, `None` if there is none with this ID.
+    pub async fn fetch(&self, id: Uuid, tenant: &Tenant) -> Result<Option<Rebuild>, Error> {
+        Ok(rebuild::Entity::find_by_id(id)
+            .filter(tenant.column_condition(rebuild::Column::Tenant))
+            .one(&self.db)
+            .await?
+            .map(Rebuild::from))
+    }
+
+    /// List the rebuilds started by the tenant, most recently started first.
+    pub async fn list(
+        &self,
+        paginated: Paginated,
+        tenant: &Tenant,
+    ) -> Result<PaginatedResults<Rebuild>, Error> {
+        let limiter = rebuild::Entity::find()
+            .filter(tenant.column_condition(rebuild::Column::Tenant))
+            .order_by_desc(rebuild::Column::Started)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        Ok(PaginatedResults {
+            total: limiter.total().await?,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(Rebuild::from)
+                .collect(),
+        })
+    }
+
+    /// Run the tasks, in order, recording each completed one.
+    #[instrument(skip(self), err)]
+    async fn run(&self, id: Uuid, tasks: &[RebuildTask]) -> Result<(), Error> {
+        for task in tasks {
+            let tx = self.db.begin().await?;
+            let rows = match task {
+                // reads the documents from the storage, storing each identity on its own
+                RebuildTask::Identities => self.ingestor.backfill_identities().await?,
+                task => rebuild(*task, &tx).await?,
+            };
+
+            rebuild::Entity::update_many()
+                .filter(rebuild::Column::Id.eq(id))
+                .col_expr(
+                    rebuild::Column::Completed,
+                    Expr::cust_with_values(r#"array_append("completed", $1)"#, [task.to_string()]),
+                )
+                .exec(&tx)
+                .await?;
+
+            tx.commit().await?;
+
+            log::info!("Rebuild {id}: {task} completed, updating {rows} rows");
+        }
+
+        Ok(())
+    }
+
+    /// Record the outcome of a rebuild.
+    async fn complete(&self, id: Uuid, result: Result<(), Error>) -> Result<(), Error> {
+        let (state, error) = match result {
+            Ok(()) => (rebuild::State::Succeeded, None),
+            Err(err) => (rebuild::State::Failed, Some(err.to_string())),
+        };
+
+        rebuild::ActiveModel {
+            id: Set(id),
+            state: Set(state),
+            finished: Set(Some(OffsetDateTime::now_utc())),
+            error: Set(error),
+            ..Default::default()
+        }
+        .update(&self.db)
+        .await?;
+
+        log::info!("Completed rebuild {id}: {state:?}");
+
+        Ok(())
+    }
+}
+
+/// Rebuild the data of a task, returning the number of updated rows.
+async fn rebuild<C: ConnectionTrait>(task: RebuildTask, connection: &C) -> Result<u64, Error> {
+    let sql = match task {
+        // without an identifier, the function evaluates all advisories
+        RebuildTask::Deprecation => "SELECT update_deprecated_advisory()",
+        RebuildTask::Severity => include_str!("severity.sql"),
+        RebuildTask::Purls => include_str!("purls.sql"),
+        RebuildTask::Stats => r#"REFRESH MATERIALIZED VIEW CONCURRENTLY "summary_stats""#,
+        RebuildTask::Identities => return Ok(0),
+    };
+
+    Ok(connection.execute_unprepared(sql).await?.rows_affected())
+}
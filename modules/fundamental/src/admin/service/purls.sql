@@ -0,0 +1,20 @@
+-- the coordinates of each qualified PURL, as stored in the base and versioned PURLs
+WITH coordinates AS (
+    SELECT
+        qp.id,
+        jsonb_build_object(
+            'ty', b."type",
+            'namespace', b.namespace,
+            'name', b.name,
+            'version', v.version,
+            'qualifiers', qp.qualifiers
+        ) AS purl
+    FROM qualified_purl qp
+        JOIN versioned_purl v ON v.id = qp.versioned_purl_id
+        JOIN base_purl b ON b.id = v.base_purl_id
+)
+UPDATE qualified_purl
+SET purl = coordinates.purl
+FROM coordinates
+WHERE qualified_purl.id = coordinates.id
+    AND qualified_purl.purl IS DISTINCT FROM coordinates.purl
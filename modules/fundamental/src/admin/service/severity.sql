@@ -0,0 +1,4 @@
+-- the severity of each score, as defined by CVSS v3
+UPDATE cvss3
+SET severity = CASE WHEN score = 0 THEN 'none'::cvss3_severity ELSE cvss3_severity(score) END
+WHERE severity IS DISTINCT FROM CASE WHEN score = 0 THEN 'none'::cvss3_severity ELSE cvss3_severity(score) END
@@ -1,5 +1,22 @@
+use trustify_common::decompress::Limits;
+use trustify_module_settings::model::Settings;
+
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 pub struct Config {
-    /// An upload limit in bytes. Zero meaning "unlimited".
-    pub upload_limit: usize,
+    /// Limits of an upload, after decompression.
+    pub upload_limit: Limits,
+}
+
+impl Config {
+    /// The configuration, with the runtime settings applied.
+    pub fn apply(&self, settings: &Settings) -> Self {
+        Self {
+            upload_limit: Limits {
+                size: settings
+                    .advisory_upload_limit
+                    .unwrap_or(self.upload_limit.size),
+                ..self.upload_limit
+            },
+        }
+    }
 }
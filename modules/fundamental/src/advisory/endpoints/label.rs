@@ -1,9 +1,19 @@
-use crate::advisory::service::AdvisoryService;
+use crate::{
+    advisory::service::AdvisoryService,
+    audit::{
+        model::{Action, DocumentKind},
+        service::AuditService,
+    },
+    tenant::Tenant,
+};
 use actix_web::{HttpResponse, Responder, patch, put, web};
+use serde_json::json;
 use trustify_auth::{UpdateAdvisory, authorizer::Require};
 use trustify_common::db::Database;
+use trustify_common::error::ErrorInformation;
 use trustify_common::id::Id;
 use trustify_entity::labels::Labels;
+use trustify_module_ingestor::service::audit::Actor;
 
 /// Replace the labels of an advisory
 #[utoipa::path(
@@ -15,7 +25,7 @@ use trustify_entity::labels::Labels;
     ),
     responses(
         (status = 204, description = "Replaced the labels of the advisory"),
-        (status = 404, description = "The advisory could not be found"),
+        (status = 404, description = "The advisory could not be found", body = ErrorInformation),
     ),
 )]
 #[put("/v2/advisory/{id}/label")]
@@ -24,14 +34,31 @@ pub async fn set(
     db: web::Data<Database>,
     id: web::Path<Id>,
     web::Json(labels): web::Json<Labels>,
+    audit: web::Data<AuditService>,
+    actor: Actor,
     _: Require<UpdateAdvisory>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
+    let id = id.into_inner();
+    let id = tenant.require_advisory(&id, db.as_ref()).await?;
+    let labels = tenant.stamp(labels);
+
     Ok(
-        match advisory
-            .set_labels(id.into_inner(), labels, db.as_ref())
-            .await?
-        {
-            Some(()) => HttpResponse::NoContent(),
+        match advisory.set_labels(id, labels.clone(), db.as_ref()).await? {
+            Some(id) => {
+                let details = json!({ "labels": labels });
+                audit
+                    .record(
+                        &actor,
+                        Action::LabelsChanged,
+                        DocumentKind::Advisory,
+                        id,
+                        Some(details),
+                        db.as_ref(),
+                    )
+                    .await?;
+                HttpResponse::NoContent()
+            }
             None => HttpResponse::NotFound(),
         },
     )
@@ -47,22 +74,43 @@ pub async fn set(
     ),
     responses(
         (status = 204, description = "Modified the labels of the advisory"),
-        (status = 404, description = "The advisory could not be found"),
+        (status = 404, description = "The advisory could not be found", body = ErrorInformation),
     ),
 )]
 #[patch("/v2/advisory/{id}/label")]
 pub async fn update(
     advisory: web::Data<AdvisoryService>,
+    db: web::Data<Database>,
     id: web::Path<Id>,
     web::Json(update): web::Json<Labels>,
+    audit: web::Data<AuditService>,
+    actor: Actor,
     _: Require<UpdateAdvisory>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
+    let id = id.into_inner();
+    let id = tenant.require_advisory(&id, db.as_ref()).await?;
+    let update = tenant.protect(update);
+    let details = json!({ "patch": &update });
+
     Ok(
         match advisory
-            .update_labels(id.into_inner(), |labels| labels.apply(update))
+            .update_labels(id, |labels| labels.apply(update))
             .await?
         {
-            Some(()) => HttpResponse::NoContent(),
+            Some(id) => {
+                audit
+                    .record(
+                        &actor,
+                        Action::LabelsChanged,
+                        DocumentKind::Advisory,
+                        id,
+                        Some(details),
+                        db.as_ref(),
+                    )
+                    .await?;
+                HttpResponse::NoContent()
+            }
             None => HttpResponse::NotFound(),
         },
     )
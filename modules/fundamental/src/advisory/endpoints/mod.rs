@@ -6,47 +6,84 @@ mod test;
 use crate::{
     Error,
     advisory::{
-        model::{AdvisoryDetails, AdvisorySummary},
-        service::AdvisoryService,
+        model::{
+            AdvisoryDeletion, AdvisoryDeletionToken, AdvisoryDetails, AdvisoryPreview,
+            AdvisorySummary,
+        },
+        service::{AdvisoryService, DeletionKey, DeletionService},
     },
-    endpoints::Deprecation,
+    audit::{
+        model::{Action, DocumentKind},
+        service::AuditService,
+    },
+    endpoints::{AsOf, Deprecation, Fields, Precheck},
+    listing::ListFormat,
     purl::service::PurlService,
+    source_document::{
+        conditional::{Preconditions, download},
+        retrieve,
+    },
+    tenant::Tenant,
 };
-use actix_web::{HttpResponse, Responder, delete, get, http::header, post, web};
+use actix_web::{HttpRequest, HttpResponse, Responder, delete, get, head, http::header, post, web};
 use config::Config;
-use futures_util::TryStreamExt;
 use sea_orm::TransactionTrait;
 use std::str::FromStr;
-use trustify_auth::{CreateAdvisory, DeleteAdvisory, ReadAdvisory, authorizer::Require};
+use trustify_auth::{
+    CreateAdvisory, DeleteAdvisory, ReadAdvisory, authenticator::user::UserInformation,
+    authorizer::Require,
+};
 use trustify_common::{
-    db::{Database, query::Query},
-    decompress::decompress_async,
+    db::{
+        Database,
+        query::{LabelFilters, LabelQuery, Query},
+    },
+    decompress::{Limits, decompress_async},
+    digest::VerifiedBytes,
+    error::ErrorInformation,
     id::Id,
     model::{BinaryData, Paginated, PaginatedResults},
+    purl::Purl,
 };
 use trustify_entity::labels::Labels;
-use trustify_module_ingestor::service::{Format, IngestorService};
-use trustify_module_storage::service::StorageBackend;
+use trustify_module_ingestor::{
+    model::IngestResult,
+    service::{
+        Format, IngestorService,
+        audit::{Actor, Upload},
+    },
+};
+use trustify_module_settings::service::SettingsService;
 use utoipa::IntoParams;
+use uuid::Uuid;
 
 pub fn configure(
     config: &mut utoipa_actix_web::service_config::ServiceConfig,
     db: Database,
-    upload_limit: usize,
+    upload_limit: Limits,
+    soft_delete: bool,
+    deletion_key: DeletionKey,
 ) {
-    let advisory_service = AdvisoryService::new(db.clone());
+    let advisory_service = AdvisoryService::new(db.clone()).with_soft_delete(soft_delete);
+    let deletion_service = DeletionService::new(db.clone(), advisory_service.clone(), deletion_key);
     let purl_service = PurlService::new();
 
     config
         .app_data(web::Data::new(db))
         .app_data(web::Data::new(advisory_service))
+        .app_data(web::Data::new(deletion_service))
         .app_data(web::Data::new(purl_service))
         .app_data(web::Data::new(Config { upload_limit }))
         .service(all)
+        .service(precheck)
+        .service(get_deletion)
         .service(get)
         .service(delete)
+        .service(delete_all)
+        .service(restore)
         .service(upload)
         .service(download)
+        .service(preview)
         .service(label::set)
         .service(label::update);
 }
@@ -58,26 +95,119 @@ pub fn configure(
         Query,
         Paginated,
         Deprecation,
+        AsOf,
+        LabelQuery,
+        AffectsQuery,
+        Fields,
     ),
     responses(
-        (status = 200, description = "Matching vulnerabilities", body = PaginatedResults<AdvisorySummary>),
+        (status = 200, description = "Matching advisories, as JSON, or streamed as CSV or NDJSON, depending on the Accept header", content(
+            (PaginatedResults<AdvisorySummary> = "application/json"),
+            (String = "text/csv"),
+            (String = "application/x-ndjson"),
+        )),
+        (status = 400, description = "The PURL could not be parsed, or lacks a version, or the point in time is invalid", body = ErrorInformation),
     ),
 )]
 #[get("/v2/advisory")]
 /// List advisories
 pub async fn all(
+    req: HttpRequest,
     state: web::Data<AdvisoryService>,
     db: web::Data<Database>,
     web::Query(search): web::Query<Query>,
     web::Query(paginated): web::Query<Paginated>,
     web::Query(Deprecation { deprecated }): web::Query<Deprecation>,
+    web::Query(as_of): web::Query<AsOf>,
+    web::Query(labels): web::Query<LabelQuery>,
+    web::Query(AffectsQuery { affects }): web::Query<AffectsQuery>,
+    web::Query(fields): web::Query<Fields>,
     _: Require<ReadAdvisory>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
-    Ok(HttpResponse::Ok().json(
+    let labels = tenant.restrict(LabelFilters::try_from(labels).map_err(Error::from)?);
+    let as_of = as_of.parse()?;
+    let affects = affects
+        .as_deref()
+        .map(Purl::from_str)
+        .transpose()
+        .map_err(Error::from)?;
+
+    let format = ListFormat::negotiate(&req);
+    if format != ListFormat::Json {
+        return Ok(format.stream(fields, paginated, move |paginated| {
+            let (state, db, search, labels, affects) = (
+                state.clone(),
+                db.clone(),
+                search.clone(),
+                labels.clone(),
+                affects.clone(),
+            );
+            async move {
+                state
+                    .fetch_advisories_affecting(
+                        search,
+                        paginated,
+                        deprecated,
+                        as_of,
+                        labels,
+                        affects.as_ref(),
+                        db.read(),
+                    )
+                    .await
+            }
+        }));
+    }
+
+    Ok(fields.respond(
         state
-            .fetch_advisories(search, paginated, deprecated, db.as_ref())
+            .fetch_advisories_affecting(
+                search,
+                paginated,
+                deprecated,
+                as_of,
+                labels,
+                affects.as_ref(),
+                db.read(),
+            )
             .await?,
-    ))
+    )?)
+}
+
+#[derive(IntoParams, Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+struct AffectsQuery {
+    /// Only return advisories affecting this PURL, which must have a version.
+    #[serde(default)]
+    affects: Option<String>,
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "precheckAdvisory",
+    params(
+        Precheck,
+    ),
+    responses(
+        (status = 200, description = "An advisory with this digest exists"),
+        (status = 404, description = "No advisory with this digest exists", body = ErrorInformation),
+    ),
+)]
+#[head("/v2/advisory")]
+/// Check if an advisory exists, before uploading it
+pub async fn precheck(
+    state: web::Data<AdvisoryService>,
+    db: web::Data<Database>,
+    web::Query(Precheck { sha256 }): web::Query<Precheck>,
+    _: Require<ReadAdvisory>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let id = Id::Sha256(sha256.to_lowercase());
+    let id = tenant.require_advisory(&id, db.read()).await?;
+    if state.exists(id, db.read()).await? {
+        Ok(HttpResponse::Ok().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
 }
 
 #[utoipa::path(
@@ -85,10 +215,12 @@ pub async fn all(
     operation_id = "getAdvisory",
     params(
         ("key" = Id, Path),
+        AsOf,
     ),
     responses(
         (status = 200, description = "Matching advisory", body = AdvisoryDetails),
-        (status = 404, description = "Matching advisory not found"),
+        (status = 400, description = "The point in time is invalid", body = ErrorInformation),
+        (status = 404, description = "Matching advisory not found", body = ErrorInformation),
     ),
 )]
 #[get("/v2/advisory/{key}")]
@@ -97,10 +229,15 @@ pub async fn get(
     state: web::Data<AdvisoryService>,
     db: web::Data<Database>,
     key: web::Path<String>,
+    web::Query(as_of): web::Query<AsOf>,
     _: Require<ReadAdvisory>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
     let hash_key = Id::from_str(&key).map_err(Error::IdKey)?;
-    let fetched = state.fetch_advisory(hash_key, db.as_ref()).await?;
+    let as_of = as_of.parse()?;
+    let tx = db.begin_read().await.map_err(Error::from)?;
+    let hash_key = tenant.require_advisory(&hash_key, &tx).await?;
+    let fetched = state.fetch_advisory_as_of(hash_key, as_of, &tx).await?;
 
     if let Some(fetched) = fetched {
         Ok(HttpResponse::Ok().json(fetched))
@@ -117,7 +254,7 @@ pub async fn get(
     ),
     responses(
         (status = 200, description = "Matching advisory", body = AdvisoryDetails),
-        (status = 404, description = "Matching advisory not found"),
+        (status = 404, description = "Matching advisory not found", body = ErrorInformation),
     ),
 )]
 #[delete("/v2/advisory/{key}")]
@@ -126,12 +263,16 @@ pub async fn delete(
     state: web::Data<AdvisoryService>,
     db: web::Data<Database>,
     purl_service: web::Data<PurlService>,
+    audit: web::Data<AuditService>,
     key: web::Path<String>,
+    actor: Actor,
     _: Require<DeleteAdvisory>,
+    tenant: Tenant,
 ) -> Result<impl Responder, Error> {
     let tx = db.begin().await?;
 
     let hash_key = Id::from_str(&key)?;
+    let hash_key = tenant.require_advisory(&hash_key, &tx).await?;
     let fetched = state.fetch_advisory(hash_key, &tx).await?;
 
     if let Some(fetched) = fetched {
@@ -140,6 +281,16 @@ pub async fn delete(
             0 => Ok(HttpResponse::NotFound().finish()),
             1 => {
                 let _ = purl_service.gc_purls(&tx).await; // ignore gc failure..
+                audit
+                    .record(
+                        &actor,
+                        Action::Deleted,
+                        DocumentKind::Advisory,
+                        fetched.head.uuid,
+                        None,
+                        &tx,
+                    )
+                    .await?;
                 tx.commit().await?;
                 Ok(HttpResponse::Ok().json(fetched))
             }
@@ -150,6 +301,145 @@ pub async fn delete(
     }
 }
 
+#[derive(IntoParams, Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+struct ConfirmQuery {
+    /// The token returned by the previous call, confirming the deletion of the matching
+    /// advisories.
+    #[serde(default)]
+    confirm: Option<String>,
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "deleteAdvisories",
+    params(
+        Query,
+        LabelQuery,
+        ConfirmQuery,
+    ),
+    responses(
+        (status = 200, description = "The number of matching advisories, and the token confirming their deletion", body = AdvisoryDeletionToken),
+        (status = 202, description = "The deletion was started", body = AdvisoryDeletion),
+        (status = 400, description = "Neither a query nor label filters were provided, they are invalid, or the token is malformed", body = ErrorInformation),
+        (status = 409, description = "The token expired, was issued to another user, or the matching advisories changed since it was issued", body = ErrorInformation),
+    ),
+)]
+#[delete("/v2/advisory")]
+/// Delete all advisories matching a query
+///
+/// This takes two calls: the first one returns the number of matching advisories, along with a
+/// token, valid for the calling user for a limited time. Passing that token as `confirm` to the
+/// second call starts deleting the advisories in the background. If the matching advisories
+/// changed in between, the deletion is rejected.
+pub async fn delete_all(
+    state: web::Data<AdvisoryService>,
+    deletion: web::Data<DeletionService>,
+    db: web::Data<Database>,
+    web::Query(search): web::Query<Query>,
+    web::Query(labels): web::Query<LabelQuery>,
+    web::Query(ConfirmQuery { confirm }): web::Query<ConfirmQuery>,
+    actor: Actor,
+    _: Require<DeleteAdvisory>,
+    tenant: Tenant,
+) -> Result<impl Responder, Error> {
+    let labels = LabelFilters::try_from(labels)?;
+    if search.q.trim().is_empty() && labels.is_empty() {
+        return Err(Error::BadRequest(
+            "Requires a query or label filters, selecting the advisories to delete".into(),
+        ));
+    }
+
+    let ids = state
+        .matching_advisories(search, tenant.restrict(labels), db.read())
+        .await?;
+
+    let Some(confirm) = confirm else {
+        return Ok(HttpResponse::Ok().json(deletion.confirmation(&ids, &actor)));
+    };
+
+    let result = deletion.start(ids, &confirm, actor, &tenant).await?;
+    Ok(HttpResponse::Accepted()
+        .insert_header((
+            header::LOCATION,
+            format!("/api/v2/advisory/deletion/{}", result.id),
+        ))
+        .json(result))
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "getAdvisoryDeletion",
+    params(
+        ("id" = Uuid, Path, description = "The ID of the deletion"),
+    ),
+    responses(
+        (status = 200, description = "The state of the deletion", body = AdvisoryDeletion),
+        (status = 404, description = "The deletion could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/advisory/deletion/{id}")]
+/// Get the state of deleting all advisories matching a query
+pub async fn get_deletion(
+    deletion: web::Data<DeletionService>,
+    id: web::Path<Uuid>,
+    _: Require<DeleteAdvisory>,
+    tenant: Tenant,
+) -> Result<impl Responder, Error> {
+    Ok(match deletion.fetch(id.into_inner(), &tenant).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "restoreAdvisory",
+    params(
+        ("id" = Uuid, Path, description = "ID of the advisory"),
+    ),
+    responses(
+        (status = 200, description = "The restored advisory", body = AdvisoryDetails),
+        (status = 404, description = "No deleted advisory with this ID found", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/advisory/{id}/restore")]
+/// Restore a deleted advisory
+pub async fn restore(
+    state: web::Data<AdvisoryService>,
+    db: web::Data<Database>,
+    audit: web::Data<AuditService>,
+    id: web::Path<Uuid>,
+    actor: Actor,
+    _: Require<DeleteAdvisory>,
+    tenant: Tenant,
+) -> Result<impl Responder, Error> {
+    let tx = db.begin().await?;
+
+    let id = id.into_inner();
+    tenant.require_advisory(&Id::Uuid(id), &tx).await?;
+    if state.restore_advisory(id, &tx).await? == 0 {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    audit
+        .record(
+            &actor,
+            Action::Restored,
+            DocumentKind::Advisory,
+            id,
+            None,
+            &tx,
+        )
+        .await?;
+
+    let fetched = state.fetch_advisory(Id::Uuid(id), &tx).await?;
+    tx.commit().await?;
+
+    Ok(match fetched {
+        Some(fetched) => HttpResponse::Ok().json(fetched),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
 #[derive(
     IntoParams, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize,
 )]
@@ -157,6 +447,13 @@ struct UploadParams {
     /// Optional issuer if it cannot be determined from advisory contents.
     #[serde(default)]
     issuer: Option<String>,
+    /// Optional external ID, like a build or ticket ID, for looking up the advisory later on.
+    #[serde(default)]
+    external_id: Option<String>,
+    /// Optional format of the document, skipping the detection of the format, e.g. `cycloneDx`.
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    format: Option<Format>,
     /// Optional labels.
     ///
     /// Only use keys with a prefix of `labels.`
@@ -168,26 +465,56 @@ struct UploadParams {
     tag = "advisory",
     operation_id = "uploadAdvisory",
     request_body = inline(BinaryData),
-    params(UploadParams),
+    params(
+        UploadParams,
+        ("digest" = Option<String>, Header, description = "Digests of the body, like `sha-256=<base64>`, rejecting the upload if it doesn't match"),
+    ),
     responses(
         (status = 201, description = "Upload a file"),
-        (status = 400, description = "The file could not be parsed as an advisory"),
+        (status = 400, description = "The file could not be parsed as an advisory", body = ErrorInformation),
+        (status = 409, description = "The advisory was already ingested, reporting the existing one", body = IngestResult),
+        (status = 413, description = "The file exceeds the size limit", body = ErrorInformation),
     )
 )]
 #[post("/v2/advisory")]
 /// Upload a new advisory
+///
+/// Uploading an advisory which was already ingested doesn't ingest it again, but responds with
+/// the existing advisory, flagged as a duplicate.
 pub async fn upload(
     service: web::Data<IngestorService>,
     config: web::Data<Config>,
-    web::Query(UploadParams { issuer, labels }): web::Query<UploadParams>,
+    settings: web::Data<SettingsService>,
+    web::Query(UploadParams {
+        issuer,
+        external_id,
+        format,
+        labels,
+    }): web::Query<UploadParams>,
     content_type: Option<web::Header<header::ContentType>>,
-    bytes: web::Bytes,
+    VerifiedBytes(bytes): VerifiedBytes,
+    actor: Actor,
     _: Require<CreateAdvisory>,
+    tenant: Tenant,
 ) -> Result<impl Responder, Error> {
+    let format = Format::Advisory.with_override(format)?;
+    let config = config.apply(&settings.current().await?);
     let bytes = decompress_async(bytes, content_type.map(|ct| ct.0), config.upload_limit).await??;
+    let labels = tenant.stamp(labels);
     let result = service
-        .ingest(&bytes, Format::Advisory, labels, issuer)
+        .ingest_with_external_id(
+            &bytes,
+            format,
+            labels,
+            issuer,
+            external_id,
+            Some(&Upload::new(actor)),
+        )
         .await?;
+    if result.duplicate {
+        log::info!("Advisory already ingested: {}", result.id);
+        return Ok(HttpResponse::Conflict().json(result));
+    }
     log::info!("Uploaded Advisory: {}", result.id);
     Ok(HttpResponse::Created().json(result))
 }
@@ -200,7 +527,10 @@ pub async fn upload(
     ),
     responses(
         (status = 200, description = "Download a an advisory", body = inline(BinaryData)),
-        (status = 404, description = "The document could not be found"),
+        (status = 206, description = "Download a range of the document", body = inline(BinaryData)),
+        (status = 304, description = "The document was not modified"),
+        (status = 404, description = "The document could not be found", body = ErrorInformation),
+        (status = 416, description = "The requested range can't be satisfied", body = ErrorInformation),
     )
 )]
 #[get("/v2/advisory/{key}/download")]
@@ -209,32 +539,79 @@ pub async fn download(
     db: web::Data<Database>,
     ingestor: web::Data<IngestorService>,
     advisory: web::Data<AdvisoryService>,
+    audit: web::Data<AuditService>,
     key: web::Path<String>,
+    preconditions: Preconditions,
+    user: UserInformation,
     _: Require<ReadAdvisory>,
+    tenant: Tenant,
 ) -> Result<impl Responder, Error> {
     // the user requested id
     let id = Id::from_str(&key).map_err(Error::IdKey)?;
+    let id = tenant.require_advisory(&id, db.read()).await?;
 
     // look up document by id
-    let Some(advisory) = advisory.fetch_advisory(id, db.as_ref()).await? else {
+    let Some(advisory) = advisory.fetch_advisory(id, db.read()).await? else {
         return Ok(HttpResponse::NotFound().finish());
     };
 
-    if let Some(doc) = &advisory.source_document {
-        let stream = ingestor
-            .get_ref()
-            .storage()
-            .clone()
-            .retrieve(doc.try_into()?)
-            .await
-            .map_err(Error::Storage)?
-            .map(|stream| stream.map_err(Error::Storage));
-
-        Ok(match stream {
-            Some(s) => HttpResponse::Ok().streaming(s),
-            None => HttpResponse::NotFound().finish(),
-        })
-    } else {
-        Ok(HttpResponse::NotFound().finish())
+    let Some(doc) = &advisory.source_document else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    let response = download(ingestor.storage(), doc, &preconditions).await?;
+    // not modified or failed responses don't count as downloads
+    if let Some(bytes) = preconditions
+        .served_bytes(doc)
+        .filter(|_| response.status().is_success())
+    {
+        audit
+            .record_download(user.id(), DocumentKind::Advisory, advisory.head.uuid, bytes)
+            .await?;
     }
+
+    Ok(response)
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "previewAdvisory",
+    params(
+        ("key" = Id, Path),
+    ),
+    responses(
+        (status = 200, description = "A rendering of the advisory document", body = AdvisoryPreview),
+        (status = 404, description = "The document could not be found", body = ErrorInformation),
+    )
+)]
+#[get("/v2/advisory/{key}/preview")]
+/// Render an advisory document, independent of its format
+pub async fn preview(
+    db: web::Data<Database>,
+    ingestor: web::Data<IngestorService>,
+    advisory: web::Data<AdvisoryService>,
+    key: web::Path<String>,
+    _: Require<ReadAdvisory>,
+    tenant: Tenant,
+) -> Result<impl Responder, Error> {
+    let id = Id::from_str(&key).map_err(Error::IdKey)?;
+    let id = tenant.require_advisory(&id, db.read()).await?;
+
+    let Some(advisory) = advisory.fetch_advisory(id, db.read()).await? else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    let Some(doc) = &advisory.source_document else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    let Some(bytes) = retrieve(ingestor.storage(), doc).await? else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    // parsing large documents would block the executor
+    let preview =
+        tokio::task::spawn_blocking(move || AdvisoryPreview::from_document(&bytes)).await??;
+
+    Ok(HttpResponse::Ok().json(preview))
 }
@@ -1,11 +1,23 @@
 use crate::{
-    advisory::model::{AdvisoryDetails, AdvisorySummary},
-    test::caller,
+    advisory::model::{
+        AdvisoryDeletion, AdvisoryDeletionState, AdvisoryDeletionToken, AdvisoryDetails,
+        AdvisoryPreview, AdvisorySummary, PreviewFormat,
+    },
+    audit::model::{Action, AuditEvent},
+    endpoints::Config,
+    test::{caller, caller_with},
 };
 use actix_http::StatusCode;
-use actix_web::test::TestRequest;
+use actix_web::{
+    http::{
+        Method,
+        header::{self, HeaderValue},
+    },
+    test::TestRequest,
+};
 use hex::ToHex;
 use jsonpath_rust::JsonPath;
+use sea_orm::{ActiveValue::Set, EntityTrait};
 use serde_json::{Value, json};
 use sha2::{Digest, Sha256};
 use test_context::test_context;
@@ -16,9 +28,11 @@ use trustify_cvss::cvss3::{
     AttackComplexity, AttackVector, Availability, Confidentiality, Cvss3Base, Integrity,
     PrivilegesRequired, Scope, UserInteraction,
 };
-use trustify_entity::labels::Labels;
+use trustify_entity::{ingestion_warning, labels::Labels};
 use trustify_module_ingestor::{graph::advisory::AdvisoryInformation, model::IngestResult};
-use trustify_test_context::{TrustifyContext, call::CallService, document_bytes};
+use trustify_test_context::{
+    TrustifyContext, auth::TestAuthentication, call::CallService, document_bytes,
+};
 use uuid::Uuid;
 
 #[test_context(TrustifyContext)]
@@ -339,6 +353,121 @@ async fn search_advisories(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn search_advisories_with_warnings(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    let result = ctx
+        .ingest_documents(["mitre/CVE-2024-27088.json", "mitre/CVE-2024-28111.json"])
+        .await?;
+    let Id::Uuid(id) = result[0].id else {
+        panic!("unexpected ID: {}", result[0].id);
+    };
+
+    ingestion_warning::Entity::insert(ingestion_warning::ActiveModel {
+        id: Set(Uuid::now_v7()),
+        sbom_id: Set(None),
+        advisory_id: Set(Some(id)),
+        message: Set("something is off".into()),
+    })
+    .exec(&ctx.db)
+    .await?;
+
+    let uri = format!("/api/v2/advisory?q={}", urlencoding::encode("warnings>0"));
+    let result: PaginatedResults<AdvisorySummary> = app
+        .call_and_read_body_json(TestRequest::get().uri(&uri).to_request())
+        .await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].head.identifier, "CVE-2024-27088");
+
+    let uri = format!("/api/v2/advisory/{id}");
+    let details: AdvisoryDetails = app
+        .call_and_read_body_json(TestRequest::get().uri(&uri).to_request())
+        .await;
+    assert_eq!(details.warnings, vec!["something is off".to_string()]);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn supersedence(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let csaf: Value = serde_json::from_slice(&document_bytes("csaf/CVE-2023-20862.json").await?)?;
+
+    // ingest a chain of advisories, each superseding the previous one
+
+    let mut results = vec![];
+    for (id, supersedes) in [
+        ("EX-1", None),
+        ("EX-2", Some("EX-1")),
+        ("EX-3", Some("EX-2")),
+    ] {
+        let mut csaf = csaf.clone();
+        csaf["document"]["tracking"]["id"] = id.into();
+        if let Some(supersedes) = supersedes {
+            csaf["document"]["references"]
+                .as_array_mut()
+                .expect("must have references")
+                .push(json!({
+                    "category": "external",
+                    "summary": format!("Supersedes {supersedes}"),
+                    "url": format!("https://example.com/{supersedes}"),
+                }));
+        }
+        results.push(ctx.ingest_json(&csaf).await?);
+    }
+    let identifiers: Vec<String> = results
+        .iter()
+        .map(|result| result.document_id.clone().expect("must have a document ID"))
+        .collect();
+
+    let uri = format!("/api/v2/advisory/{}", results[0].id);
+    let details: AdvisoryDetails = app
+        .call_and_read_body_json(TestRequest::get().uri(&uri).to_request())
+        .await;
+    assert!(details.supersedes.is_empty());
+    assert_eq!(details.superseded_by, [&*identifiers[1], &*identifiers[2]]);
+
+    let uri = format!("/api/v2/advisory/{}", results[2].id);
+    let details: AdvisoryDetails = app
+        .call_and_read_body_json(TestRequest::get().uri(&uri).to_request())
+        .await;
+    assert_eq!(details.supersedes, [&*identifiers[1], &*identifiers[0]]);
+    assert!(details.superseded_by.is_empty());
+
+    let uri = format!(
+        "/api/v2/advisory?q={}",
+        urlencoding::encode("superseded=false")
+    );
+    let result: PaginatedResults<AdvisorySummary> = app
+        .call_and_read_body_json(TestRequest::get().uri(&uri).to_request())
+        .await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].head.identifier, identifiers[2]);
+
+    // deleting the last one, the previous one is no longer superseded
+
+    let uri = format!("/api/v2/advisory/{}", results[2].id);
+    let response = app
+        .call_service(TestRequest::delete().uri(&uri).to_request())
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let uri = format!(
+        "/api/v2/advisory?q={}",
+        urlencoding::encode("superseded=false")
+    );
+    let result: PaginatedResults<AdvisorySummary> = app
+        .call_and_read_body_json(TestRequest::get().uri(&uri).to_request())
+        .await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].head.identifier, identifiers[1]);
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn upload_default_csaf_format(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
@@ -363,6 +492,45 @@ async fn upload_default_csaf_format(ctx: &TrustifyContext) -> Result<(), anyhow:
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn upload_explicit_format(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    // an SBOM is reported as such
+
+    let payload = document_bytes("cyclonedx/simple_1dot6.json").await?;
+    let request = TestRequest::post()
+        .uri("/api/v2/advisory")
+        .set_payload(payload)
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let error: Value = actix_web::test::read_body_json(response).await;
+    assert_eq!(error["error"], "FormatMismatch");
+
+    // an SBOM format can't be requested
+
+    let payload = document_bytes("csaf/cve-2023-33201.json").await?;
+    let request = TestRequest::post()
+        .uri("/api/v2/advisory?format=spdx")
+        .set_payload(payload.clone())
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // an advisory format skips the detection
+
+    let request = TestRequest::post()
+        .uri("/api/v2/advisory?format=csaf")
+        .set_payload(payload)
+        .to_request();
+    let result: IngestResult = app.call_and_read_body_json(request).await;
+    assert!(matches!(result.id, Id::Uuid(_)));
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn upload_default_csaf_format_multiple(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
@@ -421,6 +589,36 @@ async fn upload_osv_format(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn upload_duplicate(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let payload = document_bytes("csaf/cve-2023-33201.json").await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/advisory")
+        .set_payload(payload.clone())
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let first: IngestResult = actix_web::test::read_body_json(response).await;
+    assert!(!first.duplicate);
+
+    // uploading it again reports the existing advisory
+    let request = TestRequest::post()
+        .uri("/api/v2/advisory")
+        .set_payload(payload)
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let second: IngestResult = actix_web::test::read_body_json(response).await;
+    assert!(second.duplicate);
+    assert_eq!(second.id, first.id);
+    assert_eq!(second.document_id, first.document_id);
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn upload_cve_format(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
@@ -460,6 +658,40 @@ async fn upload_unknown_format(ctx: &TrustifyContext) -> Result<(), anyhow::Erro
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn upload_schema_violation(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    let mut csaf: Value =
+        serde_json::from_slice(&document_bytes("csaf/cve-2023-0044.json").await?)?;
+    csaf["document"]["tracking"]["status"] = json!("published");
+    csaf["vulnerabilities"][0]["cve"] = json!("CVE-1");
+
+    let request = TestRequest::post()
+        .uri("/api/v2/advisory")
+        .set_json(csaf)
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let error: Value = actix_web::test::read_body_json(response).await;
+    assert_eq!(error["error"], "SchemaViolation");
+    let mut pointers: Vec<_> = error["violations"]
+        .as_array()
+        .expect("must be an array")
+        .iter()
+        .map(|violation| violation["pointer"].as_str().unwrap_or_default())
+        .collect();
+    pointers.sort();
+    assert_eq!(
+        pointers,
+        ["/document/tracking/status", "/vulnerabilities/0/cve"]
+    );
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn upload_with_labels(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
@@ -517,6 +749,37 @@ async fn download_advisory(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+/// Test checking if a document exists, before uploading it
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn precheck_advisory(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let digest: String = Sha256::digest(document_bytes(DOC).await?).encode_hex();
+    let app = caller(ctx).await?;
+
+    let precheck = async |digest: &str| {
+        let request = TestRequest::default()
+            .method(Method::HEAD)
+            .uri(&format!("/api/v2/advisory?sha256={digest}"))
+            .to_request();
+        app.call_service(request).await.status()
+    };
+
+    assert_eq!(precheck(&digest).await, StatusCode::NOT_FOUND);
+
+    ctx.ingest_document(DOC).await?;
+
+    assert_eq!(precheck(&digest).await, StatusCode::OK);
+    assert_eq!(precheck(&digest.to_uppercase()).await, StatusCode::OK);
+
+    // the listing is not affected by the check
+    let advisory_list: PaginatedResults<AdvisorySummary> = app
+        .call_and_read_body_json(TestRequest::get().uri("/api/v2/advisory").to_request())
+        .await;
+    assert_eq!(advisory_list.total, 1);
+
+    Ok(())
+}
+
 /// Test downloading a document by its upload ID
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
@@ -531,6 +794,173 @@ async fn download_advisory_by_id(ctx: &TrustifyContext) -> Result<(), anyhow::Er
     Ok(())
 }
 
+/// Test conditional downloads, using the ETag of the document
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn download_advisory_conditional(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let digest: String = Sha256::digest(document_bytes(DOC).await?).encode_hex();
+    let app = caller(ctx).await?;
+    let result = ctx.ingest_document(DOC).await?;
+    let uri = format!("/api/v2/advisory/{}/download", result.id);
+
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::ETAG),
+        Some(&HeaderValue::from_str(&format!(r#""{digest}""#))?)
+    );
+    assert!(response.headers().contains_key(header::LAST_MODIFIED));
+
+    // unchanged
+
+    let request = TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::IF_NONE_MATCH, format!(r#""{digest}""#)))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    // changed
+
+    let request = TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::IF_NONE_MATCH, r#""0000""#))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+/// Test downloading a range of a document
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn download_advisory_range(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let data = document_bytes(DOC).await?;
+    let app = caller(ctx).await?;
+    let result = ctx.ingest_document(DOC).await?;
+    let uri = format!("/api/v2/advisory/{}/download", result.id);
+
+    let request = TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::RANGE, "bytes=10-19"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(
+        response.headers().get(header::CONTENT_RANGE),
+        Some(&HeaderValue::from_str(&format!(
+            "bytes 10-19/{}",
+            data.len()
+        ))?)
+    );
+    let body = actix_web::test::read_body(response).await;
+    assert_eq!(body, data.slice(10..20));
+
+    // out of range
+
+    let request = TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::RANGE, format!("bytes={}-", data.len())))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+
+    Ok(())
+}
+
+/// Test rendering documents of all advisory formats the same way
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn preview_advisory(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let mut previews = vec![];
+
+    for (path, format, id) in [
+        (DOC, PreviewFormat::Csaf, "CVE-2023-33201"),
+        (
+            "mitre/CVE-2024-28111.json",
+            PreviewFormat::Cve,
+            "CVE-2024-28111",
+        ),
+        (
+            "osv/GHSA-2ccf-ffrj-m4qw.json",
+            PreviewFormat::Osv,
+            "GHSA-2ccf-ffrj-m4qw",
+        ),
+    ] {
+        let result = ctx.ingest_document(path).await?;
+        let uri = format!("/api/v2/advisory/{}/preview", result.id);
+        let request = TestRequest::get().uri(&uri).to_request();
+        let preview: AdvisoryPreview = app.call_and_read_body_json(request).await;
+
+        assert_eq!(preview.format, format);
+        assert_eq!(preview.id, id);
+        assert!(!preview.sections.is_empty(), "{path}");
+        assert!(!preview.references.is_empty(), "{path}");
+        assert!(!preview.product_tree.is_empty(), "{path}");
+        assert_eq!(preview.vulnerabilities.len(), 1, "{path}");
+        previews.push(preview);
+    }
+
+    // the CSAF statuses and remediations are kept
+
+    let vulnerability = &previews[0].vulnerabilities[0];
+    assert_eq!(vulnerability.id.as_deref(), Some("CVE-2023-33201"));
+    assert!(vulnerability.product_status.contains_key("fixed"));
+    assert!(
+        vulnerability
+            .remediations
+            .iter()
+            .any(|remediation| remediation.category == "vendor_fix")
+    );
+
+    let request = TestRequest::get()
+        .uri(&format!(
+            "/api/v2/advisory/{}/preview",
+            Id::Uuid(Uuid::now_v7())
+        ))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+/// Test filtering advisories by their labels
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn list_by_labels(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    ctx.ingest_documents(["csaf/cve-2023-33201.json", "mitre/CVE-2024-27088.json"])
+        .await?;
+
+    for (labels, expected) in [
+        ("type%3Dcs*", 1),
+        ("!type%3Dcsaf", 1),
+        ("has:type", 2),
+        ("!has:type", 0),
+        ("has:type,!type%3D*v*", 1),
+    ] {
+        let request = TestRequest::get()
+            .uri(&format!("/api/v2/advisory?labels={labels}"))
+            .to_request();
+        let result: PaginatedResults<AdvisorySummary> = app.call_and_read_body_json(request).await;
+        assert_eq!(result.total, expected, "{labels}");
+    }
+
+    // invalid filters are rejected
+
+    let request = TestRequest::get()
+        .uri("/api/v2/advisory?labels=type")
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
 /// Test setting labels
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
@@ -612,3 +1042,330 @@ async fn delete_advisory(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn delete_all_advisories(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    async fn ingest(ctx: &TrustifyContext, id: &str, source: &str) -> Result<Uuid, anyhow::Error> {
+        let advisory = ctx
+            .graph
+            .ingest_advisory(
+                id,
+                ("source", source),
+                &Digests::digest(id),
+                AdvisoryInformation {
+                    id: id.to_string(),
+                    title: Some(id.to_string()),
+                    version: None,
+                    issuer: None,
+                    published: Some(OffsetDateTime::now_utc()),
+                    modified: None,
+                    withdrawn: None,
+                },
+                &ctx.db,
+            )
+            .await?;
+        Ok(advisory.advisory.id)
+    }
+
+    let first_id = ingest(ctx, "RHSA-1", "feed").await?;
+    ingest(ctx, "RHSA-2", "feed").await?;
+    ingest(ctx, "RHSA-3", "other").await?;
+
+    // deleting everything requires a filter
+
+    let response = app
+        .call_service(TestRequest::delete().uri("/api/v2/advisory").to_request())
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // the first call only counts
+
+    let uri = "/api/v2/advisory?labels=source=feed";
+    let first: AdvisoryDeletionToken = app
+        .call_and_read_body_json(
+            TestRequest::delete()
+                .uri(uri)
+                .to_request()
+                .test_auth("user-a"),
+        )
+        .await;
+    assert_eq!(first.total, 2);
+    assert!(first.expires > OffsetDateTime::now_utc());
+
+    // the token is only valid for the user it was issued to
+
+    let response = app
+        .call_service(
+            TestRequest::delete()
+                .uri(&format!("{uri}&confirm={}", first.token))
+                .to_request()
+                .test_auth("user-b"),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // tokens can't be forged
+
+    let response = app
+        .call_service(
+            TestRequest::delete()
+                .uri(&format!("{uri}&confirm=4102444800.00"))
+                .to_request()
+                .test_auth("user-a"),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = app
+        .call_service(
+            TestRequest::delete()
+                .uri(&format!("{uri}&confirm=not-a-token"))
+                .to_request()
+                .test_auth("user-a"),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // a change of the matching advisories invalidates the token
+
+    ingest(ctx, "RHSA-4", "feed").await?;
+
+    let response = app
+        .call_service(
+            TestRequest::delete()
+                .uri(&format!("{uri}&confirm={}", first.token))
+                .to_request()
+                .test_auth("user-a"),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // confirming with a current token deletes in the background
+
+    let second: AdvisoryDeletionToken = app
+        .call_and_read_body_json(
+            TestRequest::delete()
+                .uri(uri)
+                .to_request()
+                .test_auth("user-a"),
+        )
+        .await;
+    assert_eq!(second.total, 3);
+
+    let response = app
+        .call_service(
+            TestRequest::delete()
+                .uri(&format!("{uri}&confirm={}", second.token))
+                .to_request()
+                .test_auth("user-a"),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let location = response
+        .headers()
+        .get(header::LOCATION)
+        .expect("must have a location")
+        .to_str()?
+        .to_string();
+    let mut result: AdvisoryDeletion = actix_web::test::read_body_json(response).await;
+    assert_eq!(location, format!("/api/v2/advisory/deletion/{}", result.id));
+    assert_eq!(result.total, 3);
+
+    for _ in 0..100 {
+        if result.state != AdvisoryDeletionState::Running {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        result = app
+            .call_and_read_body_json(TestRequest::get().uri(&location).to_request())
+            .await;
+    }
+
+    assert_eq!(result.state, AdvisoryDeletionState::Succeeded);
+    assert_eq!(result.deleted, 3);
+    assert!(result.finished.is_some());
+    assert_eq!(result.error, None);
+
+    let advisory_list: PaginatedResults<AdvisorySummary> = app
+        .call_and_read_body_json(TestRequest::get().uri("/api/v2/advisory").to_request())
+        .await;
+    assert_eq!(advisory_list.total, 1);
+    assert_eq!(advisory_list.items[0].head.identifier, "RHSA-3");
+
+    // each deletion is part of the audit trail
+
+    let trail: PaginatedResults<AuditEvent> = app
+        .call_and_read_body_json(
+            TestRequest::get()
+                .uri(&format!("/api/v2/audit?entity=advisory&id={first_id}"))
+                .to_request(),
+        )
+        .await;
+    assert_eq!(
+        trail
+            .items
+            .iter()
+            .map(|event| (event.action, event.user.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![(Action::Deleted, Some("user-a"))]
+    );
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn advisory_deletion_unknown(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/advisory/deletion/{}", Uuid::now_v7()))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn soft_delete_and_restore_advisory(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller_with(
+        ctx,
+        Config {
+            soft_delete: true,
+            ..Default::default()
+        },
+    )
+    .await?;
+    let doc = ctx.ingest_document(DOC).await?;
+    let id = doc.id.try_as_uid().expect("must be a UUID");
+
+    // deleting hides the document
+
+    let response = app
+        .call_service(
+            TestRequest::delete()
+                .uri(&format!("/api/v2/advisory/{id}"))
+                .to_request(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let advisory_list: PaginatedResults<AdvisorySummary> = app
+        .call_and_read_body_json(TestRequest::get().uri("/api/v2/advisory").to_request())
+        .await;
+    assert_eq!(advisory_list.total, 0);
+
+    for request in [
+        TestRequest::get().uri(&format!("/api/v2/advisory/{id}")),
+        TestRequest::delete().uri(&format!("/api/v2/advisory/{id}")),
+    ] {
+        let response = app.call_service(request.to_request()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    // but it can be restored, once
+
+    let response = app
+        .call_service(
+            TestRequest::post()
+                .uri(&format!("/api/v2/advisory/{id}/restore"))
+                .to_request(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let advisory_list: PaginatedResults<AdvisorySummary> = app
+        .call_and_read_body_json(TestRequest::get().uri("/api/v2/advisory").to_request())
+        .await;
+    assert_eq!(advisory_list.total, 1);
+    assert_eq!(advisory_list.items[0].head.uuid, id);
+
+    let response = app
+        .call_service(
+            TestRequest::post()
+                .uri(&format!("/api/v2/advisory/{id}/restore"))
+                .to_request(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // uploading a deleted document again restores it too
+
+    let response = app
+        .call_service(
+            TestRequest::delete()
+                .uri(&format!("/api/v2/advisory/{id}"))
+                .to_request(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let result = ctx.ingest_document(DOC).await?;
+    assert_eq!(result.id.try_as_uid(), Some(id));
+
+    let response = app
+        .call_service(
+            TestRequest::get()
+                .uri(&format!("/api/v2/advisory/{id}"))
+                .to_request(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn list_advisories_affecting(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    ctx.ingest_documents(["osv/RUSTSEC-2021-0079.json", "osv/RUSTSEC-2022-0022.json"])
+        .await?;
+
+    let affecting = |purl: &'static str| {
+        let app = &app;
+        async move {
+            let uri = format!(
+                "/api/v2/advisory?affects={}&sort={}",
+                urlencoding::encode(purl),
+                urlencoding::encode("identifier")
+            );
+            let request = TestRequest::get().uri(&uri).to_request();
+            let result: PaginatedResults<AdvisorySummary> =
+                app.call_and_read_body_json(request).await;
+            result
+                .items
+                .into_iter()
+                .map(|advisory| advisory.head.identifier)
+                .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(
+        affecting("pkg:cargo/hyper@0.14.5").await,
+        ["RUSTSEC-2021-0079", "RUSTSEC-2022-0022"]
+    );
+    assert_eq!(
+        affecting("pkg:cargo/hyper@0.14.11").await,
+        ["RUSTSEC-2022-0022"]
+    );
+    assert!(affecting("pkg:cargo/hyper@0.14.12").await.is_empty());
+    assert!(affecting("pkg:cargo/tokio@0.14.5").await.is_empty());
+
+    // a version is required
+
+    let uri = format!(
+        "/api/v2/advisory?affects={}",
+        urlencoding::encode("pkg:cargo/hyper")
+    );
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
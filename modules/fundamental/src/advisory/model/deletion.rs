@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::advisory_deletion;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The advisories matching a query, along with the token confirming their deletion.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct AdvisoryDeletionToken {
+    /// The number of advisories matching the query.
+    pub total: u64,
+
+    /// The token confirming the deletion of the matching advisories, by the user it was issued
+    /// to.
+    pub token: String,
+
+    /// The time the token expires.
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires: OffsetDateTime,
+}
+
+/// The state of deleting all advisories matching a query, running in the background.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct AdvisoryDeletion {
+    /// The ID of the deletion
+    pub id: Uuid,
+    /// The current state of the deletion
+    pub state: AdvisoryDeletionState,
+    /// The number of advisories to delete
+    pub total: u64,
+    /// The number of advisories deleted so far
+    pub deleted: u64,
+    /// The time the deletion started
+    #[serde(with = "time::serde::rfc3339")]
+    pub started: OffsetDateTime,
+    /// The time the deletion finished
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub finished: Option<OffsetDateTime>,
+    /// The error of the deletion, if it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The state of a deletion
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum AdvisoryDeletionState {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl From<advisory_deletion::State> for AdvisoryDeletionState {
+    fn from(value: advisory_deletion::State) -> Self {
+        match value {
+            advisory_deletion::State::Running => Self::Running,
+            advisory_deletion::State::Succeeded => Self::Succeeded,
+            advisory_deletion::State::Failed => Self::Failed,
+        }
+    }
+}
+
+impl From<advisory_deletion::Model> for AdvisoryDeletion {
+    fn from(value: advisory_deletion::Model) -> Self {
+        Self {
+            id: value.id,
+            state: value.state.into(),
+            total: value.total as u64,
+            deleted: value.deleted as u64,
+            started: value.started,
+            finished: value.finished,
+            error: value.error,
+        }
+    }
+}
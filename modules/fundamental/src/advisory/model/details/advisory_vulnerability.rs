@@ -1,5 +1,5 @@
 use crate::{Error, vulnerability::model::VulnerabilityHead};
-use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, LoaderTrait, QueryFilter};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, LoaderTrait, QueryFilter, QueryOrder};
 use serde::{Deserialize, Serialize};
 use trustify_common::memo::Memo;
 use trustify_cvss::cvss3::severity::Severity;
@@ -74,7 +74,9 @@ impl AdvisoryVulnerabilityHead {
     ) -> Result<Vec<Self>, Error> {
         let cvss3s = vulnerabilities
             .load_many(
-                cvss3::Entity::find().filter(cvss3::Column::AdvisoryId.eq(advisory.id)),
+                cvss3::Entity::find()
+                    .filter(cvss3::Column::AdvisoryId.eq(advisory.id))
+                    .order_by_asc(cvss3::Column::MinorVersion),
                 tx,
             )
             .await?;
@@ -115,7 +117,8 @@ pub struct AdvisoryVulnerabilitySummary {
     pub head: AdvisoryVulnerabilityHead,
 
     /// All CVSS3 scores from the advisory for the given vulnerability.
-    /// May include several, varying by minor version of the CVSS3 vector.
+    /// May include several, varying by minor version of the CVSS3 vector, ordered by that
+    /// minor version.
     #[schema(default, value_type = Vec<String>)]
     pub cvss3_scores: Vec<String>,
 }
@@ -128,7 +131,9 @@ impl AdvisoryVulnerabilitySummary {
     ) -> Result<Vec<Self>, Error> {
         let mut cvss3s = vulnerabilities
             .load_many(
-                cvss3::Entity::find().filter(cvss3::Column::AdvisoryId.eq(advisory.id)),
+                cvss3::Entity::find()
+                    .filter(cvss3::Column::AdvisoryId.eq(advisory.id))
+                    .order_by_asc(cvss3::Column::MinorVersion),
                 tx,
             )
             .await?;
@@ -1,10 +1,12 @@
 pub mod advisory_vulnerability;
 
 use crate::advisory::service::AdvisoryCatcher;
+use crate::attachment::model::Attachment;
+use crate::comment::model::Comment;
 use crate::source_document::model::SourceDocument;
 use crate::{Error, advisory::model::AdvisoryHead};
 use advisory_vulnerability::AdvisoryVulnerabilitySummary;
-use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QuerySelect};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
 use serde::{Deserialize, Serialize};
 use trustify_common::memo::Memo;
 use trustify_cvss::cvss3::severity::Severity;
@@ -19,7 +21,7 @@ pub struct AdvisoryDetails {
     #[serde(flatten)]
     pub source_document: Option<SourceDocument>,
 
-    /// Vulnerabilities addressed within this advisory.
+    /// Vulnerabilities addressed within this advisory, ordered by their identifier.
     pub vulnerabilities: Vec<AdvisoryVulnerabilitySummary>,
 
     /// Average (arithmetic mean) severity of the advisory aggregated from *all* related vulnerability assertions.
@@ -29,6 +31,23 @@ pub struct AdvisoryDetails {
     /// Average (arithmetic mean) score of the advisory aggregated from *all* related vulnerability assertions.
     #[schema(required)]
     pub average_score: Option<f64>,
+
+    /// Warnings reported while ingesting the advisory.
+    pub warnings: Vec<String>,
+
+    /// Files attached to the advisory, oldest first.
+    pub attachments: Vec<Attachment>,
+
+    /// Comments on the advisory, oldest first.
+    pub comments: Vec<Comment>,
+
+    /// Identifiers of advisories superseded by this advisory, directly or through others,
+    /// nearest first.
+    pub supersedes: Vec<String>,
+
+    /// Identifiers of advisories superseding this advisory, directly or through others, nearest
+    /// first.
+    pub superseded_by: Vec<String>,
 }
 
 impl AdvisoryDetails {
@@ -43,6 +62,7 @@ impl AdvisoryDetails {
                 entity::vulnerability::Column::Id,
             )
             .filter(entity::advisory_vulnerability::Column::AdvisoryId.eq(advisory.advisory.id))
+            .order_by_asc(entity::advisory_vulnerability::Column::VulnerabilityId)
             .all(tx)
             .await?;
 
@@ -50,6 +70,36 @@ impl AdvisoryDetails {
             AdvisoryVulnerabilitySummary::from_entities(&advisory.advisory, &vulnerabilities, tx)
                 .await?;
 
+        let warnings = entity::ingestion_warning::Entity::find()
+            .filter(entity::ingestion_warning::Column::AdvisoryId.eq(advisory.advisory.id))
+            .order_by_asc(entity::ingestion_warning::Column::Id)
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|warning| warning.message)
+            .collect();
+
+        let attachments = Attachment::find(
+            entity::attachment::Column::AdvisoryId.eq(advisory.advisory.id),
+            tx,
+        )
+        .await?;
+
+        let comments = Comment::find(
+            entity::comment::Column::AdvisoryId.eq(advisory.advisory.id),
+            tx,
+        )
+        .await?;
+
+        let supersedes =
+            supersedence_chain(&advisory.advisory.identifier, Supersedence::Supersedes, tx).await?;
+        let superseded_by = supersedence_chain(
+            &advisory.advisory.identifier,
+            Supersedence::SupersededBy,
+            tx,
+        )
+        .await?;
+
         Ok(AdvisoryDetails {
             head: AdvisoryHead::from_advisory(
                 &advisory.advisory,
@@ -64,6 +114,58 @@ impl AdvisoryDetails {
             vulnerabilities,
             average_severity: advisory.average_severity.map(|sev| sev.into()),
             average_score: advisory.average_score,
+            warnings,
+            attachments,
+            comments,
+            supersedes,
+            superseded_by,
         })
     }
 }
+
+#[derive(Copy, Clone, Debug)]
+enum Supersedence {
+    Supersedes,
+    SupersededBy,
+}
+
+/// Follow the chain of supersedence of an advisory, in one direction.
+///
+/// Only advisories which weren't deleted take part. Advisories superseded by several others, or
+/// cycles, are reported only once.
+async fn supersedence_chain<C: ConnectionTrait>(
+    identifier: &str,
+    direction: Supersedence,
+    tx: &C,
+) -> Result<Vec<String>, Error> {
+    let mut result = Vec::<String>::new();
+    let mut next = vec![identifier.to_string()];
+
+    while !next.is_empty() {
+        let query = entity::advisory_supersedence::Entity::find()
+            .inner_join(entity::advisory::Entity)
+            .filter(entity::advisory::Column::DeletedAt.is_null())
+            .select_only();
+
+        let mut found: Vec<String> = match direction {
+            Supersedence::Supersedes => query
+                .filter(entity::advisory::Column::Identifier.is_in(next))
+                .column(entity::advisory_supersedence::Column::Supersedes),
+            Supersedence::SupersededBy => query
+                .filter(entity::advisory_supersedence::Column::Supersedes.is_in(next))
+                .column(entity::advisory::Column::Identifier),
+        }
+        .into_tuple()
+        .all(tx)
+        .await?;
+
+        found.sort();
+        found.dedup();
+        found.retain(|found| found != identifier && !result.contains(found));
+
+        result.extend(found.iter().cloned());
+        next = found;
+    }
+
+    Ok(result)
+}
@@ -1,13 +1,18 @@
+mod deletion;
 mod details;
+mod preview;
 mod summary;
 
+pub use deletion::*;
 pub use details::advisory_vulnerability::*;
 pub use details::*;
+pub use preview::*;
 pub use summary::*;
 
 use crate::{Error, organization::model::OrganizationSummary};
 use sea_orm::{ConnectionTrait, LoaderTrait, ModelTrait, prelude::Uuid};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use time::OffsetDateTime;
 use tracing::instrument;
 use trustify_common::memo::Memo;
@@ -52,9 +57,22 @@ pub struct AdvisoryHead {
 
     /// Informational labels attached by the system or users to this advisory.
     pub labels: Labels,
+
+    /// The external ID, as provided when uploading the advisory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
 }
 
 impl AdvisoryHead {
+    /// Compare by identifier, falling back to the UUID for advisories sharing an identifier.
+    ///
+    /// This is the order of advisories nested in other details.
+    pub fn cmp_identifier(&self, other: &Self) -> Ordering {
+        self.identifier
+            .cmp(&other.identifier)
+            .then_with(|| self.uuid.cmp(&other.uuid))
+    }
+
     #[instrument(skip_all, fields(advisory.id = ?advisory.id), err(level=tracing::Level::INFO))]
     pub async fn from_advisory<C: ConnectionTrait>(
         advisory: &advisory::Model,
@@ -81,6 +99,7 @@ impl AdvisoryHead {
             withdrawn: advisory.withdrawn,
             title: advisory.title.clone(),
             labels: advisory.labels.clone(),
+            external_id: advisory.external_id.clone(),
         })
     }
 
@@ -105,6 +124,7 @@ impl AdvisoryHead {
                 withdrawn: advisory.withdrawn,
                 title: advisory.title.clone(),
                 labels: advisory.labels.clone(),
+                external_id: advisory.external_id.clone(),
             })
         }
 
@@ -0,0 +1,473 @@
+use crate::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use trustify_module_ingestor::service::{Format, advisory::osv};
+use utoipa::ToSchema;
+
+/// The format of the document an advisory was ingested from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewFormat {
+    Csaf,
+    Cve,
+    Osv,
+}
+
+/// A rendering of an advisory document, independent of the format of the document.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AdvisoryPreview {
+    pub format: PreviewFormat,
+    /// The identifier of the document, as claimed by the document
+    pub id: String,
+    #[schema(required)]
+    pub title: Option<String>,
+    /// The date the document got published, as stated by the document
+    #[schema(required)]
+    pub published: Option<String>,
+    /// The date the document got last modified, as stated by the document
+    #[schema(required)]
+    pub modified: Option<String>,
+    /// Textual sections of the document, like the summary or legal notes
+    pub sections: Vec<PreviewSection>,
+    pub references: Vec<PreviewReference>,
+    /// The products mentioned by the document, nested by vendor, ecosystem, or CSAF branches
+    pub product_tree: Vec<PreviewProduct>,
+    pub vulnerabilities: Vec<PreviewVulnerability>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PreviewSection {
+    #[schema(required)]
+    pub title: Option<String>,
+    /// The category of the section, like `summary` or `description`
+    #[schema(required)]
+    pub category: Option<String>,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PreviewReference {
+    pub url: String,
+    #[schema(required)]
+    pub category: Option<String>,
+    #[schema(required)]
+    pub summary: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PreviewProduct {
+    /// The kind of node, like `vendor`, `product_name`, or `ecosystem`
+    pub category: String,
+    pub name: String,
+    /// The ID used for referring to the product from statuses and remediations
+    #[schema(required)]
+    pub product_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schema(no_recursion)]
+    pub children: Vec<PreviewProduct>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PreviewVulnerability {
+    #[schema(required)]
+    pub id: Option<String>,
+    #[schema(required)]
+    pub title: Option<String>,
+    pub sections: Vec<PreviewSection>,
+    /// The IDs of the products, by their status, like `fixed` or `known_affected`
+    pub product_status: BTreeMap<String, Vec<String>>,
+    pub remediations: Vec<PreviewRemediation>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PreviewRemediation {
+    /// The category of the remediation, like `vendor_fix` or `workaround`
+    pub category: String,
+    pub details: String,
+    #[schema(required)]
+    pub url: Option<String>,
+    /// The IDs of the products the remediation applies to
+    pub products: Vec<String>,
+}
+
+impl AdvisoryPreview {
+    /// Render a stored advisory document. Fields not known to the format's schema are ignored.
+    pub fn from_document(bytes: &[u8]) -> Result<Self, Error> {
+        let format = Format::advisory_from_bytes(bytes)?;
+
+        let document = match serde_json::from_slice::<Value>(bytes) {
+            Ok(document) => document,
+            // OSV documents may also be YAML
+            Err(_) if matches!(format, Format::OSV) => serde_json::to_value(osv::parse(bytes)?)
+                .map_err(|err| Error::Data(err.to_string()))?,
+            Err(err) => return Err(Error::Data(err.to_string())),
+        };
+
+        Ok(match format {
+            Format::CSAF => Self::from_csaf(&document),
+            Format::CVE => Self::from_cve(&document),
+            Format::OSV => Self::from_osv(&document),
+            other => return Err(Error::Data(format!("Not an advisory format: {other:?}"))),
+        })
+    }
+
+    fn from_csaf(document: &Value) -> Self {
+        let header = &document["document"];
+
+        let mut product_tree = array(&document["product_tree"]["full_product_names"])
+            .map(|product| csaf_product("product_name", product))
+            .collect::<Vec<_>>();
+        product_tree.extend(array(&document["product_tree"]["branches"]).map(csaf_branch));
+        product_tree.extend(
+            array(&document["product_tree"]["relationships"]).filter_map(|relationship| {
+                Some(csaf_product(
+                    &string(&relationship["category"])?,
+                    &relationship["full_product_name"],
+                ))
+            }),
+        );
+
+        let vulnerabilities = array(&document["vulnerabilities"])
+            .map(|vulnerability| PreviewVulnerability {
+                id: string(&vulnerability["cve"]),
+                title: string(&vulnerability["title"]),
+                sections: csaf_notes(&vulnerability["notes"]),
+                product_status: vulnerability["product_status"]
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .map(|(status, products)| (status.clone(), strings(products)))
+                    .collect(),
+                remediations: array(&vulnerability["remediations"])
+                    .map(|remediation| PreviewRemediation {
+                        category: string(&remediation["category"]).unwrap_or_default(),
+                        details: string(&remediation["details"]).unwrap_or_default(),
+                        url: string(&remediation["url"]),
+                        products: strings(&remediation["product_ids"]),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            format: PreviewFormat::Csaf,
+            id: string(&header["tracking"]["id"]).unwrap_or_default(),
+            title: string(&header["title"]),
+            published: string(&header["tracking"]["initial_release_date"]),
+            modified: string(&header["tracking"]["current_release_date"]),
+            sections: csaf_notes(&header["notes"]),
+            references: array(&header["references"])
+                .chain(array(&document["vulnerabilities"]).flat_map(|v| array(&v["references"])))
+                .filter_map(|reference| {
+                    Some(PreviewReference {
+                        url: string(&reference["url"])?,
+                        category: string(&reference["category"]),
+                        summary: string(&reference["summary"]),
+                    })
+                })
+                .collect(),
+            product_tree,
+            vulnerabilities,
+        }
+    }
+
+    fn from_cve(document: &Value) -> Self {
+        let metadata = &document["cveMetadata"];
+        let cna = &document["containers"]["cna"];
+        let id = string(&metadata["cveId"]).unwrap_or_default();
+
+        let mut product_tree = Vec::<PreviewProduct>::new();
+        let mut product_status = BTreeMap::<String, Vec<String>>::new();
+
+        for affected in array(&cna["affected"]) {
+            let vendor = string(&affected["vendor"]).unwrap_or_else(|| "n/a".into());
+            let product = string(&affected["product"])
+                .or_else(|| string(&affected["packageName"]))
+                .unwrap_or_else(|| "n/a".into());
+            let product_id = format!("{vendor}:{product}");
+
+            for version in array(&affected["versions"]) {
+                if let Some(status) = string(&version["status"]) {
+                    product_status
+                        .entry(status)
+                        .or_default()
+                        .push(format!("{product_id}:{}", cve_version(version)));
+                }
+            }
+
+            let node = PreviewProduct {
+                category: "product_name".into(),
+                name: product,
+                product_id: Some(product_id),
+                children: vec![],
+            };
+            match product_tree.iter_mut().find(|v| v.name == vendor) {
+                Some(entry) => entry.children.push(node),
+                None => product_tree.push(PreviewProduct {
+                    category: "vendor".into(),
+                    name: vendor,
+                    product_id: None,
+                    children: vec![node],
+                }),
+            }
+        }
+
+        let remediations = [("solutions", "vendor_fix"), ("workarounds", "workaround")]
+            .into_iter()
+            .flat_map(|(key, category)| {
+                english(&cna[key]).map(move |details| PreviewRemediation {
+                    category: category.into(),
+                    details,
+                    url: None,
+                    products: vec![],
+                })
+            })
+            .collect();
+
+        let sections = english(&cna["descriptions"])
+            .map(|text| PreviewSection {
+                title: None,
+                category: Some("description".into()),
+                text,
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            format: PreviewFormat::Cve,
+            id: id.clone(),
+            title: string(&cna["title"]),
+            published: string(&metadata["datePublished"]),
+            modified: string(&metadata["dateUpdated"]),
+            sections: sections.clone(),
+            references: array(&cna["references"])
+                .filter_map(|reference| {
+                    Some(PreviewReference {
+                        url: string(&reference["url"])?,
+                        category: array(&reference["tags"]).next().and_then(string),
+                        summary: string(&reference["name"]),
+                    })
+                })
+                .collect(),
+            product_tree,
+            vulnerabilities: vec![PreviewVulnerability {
+                id: Some(id),
+                title: string(&cna["title"]),
+                sections,
+                product_status,
+                remediations,
+            }],
+        }
+    }
+
+    fn from_osv(document: &Value) -> Self {
+        let id = string(&document["id"]).unwrap_or_default();
+
+        let mut product_tree = Vec::<PreviewProduct>::new();
+        let mut affected_products = Vec::<String>::new();
+        let mut remediations = Vec::<PreviewRemediation>::new();
+
+        for affected in array(&document["affected"]) {
+            let package = &affected["package"];
+            let ecosystem = string(&package["ecosystem"]).unwrap_or_else(|| "n/a".into());
+            let name = string(&package["name"]).unwrap_or_default();
+            let product_id =
+                string(&package["purl"]).unwrap_or_else(|| format!("{ecosystem}:{name}"));
+
+            let fixed = array(&affected["ranges"])
+                .flat_map(|range| array(&range["events"]))
+                .filter_map(|event| string(&event["fixed"]))
+                .collect::<Vec<_>>();
+            if !fixed.is_empty() {
+                remediations.push(PreviewRemediation {
+                    category: "vendor_fix".into(),
+                    details: format!("Fixed in: {}", fixed.join(", ")),
+                    url: None,
+                    products: vec![product_id.clone()],
+                });
+            }
+
+            // the same package may be listed multiple times, for different ranges
+            if affected_products.contains(&product_id) {
+                continue;
+            }
+            affected_products.push(product_id.clone());
+
+            let node = PreviewProduct {
+                category: "package".into(),
+                name,
+                product_id: Some(product_id),
+                children: vec![],
+            };
+            match product_tree.iter_mut().find(|e| e.name == ecosystem) {
+                Some(entry) => entry.children.push(node),
+                None => product_tree.push(PreviewProduct {
+                    category: "ecosystem".into(),
+                    name: ecosystem,
+                    product_id: None,
+                    children: vec![node],
+                }),
+            }
+        }
+
+        let sections = [
+            ("summary", &document["summary"]),
+            ("details", &document["details"]),
+        ]
+        .into_iter()
+        .filter_map(|(category, text)| {
+            Some(PreviewSection {
+                title: None,
+                category: Some(category.into()),
+                text: string(text)?,
+            })
+        })
+        .collect::<Vec<_>>();
+
+        Self {
+            format: PreviewFormat::Osv,
+            id: id.clone(),
+            title: string(&document["summary"]),
+            published: string(&document["published"]),
+            modified: string(&document["modified"]),
+            sections: sections.clone(),
+            references: array(&document["references"])
+                .filter_map(|reference| {
+                    Some(PreviewReference {
+                        url: string(&reference["url"])?,
+                        category: string(&reference["type"]).map(|t| t.to_lowercase()),
+                        summary: None,
+                    })
+                })
+                .collect(),
+            product_tree,
+            vulnerabilities: vec![PreviewVulnerability {
+                id: Some(id),
+                title: string(&document["summary"]),
+                sections,
+                product_status: BTreeMap::from_iter([("affected".into(), affected_products)]),
+                remediations,
+            }],
+        }
+    }
+}
+
+fn array(value: &Value) -> impl Iterator<Item = &Value> {
+    value.as_array().into_iter().flatten()
+}
+
+fn string(value: &Value) -> Option<String> {
+    value.as_str().map(ToString::to_string)
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    array(value).filter_map(string).collect()
+}
+
+/// The values of CVE descriptions, solutions, or workarounds, preferring English ones.
+fn english(value: &Value) -> impl Iterator<Item = String> {
+    let all = array(value).collect::<Vec<_>>();
+    let english = all
+        .iter()
+        .filter(|v| {
+            v["lang"]
+                .as_str()
+                .is_some_and(|lang| lang.starts_with("en"))
+        })
+        .copied()
+        .collect::<Vec<_>>();
+
+    let values = if english.is_empty() { all } else { english };
+    values.into_iter().filter_map(|v| string(&v["value"]))
+}
+
+/// Describe the versions of a CVE version entry, like `< 1.2.3` or `1.0 - 1.2`.
+fn cve_version(version: &Value) -> String {
+    let start = string(&version["version"]).unwrap_or_default();
+    match (
+        string(&version["lessThan"]),
+        string(&version["lessThanOrEqual"]),
+    ) {
+        (Some(end), _) => format!("{start} - <{end}"),
+        (None, Some(end)) => format!("{start} - {end}"),
+        (None, None) => start,
+    }
+}
+
+fn csaf_notes(notes: &Value) -> Vec<PreviewSection> {
+    array(notes)
+        .filter_map(|note| {
+            Some(PreviewSection {
+                title: string(&note["title"]),
+                category: string(&note["category"]),
+                text: string(&note["text"])?,
+            })
+        })
+        .collect()
+}
+
+fn csaf_product(category: &str, product: &Value) -> PreviewProduct {
+    PreviewProduct {
+        category: category.into(),
+        name: string(&product["name"]).unwrap_or_default(),
+        product_id: string(&product["product_id"]),
+        children: vec![],
+    }
+}
+
+fn csaf_branch(branch: &Value) -> PreviewProduct {
+    PreviewProduct {
+        category: string(&branch["category"]).unwrap_or_default(),
+        name: string(&branch["name"]).unwrap_or_default(),
+        product_id: string(&branch["product"]["product_id"]),
+        children: array(&branch["branches"]).map(csaf_branch).collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn osv_yaml() {
+        let document = r#"
+id: GHSA-0000-0000-0000
+modified: 2024-01-01T00:00:00Z
+summary: Something bad
+affected:
+  - package:
+      ecosystem: npm
+      name: foo
+    ranges:
+      - type: SEMVER
+        events:
+          - introduced: "0"
+          - fixed: "1.2.3"
+"#;
+
+        let preview = AdvisoryPreview::from_document(document.as_bytes()).unwrap();
+        assert_eq!(preview.format, PreviewFormat::Osv);
+        assert_eq!(preview.id, "GHSA-0000-0000-0000");
+        assert_eq!(preview.title.as_deref(), Some("Something bad"));
+        assert_eq!(preview.product_tree[0].name, "npm");
+        assert_eq!(preview.product_tree[0].children[0].name, "foo");
+        assert_eq!(
+            preview.vulnerabilities[0].remediations[0].details,
+            "Fixed in: 1.2.3"
+        );
+    }
+
+    #[test]
+    fn cve_versions() {
+        assert_eq!(cve_version(&json!({"version": "1.0"})), "1.0");
+        assert_eq!(
+            cve_version(&json!({"version": "1.0", "lessThan": "1.2"})),
+            "1.0 - <1.2"
+        );
+        assert_eq!(
+            cve_version(&json!({"version": "1.0", "lessThanOrEqual": "1.2"})),
+            "1.0 - 1.2"
+        );
+    }
+}
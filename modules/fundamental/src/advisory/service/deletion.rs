@@ -0,0 +1,327 @@
+use crate::{
+    Error,
+    advisory::{
+        model::{AdvisoryDeletion, AdvisoryDeletionToken},
+        service::AdvisoryService,
+    },
+    purl::service::PurlService,
+    tenant::Tenant,
+};
+use hex::ToHex;
+use hmac::{Hmac, Mac};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, EntityTrait, QueryFilter,
+    TransactionTrait,
+};
+use sea_query::Expr;
+use sha2::Sha256;
+use std::fmt::{self, Debug, Formatter};
+use time::{Duration, OffsetDateTime};
+use tracing::instrument;
+use trustify_common::db::Database;
+use trustify_entity::{
+    advisory_deletion,
+    document_audit::{Action, DocumentKind},
+};
+use trustify_module_ingestor::service::audit::Actor;
+use uuid::Uuid;
+
+/// The number of advisories deleted in one transaction.
+const BATCH_SIZE: usize = 100;
+
+/// The time a token, confirming a deletion, stays valid.
+const TOKEN_VALIDITY: Duration = Duration::minutes(15);
+
+/// The time after which a running deletion, which made no progress, is considered interrupted.
+const STALE_AFTER: Duration = Duration::minutes(30);
+
+/// The secret signing the tokens, which confirm the deletion of advisories.
+#[derive(Clone)]
+pub struct DeletionKey(Vec<u8>);
+
+impl DeletionKey {
+    /// Use the configured secret, or a random one if there is none.
+    ///
+    /// A random secret only verifies tokens issued by the same instance, before it got
+    /// restarted. Running more than one instance requires a configured secret.
+    pub fn new(secret: Option<&str>) -> Self {
+        match secret {
+            Some(secret) => Self(secret.as_bytes().to_vec()),
+            None => Self(rand::random::<[u8; 32]>().to_vec()),
+        }
+    }
+
+    /// Issue a token, confirming the deletion of exactly this set of advisories by the user.
+    fn issue(&self, ids: &[Uuid], user: Option<&str>, expires: OffsetDateTime) -> String {
+        let expires = expires.unix_timestamp();
+        let signature: String = self
+            .mac(ids, user, expires)
+            .finalize()
+            .into_bytes()
+            .encode_hex();
+        format!("{expires}.{signature}")
+    }
+
+    /// Verify a token, issued by [`Self::issue`].
+    fn verify(&self, token: &str, ids: &[Uuid], user: Option<&str>) -> Result<(), Error> {
+        let invalid = || Error::BadRequest("Invalid confirmation token".into());
+
+        let (expires, signature) = token.split_once('.').ok_or_else(invalid)?;
+        let expires: i64 = expires.parse().map_err(|_| invalid())?;
+        let signature = hex::decode(signature).map_err(|_| invalid())?;
+
+        if expires < OffsetDateTime::now_utc().unix_timestamp() {
+            return Err(Error::Conflict(
+                "The token expired, request a new token".into(),
+            ));
+        }
+
+        self.mac(ids, user, expires)
+            .verify_slice(&signature)
+            .map_err(|_| {
+                Error::Conflict(
+                    "The matching advisories changed, or the token is another user's".into(),
+                )
+            })
+    }
+
+    /// The signature doesn't depend on the order of the IDs, but changes as soon as advisories
+    /// get added to or removed from the set.
+    fn mac(&self, ids: &[Uuid], user: Option<&str>, expires: i64) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.0).expect("HMAC accepts keys of any length");
+
+        mac.update(&expires.to_be_bytes());
+        // tells apart a missing user from an empty one, and the user from the IDs
+        match user {
+            Some(user) => {
+                mac.update(&[1]);
+                mac.update(&(user.len() as u64).to_be_bytes());
+                mac.update(user.as_bytes());
+            }
+            None => mac.update(&[0]),
+        }
+
+        let mut ids = ids.to_vec();
+        ids.sort_unstable();
+        for id in ids {
+            mac.update(id.as_bytes());
+        }
+
+        mac
+    }
+}
+
+impl Debug for DeletionKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DeletionKey").field(&"***").finish()
+    }
+}
+
+/// Deletes all advisories matching a query in the background, once the deletion got confirmed.
+#[derive(Clone)]
+pub struct DeletionService {
+    db: Database,
+    advisories: AdvisoryService,
+    key: DeletionKey,
+}
+
+impl DeletionService {
+    pub fn new(db: Database, advisories: AdvisoryService, key: DeletionKey) -> Self {
+        Self {
+            db,
+            advisories,
+            key,
+        }
+    }
+
+    /// Issue a token to the actor, confirming the deletion of exactly these advisories.
+    pub fn confirmation(&self, ids: &[Uuid], actor: &Actor) -> AdvisoryDeletionToken {
+        let expires = OffsetDateTime::now_utc() + TOKEN_VALIDITY;
+        // only full seconds are part of the token
+        let expires = expires.replace_nanosecond(0).unwrap_or(expires);
+
+        AdvisoryDeletionToken {
+            total: ids.len() as u64,
+            token: self.key.issue(ids, actor.user.as_deref(), expires),
+            expires,
+        }
+    }
+
+    /// Start deleting the advisories in the background, returning the initial state.
+    ///
+    /// The token must have been issued to the actor, for exactly these advisories, and must not
+    /// have expired yet. Each batch of advisories is deleted in its own transaction, so that
+    /// deleted advisories stay deleted if a later batch fails.
+    #[instrument(skip(self, ids, token), err(level=tracing::Level::INFO))]
+    pub async fn start(
+        &self,
+        ids: Vec<Uuid>,
+        token: &str,
+        actor: Actor,
+        tenant: &Tenant,
+    ) -> Result<AdvisoryDeletion, Error> {
+        self.key.verify(token, &ids, actor.user.as_deref())?;
+
+        let model = advisory_deletion::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            state: Set(advisory_deletion::State::Running),
+            total: Set(ids.len() as i64),
+            deleted: Set(0),
+            tenant: Set(tenant.name().map(ToString::to_string)),
+            user_id: Set(actor.user.clone()),
+            started: Set(OffsetDateTime::now_utc()),
+            finished: Set(None),
+            heartbeat: Set(OffsetDateTime::now_utc()),
+            error: Set(None),
+        }
+        .insert(&self.db)
+        .await?;
+
+        log::info!("Starting deletion {}: {} advisories", model.id, model.total);
+
+        let service = self.clone();
+        let id = model.id;
+        tokio::spawn(async move {
+            let result = service.run(id, &ids, &actor).await;
+            if let Err(err) = service.complete(id, result).await {
+                log::warn!("Failed to record the outcome of deletion {id}: {err}");
+            }
+        });
+
+        Ok(model.into())
+    }
+
+    /// Mark running deletions as failed, which got interrupted by stopping the instance running
+    /// them. Returns the number of interrupted deletions.
+    ///
+    /// Intended to be called on startup, before any deletion gets started. With a single
+    /// instance, all running deletions got interrupted. Otherwise, only the ones which didn't make
+    /// progress for a while, as they may still be running on another instance.
+    pub async fn fail_interrupted(db: &Database, single_instance: bool) -> Result<u64, Error> {
+        let mut condition = Condition::all()
+            .add(advisory_deletion::Column::State.eq(advisory_deletion::State::Running));
+        if !single_instance {
+            condition = condition.add(
+                advisory_deletion::Column::Heartbeat.lt(OffsetDateTime::now_utc() - STALE_AFTER),
+            );
+        }
+
+        let result = advisory_deletion::Entity::update_many()
+            .filter(condition)
+            .col_expr(
+                advisory_deletion::Column::State,
+                Expr::value(advisory_deletion::State::Failed),
+            )
+            .col_expr(
+                advisory_deletion::Column::Finished,
+                Expr::value(OffsetDateTime::now_utc()),
+            )
+            .col_expr(
+                advisory_deletion::Column::Error,
+                Expr::value("Interrupted, the instance running the deletion stopped"),
+            )
+            .exec(db)
+            .await?;
+
+        if result.rows_affected > 0 {
+            log::warn!(
+                "Marked {} interrupted deletions as failed",
+                result.rows_affected
+            );
+        }
+
+        Ok(result.rows_affected)
+    }
+
+    /// Get the state of a deletion, `None` if there is none with this ID.
+    pub async fn fetch(
+        &self,
+        id: Uuid,
+        tenant: &Tenant,
+    ) -> Result<Option<AdvisoryDeletion>, Error> {
+        Ok(advisory_deletion::Entity::find_by_id(id)
+            .filter(tenant.column_condition(advisory_deletion::Column::Tenant))
+            .one(&self.db)
+            .await?
+            .map(AdvisoryDeletion::from))
+    }
+
+    /// Delete the advisories in batches, recording the progress and each deleted advisory.
+    ///
+    /// Advisories which are already gone are skipped. Orphaned PURLs are collected once, after
+    /// all advisories got deleted.
+    #[instrument(skip(self, ids, actor), err)]
+    async fn run(&self, id: Uuid, ids: &[Uuid], actor: &Actor) -> Result<(), Error> {
+        let mut deleted = 0;
+
+        for batch in ids.chunks(BATCH_SIZE) {
+            let tx = self.db.begin().await?;
+
+            let mut rows: i64 = 0;
+            for advisory in batch {
+                if self.advisories.delete_advisory(*advisory, &tx).await? > 0 {
+                    actor
+                        .record(
+                            Action::Deleted,
+                            DocumentKind::Advisory,
+                            *advisory,
+                            None,
+                            &tx,
+                        )
+                        .await?;
+                    rows += 1;
+                }
+            }
+
+            advisory_deletion::Entity::update_many()
+                .filter(advisory_deletion::Column::Id.eq(id))
+                .col_expr(
+                    advisory_deletion::Column::Deleted,
+                    Expr::col(advisory_deletion::Column::Deleted).add(rows),
+                )
+                .col_expr(
+                    advisory_deletion::Column::Heartbeat,
+                    Expr::value(OffsetDateTime::now_utc()),
+                )
+                .exec(&tx)
+                .await?;
+
+            tx.commit().await?;
+
+            deleted += rows;
+            log::info!(
+                "Deletion {id}: deleted {deleted} of {} advisories",
+                ids.len()
+            );
+        }
+
+        let tx = self.db.begin().await?;
+        let _ = PurlService::new().gc_purls(&tx).await; // ignore gc failure..
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Record the outcome of a deletion.
+    async fn complete(&self, id: Uuid, result: Result<(), Error>) -> Result<(), Error> {
+        let (state, error) = match result {
+            Ok(()) => (advisory_deletion::State::Succeeded, None),
+            Err(err) => (advisory_deletion::State::Failed, Some(err.to_string())),
+        };
+
+        advisory_deletion::ActiveModel {
+            id: Set(id),
+            state: Set(state),
+            finished: Set(Some(OffsetDateTime::now_utc())),
+            error: Set(error),
+            ..Default::default()
+        }
+        .update(&self.db)
+        .await?;
+
+        log::info!("Completed deletion {id}: {state:?}");
+
+        Ok(())
+    }
+}
@@ -1,3 +1,7 @@
+mod deletion;
+
+pub use deletion::*;
+
 use crate::{
     Error,
     advisory::model::{AdvisoryDetails, AdvisorySummary},
@@ -5,35 +9,49 @@ use crate::{
 use sea_orm::{
     ActiveModelTrait, ActiveValue::Set, ColumnTrait, ColumnTypeTrait, ConnectionTrait,
     DatabaseBackend, DbErr, EntityTrait, FromQueryResult, IntoActiveModel, IntoIdentity,
-    QueryResult, QuerySelect, QueryTrait, RelationTrait, Select, Statement, TransactionTrait,
+    PaginatorTrait, QueryFilter, QueryResult, QuerySelect, QueryTrait, RelationTrait, Select,
+    Statement, TransactionTrait,
 };
 use sea_query::{ColumnRef, ColumnType, Expr, Func, IntoColumnRef, IntoIden, JoinType, SimpleExpr};
+use tracing::{Instrument, Span, field::Empty, info_span, instrument};
 use trustify_common::{
     db::{
-        Database, UpdateDeprecatedAdvisory,
+        Database, RecomputeProductStatusSummary, UpdateDeprecatedAdvisory,
         limiter::LimiterAsModelTrait,
         multi_model::{FromQueryResultMultiModel, SelectIntoMultiModel},
-        query::{Columns, Filtering, Query},
+        query::{Columns, Filtering, LabelFilters, Query},
     },
     id::{Id, TrySelectForId},
     model::{Paginated, PaginatedResults},
+    purl::{Purl, PurlErr},
 };
 use trustify_entity::{
     advisory,
     cvss3::{self, Severity},
     labels::Labels,
-    organization, source_document,
+    organization, product_status, source_document,
 };
-use trustify_module_ingestor::common::{Deprecation, DeprecationExt};
+use trustify_module_ingestor::common::{AsOf, Deprecation, DeprecationExt};
 use uuid::Uuid;
 
+#[derive(Clone)]
 pub struct AdvisoryService {
     db: Database,
+    soft_delete: bool,
 }
 
 impl AdvisoryService {
     pub fn new(db: Database) -> Self {
-        Self { db }
+        Self {
+            db,
+            soft_delete: false,
+        }
+    }
+
+    /// Mark advisories as deleted when deleting them, instead of removing them.
+    pub fn with_soft_delete(mut self, soft_delete: bool) -> Self {
+        self.soft_delete = soft_delete;
+        self
     }
 
     pub async fn fetch_advisories<C: ConnectionTrait + Sync + Send>(
@@ -41,93 +59,56 @@ impl AdvisoryService {
         search: Query,
         paginated: Paginated,
         deprecation: Deprecation,
+        labels: impl Into<LabelFilters>,
         connection: &C,
     ) -> Result<PaginatedResults<AdvisorySummary>, Error> {
-        // To be able to ORDER or WHERE using a synthetic column, we must first
-        // SELECT col, extra_col FROM (SELECT col, random as extra_col FROM...)
-        // which involves mucking about inside the Select<E> to re-target from
-        // the original underlying table it expects the entity to live in.
-        let inner_query = advisory::Entity::find()
-            .with_deprecation(deprecation)
-            .left_join(cvss3::Entity)
-            .expr_as_(
-                SimpleExpr::FunctionCall(Func::avg(SimpleExpr::Column(
-                    cvss3::Column::Score.into_column_ref(),
-                ))),
-                "average_score",
-            )
-            .expr_as_(
-                SimpleExpr::FunctionCall(Func::cust("cvss3_severity".into_identity()).arg(
-                    SimpleExpr::FunctionCall(Func::avg(SimpleExpr::Column(
-                        cvss3::Column::Score.into_column_ref(),
-                    ))),
-                )),
-                "average_severity",
-            )
-            .group_by(advisory::Column::Id);
-
-        let mut outer_query = advisory::Entity::find();
-
-        // Alias the inner query as exactly the table the entity is expecting
-        // so that column aliases link up correctly.
-        QueryTrait::query(&mut outer_query)
-            .from_clear()
-            .from_subquery(inner_query.into_query(), "advisory".into_identity());
+        self.fetch_advisories_affecting(
+            search,
+            paginated,
+            deprecation,
+            AsOf::default(),
+            labels,
+            None,
+            connection,
+        )
+        .await
+    }
 
-        // And then proceed as usual.
-        let limiter = outer_query
-            .left_join(source_document::Entity)
-            .join(JoinType::LeftJoin, advisory::Relation::Issuer.def())
-            .column_as(
-                SimpleExpr::Column(ColumnRef::Column(
-                    "average_score".into_identity().into_iden(),
-                )),
-                "average_score",
-            )
-            .column_as(
-                SimpleExpr::Column(ColumnRef::Column(
-                    "average_severity".into_identity().into_iden(),
-                ))
-                .cast_as("TEXT".into_identity()),
-                "average_severity",
-            )
-            .filtering_with(
-                search,
-                Columns::from_entity::<advisory::Entity>()
-                    .add_column(
-                        source_document::Column::Ingested.into_identity(),
-                        source_document::Column::Ingested.def(),
-                    )
-                    .add_column("average_score", ColumnType::Decimal(None).def())
-                    .add_column(
-                        "average_severity",
-                        ColumnType::Enum {
-                            name: "cvss3_severity".into_identity().into_iden(),
-                            variants: vec![
-                                "none".into_identity().into_iden(),
-                                "low".into_identity().into_iden(),
-                                "medium".into_identity().into_iden(),
-                                "high".into_identity().into_iden(),
-                                "critical".into_identity().into_iden(),
-                            ],
-                        }
-                        .def(),
-                    )
-                    .translator(|f, op, v| match (f, v) {
-                        // v = "" for all sort fields
-                        ("average_severity", "") => Some(format!("average_score:{op}")),
-                        _ => None,
-                    }),
-            )?
+    /// Fetch advisories, limited to those affecting the version of a PURL, if one is provided.
+    ///
+    /// The PURL must have a version, which gets evaluated against the version ranges the
+    /// advisories mark as affected. For a point in time, only the advisories known at that time
+    /// are returned.
+    #[instrument(
+        skip(self, labels, connection),
+        fields(db.operation = "SELECT", db.collection.name = "advisory", total = Empty, rows = Empty),
+        err(level=tracing::Level::INFO)
+    )]
+    pub async fn fetch_advisories_affecting<C: ConnectionTrait + Sync + Send>(
+        &self,
+        search: Query,
+        paginated: Paginated,
+        deprecation: Deprecation,
+        as_of: AsOf,
+        labels: impl Into<LabelFilters>,
+        affects: Option<&Purl>,
+        connection: &C,
+    ) -> Result<PaginatedResults<AdvisorySummary>, Error> {
+        let limiter = select_advisories(search, deprecation, as_of, labels.into(), affects)?
             .try_limiting_as_multi_model::<AdvisoryCatcher>(
                 connection,
                 paginated.offset,
                 paginated.limit,
-            )?;
+            )?
+            .counting(paginated.count);
 
-        let total = limiter.total().await?;
+        let total = limiter.total().instrument(info_span!("count")).await?;
 
-        let items = limiter.fetch().await?;
+        let items = limiter.fetch().instrument(info_span!("fetch")).await?;
+
+        let span = Span::current();
+        span.record("total", total);
+        span.record("rows", items.len());
 
         Ok(PaginatedResults {
             total,
@@ -135,16 +116,66 @@ impl AdvisoryService {
         })
     }
 
+    /// Find the IDs of all advisories matching a query, including deprecated ones.
+    #[instrument(
+        skip(self, labels, connection),
+        fields(db.operation = "SELECT", db.collection.name = "advisory", rows = Empty),
+        err(level=tracing::Level::INFO)
+    )]
+    pub async fn matching_advisories<C: ConnectionTrait + Sync + Send>(
+        &self,
+        search: Query,
+        labels: impl Into<LabelFilters>,
+        connection: &C,
+    ) -> Result<Vec<Uuid>, Error> {
+        let ids: Vec<Uuid> = select_advisories(
+            search,
+            Deprecation::Consider,
+            AsOf::default(),
+            labels.into(),
+            None,
+        )?
+        .select_only()
+        .column(advisory::Column::Id)
+        .into_tuple()
+        .all(connection)
+        .await?;
+
+        Span::current().record("rows", ids.len());
+
+        Ok(ids)
+    }
+
     pub async fn fetch_advisory<C: ConnectionTrait + Sync + Send>(
         &self,
         id: Id,
         connection: &C,
+    ) -> Result<Option<AdvisoryDetails>, Error> {
+        self.fetch_advisory_as_of(id, AsOf::default(), connection)
+            .await
+    }
+
+    /// Fetch an advisory, as known at a point in time.
+    ///
+    /// Returns `None` if the advisory wasn't ingested yet, or was already deleted, at that time.
+    #[instrument(
+        skip(self, connection),
+        fields(db.operation = "SELECT", db.collection.name = "advisory", rows = Empty),
+        err(level=tracing::Level::INFO)
+    )]
+    pub async fn fetch_advisory_as_of<C: ConnectionTrait + Sync + Send>(
+        &self,
+        id: Id,
+        as_of: AsOf,
+        connection: &C,
     ) -> Result<Option<AdvisoryDetails>, Error> {
         // To be able to ORDER or WHERE using a synthetic column, we must first
         // SELECT col, extra_col FROM (SELECT col, random as extra_col FROM...)
         // which involves mucking about inside the Select<E> to re-target from
         // the original underlying table it expects the entity to live in.
         let inner_query = advisory::Entity::find()
+            .filter(as_of.not_deleted())
+            .filter(as_of.condition(advisory::Column::Id, Deprecation::Consider))
             .left_join(cvss3::Entity)
             .expr_as_(
                 SimpleExpr::FunctionCall(Func::avg(SimpleExpr::Column(
@@ -191,6 +222,8 @@ impl AdvisoryService {
             .one(connection)
             .await?;
 
+        Span::current().record("rows", usize::from(results.is_some()));
+
         if let Some(catcher) = results {
             Ok(Some(
                 AdvisoryDetails::from_entity(&catcher, connection).await?,
@@ -200,55 +233,127 @@ impl AdvisoryService {
         }
     }
 
+    /// Check if an advisory exists.
+    ///
+    /// This excludes advisories marked as deleted, as uploading them again restores them.
+    pub async fn exists<C: ConnectionTrait>(&self, id: Id, connection: &C) -> Result<bool, Error> {
+        Ok(advisory::Entity::find()
+            .left_join(source_document::Entity)
+            .filter(advisory::Column::DeletedAt.is_null())
+            .try_filter(id)?
+            .count(connection)
+            .await?
+            > 0)
+    }
+
     /// delete one advisory
+    ///
+    /// If soft deletion is enabled, the advisory is only marked as deleted. It can then be
+    /// restored using [`Self::restore_advisory`].
+    #[instrument(
+        skip(self, connection),
+        fields(db.operation = "DELETE", db.collection.name = "advisory", rows = Empty),
+        err(level=tracing::Level::INFO)
+    )]
     pub async fn delete_advisory<C: ConnectionTrait>(
         &self,
         id: Uuid,
         connection: &C,
     ) -> Result<u64, Error> {
-        let stmt = Statement::from_sql_and_values(
-            connection.get_database_backend(),
-            r#"DELETE FROM advisory WHERE id=$1 RETURNING identifier"#,
-            [id.into()],
-        );
+        let sql = if self.soft_delete {
+            r#"UPDATE advisory SET deleted_at = now() WHERE id=$1 AND deleted_at IS NULL RETURNING identifier"#
+        } else {
+            r#"DELETE FROM advisory WHERE id=$1 RETURNING identifier"#
+        };
+
+        self.update_and_deprecate(id, sql, connection).await
+    }
+
+    /// restore one advisory, which was marked as deleted
+    #[instrument(
+        skip(self, connection),
+        fields(db.operation = "UPDATE", db.collection.name = "advisory", rows = Empty),
+        err(level=tracing::Level::INFO)
+    )]
+    pub async fn restore_advisory<C: ConnectionTrait>(
+        &self,
+        id: Uuid,
+        connection: &C,
+    ) -> Result<u64, Error> {
+        self.update_and_deprecate(
+            id,
+            r#"UPDATE advisory SET deleted_at = NULL WHERE id=$1 AND deleted_at IS NOT NULL RETURNING identifier"#,
+            connection,
+        )
+        .await
+    }
+
+    /// Run a statement on one advisory, returning its identifier, and re-evaluate the deprecation
+    /// of the advisories sharing the identifier, as well as the effective product statuses.
+    async fn update_and_deprecate<C: ConnectionTrait>(
+        &self,
+        id: Uuid,
+        sql: &str,
+        connection: &C,
+    ) -> Result<u64, Error> {
+        // collected up front, deleting the advisory deletes its statements too
+        let vulnerabilities: Vec<String> = product_status::Entity::find()
+            .select_only()
+            .distinct()
+            .column(product_status::Column::VulnerabilityId)
+            .filter(product_status::Column::AdvisoryId.eq(id))
+            .into_tuple()
+            .all(connection)
+            .await?;
+
+        let stmt =
+            Statement::from_sql_and_values(connection.get_database_backend(), sql, [id.into()]);
 
         let result = connection.query_all(stmt).await?;
         let rows_affected = result.len();
+        Span::current().record("rows", rows_affected);
 
         for row in result {
             let identifier = row.try_get_by_index::<String>(0)?;
             UpdateDeprecatedAdvisory::execute(connection, &identifier).await?;
         }
 
+        if rows_affected > 0 {
+            for vulnerability in &vulnerabilities {
+                RecomputeProductStatusSummary::execute(connection, Some(vulnerability), None)
+                    .await?;
+            }
+        }
+
         Ok(rows_affected as u64)
     }
 
     /// Set the labels of an advisory
     ///
-    /// Returns `Ok(Some(()))` if a document was found and updated. If no document was found, it will
-    /// return `Ok(None)`.
+    /// Returns the ID of the document if it was found and updated. If no document was found, it
+    /// will return `Ok(None)`.
     pub async fn set_labels<C: ConnectionTrait>(
         &self,
         id: Id,
         labels: Labels,
         connection: &C,
-    ) -> Result<Option<()>, Error> {
+    ) -> Result<Option<Uuid>, Error> {
         let result = advisory::Entity::update_many()
             .try_filter(id)?
             .col_expr(advisory::Column::Labels, Expr::value(labels))
-            .exec(connection)
+            .exec_with_returning(connection)
             .await?;
 
-        Ok((result.rows_affected > 0).then_some(()))
+        Ok(result.into_iter().next().map(|advisory| advisory.id))
     }
 
     /// Update the labels of an advisory
     ///
-    /// Returns `Ok(Some(()))` if a document was found and updated. If no document was found, it will
-    /// return `Ok(None)`.
+    /// Returns the ID of the document if it was found and updated. If no document was found, it
+    /// will return `Ok(None)`.
     ///
     /// The function will handle its own transaction.
-    pub async fn update_labels<F>(&self, id: Id, mutator: F) -> Result<Option<()>, Error>
+    pub async fn update_labels<F>(&self, id: Id, mutator: F) -> Result<Option<Uuid>, Error>
     where
         F: FnOnce(Labels) -> Labels,
     {
@@ -281,7 +386,7 @@ impl AdvisoryService {
 
         // store
 
-        result.update(&tx).await?;
+        let result = result.update(&tx).await?;
 
         // commit
 
@@ -289,7 +394,7 @@ impl AdvisoryService {
 
         // return
 
-        Ok(Some(()))
+        Ok(Some(result.id))
     }
 }
 
@@ -329,3 +434,136 @@ impl FromQueryResultMultiModel for AdvisoryCatcher {
 
 #[cfg(test)]
 pub mod test;
+
+/// Build the query for advisories, limited to those affecting the version of a PURL, if one is
+/// provided.
+fn select_advisories(
+    search: Query,
+    deprecation: Deprecation,
+    as_of: AsOf,
+    labels: LabelFilters,
+    affects: Option<&Purl>,
+) -> Result<Select<advisory::Entity>, Error> {
+    // To be able to ORDER or WHERE using a synthetic column, we must first
+    // SELECT col, extra_col FROM (SELECT col, random as extra_col FROM...)
+    // which involves mucking about inside the Select<E> to re-target from
+    // the original underlying table it expects the entity to live in.
+    let mut inner_query = advisory::Entity::find()
+        .filter(as_of.not_deleted())
+        .filter(labels.into_condition(advisory::Column::Labels))
+        .with_deprecation(as_of.deprecation(deprecation))
+        .filter(as_of.condition(advisory::Column::Id, deprecation))
+        .left_join(cvss3::Entity)
+        .expr_as_(
+            SimpleExpr::FunctionCall(Func::avg(SimpleExpr::Column(
+                cvss3::Column::Score.into_column_ref(),
+            ))),
+            "average_score",
+        )
+        .expr_as_(
+            SimpleExpr::FunctionCall(Func::cust("cvss3_severity".into_identity()).arg(
+                SimpleExpr::FunctionCall(Func::avg(SimpleExpr::Column(
+                    cvss3::Column::Score.into_column_ref(),
+                ))),
+            )),
+            "average_severity",
+        )
+        .group_by(advisory::Column::Id);
+
+    if let Some(purl) = affects {
+        inner_query = inner_query.filter(affecting(purl)?);
+    }
+
+    let mut outer_query = advisory::Entity::find();
+
+    // Alias the inner query as exactly the table the entity is expecting
+    // so that column aliases link up correctly.
+    QueryTrait::query(&mut outer_query)
+        .from_clear()
+        .from_subquery(inner_query.into_query(), "advisory".into_identity());
+
+    // And then proceed as usual.
+    Ok(outer_query
+            .left_join(source_document::Entity)
+            .join(JoinType::LeftJoin, advisory::Relation::Issuer.def())
+            .column_as(
+                SimpleExpr::Column(ColumnRef::Column(
+                    "average_score".into_identity().into_iden(),
+                )),
+                "average_score",
+            )
+            .column_as(
+                SimpleExpr::Column(ColumnRef::Column(
+                    "average_severity".into_identity().into_iden(),
+                ))
+                .cast_as("TEXT".into_identity()),
+                "average_severity",
+            )
+            .filtering_with(
+                search,
+                Columns::from_entity::<advisory::Entity>()
+                    .add_column(
+                        source_document::Column::Ingested.into_identity(),
+                        source_document::Column::Ingested.def(),
+                    )
+                    .add_column("average_score", ColumnType::Decimal(None).def())
+                    .add_column(
+                        "average_severity",
+                        ColumnType::Enum {
+                            name: "cvss3_severity".into_identity().into_iden(),
+                            variants: vec![
+                                "none".into_identity().into_iden(),
+                                "low".into_identity().into_iden(),
+                                "medium".into_identity().into_iden(),
+                                "high".into_identity().into_iden(),
+                                "critical".into_identity().into_iden(),
+                            ],
+                        }
+                        .def(),
+                    )
+                    // the number of warnings, reported while ingesting the advisory
+                    .add_expr(
+                        "warnings",
+                        Expr::cust(
+                            r#"(SELECT COUNT(*) FROM "ingestion_warning" WHERE "ingestion_warning"."advisory_id" = "advisory"."id")::integer"#,
+                        ),
+                        ColumnType::Integer,
+                    )
+                    // whether the advisory is superseded by another one, which wasn't deleted
+                    .add_expr(
+                        "superseded",
+                        Expr::cust(
+                            r#"EXISTS (SELECT 1 FROM "advisory_supersedence" JOIN "advisory" AS "superseding" ON "superseding"."id" = "advisory_supersedence"."advisory_id" WHERE "advisory_supersedence"."supersedes" = "advisory"."identifier" AND "superseding"."deleted_at" IS NULL)"#,
+                        ),
+                        ColumnType::Boolean,
+                    )
+                    .translator(|f, op, v| match (f, v) {
+                        // v = "" for all sort fields
+                        ("average_severity", "") => Some(format!("average_score:{op}")),
+                        _ => None,
+                    }),
+            )?)
+}
+
+/// A condition, selecting advisories which mark the version of the PURL as affected.
+fn affecting(purl: &Purl) -> Result<SimpleExpr, Error> {
+    let Some(version) = &purl.version else {
+        return Err(Error::Purl(PurlErr::MissingVersion(purl.to_string())));
+    };
+
+    // PURLs are stored in their canonical form, so are their IDs
+    Ok(Expr::cust_with_values(
+        r#""advisory"."id" IN (
+            SELECT "purl_status"."advisory_id" FROM "purl_status"
+                JOIN "version_range" ON "version_range"."id" = "purl_status"."version_range_id"
+                JOIN "status" ON "status"."id" = "purl_status"."status_id"
+            WHERE "purl_status"."base_purl_id" = $1
+                AND "status"."slug" = 'affected'
+                AND version_matches($2, "version_range".*)
+        )"#,
+        [
+            sea_orm::Value::from(purl.package_uuid()),
+            sea_orm::Value::from(version.clone()),
+        ],
+    ))
+}
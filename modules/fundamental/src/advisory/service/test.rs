@@ -9,7 +9,7 @@ use trustify_cvss::cvss3::{
     AttackComplexity, AttackVector, Availability, Confidentiality, Cvss3Base, Integrity,
     PrivilegesRequired, Scope, UserInteraction, severity::Severity,
 };
-use trustify_entity::version_scheme::VersionScheme;
+use trustify_entity::{advisory_deletion, version_scheme::VersionScheme};
 use trustify_module_ingestor::graph::Outcome;
 use trustify_module_ingestor::graph::advisory::{
     AdvisoryContext, AdvisoryInformation,
@@ -77,7 +77,7 @@ async fn all_advisories(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     let fetch = AdvisoryService::new(ctx.db.clone());
     let fetched = fetch
-        .fetch_advisories(q(""), Paginated::default(), Default::default(), &ctx.db)
+        .fetch_advisories(q(""), Paginated::default(), Default::default(), (), &ctx.db)
         .await?;
 
     assert_eq!(fetched.total, 2);
@@ -99,6 +99,7 @@ async fn all_advisories_filtered_by_average_score(
             q("average_score>8"),
             Paginated::default(),
             Default::default(),
+            (),
             &ctx.db,
         )
         .await?;
@@ -122,6 +123,7 @@ async fn all_advisories_filtered_by_average_severity(
             q("average_severity>=critical"),
             Paginated::default(),
             Default::default(),
+            (),
             &ctx.db,
         )
         .await?;
@@ -230,6 +232,79 @@ async fn single_advisory(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn single_advisory_ordered(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let digests = Digests::digest("RHSA-1");
+
+    let advisory = ingest_sample_advisory(ctx, "RHSA-1", "RHSA-1").await?;
+    for id in ["CVE-456", "CVE-123", "CVE-789"] {
+        advisory.link_to_vulnerability(id, None, &ctx.db).await?;
+    }
+
+    let fetch = AdvisoryService::new(ctx.db.clone());
+    let fetched = fetch
+        .fetch_advisory(Id::sha256(&digests.sha256), &ctx.db)
+        .await?
+        .expect("advisory must be found");
+
+    let ids = fetched
+        .vulnerabilities
+        .iter()
+        .map(|vuln| vuln.head.head.identifier.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["CVE-123", "CVE-456", "CVE-789"]);
+
+    Ok(())
+}
+
+/// Ensure fetching the details doesn't observe changes committed in between its queries.
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn single_advisory_snapshot(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let digests = Digests::digest("RHSA-1");
+
+    let advisory = ingest_sample_advisory(ctx, "RHSA-1", "RHSA-1").await?;
+    advisory
+        .link_to_vulnerability("CVE-123", None, &ctx.db)
+        .await?;
+
+    let fetch = AdvisoryService::new(ctx.db.clone());
+    let id = Id::sha256(&digests.sha256);
+
+    let tx = ctx.db.begin_read().await?;
+    let before = fetch
+        .fetch_advisory(id.clone(), &tx)
+        .await?
+        .expect("advisory must be found");
+    assert_eq!(before.vulnerabilities.len(), 1);
+
+    // change the advisory while the snapshot is being held
+
+    advisory
+        .link_to_vulnerability("CVE-456", None, &ctx.db)
+        .await?;
+
+    let after = fetch
+        .fetch_advisory(id.clone(), &tx)
+        .await?
+        .expect("advisory must be found");
+    assert_eq!(
+        serde_json::to_value(&before)?,
+        serde_json::to_value(&after)?
+    );
+
+    drop(tx);
+
+    let current = fetch
+        .fetch_advisory(id, &ctx.db)
+        .await?
+        .expect("advisory must be found");
+    assert_eq!(current.vulnerabilities.len(), 2);
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn delete_advisory(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
@@ -297,3 +372,54 @@ async fn delete_advisory(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn fail_interrupted_deletions(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let now = OffsetDateTime::now_utc();
+    let mut ids = vec![];
+    // one deletion making progress recently, and one stuck for an hour
+    for heartbeat in [now, now - time::Duration::hours(1)] {
+        let id = Uuid::now_v7();
+        advisory_deletion::ActiveModel {
+            id: Set(id),
+            state: Set(advisory_deletion::State::Running),
+            total: Set(10),
+            deleted: Set(0),
+            tenant: Set(None),
+            user_id: Set(None),
+            started: Set(now - time::Duration::hours(2)),
+            finished: Set(None),
+            heartbeat: Set(heartbeat),
+            error: Set(None),
+        }
+        .insert(&ctx.db)
+        .await?;
+        ids.push(id);
+    }
+
+    let state = |id| async move {
+        Ok::<_, anyhow::Error>(
+            advisory_deletion::Entity::find_by_id(id)
+                .one(&ctx.db)
+                .await?
+                .map(|deletion| deletion.state),
+        )
+    };
+
+    // other instances may still be running the recent one
+
+    assert_eq!(DeletionService::fail_interrupted(&ctx.db, false).await?, 1);
+    assert_eq!(
+        state(ids[0]).await?,
+        Some(advisory_deletion::State::Running)
+    );
+    assert_eq!(state(ids[1]).await?, Some(advisory_deletion::State::Failed));
+
+    // a single instance is running none of them
+
+    assert_eq!(DeletionService::fail_interrupted(&ctx.db, true).await?, 1);
+    assert_eq!(state(ids[0]).await?, Some(advisory_deletion::State::Failed));
+
+    Ok(())
+}
@@ -5,8 +5,11 @@ mod test;
 use crate::{
     Error,
     ai::{
-        model::{AiFlags, AiTool, ChatMessage, ChatState, Conversation, ConversationSummary},
-        service::AiService,
+        model::{
+            AiFlags, AiTool, ChatMessage, ChatState, CompletionEvent, Conversation,
+            ConversationSummary, ToolInvocation,
+        },
+        service::{AiService, backend::LlmConfig, tools::guard::Caller},
     },
 };
 use actix_web::{
@@ -16,18 +19,25 @@ use actix_web::{
 };
 use itertools::Itertools;
 use time::OffsetDateTime;
+use tokio::sync::mpsc;
 use trustify_auth::{Ai, authenticator::user::UserDetails, authorizer::Require};
 use trustify_common::{
     db::{Database, query::Query},
+    error::ErrorInformation,
     model::{Paginated, PaginatedResults},
 };
 use uuid::Uuid;
 
-pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
-    let service = AiService::new(db.clone());
+pub fn configure(
+    config: &mut utoipa_actix_web::service_config::ServiceConfig,
+    db: Database,
+    llm: LlmConfig,
+) {
+    let service = AiService::with_config(db.clone(), llm);
     config
         .app_data(web::Data::new(service))
         .service(completions)
+        .service(completions_stream)
         .service(flags)
         .service(tools)
         .service(tool_call)
@@ -35,7 +45,10 @@ pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, d
         .service(update_conversation)
         .service(list_conversations)
         .service(get_conversation)
-        .service(delete_conversation);
+        .service(delete_conversation)
+        .service(resume_conversation)
+        .service(list_conversation_messages)
+        .service(list_tool_invocations);
 }
 
 #[utoipa::path(
@@ -44,26 +57,80 @@ pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, d
     request_body = ChatState,
     responses(
         (status = 200, description = "The resulting completion", body = ChatState),
-        (status = 400, description = "The request was invalid"),
-        (status = 404, description = "The AI service is not enabled")
+        (status = 400, description = "The request was invalid", body = ErrorInformation),
+        (status = 404, description = "The AI service is not enabled", body = ErrorInformation)
     )
 )]
 #[post("/v2/ai/completions")]
 pub async fn completions(
     service: web::Data<AiService>,
     request: web::Json<ChatState>,
+    caller: Caller,
     _: Require<Ai>,
 ) -> actix_web::Result<impl Responder> {
-    let response = service.completions(&request).await?;
+    let response = service.completions(&request, &caller).await?;
     Ok(HttpResponse::Ok().json(response))
 }
 
+#[utoipa::path(
+    tag = "ai",
+    operation_id = "completionsStream",
+    request_body = ChatState,
+    responses(
+        (status = 200, description = "The events of the completion, as server-sent events", body = CompletionEvent, content_type = "text/event-stream"),
+        (status = 400, description = "The request was invalid", body = ErrorInformation),
+        (status = 404, description = "The AI service is not enabled", body = ErrorInformation)
+    )
+)]
+#[post("/v2/ai/completions/stream")]
+pub async fn completions_stream(
+    service: web::Data<AiService>,
+    request: web::Json<ChatState>,
+    caller: Caller,
+    _: Require<Ai>,
+) -> actix_web::Result<impl Responder> {
+    if !service.completions_enabled() {
+        return Err(Error::NotFound("AI service is not enabled".to_string()).into());
+    }
+
+    let service = service.into_inner();
+    let request = request.into_inner();
+    let (tx, rx) = mpsc::channel(16);
+
+    actix_web::rt::spawn(async move {
+        let event = match service
+            .completions_stream(&request, &caller, tx.clone())
+            .await
+        {
+            Ok(state) => CompletionEvent::Done { state },
+            Err(err) => CompletionEvent::Error {
+                message: err.to_string(),
+            },
+        };
+        let _ = tx.send(event).await;
+    });
+
+    let events = futures_util::stream::unfold(rx, |mut rx| async move {
+        let event = rx.recv().await?;
+        let data = serde_json::to_string(&event).unwrap_or_default();
+        Some((
+            Ok::<_, actix_web::Error>(web::Bytes::from(format!("data: {data}\n\n"))),
+            rx,
+        ))
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(header::CacheControl(vec![header::CacheDirective::NoCache]))
+        .streaming(events))
+}
+
 #[utoipa::path(
     tag = "ai",
     operation_id = "aiFlags",
     responses(
         (status = 200, description = "The resulting Flags", body = AiFlags),
-        (status = 404, description = "The AI service is not enabled")
+        (status = 404, description = "The AI service is not enabled", body = ErrorInformation)
     )
 )]
 #[get("/v2/ai/flags")]
@@ -82,18 +149,22 @@ pub async fn flags(
     operation_id = "aiTools",
     responses(
         (status = 200, description = "The resulting list of tools", body = Vec<AiTool>),
-        (status = 404, description = "The AI service is not enabled")
+        (status = 404, description = "The AI service is not enabled", body = ErrorInformation)
     )
 )]
 #[get("/v2/ai/tools")]
-// Gets the list of tools that are available to assist AI services.
+// Gets the list of tools that are available to assist AI services, and that the user has the
+// permissions for.
 pub async fn tools(
     service: web::Data<AiService>,
+    caller: Caller,
     _: Require<Ai>,
 ) -> actix_web::Result<impl Responder> {
     let tools = &service
-        .local_tools
+        .local_tools(&caller)
         .iter()
+        .filter(|tool| tool.permitted(&caller))
+        .map(|tool| &tool.tool)
         .map(|tool| AiTool {
             name: tool.name(),
             description: tool.description(),
@@ -112,8 +183,9 @@ pub async fn tools(
     ),
     responses(
         (status = 200, description = "The result of the tool call", body = String, content_type = "text/plain"),
-        (status = 400, description = "The tool request was invalid"),
-        (status = 404, description = "The tool was not found")
+        (status = 400, description = "The tool request was invalid", body = ErrorInformation),
+        (status = 403, description = "The user is missing permissions required by the tool", body = ErrorInformation),
+        (status = 404, description = "The tool was not found", body = ErrorInformation)
     )
 )]
 #[post("/v2/ai/tools/{name}")]
@@ -121,15 +193,19 @@ pub async fn tool_call(
     service: web::Data<AiService>,
     name: web::Path<String>,
     request: String,
+    caller: Caller,
     _: Require<Ai>,
 ) -> actix_web::Result<impl Responder> {
     let tool = service
-        .local_tools
-        .iter()
-        .find(|tool| tool.name() == name.clone())
+        .local_tools(&caller)
+        .into_iter()
+        .find(|tool| tool.tool.name() == name.clone())
         .ok_or_else(|| actix_web::error::ErrorNotFound("Tool not found"))?;
 
+    caller.require(&tool)?;
+
     let result = tool
+        .tool
         .call(request.as_str())
         .await
         .map_err(|e| Error::BadRequest(e.to_string()))?;
@@ -144,8 +220,8 @@ pub async fn tool_call(
     operation_id = "createConversation",
     responses(
         (status = 200, description = "The resulting conversation", body = Conversation),
-        (status = 400, description = "The request was invalid"),
-        (status = 404, description = "The AI service is not enabled")
+        (status = 400, description = "The request was invalid", body = ErrorInformation),
+        (status = 404, description = "The AI service is not enabled", body = ErrorInformation)
     )
 )]
 #[post("/v2/ai/conversations")]
@@ -182,8 +258,8 @@ fn to_offset_date_time(uuid: Uuid) -> Result<OffsetDateTime, Error> {
     request_body = Vec<ChatMessage>,
     responses(
         (status = 200, description = "The resulting conversation", body = Conversation),
-        (status = 400, description = "The request was invalid"),
-        (status = 404, description = "The AI service is not enabled or the conversation was not found")
+        (status = 400, description = "The request was invalid", body = ErrorInformation),
+        (status = 404, description = "The AI service is not enabled or the conversation was not found", body = ErrorInformation)
     )
 )]
 #[put("/v2/ai/conversations/{id}")]
@@ -194,19 +270,22 @@ pub async fn update_conversation(
     web::Header(if_match): web::Header<IfMatch>,
     user: UserDetails,
     request: web::Json<Vec<ChatMessage>>,
+    caller: Caller,
     _: Require<Ai>,
 ) -> actix_web::Result<impl Responder> {
     let user_id = user.id;
-    let seq = match &if_match {
-        IfMatch::Any => None,
-        IfMatch::Items(items) => items
-            .first()
-            .and_then(|etag| etag.tag().parse::<i32>().ok()),
-    };
+    let seq = if_match_seq(&if_match);
     let conversation_id = id.into_inner();
 
     let (conversation, messages) = service
-        .upsert_conversation(conversation_id, user_id, &request, seq, db.as_ref())
+        .upsert_conversation(
+            conversation_id,
+            user_id,
+            &request,
+            seq,
+            &caller,
+            db.as_ref(),
+        )
         .await?;
 
     let conversation = Conversation {
@@ -228,7 +307,7 @@ pub async fn update_conversation(
     ),
     responses(
         (status = 200, description = "The resulting list of conversation summaries", body = PaginatedResults<ConversationSummary>),
-        (status = 404, description = "The AI service is not enabled")
+        (status = 404, description = "The AI service is not enabled", body = ErrorInformation)
     )
 )]
 #[get("/v2/ai/conversations")]
@@ -273,8 +352,8 @@ pub async fn list_conversations(
         (status = 200, description = "The resulting conversation", body = Conversation, headers(
             ("etag" = String, description = "Sequence ID")
         )),
-        (status = 400, description = "The request was invalid"),
-        (status = 404, description = "The AI service is not enabled")
+        (status = 400, description = "The request was invalid", body = ErrorInformation),
+        (status = 404, description = "The AI service is not enabled", body = ErrorInformation)
     )
 )]
 #[get("/v2/ai/conversations/{id}")]
@@ -333,8 +412,8 @@ pub async fn get_conversation(
     ),
     responses(
         (status = 200, description = "The resulting conversation", body = Conversation),
-        (status = 400, description = "The request was invalid"),
-        (status = 404, description = "The AI service is not enabled or the conversation was not found")
+        (status = 400, description = "The request was invalid", body = ErrorInformation),
+        (status = 404, description = "The AI service is not enabled or the conversation was not found", body = ErrorInformation)
     )
 )]
 #[delete("/v2/ai/conversations/{id}")]
@@ -381,3 +460,143 @@ pub async fn delete_conversation(
         }
     }
 }
+
+fn if_match_seq(if_match: &IfMatch) -> Option<i32> {
+    match if_match {
+        IfMatch::Any => None,
+        IfMatch::Items(items) => items
+            .first()
+            .and_then(|etag| etag.tag().parse::<i32>().ok()),
+    }
+}
+
+/// Ensure that a conversation exists and belongs to the user.
+async fn require_conversation(
+    service: &AiService,
+    db: &Database,
+    id: Uuid,
+    user_id: &str,
+) -> Result<(), Error> {
+    match service.fetch_conversation(id, db).await? {
+        Some((conversation, _)) if conversation.user_id == user_id => Ok(()),
+        // make this error look like a not found error to avoid leaking
+        // existence of the conversation
+        _ => Err(Error::NotFound("conversation not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    tag = "ai",
+    operation_id = "resumeConversation",
+    params(
+        ("id", Path, description = "Opaque ID of the conversation"),
+        ("if-match"=Option<String>, Header, description = "The revision to update")
+    ),
+    request_body(content = Vec<ChatMessage>, description = "The new messages of the user, without the previous messages of the conversation"),
+    responses(
+        (status = 200, description = "The resulting conversation", body = Conversation),
+        (status = 400, description = "The request was invalid", body = ErrorInformation),
+        (status = 404, description = "The AI service is not enabled or the conversation was not found", body = ErrorInformation)
+    )
+)]
+#[post("/v2/ai/conversations/{id}/messages")]
+pub async fn resume_conversation(
+    service: web::Data<AiService>,
+    db: web::Data<Database>,
+    id: web::Path<Uuid>,
+    web::Header(if_match): web::Header<IfMatch>,
+    user: UserDetails,
+    request: web::Json<Vec<ChatMessage>>,
+    caller: Caller,
+    _: Require<Ai>,
+) -> actix_web::Result<impl Responder> {
+    let seq = if_match_seq(&if_match);
+
+    let Some((conversation, messages)) = service
+        .resume_conversation(
+            id.into_inner(),
+            user.id,
+            &request,
+            seq,
+            &caller,
+            db.as_ref(),
+        )
+        .await?
+    else {
+        return Err(Error::NotFound("conversation not found".to_string()).into());
+    };
+
+    Ok(HttpResponse::Ok()
+        .append_header((
+            header::ETAG,
+            ETag(EntityTag::new_strong(format!("{}", conversation.seq))),
+        ))
+        .json(Conversation {
+            id: conversation.id,
+            updated_at: conversation.updated_at,
+            messages,
+            seq: conversation.seq,
+        }))
+}
+
+#[utoipa::path(
+    tag = "ai",
+    operation_id = "listConversationMessages",
+    params(
+        ("id", Path, description = "Opaque ID of the conversation"),
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "The messages of the conversation, oldest first", body = PaginatedResults<ChatMessage>),
+        (status = 404, description = "The AI service is not enabled or the conversation was not found", body = ErrorInformation)
+    )
+)]
+#[get("/v2/ai/conversations/{id}/messages")]
+pub async fn list_conversation_messages(
+    service: web::Data<AiService>,
+    db: web::Data<Database>,
+    id: web::Path<Uuid>,
+    web::Query(paginated): web::Query<Paginated>,
+    user: UserDetails,
+    _: Require<Ai>,
+) -> actix_web::Result<impl Responder> {
+    let id = id.into_inner();
+    require_conversation(&service, &db, id, &user.id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        service
+            .fetch_conversation_messages(id, paginated, db.as_ref())
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    tag = "ai",
+    operation_id = "listToolInvocations",
+    params(
+        ("id", Path, description = "Opaque ID of the conversation"),
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "The tools called for the conversation, oldest first", body = PaginatedResults<ToolInvocation>),
+        (status = 404, description = "The AI service is not enabled or the conversation was not found", body = ErrorInformation)
+    )
+)]
+#[get("/v2/ai/conversations/{id}/tool-invocations")]
+pub async fn list_tool_invocations(
+    service: web::Data<AiService>,
+    db: web::Data<Database>,
+    id: web::Path<Uuid>,
+    web::Query(paginated): web::Query<Paginated>,
+    user: UserDetails,
+    _: Require<Ai>,
+) -> actix_web::Result<impl Responder> {
+    let id = id.into_inner();
+    require_conversation(&service, &db, id, &user.id).await?;
+
+    Ok(HttpResponse::Ok().json(
+        service
+            .fetch_tool_invocations(id, paginated, db.as_ref())
+            .await?,
+    ))
+}
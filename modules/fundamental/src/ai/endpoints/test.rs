@@ -1,7 +1,11 @@
-use crate::ai::model::{ChatMessage, ChatState, Conversation, ConversationSummary};
+use crate::ai::model::{
+    ChatMessage, ChatState, CompletionEvent, Conversation, ConversationSummary, MessageType,
+};
 use crate::ai::service::AiService;
+use crate::ai::service::backend::{LlmBackend, LlmConfig};
 use crate::ai::service::test::{ingest_fixtures, sanitize_uuid_field, sanitize_uuid_urn};
-use crate::test::caller;
+use crate::endpoints::Config;
+use crate::test::caller_with;
 use actix_http::StatusCode;
 use actix_web::dev::ServiceResponse;
 use actix_web::test::{TestRequest, read_body_json};
@@ -13,6 +17,18 @@ use trustify_test_context::TrustifyContext;
 use trustify_test_context::auth::TestAuthentication;
 use trustify_test_context::call::CallService;
 
+/// Create the endpoints, configuring the LLM from the environment like [`AiService::new`] does.
+async fn caller(ctx: &TrustifyContext) -> anyhow::Result<impl CallService + '_> {
+    caller_with(
+        ctx,
+        Config {
+            ai: LlmConfig::from_env(),
+            ..Default::default()
+        },
+    )
+    .await
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn configure(ctx: &TrustifyContext) -> anyhow::Result<()> {
@@ -76,6 +92,104 @@ async fn flags(ctx: &TrustifyContext) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn noop_backend(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_with(
+        ctx,
+        Config {
+            ai: LlmConfig {
+                backend: LlmBackend::Noop,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let request = TestRequest::get().uri("/api/v2/ai/flags").to_request();
+    let result: serde_json::Value = app.call_and_read_body_json(request).await;
+    assert_eq!(result, json!({"completions": true}));
+
+    let mut req = ChatState::default();
+    req.messages.push(ChatMessage::human("Hello".into()));
+
+    let request = TestRequest::post()
+        .uri("/api/v2/ai/completions")
+        .set_json(req)
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // the noop backend replies with the message of the user
+    let result: ChatState = actix_web::test::read_body_json(response).await;
+    let last = result.messages.last().expect("must have a reply");
+    assert_eq!(last.message_type, MessageType::Ai);
+    assert_eq!(last.content, "Hello");
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn completions_stream(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_with(
+        ctx,
+        Config {
+            ai: LlmConfig {
+                backend: LlmBackend::Noop,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let mut req = ChatState::default();
+    req.messages.push(ChatMessage::human("Hello there".into()));
+
+    let request = TestRequest::post()
+        .uri("/api/v2/ai/completions/stream")
+        .set_json(req)
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/event-stream"
+    );
+
+    let events = read_text(response)
+        .await?
+        .split("\n\n")
+        .filter_map(|event| event.strip_prefix("data: "))
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<CompletionEvent>, _>>()?;
+
+    // the noop backend replies with the message of the user, without calling tools
+    assert_eq!(
+        events[..2],
+        [
+            CompletionEvent::Token {
+                content: "Hello ".into()
+            },
+            CompletionEvent::Token {
+                content: "there".into()
+            },
+        ]
+    );
+    let Some(CompletionEvent::Done { state }) = events.last() else {
+        panic!("must end with the final state: {events:?}");
+    };
+    assert_eq!(events.len(), 3);
+    assert_eq!(state.messages.last().unwrap().content, "Hello there");
+    assert!(state.internal_state.is_some());
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn tools(ctx: &TrustifyContext) -> anyhow::Result<()> {
@@ -315,3 +429,85 @@ async fn conversation_crud(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn conversation_resume(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_with(
+        ctx,
+        Config {
+            ai: LlmConfig {
+                backend: LlmBackend::Noop,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let id = uuid::Uuid::now_v7();
+
+    // resuming an unknown conversation fails
+    let request = TestRequest::post()
+        .uri(&format!("/api/v2/ai/conversations/{id}/messages"))
+        .set_json(vec![ChatMessage::human("Hello".into())])
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = TestRequest::put()
+        .uri(&format!("/api/v2/ai/conversations/{id}"))
+        .set_json(vec![ChatMessage::human("Hello".into())])
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // only send the new message
+    let request = TestRequest::post()
+        .uri(&format!("/api/v2/ai/conversations/{id}/messages"))
+        .append_header(("if-match", "\"1\""))
+        .set_json(vec![ChatMessage::human("Hello again".into())])
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let conversation: Conversation = read_body_json(response).await;
+    assert_eq!(conversation.seq, 2);
+    assert_eq!(conversation.messages.len(), 4);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/ai/conversations/{id}/messages?offset=2"))
+        .to_request()
+        .test_auth("user-a");
+    let result: PaginatedResults<ChatMessage> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 4);
+    assert_eq!(result.items, conversation.messages[2..]);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/ai/conversations/{id}/tool-invocations"))
+        .to_request()
+        .test_auth("user-a");
+    let result: serde_json::Value = app.call_and_read_body_json(request).await;
+    assert_eq!(result, json!({"items": [], "total": 0}));
+
+    // the conversation is not visible to other users
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/ai/conversations/{id}/messages"))
+        .to_request()
+        .test_auth("user-b");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = TestRequest::post()
+        .uri(&format!("/api/v2/ai/conversations/{id}/messages"))
+        .set_json(vec![ChatMessage::human("Hello".into())])
+        .to_request()
+        .test_auth("user-b");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
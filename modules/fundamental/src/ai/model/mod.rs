@@ -29,6 +29,22 @@ pub struct ChatState {
     pub internal_state: Option<String>,
 }
 
+/// An event of a streamed completion.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CompletionEvent {
+    /// The LLM calls a tool.
+    ToolCall { name: String, input: String },
+    /// A tool replied to the LLM.
+    ToolResult { name: String, success: bool },
+    /// A part of the response of the LLM.
+    Token { content: String },
+    /// The completion finished, the last event of a successful completion.
+    Done { state: ChatState },
+    /// The completion failed, the last event of a failed completion.
+    Error { message: String },
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct InternalState {
     pub messages: Vec<Message>,
@@ -43,6 +59,19 @@ pub struct ChatMessage {
     pub timestamp: OffsetDateTime,
 }
 
+/// A tool called by the LLM while generating a response.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq)]
+pub struct ToolInvocation {
+    /// The name of the tool.
+    pub name: String,
+    /// The input passed to the tool, as provided by the LLM.
+    pub input: String,
+    /// The output of the tool, if it replied.
+    pub output: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+}
+
 #[derive(Clone, Eq, PartialEq, Default, Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum MessageType {
@@ -0,0 +1,192 @@
+//! Backends providing the LLM of the AI service.
+
+use crate::ai::model::LLMInfo;
+use async_trait::async_trait;
+use futures_util::Stream;
+use langchain_rust::{
+    language_models::{GenerateResult, LLMError, llm::LLM, options::CallOptions},
+    llm::openai::{AzureConfig, OpenAI, OpenAIConfig},
+    schemas::{Message, MessageType, StreamData},
+};
+use std::{env, pin::Pin};
+
+/// The kind of service providing the LLM.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    strum::EnumString,
+    strum::Display,
+    strum::VariantNames,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum LlmBackend {
+    /// Any OpenAI compatible API, like OpenAI itself or Groq.
+    #[default]
+    OpenAi,
+    /// A local Ollama instance, using its OpenAI compatible API.
+    Ollama,
+    /// Azure OpenAI, using the model as the name of the deployment.
+    Azure,
+    /// Replies with the last message of the user, without calling any service. For testing only.
+    Noop,
+}
+
+/// The configuration of the LLM backend.
+///
+/// Unset values default depending on the backend.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LlmConfig {
+    pub backend: LlmBackend,
+    /// The base URL of the API.
+    pub api_base: Option<String>,
+    /// The API key. Required for the `openai` and `azure` backends, which are disabled otherwise.
+    pub api_key: Option<String>,
+    /// The model to use.
+    pub model: Option<String>,
+    /// The version of the API, only used by the `azure` backend.
+    pub api_version: Option<String>,
+    /// The sampling temperature.
+    pub temperature: f32,
+    /// The maximum number of tokens generated per completion.
+    pub max_tokens: u32,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            backend: Default::default(),
+            api_base: None,
+            api_key: None,
+            model: None,
+            api_version: None,
+            temperature: 0.0,
+            max_tokens: 1000,
+        }
+    }
+}
+
+impl LlmConfig {
+    /// Read the configuration of an OpenAI compatible backend from the `OPENAI_API_KEY`,
+    /// `OPENAI_API_BASE`, and `OPENAI_MODEL` environment variables.
+    pub fn from_env() -> Self {
+        Self {
+            api_base: env::var("OPENAI_API_BASE").ok(),
+            api_key: env::var("OPENAI_API_KEY").ok(),
+            model: env::var("OPENAI_MODEL").ok(),
+            ..Default::default()
+        }
+    }
+}
+
+/// An LLM created from an [`LlmConfig`].
+#[derive(Clone)]
+pub enum Llm {
+    OpenAi(OpenAI<OpenAIConfig>),
+    Azure(OpenAI<AzureConfig>),
+    Noop(NoopLlm),
+}
+
+impl Llm {
+    /// Create the LLM of a configuration, returning `None` if the backend is not fully configured.
+    pub fn new(config: &LlmConfig) -> Option<(Self, LLMInfo)> {
+        let options = CallOptions::default().with_seed(2000);
+
+        match config.backend {
+            LlmBackend::OpenAi | LlmBackend::Ollama => {
+                let (default_api_base, default_model, api_key) = match config.backend {
+                    LlmBackend::Ollama => (
+                        "http://localhost:11434/v1",
+                        "llama3.1:70b",
+                        // Ollama ignores the key, but the client requires one
+                        Some(config.api_key.clone().unwrap_or_else(|| "ollama".into())),
+                    ),
+                    _ => (
+                        "https://api.openai.com/v1",
+                        "gpt-4o",
+                        config.api_key.clone(),
+                    ),
+                };
+
+                let api_base = config.api_base.as_deref().unwrap_or(default_api_base);
+                let model = config.model.as_deref().unwrap_or(default_model);
+
+                let llm_config = OpenAIConfig::default()
+                    .with_api_base(api_base)
+                    .with_api_key(api_key?);
+
+                Some((
+                    Self::OpenAi(
+                        OpenAI::default()
+                            .with_config(llm_config)
+                            .with_model(model)
+                            .with_options(options),
+                    ),
+                    LLMInfo {
+                        api_base: api_base.into(),
+                        model: model.into(),
+                    },
+                ))
+            }
+            LlmBackend::Azure => {
+                let api_base = config.api_base.clone()?;
+                let model = config.model.clone()?;
+
+                let llm_config = AzureConfig::default()
+                    .with_api_base(&api_base)
+                    .with_api_key(config.api_key.clone()?)
+                    .with_deployment_id(&model)
+                    .with_api_version(config.api_version.as_deref().unwrap_or("2024-10-21"));
+
+                Some((
+                    Self::Azure(
+                        OpenAI::new(llm_config)
+                            .with_model(&model)
+                            .with_options(options),
+                    ),
+                    LLMInfo { api_base, model },
+                ))
+            }
+            LlmBackend::Noop => Some((
+                Self::Noop(NoopLlm),
+                LLMInfo {
+                    api_base: String::new(),
+                    model: "noop".into(),
+                },
+            )),
+        }
+    }
+}
+
+/// An LLM replying with the last message of the user.
+#[derive(Clone, Debug, Default)]
+pub struct NoopLlm;
+
+#[async_trait]
+impl LLM for NoopLlm {
+    async fn generate(&self, messages: &[Message]) -> Result<GenerateResult, LLMError> {
+        let generation = messages
+            .iter()
+            .rev()
+            .find(|message| matches!(message.message_type, MessageType::HumanMessage))
+            .map(|message| message.content.clone())
+            .unwrap_or_default();
+
+        Ok(GenerateResult {
+            tokens: None,
+            generation,
+        })
+    }
+
+    async fn stream(
+        &self,
+        _messages: &[Message],
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamData, LLMError>> + Send>>, LLMError> {
+        Err(LLMError::OtherError(
+            "streaming is not supported by the noop backend".into(),
+        ))
+    }
+}
@@ -1,20 +1,23 @@
+pub mod backend;
 pub mod tools;
 
-use crate::ai::model::{ChatMessage, ChatState, InternalState, LLMInfo, MessageType};
+use crate::ai::model::{
+    ChatMessage, ChatState, CompletionEvent, InternalState, LLMInfo, MessageType, ToolInvocation,
+};
 
 use crate::Error;
+use crate::ai::service::backend::{Llm, LlmConfig};
+use crate::ai::service::tools::events::ToolEvents;
+use crate::ai::service::tools::guard::{Caller, LocalTool};
 use crate::ai::service::tools::remote::RemoteToolsProvider;
 use base64::engine::Engine as _;
 use base64::engine::general_purpose::STANDARD;
 
 use langchain_rust::chain::Chain;
 use langchain_rust::chain::options::ChainCallOptions;
-use langchain_rust::language_models::options::CallOptions;
 use langchain_rust::schemas::{BaseMemory, Message};
-use langchain_rust::tools::OpenAIConfig;
 use langchain_rust::{
     agent::{AgentExecutor, OpenAiToolAgentBuilder},
-    llm::openai::OpenAI,
     memory::SimpleMemory,
     prompt_args,
     tools::Tool,
@@ -25,33 +28,37 @@ use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, prelude::Uuid};
 use std::env;
 use std::sync::Arc;
 use time::OffsetDateTime;
-use tokio::sync::OnceCell;
+use tokio::sync::{OnceCell, mpsc};
 
 use trustify_common::db::limiter::LimiterTrait;
 
 use trustify_common::db::Database;
 use trustify_common::db::query::{Filtering, Query};
 use trustify_common::model::{Paginated, PaginatedResults};
-use trustify_entity::conversation;
+use trustify_entity::{conversation, conversation_message, conversation_tool_invocation};
 
 pub const PREFIX: &str = include_str!("prefix.txt");
 
 pub struct AiService {
-    llm: Option<OpenAI<OpenAIConfig>>,
+    llm: Option<Llm>,
     llm_info: Option<LLMInfo>,
+    config: LlmConfig,
     remote_tools_providers: Vec<RemoteToolsProvider>,
-    pub local_tools: Vec<Arc<dyn Tool>>,
-    tools: OnceCell<Vec<Arc<dyn Tool>>>,
+    db: Database,
+    remote_tools: OnceCell<Vec<Arc<dyn Tool>>>,
 }
 
 impl AiService {
-    /// Creates a new instance of the AI service.  It can be run against any OpenAI compatible
-    /// API endpoint.  The service is disabled if the OPENAI_API_KEY environment variable is not set.
-    /// You can configure the following environment variables to run against different OpenAI compatible:
-    ///
-    /// * OPENAI_API_KEY
-    /// * OPENAI_API_BASE (default: https://api.openai.com/v1)
-    /// * OPENAI_MODEL (default: gpt-4o)
+    /// Creates a new instance of the AI service, running against an OpenAI compatible API
+    /// endpoint. The service is disabled if the OPENAI_API_KEY environment variable is not set.
+    /// See [`LlmConfig::from_env`] for the environment variables being read, and
+    /// [`Self::with_config`] for using other backends.
+    pub fn new(db: Database) -> Self {
+        Self::with_config(db, LlmConfig::from_env())
+    }
+
+    /// Creates a new instance of the AI service, using the configured LLM backend. The service is
+    /// disabled if the backend is not fully configured.
     ///
     /// ## Running Against OpenAI:
     /// OpenAI tends to provide cutting edge proprietary models, but they are not open source.
@@ -81,43 +88,35 @@ impl AiService {
     /// 2. run `ollama pull llama3.1:70b`
     /// 3. export the following env variables:
     /// ```bash
-    /// export OPENAI_API_KEY=ollama
-    /// export OPENAI_API_BASE=http://localhost:11434/v1
+    /// export TRUSTD_AI_BACKEND=ollama
     /// export OPENAI_MODEL=llama3.1:70b
     /// ```
     ///
-    pub fn new(db: Database) -> Self {
-        let local_tools = tools::new(db.clone());
-
-        let api_key = env::var("OPENAI_API_KEY");
-        let api_key = match api_key {
-            Ok(api_key) => api_key,
-            Err(_) => {
-                return Self {
-                    llm: None,
-                    llm_info: None,
-                    remote_tools_providers: Vec::new(),
-                    local_tools,
-                    tools: OnceCell::new(),
-                };
-            }
+    /// ## Running Against Azure OpenAI:
+    /// The model is the name of the deployment of the Azure OpenAI resource.
+    ///
+    /// ```bash
+    /// export TRUSTD_AI_BACKEND=azure
+    /// export OPENAI_API_KEY=xxxx
+    /// export OPENAI_API_BASE=https://my-resource.openai.azure.com
+    /// export OPENAI_MODEL=my-gpt-4o-deployment
+    /// ```
+    ///
+    pub fn with_config(db: Database, config: LlmConfig) -> Self {
+        let Some((llm, llm_info)) = Llm::new(&config) else {
+            return Self {
+                llm: None,
+                llm_info: None,
+                config,
+                remote_tools_providers: Vec::new(),
+                db,
+                remote_tools: OnceCell::new(),
+            };
         };
 
-        let api_base =
-            env::var("OPENAI_API_BASE").unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
-        let model = env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-4o".to_string());
-
-        log::info!("LLM API: {}", api_base.clone());
-        log::info!("LLM Model: {}", model);
-
-        let llm_config = OpenAIConfig::default()
-            .with_api_base(api_base.clone())
-            .with_api_key(api_key);
-
-        let llm = OpenAI::default()
-            .with_config(llm_config.clone())
-            .with_model(model.clone())
-            .with_options(CallOptions::default().with_seed(2000));
+        log::info!("LLM backend: {}", config.backend);
+        log::info!("LLM API: {}", llm_info.api_base);
+        log::info!("LLM Model: {}", llm_info.model);
 
         let mut remote_tools_providers = vec![];
 
@@ -135,10 +134,11 @@ impl AiService {
 
         Self {
             llm: Some(llm),
-            llm_info: Some(LLMInfo { api_base, model }),
+            llm_info: Some(llm_info),
+            config,
             remote_tools_providers,
-            local_tools,
-            tools: OnceCell::new(),
+            db,
+            remote_tools: OnceCell::new(),
         }
     }
 
@@ -150,7 +150,7 @@ impl AiService {
         self.llm_info.clone()
     }
 
-    async fn fetch_tools(&self) -> Vec<Arc<dyn Tool>> {
+    async fn fetch_remote_tools(&self) -> Vec<Arc<dyn Tool>> {
         let mut result = vec![];
         for provider in &self.remote_tools_providers {
             match provider.tools().await {
@@ -165,22 +165,35 @@ impl AiService {
             }
         }
 
-        if env::var("AGENT_DISABLE_LOCAL_TOOLS").is_err() {
-            for tool in &self.local_tools {
-                result.push(tool.clone());
-            }
-        }
-
         result
     }
 
-    async fn tools_ref(&self) -> &Vec<Arc<dyn Tool>> {
+    /// Create the local tools, limited to the documents of the tenant of a caller.
+    pub fn local_tools(&self, caller: &Caller) -> Vec<LocalTool> {
+        tools::new(self.db.clone(), &caller.tenant)
+    }
+
+    /// Get the tools available to a caller: the remote tools, and the local tools the caller has
+    /// the permissions for.
+    async fn tools_for(&self, caller: &Caller) -> Vec<Arc<dyn Tool>> {
         // this handles fetching the remote tools only once on the first request...
         // would be better if we could periodically check for tool updates
         // and cache the results for a certain amount of time
-        self.tools
-            .get_or_init(|| async { self.fetch_tools().await })
+        let mut result = self
+            .remote_tools
+            .get_or_init(|| async { self.fetch_remote_tools().await })
             .await
+            .clone();
+
+        if env::var("AGENT_DISABLE_LOCAL_TOOLS").is_err() {
+            for tool in self.local_tools(caller) {
+                if tool.permitted(caller) {
+                    result.push(tool.tool);
+                }
+            }
+        }
+
+        result
     }
 
     pub async fn summarize(&self, messages: &[ChatMessage]) -> Result<String, Error> {
@@ -197,7 +210,49 @@ impl AiService {
             None => Ok("...".to_string()),
         }
     }
-    pub async fn completions(&self, request: &ChatState) -> Result<ChatState, Error> {
+    pub async fn completions(
+        &self,
+        request: &ChatState,
+        caller: &Caller,
+    ) -> Result<ChatState, Error> {
+        self.completions_with_events(request, caller, None).await
+    }
+
+    /// Like [`Self::completions`], reporting the tool calls and the response while generating
+    /// it.
+    ///
+    /// The agent only provides complete messages, so the tokens of the response are reported
+    /// once the response is complete.
+    pub async fn completions_stream(
+        &self,
+        request: &ChatState,
+        caller: &Caller,
+        events: mpsc::Sender<CompletionEvent>,
+    ) -> Result<ChatState, Error> {
+        let state = self
+            .completions_with_events(request, caller, Some(&events))
+            .await?;
+
+        if let Some(message) = state.messages.last() {
+            for content in message.content.split_inclusive(char::is_whitespace) {
+                // the receiver going away must not fail the completion
+                let _ = events
+                    .send(CompletionEvent::Token {
+                        content: content.to_string(),
+                    })
+                    .await;
+            }
+        }
+
+        Ok(state)
+    }
+
+    async fn completions_with_events(
+        &self,
+        request: &ChatState,
+        caller: &Caller,
+        events: Option<&mpsc::Sender<CompletionEvent>>,
+    ) -> Result<ChatState, Error> {
         // get the previous LLM message history
         let internal_state = match &request.internal_state {
             Some(internal_state) => match STANDARD.decode(internal_state) {
@@ -217,7 +272,7 @@ impl AiService {
         };
 
         let internal_state = self
-            .completions_decoded(&request.messages, &internal_state)
+            .completions_decoded(&request.messages, &internal_state, caller, events)
             .await?;
 
         let messages = internal_state.chat_messages();
@@ -241,23 +296,43 @@ impl AiService {
         &self,
         request_messages: &Vec<ChatMessage>,
         internal_state: &InternalState,
+        caller: &Caller,
+        events: Option<&mpsc::Sender<CompletionEvent>>,
     ) -> Result<InternalState, Error> {
         let llm = match self.llm.clone() {
             Some(llm) => llm,
             None => return Err(Error::NotFound("AI service is not enabled".to_string())),
         };
 
-        let agent = OpenAiToolAgentBuilder::new()
+        let mut tools = self.tools_for(caller).await;
+        if let Some(events) = events {
+            tools = tools
+                .into_iter()
+                .map(|tool| {
+                    Arc::new(ToolEvents {
+                        tool,
+                        events: events.clone(),
+                    }) as Arc<dyn Tool>
+                })
+                .collect();
+        }
+
+        let builder = OpenAiToolAgentBuilder::new()
             .prefix(PREFIX)
-            .tools(self.tools_ref().await)
+            .tools(&tools)
             .options(
                 ChainCallOptions::new()
-                    .with_max_tokens(1000)
-                    .with_temperature(0.0)
+                    .with_max_tokens(self.config.max_tokens)
+                    .with_temperature(self.config.temperature)
                     .with_seed(1000),
-            )
-            .build(llm)
-            .map_err(Error::AgentError)?;
+            );
+
+        let agent = match llm {
+            Llm::OpenAi(llm) => builder.build(llm),
+            Llm::Azure(llm) => builder.build(llm),
+            Llm::Noop(llm) => builder.build(llm),
+        }
+        .map_err(Error::AgentError)?;
 
         if internal_state.messages.len() != internal_state.timestamps.len() {
             return Err(Error::BadRequest("invalid internal_state".to_string()));
@@ -341,6 +416,7 @@ impl AiService {
         user_id: String,
         messages: &Vec<ChatMessage>,
         if_seq: Option<i32>,
+        caller: &Caller,
         connection: &C,
     ) -> Result<(conversation::Model, Vec<ChatMessage>), Error> {
         let found = self.fetch_conversation(conversation_id, connection).await?;
@@ -384,7 +460,10 @@ impl AiService {
             current_seq,
             if_seq
         );
-        let internal_state = self.completions_decoded(messages, &internal_state).await?;
+        let previous = internal_state.messages.len();
+        let internal_state = self
+            .completions_decoded(messages, &internal_state, caller, None)
+            .await?;
 
         let response = internal_state.chat_messages();
 
@@ -408,9 +487,48 @@ impl AiService {
         }
         let result = query.exec(connection).await?;
 
+        record_messages(conversation_id, previous, &internal_state, connection).await?;
+
         Ok((result, response))
     }
 
+    /// Continue an existing conversation of a user with new messages of the user, without
+    /// replaying the previous messages. Returns `None` if the conversation was not found.
+    pub async fn resume_conversation<C: ConnectionTrait>(
+        &self,
+        conversation_id: Uuid,
+        user_id: String,
+        messages: &Vec<ChatMessage>,
+        if_seq: Option<i32>,
+        caller: &Caller,
+        connection: &C,
+    ) -> Result<Option<(conversation::Model, Vec<ChatMessage>)>, Error> {
+        match self.fetch_conversation(conversation_id, connection).await? {
+            Some((conversation, _)) if conversation.user_id == user_id => {}
+            _ => return Ok(None),
+        }
+
+        if messages
+            .iter()
+            .any(|message| message.message_type != MessageType::Human)
+        {
+            return Err(Error::BadRequest(
+                "only user messages can be added to a conversation".to_string(),
+            ));
+        }
+
+        self.upsert_conversation(
+            conversation_id,
+            user_id,
+            messages,
+            if_seq,
+            caller,
+            connection,
+        )
+        .await
+        .map(Some)
+    }
+
     pub async fn fetch_conversation<C: ConnectionTrait>(
         &self,
         id: Uuid,
@@ -439,7 +557,8 @@ impl AiService {
             .order_by_desc(conversation::Column::UpdatedAt)
             .filtering(search)?
             .filter(conversation::Column::UserId.eq(user_id))
-            .limiting(connection, paginated.offset, paginated.limit);
+            .limiting(connection, paginated.offset, paginated.limit)
+            .counting(paginated.count);
 
         let total = limiter.total().await?;
 
@@ -449,6 +568,69 @@ impl AiService {
         })
     }
 
+    /// Get the messages of a conversation shown to the user, oldest first.
+    pub async fn fetch_conversation_messages<C: ConnectionTrait + Sync + Send>(
+        &self,
+        id: Uuid,
+        paginated: Paginated,
+        connection: &C,
+    ) -> Result<PaginatedResults<ChatMessage>, Error> {
+        let limiter = conversation_message::Entity::find()
+            .filter(conversation_message::Column::ConversationId.eq(id))
+            .order_by_asc(conversation_message::Column::Idx)
+            .limiting(connection, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        Ok(PaginatedResults {
+            total: limiter.total().await?,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(|message| ChatMessage {
+                    message_type: match message.message_type.as_str() {
+                        "ai" => MessageType::Ai,
+                        "system" => MessageType::System,
+                        "tool" => MessageType::Tool,
+                        _ => MessageType::Human,
+                    },
+                    content: message.content,
+                    timestamp: message.timestamp,
+                })
+                .collect(),
+        })
+    }
+
+    /// Get the tools called while generating the responses of a conversation, oldest first.
+    pub async fn fetch_tool_invocations<C: ConnectionTrait + Sync + Send>(
+        &self,
+        id: Uuid,
+        paginated: Paginated,
+        connection: &C,
+    ) -> Result<PaginatedResults<ToolInvocation>, Error> {
+        let limiter = conversation_tool_invocation::Entity::find()
+            .filter(conversation_tool_invocation::Column::ConversationId.eq(id))
+            .order_by_asc(conversation_tool_invocation::Column::Idx)
+            .order_by_asc(conversation_tool_invocation::Column::Id)
+            .limiting(connection, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        Ok(PaginatedResults {
+            total: limiter.total().await?,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(|invocation| ToolInvocation {
+                    name: invocation.name,
+                    input: invocation.input,
+                    output: invocation.output,
+                    timestamp: invocation.timestamp,
+                })
+                .collect(),
+        })
+    }
+
     pub async fn delete_conversation<C: ConnectionTrait>(
         &self,
         id: Uuid,
@@ -460,8 +642,92 @@ impl AiService {
     }
 }
 
+/// Store the messages and tool invocations of a conversation, starting at the message `from`
+/// of its state.
+///
+/// Records of a previous update starting at the same position are replaced.
+async fn record_messages<C: ConnectionTrait>(
+    conversation_id: Uuid,
+    from: usize,
+    internal_state: &InternalState,
+    connection: &C,
+) -> Result<(), Error> {
+    let from_idx = from as i32;
+
+    conversation_message::Entity::delete_many()
+        .filter(conversation_message::Column::ConversationId.eq(conversation_id))
+        .filter(conversation_message::Column::Idx.gte(from_idx))
+        .exec(connection)
+        .await?;
+    conversation_tool_invocation::Entity::delete_many()
+        .filter(conversation_tool_invocation::Column::ConversationId.eq(conversation_id))
+        .filter(conversation_tool_invocation::Column::Idx.gte(from_idx))
+        .exec(connection)
+        .await?;
+
+    let messages = internal_state
+        .indexed_chat_messages()
+        .into_iter()
+        .filter(|(index, _)| *index >= from)
+        .map(|(index, message)| conversation_message::ActiveModel {
+            conversation_id: Set(conversation_id),
+            idx: Set(index as i32),
+            message_type: Set(message.message_type.to_string()),
+            content: Set(message.content),
+            timestamp: Set(message.timestamp),
+        })
+        .collect::<Vec<_>>();
+
+    if !messages.is_empty() {
+        conversation_message::Entity::insert_many(messages)
+            .exec_without_returning(connection)
+            .await?;
+    }
+
+    let invocations = internal_state
+        .indexed_tool_invocations()
+        .into_iter()
+        .filter(|(index, _, _)| *index >= from)
+        .map(
+            |(index, call_id, invocation)| conversation_tool_invocation::ActiveModel {
+                id: Set(Uuid::now_v7()),
+                conversation_id: Set(conversation_id),
+                idx: Set(index as i32),
+                call_id: Set(call_id),
+                name: Set(invocation.name),
+                input: Set(invocation.input),
+                output: Set(invocation.output),
+                timestamp: Set(invocation.timestamp),
+            },
+        )
+        .collect::<Vec<_>>();
+
+    if !invocations.is_empty() {
+        conversation_tool_invocation::Entity::insert_many(invocations)
+            .exec_without_returning(connection)
+            .await?;
+    }
+
+    Ok(())
+}
+
 impl InternalState {
+    fn timestamp(&self, index: usize) -> OffsetDateTime {
+        self.timestamps
+            .get(index)
+            .and_then(|timestamp| OffsetDateTime::from_unix_timestamp(*timestamp).ok())
+            .unwrap_or_else(OffsetDateTime::now_utc)
+    }
+
     pub fn chat_messages(&self) -> Vec<ChatMessage> {
+        self.indexed_chat_messages()
+            .into_iter()
+            .map(|(_, message)| message)
+            .collect()
+    }
+
+    /// The messages shown to the user, along with their position in the state.
+    fn indexed_chat_messages(&self) -> Vec<(usize, ChatMessage)> {
         let mut response_messages = Vec::new();
         for (index, message) in self.messages.iter().enumerate() {
             // Skip showing some of the messages to the user.
@@ -477,20 +743,74 @@ impl InternalState {
                 }
             }
 
-            response_messages.push(ChatMessage {
-                content: message.content.clone(),
-                message_type: match message.message_type.clone() {
-                    langchain_rust::schemas::MessageType::HumanMessage => MessageType::Human,
-                    langchain_rust::schemas::MessageType::AIMessage => MessageType::Ai,
-                    langchain_rust::schemas::MessageType::SystemMessage => MessageType::System,
-                    langchain_rust::schemas::MessageType::ToolMessage => MessageType::Tool,
+            response_messages.push((
+                index,
+                ChatMessage {
+                    content: message.content.clone(),
+                    message_type: match message.message_type.clone() {
+                        langchain_rust::schemas::MessageType::HumanMessage => MessageType::Human,
+                        langchain_rust::schemas::MessageType::AIMessage => MessageType::Ai,
+                        langchain_rust::schemas::MessageType::SystemMessage => MessageType::System,
+                        langchain_rust::schemas::MessageType::ToolMessage => MessageType::Tool,
+                    },
+                    timestamp: self.timestamp(index),
                 },
-                timestamp: OffsetDateTime::from_unix_timestamp(self.timestamps[index])
-                    .unwrap_or_else(|_| OffsetDateTime::now_utc()),
-            });
+            ));
         }
         response_messages
     }
+
+    /// The tools called by the LLM, along with the position of the message requesting the call
+    /// and the ID of the call.
+    ///
+    /// The output is taken from the tool message replying to the call.
+    fn indexed_tool_invocations(&self) -> Vec<(usize, Option<String>, ToolInvocation)> {
+        let mut invocations = Vec::new();
+        for (index, message) in self.messages.iter().enumerate() {
+            if !matches!(
+                message.message_type,
+                langchain_rust::schemas::MessageType::AIMessage
+            ) {
+                continue;
+            }
+            let Some(serde_json::Value::Array(calls)) = &message.tool_calls else {
+                continue;
+            };
+
+            for call in calls {
+                let call_id = call["id"].as_str().map(ToString::to_string);
+                let output = call_id.as_ref().and_then(|call_id| {
+                    self.messages
+                        .iter()
+                        .find(|message| {
+                            matches!(
+                                message.message_type,
+                                langchain_rust::schemas::MessageType::ToolMessage
+                            ) && message.id.as_ref() == Some(call_id)
+                        })
+                        .map(|message| message.content.clone())
+                });
+
+                invocations.push((
+                    index,
+                    call_id,
+                    ToolInvocation {
+                        name: call["function"]["name"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string(),
+                        input: call["function"]["arguments"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string(),
+                        output,
+                        timestamp: self.timestamp(index),
+                    },
+                ));
+            }
+        }
+        invocations
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,9 @@
-use crate::ai::model::{ChatMessage, ChatState};
+use crate::ai::model::{ChatMessage, ChatState, InternalState, MessageType};
 use crate::ai::service::AiService;
+use crate::ai::service::backend::{LlmBackend, LlmConfig};
+use crate::ai::service::tools::guard::Caller;
+use langchain_rust::schemas::Message;
+use serde_json::json;
 
 use test_context::test_context;
 use test_log::test;
@@ -80,7 +84,7 @@ async fn test_completions_sbom_info(ctx: &TrustifyContext) -> Result<(), anyhow:
             .into(),
     ));
 
-    let result = service.completions(&req).await?;
+    let result = service.completions(&req, &Caller::unrestricted()).await?;
 
     log::info!("result: {:#?}", result);
     let last_message_content = result.messages.last().unwrap().content.clone();
@@ -112,7 +116,7 @@ async fn test_completions_package_info(ctx: &TrustifyContext) -> Result<(), anyh
         "List the httpclient packages with their identifiers".into(),
     ));
 
-    let result = service.completions(&req).await?;
+    let result = service.completions(&req, &Caller::unrestricted()).await?;
 
     log::info!("result: {:#?}", result);
     let last_message_content = result.messages.last().unwrap().content.clone();
@@ -145,7 +149,7 @@ async fn test_completions_cve_info(ctx: &TrustifyContext) -> Result<(), anyhow::
         "Give me details for CVE-2021-32714".into(),
     ));
 
-    let result = service.completions(&req).await?;
+    let result = service.completions(&req, &Caller::unrestricted()).await?;
 
     log::info!("result: {:#?}", result);
     let last_message_content = result.messages.last().unwrap().content.clone();
@@ -175,7 +179,7 @@ async fn test_completions_advisory_info(ctx: &TrustifyContext) -> Result<(), any
         "Give me details for the RHSA-2024_3666 advisory".into(),
     ));
 
-    let result = service.completions(&req).await?;
+    let result = service.completions(&req, &Caller::unrestricted()).await?;
 
     log::info!("result: {:#?}", result);
     let last_message_content = result.messages.last().unwrap().content.clone();
@@ -211,6 +215,7 @@ async fn conversation_crud(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             "user_a".into(),
             &state.messages,
             Some(0),
+            &Caller::unrestricted(),
             &ctx.db,
         )
         .await?;
@@ -235,6 +240,7 @@ async fn conversation_crud(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             Paginated {
                 offset: 0,
                 limit: 10,
+                ..Default::default()
             },
             &ctx.db,
         )
@@ -254,6 +260,7 @@ async fn conversation_crud(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             "user_a".into(),
             &state.messages,
             Some(1),
+            &Caller::unrestricted(),
             &ctx.db,
         )
         .await?
@@ -270,7 +277,14 @@ async fn conversation_crud(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     // verify that the update fails due to old seq
     service
-        .upsert_conversation(conversation_id, "user_a".into(), &vec![], Some(0), &ctx.db)
+        .upsert_conversation(
+            conversation_id,
+            "user_a".into(),
+            &vec![],
+            Some(0),
+            &Caller::unrestricted(),
+            &ctx.db,
+        )
         .await
         .expect_err("should fail due to old seq");
 
@@ -290,3 +304,157 @@ async fn conversation_crud(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn conversation_messages(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let service = AiService::with_config(
+        ctx.db.clone(),
+        LlmConfig {
+            backend: LlmBackend::Noop,
+            ..Default::default()
+        },
+    );
+
+    let conversation_id = Uuid::now_v7();
+
+    // resuming requires an existing conversation
+    let resumed = service
+        .resume_conversation(
+            conversation_id,
+            "user_a".into(),
+            &vec![ChatMessage::human("hello".into())],
+            None,
+            &Caller::unrestricted(),
+            &ctx.db,
+        )
+        .await?;
+    assert!(resumed.is_none());
+
+    service
+        .upsert_conversation(
+            conversation_id,
+            "user_a".into(),
+            &vec![ChatMessage::human("hello".into())],
+            None,
+            &Caller::unrestricted(),
+            &ctx.db,
+        )
+        .await?;
+
+    // only send the new message
+    let (conversation, messages) = service
+        .resume_conversation(
+            conversation_id,
+            "user_a".into(),
+            &vec![ChatMessage::human("hello again".into())],
+            Some(1),
+            &Caller::unrestricted(),
+            &ctx.db,
+        )
+        .await?
+        .expect("must be found");
+
+    assert_eq!(conversation.seq, 2);
+    assert_eq!(
+        messages
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect::<Vec<_>>(),
+        ["hello", "hello", "hello again", "hello again"]
+    );
+
+    // the conversation belongs to another user
+    let resumed = service
+        .resume_conversation(
+            conversation_id,
+            "user_b".into(),
+            &vec![ChatMessage::human("hello".into())],
+            None,
+            &Caller::unrestricted(),
+            &ctx.db,
+        )
+        .await?;
+    assert!(resumed.is_none());
+
+    // the stored messages match the ones of the state
+    let stored = service
+        .fetch_conversation_messages(conversation_id, Paginated::default(), &ctx.db)
+        .await?;
+    assert_eq!(stored.total, 4);
+    assert_eq!(
+        stored
+            .items
+            .iter()
+            .map(|m| (m.message_type.clone(), m.content.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            (MessageType::Human, "hello"),
+            (MessageType::Ai, "hello"),
+            (MessageType::Human, "hello again"),
+            (MessageType::Ai, "hello again"),
+        ]
+    );
+
+    // the noop backend doesn't call any tools
+    let invocations = service
+        .fetch_tool_invocations(conversation_id, Paginated::default(), &ctx.db)
+        .await?;
+    assert_eq!(invocations.total, 0);
+
+    // deleting the conversation deletes its messages
+    service
+        .delete_conversation(conversation_id, &ctx.db)
+        .await?;
+    let stored = service
+        .fetch_conversation_messages(conversation_id, Paginated::default(), &ctx.db)
+        .await?;
+    assert_eq!(stored.total, 0);
+
+    Ok(())
+}
+
+#[test]
+fn tool_invocations() {
+    let state = InternalState {
+        messages: vec![
+            Message::new_human_message("what is CVE-2021-32714?"),
+            Message::new_ai_message("").with_tool_calls(json!([
+                {
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {
+                        "name": "cve-info",
+                        "arguments": r#"{"input":"CVE-2021-32714"}"#
+                    }
+                }
+            ])),
+            Message::new_tool_message("a vulnerability in hyper", "call_1"),
+            Message::new_ai_message("It is a vulnerability in hyper."),
+        ],
+        timestamps: vec![0, 1, 2, 3],
+    };
+
+    assert_eq!(
+        state
+            .chat_messages()
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect::<Vec<_>>(),
+        ["what is CVE-2021-32714?", "It is a vulnerability in hyper."]
+    );
+
+    let invocations = state.indexed_tool_invocations();
+    assert_eq!(invocations.len(), 1);
+
+    let (index, call_id, invocation) = &invocations[0];
+    assert_eq!(*index, 1);
+    assert_eq!(call_id.as_deref(), Some("call_1"));
+    assert_eq!(invocation.name, "cve-info");
+    assert_eq!(invocation.input, r#"{"input":"CVE-2021-32714"}"#);
+    assert_eq!(
+        invocation.output.as_deref(),
+        Some("a vulnerability in hyper")
+    );
+    assert_eq!(invocation.timestamp.unix_timestamp(), 1);
+}
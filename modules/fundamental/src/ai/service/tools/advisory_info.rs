@@ -1,6 +1,7 @@
 use crate::{
     advisory::service::AdvisoryService,
     ai::service::tools::{self, input_description},
+    tenant::Tenant,
 };
 use async_trait::async_trait;
 use langchain_rust::tools::Tool;
@@ -9,19 +10,33 @@ use serde_json::Value;
 use std::error::Error;
 use time::OffsetDateTime;
 use trustify_common::db::Database;
-use trustify_common::{db::query::Query, id::Id};
+use trustify_common::{
+    db::query::{LabelFilters, Query},
+    id::Id,
+};
 use trustify_module_ingestor::common::Deprecation;
 use uuid::Uuid;
 
 pub struct AdvisoryInfo {
     db: Database,
     service: AdvisoryService,
+    tenant: Tenant,
 }
 
 impl AdvisoryInfo {
     pub fn new(db: Database) -> Self {
         let service = AdvisoryService::new(db.clone());
-        Self { db, service }
+        Self {
+            db,
+            service,
+            tenant: Tenant::Any,
+        }
+    }
+
+    /// Limit the tool to the advisories of a tenant.
+    pub fn tenant(mut self, tenant: Tenant) -> Self {
+        self.tenant = tenant;
+        self
     }
 }
 
@@ -70,6 +85,7 @@ Advisories have a UUID that uniquely identifies the advisory.
                         },
                         Default::default(),
                         Deprecation::Ignore,
+                        self.tenant.restrict(LabelFilters::default()),
                         &self.db,
                     )
                     .await?;
@@ -100,7 +116,7 @@ Advisories have a UUID that uniquely identifies the advisory.
             }
         };
 
-        let item = match item {
+        let item = match item.filter(|item| self.tenant.owns(&item.head.labels)) {
             Some(v) => v,
             None => return Ok(format!("Advisory '{input}' not found")),
         };
@@ -1,5 +1,6 @@
 use crate::{
     ai::service::tools::{self, input_description},
+    tenant::Tenant,
     vulnerability::service::VulnerabilityService,
 };
 use async_trait::async_trait;
@@ -12,11 +13,12 @@ use trustify_common::{
     db::{Database, query::Query},
     purl::Purl,
 };
-use trustify_module_ingestor::common::Deprecation;
+use trustify_module_ingestor::common::{AsOf, Deprecation, Withdrawal};
 
 pub struct CVEInfo {
     pub db: Database,
     pub service: VulnerabilityService,
+    pub tenant: Tenant,
 }
 
 impl CVEInfo {
@@ -24,8 +26,15 @@ impl CVEInfo {
         Self {
             db,
             service: VulnerabilityService::new(),
+            tenant: Tenant::Any,
         }
     }
+
+    /// Limit the tool to the advisories of a tenant.
+    pub fn tenant(mut self, tenant: Tenant) -> Self {
+        self.tenant = tenant;
+        self
+    }
 }
 
 #[async_trait]
@@ -68,7 +77,14 @@ The input should be the partial or full name of the Vulnerability to search for.
             .to_string();
 
         let item = match service
-            .fetch_vulnerability(input.as_str(), Deprecation::Ignore, &self.db)
+            .fetch_vulnerability(
+                input.as_str(),
+                Deprecation::Ignore,
+                Withdrawal::Ignore,
+                AsOf::default(),
+                &self.tenant,
+                &self.db,
+            )
             .await?
         {
             Some(v) => v,
@@ -82,6 +98,8 @@ The input should be the partial or full name of the Vulnerability to search for.
                         },
                         Default::default(),
                         Deprecation::Ignore,
+                        AsOf::default(),
+                        &self.tenant,
                         &self.db,
                     )
                     .await?;
@@ -110,6 +128,9 @@ The input should be the partial or full name of the Vulnerability to search for.
                     .fetch_vulnerability(
                         results.items[0].head.identifier.as_str(),
                         Deprecation::Ignore,
+                        Withdrawal::Ignore,
+                        AsOf::default(),
+                        &self.tenant,
                         &self.db,
                     )
                     .await?
@@ -0,0 +1,58 @@
+use crate::ai::model::CompletionEvent;
+use async_trait::async_trait;
+use langchain_rust::tools::Tool;
+use serde_json::Value;
+use std::{error::Error, sync::Arc};
+use tokio::sync::mpsc;
+
+/// Reports the calls of a tool as events of a streamed completion.
+pub struct ToolEvents {
+    pub tool: Arc<dyn Tool>,
+    pub events: mpsc::Sender<CompletionEvent>,
+}
+
+#[async_trait]
+impl Tool for ToolEvents {
+    fn name(&self) -> String {
+        self.tool.name()
+    }
+
+    fn description(&self) -> String {
+        self.tool.description()
+    }
+
+    fn parameters(&self) -> Value {
+        self.tool.parameters()
+    }
+
+    async fn call(&self, input: &str) -> Result<String, Box<dyn Error>> {
+        // the receiver going away must not fail the completion
+        let _ = self
+            .events
+            .send(CompletionEvent::ToolCall {
+                name: self.name(),
+                input: input.to_string(),
+            })
+            .await;
+
+        let result = self.tool.call(input).await;
+
+        let _ = self
+            .events
+            .send(CompletionEvent::ToolResult {
+                name: self.name(),
+                success: result.is_ok(),
+            })
+            .await;
+
+        result
+    }
+
+    async fn run(&self, input: Value) -> Result<String, Box<dyn Error>> {
+        self.tool.run(input).await
+    }
+
+    async fn parse_input(&self, input: &str) -> Value {
+        self.tool.parse_input(input).await
+    }
+}
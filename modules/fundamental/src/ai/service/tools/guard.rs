@@ -0,0 +1,169 @@
+use crate::tenant::Tenant;
+use langchain_rust::tools::Tool;
+use std::sync::Arc;
+use trustify_auth::{
+    Permission,
+    authenticator::{error::AuthorizationError, user::UserInformation},
+    authorizer::Authorizer,
+};
+
+/// The user on whose behalf the AI service calls tools.
+#[derive(Clone, Debug)]
+pub struct Caller {
+    authorizer: Authorizer,
+    user: UserInformation,
+    /// The tenant whose documents the tools may return.
+    pub tenant: Tenant,
+}
+
+impl Caller {
+    pub fn new(authorizer: Authorizer, user: UserInformation) -> Self {
+        Self {
+            authorizer,
+            user,
+            tenant: Tenant::Any,
+        }
+    }
+
+    /// Limit the tools to the documents of a tenant.
+    pub fn tenant(mut self, tenant: Tenant) -> Self {
+        self.tenant = tenant;
+        self
+    }
+
+    /// A caller permitted to use all tools, as if authorization was disabled.
+    pub fn unrestricted() -> Self {
+        Self::new(Authorizer::new(None), UserInformation::Anonymous)
+    }
+
+    /// Require all permissions of a tool from the caller.
+    pub fn require(&self, tool: &LocalTool) -> Result<(), AuthorizationError> {
+        for permission in tool.permissions {
+            self.authorizer.require(&self.user, *permission)?;
+        }
+        Ok(())
+    }
+}
+
+/// Extract the caller from the authorizer, the user, and the tenant of a request.
+impl actix_web::FromRequest for Caller {
+    type Error = actix_web::Error;
+    type Future = core::future::Ready<Result<Self, Self::Error>>;
+
+    fn from_request(
+        req: &actix_web::HttpRequest,
+        payload: &mut actix_web::dev::Payload,
+    ) -> Self::Future {
+        use actix_web::HttpMessage;
+
+        let Some(authorizer) = req.app_data::<actix_web::web::Data<Authorizer>>() else {
+            return core::future::ready(Err(actix_web::error::ErrorForbidden(
+                "missing authorizer",
+            )));
+        };
+        let user = req
+            .extensions()
+            .get::<UserInformation>()
+            .cloned()
+            .unwrap_or(UserInformation::Anonymous);
+        let tenant =
+            match <Tenant as actix_web::FromRequest>::from_request(req, payload).into_inner() {
+                Ok(tenant) => tenant,
+                Err(err) => return core::future::ready(Err(err)),
+            };
+
+        core::future::ready(Ok(
+            Self::new(authorizer.as_ref().clone(), user).tenant(tenant)
+        ))
+    }
+}
+
+/// A tool querying the database, along with the permissions required for reading the data it
+/// returns.
+#[derive(Clone)]
+pub struct LocalTool {
+    pub tool: Arc<dyn Tool>,
+    pub permissions: &'static [Permission],
+}
+
+impl LocalTool {
+    pub fn new<T: Tool + 'static>(tool: T, permissions: &'static [Permission]) -> Self {
+        Self {
+            tool: Arc::new(tool),
+            permissions,
+        }
+    }
+
+    /// Check if the caller may use the tool.
+    pub fn permitted(&self, caller: &Caller) -> bool {
+        caller.require(self).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ai::service::tools;
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_auth::{authenticator::user::UserDetails, authorizer::AuthorizerConfig};
+    use trustify_common::db::Database;
+    use trustify_test_context::TrustifyContext;
+
+    fn caller(permissions: &[Permission]) -> Caller {
+        Caller::new(
+            Authorizer::new(Some(AuthorizerConfig::default())),
+            UserInformation::Authenticated(UserDetails {
+                id: "user-a".into(),
+                permissions: permissions.iter().map(ToString::to_string).collect(),
+                tenant: None,
+            }),
+        )
+    }
+
+    fn permitted(caller: &Caller, db: &Database) -> Vec<String> {
+        tools::new(db.clone(), &caller.tenant)
+            .iter()
+            .filter(|tool| tool.permitted(caller))
+            .map(|tool| tool.tool.name())
+            .collect()
+    }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn permitted_tools(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        assert_eq!(
+            permitted(
+                &caller(&[Permission::Ai, Permission::ReadAdvisory]),
+                &ctx.db
+            ),
+            ["cve-info", "advisory-info"]
+        );
+        assert_eq!(
+            permitted(&caller(&[Permission::Ai, Permission::ReadSbom]), &ctx.db),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            permitted(
+                &caller(&[
+                    Permission::Ai,
+                    Permission::ReadSbom,
+                    Permission::ReadAdvisory
+                ]),
+                &ctx.db
+            )
+            .len(),
+            6
+        );
+        assert_eq!(permitted(&Caller::unrestricted(), &ctx.db).len(), 6);
+
+        // anonymous users don't get any tool when authorization is enabled
+        let anonymous = Caller::new(
+            Authorizer::new(Some(AuthorizerConfig::default())),
+            UserInformation::Anonymous,
+        );
+        assert!(permitted(&anonymous, &ctx.db).is_empty());
+
+        Ok(())
+    }
+}
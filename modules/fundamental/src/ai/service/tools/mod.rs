@@ -1,28 +1,58 @@
-use crate::ai::service::tools::{
-    advisory_info::AdvisoryInfo, cve_info::CVEInfo, logger::ToolLogger, package_info::PackageInfo,
-    sbom_info::SbomInfo,
+use crate::{
+    ai::service::tools::{
+        advisory_info::AdvisoryInfo, cve_info::CVEInfo, guard::LocalTool, logger::ToolLogger,
+        package_info::PackageInfo, remediation_info::RemediationInfo, sbom_diff::SbomDiff,
+        sbom_info::SbomInfo,
+    },
+    tenant::Tenant,
 };
-use langchain_rust::tools::Tool;
 use serde::Serialize;
 use serde_json::{Value, json};
-use std::{error::Error, sync::Arc};
+use std::error::Error;
+use trustify_auth::Permission::{ReadAdvisory, ReadSbom};
 use trustify_common::{db::Database, model::PaginatedResults};
 
 pub mod advisory_info;
 pub mod cve_info;
+pub mod events;
+pub mod guard;
 pub mod logger;
 pub mod package_info;
 pub mod product_info;
+pub mod remediation_info;
 pub mod remote;
+pub mod sbom_diff;
 pub mod sbom_info;
 
-pub fn new(db: Database) -> Vec<Arc<dyn Tool>> {
+/// Create the local tools, requiring the same permissions as the endpoints returning the same
+/// data, and limited to the documents of the tenant.
+pub fn new(db: Database, tenant: &Tenant) -> Vec<LocalTool> {
     vec![
-        // Arc::new(ToolLogger(ProductInfo(ProductService::new(db.clone())))),
-        Arc::new(ToolLogger(CVEInfo::new(db.clone()))),
-        Arc::new(ToolLogger(AdvisoryInfo::new(db.clone()))),
-        Arc::new(ToolLogger(PackageInfo::new(db.clone()))),
-        Arc::new(ToolLogger(SbomInfo::new(db.clone()))),
+        // LocalTool::new(ToolLogger(ProductInfo(ProductService::new(db.clone()))), &[ReadMetadata]),
+        LocalTool::new(
+            ToolLogger(CVEInfo::new(db.clone()).tenant(tenant.clone())),
+            &[ReadAdvisory],
+        ),
+        LocalTool::new(
+            ToolLogger(AdvisoryInfo::new(db.clone()).tenant(tenant.clone())),
+            &[ReadAdvisory],
+        ),
+        LocalTool::new(
+            ToolLogger(PackageInfo::new(db.clone()).tenant(tenant.clone())),
+            &[ReadSbom, ReadAdvisory],
+        ),
+        LocalTool::new(
+            ToolLogger(RemediationInfo::new(db.clone()).tenant(tenant.clone())),
+            &[ReadSbom, ReadAdvisory],
+        ),
+        LocalTool::new(
+            ToolLogger(SbomInfo::new(db.clone()).tenant(tenant.clone())),
+            &[ReadSbom, ReadAdvisory],
+        ),
+        LocalTool::new(
+            ToolLogger(SbomDiff::new(db.clone()).tenant(tenant.clone())),
+            &[ReadSbom, ReadAdvisory],
+        ),
     ]
 }
 
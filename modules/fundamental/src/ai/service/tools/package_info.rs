@@ -1,5 +1,7 @@
 use crate::sbom::model::SbomExternalPackageReference;
-use crate::{ai::service::tools, purl::service::PurlService, sbom::service::SbomService};
+use crate::{
+    ai::service::tools, purl::service::PurlService, sbom::service::SbomService, tenant::Tenant,
+};
 use async_trait::async_trait;
 use langchain_rust::tools::Tool;
 use serde::Serialize;
@@ -16,13 +18,25 @@ pub struct PackageInfo {
     pub db: Database,
     pub purl: PurlService,
     pub sbom: SbomService,
+    pub tenant: Tenant,
 }
 
 impl PackageInfo {
     pub fn new(db: Database) -> Self {
         let purl = PurlService::new();
         let sbom = SbomService::new(db.clone());
-        Self { db, purl, sbom }
+        Self {
+            db,
+            purl,
+            sbom,
+            tenant: Tenant::Any,
+        }
+    }
+
+    /// Limit the tool to the packages and documents of a tenant.
+    pub fn tenant(mut self, tenant: Tenant) -> Self {
+        self.tenant = tenant;
+        self
     }
 }
 
@@ -60,6 +74,7 @@ Input: The package name, its Identifier URI, or UUID.
             purl: service,
             sbom: sbom_service,
             db,
+            tenant,
         } = &self;
 
         let input = input
@@ -70,14 +85,22 @@ Input: The package name, its Identifier URI, or UUID.
         // Try lookup as a PURL
         let mut purl_details = match Purl::try_from(input.clone()) {
             Err(_) => None,
-            Ok(purl) => service.purl_by_purl(&purl, Deprecation::Ignore, db).await?,
+            Ok(purl) => {
+                service
+                    .purl_by_purl(&purl, Deprecation::Ignore, tenant, db)
+                    .await?
+            }
         };
 
         // Try lookup as a UUID
         if purl_details.is_none() {
             purl_details = match Uuid::parse_str(input.as_str()) {
                 Err(_) => None,
-                Ok(uuid) => service.purl_by_uuid(&uuid, Deprecation::Ignore, db).await?,
+                Ok(uuid) => {
+                    service
+                        .purl_by_uuid(&uuid, Deprecation::Ignore, tenant, db)
+                        .await?
+                }
             };
         }
 
@@ -91,6 +114,7 @@ Input: The package name, its Identifier URI, or UUID.
                         ..Default::default()
                     },
                     Default::default(),
+                    tenant,
                     &db,
                 )
                 .await?;
@@ -99,7 +123,7 @@ Input: The package name, its Identifier URI, or UUID.
                 0 => None,
                 1 => {
                     service
-                        .purl_by_uuid(&results.items[0].head.uuid, Deprecation::Ignore, db)
+                        .purl_by_uuid(&results.items[0].head.uuid, Deprecation::Ignore, tenant, db)
                         .await?
                 }
                 _ => {
@@ -132,6 +156,7 @@ Input: The package name, its Identifier URI, or UUID.
                 SbomExternalPackageReference::Purl(&item.head.purl),
                 Default::default(),
                 Default::default(),
+                tenant,
                 db,
             )
             .await?;
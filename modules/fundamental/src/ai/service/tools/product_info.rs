@@ -1,6 +1,7 @@
 use crate::{
     ai::service::tools::{self, input_description},
     product::service::ProductService,
+    tenant::Tenant,
 };
 use async_trait::async_trait;
 use langchain_rust::tools::Tool;
@@ -13,12 +14,23 @@ use uuid::Uuid;
 pub struct ProductInfo {
     pub db: Database,
     pub service: ProductService,
+    pub tenant: Tenant,
 }
 
 impl ProductInfo {
     pub fn new(db: Database) -> Self {
         let service = ProductService::new();
-        Self { db, service }
+        Self {
+            db,
+            service,
+            tenant: Tenant::Any,
+        }
+    }
+
+    /// Limit the tool to the SBOMs of a tenant.
+    pub fn tenant(mut self, tenant: Tenant) -> Self {
+        self.tenant = tenant;
+        self
     }
 }
 
@@ -63,6 +75,7 @@ Products are names of Software Products.  Examples:
                     ..Default::default()
                 },
                 Default::default(),
+                &self.tenant,
                 &self.db,
             )
             .await?;
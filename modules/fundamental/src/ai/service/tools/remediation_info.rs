@@ -0,0 +1,196 @@
+use crate::{ai::service::tools, purl::service::PurlService, tenant::Tenant};
+use async_trait::async_trait;
+use langchain_rust::tools::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::error::Error;
+use trustify_common::{db::Database, purl::Purl};
+use uuid::Uuid;
+
+pub struct RemediationInfo {
+    pub db: Database,
+    pub service: PurlService,
+    pub tenant: Tenant,
+}
+
+impl RemediationInfo {
+    pub fn new(db: Database) -> Self {
+        Self {
+            db,
+            service: PurlService::new(),
+            tenant: Tenant::Any,
+        }
+    }
+
+    /// Limit the tool to the advisories of a tenant.
+    pub fn tenant(mut self, tenant: Tenant) -> Self {
+        self.tenant = tenant;
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for RemediationInfo {
+    fn name(&self) -> String {
+        String::from("remediation-info")
+    }
+
+    fn description(&self) -> String {
+        String::from(
+            r##"
+This tool provides advice on how to remediate a vulnerability (CVE) of a Package. Packages are identified by a URI, which includes their version. Examples:
+
+* pkg:rpm/redhat/libsepol@3.5-1.el9?arch=ppc64le
+* pkg:maven/org.apache.maven.wagon/wagon-provider-api@3.5.1?type=jar
+
+The tool provides the versions of the package which advisories state as fixed, and the lowest of them to upgrade to.
+"##
+            .trim(),
+        )
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "vulnerability": {
+                    "type": "string",
+                    "description": "The identifier of the vulnerability, for example CVE-2014-0160.",
+                },
+                "purl": {
+                    "type": "string",
+                    "description": "The Identifier URI of the package, including its version.",
+                },
+            },
+            "required": ["vulnerability", "purl"]
+        })
+    }
+
+    async fn run(&self, input: Value) -> Result<String, Box<dyn Error>> {
+        #[derive(Deserialize)]
+        struct Input {
+            vulnerability: String,
+            purl: String,
+        }
+
+        let input: Input = match input {
+            Value::String(input) => serde_json::from_str(&input)?,
+            input => serde_json::from_value(input)?,
+        };
+
+        let purl = match Purl::try_from(input.purl.as_str()) {
+            Ok(purl) => purl,
+            Err(_) => return Ok(format!("'{}' is not a valid package URI", input.purl)),
+        };
+
+        let Some(remediation) = self
+            .service
+            .remediation(&purl, &input.vulnerability, &self.tenant, &self.db)
+            .await?
+        else {
+            return Ok(format!("Package '{}' not found", input.purl));
+        };
+
+        #[derive(Serialize)]
+        struct Item {
+            identifier: Purl,
+            vulnerability: String,
+            fixed_versions: Vec<FixedVersion>,
+            upgrade: Option<Purl>,
+        }
+
+        #[derive(Serialize)]
+        struct FixedVersion {
+            version: String,
+            advisory: Advisory,
+        }
+
+        #[derive(Serialize)]
+        struct Advisory {
+            uuid: Uuid,
+            identifier: String,
+            link: String,
+        }
+
+        tools::to_json(&Item {
+            upgrade: remediation.upgrade.map(|version| Purl {
+                version: Some(version),
+                ..remediation.purl.clone()
+            }),
+            identifier: remediation.purl,
+            vulnerability: remediation.vulnerability,
+            fixed_versions: remediation
+                .fixed
+                .into_iter()
+                .map(|fixed| FixedVersion {
+                    version: fixed.version,
+                    advisory: Advisory {
+                        link: format!(
+                            "http://localhost:3000/advisory/urn:uuid:{}",
+                            fixed.advisory_id
+                        ),
+                        uuid: fixed.advisory_id,
+                        identifier: fixed.advisory_identifier,
+                    },
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::service::tools::tests::assert_tool_contains;
+    use std::rc::Rc;
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_test_context::TrustifyContext;
+
+    #[test_context(TrustifyContext)]
+    #[test(actix_web::test)]
+    async fn remediation_info_tool(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        ctx.ingest_documents(["csaf/rhsa-2024-2705.json"]).await?;
+
+        let tool = Rc::new(RemediationInfo::new(ctx.db.clone()));
+
+        assert_tool_contains(
+            tool.clone(),
+            &json!({
+                "vulnerability": "CVE-2024-29025",
+                "purl": "pkg:maven/io.netty/netty-codec-http@4.1.100.Final-redhat-00001?repository_url=https://maven.repository.redhat.com/ga/&type=jar",
+            })
+            .to_string(),
+            r#"
+{
+  "identifier": "pkg:maven/io.netty/netty-codec-http@4.1.100.Final-redhat-00001?repository_url=https://maven.repository.redhat.com/ga/&type=jar",
+  "vulnerability": "CVE-2024-29025",
+  "fixed_versions": [
+    {
+      "version": "4.1.108.Final-redhat-00001",
+      "advisory": {
+        "uuid": "xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx",
+        "identifier": "RHSA-2024:2705",
+        "link": "http://localhost:3000/advisory/urn:uuid:xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx"
+      }
+    }
+  ],
+  "upgrade": "pkg:maven/io.netty/netty-codec-http@4.1.108.Final-redhat-00001?repository_url=https://maven.repository.redhat.com/ga/&type=jar"
+}
+"#,
+        )
+        .await?;
+
+        // already fixed, nothing to upgrade to
+        assert_tool_contains(
+            tool.clone(),
+            &json!({
+                "vulnerability": "CVE-2024-29025",
+                "purl": "pkg:maven/io.netty/netty-codec-http@4.1.108.Final-redhat-00001?repository_url=https://maven.repository.redhat.com/ga/&type=jar",
+            })
+            .to_string(),
+            r#""upgrade": null"#,
+        )
+        .await
+    }
+}
@@ -0,0 +1,296 @@
+use crate::{
+    ai::service::tools,
+    sbom::{
+        model::{SbomHead, SbomPackage},
+        service::SbomService,
+    },
+    tenant::Tenant,
+};
+use async_trait::async_trait;
+use langchain_rust::tools::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::{error::Error, str::FromStr};
+use time::OffsetDateTime;
+use trustify_common::{
+    db::Database,
+    db::query::{LabelFilters, Query},
+    id::Id,
+};
+use uuid::Uuid;
+
+pub struct SbomDiff {
+    pub db: Database,
+    pub service: SbomService,
+    pub tenant: Tenant,
+}
+
+impl SbomDiff {
+    pub fn new(db: Database) -> Self {
+        let service = SbomService::new(db.clone());
+        Self {
+            db,
+            service,
+            tenant: Tenant::Any,
+        }
+    }
+
+    /// Limit the tool to the SBOMs of a tenant.
+    pub fn tenant(mut self, tenant: Tenant) -> Self {
+        self.tenant = tenant;
+        self
+    }
+
+    /// Find the single SBOM an identifier or name refers to.
+    ///
+    /// If there is no such SBOM, or the name is ambiguous, the error is the message to reply with.
+    async fn resolve(&self, input: &str) -> Result<Result<Uuid, String>, Box<dyn Error>> {
+        let service = &self.service;
+
+        if let Ok(id) = Id::from_str(input) {
+            if let Some(summary) = service.fetch_sbom_summary(id, &self.db).await? {
+                if self.tenant.owns(&summary.head.labels) {
+                    return Ok(Ok(summary.head.id));
+                }
+            }
+        }
+
+        if let Ok(id) = Uuid::from_str(input) {
+            if let Some(summary) = service.fetch_sbom_summary(Id::Uuid(id), &self.db).await? {
+                if self.tenant.owns(&summary.head.labels) {
+                    return Ok(Ok(summary.head.id));
+                }
+            }
+        }
+
+        // Fallback to search
+        let results = service
+            .fetch_sboms(
+                Query {
+                    q: input.to_string(),
+                    ..Default::default()
+                },
+                Default::default(),
+                false,
+                self.tenant.restrict(LabelFilters::default()),
+                &self.db,
+            )
+            .await?;
+
+        Ok(match results.items.len() {
+            0 => Err(format!("SBOM '{input}' not found")),
+            1 => Ok(results.items[0].head.id),
+            _ => {
+                #[derive(Serialize)]
+                struct Item {
+                    uuid: Uuid,
+                    name: String,
+                    #[serde(with = "time::serde::rfc3339::option")]
+                    published: Option<OffsetDateTime>,
+                    link: String,
+                }
+
+                let json = tools::paginated_to_json(results, |item| Item {
+                    uuid: item.head.id,
+                    name: item.head.name.clone(),
+                    published: item.head.published,
+                    link: format!("http://localhost:3000/sboms/urn:uuid:{}", item.head.id),
+                })?;
+                Err(format!(
+                    "There are multiple that match '{input}':\n\n{json}"
+                ))
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for SbomDiff {
+    fn name(&self) -> String {
+        String::from("sbom-diff")
+    }
+
+    fn description(&self) -> String {
+        String::from(
+            r##"
+This tool compares two Software Bills of Materials (SBOMs), for example two builds of the same product. SBOMs are identified by SHA-256, SHA-384, SHA-512 hashes, UUID URIs, or their name. Examples:
+
+sha256:315f7c672f6e4948ffcc6d5a2b30f269c767d6d7d6f41d82ae716b5a46e5a68e
+urn:uuid:2fd0d1b7-a908-4d63-9310-d57a7f77c6df
+
+The tool provides the packages added to and removed from the right SBOM, compared to the left one, as well as the vulnerabilities newly affecting the right SBOM.
+"##
+            .trim(),
+        )
+    }
+
+    fn parameters(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "left": {
+                    "type": "string",
+                    "description": "The identifier or name of the SBOM to compare against, typically the older one.",
+                },
+                "right": {
+                    "type": "string",
+                    "description": "The identifier or name of the SBOM being compared, typically the newer one.",
+                },
+            },
+            "required": ["left", "right"]
+        })
+    }
+
+    async fn run(&self, input: Value) -> Result<String, Box<dyn Error>> {
+        #[derive(Deserialize)]
+        struct Input {
+            left: String,
+            right: String,
+        }
+
+        let input: Input = match input {
+            Value::String(input) => serde_json::from_str(&input)?,
+            input => serde_json::from_value(input)?,
+        };
+
+        let left = match self.resolve(&input.left).await? {
+            Ok(id) => id,
+            Err(message) => return Ok(message),
+        };
+        let right = match self.resolve(&input.right).await? {
+            Ok(id) => id,
+            Err(message) => return Ok(message),
+        };
+
+        let Some(diff) = self
+            .service
+            .diff_sboms(Id::Uuid(left), Id::Uuid(right), &self.tenant, &self.db)
+            .await?
+        else {
+            return Ok(format!(
+                "SBOMs '{}' and '{}' could not be compared",
+                input.left, input.right
+            ));
+        };
+
+        #[derive(Serialize)]
+        struct Item {
+            left: Sbom,
+            right: Sbom,
+            added_packages: Vec<String>,
+            removed_packages: Vec<String>,
+            new_vulnerabilities: Vec<Vulnerability>,
+        }
+
+        #[derive(Serialize)]
+        struct Sbom {
+            uuid: Uuid,
+            name: String,
+            #[serde(with = "time::serde::rfc3339::option")]
+            published: Option<OffsetDateTime>,
+            link: String,
+        }
+
+        impl From<&SbomHead> for Sbom {
+            fn from(head: &SbomHead) -> Self {
+                Self {
+                    uuid: head.id,
+                    name: head.name.clone(),
+                    published: head.published,
+                    link: format!("http://localhost:3000/sboms/urn:uuid:{}", head.id),
+                }
+            }
+        }
+
+        #[derive(Serialize)]
+        struct Vulnerability {
+            identifier: String,
+            title: Option<String>,
+            link: String,
+        }
+
+        let package = |package: &SbomPackage| match package.purl.first() {
+            Some(purl) => purl.head.purl.to_string(),
+            None => match &package.version {
+                Some(version) => format!("{} {version}", package.name),
+                None => package.name.clone(),
+            },
+        };
+
+        tools::to_json(&Item {
+            left: (&diff.left).into(),
+            right: (&diff.right).into(),
+            added_packages: diff.added.iter().map(package).collect(),
+            removed_packages: diff.removed.iter().map(package).collect(),
+            new_vulnerabilities: diff
+                .new_vulnerabilities
+                .iter()
+                .map(|vulnerability| Vulnerability {
+                    identifier: vulnerability.identifier.clone(),
+                    title: vulnerability.title.clone(),
+                    link: format!(
+                        "http://localhost:3000/vulnerability/{}",
+                        vulnerability.identifier
+                    ),
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::service::tools::tests::assert_tool_contains;
+    use std::rc::Rc;
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_test_context::TrustifyContext;
+
+    #[test_context(TrustifyContext)]
+    #[test(actix_web::test)]
+    async fn sbom_diff_tool(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        let results = ctx
+            .ingest_documents([
+                "spdx/quarkus-bom-3.2.11.Final-redhat-00001.json",
+                "spdx/quarkus-bom-3.2.12.Final-redhat-00002.json",
+            ])
+            .await?;
+
+        let tool = Rc::new(SbomDiff::new(ctx.db.clone()));
+
+        let input = json!({
+            "left": results[0].id.to_string(),
+            "right": results[1].id.to_string(),
+        })
+        .to_string();
+
+        assert_tool_contains(
+            tool.clone(),
+            &input,
+            r#"
+  "right": {
+    "uuid": "xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx",
+    "name": "quarkus-bom-3.2.12.Final-redhat-00002",
+    "published": "2024-07-05T09:40:48Z",
+    "link": "http://localhost:3000/sboms/urn:uuid:xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx"
+  },
+"#,
+        )
+        .await?;
+
+        assert_tool_contains(
+            tool.clone(),
+            &input,
+            r#""pkg:maven/io.netty/netty-codec-http@4.1.108.Final-redhat-00001?repository_url=https://maven.repository.redhat.com/ga/&type=jar""#,
+        )
+        .await?;
+
+        assert_tool_contains(
+            tool.clone(),
+            &json!({"left": "quarkus-bom-0.0.1", "right": "quarkus-bom-0.0.2"}).to_string(),
+            "SBOM 'quarkus-bom-0.0.1' not found",
+        )
+        .await
+    }
+}
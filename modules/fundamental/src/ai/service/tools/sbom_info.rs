@@ -1,6 +1,7 @@
 use crate::{
     ai::service::tools::{self, input_description},
     sbom::service::SbomService,
+    tenant::Tenant,
 };
 use async_trait::async_trait;
 use itertools::Itertools;
@@ -9,18 +10,33 @@ use serde::Serialize;
 use serde_json::Value;
 use std::{error::Error, str::FromStr};
 use time::OffsetDateTime;
-use trustify_common::{db::Database, db::query::Query, id::Id};
+use trustify_common::{
+    db::Database,
+    db::query::{LabelFilters, Query},
+    id::Id,
+};
 use uuid::Uuid;
 
 pub struct SbomInfo {
     pub db: Database,
     pub service: SbomService,
+    pub tenant: Tenant,
 }
 
 impl SbomInfo {
     pub fn new(db: Database) -> Self {
         let service = SbomService::new(db.clone());
-        Self { db, service }
+        Self {
+            db,
+            service,
+            tenant: Tenant::Any,
+        }
+    }
+
+    /// Limit the tool to the SBOMs of a tenant.
+    pub fn tenant(mut self, tenant: Tenant) -> Self {
+        self.tenant = tenant;
+        self
     }
 }
 
@@ -67,7 +83,10 @@ For example, input "quarkus" instead of "quarkus 3.2.11".
             Err(_) => None,
             Ok(id) => {
                 log::info!("Fetching SBOM details by Id: {}", id);
-                service.fetch_sbom_details(id, vec![], &self.db).await?
+                service
+                    .fetch_sbom_details(id, Default::default(), &self.tenant, &self.db)
+                    .await?
+                    .filter(|details| self.tenant.owns(&details.summary.head.labels))
             }
         };
 
@@ -77,8 +96,14 @@ For example, input "quarkus" instead of "quarkus 3.2.11".
                 Ok(id) => {
                     log::info!("Fetching SBOM details by UUID: {}", id);
                     service
-                        .fetch_sbom_details(Id::Uuid(id), vec![], &self.db)
+                        .fetch_sbom_details(
+                            Id::Uuid(id),
+                            Default::default(),
+                            &self.tenant,
+                            &self.db,
+                        )
                         .await?
+                        .filter(|details| self.tenant.owns(&details.summary.head.labels))
                 }
             };
         }
@@ -93,7 +118,8 @@ For example, input "quarkus" instead of "quarkus 3.2.11".
                         ..Default::default()
                     },
                     Default::default(),
-                    (),
+                    false,
+                    self.tenant.restrict(LabelFilters::default()),
                     &self.db,
                 )
                 .await?;
@@ -102,7 +128,12 @@ For example, input "quarkus" instead of "quarkus 3.2.11".
                 0 => None,
                 1 => {
                     service
-                        .fetch_sbom_details(Id::Uuid(results.items[0].head.id), vec![], &self.db)
+                        .fetch_sbom_details(
+                            Id::Uuid(results.items[0].head.id),
+                            Default::default(),
+                            &self.tenant,
+                            &self.db,
+                        )
                         .await?
                 }
                 _ => {
@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    annotation::{
+        model::{AnnotationRequest, VulnerabilityAnnotation},
+        service::AnnotationService,
+    },
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, delete, get, http::header, post, put, web};
+use trustify_auth::{
+    CreateAnnotation, DeleteAnnotation, ReadAdvisory, UpdateAnnotation,
+    authenticator::user::UserInformation, authorizer::Require,
+};
+use trustify_common::{
+    db::Database,
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults},
+};
+use uuid::Uuid;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = AnnotationService::new(db);
+
+    config
+        .app_data(web::Data::new(service))
+        .service(list)
+        .service(create)
+        .service(update)
+        .service(delete);
+}
+
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "listVulnerabilityAnnotations",
+    params(
+        ("id", Path, description = "ID of the vulnerability"),
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Annotations of the vulnerability, oldest first", body = PaginatedResults<VulnerabilityAnnotation>),
+        (status = 404, description = "The vulnerability could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/vulnerability/{id}/annotation")]
+/// List the threat intelligence annotations of a vulnerability
+pub async fn list(
+    service: web::Data<AnnotationService>,
+    id: web::Path<String>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadAdvisory>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(match service.list(&id, paginated, &tenant).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "createVulnerabilityAnnotation",
+    request_body = AnnotationRequest,
+    params(
+        ("id", Path, description = "ID of the vulnerability"),
+    ),
+    responses(
+        (status = 201, description = "Created the annotation", body = VulnerabilityAnnotation),
+        (status = 404, description = "The vulnerability could not be found", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/vulnerability/{id}/annotation")]
+/// Annotate a vulnerability, like with the availability of an exploit
+pub async fn create(
+    service: web::Data<AnnotationService>,
+    id: web::Path<String>,
+    user: UserInformation,
+    web::Json(request): web::Json<AnnotationRequest>,
+    _: Require<CreateAnnotation>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(
+        match service
+            .create(&id, request, user.id().map(ToString::to_string), &tenant)
+            .await?
+        {
+            Some(result) => HttpResponse::Created()
+                .insert_header((
+                    header::LOCATION,
+                    format!("/api/v2/vulnerability/{id}/annotation/{}", result.id),
+                ))
+                .json(result),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "updateVulnerabilityAnnotation",
+    request_body = AnnotationRequest,
+    params(
+        ("id", Path, description = "ID of the vulnerability"),
+        ("annotation" = Uuid, Path, description = "ID of the annotation"),
+    ),
+    responses(
+        (status = 200, description = "The updated annotation", body = VulnerabilityAnnotation),
+        (status = 404, description = "The annotation could not be found", body = ErrorInformation),
+    ),
+)]
+#[put("/v2/vulnerability/{id}/annotation/{annotation}")]
+/// Replace the content of an annotation
+pub async fn update(
+    service: web::Data<AnnotationService>,
+    path: web::Path<(String, Uuid)>,
+    web::Json(request): web::Json<AnnotationRequest>,
+    _: Require<UpdateAnnotation>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (id, annotation) = path.into_inner();
+    Ok(
+        match service.update(&id, annotation, request, &tenant).await? {
+            Some(result) => HttpResponse::Ok().json(result),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "deleteVulnerabilityAnnotation",
+    params(
+        ("id", Path, description = "ID of the vulnerability"),
+        ("annotation" = Uuid, Path, description = "ID of the annotation"),
+    ),
+    responses(
+        (status = 204, description = "The annotation was deleted"),
+        (status = 404, description = "The annotation could not be found", body = ErrorInformation),
+    ),
+)]
+#[delete("/v2/vulnerability/{id}/annotation/{annotation}")]
+/// Delete an annotation
+pub async fn delete(
+    service: web::Data<AnnotationService>,
+    path: web::Path<(String, Uuid)>,
+    _: Require<DeleteAnnotation>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (id, annotation) = path.into_inner();
+    Ok(match service.delete(&id, annotation, &tenant).await? {
+        true => HttpResponse::NoContent().finish(),
+        false => HttpResponse::NotFound().finish(),
+    })
+}
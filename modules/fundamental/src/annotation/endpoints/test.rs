@@ -0,0 +1,155 @@
+use crate::{
+    annotation::model::{AnnotationKind, VulnerabilityAnnotation},
+    test::{caller, caller_with, tenant_isolation, tenant_user},
+    vulnerability::model::{VulnerabilityDetails, VulnerabilitySummary},
+};
+use actix_http::StatusCode;
+use actix_web::test::{TestRequest, read_body_json};
+use serde_json::json;
+use test_context::test_context;
+use test_log::test;
+use trustify_common::model::PaginatedResults;
+use trustify_test_context::{TrustifyContext, auth::TestAuthentication, call::CallService};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn annotate(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_documents(["mitre/CVE-2024-27088.json", "mitre/CVE-2024-28111.json"])
+        .await?;
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/vulnerability/CVE-2024-27088/annotation")
+        .set_json(json!({
+            "kind": "proof_of_concept",
+            "url": "https://example.com/poc",
+        }))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let annotation: VulnerabilityAnnotation = read_body_json(response).await;
+    assert_eq!(annotation.kind, AnnotationKind::ProofOfConcept);
+    assert_eq!(annotation.user.as_deref(), Some("user-a"));
+
+    // unknown vulnerabilities can't be annotated
+
+    let request = TestRequest::post()
+        .uri("/api/v2/vulnerability/CVE-0000-0000/annotation")
+        .set_json(json!({"kind": "actively_exploited"}))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // annotations are part of the details
+
+    let request = TestRequest::get()
+        .uri("/api/v2/vulnerability/CVE-2024-27088")
+        .to_request();
+    let result: VulnerabilityDetails = app.call_and_read_body_json(request).await;
+    assert_eq!(result.annotations, vec![annotation.clone()]);
+
+    // a feed reports the vulnerability as being exploited
+
+    let uri = format!(
+        "/api/v2/vulnerability/CVE-2024-27088/annotation/{}",
+        annotation.id
+    );
+    let request = TestRequest::put()
+        .uri(&uri)
+        .set_json(json!({
+            "kind": "actively_exploited",
+            "source": "kev",
+        }))
+        .to_request();
+    let result: VulnerabilityAnnotation = app.call_and_read_body_json(request).await;
+    assert_eq!(result.kind, AnnotationKind::ActivelyExploited);
+    assert_eq!(result.source.as_deref(), Some("kev"));
+    assert_eq!(result.url, None);
+    assert_eq!(result.user.as_deref(), Some("user-a"));
+
+    // which can be used for prioritizing
+
+    let request = TestRequest::get()
+        .uri("/api/v2/vulnerability?q=annotation%3Dactively_exploited")
+        .to_request();
+    let result: PaginatedResults<VulnerabilitySummary> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].head.identifier, "CVE-2024-27088");
+
+    // remove the annotation
+
+    let request = TestRequest::delete().uri(&uri).to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::delete().uri(&uri).to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = TestRequest::get()
+        .uri("/api/v2/vulnerability/CVE-2024-27088/annotation")
+        .to_request();
+    let result: PaginatedResults<VulnerabilityAnnotation> =
+        app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 0);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn other_tenants(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_document("mitre/CVE-2024-27088.json").await?;
+    let app = caller_with(ctx, tenant_isolation()).await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/vulnerability/CVE-2024-27088/annotation")
+        .set_json(json!({"kind": "actively_exploited"}))
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let annotation: VulnerabilityAnnotation = read_body_json(response).await;
+
+    // tenant b neither sees, nor finds, nor changes the annotation of tenant a
+
+    let request = TestRequest::get()
+        .uri("/api/v2/vulnerability/CVE-2024-27088/annotation")
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let result: PaginatedResults<VulnerabilityAnnotation> =
+        app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 0);
+
+    let request = TestRequest::get()
+        .uri("/api/v2/vulnerability?q=annotation%3Dactively_exploited")
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let result: PaginatedResults<VulnerabilitySummary> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 0);
+
+    let uri = format!(
+        "/api/v2/vulnerability/CVE-2024-27088/annotation/{}",
+        annotation.id
+    );
+    for request in [
+        TestRequest::put()
+            .uri(&uri)
+            .set_json(json!({"kind": "proof_of_concept"})),
+        TestRequest::delete().uri(&uri),
+    ] {
+        let request = request.to_request().test_auth_details(tenant_user("b"));
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    let request = TestRequest::get()
+        .uri("/api/v2/vulnerability?q=annotation%3Dactively_exploited")
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let result: PaginatedResults<VulnerabilitySummary> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 1);
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::vulnerability_annotation;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub use vulnerability_annotation::Kind as AnnotationKind;
+
+/// Threat intelligence about a vulnerability, like the availability of an exploit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct VulnerabilityAnnotation {
+    pub id: Uuid,
+    /// The identifier of the vulnerability
+    pub vulnerability: String,
+    pub kind: AnnotationKind,
+    /// A link to further information, like a proof of concept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// The feed supplying the annotation, absent if supplied by a user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// The ID of the user creating the annotation, absent if authentication is disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    /// The time of the last change
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified: OffsetDateTime,
+}
+
+impl From<vulnerability_annotation::Model> for VulnerabilityAnnotation {
+    fn from(value: vulnerability_annotation::Model) -> Self {
+        let vulnerability_annotation::Model {
+            id,
+            vulnerability_id,
+            kind,
+            url,
+            comment,
+            source,
+            user_id,
+            tenant: _,
+            created,
+            modified,
+        } = value;
+
+        Self {
+            id,
+            vulnerability: vulnerability_id,
+            kind,
+            url,
+            comment,
+            source,
+            user: user_id,
+            created,
+            modified,
+        }
+    }
+}
+
+/// The content of an annotation, when creating or changing it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AnnotationRequest {
+    pub kind: AnnotationKind,
+    /// A link to further information, like a proof of concept
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub comment: Option<String>,
+    /// The feed supplying the annotation, absent if supplied by a user
+    #[serde(default)]
+    pub source: Option<String>,
+}
@@ -0,0 +1,178 @@
+use crate::{
+    Error,
+    annotation::model::{AnnotationRequest, VulnerabilityAnnotation},
+    tenant::Tenant,
+};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+    TransactionTrait, prelude::Uuid,
+};
+use time::OffsetDateTime;
+use trustify_common::{
+    db::{Database, limiter::LimiterTrait},
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{vulnerability, vulnerability_annotation};
+
+pub struct AnnotationService {
+    db: Database,
+}
+
+impl AnnotationService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Annotate a vulnerability. Returns `None` if the vulnerability was not found.
+    pub async fn create(
+        &self,
+        vulnerability: &str,
+        request: AnnotationRequest,
+        user_id: Option<String>,
+        tenant: &Tenant,
+    ) -> Result<Option<VulnerabilityAnnotation>, Error> {
+        if !self.exists(vulnerability).await? {
+            return Ok(None);
+        }
+
+        let AnnotationRequest {
+            kind,
+            url,
+            comment,
+            source,
+        } = request;
+
+        let now = OffsetDateTime::now_utc();
+        let result =
+            vulnerability_annotation::Entity::insert(vulnerability_annotation::ActiveModel {
+                id: Set(Uuid::now_v7()),
+                vulnerability_id: Set(vulnerability.to_string()),
+                kind: Set(kind),
+                url: Set(url),
+                comment: Set(comment),
+                source: Set(source),
+                user_id: Set(user_id),
+                tenant: Set(tenant.name().map(ToString::to_string)),
+                created: Set(now),
+                modified: Set(now),
+            })
+            .exec_with_returning(&self.db)
+            .await?;
+
+        Ok(Some(result.into()))
+    }
+
+    /// List the annotations of a vulnerability, oldest first. Returns `None` if the vulnerability
+    /// was not found.
+    pub async fn list(
+        &self,
+        vulnerability: &str,
+        paginated: Paginated,
+        tenant: &Tenant,
+    ) -> Result<Option<PaginatedResults<VulnerabilityAnnotation>>, Error> {
+        if !self.exists(vulnerability).await? {
+            return Ok(None);
+        }
+
+        let limiter = vulnerability_annotation::Entity::find()
+            .filter(vulnerability_annotation::Column::VulnerabilityId.eq(vulnerability))
+            .filter(visible(tenant))
+            .order_by_asc(vulnerability_annotation::Column::Created)
+            .order_by_asc(vulnerability_annotation::Column::Id)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        Ok(Some(PaginatedResults {
+            total: limiter.total().await?,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(VulnerabilityAnnotation::from)
+                .collect(),
+        }))
+    }
+
+    /// Change an annotation. Returns `None` if the annotation was not found.
+    ///
+    /// The user who created the annotation is kept. Tenants only change their own annotations,
+    /// not the ones of feeds.
+    pub async fn update(
+        &self,
+        vulnerability: &str,
+        id: Uuid,
+        request: AnnotationRequest,
+        tenant: &Tenant,
+    ) -> Result<Option<VulnerabilityAnnotation>, Error> {
+        let AnnotationRequest {
+            kind,
+            url,
+            comment,
+            source,
+        } = request;
+
+        let tx = self.db.begin().await?;
+
+        if vulnerability_annotation::Entity::find_by_id(id)
+            .filter(vulnerability_annotation::Column::VulnerabilityId.eq(vulnerability))
+            .filter(tenant.column_condition(vulnerability_annotation::Column::Tenant))
+            .lock_exclusive()
+            .one(&tx)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let result =
+            vulnerability_annotation::Entity::update(vulnerability_annotation::ActiveModel {
+                id: Set(id),
+                kind: Set(kind),
+                url: Set(url),
+                comment: Set(comment),
+                source: Set(source),
+                modified: Set(OffsetDateTime::now_utc()),
+                ..Default::default()
+            })
+            .exec(&tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(result.into()))
+    }
+
+    /// Delete an annotation. Returns `false` if the annotation was not found.
+    pub async fn delete(
+        &self,
+        vulnerability: &str,
+        id: Uuid,
+        tenant: &Tenant,
+    ) -> Result<bool, Error> {
+        let result = vulnerability_annotation::Entity::delete_many()
+            .filter(vulnerability_annotation::Column::Id.eq(id))
+            .filter(vulnerability_annotation::Column::VulnerabilityId.eq(vulnerability))
+            .filter(tenant.column_condition(vulnerability_annotation::Column::Tenant))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn exists(&self, vulnerability: &str) -> Result<bool, Error> {
+        Ok(vulnerability::Entity::find_by_id(vulnerability)
+            .one(&self.db)
+            .await?
+            .is_some())
+    }
+}
+
+/// The annotations visible to a tenant: its own ones, and the ones of feeds.
+fn visible(tenant: &Tenant) -> Condition {
+    match tenant.name() {
+        None => Condition::all(),
+        Some(name) => Condition::any()
+            .add(vulnerability_annotation::Column::Tenant.is_null())
+            .add(vulnerability_annotation::Column::Tenant.eq(name)),
+    }
+}
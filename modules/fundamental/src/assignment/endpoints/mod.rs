@@ -0,0 +1,208 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    assignment::{
+        model::{Assignment, AssignmentEvent, AssignmentUpdate, NewAssignment},
+        service::AssignmentService,
+    },
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, get, http::header, post, put, web};
+use trustify_auth::{
+    CreateAssignment, ReadAssignment, UpdateAssignment,
+    authenticator::user::{UserDetails, UserInformation},
+    authorizer::Require,
+};
+use trustify_common::{
+    db::{Database, query::Query},
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults},
+};
+use uuid::Uuid;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = AssignmentService::new(db);
+
+    config
+        .app_data(web::Data::new(service))
+        .service(list)
+        // must be registered before the lookup by ID
+        .service(list_mine)
+        .service(create)
+        .service(get)
+        .service(update)
+        .service(history);
+}
+
+#[utoipa::path(
+    tag = "assignment",
+    operation_id = "listAssignments",
+    params(
+        Query,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Matching assignments, most urgent first", body = PaginatedResults<Assignment>),
+    ),
+)]
+#[get("/v2/assignment")]
+/// List assignments
+pub async fn list(
+    service: web::Data<AssignmentService>,
+    web::Query(search): web::Query<Query>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadAssignment>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(service.list(search, paginated, &tenant).await?))
+}
+
+#[utoipa::path(
+    tag = "assignment",
+    operation_id = "listMyAssignments",
+    params(
+        Query,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Open assignments of the current user, most urgent first", body = PaginatedResults<Assignment>),
+    ),
+)]
+#[get("/v2/assignment/mine")]
+/// List the open assignments of the current user
+pub async fn list_mine(
+    service: web::Data<AssignmentService>,
+    web::Query(search): web::Query<Query>,
+    web::Query(paginated): web::Query<Paginated>,
+    user: UserDetails,
+    _: Require<ReadAssignment>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(
+        service
+            .list_open(&user.id, search, paginated, &tenant)
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    tag = "assignment",
+    operation_id = "createAssignment",
+    request_body = NewAssignment,
+    responses(
+        (status = 201, description = "Created the assignment", body = Assignment),
+        (status = 400, description = "The vulnerability or SBOM does not exist", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/assignment")]
+/// Assign a vulnerability to a user or team
+pub async fn create(
+    service: web::Data<AssignmentService>,
+    user: UserInformation,
+    web::Json(request): web::Json<NewAssignment>,
+    _: Require<CreateAssignment>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let result = service
+        .create(request, user.id().map(ToString::to_string), &tenant)
+        .await?;
+    Ok(HttpResponse::Created()
+        .insert_header((
+            header::LOCATION,
+            format!("/api/v2/assignment/{}", result.id),
+        ))
+        .json(result))
+}
+
+#[utoipa::path(
+    tag = "assignment",
+    operation_id = "getAssignment",
+    params(
+        ("id" = Uuid, Path, description = "ID of the assignment"),
+    ),
+    responses(
+        (status = 200, description = "The assignment", body = Assignment),
+        (status = 404, description = "The assignment could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/assignment/{id}")]
+/// Retrieve an assignment
+pub async fn get(
+    service: web::Data<AssignmentService>,
+    id: web::Path<Uuid>,
+    _: Require<ReadAssignment>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(match service.fetch(id.into_inner(), &tenant).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "assignment",
+    operation_id = "updateAssignment",
+    request_body = AssignmentUpdate,
+    params(
+        ("id" = Uuid, Path, description = "ID of the assignment"),
+    ),
+    responses(
+        (status = 200, description = "The updated assignment", body = Assignment),
+        (status = 404, description = "The assignment could not be found", body = ErrorInformation),
+    ),
+)]
+#[put("/v2/assignment/{id}")]
+/// Change the assignee, state, or due date of an assignment
+pub async fn update(
+    service: web::Data<AssignmentService>,
+    id: web::Path<Uuid>,
+    user: UserInformation,
+    web::Json(request): web::Json<AssignmentUpdate>,
+    _: Require<UpdateAssignment>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(
+        match service
+            .update(
+                id.into_inner(),
+                request,
+                user.id().map(ToString::to_string),
+                &tenant,
+            )
+            .await?
+        {
+            Some(result) => HttpResponse::Ok().json(result),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+#[utoipa::path(
+    tag = "assignment",
+    operation_id = "listAssignmentHistory",
+    params(
+        ("id" = Uuid, Path, description = "ID of the assignment"),
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "The changes of the assignment, oldest first", body = PaginatedResults<AssignmentEvent>),
+        (status = 404, description = "The assignment could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/assignment/{id}/history")]
+/// Get the changes of an assignment
+pub async fn history(
+    service: web::Data<AssignmentService>,
+    id: web::Path<Uuid>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadAssignment>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(
+        match service.history(id.into_inner(), paginated, &tenant).await? {
+            Some(result) => HttpResponse::Ok().json(result),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
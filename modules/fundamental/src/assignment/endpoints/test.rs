@@ -0,0 +1,212 @@
+use crate::{
+    assignment::model::{Assignment, AssignmentEvent, AssignmentState},
+    test::{caller, caller_with, tenant_isolation, tenant_user},
+};
+use actix_http::StatusCode;
+use actix_web::test::{TestRequest, read_body_json};
+use serde_json::json;
+use test_context::test_context;
+use test_log::test;
+use trustify_common::{id::Id, model::PaginatedResults};
+use trustify_module_ingestor::model::IngestResult;
+use trustify_test_context::{
+    TrustifyContext, auth::TestAuthentication, call::CallService, document_bytes,
+};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn assign_and_resolve(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_documents(["mitre/CVE-2024-27088.json", "mitre/CVE-2024-28111.json"])
+        .await?;
+    let app = caller(ctx).await?;
+
+    // assign both vulnerabilities, one of them to a team
+
+    let mut ids = vec![];
+    for (vulnerability, assignee, due) in [
+        ("CVE-2024-27088", "user-a", json!("2030-01-01T00:00:00Z")),
+        ("CVE-2024-28111", "team-a", json!(null)),
+    ] {
+        let request = TestRequest::post()
+            .uri("/api/v2/assignment")
+            .set_json(json!({
+                "vulnerability": vulnerability,
+                "assignee": assignee,
+                "due": due,
+            }))
+            .to_request()
+            .test_auth("user-b");
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let result: Assignment = read_body_json(response).await;
+        assert_eq!(result.state, AssignmentState::New);
+        ids.push(result.id);
+    }
+
+    // unknown vulnerabilities can't be assigned
+
+    let request = TestRequest::post()
+        .uri("/api/v2/assignment")
+        .set_json(json!({"vulnerability": "CVE-0000-0000", "assignee": "user-a"}))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // the queue of user-a
+
+    let request = TestRequest::get()
+        .uri("/api/v2/assignment/mine")
+        .to_request()
+        .test_auth("user-a");
+    let result: PaginatedResults<Assignment> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].vulnerability, "CVE-2024-27088");
+
+    // work on it, and resolve it
+
+    for state in ["in_progress", "resolved"] {
+        let request = TestRequest::put()
+            .uri(&format!("/api/v2/assignment/{}", ids[0]))
+            .set_json(json!({"assignee": "user-a", "state": state, "due": "2030-01-01T00:00:00Z"}))
+            .to_request()
+            .test_auth("user-a");
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // resolved assignments leave the queue
+
+    let request = TestRequest::get()
+        .uri("/api/v2/assignment/mine")
+        .to_request()
+        .test_auth("user-a");
+    let result: PaginatedResults<Assignment> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 0);
+
+    // but can still be found, as can the open assignments of the team
+
+    let request = TestRequest::get()
+        .uri("/api/v2/assignment?q=state%3Dresolved")
+        .to_request();
+    let result: PaginatedResults<Assignment> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].id, ids[0]);
+
+    let request = TestRequest::get()
+        .uri("/api/v2/assignment?q=assignee%3Dteam-a%26state!%3Dresolved")
+        .to_request();
+    let result: PaginatedResults<Assignment> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].id, ids[1]);
+
+    // all transitions are recorded
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/assignment/{}/history", ids[0]))
+        .to_request();
+    let result: PaginatedResults<AssignmentEvent> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 3);
+    assert_eq!(
+        result
+            .items
+            .iter()
+            .map(|event| (event.user.as_deref(), event.state))
+            .collect::<Vec<_>>(),
+        vec![
+            (Some("user-b"), AssignmentState::New),
+            (Some("user-a"), AssignmentState::InProgress),
+            (Some("user-a"), AssignmentState::Resolved),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn not_found(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller(ctx).await?;
+    let id = uuid::Uuid::now_v7();
+
+    for request in [
+        TestRequest::get().uri(&format!("/api/v2/assignment/{id}")),
+        TestRequest::get().uri(&format!("/api/v2/assignment/{id}/history")),
+        TestRequest::put()
+            .uri(&format!("/api/v2/assignment/{id}"))
+            .set_json(json!({"assignee": "user-a", "state": "new"})),
+    ] {
+        let response = app.call_service(request.to_request()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn other_tenants(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_document("mitre/CVE-2024-27088.json").await?;
+    let app = caller_with(ctx, tenant_isolation()).await?;
+
+    // tenant a assigns a finding in its SBOM
+
+    let request = TestRequest::post()
+        .uri("/api/v2/sbom")
+        .set_payload(document_bytes("zookeeper-3.9.2-cyclonedx.json").await?)
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let result: IngestResult = read_body_json(response).await;
+    let Id::Uuid(sbom) = result.id else {
+        panic!("unexpected ID: {}", result.id);
+    };
+
+    let assignment = json!({
+        "vulnerability": "CVE-2024-27088",
+        "sbom": sbom,
+        "assignee": "user-a",
+    });
+    let request = TestRequest::post()
+        .uri("/api/v2/assignment")
+        .set_json(&assignment)
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let result: Assignment = read_body_json(response).await;
+
+    // tenant b can't assign findings in the SBOM of tenant a
+
+    let request = TestRequest::post()
+        .uri("/api/v2/assignment")
+        .set_json(&assignment)
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // nor sees or changes the assignment of tenant a
+
+    let request = TestRequest::get()
+        .uri("/api/v2/assignment")
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let list: PaginatedResults<Assignment> = app.call_and_read_body_json(request).await;
+    assert_eq!(list.total, 0);
+
+    let uri = format!("/api/v2/assignment/{}", result.id);
+    for request in [
+        TestRequest::get().uri(&uri),
+        TestRequest::get().uri(&format!("{uri}/history")),
+        TestRequest::put()
+            .uri(&uri)
+            .set_json(json!({"assignee": "user-b", "state": "new"})),
+    ] {
+        let request = request.to_request().test_auth_details(tenant_user("b"));
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::{assignment, assignment_event};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub use assignment::State as AssignmentState;
+
+/// A vulnerability, assigned to a user or team for triage.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Assignment {
+    pub id: Uuid,
+    /// The identifier of the vulnerability
+    pub vulnerability: String,
+    /// The SBOM, in case the assignment is about a finding in that SBOM
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sbom: Option<Uuid>,
+    /// The user or team working on the assignment
+    pub assignee: String,
+    pub state: AssignmentState,
+    /// The date the assignment is due
+    #[schema(required)]
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub due: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    /// The time of the last change
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified: OffsetDateTime,
+}
+
+impl From<assignment::Model> for Assignment {
+    fn from(value: assignment::Model) -> Self {
+        let assignment::Model {
+            id,
+            vulnerability_id,
+            sbom_id,
+            assignee,
+            state,
+            due,
+            tenant: _,
+            created,
+            modified,
+        } = value;
+
+        Self {
+            id,
+            vulnerability: vulnerability_id,
+            sbom: sbom_id,
+            assignee,
+            state,
+            due,
+            created,
+            modified,
+        }
+    }
+}
+
+/// A request to assign a vulnerability.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct NewAssignment {
+    /// The identifier of the vulnerability
+    pub vulnerability: String,
+    /// The SBOM, in case the assignment is about a finding in that SBOM
+    #[serde(default)]
+    pub sbom: Option<Uuid>,
+    /// The user or team to work on the assignment
+    pub assignee: String,
+    /// The date the assignment is due
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub due: Option<OffsetDateTime>,
+}
+
+/// The changeable state of an assignment.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AssignmentUpdate {
+    /// The user or team working on the assignment
+    pub assignee: String,
+    pub state: AssignmentState,
+    /// The date the assignment is due
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub due: Option<OffsetDateTime>,
+}
+
+/// A change of an assignment, with the state after the change.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AssignmentEvent {
+    /// The time of the change
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    /// The ID of the user performing the change, absent if authentication is disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    pub assignee: String,
+    pub state: AssignmentState,
+    #[schema(required)]
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub due: Option<OffsetDateTime>,
+}
+
+impl From<assignment_event::Model> for AssignmentEvent {
+    fn from(value: assignment_event::Model) -> Self {
+        let assignment_event::Model {
+            id: _,
+            assignment_id: _,
+            timestamp,
+            user_id,
+            assignee,
+            state,
+            due,
+        } = value;
+
+        Self {
+            timestamp,
+            user: user_id,
+            assignee,
+            state,
+            due,
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use crate::{
+    Error,
+    assignment::model::{
+        Assignment, AssignmentEvent, AssignmentState, AssignmentUpdate, NewAssignment,
+    },
+    tenant::Tenant,
+};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Select, TransactionTrait, prelude::Uuid,
+};
+use time::OffsetDateTime;
+use trustify_common::{
+    db::{
+        Database,
+        limiter::LimiterTrait,
+        query::{Filtering, Query},
+    },
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{assignment, assignment_event, sbom, vulnerability};
+
+pub struct AssignmentService {
+    db: Database,
+}
+
+impl AssignmentService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Assign a vulnerability, recording the assignment as its first event.
+    pub async fn create(
+        &self,
+        request: NewAssignment,
+        user_id: Option<String>,
+        tenant: &Tenant,
+    ) -> Result<Assignment, Error> {
+        let tx = self.db.begin().await?;
+
+        if vulnerability::Entity::find_by_id(&request.vulnerability)
+            .one(&tx)
+            .await?
+            .is_none()
+        {
+            return Err(Error::BadRequest(format!(
+                "Unknown vulnerability: {}",
+                request.vulnerability
+            )));
+        }
+        if let Some(sbom_id) = request.sbom {
+            if sbom::Entity::find_by_id(sbom_id)
+                .filter(tenant.sbom_condition(sbom::Column::SbomId))
+                .one(&tx)
+                .await?
+                .is_none()
+            {
+                return Err(Error::BadRequest(format!("Unknown SBOM: {sbom_id}")));
+            }
+        }
+
+        let now = OffsetDateTime::now_utc();
+        let result = assignment::Entity::insert(assignment::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            vulnerability_id: Set(request.vulnerability),
+            sbom_id: Set(request.sbom),
+            assignee: Set(request.assignee),
+            state: Set(AssignmentState::New),
+            due: Set(request.due),
+            tenant: Set(tenant.name().map(ToString::to_string)),
+            created: Set(now),
+            modified: Set(now),
+        })
+        .exec_with_returning(&tx)
+        .await?;
+
+        record(&tx, &result, user_id).await?;
+
+        tx.commit().await?;
+
+        Ok(result.into())
+    }
+
+    pub async fn fetch(&self, id: Uuid, tenant: &Tenant) -> Result<Option<Assignment>, Error> {
+        Ok(assignment::Entity::find_by_id(id)
+            .filter(tenant.column_condition(assignment::Column::Tenant))
+            .one(&self.db)
+            .await?
+            .map(Assignment::from))
+    }
+
+    /// Change an assignment, recording the change. Returns `None` if the assignment was not found.
+    ///
+    /// Requests not changing anything are not recorded.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        update: AssignmentUpdate,
+        user_id: Option<String>,
+        tenant: &Tenant,
+    ) -> Result<Option<Assignment>, Error> {
+        let tx = self.db.begin().await?;
+
+        let Some(current) = assignment::Entity::find_by_id(id)
+            .filter(tenant.column_condition(assignment::Column::Tenant))
+            .lock_exclusive()
+            .one(&tx)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let AssignmentUpdate {
+            assignee,
+            state,
+            due,
+        } = update;
+
+        if current.assignee == assignee && current.state == state && current.due == due {
+            return Ok(Some(current.into()));
+        }
+
+        let result = assignment::Entity::update(assignment::ActiveModel {
+            id: Set(id),
+            assignee: Set(assignee),
+            state: Set(state),
+            due: Set(due),
+            modified: Set(OffsetDateTime::now_utc()),
+            ..Default::default()
+        })
+        .exec(&tx)
+        .await?;
+
+        record(&tx, &result, user_id).await?;
+
+        tx.commit().await?;
+
+        Ok(Some(result.into()))
+    }
+
+    /// List assignments, ordered by their due date by default.
+    pub async fn list(
+        &self,
+        query: Query,
+        paginated: Paginated,
+        tenant: &Tenant,
+    ) -> Result<PaginatedResults<Assignment>, Error> {
+        self.list_where(assignment::Entity::find(), query, paginated, tenant)
+            .await
+    }
+
+    /// List the open assignments of an assignee, ordered by their due date by default.
+    pub async fn list_open(
+        &self,
+        assignee: &str,
+        query: Query,
+        paginated: Paginated,
+        tenant: &Tenant,
+    ) -> Result<PaginatedResults<Assignment>, Error> {
+        self.list_where(
+            assignment::Entity::find()
+                .filter(assignment::Column::Assignee.eq(assignee))
+                .filter(assignment::Column::State.ne(AssignmentState::Resolved)),
+            query,
+            paginated,
+            tenant,
+        )
+        .await
+    }
+
+    async fn list_where(
+        &self,
+        select: Select<assignment::Entity>,
+        query: Query,
+        paginated: Paginated,
+        tenant: &Tenant,
+    ) -> Result<PaginatedResults<Assignment>, Error> {
+        let limiter = select
+            .filter(tenant.column_condition(assignment::Column::Tenant))
+            .filtering(query)?
+            // default order: most urgent first, without a due date last
+            .order_by_asc(assignment::Column::Due)
+            .order_by_asc(assignment::Column::Created)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        let total = limiter.total().await?;
+        let items = limiter.fetch().await?;
+
+        Ok(PaginatedResults {
+            items: items.into_iter().map(Assignment::from).collect(),
+            total,
+        })
+    }
+
+    /// Get the changes of an assignment, oldest first. Returns `None` if the assignment was not
+    /// found.
+    pub async fn history(
+        &self,
+        id: Uuid,
+        paginated: Paginated,
+        tenant: &Tenant,
+    ) -> Result<Option<PaginatedResults<AssignmentEvent>>, Error> {
+        if assignment::Entity::find_by_id(id)
+            .filter(tenant.column_condition(assignment::Column::Tenant))
+            .one(&self.db)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let limiter = assignment_event::Entity::find()
+            .filter(assignment_event::Column::AssignmentId.eq(id))
+            .order_by_asc(assignment_event::Column::Timestamp)
+            .order_by_asc(assignment_event::Column::Id)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        Ok(Some(PaginatedResults {
+            total: limiter.total().await?,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(AssignmentEvent::from)
+                .collect(),
+        }))
+    }
+}
+
+/// Record the current state of an assignment in its history.
+async fn record(
+    db: &impl ConnectionTrait,
+    assignment: &assignment::Model,
+    user_id: Option<String>,
+) -> Result<(), Error> {
+    assignment_event::Entity::insert(assignment_event::ActiveModel {
+        id: Set(Uuid::now_v7()),
+        assignment_id: Set(assignment.id),
+        timestamp: Set(assignment.modified),
+        user_id: Set(user_id),
+        assignee: Set(assignment.assignee.clone()),
+        state: Set(assignment.state),
+        due: Set(assignment.due),
+    })
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,389 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    Error,
+    attachment::{
+        model::Attachment,
+        service::{AttachmentService, Document, NewAttachment},
+    },
+    tenant::Tenant,
+};
+use actix_web::{
+    HttpRequest, HttpResponse, Responder, delete, get,
+    http::header::{self, ContentDisposition, DispositionParam, DispositionType},
+    post, web,
+};
+use futures_util::TryStreamExt;
+use std::str::FromStr;
+use trustify_auth::{
+    ReadAdvisory, ReadSbom, UpdateAdvisory, UpdateSbom, authenticator::user::UserInformation,
+    authorizer::Require,
+};
+use trustify_common::{db::Database, error::ErrorInformation, id::Id, model::BinaryData};
+use trustify_module_ingestor::service::IngestorService;
+use trustify_module_storage::service::StorageBackend;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = AttachmentService::new(db);
+
+    config
+        .app_data(web::Data::new(service))
+        .service(list_sbom)
+        .service(upload_sbom)
+        .service(download_sbom)
+        .service(delete_sbom)
+        .service(list_advisory)
+        .service(upload_advisory)
+        .service(download_advisory)
+        .service(delete_advisory);
+}
+
+#[derive(IntoParams, Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+struct UploadParams {
+    /// The file name of the attachment
+    name: String,
+}
+
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "listSbomAttachments",
+    params(
+        ("id" = Id, Path, description = "Digest/hash of the document, prefixed by hash type, such as 'sha256:<hash>' or 'urn:uuid:<uuid>'"),
+    ),
+    responses(
+        (status = 200, description = "Files attached to the SBOM, oldest first", body = Vec<Attachment>),
+        (status = 404, description = "The SBOM could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/sbom/{id}/attachment")]
+/// List the files attached to an SBOM
+pub async fn list_sbom(
+    service: web::Data<AttachmentService>,
+    db: web::Data<Database>,
+    id: web::Path<Id>,
+    _: Require<ReadSbom>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let id = id.into_inner();
+    let id = tenant.require_sbom(&id, db.as_ref()).await?;
+
+    list(&service, Document::Sbom(id)).await
+}
+
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "uploadSbomAttachment",
+    request_body = inline(BinaryData),
+    params(
+        ("id" = Id, Path, description = "Digest/hash of the document, prefixed by hash type, such as 'sha256:<hash>' or 'urn:uuid:<uuid>'"),
+        UploadParams,
+    ),
+    responses(
+        (status = 201, description = "Attached the file to the SBOM", body = Attachment),
+        (status = 400, description = "The file name is invalid", body = ErrorInformation),
+        (status = 404, description = "The SBOM could not be found", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/sbom/{id}/attachment")]
+/// Attach a file, like a scan report, to an SBOM
+///
+/// The media type of the file is taken from the `Content-Type` header.
+pub async fn upload_sbom(
+    service: web::Data<AttachmentService>,
+    db: web::Data<Database>,
+    ingestor: web::Data<IngestorService>,
+    id: web::Path<Id>,
+    web::Query(params): web::Query<UploadParams>,
+    req: HttpRequest,
+    user: UserInformation,
+    content: web::Bytes,
+    _: Require<UpdateSbom>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let id = id.into_inner();
+    let id = tenant.require_sbom(&id, db.as_ref()).await?;
+
+    upload(
+        &service,
+        &ingestor,
+        Document::Sbom(id),
+        params,
+        &req,
+        &user,
+        content,
+    )
+    .await
+}
+
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "downloadSbomAttachment",
+    params(
+        ("id" = Id, Path, description = "Digest/hash of the document, prefixed by hash type, such as 'sha256:<hash>' or 'urn:uuid:<uuid>'"),
+        ("attachment" = Uuid, Path, description = "ID of the attachment"),
+    ),
+    responses(
+        (status = 200, description = "The content of the file", body = inline(BinaryData)),
+        (status = 404, description = "The SBOM or the attachment could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/sbom/{id}/attachment/{attachment}")]
+/// Download a file attached to an SBOM
+pub async fn download_sbom(
+    service: web::Data<AttachmentService>,
+    db: web::Data<Database>,
+    ingestor: web::Data<IngestorService>,
+    path: web::Path<(Id, Uuid)>,
+    _: Require<ReadSbom>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (id, attachment) = path.into_inner();
+    let id = tenant.require_sbom(&id, db.as_ref()).await?;
+
+    download(&service, &ingestor, Document::Sbom(id), attachment).await
+}
+
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "deleteSbomAttachment",
+    params(
+        ("id" = Id, Path, description = "Digest/hash of the document, prefixed by hash type, such as 'sha256:<hash>' or 'urn:uuid:<uuid>'"),
+        ("attachment" = Uuid, Path, description = "ID of the attachment"),
+    ),
+    responses(
+        (status = 200, description = "Removed the attachment", body = Attachment),
+        (status = 404, description = "The SBOM or the attachment could not be found", body = ErrorInformation),
+    ),
+)]
+#[delete("/v2/sbom/{id}/attachment/{attachment}")]
+/// Remove a file attached to an SBOM
+pub async fn delete_sbom(
+    service: web::Data<AttachmentService>,
+    db: web::Data<Database>,
+    path: web::Path<(Id, Uuid)>,
+    _: Require<UpdateSbom>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (id, attachment) = path.into_inner();
+    let id = tenant.require_sbom(&id, db.as_ref()).await?;
+
+    remove(&service, Document::Sbom(id), attachment).await
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "listAdvisoryAttachments",
+    params(
+        ("key" = Id, Path),
+    ),
+    responses(
+        (status = 200, description = "Files attached to the advisory, oldest first", body = Vec<Attachment>),
+        (status = 404, description = "The advisory could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/advisory/{key}/attachment")]
+/// List the files attached to an advisory
+pub async fn list_advisory(
+    service: web::Data<AttachmentService>,
+    db: web::Data<Database>,
+    key: web::Path<String>,
+    _: Require<ReadAdvisory>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let id = Id::from_str(&key).map_err(Error::IdKey)?;
+    let id = tenant.require_advisory(&id, db.as_ref()).await?;
+
+    list(&service, Document::Advisory(id)).await
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "uploadAdvisoryAttachment",
+    request_body = inline(BinaryData),
+    params(
+        ("key" = Id, Path),
+        UploadParams,
+    ),
+    responses(
+        (status = 201, description = "Attached the file to the advisory", body = Attachment),
+        (status = 400, description = "The file name is invalid", body = ErrorInformation),
+        (status = 404, description = "The advisory could not be found", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/advisory/{key}/attachment")]
+/// Attach a file, like a waiver, to an advisory
+///
+/// The media type of the file is taken from the `Content-Type` header.
+pub async fn upload_advisory(
+    service: web::Data<AttachmentService>,
+    db: web::Data<Database>,
+    ingestor: web::Data<IngestorService>,
+    key: web::Path<String>,
+    web::Query(params): web::Query<UploadParams>,
+    req: HttpRequest,
+    user: UserInformation,
+    content: web::Bytes,
+    _: Require<UpdateAdvisory>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let id = Id::from_str(&key).map_err(Error::IdKey)?;
+    let id = tenant.require_advisory(&id, db.as_ref()).await?;
+
+    upload(
+        &service,
+        &ingestor,
+        Document::Advisory(id),
+        params,
+        &req,
+        &user,
+        content,
+    )
+    .await
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "downloadAdvisoryAttachment",
+    params(
+        ("key" = Id, Path),
+        ("attachment" = Uuid, Path, description = "ID of the attachment"),
+    ),
+    responses(
+        (status = 200, description = "The content of the file", body = inline(BinaryData)),
+        (status = 404, description = "The advisory or the attachment could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/advisory/{key}/attachment/{attachment}")]
+/// Download a file attached to an advisory
+pub async fn download_advisory(
+    service: web::Data<AttachmentService>,
+    db: web::Data<Database>,
+    ingestor: web::Data<IngestorService>,
+    path: web::Path<(String, Uuid)>,
+    _: Require<ReadAdvisory>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (key, attachment) = path.into_inner();
+    let id = Id::from_str(&key).map_err(Error::IdKey)?;
+    let id = tenant.require_advisory(&id, db.as_ref()).await?;
+
+    download(&service, &ingestor, Document::Advisory(id), attachment).await
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "deleteAdvisoryAttachment",
+    params(
+        ("key" = Id, Path),
+        ("attachment" = Uuid, Path, description = "ID of the attachment"),
+    ),
+    responses(
+        (status = 200, description = "Removed the attachment", body = Attachment),
+        (status = 404, description = "The advisory or the attachment could not be found", body = ErrorInformation),
+    ),
+)]
+#[delete("/v2/advisory/{key}/attachment/{attachment}")]
+/// Remove a file attached to an advisory
+pub async fn delete_advisory(
+    service: web::Data<AttachmentService>,
+    db: web::Data<Database>,
+    path: web::Path<(String, Uuid)>,
+    _: Require<UpdateAdvisory>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (key, attachment) = path.into_inner();
+    let id = Id::from_str(&key).map_err(Error::IdKey)?;
+    let id = tenant.require_advisory(&id, db.as_ref()).await?;
+
+    remove(&service, Document::Advisory(id), attachment).await
+}
+
+async fn list(service: &AttachmentService, document: Document) -> actix_web::Result<HttpResponse> {
+    Ok(match service.list(document).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+async fn upload(
+    service: &AttachmentService,
+    ingestor: &IngestorService,
+    document: Document,
+    UploadParams { name }: UploadParams,
+    req: &HttpRequest,
+    user: &UserInformation,
+    content: web::Bytes,
+) -> actix_web::Result<HttpResponse> {
+    let name = sanitize_filename::sanitize(name);
+    if name.is_empty() {
+        return Err(Error::BadRequest("Missing file name".into()).into());
+    }
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("application/octet-stream")
+        .to_string();
+
+    let attachment = NewAttachment {
+        name,
+        content_type,
+        content,
+        uploader: user.id().map(ToString::to_string),
+    };
+
+    Ok(
+        match service
+            .create(document, attachment, ingestor.storage())
+            .await?
+        {
+            Some(result) => {
+                log::info!("Attached {} ({})", result.name, result.id);
+                HttpResponse::Created().json(result)
+            }
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+async fn download(
+    service: &AttachmentService,
+    ingestor: &IngestorService,
+    document: Document,
+    id: Uuid,
+) -> actix_web::Result<HttpResponse> {
+    let Some((attachment, key)) = service.locate(document, id).await? else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    let Some(stream) = ingestor
+        .storage()
+        .retrieve(key)
+        .await
+        .map_err(Error::Storage)?
+    else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, attachment.content_type))
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(attachment.name)],
+        })
+        .streaming(stream.map_err(Error::Storage)))
+}
+
+async fn remove(
+    service: &AttachmentService,
+    document: Document,
+    id: Uuid,
+) -> actix_web::Result<HttpResponse> {
+    Ok(match service.delete(document, id).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
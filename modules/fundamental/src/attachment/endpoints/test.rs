@@ -0,0 +1,118 @@
+use crate::{attachment::model::Attachment, test::caller};
+use actix_http::StatusCode;
+use actix_web::{
+    http::header,
+    test::{TestRequest, read_body, read_body_json},
+};
+use serde_json::Value;
+use test_context::test_context;
+use test_log::test;
+use trustify_test_context::{TrustifyContext, auth::TestAuthentication, call::CallService};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn attach_to_sbom(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let id = ctx
+        .ingest_document("quarkus-bom-2.13.8.Final-redhat-00004.json")
+        .await?
+        .id
+        .to_string();
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::post()
+        .uri(&format!("/api/v2/sbom/{id}/attachment?name=scan.txt"))
+        .insert_header((header::CONTENT_TYPE, "text/plain"))
+        .set_payload("no findings")
+        .test_auth("user-a")
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let attachment: Attachment = read_body_json(response).await;
+    assert_eq!(attachment.name, "scan.txt");
+    assert_eq!(attachment.content_type, "text/plain");
+    assert_eq!(attachment.size, 11);
+    assert_eq!(attachment.uploader.as_deref(), Some("user-a"));
+
+    // listed, also with the details of the SBOM
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{id}/attachment"))
+        .to_request();
+    let attachments: Vec<Attachment> = app.call_and_read_body_json(request).await;
+    assert_eq!(attachments, vec![attachment.clone()]);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{id}"))
+        .to_request();
+    let sbom: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(sbom["attachments"][0]["name"], "scan.txt");
+
+    // downloaded with the original name and media type
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{id}/attachment/{}", attachment.id))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "text/plain");
+    assert_eq!(
+        response.headers()[header::CONTENT_DISPOSITION],
+        r#"attachment; filename="scan.txt""#
+    );
+    assert_eq!(read_body(response).await, "no findings");
+
+    // removed
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/sbom/{id}/attachment/{}", attachment.id))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{id}/attachment/{}", attachment.id))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn attach_to_advisory(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let id = ctx
+        .ingest_document("csaf/cve-2023-0044.json")
+        .await?
+        .id
+        .to_string();
+    let app = caller(ctx).await?;
+
+    // without a media type, and with a path as name
+
+    let request = TestRequest::post()
+        .uri(&format!(
+            "/api/v2/advisory/{id}/attachment?name=..%2Fwaiver.pdf"
+        ))
+        .set_payload("%PDF-1.7")
+        .to_request();
+    let attachment: Attachment = app.call_and_read_body_json(request).await;
+    assert_eq!(attachment.name, "..waiver.pdf");
+    assert_eq!(attachment.content_type, "application/octet-stream");
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/advisory/{id}"))
+        .to_request();
+    let advisory: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(advisory["attachments"][0]["id"], attachment.id.to_string());
+
+    // only attached to the advisory
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{id}/attachment/{}", attachment.id))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
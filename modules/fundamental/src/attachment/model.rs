@@ -0,0 +1,70 @@
+use crate::Error;
+use sea_orm::{ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, sea_query::IntoCondition};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::attachment;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// An auxiliary file, like a scan report or a waiver, attached to a document.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Attachment {
+    pub id: Uuid,
+    /// The file name
+    pub name: String,
+    /// The media type of the content
+    pub content_type: String,
+    /// The size of the content, in bytes
+    pub size: u64,
+    /// The SHA256 digest of the content, prefixed by `sha256:`
+    pub sha256: String,
+    /// The ID of the user uploading the file, absent if authentication is disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uploader: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+}
+
+impl From<attachment::Model> for Attachment {
+    fn from(value: attachment::Model) -> Self {
+        let attachment::Model {
+            id,
+            sbom_id: _,
+            advisory_id: _,
+            name,
+            content_type,
+            size,
+            sha256,
+            uploader,
+            created,
+        } = value;
+
+        Self {
+            id,
+            name,
+            content_type,
+            size: size as u64,
+            sha256: format!("sha256:{sha256}"),
+            uploader,
+            created,
+        }
+    }
+}
+
+impl Attachment {
+    /// Collect the attachments matching a condition, like the ones of an SBOM, oldest first.
+    pub async fn find<C: ConnectionTrait>(
+        condition: impl IntoCondition,
+        db: &C,
+    ) -> Result<Vec<Self>, Error> {
+        Ok(attachment::Entity::find()
+            .filter(condition)
+            .order_by_asc(attachment::Column::Created)
+            .order_by_asc(attachment::Column::Id)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(Self::from)
+            .collect())
+    }
+}
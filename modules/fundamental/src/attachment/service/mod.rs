@@ -0,0 +1,194 @@
+use crate::{Error, attachment::model::Attachment};
+use actix_web::web::Bytes;
+use anyhow::anyhow;
+use futures_util::stream;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, JoinType, QueryFilter,
+    QuerySelect, RelationTrait, prelude::Uuid,
+};
+use time::OffsetDateTime;
+use trustify_common::{
+    db::Database,
+    id::{Id, TrySelectForId},
+};
+use trustify_entity::{advisory, attachment, sbom};
+use trustify_module_storage::service::{StorageBackend, StorageKey, dispatch::DispatchBackend};
+
+/// The document an attachment belongs to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Document {
+    Sbom(Id),
+    Advisory(Id),
+}
+
+/// A new attachment.
+#[derive(Clone, Debug)]
+pub struct NewAttachment {
+    pub name: String,
+    pub content_type: String,
+    pub content: Bytes,
+    pub uploader: Option<String>,
+}
+
+pub struct AttachmentService {
+    db: Database,
+}
+
+impl AttachmentService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// List the attachments of a document, oldest first. Returns `None` if the document was not
+    /// found.
+    pub async fn list(&self, document: Document) -> Result<Option<Vec<Attachment>>, Error> {
+        let Some(document) = resolve(&self.db, document).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(
+            Attachment::find(document.condition(), &self.db).await?,
+        ))
+    }
+
+    /// Attach a file to a document, storing its content. Returns `None` if the document was not
+    /// found.
+    pub async fn create(
+        &self,
+        document: Document,
+        attachment: NewAttachment,
+        storage: &DispatchBackend,
+    ) -> Result<Option<Attachment>, Error> {
+        let Some(document) = resolve(&self.db, document).await? else {
+            return Ok(None);
+        };
+
+        let NewAttachment {
+            name,
+            content_type,
+            content,
+            uploader,
+        } = attachment;
+        let size = content.len() as i64;
+
+        let stored = storage
+            .store(stream::once(async { Ok::<_, std::io::Error>(content) }))
+            .await
+            .map_err(|err| Error::Storage(anyhow!("{err}")))?;
+
+        let (sbom_id, advisory_id) = match document {
+            Resolved::Sbom(id) => (Some(id), None),
+            Resolved::Advisory(id) => (None, Some(id)),
+        };
+
+        let result = attachment::Entity::insert(attachment::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            sbom_id: Set(sbom_id),
+            advisory_id: Set(advisory_id),
+            name: Set(name),
+            content_type: Set(content_type),
+            size: Set(size),
+            sha256: Set(stored.key().to_string()),
+            uploader: Set(uploader),
+            created: Set(OffsetDateTime::now_utc()),
+        })
+        .exec_with_returning(&self.db)
+        .await?;
+
+        Ok(Some(result.into()))
+    }
+
+    /// Get an attachment of a document. Returns `None` if the document or the attachment was not
+    /// found.
+    pub async fn fetch(&self, document: Document, id: Uuid) -> Result<Option<Attachment>, Error> {
+        Ok(find(&self.db, document, id).await?.map(Attachment::from))
+    }
+
+    /// Get an attachment of a document, along with the key of its content in the storage.
+    /// Returns `None` if the document or the attachment was not found.
+    pub async fn locate(
+        &self,
+        document: Document,
+        id: Uuid,
+    ) -> Result<Option<(Attachment, StorageKey)>, Error> {
+        let Some(attachment) = find(&self.db, document, id).await? else {
+            return Ok(None);
+        };
+
+        let key = Id::Sha256(attachment.sha256.clone()).try_into()?;
+        Ok(Some((attachment.into(), key)))
+    }
+
+    /// Remove an attachment from a document. Returns `None` if the document or the attachment was
+    /// not found.
+    ///
+    /// The content is kept in the storage, as it might be shared with other documents.
+    pub async fn delete(&self, document: Document, id: Uuid) -> Result<Option<Attachment>, Error> {
+        let Some(attachment) = find(&self.db, document, id).await? else {
+            return Ok(None);
+        };
+
+        attachment::Entity::delete_by_id(attachment.id)
+            .exec(&self.db)
+            .await?;
+
+        Ok(Some(attachment.into()))
+    }
+}
+
+/// A document, by its internal ID.
+#[derive(Copy, Clone, Debug)]
+enum Resolved {
+    Sbom(Uuid),
+    Advisory(Uuid),
+}
+
+impl Resolved {
+    fn condition(self) -> sea_orm::Condition {
+        match self {
+            Self::Sbom(id) => attachment::Column::SbomId.eq(id).into(),
+            Self::Advisory(id) => attachment::Column::AdvisoryId.eq(id).into(),
+        }
+    }
+}
+
+/// Look up the ID of a (not deleted) document.
+async fn resolve(db: &impl ConnectionTrait, document: Document) -> Result<Option<Resolved>, Error> {
+    Ok(match document {
+        Document::Sbom(id) => sbom::Entity::find()
+            .select_only()
+            .column(sbom::Column::SbomId)
+            .join(JoinType::LeftJoin, sbom::Relation::SourceDocument.def())
+            .try_filter(id)?
+            .filter(sbom::Column::DeletedAt.is_null())
+            .into_tuple()
+            .one(db)
+            .await?
+            .map(Resolved::Sbom),
+        Document::Advisory(id) => advisory::Entity::find()
+            .select_only()
+            .column(advisory::Column::Id)
+            .join(JoinType::LeftJoin, advisory::Relation::SourceDocument.def())
+            .try_filter(id)?
+            .filter(advisory::Column::DeletedAt.is_null())
+            .into_tuple()
+            .one(db)
+            .await?
+            .map(Resolved::Advisory),
+    })
+}
+
+async fn find(
+    db: &impl ConnectionTrait,
+    document: Document,
+    id: Uuid,
+) -> Result<Option<attachment::Model>, Error> {
+    let Some(document) = resolve(db, document).await? else {
+        return Ok(None);
+    };
+
+    Ok(attachment::Entity::find_by_id(id)
+        .filter(document.condition())
+        .one(db)
+        .await?)
+}
@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    audit::{
+        model::{AuditEvent, DocumentKind, Download, Egress},
+        service::{AuditService, parse_month},
+    },
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, get, web};
+use trustify_auth::{ReadAudit, authorizer::Require};
+use trustify_common::{
+    db::query::Query,
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults},
+};
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig) {
+    // the service itself is registered globally, as actions and downloads get recorded by other
+    // modules
+    config.service(trail).service(downloads).service(egress);
+}
+
+#[derive(Clone, Debug, serde::Deserialize, IntoParams)]
+struct TrailParams {
+    /// The kind of document
+    #[param(inline)]
+    entity: DocumentKind,
+    /// The ID of the SBOM or advisory
+    id: Uuid,
+}
+
+#[utoipa::path(
+    tag = "audit",
+    operation_id = "getAuditTrail",
+    params(
+        TrailParams,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "The actions on the document, oldest first", body = PaginatedResults<AuditEvent>),
+    ),
+)]
+#[get("/v2/audit")]
+/// List the audit trail of a document
+///
+/// This covers uploads, changes of labels, deletions, and restorations, along with the acting
+/// user and client. Documents ingested by importers have no recorded upload.
+pub async fn trail(
+    service: web::Data<AuditService>,
+    web::Query(TrailParams { entity, id }): web::Query<TrailParams>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadAudit>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(service.trail(entity, id, paginated, &tenant).await?))
+}
+
+#[utoipa::path(
+    tag = "audit",
+    operation_id = "listDownloads",
+    params(
+        Query,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Matching downloads, newest first", body = PaginatedResults<Download>),
+    ),
+)]
+#[get("/v2/audit/download")]
+/// List downloads of documents
+pub async fn downloads(
+    service: web::Data<AuditService>,
+    web::Query(search): web::Query<Query>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadAudit>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(service.downloads(search, paginated, &tenant).await?))
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize, IntoParams)]
+struct EgressParams {
+    /// Only report a single month, in the format `YYYY-MM`
+    #[serde(default)]
+    month: Option<String>,
+}
+
+#[utoipa::path(
+    tag = "audit",
+    operation_id = "getEgress",
+    params(
+        EgressParams,
+    ),
+    responses(
+        (status = 200, description = "The downloads per principal and month, latest month first", body = Vec<Egress>),
+        (status = 400, description = "The month is invalid", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/audit/egress")]
+/// Report the downloads per principal and month
+pub async fn egress(
+    service: web::Data<AuditService>,
+    web::Query(EgressParams { month }): web::Query<EgressParams>,
+    _: Require<ReadAudit>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let month = month.as_deref().map(parse_month).transpose()?;
+    Ok(HttpResponse::Ok().json(service.egress(month, &tenant).await?))
+}
@@ -0,0 +1,265 @@
+use crate::{
+    audit::model::{Action, AuditEvent, DocumentKind, Download, Egress},
+    test::{caller, caller_with, tenant_isolation, tenant_user},
+};
+use actix_http::StatusCode;
+use actix_web::{
+    http::header,
+    test::{TestRequest, read_body},
+};
+use hex::ToHex;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use test_context::test_context;
+use test_log::test;
+use time::OffsetDateTime;
+use trustify_common::{id::Id, model::PaginatedResults};
+use trustify_module_ingestor::model::IngestResult;
+use trustify_test_context::{
+    TrustifyContext, auth::TestAuthentication, call::CallService, document_bytes,
+};
+
+const DOC: &str = "csaf/cve-2023-33201.json";
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn download_egress(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let bytes = document_bytes(DOC).await?;
+    let size = bytes.len() as u64;
+    let digest: String = Sha256::digest(&bytes).encode_hex();
+    let app = caller(ctx).await?;
+    let result = ctx.ingest_document(DOC).await?;
+    let uri = format!("/api/v2/advisory/{}/download", result.id);
+
+    // a full and a partial download by user-a
+
+    let request = TestRequest::get()
+        .uri(&uri)
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_body(response).await.len() as u64, size);
+
+    let request = TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::RANGE, "bytes=0-9"))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+
+    // a full download by user-b, and a request not transferring the document again
+
+    let request = TestRequest::get()
+        .uri(&uri)
+        .to_request()
+        .test_auth("user-b");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::IF_NONE_MATCH, format!(r#""{digest}""#)))
+        .to_request()
+        .test_auth("user-b");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    // who downloaded what
+
+    let request = TestRequest::get()
+        .uri("/api/v2/audit/download?q=user_id%3Duser-a")
+        .to_request();
+    let result: PaginatedResults<Download> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 2);
+    assert!(
+        result
+            .items
+            .iter()
+            .all(|download| download.kind == DocumentKind::Advisory)
+    );
+    // newest first
+    assert_eq!(
+        result
+            .items
+            .iter()
+            .map(|download| download.bytes)
+            .collect::<Vec<_>>(),
+        vec![10, size]
+    );
+
+    // the egress of the current month
+
+    let now = OffsetDateTime::now_utc();
+    let month = format!("{:04}-{:02}", now.year(), u8::from(now.month()));
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/audit/egress?month={month}"))
+        .to_request();
+    let result: Vec<Egress> = app.call_and_read_body_json(request).await;
+    assert_eq!(
+        result,
+        vec![
+            Egress {
+                principal: Some("user-a".into()),
+                month: month.clone(),
+                downloads: 2,
+                documents: 1,
+                bytes: size + 10,
+            },
+            Egress {
+                principal: Some("user-b".into()),
+                month: month.clone(),
+                downloads: 1,
+                documents: 1,
+                bytes: size,
+            },
+        ]
+    );
+
+    // invalid months are rejected
+
+    let request = TestRequest::get()
+        .uri("/api/v2/audit/egress?month=2025-13")
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn audit_trail(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller(ctx).await?;
+
+    // user-a uploads an advisory, claiming to be someone else
+
+    let request = TestRequest::post()
+        .uri("/api/v2/advisory")
+        .peer_addr("192.0.2.1:4711".parse()?)
+        .insert_header((header::USER_AGENT, "trustify-test"))
+        .insert_header(("X-Forwarded-For", "198.51.100.1"))
+        .set_payload(document_bytes(DOC).await?)
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let result: IngestResult = actix_web::test::read_body_json(response).await;
+    let Id::Uuid(id) = result.id else {
+        panic!("unexpected ID: {}", result.id);
+    };
+
+    // user-b changes its labels, user-a deletes it
+
+    let request = TestRequest::put()
+        .uri(&format!("/api/v2/advisory/{}/label", result.id))
+        .set_json(json!({ "source": "test" }))
+        .to_request()
+        .test_auth("user-b");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/advisory/{}", result.id))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // the trail outlives the advisory
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/audit?entity=advisory&id={id}"))
+        .to_request();
+    let result: PaginatedResults<AuditEvent> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 3);
+    assert_eq!(
+        result
+            .items
+            .iter()
+            .map(|event| (event.action, event.user.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![
+            (Action::Uploaded, Some("user-a")),
+            (Action::LabelsChanged, Some("user-b")),
+            (Action::Deleted, Some("user-a")),
+        ]
+    );
+    assert_eq!(result.items[0].user_agent.as_deref(), Some("trustify-test"));
+    // the address is the one of the peer, not the one claimed by the header
+    assert_eq!(result.items[0].client_address.as_deref(), Some("192.0.2.1"));
+    assert_eq!(
+        result.items[1].details,
+        Some(json!({ "labels": { "source": "test" } }))
+    );
+
+    // the trail is per kind of document
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/audit?entity=sbom&id={id}"))
+        .to_request();
+    let result: PaginatedResults<AuditEvent> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 0);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn other_tenants(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_with(ctx, tenant_isolation()).await?;
+
+    // tenant a uploads and downloads an advisory
+
+    let request = TestRequest::post()
+        .uri("/api/v2/advisory")
+        .set_payload(document_bytes(DOC).await?)
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let result: IngestResult = actix_web::test::read_body_json(response).await;
+    let Id::Uuid(id) = result.id else {
+        panic!("unexpected ID: {}", result.id);
+    };
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/advisory/{id}/download"))
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/audit?entity=advisory&id={id}"))
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let result: PaginatedResults<AuditEvent> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 1);
+
+    // tenant b sees neither the trail, nor the downloads
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/audit?entity=advisory&id={id}"))
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let result: PaginatedResults<AuditEvent> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 0);
+
+    let request = TestRequest::get()
+        .uri("/api/v2/audit/download")
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let result: PaginatedResults<Download> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 0);
+
+    let request = TestRequest::get()
+        .uri("/api/v2/audit/egress")
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let result: Vec<Egress> = app.call_and_read_body_json(request).await;
+    assert!(result.is_empty());
+
+    Ok(())
+}
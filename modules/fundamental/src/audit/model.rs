@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::{document_audit, download_audit};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub use document_audit::{Action, DocumentKind};
+
+/// A download of a source document.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Download {
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    /// The ID of the user downloading the document, absent if authentication is disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    pub kind: DocumentKind,
+    /// The ID of the SBOM or advisory
+    pub document: Uuid,
+    /// The number of bytes served
+    pub bytes: u64,
+}
+
+impl From<download_audit::Model> for Download {
+    fn from(value: download_audit::Model) -> Self {
+        let download_audit::Model {
+            id: _,
+            timestamp,
+            user_id,
+            document_kind,
+            document_id,
+            bytes,
+        } = value;
+
+        Self {
+            timestamp,
+            user: user_id,
+            kind: document_kind,
+            document: document_id,
+            bytes: bytes as u64,
+        }
+    }
+}
+
+/// The downloads of a principal within a month.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Egress {
+    /// The ID of the user, absent for downloads without authentication
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub principal: Option<String>,
+    /// The month, in the format `YYYY-MM`
+    pub month: String,
+    /// The number of downloads
+    pub downloads: u64,
+    /// The number of distinct documents downloaded
+    pub documents: u64,
+    /// The number of bytes served
+    pub bytes: u64,
+}
+
+/// An action on a document, like its upload or deletion.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AuditEvent {
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    /// The ID of the acting user, absent if authentication is disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// The address of the client, as seen by the server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_address: Option<String>,
+    /// The user agent of the client
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    pub action: Action,
+    pub kind: DocumentKind,
+    /// The ID of the SBOM or advisory
+    pub document: Uuid,
+    /// Details of the action, like the new labels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+impl From<document_audit::Model> for AuditEvent {
+    fn from(value: document_audit::Model) -> Self {
+        let document_audit::Model {
+            id: _,
+            timestamp,
+            user_id,
+            client_address,
+            user_agent,
+            action,
+            document_kind,
+            document_id,
+            details,
+        } = value;
+
+        Self {
+            timestamp,
+            user: user_id,
+            client_address,
+            user_agent,
+            action,
+            kind: document_kind,
+            document: document_id,
+            details,
+        }
+    }
+}
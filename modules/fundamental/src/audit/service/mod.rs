@@ -0,0 +1,260 @@
+use crate::{
+    Error,
+    audit::model::{Action, AuditEvent, DocumentKind, Download, Egress},
+    tenant::Tenant,
+};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait, DbBackend, EntityTrait,
+    FromQueryResult, QueryFilter, QueryOrder, Statement, prelude::Uuid,
+};
+use std::ops::Range;
+use time::{Date, Month, OffsetDateTime};
+use trustify_common::{
+    db::{
+        Database,
+        limiter::LimiterTrait,
+        query::{Filtering, Query},
+    },
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{document_audit, download_audit};
+use trustify_module_ingestor::service::audit::Actor;
+
+pub struct AuditService {
+    db: Database,
+}
+
+impl AuditService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Record the download of a document.
+    pub async fn record_download(
+        &self,
+        user_id: Option<&str>,
+        kind: DocumentKind,
+        document_id: Uuid,
+        bytes: u64,
+    ) -> Result<(), Error> {
+        download_audit::Entity::insert(download_audit::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            timestamp: Set(OffsetDateTime::now_utc()),
+            user_id: Set(user_id.map(ToString::to_string)),
+            document_kind: Set(kind),
+            document_id: Set(document_id),
+            bytes: Set(bytes as i64),
+        })
+        .exec_without_returning(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record an action on a document, as part of the transaction performing it.
+    pub async fn record<C: ConnectionTrait>(
+        &self,
+        actor: &Actor,
+        action: Action,
+        kind: DocumentKind,
+        document_id: Uuid,
+        details: Option<serde_json::Value>,
+        connection: &C,
+    ) -> Result<(), Error> {
+        actor
+            .record(action, kind, document_id, details, connection)
+            .await?;
+
+        Ok(())
+    }
+
+    /// List the actions on a document of the tenant, oldest first.
+    pub async fn trail(
+        &self,
+        kind: DocumentKind,
+        document_id: Uuid,
+        paginated: Paginated,
+        tenant: &Tenant,
+    ) -> Result<PaginatedResults<AuditEvent>, Error> {
+        let limiter = document_audit::Entity::find()
+            .filter(document_audit::Column::DocumentKind.eq(kind))
+            .filter(document_audit::Column::DocumentId.eq(document_id))
+            .filter(document_condition(
+                tenant,
+                document_audit::Column::DocumentKind,
+                document_audit::Column::DocumentId,
+            ))
+            .order_by_asc(document_audit::Column::Timestamp)
+            .order_by_asc(document_audit::Column::Id)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        let total = limiter.total().await?;
+        let items = limiter.fetch().await?;
+
+        Ok(PaginatedResults {
+            items: items.into_iter().map(AuditEvent::from).collect(),
+            total,
+        })
+    }
+
+    /// List downloads of documents of the tenant, newest first by default.
+    pub async fn downloads(
+        &self,
+        query: Query,
+        paginated: Paginated,
+        tenant: &Tenant,
+    ) -> Result<PaginatedResults<Download>, Error> {
+        let limiter = download_audit::Entity::find()
+            .filter(document_condition(
+                tenant,
+                download_audit::Column::DocumentKind,
+                download_audit::Column::DocumentId,
+            ))
+            .filtering(query)?
+            .order_by_desc(download_audit::Column::Timestamp)
+            .order_by_desc(download_audit::Column::Id)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        let total = limiter.total().await?;
+        let items = limiter.fetch().await?;
+
+        Ok(PaginatedResults {
+            items: items.into_iter().map(Download::from).collect(),
+            total,
+        })
+    }
+
+    /// Sum up the downloads of documents of the tenant per principal and month, in UTC. Ordered
+    /// by the month, latest first, and then by the bytes served, largest first.
+    pub async fn egress(
+        &self,
+        month: Option<Range<OffsetDateTime>>,
+        tenant: &Tenant,
+    ) -> Result<Vec<Egress>, Error> {
+        let (start, end) = month.map(|month| (month.start, month.end)).unzip();
+
+        let query = r#"
+            SELECT
+                "user_id" AS "principal",
+                to_char("timestamp" AT TIME ZONE 'UTC', 'YYYY-MM') AS "month",
+                count(*) AS "downloads",
+                count(DISTINCT "document_id") AS "documents",
+                sum("bytes")::BIGINT AS "bytes"
+            FROM "download_audit"
+            WHERE
+                ($1::timestamptz IS NULL OR "timestamp" >= $1) AND
+                ($2::timestamptz IS NULL OR "timestamp" < $2) AND
+                ($3::text IS NULL OR
+                    ("document_kind" = 'sbom' AND "document_id" IN (
+                        SELECT "sbom_id" FROM "sbom" WHERE "labels" ->> 'tenant' = $3
+                    )) OR
+                    ("document_kind" = 'advisory' AND "document_id" IN (
+                        SELECT "id" FROM "advisory" WHERE "labels" ->> 'tenant' = $3
+                    ))
+                )
+            GROUP BY 1, 2
+            ORDER BY 2 DESC, 5 DESC, 1
+            "#;
+
+        let rows = self
+            .db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                query,
+                [
+                    start.into(),
+                    end.into(),
+                    tenant.name().map(ToString::to_string).into(),
+                ],
+            ))
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                let row = EgressRow::from_query_result(row, "")?;
+                Ok(Egress {
+                    principal: row.principal,
+                    month: row.month,
+                    downloads: row.downloads as u64,
+                    documents: row.documents as u64,
+                    bytes: row.bytes as u64,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A condition on audit records, ruling out the ones of documents of other tenants.
+fn document_condition(
+    tenant: &Tenant,
+    kind: impl ColumnTrait,
+    document_id: impl ColumnTrait,
+) -> Condition {
+    match tenant {
+        Tenant::Any => Condition::all(),
+        Tenant::Of(_) => Condition::any()
+            .add(
+                Condition::all()
+                    .add(kind.eq(DocumentKind::Sbom))
+                    .add(tenant.sbom_condition(document_id)),
+            )
+            .add(
+                Condition::all()
+                    .add(kind.eq(DocumentKind::Advisory))
+                    .add(tenant.advisory_condition(document_id)),
+            ),
+    }
+}
+
+#[derive(FromQueryResult)]
+struct EgressRow {
+    principal: Option<String>,
+    month: String,
+    downloads: i64,
+    documents: i64,
+    bytes: i64,
+}
+
+/// Parse a month in the format `YYYY-MM` into the range of time it covers, in UTC.
+pub fn parse_month(month: &str) -> Result<Range<OffsetDateTime>, Error> {
+    let invalid = || Error::BadRequest(format!("Invalid month, expected YYYY-MM: {month}"));
+
+    let (year, number) = month.split_once('-').ok_or_else(invalid)?;
+    let year = year.parse::<i32>().map_err(|_| invalid())?;
+    let number =
+        Month::try_from(number.parse::<u8>().map_err(|_| invalid())?).map_err(|_| invalid())?;
+
+    let (next_year, next_number) = match number {
+        Month::December => (year + 1, Month::January),
+        _ => (year, number.next()),
+    };
+
+    let start = Date::from_calendar_date(year, number, 1).map_err(|_| invalid())?;
+    let end = Date::from_calendar_date(next_year, next_number, 1).map_err(|_| invalid())?;
+
+    Ok(start.midnight().assume_utc()..end.midnight().assume_utc())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn month() {
+        assert_eq!(
+            parse_month("2025-01").expect("must parse"),
+            datetime!(2025-01-01 00:00 UTC)..datetime!(2025-02-01 00:00 UTC)
+        );
+        assert_eq!(
+            parse_month("2024-12").expect("must parse"),
+            datetime!(2024-12-01 00:00 UTC)..datetime!(2025-01-01 00:00 UTC)
+        );
+
+        for invalid in ["2025", "2025-13", "2025-00", "January"] {
+            assert!(parse_month(invalid).is_err(), "{invalid}");
+        }
+    }
+}
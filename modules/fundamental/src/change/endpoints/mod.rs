@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    Error,
+    change::{
+        model::{DocumentChange, DocumentKind},
+        service::ChangeService,
+    },
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, get, web};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use trustify_auth::{Permission, authenticator::user::UserInformation, authorizer::Authorizer};
+use trustify_common::{
+    db::{
+        Database,
+        query::{LabelFilters, LabelQuery},
+    },
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults},
+};
+use utoipa::IntoParams;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = ChangeService::new(db);
+
+    config.app_data(web::Data::new(service)).service(changes);
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize, IntoParams)]
+struct ChangesParams {
+    /// Only report changes at or after this point in time, in RFC 3339 format
+    since: String,
+    /// The types of documents to report, `,`-delimited: `advisory` and `sbom`. Defaults to all.
+    #[serde(default)]
+    types: Option<String>,
+}
+
+#[utoipa::path(
+    tag = "change",
+    operation_id = "listChanges",
+    params(
+        ChangesParams,
+        LabelQuery,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Created, updated, and deleted documents, in the order of their changes", body = PaginatedResults<DocumentChange>),
+        (status = 400, description = "The point in time or the types are invalid", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/changes")]
+/// List the changes of documents since a point in time
+///
+/// Several changes of a document within one transaction are reported as one change. A soft
+/// deleted document is reported as deleted, and as created again when it gets restored.
+///
+/// For mirroring documents incrementally, request the changes since the time of the last change
+/// seen. As the point in time is inclusive, the last change is reported again.
+pub async fn changes(
+    service: web::Data<ChangeService>,
+    web::Query(ChangesParams { since, types }): web::Query<ChangesParams>,
+    web::Query(labels): web::Query<LabelQuery>,
+    web::Query(paginated): web::Query<Paginated>,
+    authorizer: web::Data<Authorizer>,
+    user: UserInformation,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let since = OffsetDateTime::parse(&since, &Rfc3339)
+        .map_err(|err| Error::BadRequest(format!("Invalid point in time '{since}': {err}")))?;
+    let kinds = parse_types(types.as_deref())?;
+
+    for kind in &kinds {
+        authorizer.require(
+            &user,
+            match kind {
+                DocumentKind::Sbom => Permission::ReadSbom,
+                DocumentKind::Advisory => Permission::ReadAdvisory,
+            },
+        )?;
+    }
+
+    let labels = tenant.restrict(LabelFilters::try_from(labels).map_err(Error::from)?);
+
+    Ok(HttpResponse::Ok().json(service.changes(since, kinds, labels, paginated).await?))
+}
+
+pub(crate) fn parse_types(types: Option<&str>) -> Result<Vec<DocumentKind>, Error> {
+    let Some(types) = types else {
+        return Ok(vec![DocumentKind::Advisory, DocumentKind::Sbom]);
+    };
+
+    let mut result = vec![];
+    for r#type in types.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        let kind = match r#type {
+            "advisory" => DocumentKind::Advisory,
+            "sbom" => DocumentKind::Sbom,
+            other => {
+                return Err(Error::BadRequest(format!("Unknown document type: {other}")));
+            }
+        };
+        if !result.contains(&kind) {
+            result.push(kind);
+        }
+    }
+
+    Ok(result)
+}
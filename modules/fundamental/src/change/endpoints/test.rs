@@ -0,0 +1,87 @@
+use crate::{
+    change::model::{ChangeKind, DocumentChange, DocumentKind},
+    test::caller,
+};
+use actix_http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+use test_context::test_context;
+use test_log::test;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use trustify_common::{id::Id, model::PaginatedResults};
+use trustify_test_context::{TrustifyContext, call::CallService};
+
+fn changes(result: &PaginatedResults<DocumentChange>) -> Vec<(DocumentKind, ChangeKind)> {
+    result
+        .items
+        .iter()
+        .map(|change| (change.kind, change.change))
+        .collect()
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn mirror_changes(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let sbom = ctx
+        .ingest_document("quarkus-bom-2.13.8.Final-redhat-00004.json")
+        .await?
+        .id;
+    ctx.ingest_document("csaf/cve-2023-0044.json").await?;
+    let app = caller(ctx).await?;
+
+    let since = OffsetDateTime::UNIX_EPOCH.format(&Rfc3339)?;
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/changes?since={since}"))
+        .to_request();
+    let result: PaginatedResults<DocumentChange> = app.call_and_read_body_json(request).await;
+    assert_eq!(
+        changes(&result)[..2],
+        [
+            (DocumentKind::Sbom, ChangeKind::Created),
+            (DocumentKind::Advisory, ChangeKind::Created),
+        ]
+    );
+    assert_eq!(Id::Uuid(result.items[0].id), sbom);
+
+    // continue after the last change
+
+    let last = result.items.last().expect("must have changes");
+    let since = last.timestamp.format(&Rfc3339)?;
+
+    let request = TestRequest::patch()
+        .uri(&format!("/api/v2/sbom/{sbom}/label"))
+        .set_json(json!({ "team": "a" }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/sbom/{sbom}"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/changes?since={since}&types=sbom"))
+        .to_request();
+    let result: PaginatedResults<DocumentChange> = app.call_and_read_body_json(request).await;
+    assert!(
+        changes(&result).ends_with(&[
+            (DocumentKind::Sbom, ChangeKind::Updated),
+            (DocumentKind::Sbom, ChangeKind::Deleted),
+        ]),
+        "unexpected changes: {result:?}"
+    );
+
+    // invalid parameters
+
+    for query in ["since=yesterday", "since=2024-05-01T00:00:00Z&types=vex"] {
+        let request = TestRequest::get()
+            .uri(&format!("/api/v2/changes?{query}"))
+            .to_request();
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{query}");
+    }
+
+    Ok(())
+}
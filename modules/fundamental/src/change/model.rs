@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::document_change;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub use document_change::Change as ChangeKind;
+pub use trustify_entity::download_audit::DocumentKind;
+
+/// A change of an SBOM or advisory.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DocumentChange {
+    /// The time of the change
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    #[serde(rename = "type")]
+    pub kind: DocumentKind,
+    /// The ID of the SBOM or advisory
+    pub id: Uuid,
+    pub change: ChangeKind,
+}
+
+impl From<document_change::Model> for DocumentChange {
+    fn from(value: document_change::Model) -> Self {
+        let document_change::Model {
+            seq: _,
+            tx: _,
+            timestamp,
+            document_kind,
+            document_id,
+            change,
+            labels: _,
+        } = value;
+
+        Self {
+            timestamp,
+            kind: document_kind,
+            id: document_id,
+            change,
+        }
+    }
+}
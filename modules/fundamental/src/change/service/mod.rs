@@ -0,0 +1,51 @@
+use crate::{
+    Error,
+    change::model::{DocumentChange, DocumentKind},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use time::OffsetDateTime;
+use trustify_common::{
+    db::{Database, limiter::LimiterTrait, query::LabelFilters},
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::document_change;
+
+pub struct ChangeService {
+    db: Database,
+}
+
+impl ChangeService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// List the changes of documents at or after a point in time, in the order they were
+    /// recorded.
+    ///
+    /// Labels are matched against the labels a document had at the time of the change.
+    pub async fn changes(
+        &self,
+        since: OffsetDateTime,
+        kinds: Vec<DocumentKind>,
+        labels: LabelFilters,
+        paginated: Paginated,
+    ) -> Result<PaginatedResults<DocumentChange>, Error> {
+        let limiter = document_change::Entity::find()
+            .filter(document_change::Column::Timestamp.gte(since))
+            .filter(document_change::Column::DocumentKind.is_in(kinds))
+            .filter(labels.into_condition(document_change::Column::Labels))
+            .order_by_asc(document_change::Column::Seq)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        Ok(PaginatedResults {
+            total: limiter.total().await?,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(DocumentChange::from)
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,356 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    Error,
+    comment::{
+        model::{Comment, CommentRequest},
+        service::{CommentService, Subject},
+    },
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, delete, get, http::header, post, put, web};
+use std::str::FromStr;
+use trustify_auth::{
+    CreateComment, DeleteComment, ReadAdvisory, UpdateComment,
+    authenticator::user::UserInformation, authorizer::Require,
+};
+use trustify_common::{
+    db::{Database, query::Query},
+    error::ErrorInformation,
+    id::Id,
+    model::{Paginated, PaginatedResults},
+};
+use uuid::Uuid;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = CommentService::new(db);
+
+    config
+        .app_data(web::Data::new(service))
+        .service(list_advisory)
+        .service(create_advisory)
+        .service(update_advisory)
+        .service(delete_advisory)
+        .service(list_vulnerability)
+        .service(create_vulnerability)
+        .service(update_vulnerability)
+        .service(delete_vulnerability);
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "listAdvisoryComments",
+    params(
+        ("key" = Id, Path),
+        Query,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Comments on the advisory, oldest first", body = PaginatedResults<Comment>),
+        (status = 404, description = "The advisory could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/advisory/{key}/comment")]
+/// List the comments on an advisory
+///
+/// The comments can be filtered by their author, like `q=author=alice`.
+pub async fn list_advisory(
+    service: web::Data<CommentService>,
+    db: web::Data<Database>,
+    key: web::Path<String>,
+    web::Query(search): web::Query<Query>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadAdvisory>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let id = Id::from_str(&key).map_err(Error::IdKey)?;
+    let id = tenant.require_advisory(&id, db.as_ref()).await?;
+
+    list(&service, Subject::Advisory(id), search, paginated).await
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "createAdvisoryComment",
+    request_body = CommentRequest,
+    params(
+        ("key" = Id, Path),
+    ),
+    responses(
+        (status = 201, description = "Created the comment", body = Comment),
+        (status = 400, description = "The comment is empty", body = ErrorInformation),
+        (status = 404, description = "The advisory could not be found", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/advisory/{key}/comment")]
+/// Comment on an advisory
+pub async fn create_advisory(
+    service: web::Data<CommentService>,
+    db: web::Data<Database>,
+    key: web::Path<String>,
+    user: UserInformation,
+    web::Json(request): web::Json<CommentRequest>,
+    _: Require<CreateComment>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let id = Id::from_str(&key).map_err(Error::IdKey)?;
+    let id = tenant.require_advisory(&id, db.as_ref()).await?;
+
+    create(
+        &service,
+        Subject::Advisory(id),
+        request,
+        &user,
+        &format!("/api/v2/advisory/{key}/comment"),
+    )
+    .await
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "updateAdvisoryComment",
+    request_body = CommentRequest,
+    params(
+        ("key" = Id, Path),
+        ("comment" = Uuid, Path, description = "ID of the comment"),
+    ),
+    responses(
+        (status = 200, description = "The updated comment", body = Comment),
+        (status = 400, description = "The comment is empty", body = ErrorInformation),
+        (status = 403, description = "The comment was written by another user", body = ErrorInformation),
+        (status = 404, description = "The advisory or the comment could not be found", body = ErrorInformation),
+    ),
+)]
+#[put("/v2/advisory/{key}/comment/{comment}")]
+/// Replace the text of a comment on an advisory
+pub async fn update_advisory(
+    service: web::Data<CommentService>,
+    db: web::Data<Database>,
+    path: web::Path<(String, Uuid)>,
+    user: UserInformation,
+    web::Json(request): web::Json<CommentRequest>,
+    _: Require<UpdateComment>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (key, comment) = path.into_inner();
+    let id = Id::from_str(&key).map_err(Error::IdKey)?;
+    let id = tenant.require_advisory(&id, db.as_ref()).await?;
+
+    update(&service, Subject::Advisory(id), comment, request, &user).await
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "deleteAdvisoryComment",
+    params(
+        ("key" = Id, Path),
+        ("comment" = Uuid, Path, description = "ID of the comment"),
+    ),
+    responses(
+        (status = 204, description = "The comment was deleted"),
+        (status = 403, description = "The comment was written by another user", body = ErrorInformation),
+        (status = 404, description = "The advisory or the comment could not be found", body = ErrorInformation),
+    ),
+)]
+#[delete("/v2/advisory/{key}/comment/{comment}")]
+/// Delete a comment on an advisory
+pub async fn delete_advisory(
+    service: web::Data<CommentService>,
+    db: web::Data<Database>,
+    path: web::Path<(String, Uuid)>,
+    user: UserInformation,
+    _: Require<DeleteComment>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (key, comment) = path.into_inner();
+    let id = Id::from_str(&key).map_err(Error::IdKey)?;
+    let id = tenant.require_advisory(&id, db.as_ref()).await?;
+
+    remove(&service, Subject::Advisory(id), comment, &user).await
+}
+
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "listVulnerabilityComments",
+    params(
+        ("id", Path, description = "ID of the vulnerability"),
+        Query,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Comments on the vulnerability, oldest first", body = PaginatedResults<Comment>),
+        (status = 404, description = "The vulnerability could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/vulnerability/{id}/comment")]
+/// List the comments on a vulnerability
+///
+/// The comments can be filtered by their author, like `q=author=alice`.
+pub async fn list_vulnerability(
+    service: web::Data<CommentService>,
+    id: web::Path<String>,
+    web::Query(search): web::Query<Query>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadAdvisory>,
+) -> actix_web::Result<impl Responder> {
+    list(
+        &service,
+        Subject::Vulnerability(id.into_inner()),
+        search,
+        paginated,
+    )
+    .await
+}
+
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "createVulnerabilityComment",
+    request_body = CommentRequest,
+    params(
+        ("id", Path, description = "ID of the vulnerability"),
+    ),
+    responses(
+        (status = 201, description = "Created the comment", body = Comment),
+        (status = 400, description = "The comment is empty", body = ErrorInformation),
+        (status = 404, description = "The vulnerability could not be found", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/vulnerability/{id}/comment")]
+/// Comment on a vulnerability
+pub async fn create_vulnerability(
+    service: web::Data<CommentService>,
+    id: web::Path<String>,
+    user: UserInformation,
+    web::Json(request): web::Json<CommentRequest>,
+    _: Require<CreateComment>,
+) -> actix_web::Result<impl Responder> {
+    let location = format!("/api/v2/vulnerability/{id}/comment");
+    create(
+        &service,
+        Subject::Vulnerability(id.into_inner()),
+        request,
+        &user,
+        &location,
+    )
+    .await
+}
+
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "updateVulnerabilityComment",
+    request_body = CommentRequest,
+    params(
+        ("id", Path, description = "ID of the vulnerability"),
+        ("comment" = Uuid, Path, description = "ID of the comment"),
+    ),
+    responses(
+        (status = 200, description = "The updated comment", body = Comment),
+        (status = 400, description = "The comment is empty", body = ErrorInformation),
+        (status = 403, description = "The comment was written by another user", body = ErrorInformation),
+        (status = 404, description = "The vulnerability or the comment could not be found", body = ErrorInformation),
+    ),
+)]
+#[put("/v2/vulnerability/{id}/comment/{comment}")]
+/// Replace the text of a comment on a vulnerability
+pub async fn update_vulnerability(
+    service: web::Data<CommentService>,
+    path: web::Path<(String, Uuid)>,
+    user: UserInformation,
+    web::Json(request): web::Json<CommentRequest>,
+    _: Require<UpdateComment>,
+) -> actix_web::Result<impl Responder> {
+    let (id, comment) = path.into_inner();
+    update(
+        &service,
+        Subject::Vulnerability(id),
+        comment,
+        request,
+        &user,
+    )
+    .await
+}
+
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "deleteVulnerabilityComment",
+    params(
+        ("id", Path, description = "ID of the vulnerability"),
+        ("comment" = Uuid, Path, description = "ID of the comment"),
+    ),
+    responses(
+        (status = 204, description = "The comment was deleted"),
+        (status = 403, description = "The comment was written by another user", body = ErrorInformation),
+        (status = 404, description = "The vulnerability or the comment could not be found", body = ErrorInformation),
+    ),
+)]
+#[delete("/v2/vulnerability/{id}/comment/{comment}")]
+/// Delete a comment on a vulnerability
+pub async fn delete_vulnerability(
+    service: web::Data<CommentService>,
+    path: web::Path<(String, Uuid)>,
+    user: UserInformation,
+    _: Require<DeleteComment>,
+) -> actix_web::Result<impl Responder> {
+    let (id, comment) = path.into_inner();
+    remove(&service, Subject::Vulnerability(id), comment, &user).await
+}
+
+async fn list(
+    service: &CommentService,
+    subject: Subject,
+    search: Query,
+    paginated: Paginated,
+) -> actix_web::Result<HttpResponse> {
+    Ok(match service.list(subject, search, paginated).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+async fn create(
+    service: &CommentService,
+    subject: Subject,
+    request: CommentRequest,
+    user: &UserInformation,
+    location: &str,
+) -> actix_web::Result<HttpResponse> {
+    Ok(
+        match service
+            .create(subject, request, user.id().map(ToString::to_string))
+            .await?
+        {
+            Some(result) => HttpResponse::Created()
+                .insert_header((header::LOCATION, format!("{location}/{}", result.id)))
+                .json(result),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+async fn update(
+    service: &CommentService,
+    subject: Subject,
+    id: Uuid,
+    request: CommentRequest,
+    user: &UserInformation,
+) -> actix_web::Result<HttpResponse> {
+    Ok(
+        match service.update(subject, id, request, user.id()).await? {
+            Some(result) => HttpResponse::Ok().json(result),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+async fn remove(
+    service: &CommentService,
+    subject: Subject,
+    id: Uuid,
+    user: &UserInformation,
+) -> actix_web::Result<HttpResponse> {
+    Ok(match service.delete(subject, id, user.id()).await? {
+        true => HttpResponse::NoContent().finish(),
+        false => HttpResponse::NotFound().finish(),
+    })
+}
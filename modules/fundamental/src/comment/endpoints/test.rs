@@ -0,0 +1,134 @@
+use crate::{comment::model::Comment, test::caller};
+use actix_http::StatusCode;
+use actix_web::test::{TestRequest, read_body_json};
+use serde_json::{Value, json};
+use test_context::test_context;
+use test_log::test;
+use trustify_common::model::PaginatedResults;
+use trustify_test_context::{TrustifyContext, auth::TestAuthentication, call::CallService};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn comment_on_advisory(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let id = ctx
+        .ingest_document("csaf/cve-2023-0044.json")
+        .await?
+        .id
+        .to_string();
+    let app = caller(ctx).await?;
+
+    let mut comments = vec![];
+    for (user, body) in [("user-a", "Not *shipped* by us"), ("user-b", "Agreed")] {
+        let request = TestRequest::post()
+            .uri(&format!("/api/v2/advisory/{id}/comment"))
+            .set_json(json!({ "body": body }))
+            .test_auth(user)
+            .to_request();
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let comment: Comment = read_body_json(response).await;
+        assert_eq!(comment.author.as_deref(), Some(user));
+        comments.push(comment);
+    }
+
+    // filtered by author
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/advisory/{id}/comment?q=author%3Duser-a"))
+        .to_request();
+    let result: PaginatedResults<Comment> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.items, vec![comments[0].clone()]);
+
+    // surfaced in the details
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/advisory/{id}"))
+        .to_request();
+    let advisory: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(advisory["comments"][0]["body"], "Not *shipped* by us");
+    assert_eq!(advisory["comments"][1]["author"], "user-b");
+
+    // only the author can change a comment
+
+    let uri = format!("/api/v2/advisory/{id}/comment/{}", comments[0].id);
+
+    let request = TestRequest::put()
+        .uri(&uri)
+        .set_json(json!({ "body": "Shipped by us" }))
+        .test_auth("user-b")
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let request = TestRequest::put()
+        .uri(&uri)
+        .set_json(json!({ "body": "Shipped by us" }))
+        .test_auth("user-a")
+        .to_request();
+    let comment: Comment = app.call_and_read_body_json(request).await;
+    assert_eq!(comment.body, "Shipped by us");
+    assert!(comment.modified > comment.created);
+
+    let request = TestRequest::delete()
+        .uri(&uri)
+        .test_auth("user-b")
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let request = TestRequest::delete()
+        .uri(&uri)
+        .test_auth("user-a")
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/advisory/{id}/comment"))
+        .to_request();
+    let result: PaginatedResults<Comment> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.items, vec![comments[1].clone()]);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn comment_on_vulnerability(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_document("csaf/cve-2023-0044.json").await?;
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/vulnerability/CVE-2023-0044/comment")
+        .set_json(json!({ "body": "Only exploitable with a custom form handler" }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let request = TestRequest::get()
+        .uri("/api/v2/vulnerability/CVE-2023-0044")
+        .to_request();
+    let vulnerability: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(
+        vulnerability["comments"][0]["body"],
+        "Only exploitable with a custom form handler"
+    );
+
+    // comments must not be empty
+
+    let request = TestRequest::post()
+        .uri("/api/v2/vulnerability/CVE-2023-0044/comment")
+        .set_json(json!({ "body": " " }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = TestRequest::post()
+        .uri("/api/v2/vulnerability/CVE-0000-0000/comment")
+        .set_json(json!({ "body": "Unknown" }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
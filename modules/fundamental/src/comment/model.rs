@@ -0,0 +1,70 @@
+use crate::Error;
+use sea_orm::{ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, sea_query::IntoCondition};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::comment;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A note of an analyst on an advisory or a vulnerability.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Comment {
+    pub id: Uuid,
+    /// The ID of the user writing the comment, absent if authentication is disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// The text of the comment, as Markdown
+    pub body: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    /// The time of the last change
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified: OffsetDateTime,
+}
+
+impl From<comment::Model> for Comment {
+    fn from(value: comment::Model) -> Self {
+        let comment::Model {
+            id,
+            advisory_id: _,
+            vulnerability_id: _,
+            author,
+            body,
+            created,
+            modified,
+        } = value;
+
+        Self {
+            id,
+            author,
+            body,
+            created,
+            modified,
+        }
+    }
+}
+
+impl Comment {
+    /// Collect the comments matching a condition, like the ones of an advisory, oldest first.
+    pub async fn find<C: ConnectionTrait>(
+        condition: impl IntoCondition,
+        db: &C,
+    ) -> Result<Vec<Self>, Error> {
+        Ok(comment::Entity::find()
+            .filter(condition)
+            .order_by_asc(comment::Column::Created)
+            .order_by_asc(comment::Column::Id)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(Self::from)
+            .collect())
+    }
+}
+
+/// The content of a comment.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CommentRequest {
+    /// The text of the comment, as Markdown
+    pub body: String,
+}
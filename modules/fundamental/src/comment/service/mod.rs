@@ -0,0 +1,229 @@
+use crate::{
+    Error,
+    comment::model::{Comment, CommentRequest},
+};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait, EntityTrait, JoinType, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait, TransactionTrait, prelude::Uuid,
+};
+use time::OffsetDateTime;
+use trustify_common::{
+    db::{
+        Database,
+        limiter::LimiterTrait,
+        query::{Filtering, Query},
+    },
+    id::{Id, TrySelectForId},
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{advisory, comment, vulnerability};
+
+/// What a comment is about.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Subject {
+    Advisory(Id),
+    Vulnerability(String),
+}
+
+pub struct CommentService {
+    db: Database,
+}
+
+impl CommentService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// List the comments on a subject, oldest first. Returns `None` if the subject was not found.
+    ///
+    /// The comments can be filtered by their author, like `author=alice`.
+    pub async fn list(
+        &self,
+        subject: Subject,
+        query: Query,
+        paginated: Paginated,
+    ) -> Result<Option<PaginatedResults<Comment>>, Error> {
+        let Some(subject) = resolve(&self.db, subject).await? else {
+            return Ok(None);
+        };
+
+        let limiter = comment::Entity::find()
+            .filter(subject.condition())
+            .filtering(query)?
+            .order_by_asc(comment::Column::Created)
+            .order_by_asc(comment::Column::Id)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        Ok(Some(PaginatedResults {
+            total: limiter.total().await?,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(Comment::from)
+                .collect(),
+        }))
+    }
+
+    /// Comment on a subject. Returns `None` if the subject was not found.
+    pub async fn create(
+        &self,
+        subject: Subject,
+        request: CommentRequest,
+        author: Option<String>,
+    ) -> Result<Option<Comment>, Error> {
+        let CommentRequest { body } = validate(request)?;
+
+        let Some(subject) = resolve(&self.db, subject).await? else {
+            return Ok(None);
+        };
+
+        let (advisory_id, vulnerability_id) = match subject {
+            Resolved::Advisory(id) => (Some(id), None),
+            Resolved::Vulnerability(id) => (None, Some(id)),
+        };
+
+        let now = OffsetDateTime::now_utc();
+        let result = comment::Entity::insert(comment::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            advisory_id: Set(advisory_id),
+            vulnerability_id: Set(vulnerability_id),
+            author: Set(author),
+            body: Set(body),
+            created: Set(now),
+            modified: Set(now),
+        })
+        .exec_with_returning(&self.db)
+        .await?;
+
+        Ok(Some(result.into()))
+    }
+
+    /// Change the text of a comment. Returns `None` if the subject or the comment was not found.
+    ///
+    /// Only the author can change a comment.
+    pub async fn update(
+        &self,
+        subject: Subject,
+        id: Uuid,
+        request: CommentRequest,
+        user: Option<&str>,
+    ) -> Result<Option<Comment>, Error> {
+        let CommentRequest { body } = validate(request)?;
+
+        let tx = self.db.begin().await?;
+
+        let Some(current) = find(&tx, subject, id, true).await? else {
+            return Ok(None);
+        };
+        require_author(&current, user)?;
+
+        let result = comment::Entity::update(comment::ActiveModel {
+            id: Set(current.id),
+            body: Set(body),
+            modified: Set(OffsetDateTime::now_utc()),
+            ..Default::default()
+        })
+        .exec(&tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(result.into()))
+    }
+
+    /// Delete a comment. Returns `false` if the subject or the comment was not found.
+    ///
+    /// Only the author can delete a comment.
+    pub async fn delete(
+        &self,
+        subject: Subject,
+        id: Uuid,
+        user: Option<&str>,
+    ) -> Result<bool, Error> {
+        let Some(current) = find(&self.db, subject, id, false).await? else {
+            return Ok(false);
+        };
+        require_author(&current, user)?;
+
+        let result = comment::Entity::delete_by_id(current.id)
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
+
+/// A subject, by its internal ID.
+#[derive(Clone, Debug)]
+enum Resolved {
+    Advisory(Uuid),
+    Vulnerability(String),
+}
+
+impl Resolved {
+    fn condition(self) -> Condition {
+        match self {
+            Self::Advisory(id) => comment::Column::AdvisoryId.eq(id).into(),
+            Self::Vulnerability(id) => comment::Column::VulnerabilityId.eq(id).into(),
+        }
+    }
+}
+
+/// Look up the ID of a subject, ignoring deleted advisories.
+async fn resolve(db: &impl ConnectionTrait, subject: Subject) -> Result<Option<Resolved>, Error> {
+    Ok(match subject {
+        Subject::Advisory(id) => advisory::Entity::find()
+            .select_only()
+            .column(advisory::Column::Id)
+            .join(JoinType::LeftJoin, advisory::Relation::SourceDocument.def())
+            .try_filter(id)?
+            .filter(advisory::Column::DeletedAt.is_null())
+            .into_tuple()
+            .one(db)
+            .await?
+            .map(Resolved::Advisory),
+        Subject::Vulnerability(id) => vulnerability::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .map(|vulnerability| Resolved::Vulnerability(vulnerability.id)),
+    })
+}
+
+async fn find(
+    db: &impl ConnectionTrait,
+    subject: Subject,
+    id: Uuid,
+    lock: bool,
+) -> Result<Option<comment::Model>, Error> {
+    let Some(subject) = resolve(db, subject).await? else {
+        return Ok(None);
+    };
+
+    let mut select = comment::Entity::find_by_id(id).filter(subject.condition());
+    if lock {
+        select = select.lock_exclusive();
+    }
+
+    Ok(select.one(db).await?)
+}
+
+fn validate(request: CommentRequest) -> Result<CommentRequest, Error> {
+    if request.body.trim().is_empty() {
+        return Err(Error::BadRequest("The comment must not be empty".into()));
+    }
+    Ok(request)
+}
+
+/// Ensure the user is the author of a comment. Without authentication, any comment can be
+/// changed.
+fn require_author(comment: &comment::Model, user: Option<&str>) -> Result<(), Error> {
+    match user {
+        Some(user) if comment.author.as_deref() != Some(user) => Err(Error::Forbidden(format!(
+            "Comment {} was written by another user",
+            comment.id
+        ))),
+        _ => Ok(()),
+    }
+}
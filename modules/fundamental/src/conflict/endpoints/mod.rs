@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    Error,
+    change::endpoints::parse_types,
+    conflict::{
+        model::{DocumentConflict, DocumentKind},
+        service::ConflictService,
+    },
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, get, web};
+use trustify_auth::{Permission, authenticator::user::UserInformation, authorizer::Authorizer};
+use trustify_common::{
+    db::{
+        Database,
+        query::{LabelFilters, LabelQuery},
+    },
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults},
+};
+use utoipa::IntoParams;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = ConflictService::new(db);
+
+    config.app_data(web::Data::new(service)).service(conflicts);
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize, IntoParams)]
+struct ConflictsParams {
+    /// The types of documents to report, `,`-delimited: `advisory` and `sbom`. Defaults to all.
+    #[serde(default)]
+    types: Option<String>,
+}
+
+#[utoipa::path(
+    tag = "conflict",
+    operation_id = "listConflicts",
+    params(
+        ConflictsParams,
+        LabelQuery,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Conflicting documents, the most recent conflict first", body = PaginatedResults<DocumentConflict>),
+        (status = 400, description = "The types are invalid", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/conflicts")]
+/// List documents sharing the same identity, but having different content
+///
+/// Documents are identified by a format specific ID and revision, like the publisher and tracking
+/// ID of a CSAF document. Copies of a document only differing in their formatting are reported as
+/// duplicates when ingesting them, others are ingested and reported here.
+pub async fn conflicts(
+    service: web::Data<ConflictService>,
+    web::Query(ConflictsParams { types }): web::Query<ConflictsParams>,
+    web::Query(labels): web::Query<LabelQuery>,
+    web::Query(paginated): web::Query<Paginated>,
+    authorizer: web::Data<Authorizer>,
+    user: UserInformation,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let kinds = parse_types(types.as_deref())?;
+
+    for kind in &kinds {
+        authorizer.require(
+            &user,
+            match kind {
+                DocumentKind::Sbom => Permission::ReadSbom,
+                DocumentKind::Advisory => Permission::ReadAdvisory,
+            },
+        )?;
+    }
+
+    let labels = tenant.restrict(LabelFilters::try_from(labels).map_err(Error::from)?);
+
+    Ok(HttpResponse::Ok().json(service.conflicts(kinds, labels, paginated).await?))
+}
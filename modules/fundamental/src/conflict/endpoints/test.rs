@@ -0,0 +1,96 @@
+use crate::{
+    conflict::model::{DocumentConflict, DocumentKind},
+    test::caller,
+};
+use actix_web::test::TestRequest;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, sea_query::Expr};
+use serde_json::Value;
+use test_context::test_context;
+use test_log::test;
+use time::OffsetDateTime;
+use trustify_common::{id::Id, model::PaginatedResults};
+use trustify_entity::{advisory, document_identity};
+use trustify_test_context::{TrustifyContext, call::CallService, document_bytes};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn conflicting_documents(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let original = ctx.ingest_document("osv/RUSTSEC-2021-0079.json").await?;
+    let mut document: Value =
+        serde_json::from_slice(&document_bytes("osv/RUSTSEC-2021-0079.json").await?)?;
+
+    // a copy, only differing in its formatting, is the same document
+
+    let copy = ctx.ingest_json(&document).await?;
+    assert!(copy.duplicate);
+    assert_eq!(copy.id, original.id);
+
+    // a copy with a different content, but the same ID and date of update, is conflicting
+
+    document["summary"] = "A different summary".into();
+    let conflicting = ctx.ingest_json(&document).await?;
+    assert!(!conflicting.duplicate);
+    assert_ne!(conflicting.id, original.id);
+    assert_eq!(conflicting.warnings.len(), 1);
+
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::get().uri("/api/v2/conflicts").to_request();
+    let result: PaginatedResults<DocumentConflict> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 1);
+
+    let conflict = &result.items[0];
+    assert_eq!(conflict.kind, DocumentKind::Advisory);
+    assert_eq!(conflict.format, "osv");
+    assert_eq!(conflict.identifier, "RUSTSEC-2021-0079");
+    assert_eq!(conflict.revision.as_deref(), Some("2021-10-19T22:14:35Z"));
+    assert_eq!(Id::Uuid(conflict.existing), original.id);
+    assert_eq!(Id::Uuid(conflict.conflicting), conflicting.id);
+
+    let request = TestRequest::get()
+        .uri("/api/v2/conflicts?types=sbom")
+        .to_request();
+    let result: PaginatedResults<DocumentConflict> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 0);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn equivalent_of_deleted_document(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let original = ctx.ingest_document("osv/RUSTSEC-2021-0079.json").await?;
+    let Id::Uuid(id) = original.id else {
+        panic!("unexpected ID: {}", original.id);
+    };
+    let document: Value =
+        serde_json::from_slice(&document_bytes("osv/RUSTSEC-2021-0079.json").await?)?;
+
+    advisory::Entity::update_many()
+        .col_expr(
+            advisory::Column::DeletedAt,
+            Expr::value(OffsetDateTime::now_utc()),
+        )
+        .filter(advisory::Column::Id.eq(id))
+        .exec(&ctx.db)
+        .await?;
+
+    // a copy of a deleted document is a new document, taking over the identity
+
+    let copy = ctx.ingest_json(&document).await?;
+    assert!(!copy.duplicate);
+    assert_ne!(copy.id, original.id);
+    assert!(copy.warnings.is_empty());
+
+    let identities = document_identity::Entity::find().all(&ctx.db).await?;
+    assert_eq!(identities.len(), 1);
+    assert_eq!(Id::Uuid(identities[0].document_id), copy.id);
+
+    // and another copy is the same document again
+
+    let again = ctx.ingest_json(&document).await?;
+    assert!(again.duplicate);
+    assert_eq!(again.id, copy.id);
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::document_conflict;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub use trustify_entity::download_audit::DocumentKind;
+
+/// Two SBOMs or advisories with the same identity, but different content.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DocumentConflict {
+    pub id: Uuid,
+    /// The time the conflict got detected, when ingesting the second document
+    #[serde(with = "time::serde::rfc3339")]
+    pub detected: OffsetDateTime,
+    #[serde(rename = "type")]
+    pub kind: DocumentKind,
+    /// The format of the documents, like `csaf`
+    pub format: String,
+    /// The format specific ID, shared by both documents
+    pub identifier: String,
+    /// The revision, shared by both documents
+    pub revision: Option<String>,
+    /// The ID of the SBOM or advisory ingested first
+    pub existing: Uuid,
+    /// The ID of the SBOM or advisory ingested later
+    pub conflicting: Uuid,
+}
+
+impl From<document_conflict::Model> for DocumentConflict {
+    fn from(value: document_conflict::Model) -> Self {
+        let document_conflict::Model {
+            id,
+            detected,
+            document_kind,
+            format,
+            identifier,
+            revision,
+            existing_id,
+            conflicting_id,
+        } = value;
+
+        Self {
+            id,
+            detected,
+            kind: document_kind,
+            format,
+            identifier,
+            revision,
+            existing: existing_id,
+            conflicting: conflicting_id,
+        }
+    }
+}
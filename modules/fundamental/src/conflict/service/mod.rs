@@ -0,0 +1,81 @@
+use crate::{
+    Error,
+    conflict::model::{DocumentConflict, DocumentKind},
+};
+use sea_orm::{ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder};
+use sea_query::{Query, SelectStatement};
+use trustify_common::{
+    db::{Database, limiter::LimiterTrait, query::LabelFilters},
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{advisory, document_conflict, sbom};
+
+pub struct ConflictService {
+    db: Database,
+}
+
+impl ConflictService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// List the conflicts between documents, the most recent first.
+    ///
+    /// Conflicts are only reported as long as both documents exist, and labels must match both
+    /// of them.
+    pub async fn conflicts(
+        &self,
+        kinds: Vec<DocumentKind>,
+        labels: LabelFilters,
+        paginated: Paginated,
+    ) -> Result<PaginatedResults<DocumentConflict>, Error> {
+        let visible = kinds.into_iter().fold(Condition::any(), |any, kind| {
+            any.add(
+                Condition::all()
+                    .add(document_conflict::Column::DocumentKind.eq(kind))
+                    .add(
+                        document_conflict::Column::ExistingId
+                            .in_subquery(existing(kind, labels.clone())),
+                    )
+                    .add(
+                        document_conflict::Column::ConflictingId
+                            .in_subquery(existing(kind, labels.clone())),
+                    ),
+            )
+        });
+
+        let limiter = document_conflict::Entity::find()
+            .filter(visible)
+            .order_by_desc(document_conflict::Column::Detected)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        Ok(PaginatedResults {
+            total: limiter.total().await?,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(DocumentConflict::from)
+                .collect(),
+        })
+    }
+}
+
+/// Select the IDs of documents, which weren't deleted and match the labels.
+fn existing(kind: DocumentKind, labels: LabelFilters) -> SelectStatement {
+    match kind {
+        DocumentKind::Sbom => Query::select()
+            .column(sbom::Column::SbomId)
+            .from(sbom::Entity)
+            .and_where(sbom::Column::DeletedAt.is_null())
+            .cond_where(labels.into_condition(sbom::Column::Labels))
+            .to_owned(),
+        DocumentKind::Advisory => Query::select()
+            .column(advisory::Column::Id)
+            .from(advisory::Entity)
+            .and_where(advisory::Column::DeletedAt.is_null())
+            .cond_where(labels.into_condition(advisory::Column::Labels))
+            .to_owned(),
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    Error,
+    cpe::{
+        model::{CpeMatch, CpePattern, VersionRange},
+        service::CpeService,
+    },
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, get, web};
+use serde::Deserialize;
+use trustify_auth::{ReadSbom, authorizer::Require};
+use trustify_common::{
+    db::Database,
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults},
+};
+use utoipa::IntoParams;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = CpeService::new();
+    config
+        .app_data(web::Data::new(db))
+        .app_data(web::Data::new(service))
+        .service(r#match);
+}
+
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+pub struct CpeQuery {
+    /// The CPE to match, as a CPE 2.3 formatted string, a well-formed name, or a CPE 2.2 URI.
+    /// Attributes may use the `*` and `?` wildcards.
+    pub cpe: String,
+}
+
+#[utoipa::path(
+    tag = "cpe",
+    operation_id = "matchCpe",
+    params(
+        CpeQuery,
+        VersionRange,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Matching CPEs", body = PaginatedResults<CpeMatch>),
+        (status = 400, description = "The CPE could not be parsed", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/cpe/match")]
+/// Find CPEs matching a pattern, with the products and SBOM packages using them
+pub async fn r#match(
+    service: web::Data<CpeService>,
+    db: web::Data<Database>,
+    web::Query(query): web::Query<CpeQuery>,
+    web::Query(range): web::Query<VersionRange>,
+    web::Query(paginated): web::Query<Paginated>,
+    tenant: Tenant,
+    _: Require<ReadSbom>,
+) -> actix_web::Result<impl Responder> {
+    let pattern = query.cpe.parse::<CpePattern>().map_err(Error::BadRequest)?;
+
+    Ok(HttpResponse::Ok().json(
+        service
+            .fetch_matches(&pattern, &range, &tenant, paginated, db.read())
+            .await?,
+    ))
+}
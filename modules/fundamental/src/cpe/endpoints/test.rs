@@ -0,0 +1,113 @@
+use crate::{cpe::model::CpeMatch, test::caller};
+use actix_http::StatusCode;
+use actix_web::test::TestRequest;
+use std::str::FromStr;
+use test_context::test_context;
+use test_log::test;
+use trustify_common::{cpe::Cpe, model::PaginatedResults};
+use trustify_module_ingestor::graph::product::ProductInformation;
+use trustify_test_context::{TrustifyContext, call::CallService};
+use urlencoding::encode;
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn match_wildcards(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    ctx.ingest_documents([
+        "spdx/quarkus-bom-3.2.11.Final-redhat-00001.json",
+        "spdx/openssl-3.0.7-18.el9_2.spdx.alias.json",
+    ])
+    .await?;
+    ctx.graph
+        .ingest_product(
+            "Quarkus",
+            ProductInformation {
+                vendor: Some("Red Hat".to_string()),
+                cpe: Some(Cpe::from_str("cpe:/a:redhat:quarkus:3")?),
+            },
+            &ctx.db,
+        )
+        .await?;
+
+    let uri = format!(
+        "/api/v2/cpe/match?cpe={}",
+        encode("cpe:2.3:a:redhat:quark*:*:*:*:*:*:*:*:*")
+    );
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response: PaginatedResults<CpeMatch> = app.call_and_read_body_json(request).await;
+
+    assert_eq!(response.total, 1);
+    let item = &response.items[0];
+    assert!(item.cpe.starts_with("cpe:/a:redhat:quarkus:3.2"));
+    assert_eq!(item.products.len(), 1);
+    assert_eq!(item.products[0].name, "Quarkus");
+    assert_eq!(item.packages.len(), 1);
+
+    // all the openssl variants, using a single character wildcard
+
+    let uri = format!(
+        "/api/v2/cpe/match?cpe={}",
+        encode("cpe:2.3:a:REDHAT:openss?")
+    );
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response: PaginatedResults<CpeMatch> = app.call_and_read_body_json(request).await;
+
+    assert_eq!(response.total, 1);
+    assert!(response.items[0].products.is_empty());
+    assert!(!response.items[0].packages.is_empty());
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn match_version_range(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    ctx.ingest_documents([
+        "spdx/quarkus-bom-3.2.11.Final-redhat-00001.json",
+        "spdx/openssl-3.0.7-18.el9_2.spdx.alias.json",
+    ])
+    .await?;
+
+    let cpe = encode(r#"wfn:[part="a",vendor="redhat"]"#);
+
+    let uri =
+        format!("/api/v2/cpe/match?cpe={cpe}&version_start_including=3&version_end_excluding=3.1");
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response: PaginatedResults<CpeMatch> = app.call_and_read_body_json(request).await;
+
+    assert_eq!(response.total, 2);
+    assert!(
+        response
+            .items
+            .iter()
+            .all(|item| item.cpe.contains(":3.0.7"))
+    );
+
+    let uri = format!("/api/v2/cpe/match?cpe={cpe}&version_start_excluding=3.0.7");
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response: PaginatedResults<CpeMatch> = app.call_and_read_body_json(request).await;
+
+    assert_eq!(response.total, 1);
+    assert!(
+        response.items[0]
+            .cpe
+            .starts_with("cpe:/a:redhat:quarkus:3.2")
+    );
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn match_invalid(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    let uri = format!("/api/v2/cpe/match?cpe={}", encode("pkg:npm/lodash"));
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response = app.call_service(request).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
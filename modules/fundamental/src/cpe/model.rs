@@ -0,0 +1,376 @@
+use crate::product::model::ProductHead;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use trustify_common::cpe::{Component, Cpe, CpeType, Language};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// The value of an attribute of a [`CpePattern`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AttributePattern {
+    /// Matches any value
+    Any,
+    /// Matches only "not applicable"
+    NotApplicable,
+    /// Matches lowercased values using this `LIKE` pattern, with `\` as escape character
+    Like(String),
+}
+
+impl AttributePattern {
+    /// Parse a value, using the `*` and `?` wildcards of CPE 2.3 and `\` for escaping them.
+    fn wildcards(value: &str) -> Self {
+        let mut result = String::with_capacity(value.len());
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    if let Some(c) = chars.next() {
+                        Self::push_literal(&mut result, c);
+                    }
+                }
+                '*' => result.push('%'),
+                '?' => result.push('_'),
+                c => Self::push_literal(&mut result, c),
+            }
+        }
+        Self::Like(result)
+    }
+
+    /// A value without wildcards.
+    fn literal(value: &str) -> Self {
+        let mut result = String::with_capacity(value.len());
+        for c in value.chars() {
+            Self::push_literal(&mut result, c);
+        }
+        Self::Like(result)
+    }
+
+    fn push_literal(result: &mut String, c: char) {
+        if matches!(c, '%' | '_' | '\\') {
+            result.push('\\');
+        }
+        result.extend(c.to_lowercase());
+    }
+
+    /// Parse an attribute of the formatted string binding.
+    fn from_formatted(value: &str) -> Self {
+        match value {
+            "" | "*" => Self::Any,
+            "-" => Self::NotApplicable,
+            value => Self::wildcards(value),
+        }
+    }
+
+    /// Parse an attribute of a well-formed name (WFN).
+    fn from_wfn(value: &str) -> Result<Self, String> {
+        match value {
+            "ANY" => Ok(Self::Any),
+            "NA" => Ok(Self::NotApplicable),
+            value => match value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+            {
+                Some(value) => Ok(Self::wildcards(value)),
+                None => Err(format!("invalid WFN attribute value: {value}")),
+            },
+        }
+    }
+
+    fn from_component(component: Component) -> Self {
+        match component {
+            Component::Any => Self::Any,
+            Component::NotApplicable => Self::NotApplicable,
+            Component::Value(value) => Self::literal(&value),
+        }
+    }
+}
+
+/// A pattern, matching CPEs.
+///
+/// Parsed from a CPE 2.3 formatted string (`cpe:2.3:a:redhat:*:…`), a well-formed name
+/// (`wfn:[part="a",vendor="redhat"]`), or a CPE 2.2 URI (`cpe:/a:redhat`). Attributes of the
+/// formatted string and the well-formed name may use the `*` and `?` wildcards.
+///
+/// The extended attributes of CPE 2.3 (`sw_edition`, `target_sw`, `target_hw`, `other`) are
+/// not stored, and so only support matching any value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CpePattern {
+    pub part: AttributePattern,
+    pub vendor: AttributePattern,
+    pub product: AttributePattern,
+    pub version: AttributePattern,
+    pub update: AttributePattern,
+    pub edition: AttributePattern,
+    pub language: AttributePattern,
+}
+
+impl Default for CpePattern {
+    fn default() -> Self {
+        Self {
+            part: AttributePattern::Any,
+            vendor: AttributePattern::Any,
+            product: AttributePattern::Any,
+            version: AttributePattern::Any,
+            update: AttributePattern::Any,
+            edition: AttributePattern::Any,
+            language: AttributePattern::Any,
+        }
+    }
+}
+
+const EXTENDED_ATTRIBUTES: [&str; 4] = ["sw_edition", "target_sw", "target_hw", "other"];
+
+impl CpePattern {
+    fn set(&mut self, name: &str, value: AttributePattern) -> Result<(), String> {
+        match name {
+            "part" => self.part = value,
+            "vendor" => self.vendor = value,
+            "product" => self.product = value,
+            "version" => self.version = value,
+            "update" => self.update = value,
+            "edition" => self.edition = value,
+            "language" => self.language = value,
+            name if EXTENDED_ATTRIBUTES.contains(&name) => {
+                if value != AttributePattern::Any {
+                    return Err(format!("matching the '{name}' attribute is not supported"));
+                }
+            }
+            name => return Err(format!("unknown CPE attribute: {name}")),
+        }
+        Ok(())
+    }
+
+    fn from_formatted(value: &str) -> Result<Self, String> {
+        const NAMES: [&str; 11] = [
+            "part",
+            "vendor",
+            "product",
+            "version",
+            "update",
+            "edition",
+            "language",
+            "sw_edition",
+            "target_sw",
+            "target_hw",
+            "other",
+        ];
+
+        let values = split_unescaped(value, ':');
+        if values.len() > NAMES.len() {
+            return Err(format!("too many attributes in CPE: {value}"));
+        }
+
+        // missing trailing attributes match any value
+        let mut result = Self::default();
+        for (name, value) in NAMES.iter().zip(values) {
+            result.set(name, AttributePattern::from_formatted(value))?;
+        }
+        Ok(result)
+    }
+
+    fn from_wfn(value: &str) -> Result<Self, String> {
+        let mut result = Self::default();
+        for attribute in split_unescaped(value, ',') {
+            let attribute = attribute.trim();
+            if attribute.is_empty() {
+                continue;
+            }
+            let Some((name, value)) = attribute.split_once('=') else {
+                return Err(format!("invalid WFN attribute: {attribute}"));
+            };
+            result.set(name.trim(), AttributePattern::from_wfn(value.trim())?)?;
+        }
+        Ok(result)
+    }
+
+    fn from_uri(value: &str) -> Result<Self, String> {
+        let cpe = Cpe::from_str(value).map_err(|err| format!("invalid CPE: {err}"))?;
+
+        Ok(Self {
+            part: match cpe.part() {
+                CpeType::Any | CpeType::Empty => AttributePattern::Any,
+                CpeType::Hardware => AttributePattern::literal("h"),
+                CpeType::OperatingSystem => AttributePattern::literal("o"),
+                CpeType::Application => AttributePattern::literal("a"),
+            },
+            vendor: AttributePattern::from_component(cpe.vendor()),
+            product: AttributePattern::from_component(cpe.product()),
+            version: AttributePattern::from_component(cpe.version()),
+            update: AttributePattern::from_component(cpe.update()),
+            edition: AttributePattern::from_component(cpe.edition()),
+            language: match cpe.language() {
+                Language::Any => AttributePattern::Any,
+                Language::Language(value) => AttributePattern::literal(&value),
+            },
+        })
+    }
+}
+
+impl FromStr for CpePattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(value) = s.strip_prefix("cpe:2.3:") {
+            Self::from_formatted(value)
+        } else if let Some(value) = s
+            .strip_prefix("wfn:[")
+            .and_then(|value| value.strip_suffix(']'))
+        {
+            Self::from_wfn(value)
+        } else if s.starts_with("cpe:/") {
+            Self::from_uri(s)
+        } else {
+            Err(format!("unsupported CPE binding: {s}"))
+        }
+    }
+}
+
+/// Split a string by a separator, unless it is escaped or part of a quoted value.
+fn split_unescaped(value: &str, separator: char) -> Vec<&str> {
+    let mut result = vec![];
+    let mut start = 0;
+    let mut escaped = false;
+    let mut quoted = false;
+
+    for (idx, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                result.push(&value[start..idx]);
+                start = idx + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    result.push(&value[start..]);
+
+    result
+}
+
+/// A range of versions, limiting the matched CPEs.
+///
+/// Versions are compared segment by segment, like RPM versions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, IntoParams)]
+pub struct VersionRange {
+    /// Match versions starting with this one
+    pub version_start_including: Option<String>,
+    /// Match versions after this one
+    pub version_start_excluding: Option<String>,
+    /// Match versions up to, and including, this one
+    pub version_end_including: Option<String>,
+    /// Match versions before this one
+    pub version_end_excluding: Option<String>,
+}
+
+impl VersionRange {
+    pub fn is_empty(&self) -> bool {
+        self.version_start_including.is_none()
+            && self.version_start_excluding.is_none()
+            && self.version_end_including.is_none()
+            && self.version_end_excluding.is_none()
+    }
+}
+
+/// A CPE, matching a pattern, and its users.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct CpeMatch {
+    /// The matching CPE
+    pub cpe: String,
+    /// Products identified by the CPE
+    pub products: Vec<ProductHead>,
+    /// SBOM packages referencing the CPE
+    pub packages: Vec<CpeMatchPackage>,
+}
+
+/// An SBOM package, referencing a CPE.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct CpeMatchPackage {
+    /// The ID of the SBOM
+    #[serde(with = "uuid::serde::urn")]
+    #[schema(value_type=String)]
+    pub sbom_id: Uuid,
+    /// The ID of the package within the SBOM
+    pub node_id: String,
+    /// The name of the package
+    pub name: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+
+    fn like(value: &str) -> AttributePattern {
+        AttributePattern::Like(value.into())
+    }
+
+    #[test]
+    fn formatted() {
+        let pattern =
+            CpePattern::from_str("cpe:2.3:a:redhat:*:9.?:-:*:*:*:*:*:*").expect("must parse");
+
+        assert_eq!(
+            pattern,
+            CpePattern {
+                part: like("a"),
+                vendor: like("redhat"),
+                version: like("9._"),
+                update: AttributePattern::NotApplicable,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn formatted_escaped() {
+        let pattern = CpePattern::from_str(r"cpe:2.3:a:Foo\:bar:100\%_pro*").expect("must parse");
+
+        assert_eq!(pattern.vendor, like("foo:bar"));
+        assert_eq!(pattern.product, like(r"100\%\_pro%"));
+        assert_eq!(pattern.version, AttributePattern::Any);
+    }
+
+    #[test]
+    fn wfn() {
+        let pattern = CpePattern::from_str(
+            r#"wfn:[part="o",vendor="red\,hat",product=ANY,version="8*",update=NA]"#,
+        )
+        .expect("must parse");
+
+        assert_eq!(
+            pattern,
+            CpePattern {
+                part: like("o"),
+                vendor: like("red,hat"),
+                version: like("8%"),
+                update: AttributePattern::NotApplicable,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn uri() {
+        let pattern = CpePattern::from_str("cpe:/a:redhat:enterprise_linux:9").expect("must parse");
+
+        assert_eq!(
+            pattern,
+            CpePattern {
+                part: like("a"),
+                vendor: like("redhat"),
+                product: like(r"enterprise\_linux"),
+                version: like("9"),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn unsupported() {
+        assert!(CpePattern::from_str("cpe:2.3:a:redhat:*:*:*:*:*:*:linux").is_err());
+        assert!(CpePattern::from_str(r#"wfn:[color="red"]"#).is_err());
+        assert!(CpePattern::from_str("pkg:npm/lodash").is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{
+    Error,
+    cpe::model::{AttributePattern, CpeMatch, CpeMatchPackage, CpePattern, VersionRange},
+    product::model::ProductHead,
+    tenant::Tenant,
+};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, EntityTrait, JoinType, QueryFilter, QueryOrder, QuerySelect,
+    RelationTrait, Select,
+};
+use sea_query::{Expr, Func, LikeExpr, SimpleExpr};
+use std::collections::{BTreeSet, HashMap};
+use trustify_common::{
+    db::limiter::LimiterTrait,
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{cpe, product, sbom, sbom_node, sbom_package_cpe_ref};
+use uuid::Uuid;
+
+#[derive(Default)]
+pub struct CpeService {}
+
+impl CpeService {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Find CPEs matching a pattern and a range of versions, together with the products they
+    /// identify and the SBOM packages referencing them.
+    pub async fn fetch_matches<C: ConnectionTrait + Sync + Send>(
+        &self,
+        pattern: &CpePattern,
+        range: &VersionRange,
+        tenant: &Tenant,
+        paginated: Paginated,
+        connection: &C,
+    ) -> Result<PaginatedResults<CpeMatch>, Error> {
+        let limiter = filter_range(filter_pattern(cpe::Entity::find(), pattern), range)
+            .order_by_asc(cpe::Column::Vendor)
+            .order_by_asc(cpe::Column::Product)
+            .order_by_asc(cpe::Column::Version)
+            .order_by_asc(cpe::Column::Id)
+            .limiting(connection, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        let total = limiter.total().await?;
+        let cpes = limiter.fetch().await?;
+
+        let products = products(&cpes, connection).await?;
+        let mut packages = packages(&cpes, tenant, connection).await?;
+
+        let items = cpes
+            .into_iter()
+            .map(|cpe| CpeMatch {
+                products: cpe
+                    .product
+                    .as_ref()
+                    .and_then(|key| products.get(key))
+                    .cloned()
+                    .unwrap_or_default(),
+                packages: packages.remove(&cpe.id).unwrap_or_default(),
+                cpe: cpe.to_string(),
+            })
+            .collect();
+
+        Ok(PaginatedResults { items, total })
+    }
+}
+
+fn filter_pattern(mut select: Select<cpe::Entity>, pattern: &CpePattern) -> Select<cpe::Entity> {
+    for (column, attribute) in [
+        (cpe::Column::Part, &pattern.part),
+        (cpe::Column::Vendor, &pattern.vendor),
+        (cpe::Column::Product, &pattern.product),
+        (cpe::Column::Version, &pattern.version),
+        (cpe::Column::Update, &pattern.update),
+        (cpe::Column::Edition, &pattern.edition),
+        (cpe::Column::Language, &pattern.language),
+    ] {
+        select = match attribute {
+            AttributePattern::Any => select,
+            AttributePattern::NotApplicable => select.filter(column.is_null()),
+            AttributePattern::Like(like) => select.filter(
+                Expr::expr(Func::lower(Expr::col((cpe::Entity, column))))
+                    .like(LikeExpr::new(like).escape('\\')),
+            ),
+        };
+    }
+
+    select
+}
+
+fn filter_range(mut select: Select<cpe::Entity>, range: &VersionRange) -> Select<cpe::Entity> {
+    if range.is_empty() {
+        return select;
+    }
+
+    // only actual versions can be part of a range
+    select = select
+        .filter(cpe::Column::Version.is_not_null())
+        .filter(cpe::Column::Version.ne("*"));
+
+    for (version, op) in [
+        (&range.version_start_including, ">="),
+        (&range.version_start_excluding, ">"),
+        (&range.version_end_including, "<="),
+        (&range.version_end_excluding, "<"),
+    ] {
+        if let Some(version) = version {
+            select = select.filter(Expr::cust_with_exprs(
+                format!("rpmver_cmp($1, $2) {op} 0"),
+                [
+                    Expr::col((cpe::Entity, cpe::Column::Version)).into(),
+                    SimpleExpr::Value(version.clone().into()),
+                ],
+            ));
+        }
+    }
+
+    select
+}
+
+/// Look up the products identified by the CPEs, grouped by their CPE key.
+async fn products<C: ConnectionTrait>(
+    cpes: &[cpe::Model],
+    connection: &C,
+) -> Result<HashMap<String, Vec<ProductHead>>, Error> {
+    let keys = cpes
+        .iter()
+        .filter_map(|cpe| cpe.product.clone())
+        .collect::<BTreeSet<_>>();
+    if keys.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut result = HashMap::<_, Vec<_>>::new();
+    for product in product::Entity::find()
+        .filter(product::Column::CpeKey.is_in(keys))
+        .order_by_asc(product::Column::Name)
+        .all(connection)
+        .await?
+    {
+        if let Some(key) = &product.cpe_key {
+            result
+                .entry(key.clone())
+                .or_default()
+                .push(ProductHead::from_entity(&product).await?);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Look up the SBOM packages referencing the CPEs, grouped by the ID of the CPE.
+async fn packages<C: ConnectionTrait>(
+    cpes: &[cpe::Model],
+    tenant: &Tenant,
+    connection: &C,
+) -> Result<HashMap<Uuid, Vec<CpeMatchPackage>>, Error> {
+    if cpes.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut select = sbom_package_cpe_ref::Entity::find()
+        .select_only()
+        .column(sbom_package_cpe_ref::Column::CpeId)
+        .column(sbom_package_cpe_ref::Column::SbomId)
+        .column(sbom_package_cpe_ref::Column::NodeId)
+        .column(sbom_node::Column::Name)
+        .join(JoinType::Join, sbom_package_cpe_ref::Relation::Sbom.def())
+        .join(
+            JoinType::Join,
+            sbom_package_cpe_ref::Entity::belongs_to(sbom_node::Entity)
+                .from((
+                    sbom_package_cpe_ref::Column::SbomId,
+                    sbom_package_cpe_ref::Column::NodeId,
+                ))
+                .to((sbom_node::Column::SbomId, sbom_node::Column::NodeId))
+                .into(),
+        )
+        .filter(sbom_package_cpe_ref::Column::CpeId.is_in(cpes.iter().map(|cpe| cpe.id)))
+        .filter(sbom::Column::DeletedAt.is_null())
+        .order_by_asc(sbom_package_cpe_ref::Column::SbomId)
+        .order_by_asc(sbom_package_cpe_ref::Column::NodeId);
+
+    if let Some(filter) = tenant.filter() {
+        select = select.filter(filter.into_condition((sbom::Entity, sbom::Column::Labels)));
+    }
+
+    let mut result = HashMap::<_, Vec<_>>::new();
+    for (cpe_id, sbom_id, node_id, name) in select
+        .into_tuple::<(Uuid, Uuid, String, String)>()
+        .all(connection)
+        .await?
+    {
+        result.entry(cpe_id).or_default().push(CpeMatchPackage {
+            sbom_id,
+            node_id,
+            name,
+        });
+    }
+
+    Ok(result)
+}
@@ -1,15 +1,63 @@
-use actix_web::web;
-use trustify_common::db::Database;
+use crate::{
+    Error, advisory::service::DeletionKey, audit::service::AuditService,
+    subscription::service::WebhookAllowlist, tenant::TenantIsolation,
+};
+use actix_web::{HttpResponse, web};
+use reqwest::Url;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use trustify_common::{
+    db::Database, decompress::Limits, model::PaginatedResults, signing::ResponseSigner,
+};
 use trustify_module_analysis::service::AnalysisService;
-use trustify_module_ingestor::graph::Graph;
-use trustify_module_ingestor::service::IngestorService;
+use trustify_module_ingestor::graph::{Graph, organization::OrganizationAliases};
+use trustify_module_ingestor::service::{
+    IngestorService, LabelTemplates, enricher::Enrichers, listener::IngestListeners,
+};
+use trustify_module_settings::service::SettingsService;
 use trustify_module_storage::service::dispatch::DispatchBackend;
 use utoipa::{IntoParams, ToSchema};
 
-#[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[derive(Clone, Debug, PartialEq, Default)]
 pub struct Config {
+    /// Size limit of SBOMs, after decompression. Zero meaning "unlimited".
     pub sbom_upload_limit: usize,
+    /// Size limit of advisories, after decompression. Zero meaning "unlimited".
     pub advisory_upload_limit: usize,
+    /// Maximum ratio between the decompressed and compressed size of an upload. Zero meaning
+    /// "unlimited".
+    pub compression_ratio_limit: usize,
+    /// Templates for labels, evaluated against the content of uploaded documents.
+    pub label_templates: LabelTemplates,
+    /// URL of an OSV API, used for looking up purls without local vulnerability information.
+    pub osv_fallback_url: Option<Url>,
+    /// Maximum number of items of collections embedded in detail responses. Zero meaning
+    /// "unlimited".
+    pub embedded_item_limit: usize,
+    /// Stages, enriching uploaded documents after they got ingested.
+    pub enrichers: Enrichers,
+    /// Listeners, notified about uploaded documents after they got ingested.
+    pub listeners: IngestListeners,
+    /// Aliases, applied to the names of ingested organizations.
+    pub organization_aliases: OrganizationAliases,
+    /// Signs selected responses, if a key is configured.
+    pub signer: ResponseSigner,
+    /// Mark deleted SBOMs and advisories as deleted, instead of removing them, so that they can
+    /// be restored.
+    pub soft_delete: bool,
+    /// The secret signing the tokens, which confirm deleting all advisories matching a query.
+    /// Random if unset, only valid for the instance issuing them.
+    pub deletion_secret: Option<String>,
+    /// Isolate tenants from each other, limiting users to the SBOMs and advisories of their
+    /// tenant.
+    pub tenant_isolation: bool,
+    /// Hosts which webhooks of subscriptions may reach, even though they are internal.
+    pub webhook_allowlist: WebhookAllowlist,
+    /// The LLM backend of the AI service.
+    #[cfg(feature = "ai")]
+    pub ai: crate::ai::service::backend::LlmConfig,
 }
 
 pub fn configure(
@@ -19,18 +67,57 @@ pub fn configure(
     storage: impl Into<DispatchBackend>,
     analysis: AnalysisService,
 ) {
-    let ingestor_service = IngestorService::new(Graph::new(db.clone()), storage, Some(analysis));
-    svc.app_data(web::Data::new(ingestor_service));
+    let graph =
+        Graph::new(db.clone()).with_organization_aliases(config.organization_aliases.clone());
+    let ingestor_service = IngestorService::new(graph, storage, Some(analysis))
+        .with_label_templates(config.label_templates.clone())
+        .with_enrichers(config.enrichers.clone())
+        .with_listeners(config.listeners.clone());
+    svc.app_data(web::Data::new(ingestor_service.clone()))
+        .app_data(web::Data::new(SettingsService::new(db.clone())))
+        .app_data(web::Data::new(AuditService::new(db.clone())))
+        .app_data(web::Data::new(config.signer.clone()))
+        .app_data(web::Data::new(TenantIsolation(config.tenant_isolation)));
 
-    crate::advisory::endpoints::configure(svc, db.clone(), config.advisory_upload_limit);
+    crate::advisory::endpoints::configure(
+        svc,
+        db.clone(),
+        Limits::new(config.advisory_upload_limit, config.compression_ratio_limit),
+        config.soft_delete,
+        DeletionKey::new(config.deletion_secret.as_deref()),
+    );
+    crate::admin::endpoints::configure(svc, db.clone(), ingestor_service);
     crate::license::endpoints::configure(svc);
     #[cfg(feature = "ai")]
-    crate::ai::endpoints::configure(svc, db.clone());
+    crate::ai::endpoints::configure(svc, db.clone(), config.ai.clone());
+    crate::annotation::endpoints::configure(svc, db.clone());
+    crate::assignment::endpoints::configure(svc, db.clone());
+    crate::audit::endpoints::configure(svc);
+    crate::cpe::endpoints::configure(svc, db.clone());
+    crate::external_id::endpoints::configure(svc, db.clone());
     crate::organization::endpoints::configure(svc, db.clone());
+    crate::policy::endpoints::configure(svc, db.clone());
     crate::purl::endpoints::configure(svc, db.clone());
+    crate::saved_search::endpoints::configure(svc, db.clone());
+    crate::subscription::endpoints::configure(svc, db.clone(), config.webhook_allowlist.clone());
+    crate::triage::endpoints::configure(svc, db.clone());
+    crate::change::endpoints::configure(svc, db.clone());
+    crate::conflict::endpoints::configure(svc, db.clone());
+    crate::attachment::endpoints::configure(svc, db.clone());
+    crate::comment::endpoints::configure(svc, db.clone());
     crate::product::endpoints::configure(svc, db.clone());
-    crate::sbom::endpoints::configure(svc, db.clone(), config.sbom_upload_limit);
-    crate::vulnerability::endpoints::configure(svc, db.clone());
+    crate::product_status::endpoints::configure(svc, db.clone());
+    crate::severity_override::endpoints::configure(svc, db.clone());
+    crate::sbom::endpoints::configure(
+        svc,
+        db.clone(),
+        Limits::new(config.sbom_upload_limit, config.compression_ratio_limit),
+        config.embedded_item_limit,
+        config.soft_delete,
+    );
+    crate::stats::endpoints::configure(svc, db.clone());
+    crate::vex::endpoints::configure(svc);
+    crate::vulnerability::endpoints::configure(svc, db.clone(), config.osv_fallback_url.clone());
     crate::weakness::endpoints::configure(svc, db.clone());
 }
 
@@ -40,3 +127,87 @@ pub struct Deprecation {
     #[param(inline)]
     pub deprecated: trustify_module_ingestor::common::Deprecation,
 }
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, serde::Deserialize, IntoParams)]
+pub struct Withdrawal {
+    /// Consider the statuses of withdrawn advisories, which are ignored by default
+    #[serde(default)]
+    pub include_withdrawn: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, serde::Deserialize, IntoParams)]
+pub struct AsOf {
+    /// Reconstruct the state as of this point in time, in RFC 3339 format, only considering the
+    /// advisories ingested and not deleted by then. Defaults to the current state.
+    #[serde(default)]
+    pub as_of: Option<String>,
+}
+
+impl AsOf {
+    /// Parse the point in time, if any.
+    pub fn parse(&self) -> Result<trustify_module_ingestor::common::AsOf, Error> {
+        self.as_of
+            .as_deref()
+            .map(|as_of| {
+                OffsetDateTime::parse(as_of, &Rfc3339).map_err(|err| {
+                    Error::BadRequest(format!("Invalid point in time '{as_of}': {err}"))
+                })
+            })
+            .transpose()
+            .map(trustify_module_ingestor::common::AsOf)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, IntoParams)]
+pub struct Precheck {
+    /// The hex encoded SHA-256 digest of the document
+    pub sha256: String,
+}
+
+/// A sparse fieldset, limiting the fields returned for each item of a list.
+#[derive(Clone, Debug, PartialEq, Eq, Default, serde::Deserialize, IntoParams)]
+pub struct Fields {
+    /// The `,`-delimited fields to return for each item, e.g. `id,name`. Defaults to all fields.
+    #[serde(default)]
+    pub fields: Option<String>,
+}
+
+impl Fields {
+    fn names(&self) -> Option<HashSet<&str>> {
+        self.fields
+            .as_deref()
+            .filter(|fields| !fields.trim().is_empty())
+            .map(|fields| fields.split(',').map(str::trim).collect())
+    }
+
+    /// Serialize an item, only keeping the requested top-level fields. Unknown fields are
+    /// ignored.
+    pub fn select<T: Serialize>(&self, item: T) -> Result<Value, Error> {
+        let mut item = serde_json::to_value(item).map_err(|err| Error::Any(err.into()))?;
+        if let (Some(names), Some(item)) = (self.names(), item.as_object_mut()) {
+            item.retain(|name, _| names.contains(name.as_str()));
+        }
+        Ok(item)
+    }
+
+    /// Create a response for the results, only keeping the requested fields of each item.
+    pub fn respond<T: Serialize>(
+        &self,
+        results: PaginatedResults<T>,
+    ) -> Result<HttpResponse, Error> {
+        if self.names().is_none() {
+            return Ok(HttpResponse::Ok().json(results));
+        }
+
+        let items = results
+            .items
+            .into_iter()
+            .map(|item| self.select(item))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(HttpResponse::Ok().json(PaginatedResults {
+            items,
+            total: results.total,
+        }))
+    }
+}
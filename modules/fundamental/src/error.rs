@@ -22,6 +22,10 @@ pub enum Error {
     BadRequest(String),
     #[error("Not found: {0}")]
     NotFound(String),
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
     #[error(transparent)]
     Any(#[from] anyhow::Error),
     #[error("Unsupported hash algorithm")]
@@ -66,6 +70,12 @@ impl ResponseError for Error {
             Self::NotFound(msg) => {
                 HttpResponse::NotFound().json(ErrorInformation::new("Not Found", msg))
             }
+            Self::Conflict(msg) => {
+                HttpResponse::Conflict().json(ErrorInformation::new("Conflict", msg))
+            }
+            Self::Forbidden(msg) => {
+                HttpResponse::Forbidden().json(ErrorInformation::new("Forbidden", msg))
+            }
             Self::Ingestor(inner) => inner.error_response(),
             Self::Query(err) => {
                 HttpResponse::BadRequest().json(ErrorInformation::new("Query error", err))
@@ -78,7 +88,7 @@ impl ResponseError for Error {
                 HttpResponse::UnsupportedMediaType()
                     .json(ErrorInformation::new("UnsupportedCompression", self))
             }
-            Self::Compression(decompress::Error::PayloadTooLarge) => {
+            Self::Compression(decompress::Error::PayloadTooLarge { .. }) => {
                 HttpResponse::PayloadTooLarge().json(ErrorInformation::new("PayloadTooLarge", self))
             }
             Self::Compression(err) => {
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    Error,
+    external_id::{model::ExternalIdDocuments, service::ExternalIdService},
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, get, web};
+use sea_orm::TransactionTrait;
+use trustify_auth::{ReadAdvisory, ReadSbom, all, authorizer::Require};
+use trustify_common::db::Database;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = ExternalIdService::new();
+
+    config
+        .app_data(web::Data::new(service))
+        .app_data(web::Data::new(db))
+        .service(get_by_external_id);
+}
+
+all!(GetByExternalId -> ReadSbom, ReadAdvisory);
+
+#[utoipa::path(
+    tag = "externalId",
+    operation_id = "getByExternalId",
+    params(
+        ("id" = String, Path, description = "The external ID, as provided when uploading documents"),
+    ),
+    responses(
+        (status = 200, description = "Documents uploaded with the external ID", body = ExternalIdDocuments),
+    ),
+)]
+#[get("/v2/by-external-id/{id}")]
+/// Find the documents uploaded with an external ID
+pub async fn get_by_external_id(
+    service: web::Data<ExternalIdService>,
+    db: web::Data<Database>,
+    id: web::Path<String>,
+    _: Require<GetByExternalId>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let tx = db.begin_read().await.map_err(Error::from)?;
+    Ok(HttpResponse::Ok().json(service.find_documents(&id, &tenant, &tx).await?))
+}
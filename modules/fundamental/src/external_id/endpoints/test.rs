@@ -0,0 +1,115 @@
+use crate::{
+    external_id::model::ExternalIdDocuments,
+    test::{caller, caller_with, tenant_isolation, tenant_user},
+};
+use actix_http::StatusCode;
+use actix_web::test::TestRequest;
+use test_context::test_context;
+use test_log::test;
+use trustify_module_ingestor::model::IngestResult;
+use trustify_test_context::{
+    TrustifyContext, auth::TestAuthentication, call::CallService, document_bytes,
+};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn upload_and_find(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller(ctx).await?;
+
+    let mut ids = vec![];
+    for (uri, document) in [
+        (
+            "/api/v2/sbom?external_id=build-42",
+            "quarkus-bom-2.13.8.Final-redhat-00004.json",
+        ),
+        (
+            "/api/v2/advisory?external_id=build-42",
+            "csaf/cve-2023-0044.json",
+        ),
+        (
+            "/api/v2/sbom?external_id=build-43",
+            "zookeeper-3.9.2-cyclonedx.json",
+        ),
+    ] {
+        let request = TestRequest::post()
+            .uri(uri)
+            .set_payload(document_bytes(document).await?)
+            .to_request();
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let result: IngestResult = actix_web::test::read_body_json(response).await;
+        ids.push(result.id.to_string());
+    }
+
+    let request = TestRequest::get()
+        .uri("/api/v2/by-external-id/build-42")
+        .to_request();
+    let result: ExternalIdDocuments = app.call_and_read_body_json(request).await;
+
+    assert_eq!(result.sboms.len(), 1);
+    assert_eq!(result.sboms[0].id.urn().to_string(), ids[0]);
+    assert_eq!(result.sboms[0].external_id.as_deref(), Some("build-42"));
+    assert_eq!(result.advisories.len(), 1);
+    assert_eq!(result.advisories[0].uuid.urn().to_string(), ids[1]);
+    assert_eq!(
+        result.advisories[0].external_id.as_deref(),
+        Some("build-42")
+    );
+
+    // unknown IDs result in no documents
+
+    let request = TestRequest::get()
+        .uri("/api/v2/by-external-id/build-0")
+        .to_request();
+    let result: ExternalIdDocuments = app.call_and_read_body_json(request).await;
+
+    assert!(result.sboms.is_empty());
+    assert!(result.advisories.is_empty());
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn other_tenants(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_with(ctx, tenant_isolation()).await?;
+
+    for (uri, document) in [
+        (
+            "/api/v2/sbom?external_id=build-42",
+            "zookeeper-3.9.2-cyclonedx.json",
+        ),
+        (
+            "/api/v2/advisory?external_id=build-42",
+            "csaf/cve-2023-0044.json",
+        ),
+    ] {
+        let request = TestRequest::post()
+            .uri(uri)
+            .set_payload(document_bytes(document).await?)
+            .to_request()
+            .test_auth_details(tenant_user("a"));
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    let request = TestRequest::get()
+        .uri("/api/v2/by-external-id/build-42")
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let result: ExternalIdDocuments = app.call_and_read_body_json(request).await;
+    assert_eq!(result.sboms.len(), 1);
+    assert_eq!(result.advisories.len(), 1);
+
+    // the documents of other tenants are not found
+
+    let request = TestRequest::get()
+        .uri("/api/v2/by-external-id/build-42")
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let result: ExternalIdDocuments = app.call_and_read_body_json(request).await;
+    assert!(result.sboms.is_empty());
+    assert!(result.advisories.is_empty());
+
+    Ok(())
+}
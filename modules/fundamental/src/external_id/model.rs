@@ -0,0 +1,12 @@
+use crate::{advisory::model::AdvisoryHead, sbom::model::SbomHead};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Documents carrying an external ID.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Default)]
+pub struct ExternalIdDocuments {
+    /// SBOMs uploaded with the external ID
+    pub sboms: Vec<SbomHead>,
+    /// Advisories uploaded with the external ID
+    pub advisories: Vec<AdvisoryHead>,
+}
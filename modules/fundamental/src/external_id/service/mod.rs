@@ -0,0 +1,48 @@
+use crate::{
+    Error, advisory::model::AdvisoryHead, external_id::model::ExternalIdDocuments,
+    sbom::model::SbomHead, tenant::Tenant,
+};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use trustify_entity::{
+    advisory,
+    sbom::{self, SbomNodeLink},
+};
+
+#[derive(Default)]
+pub struct ExternalIdService {}
+
+impl ExternalIdService {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Find all documents of the tenant which got uploaded with the external ID.
+    pub async fn find_documents<C: ConnectionTrait>(
+        &self,
+        external_id: &str,
+        tenant: &Tenant,
+        connection: &C,
+    ) -> Result<ExternalIdDocuments, Error> {
+        let mut sboms = Vec::new();
+        for (sbom, node) in sbom::Entity::find()
+            .filter(sbom::Column::ExternalId.eq(external_id))
+            .filter(tenant.sbom_condition(sbom::Column::SbomId))
+            .order_by_asc(sbom::Column::SbomId)
+            .find_also_linked(SbomNodeLink)
+            .all(connection)
+            .await?
+        {
+            sboms.push(SbomHead::from_entity(&sbom, node, connection).await?);
+        }
+
+        let advisories = advisory::Entity::find()
+            .filter(advisory::Column::ExternalId.eq(external_id))
+            .filter(tenant.advisory_condition(advisory::Column::Id))
+            .order_by_asc(advisory::Column::Id)
+            .all(connection)
+            .await?;
+        let advisories = AdvisoryHead::from_entities(&advisories, connection).await?;
+
+        Ok(ExternalIdDocuments { sboms, advisories })
+    }
+}
@@ -1,14 +1,34 @@
+pub mod admin;
 pub mod advisory;
 #[cfg(feature = "ai")]
 pub mod ai;
+pub mod annotation;
+pub mod assignment;
+pub mod attachment;
+pub mod audit;
+pub mod change;
+pub mod comment;
+pub mod conflict;
+pub mod cpe;
 pub mod endpoints;
 pub mod error;
+pub mod external_id;
 pub mod license;
+pub mod listing;
 pub mod organization;
+pub mod policy;
 pub mod product;
+pub mod product_status;
 pub mod purl;
+pub mod saved_search;
 pub mod sbom;
+pub mod severity_override;
 pub mod source_document;
+pub mod stats;
+pub mod subscription;
+pub mod tenant;
+pub mod triage;
+pub mod vex;
 pub mod vulnerability;
 pub mod weakness;
 
@@ -0,0 +1,173 @@
+use crate::{Error, endpoints::Fields};
+use actix_web::{
+    HttpRequest, HttpResponse,
+    http::header::{self, Header},
+    web::Bytes,
+};
+use csv::WriterBuilder;
+use futures_util::stream;
+use serde::Serialize;
+use serde_json::Value;
+use trustify_common::{
+    db::limiter::Count,
+    model::{Paginated, PaginatedResults},
+};
+
+/// The number of items fetched at once, while streaming a listing.
+const PAGE_SIZE: u64 = 100;
+
+/// The output format of a list endpoint, negotiated by the `Accept` header.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ListFormat {
+    /// A single JSON document, containing a page of items.
+    Json,
+    /// CSV, one row per item, the first row holding the names of the columns.
+    Csv,
+    /// Newline delimited JSON, one object per item.
+    Ndjson,
+}
+
+impl ListFormat {
+    pub const CSV: &'static str = "text/csv";
+    pub const NDJSON: &'static str = "application/x-ndjson";
+
+    /// Pick the most preferred format accepted by the request, defaulting to JSON.
+    pub fn negotiate(req: &HttpRequest) -> Self {
+        header::Accept::parse(req)
+            .map(|accept| accept.ranked())
+            .unwrap_or_default()
+            .iter()
+            .find_map(|mime| match mime.essence_str() {
+                "application/json" | "application/*" | "*/*" => Some(Self::Json),
+                Self::CSV => Some(Self::Csv),
+                Self::NDJSON => Some(Self::Ndjson),
+                _ => None,
+            })
+            .unwrap_or(Self::Json)
+    }
+
+    /// Stream the items within the bounds of `paginated`, fetching them page by page.
+    ///
+    /// The total number of items isn't reported, so pages are fetched without counting.
+    pub fn stream<T, F, Fut>(self, fields: Fields, paginated: Paginated, fetch: F) -> HttpResponse
+    where
+        T: Serialize + 'static,
+        F: FnMut(Paginated) -> Fut + 'static,
+        Fut: Future<Output = Result<PaginatedResults<T>, Error>> + 'static,
+    {
+        let content_type = match self {
+            Self::Csv => Self::CSV,
+            Self::Json | Self::Ndjson => Self::NDJSON,
+        };
+
+        let pages = Pages {
+            format: self,
+            fields,
+            fetch,
+            offset: paginated.offset,
+            remaining: (paginated.limit > 0).then_some(paginated.limit),
+            columns: None,
+        };
+
+        HttpResponse::Ok()
+            .content_type(content_type)
+            .streaming(stream::try_unfold(pages, |mut pages| async move {
+                Ok::<_, Error>(pages.next().await?.map(|chunk| (chunk, pages)))
+            }))
+    }
+}
+
+struct Pages<F> {
+    format: ListFormat,
+    fields: Fields,
+    fetch: F,
+    offset: u64,
+    /// The number of items still to return, `None` meaning "unlimited".
+    remaining: Option<u64>,
+    /// The columns of a CSV listing, taken from the first item.
+    columns: Option<Vec<String>>,
+}
+
+impl<F> Pages<F> {
+    /// Fetch and encode the next page, returning `None` once all items got returned.
+    async fn next<T, Fut>(&mut self) -> Result<Option<Bytes>, Error>
+    where
+        T: Serialize,
+        F: FnMut(Paginated) -> Fut,
+        Fut: Future<Output = Result<PaginatedResults<T>, Error>>,
+    {
+        let limit = match self.remaining {
+            Some(0) => return Ok(None),
+            Some(remaining) => remaining.min(PAGE_SIZE),
+            None => PAGE_SIZE,
+        };
+
+        let page = (self.fetch)(Paginated {
+            offset: self.offset,
+            limit,
+            count: Count::None,
+        })
+        .await?;
+
+        let len = page.items.len() as u64;
+        if len == 0 {
+            return Ok(None);
+        }
+
+        self.offset += len;
+        self.remaining = match self.remaining {
+            // a short page is the last one
+            _ if len < limit => Some(0),
+            Some(remaining) => Some(remaining.saturating_sub(len)),
+            None => None,
+        };
+
+        let items = page
+            .items
+            .into_iter()
+            .map(|item| self.fields.select(item))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Some(Bytes::from(match self.format {
+            ListFormat::Csv => self.csv(items)?,
+            ListFormat::Json | ListFormat::Ndjson => ndjson(items)?,
+        })))
+    }
+
+    fn csv(&mut self, items: Vec<Value>) -> Result<Vec<u8>, Error> {
+        let mut wtr = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+
+        for item in items {
+            let Value::Object(mut item) = item else {
+                continue;
+            };
+
+            if self.columns.is_none() {
+                let columns = item.keys().cloned().collect::<Vec<_>>();
+                wtr.write_record(&columns)?;
+                self.columns = Some(columns);
+            }
+
+            let columns = self.columns.as_deref().unwrap_or_default();
+            wtr.write_record(columns.iter().map(|column| {
+                match item.remove(column).unwrap_or_default() {
+                    Value::Null => String::new(),
+                    Value::String(value) => value,
+                    value => value.to_string(),
+                }
+            }))?;
+        }
+
+        wtr.into_inner()
+            .map_err(|err| Error::CsvIntoInnerError(format!("csv into inner error: {}", err)))
+    }
+}
+
+fn ndjson(items: Vec<Value>) -> Result<Vec<u8>, Error> {
+    let mut out = vec![];
+    for item in items {
+        serde_json::to_writer(&mut out, &item).map_err(|err| Error::Any(err.into()))?;
+        out.push(b'\n');
+    }
+    Ok(out)
+}
@@ -1,14 +1,20 @@
 #[cfg(test)]
 mod test;
 
-use crate::organization::{
-    model::{OrganizationDetails, OrganizationSummary},
-    service::OrganizationService,
+use crate::{
+    organization::{
+        model::{OrganizationDetails, OrganizationMerge, OrganizationRequest, OrganizationSummary},
+        service::OrganizationService,
+    },
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, delete, get, http::header, post, put, web};
+use trustify_auth::{
+    CreateMetadata, DeleteMetadata, ReadMetadata, UpdateMetadata, authorizer::Require,
 };
-use actix_web::{HttpResponse, Responder, get, web};
-use trustify_auth::{ReadMetadata, authorizer::Require};
 use trustify_common::{
     db::{Database, query::Query},
+    error::ErrorInformation,
     model::Paginated,
 };
 use uuid::Uuid;
@@ -19,7 +25,11 @@ pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, d
         .app_data(web::Data::new(db))
         .app_data(web::Data::new(service))
         .service(all)
-        .service(get);
+        .service(create)
+        .service(get)
+        .service(update)
+        .service(delete)
+        .service(merge);
 }
 
 #[utoipa::path(
@@ -44,7 +54,7 @@ pub async fn all(
 ) -> actix_web::Result<impl Responder> {
     Ok(HttpResponse::Ok().json(
         state
-            .fetch_organizations(search, paginated, db.as_ref())
+            .fetch_organizations(search, paginated, db.read())
             .await?,
     ))
 }
@@ -57,7 +67,7 @@ pub async fn all(
     ),
     responses(
         (status = 200, description = "Matching organization", body = OrganizationDetails),
-        (status = 404, description = "Matching organization not found"),
+        (status = 404, description = "Matching organization not found", body = ErrorInformation),
     ),
 )]
 #[get("/v2/organization/{id}")]
@@ -67,8 +77,9 @@ pub async fn get(
     db: web::Data<Database>,
     id: web::Path<Uuid>,
     _: Require<ReadMetadata>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
-    let fetched = state.fetch_organization(*id, db.as_ref()).await?;
+    let fetched = state.fetch_organization(*id, &tenant, db.read()).await?;
 
     if let Some(fetched) = fetched {
         Ok(HttpResponse::Ok().json(fetched))
@@ -76,3 +87,116 @@ pub async fn get(
         Ok(HttpResponse::NotFound().finish())
     }
 }
+
+#[utoipa::path(
+    tag = "organization",
+    operation_id = "createOrganization",
+    request_body = OrganizationRequest,
+    responses(
+        (status = 201, description = "Created the organization", body = OrganizationSummary),
+        (status = 409, description = "An organization with the same name already exists", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/organization")]
+/// Create an organization
+pub async fn create(
+    state: web::Data<OrganizationService>,
+    db: web::Data<Database>,
+    web::Json(request): web::Json<OrganizationRequest>,
+    _: Require<CreateMetadata>,
+) -> actix_web::Result<impl Responder> {
+    let result = state.create(request, db.as_ref()).await?;
+    Ok(HttpResponse::Created()
+        .insert_header((
+            header::LOCATION,
+            format!("/api/v2/organization/{}", result.head.id),
+        ))
+        .json(result))
+}
+
+#[utoipa::path(
+    tag = "organization",
+    operation_id = "updateOrganization",
+    request_body = OrganizationRequest,
+    params(
+        ("id", Path, description = "Opaque ID of the organization")
+    ),
+    responses(
+        (status = 200, description = "The updated organization", body = OrganizationSummary),
+        (status = 404, description = "Matching organization not found", body = ErrorInformation),
+        (status = 409, description = "Another organization with the same name already exists", body = ErrorInformation),
+    ),
+)]
+#[put("/v2/organization/{id}")]
+/// Replace the information of an organization
+pub async fn update(
+    state: web::Data<OrganizationService>,
+    db: web::Data<Database>,
+    id: web::Path<Uuid>,
+    web::Json(request): web::Json<OrganizationRequest>,
+    _: Require<UpdateMetadata>,
+) -> actix_web::Result<impl Responder> {
+    Ok(match state.update(*id, request, db.as_ref()).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "organization",
+    operation_id = "deleteOrganization",
+    params(
+        ("id", Path, description = "Opaque ID of the organization")
+    ),
+    responses(
+        (status = 204, description = "The organization was deleted"),
+        (status = 404, description = "Matching organization not found", body = ErrorInformation),
+        (status = 409, description = "Advisories or products still refer to the organization", body = ErrorInformation),
+    ),
+)]
+#[delete("/v2/organization/{id}")]
+/// Delete an organization not referred to by any advisory or product
+pub async fn delete(
+    state: web::Data<OrganizationService>,
+    db: web::Data<Database>,
+    id: web::Path<Uuid>,
+    _: Require<DeleteMetadata>,
+) -> actix_web::Result<impl Responder> {
+    if state.delete(*id, db.as_ref()).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
+
+#[utoipa::path(
+    tag = "organization",
+    operation_id = "mergeOrganizations",
+    request_body = OrganizationMerge,
+    params(
+        ("id", Path, description = "Opaque ID of the organization to merge into")
+    ),
+    responses(
+        (status = 200, description = "The organization, including the advisories of the merged organizations", body = OrganizationDetails),
+        (status = 400, description = "An organization to merge does not exist, or is the target organization", body = ErrorInformation),
+        (status = 404, description = "Matching organization not found", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/organization/{id}/merge")]
+/// Merge organizations into another organization, removing them afterwards
+pub async fn merge(
+    state: web::Data<OrganizationService>,
+    db: web::Data<Database>,
+    id: web::Path<Uuid>,
+    web::Json(request): web::Json<OrganizationMerge>,
+    _: Require<UpdateMetadata>,
+    _: Require<DeleteMetadata>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(
+        match state.merge(*id, request, &tenant, db.as_ref()).await? {
+            Some(result) => HttpResponse::Ok().json(result),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
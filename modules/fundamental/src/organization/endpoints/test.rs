@@ -1,13 +1,14 @@
-use crate::test::caller;
+use crate::{organization::model::OrganizationSummary, test::caller};
+use actix_http::StatusCode;
 use actix_web::cookie::time::OffsetDateTime;
-use actix_web::test::TestRequest;
+use actix_web::test::{TestRequest, read_body_json};
 use jsonpath_rust::JsonPath;
 use serde_json::{Value, json};
 use test_context::test_context;
 use test_log::test;
 use trustify_common::db::query::Query;
 use trustify_common::hashing::Digests;
-use trustify_common::model::Paginated;
+use trustify_common::model::{Paginated, PaginatedResults};
 use trustify_module_ingestor::graph::advisory::AdvisoryInformation;
 use trustify_test_context::{TrustifyContext, call::CallService};
 
@@ -122,3 +123,103 @@ async fn one_organization(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn create_update_and_merge(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    for (identifier, issuer) in [("RH-1", "Red Hat"), ("RH-2", "Red Hat, Inc.")] {
+        ctx.graph
+            .ingest_advisory(
+                identifier,
+                ("source", "http://redhat.com/"),
+                &Digests::digest(identifier),
+                AdvisoryInformation {
+                    id: identifier.to_string(),
+                    title: None,
+                    version: None,
+                    issuer: Some(issuer.to_string()),
+                    published: Some(OffsetDateTime::now_utc()),
+                    modified: None,
+                    withdrawn: None,
+                },
+                &ctx.db,
+            )
+            .await?;
+    }
+
+    // names must be unique
+
+    let request = TestRequest::post()
+        .uri("/api/v2/organization")
+        .set_json(json!({"name": "Red Hat"}))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // create one, and change it
+
+    let request = TestRequest::post()
+        .uri("/api/v2/organization")
+        .set_json(json!({"name": "Capt Pickles"}))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: OrganizationSummary = read_body_json(response).await;
+
+    let request = TestRequest::put()
+        .uri(&format!("/api/v2/organization/{}", created.head.id))
+        .set_json(json!({"name": "Capt Pickles", "website": "http://captpickles.com"}))
+        .to_request();
+    let updated: OrganizationSummary = app.call_and_read_body_json(request).await;
+    assert_eq!(
+        updated.head.website.as_deref(),
+        Some("http://captpickles.com")
+    );
+
+    // merge the misspelled issuer
+
+    let request = TestRequest::get()
+        .uri("/api/v2/organization?q=name~Red%20Hat&sort=name")
+        .to_request();
+    let result: PaginatedResults<OrganizationSummary> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 2);
+    let (target, duplicate) = (result.items[0].head.id, result.items[1].head.id);
+
+    // still in use
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/organization/{duplicate}"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let request = TestRequest::post()
+        .uri(&format!("/api/v2/organization/{target}/merge"))
+        .set_json(json!({"organizations": [duplicate]}))
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response["name"], json!("Red Hat"));
+    assert_eq!(
+        response["advisories"].as_array().map(Vec::len),
+        Some(2),
+        "{response:#?}"
+    );
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/organization/{duplicate}"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // not in use anymore
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/organization/{}", created.head.id))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
-use crate::{Error, advisory::model::AdvisoryHead, organization::model::OrganizationHead};
-use sea_orm::{ConnectionTrait, ModelTrait};
+use crate::{
+    Error, advisory::model::AdvisoryHead, organization::model::OrganizationHead, tenant::Tenant,
+};
+use sea_orm::{ConnectionTrait, ModelTrait, QueryFilter};
 use serde::{Deserialize, Serialize};
 use trustify_entity::{advisory, organization};
 use utoipa::ToSchema;
@@ -16,9 +18,14 @@ pub struct OrganizationDetails {
 impl OrganizationDetails {
     pub async fn from_entity<C: ConnectionTrait>(
         org: &organization::Model,
+        tenant: &Tenant,
         tx: &C,
     ) -> Result<Self, Error> {
-        let advisories = org.find_related(advisory::Entity).all(tx).await?;
+        let advisories = org
+            .find_related(advisory::Entity)
+            .filter(tenant.advisory_condition(advisory::Column::Id))
+            .all(tx)
+            .await?;
         Ok(OrganizationDetails {
             head: OrganizationHead::from_entity(org),
             advisories: AdvisoryHead::from_entities(&advisories, tx).await?,
@@ -38,3 +38,26 @@ impl OrganizationHead {
         }
     }
 }
+
+/// The information of an organization, when creating or replacing it.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct OrganizationRequest {
+    /// The name of the organization, which must be unique.
+    pub name: String,
+
+    /// The `CPE` key of the organization, if known.
+    #[serde(default)]
+    pub cpe_key: Option<String>,
+
+    /// The website of the organization, if known.
+    #[serde(default)]
+    pub website: Option<String>,
+}
+
+/// Organizations to merge into another organization.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct OrganizationMerge {
+    /// The IDs of the organizations being merged. Their advisories and products get moved to
+    /// the target organization, and the organizations get deleted afterwards.
+    pub organizations: Vec<Uuid>,
+}
@@ -1,8 +1,15 @@
 use crate::{
     Error,
-    organization::model::{OrganizationDetails, OrganizationSummary},
+    organization::model::{
+        OrganizationDetails, OrganizationMerge, OrganizationRequest, OrganizationSummary,
+    },
+    tenant::Tenant,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait,
+    QueryFilter, TransactionTrait, sea_query::Expr,
 };
-use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use std::collections::HashSet;
 use trustify_common::{
     db::{
         limiter::LimiterTrait,
@@ -10,7 +17,7 @@ use trustify_common::{
     },
     model::{Paginated, PaginatedResults},
 };
-use trustify_entity::organization;
+use trustify_entity::{advisory, organization, product};
 use uuid::Uuid;
 
 #[derive(Default)]
@@ -27,11 +34,10 @@ impl OrganizationService {
         paginated: Paginated,
         connection: &C,
     ) -> Result<PaginatedResults<OrganizationSummary>, Error> {
-        let limiter = organization::Entity::find().filtering(search)?.limiting(
-            connection,
-            paginated.offset,
-            paginated.limit,
-        );
+        let limiter = organization::Entity::find()
+            .filtering(search)?
+            .limiting(connection, paginated.offset, paginated.limit)
+            .counting(paginated.count);
 
         let total = limiter.total().await?;
 
@@ -40,9 +46,11 @@ impl OrganizationService {
             items: OrganizationSummary::from_entities(&limiter.fetch().await?),
         })
     }
+
     pub async fn fetch_organization<C: ConnectionTrait>(
         &self,
         id: Uuid,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<Option<OrganizationDetails>, Error> {
         if let Some(organization) = organization::Entity::find()
@@ -51,12 +59,171 @@ impl OrganizationService {
             .await?
         {
             Ok(Some(
-                OrganizationDetails::from_entity(&organization, connection).await?,
+                OrganizationDetails::from_entity(&organization, tenant, connection).await?,
             ))
         } else {
             Ok(None)
         }
     }
+
+    /// Create an organization. Fails if an organization with the same name already exists.
+    pub async fn create<C: ConnectionTrait>(
+        &self,
+        request: OrganizationRequest,
+        connection: &C,
+    ) -> Result<OrganizationSummary, Error> {
+        ensure_unique_name(&request.name, None, connection).await?;
+
+        let result = organization::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            name: Set(request.name),
+            cpe_key: Set(request.cpe_key),
+            website: Set(request.website),
+        }
+        .insert(connection)
+        .await?;
+
+        Ok(OrganizationSummary::from_entity(&result))
+    }
+
+    /// Replace the information of an organization. Returns `None` if the organization was not
+    /// found.
+    pub async fn update<C: ConnectionTrait>(
+        &self,
+        id: Uuid,
+        request: OrganizationRequest,
+        connection: &C,
+    ) -> Result<Option<OrganizationSummary>, Error> {
+        if organization::Entity::find_by_id(id)
+            .one(connection)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        ensure_unique_name(&request.name, Some(id), connection).await?;
+
+        let result = organization::ActiveModel {
+            id: Set(id),
+            name: Set(request.name),
+            cpe_key: Set(request.cpe_key),
+            website: Set(request.website),
+        }
+        .update(connection)
+        .await?;
+
+        Ok(Some(OrganizationSummary::from_entity(&result)))
+    }
+
+    /// Delete an organization. Fails if advisories or products still refer to it, those need to
+    /// be merged into another organization instead.
+    pub async fn delete<C: ConnectionTrait>(
+        &self,
+        id: Uuid,
+        connection: &C,
+    ) -> Result<bool, Error> {
+        let advisories = advisory::Entity::find()
+            .filter(advisory::Column::IssuerId.eq(id))
+            .count(connection)
+            .await?;
+        let products = product::Entity::find()
+            .filter(product::Column::VendorId.eq(id))
+            .count(connection)
+            .await?;
+
+        if advisories > 0 || products > 0 {
+            return Err(Error::Conflict(format!(
+                "Organization is still referenced by {advisories} advisories and {products} products"
+            )));
+        }
+
+        let result = organization::Entity::delete_by_id(id)
+            .exec(connection)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Merge organizations into another one, moving their advisories and products to it, and
+    /// deleting them afterwards. Returns `None` if the target organization was not found.
+    pub async fn merge<C: TransactionTrait>(
+        &self,
+        id: Uuid,
+        merge: OrganizationMerge,
+        tenant: &Tenant,
+        db: &C,
+    ) -> Result<Option<OrganizationDetails>, Error> {
+        let organizations = merge
+            .organizations
+            .into_iter()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        if organizations.contains(&id) {
+            return Err(Error::BadRequest(
+                "An organization can't be merged into itself".into(),
+            ));
+        }
+
+        let tx = db.begin().await?;
+
+        let Some(target) = organization::Entity::find_by_id(id).one(&tx).await? else {
+            return Ok(None);
+        };
+
+        let found = organization::Entity::find()
+            .filter(organization::Column::Id.is_in(organizations.clone()))
+            .count(&tx)
+            .await?;
+        if found as usize != organizations.len() {
+            return Err(Error::BadRequest("Unknown organizations to merge".into()));
+        }
+
+        advisory::Entity::update_many()
+            .col_expr(advisory::Column::IssuerId, Expr::value(id))
+            .filter(advisory::Column::IssuerId.is_in(organizations.clone()))
+            .exec(&tx)
+            .await?;
+
+        product::Entity::update_many()
+            .col_expr(product::Column::VendorId, Expr::value(id))
+            .filter(product::Column::VendorId.is_in(organizations.clone()))
+            .exec(&tx)
+            .await?;
+
+        organization::Entity::delete_many()
+            .filter(organization::Column::Id.is_in(organizations))
+            .exec(&tx)
+            .await?;
+
+        let result = OrganizationDetails::from_entity(&target, tenant, &tx).await?;
+
+        tx.commit().await?;
+
+        Ok(Some(result))
+    }
+}
+
+/// Ensure no other organization is using the name, as ingestion looks up issuers by name.
+async fn ensure_unique_name<C: ConnectionTrait>(
+    name: &str,
+    id: Option<Uuid>,
+    connection: &C,
+) -> Result<(), Error> {
+    let mut existing = organization::Entity::find().filter(organization::Column::Name.eq(name));
+    if let Some(id) = id {
+        existing = existing.filter(organization::Column::Id.ne(id));
+    }
+
+    if existing.one(connection).await?.is_some() {
+        return Err(Error::Conflict(format!(
+            "An organization named '{name}' already exists"
+        )));
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    policy::{
+        model::{NewPolicy, Policy, PolicyEvaluation, PolicyEvaluationRequest},
+        service::PolicyService,
+    },
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, delete, get, http::header, post, put, web};
+use trustify_auth::{
+    CreatePolicy, DeletePolicy, ReadAdvisory, ReadPolicy, ReadSbom, UpdatePolicy, all,
+    authorizer::Require,
+};
+use trustify_common::{
+    db::{Database, query::Query},
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults},
+};
+use uuid::Uuid;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = PolicyService::new(db);
+
+    config
+        .app_data(web::Data::new(service))
+        .service(list)
+        .service(create)
+        // must be registered before the lookup by ID
+        .service(evaluate)
+        .service(get)
+        .service(update)
+        .service(delete);
+}
+
+#[utoipa::path(
+    tag = "policy",
+    operation_id = "listPolicies",
+    params(
+        Query,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Matching policies, ordered by name", body = PaginatedResults<Policy>),
+    ),
+)]
+#[get("/v2/policy")]
+/// List policies
+pub async fn list(
+    service: web::Data<PolicyService>,
+    web::Query(search): web::Query<Query>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadPolicy>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(service.list(search, paginated, &tenant).await?))
+}
+
+#[utoipa::path(
+    tag = "policy",
+    operation_id = "createPolicy",
+    request_body = NewPolicy,
+    responses(
+        (status = 201, description = "Created the policy", body = Policy),
+        (status = 409, description = "A policy with the same name already exists", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/policy")]
+/// Create a policy
+pub async fn create(
+    service: web::Data<PolicyService>,
+    web::Json(request): web::Json<NewPolicy>,
+    _: Require<CreatePolicy>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let result = service.create(request, &tenant).await?;
+    Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("/api/v2/policy/{}", result.id)))
+        .json(result))
+}
+
+all!(EvaluatePolicy -> ReadPolicy, ReadSbom, ReadAdvisory);
+
+#[utoipa::path(
+    tag = "policy",
+    operation_id = "evaluatePolicy",
+    request_body = PolicyEvaluationRequest,
+    responses(
+        (status = 200, description = "The result of the evaluation", body = PolicyEvaluation),
+        (status = 400, description = "Neither or both of an SBOM and a product were provided", body = ErrorInformation),
+        (status = 404, description = "The policy, SBOM, or product could not be found", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/policy/evaluate")]
+/// Evaluate an SBOM, or the SBOMs of a product, against a policy
+pub async fn evaluate(
+    service: web::Data<PolicyService>,
+    web::Json(request): web::Json<PolicyEvaluationRequest>,
+    _: Require<EvaluatePolicy>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(match service.evaluate(request, &tenant).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "policy",
+    operation_id = "getPolicy",
+    params(
+        ("id" = Uuid, Path, description = "ID of the policy"),
+    ),
+    responses(
+        (status = 200, description = "The policy", body = Policy),
+        (status = 404, description = "The policy could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/policy/{id}")]
+/// Retrieve a policy
+pub async fn get(
+    service: web::Data<PolicyService>,
+    id: web::Path<Uuid>,
+    _: Require<ReadPolicy>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(match service.fetch(id.into_inner(), &tenant).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "policy",
+    operation_id = "updatePolicy",
+    request_body = NewPolicy,
+    params(
+        ("id" = Uuid, Path, description = "ID of the policy"),
+    ),
+    responses(
+        (status = 200, description = "The updated policy", body = Policy),
+        (status = 404, description = "The policy could not be found", body = ErrorInformation),
+        (status = 409, description = "A policy with the same name already exists", body = ErrorInformation),
+    ),
+)]
+#[put("/v2/policy/{id}")]
+/// Replace the name, description, and rules of a policy
+pub async fn update(
+    service: web::Data<PolicyService>,
+    id: web::Path<Uuid>,
+    web::Json(request): web::Json<NewPolicy>,
+    _: Require<UpdatePolicy>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(
+        match service.update(id.into_inner(), request, &tenant).await? {
+            Some(result) => HttpResponse::Ok().json(result),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+#[utoipa::path(
+    tag = "policy",
+    operation_id = "deletePolicy",
+    params(
+        ("id" = Uuid, Path, description = "ID of the policy"),
+    ),
+    responses(
+        (status = 204, description = "The policy was deleted"),
+        (status = 404, description = "The policy could not be found", body = ErrorInformation),
+    ),
+)]
+#[delete("/v2/policy/{id}")]
+/// Delete a policy
+pub async fn delete(
+    service: web::Data<PolicyService>,
+    id: web::Path<Uuid>,
+    _: Require<DeletePolicy>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(match service.delete(id.into_inner(), &tenant).await? {
+        true => HttpResponse::NoContent().finish(),
+        false => HttpResponse::NotFound().finish(),
+    })
+}
@@ -0,0 +1,230 @@
+use crate::{
+    policy::model::{Policy, PolicyEvaluation},
+    test::{caller, caller_with, tenant_isolation, tenant_user},
+};
+use actix_http::StatusCode;
+use actix_web::test::{TestRequest, read_body_json};
+use serde_json::json;
+use test_context::test_context;
+use test_log::test;
+use trustify_common::model::PaginatedResults;
+use trustify_module_ingestor::model::IngestResult;
+use trustify_test_context::{
+    TrustifyContext, auth::TestAuthentication, call::CallService, document_bytes,
+};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn crud(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/policy")
+        .set_json(json!({
+            "name": "critical",
+            "rules": [{"min_severity": "critical"}],
+        }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let policy: Policy = read_body_json(response).await;
+    // the status defaults to "affected"
+    assert_eq!(policy.rules[0].statuses, ["affected"]);
+
+    let request = TestRequest::put()
+        .uri(&format!("/api/v2/policy/{}", policy.id))
+        .set_json(json!({
+            "name": "critical",
+            "description": "No critical findings",
+            "rules": [{"min_score": 9.0, "statuses": ["affected", "under_investigation"]}],
+        }))
+        .to_request();
+    let result: Policy = app.call_and_read_body_json(request).await;
+    assert_eq!(result.description.as_deref(), Some("No critical findings"));
+    assert_eq!(result.rules[0].min_score, Some(9.0));
+
+    let request = TestRequest::get().uri("/api/v2/policy").to_request();
+    let result: PaginatedResults<Policy> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].id, policy.id);
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/policy/{}", policy.id))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/policy/{}", policy.id))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn evaluate(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let sbom = ctx
+        .ingest_documents([
+            "quarkus-bom-2.13.8.Final-redhat-00004.json",
+            "csaf/cve-2023-0044.json",
+        ])
+        .await?[0]
+        .id
+        .to_string();
+    let app = caller(ctx).await?;
+
+    let mut policies = vec![];
+    for (name, rule) in [
+        ("critical", json!({"min_severity": "critical"})),
+        ("medium", json!({"min_score": 5.0})),
+    ] {
+        let request = TestRequest::post()
+            .uri("/api/v2/policy")
+            .set_json(json!({"name": name, "rules": [rule]}))
+            .to_request();
+        let policy: Policy = app.call_and_read_body_json(request).await;
+        policies.push(policy.id);
+    }
+
+    // CVE-2023-0044 is of medium severity, not violating the first policy
+
+    let request = TestRequest::post()
+        .uri("/api/v2/policy/evaluate")
+        .set_json(json!({"policy": policies[0], "sbom": sbom}))
+        .to_request();
+    let result: PolicyEvaluation = app.call_and_read_body_json(request).await;
+    assert!(result.passed);
+    assert!(result.violations.is_empty());
+
+    // but the second one
+
+    let request = TestRequest::post()
+        .uri("/api/v2/policy/evaluate")
+        .set_json(json!({"policy": policies[1], "sbom": sbom}))
+        .to_request();
+    let result: PolicyEvaluation = app.call_and_read_body_json(request).await;
+    assert!(!result.passed);
+    assert!(!result.violations.is_empty());
+    for violation in &result.violations {
+        assert_eq!(violation.vulnerability, "CVE-2023-0044");
+        assert_eq!(violation.status, "affected");
+        assert_eq!(violation.rule, 0);
+        assert!(!violation.packages.is_empty());
+    }
+
+    // exactly one of SBOM or product is required
+
+    let request = TestRequest::post()
+        .uri("/api/v2/policy/evaluate")
+        .set_json(json!({"policy": policies[1]}))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // unknown SBOMs can't be evaluated
+
+    let request = TestRequest::post()
+        .uri("/api/v2/policy/evaluate")
+        .set_json(json!({
+            "policy": policies[1],
+            "sbom": "urn:uuid:00000000-0000-0000-0000-000000000000",
+        }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn other_tenants(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_with(ctx, tenant_isolation()).await?;
+
+    // tenant a uploads an SBOM and creates a policy
+
+    let request = TestRequest::post()
+        .uri("/api/v2/sbom")
+        .set_payload(document_bytes("quarkus-bom-2.13.8.Final-redhat-00004.json").await?)
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let sbom: IngestResult = read_body_json(response).await;
+
+    let policy = json!({
+        "name": "critical",
+        "rules": [{"min_severity": "critical"}],
+    });
+    let request = TestRequest::post()
+        .uri("/api/v2/policy")
+        .set_json(&policy)
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let policy_a: Policy = read_body_json(response).await;
+
+    // the name is unique per tenant
+
+    let request = TestRequest::post()
+        .uri("/api/v2/policy")
+        .set_json(&policy)
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let request = TestRequest::post()
+        .uri("/api/v2/policy")
+        .set_json(&policy)
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let policy_b: Policy = read_body_json(response).await;
+
+    // tenant b neither sees nor changes the policy of tenant a
+
+    let request = TestRequest::get()
+        .uri("/api/v2/policy")
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let result: PaginatedResults<Policy> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].id, policy_b.id);
+
+    let uri = format!("/api/v2/policy/{}", policy_a.id);
+    for request in [
+        TestRequest::get().uri(&uri),
+        TestRequest::put().uri(&uri).set_json(&policy),
+        TestRequest::delete().uri(&uri),
+    ] {
+        let request = request.to_request().test_auth_details(tenant_user("b"));
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    // nor evaluates the SBOM of tenant a
+
+    let request = TestRequest::post()
+        .uri("/api/v2/policy/evaluate")
+        .set_json(json!({ "policy": policy_b.id, "sbom": sbom.id.to_string() }))
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = TestRequest::post()
+        .uri("/api/v2/policy/evaluate")
+        .set_json(json!({ "policy": policy_a.id, "sbom": sbom.id.to_string() }))
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use crate::Error;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_common::id::Id;
+use trustify_cvss::cvss3::severity::Severity;
+use trustify_entity::policy;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A policy, failing SBOMs with findings matching any of its rules.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Policy {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub rules: Vec<PolicyRule>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    /// The time of the last change
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified: OffsetDateTime,
+}
+
+impl TryFrom<policy::Model> for Policy {
+    type Error = Error;
+
+    fn try_from(value: policy::Model) -> Result<Self, Self::Error> {
+        let policy::Model {
+            id,
+            name,
+            description,
+            rules,
+            tenant: _,
+            created,
+            modified,
+        } = value;
+
+        Ok(Self {
+            id,
+            name,
+            description,
+            rules: serde_json::from_value(rules)
+                .map_err(|err| Error::Internal(format!("invalid rules of policy {id}: {err}")))?,
+            created,
+            modified,
+        })
+    }
+}
+
+/// The changeable content of a policy.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct NewPolicy {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub rules: Vec<PolicyRule>,
+}
+
+/// A rule matching the findings of an SBOM, like "any vulnerability with a score of at least 9.0
+/// affecting a package".
+///
+/// A finding matches if it matches all the conditions of the rule.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PolicyRule {
+    /// The minimum (average) CVSS score of the vulnerability
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_score: Option<f64>,
+    /// The minimum (average) severity of the vulnerability
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_severity: Option<Severity>,
+    /// The statuses of the finding, like `affected` or `under_investigation`
+    #[serde(default = "default_statuses")]
+    pub statuses: Vec<String>,
+}
+
+fn default_statuses() -> Vec<String> {
+    vec!["affected".into()]
+}
+
+impl PolicyRule {
+    /// Check if a finding matches the rule.
+    pub fn matches(&self, status: &str, score: f64, severity: Severity) -> bool {
+        self.statuses.iter().any(|s| s == status)
+            && self.min_score.is_none_or(|min| score >= min)
+            && self.min_severity.is_none_or(|min| severity >= min)
+    }
+}
+
+/// A request to evaluate either an SBOM, or all SBOMs of a product, against a policy.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PolicyEvaluationRequest {
+    /// The ID of the policy
+    pub policy: Uuid,
+    /// The ID of the SBOM
+    #[serde(default)]
+    pub sbom: Option<Id>,
+    /// The ID of the product, evaluating the SBOMs of all its versions
+    #[serde(default)]
+    pub product: Option<Uuid>,
+}
+
+/// The result of evaluating a policy.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PolicyEvaluation {
+    /// The ID of the policy
+    pub policy: Uuid,
+    /// If none of the rules of the policy matched a finding
+    pub passed: bool,
+    /// The findings violating the policy
+    pub violations: Vec<PolicyViolation>,
+}
+
+/// A finding of an SBOM matching a rule of a policy.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PolicyViolation {
+    /// The ID of the SBOM
+    pub sbom: Uuid,
+    /// The identifier of the advisory stating the finding
+    pub advisory: String,
+    /// The identifier of the vulnerability
+    pub vulnerability: String,
+    pub status: String,
+    pub score: f64,
+    pub severity: Severity,
+    /// The index of the first rule the finding matches
+    pub rule: usize,
+    /// The IDs of the packages of the SBOM in question
+    pub packages: Vec<String>,
+}
@@ -0,0 +1,281 @@
+use crate::{
+    Error,
+    policy::model::{
+        NewPolicy, Policy, PolicyEvaluation, PolicyEvaluationRequest, PolicyRule, PolicyViolation,
+    },
+    sbom::{
+        model::details::SbomDetails,
+        service::{DetailsOptions, SbomService},
+    },
+    tenant::Tenant,
+};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+    SqlErr, TransactionTrait, prelude::Uuid,
+};
+use time::OffsetDateTime;
+use trustify_common::{
+    db::{
+        Database,
+        limiter::LimiterTrait,
+        query::{Filtering, Query},
+    },
+    id::Id,
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{policy, product, product_version};
+
+pub struct PolicyService {
+    db: Database,
+}
+
+impl PolicyService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, request: NewPolicy, tenant: &Tenant) -> Result<Policy, Error> {
+        let NewPolicy {
+            name,
+            description,
+            rules,
+        } = request;
+
+        let now = OffsetDateTime::now_utc();
+        let result = policy::Entity::insert(policy::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            name: Set(name),
+            description: Set(description),
+            rules: Set(rules_value(&rules)?),
+            tenant: Set(tenant.name().map(ToString::to_string)),
+            created: Set(now),
+            modified: Set(now),
+        })
+        .exec_with_returning(&self.db)
+        .await
+        .map_err(conflict)?;
+
+        result.try_into()
+    }
+
+    pub async fn fetch(&self, id: Uuid, tenant: &Tenant) -> Result<Option<Policy>, Error> {
+        policy::Entity::find_by_id(id)
+            .filter(tenant.column_condition(policy::Column::Tenant))
+            .one(&self.db)
+            .await?
+            .map(Policy::try_from)
+            .transpose()
+    }
+
+    /// Replace the content of a policy. Returns `None` if the policy was not found.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        request: NewPolicy,
+        tenant: &Tenant,
+    ) -> Result<Option<Policy>, Error> {
+        let NewPolicy {
+            name,
+            description,
+            rules,
+        } = request;
+
+        let tx = self.db.begin().await?;
+
+        if policy::Entity::find_by_id(id)
+            .filter(tenant.column_condition(policy::Column::Tenant))
+            .lock_exclusive()
+            .one(&tx)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let result = policy::Entity::update(policy::ActiveModel {
+            id: Set(id),
+            name: Set(name),
+            description: Set(description),
+            rules: Set(rules_value(&rules)?),
+            modified: Set(OffsetDateTime::now_utc()),
+            ..Default::default()
+        })
+        .exec(&tx)
+        .await
+        .map_err(conflict)?;
+
+        tx.commit().await?;
+
+        Ok(Some(result.try_into()?))
+    }
+
+    /// Delete a policy, returning `false` if it was not found.
+    pub async fn delete(&self, id: Uuid, tenant: &Tenant) -> Result<bool, Error> {
+        let result = policy::Entity::delete_by_id(id)
+            .filter(tenant.column_condition(policy::Column::Tenant))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// List policies, ordered by their name by default.
+    pub async fn list(
+        &self,
+        query: Query,
+        paginated: Paginated,
+        tenant: &Tenant,
+    ) -> Result<PaginatedResults<Policy>, Error> {
+        let limiter = policy::Entity::find()
+            .filter(tenant.column_condition(policy::Column::Tenant))
+            .filtering(query)?
+            .order_by_asc(policy::Column::Name)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        let total = limiter.total().await?;
+        let items = limiter.fetch().await?;
+
+        Ok(PaginatedResults {
+            items: items
+                .into_iter()
+                .map(Policy::try_from)
+                .collect::<Result<_, _>>()?,
+            total,
+        })
+    }
+
+    /// Evaluate an SBOM, or the SBOMs of all versions of a product, against a policy. Returns
+    /// `None` if the policy, the SBOM, or the product could not be found.
+    pub async fn evaluate(
+        &self,
+        request: PolicyEvaluationRequest,
+        tenant: &Tenant,
+    ) -> Result<Option<PolicyEvaluation>, Error> {
+        let PolicyEvaluationRequest {
+            policy,
+            sbom,
+            product,
+        } = request;
+
+        let tx = self.db.begin().await?;
+
+        let sboms = match (sbom, product) {
+            (Some(sbom), None) => {
+                let sbom = match tenant.require_sbom(&sbom, &tx).await {
+                    Err(Error::NotFound(_)) => return Ok(None),
+                    result => result?,
+                };
+                vec![sbom]
+            }
+            (None, Some(product)) => {
+                if product::Entity::find_by_id(product)
+                    .one(&tx)
+                    .await?
+                    .is_none()
+                {
+                    return Ok(None);
+                }
+
+                product_version::Entity::find()
+                    .filter(product_version::Column::ProductId.eq(product))
+                    .filter(product_version::Column::SbomId.is_not_null())
+                    .filter(tenant.sbom_condition(product_version::Column::SbomId))
+                    .order_by_asc(product_version::Column::Version)
+                    .all(&tx)
+                    .await?
+                    .into_iter()
+                    .filter_map(|version| version.sbom_id.map(Id::Uuid))
+                    .collect()
+            }
+            _ => {
+                return Err(Error::BadRequest(
+                    "Exactly one of 'sbom' or 'product' must be provided".into(),
+                ));
+            }
+        };
+
+        let Some(policy) = self.fetch(policy, tenant).await? else {
+            return Ok(None);
+        };
+
+        let statuses = policy
+            .rules
+            .iter()
+            .flat_map(|rule| rule.statuses.iter().cloned())
+            .collect::<Vec<_>>();
+
+        let service = SbomService::new(self.db.clone());
+
+        let mut violations = vec![];
+        for id in sboms {
+            let Some(details) = service
+                .fetch_sbom_details(
+                    id,
+                    DetailsOptions {
+                        statuses: statuses.clone(),
+                        ..Default::default()
+                    },
+                    tenant,
+                    &tx,
+                )
+                .await?
+            else {
+                return Ok(None);
+            };
+            violations.extend(violations_of(&details, &policy.rules));
+        }
+
+        Ok(Some(PolicyEvaluation {
+            policy: policy.id,
+            passed: violations.is_empty(),
+            violations,
+        }))
+    }
+}
+
+/// Report a policy with the same name as a conflict.
+fn conflict(err: DbErr) -> Error {
+    match err.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => {
+            Error::Conflict("A policy with the same name already exists".into())
+        }
+        _ => err.into(),
+    }
+}
+
+fn rules_value(rules: &[PolicyRule]) -> Result<serde_json::Value, Error> {
+    serde_json::to_value(rules).map_err(|err| Error::Internal(err.to_string()))
+}
+
+/// Collect the findings of an SBOM matching any of the rules.
+fn violations_of(sbom: &SbomDetails, rules: &[PolicyRule]) -> Vec<PolicyViolation> {
+    sbom.advisories
+        .iter()
+        .flat_map(|advisory| {
+            advisory.status.iter().filter_map(|status| {
+                let rule = rules.iter().position(|rule| {
+                    rule.matches(
+                        &status.status,
+                        status.average_score,
+                        status.average_severity,
+                    )
+                })?;
+
+                Some(PolicyViolation {
+                    sbom: sbom.summary.head.id,
+                    advisory: advisory.head.identifier.clone(),
+                    vulnerability: status.vulnerability.identifier.clone(),
+                    status: status.status.clone(),
+                    score: status.average_score,
+                    severity: status.average_severity,
+                    rule,
+                    packages: status
+                        .packages
+                        .iter()
+                        .map(|package| package.id.clone())
+                        .collect(),
+                })
+            })
+        })
+        .collect()
+}
@@ -3,16 +3,24 @@ mod test;
 
 use crate::{
     Error,
+    advisory::model::AdvisoryHead,
     product::{
-        model::{details::ProductDetails, summary::ProductSummary},
+        model::{
+            ProductLifecycleEntry, ProductLifecycleImport, ProductVersionHead,
+            ProductVersionLifecycle,
+            details::{ProductDetails, ProductVersionDetails},
+            summary::ProductSummary,
+        },
         service::ProductService,
     },
+    tenant::Tenant,
 };
-use actix_web::{HttpResponse, Responder, delete, get, web};
+use actix_web::{HttpRequest, HttpResponse, Responder, delete, get, http::header, post, put, web};
 use sea_orm::TransactionTrait;
-use trustify_auth::{DeleteMetadata, ReadMetadata, authorizer::Require};
+use trustify_auth::{DeleteMetadata, ReadMetadata, UpdateMetadata, authorizer::Require};
 use trustify_common::{
     db::{Database, query::Query},
+    error::ErrorInformation,
     model::{Paginated, PaginatedResults},
 };
 use uuid::Uuid;
@@ -23,8 +31,13 @@ pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, d
         .app_data(web::Data::new(db))
         .app_data(web::Data::new(service))
         .service(all)
+        .service(import_lifecycle)
         .service(delete)
-        .service(get);
+        .service(get)
+        .service(advisories)
+        .service(set_lifecycle)
+        .service(attach_sbom)
+        .service(detach_sbom);
 }
 
 #[utoipa::path(
@@ -45,8 +58,13 @@ pub async fn all(
     web::Query(search): web::Query<Query>,
     web::Query(paginated): web::Query<Paginated>,
     _: Require<ReadMetadata>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
-    Ok(HttpResponse::Ok().json(state.fetch_products(search, paginated, db.as_ref()).await?))
+    Ok(HttpResponse::Ok().json(
+        state
+            .fetch_products(search, paginated, &tenant, db.read())
+            .await?,
+    ))
 }
 
 #[utoipa::path(
@@ -57,7 +75,7 @@ pub async fn all(
     ),
     responses(
         (status = 200, description = "Matching product", body = ProductDetails),
-        (status = 404, description = "Matching product not found"),
+        (status = 404, description = "Matching product not found", body = ErrorInformation),
     ),
 )]
 #[get("/v2/product/{id}")]
@@ -66,8 +84,9 @@ pub async fn get(
     db: web::Data<Database>,
     id: web::Path<Uuid>,
     _: Require<ReadMetadata>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
-    let fetched = state.fetch_product(*id, db.as_ref()).await?;
+    let fetched = state.fetch_product(*id, &tenant, db.read()).await?;
     if let Some(fetched) = fetched {
         Ok(HttpResponse::Ok().json(fetched))
     } else {
@@ -75,6 +94,39 @@ pub async fn get(
     }
 }
 
+#[utoipa::path(
+    tag = "product",
+    operation_id = "listProductAdvisories",
+    params(
+        ("id", Path, description = "Opaque ID of the product"),
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Advisories referring to the product or any of its streams and variants", body = PaginatedResults<AdvisoryHead>),
+        (status = 404, description = "Matching product not found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/product/{id}/advisory")]
+/// List the advisories of a product, rolled up from its streams and variants
+pub async fn advisories(
+    state: web::Data<ProductService>,
+    db: web::Data<Database>,
+    id: web::Path<Uuid>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadMetadata>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(
+        match state
+            .fetch_advisories(*id, paginated, &tenant, db.read())
+            .await?
+        {
+            Some(result) => HttpResponse::Ok().json(result),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
 #[utoipa::path(
     tag = "product",
     operation_id = "deleteProduct",
@@ -83,7 +135,7 @@ pub async fn get(
     ),
     responses(
         (status = 200, description = "Matching product", body = ProductDetails),
-        (status = 404, description = "Matching product not found"),
+        (status = 404, description = "Matching product not found", body = ErrorInformation),
     ),
 )]
 #[delete("/v2/product/{id}")]
@@ -92,10 +144,11 @@ pub async fn delete(
     db: web::Data<Database>,
     id: web::Path<Uuid>,
     _: Require<DeleteMetadata>,
+    tenant: Tenant,
 ) -> Result<impl Responder, Error> {
     let tx = db.begin().await?;
 
-    match state.fetch_product(*id, &tx).await? {
+    match state.fetch_product(*id, &tenant, &tx).await? {
         Some(v) => {
             let rows_affected = state.delete_product(v.head.id, &tx).await?;
             match rows_affected {
@@ -110,3 +163,154 @@ pub async fn delete(
         None => Ok(HttpResponse::NotFound().finish()),
     }
 }
+
+#[utoipa::path(
+    tag = "product",
+    operation_id = "setProductVersionLifecycle",
+    request_body = ProductVersionLifecycle,
+    params(
+        ("id", Path, description = "Opaque ID of the product"),
+        ("version", Path, description = "Version of the product"),
+    ),
+    responses(
+        (status = 200, description = "The updated product version", body = ProductVersionHead),
+        (status = 404, description = "Matching product version not found", body = ErrorInformation),
+    ),
+)]
+#[put("/v2/product/{id}/version/{version}/lifecycle")]
+/// Replace the support lifecycle of a product version
+pub async fn set_lifecycle(
+    state: web::Data<ProductService>,
+    db: web::Data<Database>,
+    path: web::Path<(Uuid, String)>,
+    web::Json(lifecycle): web::Json<ProductVersionLifecycle>,
+    _: Require<UpdateMetadata>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (id, version) = path.into_inner();
+    Ok(
+        match state
+            .set_lifecycle(id, &version, lifecycle, &tenant, db.as_ref())
+            .await?
+        {
+            Some(result) => HttpResponse::Ok().json(result),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+#[utoipa::path(
+    tag = "product",
+    operation_id = "importProductLifecycle",
+    request_body(
+        content(
+            (Vec<ProductLifecycleEntry> = "application/json"),
+            (String = "text/csv"),
+        ),
+        description = "The lifecycle entries, as a JSON array or as CSV with a header row using the field names"
+    ),
+    responses(
+        (status = 200, description = "The outcome of the import", body = ProductLifecycleImport),
+        (status = 400, description = "The feed could not be parsed", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/product/lifecycle")]
+/// Import the support lifecycle of product versions from a feed
+pub async fn import_lifecycle(
+    state: web::Data<ProductService>,
+    db: web::Data<Database>,
+    req: HttpRequest,
+    body: web::Bytes,
+    _: Require<UpdateMetadata>,
+    tenant: Tenant,
+) -> Result<impl Responder, Error> {
+    let csv = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/csv"));
+
+    let entries = if csv {
+        csv::Reader::from_reader(body.as_ref())
+            .deserialize()
+            .collect::<Result<Vec<ProductLifecycleEntry>, _>>()
+            .map_err(|err| Error::BadRequest(format!("Invalid lifecycle feed: {err}")))?
+    } else {
+        serde_json::from_slice::<Vec<ProductLifecycleEntry>>(&body)
+            .map_err(|err| Error::BadRequest(format!("Invalid lifecycle feed: {err}")))?
+    };
+
+    Ok(HttpResponse::Ok().json(
+        state
+            .import_lifecycle(entries, &tenant, db.as_ref())
+            .await?,
+    ))
+}
+
+#[utoipa::path(
+    tag = "product",
+    operation_id = "attachProductVersionSbom",
+    params(
+        ("id", Path, description = "Opaque ID of the product"),
+        ("version", Path, description = "Version of the product, created if it doesn't exist yet"),
+        ("sbom_id", Path, description = "ID of the SBOM to attach"),
+    ),
+    responses(
+        (status = 200, description = "The product version, including the attached SBOM", body = ProductVersionDetails),
+        (status = 400, description = "The SBOM does not exist", body = ErrorInformation),
+        (status = 404, description = "Matching product not found", body = ErrorInformation),
+    ),
+)]
+#[put("/v2/product/{id}/version/{version}/sbom/{sbom_id}")]
+/// Attach an SBOM to a product version, replacing a previously attached SBOM
+pub async fn attach_sbom(
+    state: web::Data<ProductService>,
+    db: web::Data<Database>,
+    path: web::Path<(Uuid, String, Uuid)>,
+    _: Require<UpdateMetadata>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (id, version, sbom_id) = path.into_inner();
+    Ok(
+        match state
+            .attach_sbom(id, &version, sbom_id, &tenant, db.as_ref())
+            .await?
+        {
+            Some(result) => HttpResponse::Ok().json(result),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+#[utoipa::path(
+    tag = "product",
+    operation_id = "detachProductVersionSbom",
+    params(
+        ("id", Path, description = "Opaque ID of the product"),
+        ("version", Path, description = "Version of the product"),
+        ("sbom_id", Path, description = "ID of the SBOM to detach"),
+    ),
+    responses(
+        (status = 204, description = "The SBOM was detached from the product version"),
+        (status = 404, description = "The SBOM is not attached to the product version", body = ErrorInformation),
+    ),
+)]
+#[delete("/v2/product/{id}/version/{version}/sbom/{sbom_id}")]
+/// Detach an SBOM from a product version, keeping the version itself
+pub async fn detach_sbom(
+    state: web::Data<ProductService>,
+    db: web::Data<Database>,
+    path: web::Path<(Uuid, String, Uuid)>,
+    _: Require<UpdateMetadata>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (id, version, sbom_id) = path.into_inner();
+    if state
+        .detach_sbom(id, &version, sbom_id, &tenant, db.as_ref())
+        .await?
+    {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
@@ -142,3 +142,181 @@ async fn delete_product(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn product_version_lifecycle(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    let product = ctx
+        .graph
+        .ingest_product(
+            "Trusted Profile Analyzer",
+            ProductInformation {
+                vendor: Some("Red Hat".to_string()),
+                cpe: None,
+            },
+            &ctx.db,
+        )
+        .await?;
+    product
+        .ingest_product_version("1.0.0".to_string(), None, &ctx.db)
+        .await?;
+
+    // set the lifecycle of an existing version
+
+    let uri = format!(
+        "/api/v2/product/{}/version/1.0.0/lifecycle",
+        product.product.id
+    );
+    let request = TestRequest::put()
+        .uri(&uri)
+        .set_json(json!({
+            "ga_date": "2023-01-01T00:00:00Z",
+            "eol_date": "2024-01-01T00:00:00Z",
+            "support_status": "maintenance",
+        }))
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response["support_status"], json!("maintenance"));
+    assert_eq!(response["eol"], json!(true));
+
+    // unknown versions can't be updated
+
+    let uri = format!(
+        "/api/v2/product/{}/version/9.9.9/lifecycle",
+        product.product.id
+    );
+    let request = TestRequest::put()
+        .uri(&uri)
+        .set_json(json!({}))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // import a feed, creating a new version
+
+    let request = TestRequest::post()
+        .uri("/api/v2/product/lifecycle")
+        .insert_header(("content-type", "text/csv"))
+        .set_payload(
+            "product,version,ga_date,eol_date,support_status\n\
+             Trusted Profile Analyzer,1.0.0,,,end_of_life\n\
+             Trusted Profile Analyzer,2.0.0,2024-01-01T00:00:00Z,,full\n\
+             Unknown,1.0.0,,,full\n",
+        )
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(
+        response,
+        json!({
+            "updated": 1,
+            "created": 1,
+            "unknown_products": ["Unknown"],
+        })
+    );
+
+    let uri = format!("/api/v2/product/{}", product.product.id);
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    let mut eol = response.query("$.versions[*].eol")?;
+    eol.sort_by_key(|v| v.to_string());
+    assert_eq!(eol, [&json!(false), &json!(true)]);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn attach_and_detach_sbom(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    let sbom_id = ctx
+        .ingest_document("spdx/quarkus-bom-3.2.11.Final-redhat-00001.json")
+        .await?
+        .id
+        .try_as_uid()
+        .expect("must be a UUID");
+
+    let product = ctx
+        .graph
+        .ingest_product(
+            "Trusted Profile Analyzer",
+            ProductInformation {
+                vendor: Some("Red Hat".to_string()),
+                cpe: None,
+            },
+            &ctx.db,
+        )
+        .await?;
+
+    let uri = format!(
+        "/api/v2/product/{}/version/1.0.0/sbom/{sbom_id}",
+        product.product.id
+    );
+    let request = TestRequest::put().uri(&uri).to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response["version"], json!("1.0.0"));
+    assert_eq!(response["sbom_id"], json!(format!("urn:uuid:{sbom_id}")));
+
+    let details = format!("/api/v2/product/{}", product.product.id);
+    let request = TestRequest::get().uri(&details).to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(
+        response.query("$.versions[*].sbom_id")?,
+        [&json!(format!("urn:uuid:{sbom_id}"))]
+    );
+
+    let request = TestRequest::delete().uri(&uri).to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::delete().uri(&uri).to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = TestRequest::get().uri(&details).to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response.query("$.versions[*].version")?, [&json!("1.0.0")]);
+    assert!(response.query("$.versions[*].sbom_id")?.is_empty());
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn product_hierarchy(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    let advisory = ctx.ingest_document("csaf/rhsa-2024-2705.json").await?;
+
+    let family = ctx
+        .graph
+        .get_product_by_name("Red Hat build of Quarkus", &ctx.db)
+        .await?
+        .expect("family must be ingested as a product");
+    let product = ctx
+        .graph
+        .get_product_by_name("Red Hat build of Quarkus 3.2.12.Final", &ctx.db)
+        .await?
+        .expect("product must be ingested");
+    assert_eq!(product.product.parent_id, Some(family.product.id));
+
+    let uri = format!("/api/v2/product/{}", family.product.id);
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(
+        response.query("$.children[*].name")?,
+        [&json!("Red Hat build of Quarkus 3.2.12.Final")]
+    );
+
+    // the advisory refers to the product, and is rolled up to its family
+
+    let uri = format!("/api/v2/product/{}/advisory", family.product.id);
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response["total"], json!(1));
+    assert_eq!(response["items"][0]["uuid"], json!(advisory.id.to_string()));
+
+    Ok(())
+}
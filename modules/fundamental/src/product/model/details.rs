@@ -1,9 +1,11 @@
 use crate::Error;
 use crate::organization::model::OrganizationSummary;
 use crate::product::model::{ProductHead, ProductVersionHead};
+use crate::tenant::Tenant;
 use itertools::izip;
-use sea_orm::ModelTrait;
-use sea_orm::{ConnectionTrait, LoaderTrait};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, EntityTrait, LoaderTrait, ModelTrait, QueryFilter, QueryOrder,
+};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use trustify_entity::labels::Labels;
@@ -17,23 +19,45 @@ pub struct ProductDetails {
     pub versions: Vec<ProductVersionDetails>,
     #[schema(required)]
     pub vendor: Option<OrganizationSummary>,
+    /// The streams and variants of the product
+    pub children: Vec<ProductHead>,
 }
 
 impl ProductDetails {
     pub async fn from_entity<C: ConnectionTrait>(
         product: &product::Model,
         org: Option<organization::Model>,
+        tenant: &Tenant,
         tx: &C,
     ) -> Result<Self, Error> {
-        let product_versions = product
+        let mut product_versions = product
             .find_related(product_version::Entity)
             .all(tx)
             .await?;
+        let own = tenant
+            .own_sboms(product_versions.iter().filter_map(|v| v.sbom_id), tx)
+            .await?;
+        for version in &mut product_versions {
+            // hide the SBOMs of other tenants
+            version.sbom_id = version.sbom_id.filter(|id| own.contains(id));
+        }
+        let children = product::Entity::find()
+            .filter(product::Column::ParentId.eq(product.id))
+            .order_by_asc(product::Column::Name)
+            .all(tx)
+            .await?;
         let vendor = org.map(|org| OrganizationSummary::from_entity(&org));
+
+        let mut heads = Vec::new();
+        for child in &children {
+            heads.push(ProductHead::from_entity(child).await?);
+        }
+
         Ok(ProductDetails {
             head: ProductHead::from_entity(product).await?,
             versions: ProductVersionDetails::from_entities(&product_versions, tx).await?,
             vendor,
+            children: heads,
         })
     }
 }
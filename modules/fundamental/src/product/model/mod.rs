@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -6,7 +7,10 @@ pub mod details;
 pub mod summary;
 
 use crate::Error;
-use trustify_entity::{product, product_version};
+use trustify_entity::{
+    product,
+    product_version::{self, SupportStatus},
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct ProductHead {
@@ -14,6 +18,14 @@ pub struct ProductHead {
     #[schema(value_type=String)]
     pub id: Uuid,
     pub name: String,
+    /// The product this one is a stream or variant of
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "trustify_common::uuid::serde::urn"
+    )]
+    #[schema(value_type=String)]
+    pub parent_id: Option<Uuid>,
 }
 
 impl ProductHead {
@@ -21,6 +33,7 @@ impl ProductHead {
         Ok(ProductHead {
             id: product.id,
             name: product.name.clone(),
+            parent_id: product.parent_id,
         })
     }
 }
@@ -38,6 +51,23 @@ pub struct ProductVersionHead {
     )]
     #[schema(value_type=String)]
     pub sbom_id: Option<Uuid>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "time::serde::rfc3339::option"
+    )]
+    pub ga_date: Option<OffsetDateTime>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "time::serde::rfc3339::option"
+    )]
+    pub eol_date: Option<OffsetDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub support_status: Option<SupportStatus>,
+    /// If the version reached its end of life, by its status or its EOL date
+    #[serde(default)]
+    pub eol: bool,
 }
 
 impl ProductVersionHead {
@@ -46,6 +76,10 @@ impl ProductVersionHead {
             id: product_version.id,
             version: product_version.version.clone(),
             sbom_id: product_version.sbom_id,
+            ga_date: product_version.ga_date,
+            eol_date: product_version.eol_date,
+            support_status: product_version.support_status,
+            eol: product_version.is_eol(OffsetDateTime::now_utc()),
         })
     }
 
@@ -61,3 +95,53 @@ impl ProductVersionHead {
         Ok(heads)
     }
 }
+
+/// The support lifecycle of a product version
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, ToSchema)]
+pub struct ProductVersionLifecycle {
+    /// The date the version became generally available
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub ga_date: Option<OffsetDateTime>,
+    /// The date the support of the version ends
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub eol_date: Option<OffsetDateTime>,
+    #[serde(default)]
+    pub support_status: Option<SupportStatus>,
+}
+
+/// An entry of a lifecycle feed, assigning a lifecycle to a version of a product
+///
+/// The product is referenced by its name. Entries for unknown products are skipped, while
+/// unknown versions of a known product get created.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct ProductLifecycleEntry {
+    pub product: String,
+    pub version: String,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub ga_date: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub eol_date: Option<OffsetDateTime>,
+    #[serde(default)]
+    pub support_status: Option<SupportStatus>,
+}
+
+impl ProductLifecycleEntry {
+    pub fn lifecycle(&self) -> ProductVersionLifecycle {
+        ProductVersionLifecycle {
+            ga_date: self.ga_date,
+            eol_date: self.eol_date,
+            support_status: self.support_status,
+        }
+    }
+}
+
+/// The outcome of importing a lifecycle feed
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, ToSchema)]
+pub struct ProductLifecycleImport {
+    /// The number of product versions updated
+    pub updated: u64,
+    /// The number of product versions created
+    pub created: u64,
+    /// Names of products from the feed which could not be found
+    pub unknown_products: Vec<String>,
+}
@@ -1,6 +1,7 @@
 use crate::Error;
 use crate::organization::model::OrganizationSummary;
 use crate::product::model::{ProductHead, ProductVersionHead};
+use crate::tenant::Tenant;
 use itertools::izip;
 use sea_orm::{ConnectionTrait, LoaderTrait};
 use serde::{Deserialize, Serialize};
@@ -32,9 +33,17 @@ impl ProductSummary {
 
     pub async fn from_entities<C: ConnectionTrait>(
         products: &[product::Model],
+        tenant: &Tenant,
         tx: &C,
     ) -> Result<Vec<Self>, Error> {
-        let versions = products.load_many(product_version::Entity, tx).await?;
+        let mut versions = products.load_many(product_version::Entity, tx).await?;
+        let own = tenant
+            .own_sboms(versions.iter().flatten().filter_map(|v| v.sbom_id), tx)
+            .await?;
+        for version in versions.iter_mut().flatten() {
+            // hide the SBOMs of other tenants
+            version.sbom_id = version.sbom_id.filter(|id| own.contains(id));
+        }
         let orgs = products.load_one(organization::Entity, tx).await?;
 
         let mut summaries = Vec::new();
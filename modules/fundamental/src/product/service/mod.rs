@@ -1,6 +1,18 @@
-use super::model::summary::ProductSummary;
-use crate::{Error, product::model::details::ProductDetails};
-use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use super::model::{
+    ProductLifecycleEntry, ProductLifecycleImport, ProductVersionHead, ProductVersionLifecycle,
+    summary::ProductSummary,
+};
+use crate::{
+    Error,
+    advisory::model::AdvisoryHead,
+    product::model::details::{ProductDetails, ProductVersionDetails},
+    tenant::Tenant,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
+use sea_query::Expr;
 use trustify_common::{
     db::{
         limiter::LimiterTrait,
@@ -8,7 +20,7 @@ use trustify_common::{
     },
     model::{Paginated, PaginatedResults},
 };
-use trustify_entity::product;
+use trustify_entity::{advisory, product, product_version, sbom};
 use uuid::Uuid;
 
 #[derive(Default)]
@@ -23,25 +35,27 @@ impl ProductService {
         &self,
         search: Query,
         paginated: Paginated,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<PaginatedResults<ProductSummary>, Error> {
-        let limiter = product::Entity::find().filtering(search)?.limiting(
-            connection,
-            paginated.offset,
-            paginated.limit,
-        );
+        let limiter = product::Entity::find()
+            .filtering(search)?
+            .limiting(connection, paginated.offset, paginated.limit)
+            .counting(paginated.count);
 
         let total = limiter.total().await?;
 
         Ok(PaginatedResults {
             total,
-            items: ProductSummary::from_entities(&limiter.fetch().await?, connection).await?,
+            items: ProductSummary::from_entities(&limiter.fetch().await?, tenant, connection)
+                .await?,
         })
     }
 
     pub async fn fetch_product<C: ConnectionTrait + Sync + Send>(
         &self,
         id: Uuid,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<Option<ProductDetails>, Error> {
         if let Some(product) = product::Entity::find()
@@ -51,13 +65,61 @@ impl ProductService {
             .await?
         {
             Ok(Some(
-                ProductDetails::from_entity(&product.0, product.1, connection).await?,
+                ProductDetails::from_entity(&product.0, product.1, tenant, connection).await?,
             ))
         } else {
             Ok(None)
         }
     }
 
+    /// Fetch the advisories referring to a product, or any of its streams and variants.
+    ///
+    /// Returns `None` if the product doesn't exist.
+    pub async fn fetch_advisories<C: ConnectionTrait + Sync + Send>(
+        &self,
+        id: Uuid,
+        paginated: Paginated,
+        tenant: &Tenant,
+        connection: &C,
+    ) -> Result<Option<PaginatedResults<AdvisoryHead>>, Error> {
+        if product::Entity::find_by_id(id)
+            .one(connection)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let limiter = advisory::Entity::find()
+            .filter(advisory::Column::DeletedAt.is_null())
+            .filter(tenant.advisory_condition(advisory::Column::Id))
+            .filter(Expr::cust_with_values(
+                r#""advisory"."id" IN (
+                    WITH RECURSIVE "tree"("id") AS (
+                        SELECT $1::uuid
+                        UNION
+                        SELECT "product"."id" FROM "product"
+                            JOIN "tree" ON "product"."parent_id" = "tree"."id"
+                    )
+                    SELECT "product_status"."advisory_id" FROM "product_status"
+                        JOIN "product_version_range" ON "product_version_range"."id" = "product_status"."product_version_range_id"
+                    WHERE "product_version_range"."product_id" IN (SELECT "id" FROM "tree")
+                )"#,
+                [id],
+            ))
+            .order_by_desc(advisory::Column::Modified)
+            .order_by_asc(advisory::Column::Id)
+            .limiting(connection, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        let total = limiter.total().await?;
+
+        Ok(Some(PaginatedResults {
+            total,
+            items: AdvisoryHead::from_entities(&limiter.fetch().await?, connection).await?,
+        }))
+    }
+
     pub async fn delete_product<C: ConnectionTrait + Sync + Send>(
         &self,
         id: Uuid,
@@ -69,6 +131,176 @@ impl ProductService {
 
         Ok(result.rows_affected)
     }
+
+    /// Attach an SBOM to a version of a product, creating the version if it doesn't exist yet.
+    ///
+    /// Returns `None` if the product doesn't exist. A version has at most one SBOM, attaching
+    /// another one replaces the previous link. SBOMs of other tenants are unknown.
+    pub async fn attach_sbom<C: ConnectionTrait>(
+        &self,
+        id: Uuid,
+        version: &str,
+        sbom_id: Uuid,
+        tenant: &Tenant,
+        connection: &C,
+    ) -> Result<Option<ProductVersionDetails>, Error> {
+        if product::Entity::find_by_id(id)
+            .one(connection)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let Some(sbom) = sbom::Entity::find_by_id(sbom_id)
+            .filter(sbom::Column::DeletedAt.is_null())
+            .filter(tenant.sbom_condition(sbom::Column::SbomId))
+            .one(connection)
+            .await?
+        else {
+            return Err(Error::BadRequest(format!("Unknown SBOM: {sbom_id}")));
+        };
+
+        let found = product_version::Entity::find()
+            .filter(product_version::Column::ProductId.eq(id))
+            .filter(product_version::Column::Version.eq(version))
+            .one(connection)
+            .await?;
+
+        let result = match found {
+            Some(found) => {
+                let mut model: product_version::ActiveModel = found.into();
+                model.sbom_id = Set(Some(sbom_id));
+                model.update(connection).await?
+            }
+            None => {
+                product_version::ActiveModel {
+                    id: Default::default(),
+                    product_id: Set(id),
+                    sbom_id: Set(Some(sbom_id)),
+                    version: Set(version.to_string()),
+                    ga_date: Set(None),
+                    eol_date: Set(None),
+                    support_status: Set(None),
+                }
+                .insert(connection)
+                .await?
+            }
+        };
+
+        Ok(Some(
+            ProductVersionDetails::from_entity(&result, Some(sbom)).await?,
+        ))
+    }
+
+    /// Detach an SBOM from a version of a product, keeping the version itself.
+    ///
+    /// Returns `false` if the SBOM wasn't attached to the version.
+    pub async fn detach_sbom<C: ConnectionTrait>(
+        &self,
+        id: Uuid,
+        version: &str,
+        sbom_id: Uuid,
+        tenant: &Tenant,
+        connection: &C,
+    ) -> Result<bool, Error> {
+        let result = product_version::Entity::update_many()
+            .col_expr(product_version::Column::SbomId, Expr::value(None::<Uuid>))
+            .filter(product_version::Column::ProductId.eq(id))
+            .filter(product_version::Column::Version.eq(version))
+            .filter(product_version::Column::SbomId.eq(sbom_id))
+            .filter(tenant.sbom_condition(product_version::Column::SbomId))
+            .exec(connection)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Set the support lifecycle of a version of a product, replacing the previous one.
+    pub async fn set_lifecycle<C: ConnectionTrait>(
+        &self,
+        id: Uuid,
+        version: &str,
+        lifecycle: ProductVersionLifecycle,
+        tenant: &Tenant,
+        connection: &C,
+    ) -> Result<Option<ProductVersionHead>, Error> {
+        let Some(found) = product_version::Entity::find()
+            .filter(product_version::Column::ProductId.eq(id))
+            .filter(product_version::Column::Version.eq(version))
+            .one(connection)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut model: product_version::ActiveModel = found.into();
+        model.ga_date = Set(lifecycle.ga_date);
+        model.eol_date = Set(lifecycle.eol_date);
+        model.support_status = Set(lifecycle.support_status);
+
+        let mut result = model.update(connection).await?;
+        result.sbom_id = tenant
+            .own_sboms(result.sbom_id, connection)
+            .await?
+            .into_iter()
+            .next();
+
+        Ok(Some(ProductVersionHead::from_entity(&result).await?))
+    }
+
+    /// Import the support lifecycle of product versions from a feed, in a single transaction.
+    pub async fn import_lifecycle<C: TransactionTrait>(
+        &self,
+        entries: Vec<ProductLifecycleEntry>,
+        tenant: &Tenant,
+        db: &C,
+    ) -> Result<ProductLifecycleImport, Error> {
+        let tx = db.begin().await?;
+        let mut result = ProductLifecycleImport::default();
+
+        for entry in entries {
+            let Some(product) = product::Entity::find()
+                .filter(product::Column::Name.eq(&entry.product))
+                .one(&tx)
+                .await?
+            else {
+                if !result.unknown_products.contains(&entry.product) {
+                    result.unknown_products.push(entry.product);
+                }
+                continue;
+            };
+
+            let lifecycle = entry.lifecycle();
+
+            if self
+                .set_lifecycle(product.id, &entry.version, lifecycle.clone(), tenant, &tx)
+                .await?
+                .is_some()
+            {
+                result.updated += 1;
+                continue;
+            }
+
+            product_version::ActiveModel {
+                id: Default::default(),
+                product_id: Set(product.id),
+                sbom_id: Set(None),
+                version: Set(entry.version),
+                ga_date: Set(lifecycle.ga_date),
+                eol_date: Set(lifecycle.eol_date),
+                support_status: Set(lifecycle.support_status),
+            }
+            .insert(&tx)
+            .await?;
+
+            result.created += 1;
+        }
+
+        tx.commit().await?;
+
+        Ok(result)
+    }
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use crate::tenant::Tenant;
 use std::str::FromStr;
 use test_context::test_context;
 use test_log::test;
@@ -41,7 +42,12 @@ async fn all_products(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     let service = crate::product::service::ProductService::new();
 
     let prods = service
-        .fetch_products(Query::default(), Paginated::default(), &ctx.db)
+        .fetch_products(
+            Query::default(),
+            Paginated::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     assert_eq!(1, prods.total);
@@ -132,7 +138,12 @@ async fn delete_product(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     let service = crate::product::service::ProductService::new();
 
     let prods = service
-        .fetch_products(Query::default(), Paginated::default(), &ctx.db)
+        .fetch_products(
+            Query::default(),
+            Paginated::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     assert_eq!(1, prods.total);
@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod test;
+
+use crate::product_status::{
+    model::{ProductStatusSummary, Recomputation, RecomputeRequest},
+    service::ProductStatusService,
+};
+use actix_web::{HttpResponse, Responder, get, http::header, post, web};
+use trustify_auth::{ReadAdvisory, ReadSettings, UpdateSettings, authorizer::Require};
+use trustify_common::{
+    db::Database,
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults},
+};
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = ProductStatusService::new(db);
+
+    config
+        .app_data(web::Data::new(service))
+        .service(all)
+        .service(recompute)
+        .service(get_recomputation);
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize, IntoParams)]
+struct ProductStatusParams {
+    /// Limit the statuses to a vulnerability
+    vulnerability: Option<String>,
+    /// Limit the statuses to a product
+    product: Option<Uuid>,
+}
+
+#[utoipa::path(
+    tag = "productStatus",
+    operation_id = "listProductStatuses",
+    params(
+        ProductStatusParams,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Matching effective product statuses", body = PaginatedResults<ProductStatusSummary>),
+    ),
+)]
+#[get("/v2/product-status")]
+/// List the effective statuses of products regarding vulnerabilities
+pub async fn all(
+    service: web::Data<ProductStatusService>,
+    web::Query(ProductStatusParams {
+        vulnerability,
+        product,
+    }): web::Query<ProductStatusParams>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadAdvisory>,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(service.list(vulnerability, product, paginated).await?))
+}
+
+#[utoipa::path(
+    tag = "admin",
+    operation_id = "recomputeStatuses",
+    request_body = RecomputeRequest,
+    responses(
+        (status = 202, description = "The recomputation was started", body = Recomputation),
+    ),
+)]
+#[post("/v2/admin/status/recompute")]
+/// Recompute the effective product statuses, like after changing the status precedence, in the
+/// background
+pub async fn recompute(
+    service: web::Data<ProductStatusService>,
+    web::Json(request): web::Json<RecomputeRequest>,
+    _: Require<UpdateSettings>,
+) -> actix_web::Result<impl Responder> {
+    let result = service
+        .recompute(request.vulnerability, request.product)
+        .await?;
+    Ok(HttpResponse::Accepted()
+        .insert_header((
+            header::LOCATION,
+            format!("/api/v2/admin/status/recompute/{}", result.id),
+        ))
+        .json(result))
+}
+
+#[utoipa::path(
+    tag = "admin",
+    operation_id = "getStatusRecomputation",
+    params(
+        ("id" = Uuid, Path, description = "The ID of the recomputation"),
+    ),
+    responses(
+        (status = 200, description = "The state of the recomputation", body = Recomputation),
+        (status = 404, description = "The recomputation could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/admin/status/recompute/{id}")]
+/// Get the state of a recomputation of the effective product statuses
+pub async fn get_recomputation(
+    service: web::Data<ProductStatusService>,
+    id: web::Path<Uuid>,
+    _: Require<ReadSettings>,
+) -> actix_web::Result<impl Responder> {
+    Ok(match service.fetch_recomputation(id.into_inner()).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
@@ -0,0 +1,84 @@
+use crate::{
+    product_status::model::{ProductStatusSummary, Recomputation, RecomputationState},
+    test::caller,
+};
+use actix_http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+use std::time::Duration;
+use test_context::test_context;
+use test_log::test;
+use trustify_common::model::PaginatedResults;
+use trustify_module_settings::{model::Settings, service::SettingsService};
+use trustify_test_context::{TrustifyContext, call::CallService};
+
+const PRODUCT: &str = "Red Hat JBoss EAP 7.4 for RHEL 8";
+
+/// The effective status of the product regarding CVE-2023-33201
+async fn status(app: &impl CallService) -> Option<String> {
+    let request = TestRequest::get()
+        .uri("/api/v2/product-status?vulnerability=CVE-2023-33201&limit=100")
+        .to_request();
+    let result: PaginatedResults<ProductStatusSummary> = app.call_and_read_body_json(request).await;
+    result
+        .items
+        .into_iter()
+        .find(|summary| summary.product.name == PRODUCT)
+        .map(|summary| summary.status)
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn recompute(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_document("csaf/cve-2023-33201.json").await?;
+    let app = caller(ctx).await?;
+
+    // computed when ingesting the advisory
+
+    assert_eq!(status(&app).await.as_deref(), Some("fixed"));
+
+    // changing the precedence leaves the cached statuses untouched
+
+    SettingsService::new(ctx.db.clone())
+        .set(
+            Settings {
+                status_precedence: Some(vec!["not_affected".into()]),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .await?;
+    assert_eq!(status(&app).await.as_deref(), Some("fixed"));
+
+    // recomputing the statuses of the vulnerability
+
+    let request = TestRequest::post()
+        .uri("/api/v2/admin/status/recompute")
+        .set_json(json!({ "vulnerability": "CVE-2023-33201" }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let mut result: Recomputation = actix_web::test::read_body_json(response).await;
+    assert_eq!(result.total, 1);
+
+    for _ in 0..100 {
+        if result.state != RecomputationState::Running {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let request = TestRequest::get()
+            .uri(&format!("/api/v2/admin/status/recompute/{}", result.id))
+            .to_request();
+        result = app.call_and_read_body_json(request).await;
+    }
+
+    assert_eq!(result.state, RecomputationState::Succeeded);
+    assert_eq!(result.processed, 1);
+    assert!(result.finished.is_some());
+    assert_eq!(result.error, None);
+
+    assert_eq!(status(&app).await.as_deref(), Some("not_affected"));
+
+    Ok(())
+}
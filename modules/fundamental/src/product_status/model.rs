@@ -0,0 +1,114 @@
+use crate::product::model::ProductHead;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::{product, product_status_summary, status_recomputation};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The effective status of a product regarding a vulnerability
+///
+/// If several advisories make a statement about the product, the status with the highest
+/// precedence wins.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProductStatusSummary {
+    /// The ID of the vulnerability
+    pub vulnerability: String,
+    pub product: ProductHead,
+    /// The effective status
+    pub status: String,
+    /// The number of advisories stating a status of the product
+    pub advisories: u64,
+    /// The time the status got computed
+    #[serde(with = "time::serde::rfc3339")]
+    pub computed: OffsetDateTime,
+}
+
+impl ProductStatusSummary {
+    pub fn from_entity(summary: product_status_summary::Model, product: &product::Model) -> Self {
+        Self {
+            vulnerability: summary.vulnerability_id,
+            product: ProductHead {
+                id: product.id,
+                name: product.name.clone(),
+            },
+            status: summary.status,
+            advisories: summary.advisories as u64,
+            computed: summary.computed,
+        }
+    }
+}
+
+/// A request for recomputing the effective product statuses.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RecomputeRequest {
+    /// Limit the recomputation to a vulnerability
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vulnerability: Option<String>,
+    /// Limit the recomputation to a product
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product: Option<Uuid>,
+}
+
+/// The state of recomputing the effective product statuses, running in the background.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Recomputation {
+    /// The ID of the recomputation
+    pub id: Uuid,
+    /// The current state of the recomputation
+    pub state: RecomputationState,
+    /// The vulnerability the recomputation is limited to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vulnerability: Option<String>,
+    /// The product the recomputation is limited to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product: Option<Uuid>,
+    /// The number of vulnerabilities to process
+    pub total: u64,
+    /// The number of vulnerabilities already processed
+    pub processed: u64,
+    /// The time the recomputation started
+    #[serde(with = "time::serde::rfc3339")]
+    pub started: OffsetDateTime,
+    /// The time the recomputation finished
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub finished: Option<OffsetDateTime>,
+    /// The error of the recomputation, if it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The state of a recomputation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RecomputationState {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl From<status_recomputation::State> for RecomputationState {
+    fn from(value: status_recomputation::State) -> Self {
+        match value {
+            status_recomputation::State::Running => Self::Running,
+            status_recomputation::State::Succeeded => Self::Succeeded,
+            status_recomputation::State::Failed => Self::Failed,
+        }
+    }
+}
+
+impl From<status_recomputation::Model> for Recomputation {
+    fn from(value: status_recomputation::Model) -> Self {
+        Self {
+            id: value.id,
+            state: value.state.into(),
+            vulnerability: value.vulnerability_id,
+            product: value.product_id,
+            total: value.total as u64,
+            processed: value.processed as u64,
+            started: value.started,
+            finished: value.finished,
+            error: value.error,
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{
+    Error,
+    product_status::model::{ProductStatusSummary, Recomputation},
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DbBackend, EntityTrait, FromQueryResult,
+    QueryFilter, QueryOrder, Statement, TransactionTrait,
+};
+use sea_query::Expr;
+use time::OffsetDateTime;
+use tracing::instrument;
+use trustify_common::{
+    db::{Database, RecomputeProductStatusSummary, limiter::LimiterTrait},
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{product, product_status_summary, status_recomputation};
+use uuid::Uuid;
+
+/// The number of vulnerabilities recomputed in a single transaction.
+const BATCH_SIZE: usize = 100;
+
+/// Serves the effective product statuses, and recomputes them in the background.
+///
+/// The statuses get recomputed when ingesting, deprecating, or deleting advisories. Changing the
+/// status precedence requires recomputing them explicitly.
+#[derive(Clone)]
+pub struct ProductStatusService {
+    db: Database,
+}
+
+impl ProductStatusService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// List the effective statuses, optionally limited to a vulnerability and a product.
+    pub async fn list(
+        &self,
+        vulnerability: Option<String>,
+        product: Option<Uuid>,
+        paginated: Paginated,
+    ) -> Result<PaginatedResults<ProductStatusSummary>, Error> {
+        let mut select = product_status_summary::Entity::find();
+        if let Some(vulnerability) = vulnerability {
+            select =
+                select.filter(product_status_summary::Column::VulnerabilityId.eq(vulnerability));
+        }
+        if let Some(product) = product {
+            select = select.filter(product_status_summary::Column::ProductId.eq(product));
+        }
+
+        let limiter = select
+            .find_also_related(product::Entity)
+            .order_by_asc(product_status_summary::Column::VulnerabilityId)
+            .order_by_asc(product::Column::Name)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        Ok(PaginatedResults {
+            total: limiter.total().await?,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .filter_map(|(summary, product)| {
+                    // the product is a foreign key, it is always present
+                    Some(ProductStatusSummary::from_entity(summary, &product?))
+                })
+                .collect(),
+        })
+    }
+
+    /// Start recomputing the effective statuses in the background, returning its initial state.
+    ///
+    /// The recomputation is limited to a vulnerability and a product if provided. It processes
+    /// the vulnerabilities in batches, each in its own transaction, reporting its progress.
+    #[instrument(skip(self), err(level=tracing::Level::INFO))]
+    pub async fn recompute(
+        &self,
+        vulnerability: Option<String>,
+        product: Option<Uuid>,
+    ) -> Result<Recomputation, Error> {
+        let vulnerabilities = VulnerabilityRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            include_str!("vulnerabilities.sql"),
+            [vulnerability.clone().into(), product.into()],
+        ))
+        .all(&self.db)
+        .await?
+        .into_iter()
+        .map(|row| row.vulnerability_id)
+        .collect::<Vec<_>>();
+
+        let model = status_recomputation::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            state: Set(status_recomputation::State::Running),
+            vulnerability_id: Set(vulnerability),
+            product_id: Set(product),
+            total: Set(vulnerabilities.len() as i32),
+            processed: Set(0),
+            started: Set(OffsetDateTime::now_utc()),
+            finished: Set(None),
+            error: Set(None),
+        }
+        .insert(&self.db)
+        .await?;
+
+        log::info!(
+            "Starting recomputation {}: {} vulnerabilities",
+            model.id,
+            vulnerabilities.len()
+        );
+
+        let service = self.clone();
+        let id = model.id;
+        tokio::spawn(async move {
+            let result = service.run(id, &vulnerabilities, product).await;
+            if let Err(err) = service.complete(id, result).await {
+                log::warn!("Failed to record the outcome of recomputation {id}: {err}");
+            }
+        });
+
+        Ok(model.into())
+    }
+
+    /// Get the state of a recomputation, `None` if there is none with this ID.
+    pub async fn fetch_recomputation(&self, id: Uuid) -> Result<Option<Recomputation>, Error> {
+        Ok(status_recomputation::Entity::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .map(Recomputation::from))
+    }
+
+    /// Recompute the statuses of the vulnerabilities, recording the progress after each batch.
+    #[instrument(skip(self, vulnerabilities), err)]
+    async fn run(
+        &self,
+        id: Uuid,
+        vulnerabilities: &[String],
+        product: Option<Uuid>,
+    ) -> Result<(), Error> {
+        for batch in vulnerabilities.chunks(BATCH_SIZE) {
+            let tx = self.db.begin().await?;
+
+            for vulnerability in batch {
+                RecomputeProductStatusSummary::execute(&tx, Some(vulnerability), product).await?;
+            }
+
+            status_recomputation::Entity::update_many()
+                .filter(status_recomputation::Column::Id.eq(id))
+                .col_expr(
+                    status_recomputation::Column::Processed,
+                    Expr::col(status_recomputation::Column::Processed).add(batch.len() as i32),
+                )
+                .exec(&tx)
+                .await?;
+
+            tx.commit().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Record the outcome of a recomputation.
+    async fn complete(&self, id: Uuid, result: Result<(), Error>) -> Result<(), Error> {
+        let (state, error) = match result {
+            Ok(()) => (status_recomputation::State::Succeeded, None),
+            Err(err) => (status_recomputation::State::Failed, Some(err.to_string())),
+        };
+
+        status_recomputation::ActiveModel {
+            id: Set(id),
+            state: Set(state),
+            finished: Set(Some(OffsetDateTime::now_utc())),
+            error: Set(error),
+            ..Default::default()
+        }
+        .update(&self.db)
+        .await?;
+
+        log::info!("Completed recomputation {id}: {state:?}");
+
+        Ok(())
+    }
+}
+
+#[derive(FromQueryResult)]
+struct VulnerabilityRow {
+    vulnerability_id: String,
+}
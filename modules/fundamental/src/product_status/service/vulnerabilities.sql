@@ -0,0 +1,13 @@
+-- the vulnerabilities with statuses in scope of a recomputation, either stated by an advisory or
+-- already cached
+SELECT "product_status"."vulnerability_id"
+FROM "product_status"
+    JOIN "product_version_range" ON "product_version_range"."id" = "product_status"."product_version_range_id"
+WHERE "product_status"."vulnerability_id" = COALESCE($1::text, "product_status"."vulnerability_id")
+  AND "product_version_range"."product_id" = COALESCE($2::uuid, "product_version_range"."product_id")
+UNION
+SELECT "vulnerability_id"
+FROM "product_status_summary"
+WHERE "vulnerability_id" = COALESCE($1::text, "vulnerability_id")
+  AND "product_id" = COALESCE($2::uuid, "product_id")
+ORDER BY 1
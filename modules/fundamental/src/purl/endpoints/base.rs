@@ -4,6 +4,7 @@ use crate::{
         model::{details::base_purl::BasePurlDetails, summary::base_purl::BasePurlSummary},
         service::PurlService,
     },
+    tenant::Tenant,
 };
 use actix_web::{HttpResponse, Responder, get, web};
 use sea_orm::prelude::Uuid;
@@ -33,13 +34,22 @@ pub async fn get_base_purl(
     db: web::Data<Database>,
     key: web::Path<String>,
     _: Require<ReadSbom>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
     if key.starts_with("pkg:") {
         let purl = Purl::from_str(&key).map_err(|e| Error::IdKey(IdError::Purl(e)))?;
-        Ok(HttpResponse::Ok().json(service.base_purl_by_purl(&purl, db.as_ref()).await?))
+        Ok(HttpResponse::Ok().json(
+            service
+                .base_purl_by_purl(&purl, &tenant, db.as_ref())
+                .await?,
+        ))
     } else {
         let uuid = Uuid::from_str(&key).map_err(|e| Error::IdKey(IdError::InvalidUuid(e)))?;
-        Ok(HttpResponse::Ok().json(service.base_purl_by_uuid(&uuid, db.as_ref()).await?))
+        Ok(HttpResponse::Ok().json(
+            service
+                .base_purl_by_uuid(&uuid, &tenant, db.as_ref())
+                .await?,
+        ))
     }
 }
 
@@ -61,6 +71,11 @@ pub async fn all_base_purls(
     db: web::Data<Database>,
     web::Query(search): web::Query<Query>,
     web::Query(paginated): web::Query<Paginated>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
-    Ok(HttpResponse::Ok().json(service.base_purls(search, paginated, db.as_ref()).await?))
+    Ok(HttpResponse::Ok().json(
+        service
+            .base_purls(search, paginated, &tenant, db.as_ref())
+            .await?,
+    ))
 }
@@ -1,10 +1,15 @@
 use crate::{
     Error,
-    endpoints::Deprecation,
+    endpoints::{Deprecation, Fields},
     purl::{
-        model::{details::purl::PurlDetails, summary::purl::PurlSummary},
+        model::{
+            PurlCoordinates,
+            details::{base_purl::BasePurlDetails, purl::PurlDetails},
+            summary::purl::PurlSummary,
+        },
         service::PurlService,
     },
+    tenant::Tenant,
 };
 use actix_web::{HttpResponse, Responder, get, web};
 use sea_orm::prelude::Uuid;
@@ -25,10 +30,39 @@ pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, d
         .app_data(web::Data::new(purl_service))
         .service(base::get_base_purl)
         .service(base::all_base_purls)
+        .service(lookup)
         .service(get)
         .service(all);
 }
 
+#[utoipa::path(
+    operation_id = "lookupPurl",
+    tag = "purl",
+    params(
+        PurlCoordinates,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Base PURLs matching the coordinates, with their versioned and qualified PURLs", body = PaginatedResults<BasePurlDetails>),
+    ),
+)]
+#[get("/v2/purl/lookup")]
+/// Look up pURLs by their type, namespace, name, and version
+pub async fn lookup(
+    service: web::Data<PurlService>,
+    db: web::Data<Database>,
+    web::Query(coordinates): web::Query<PurlCoordinates>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadSbom>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(
+        service
+            .lookup(&coordinates, paginated, &tenant, db.read())
+            .await?,
+    ))
+}
+
 #[utoipa::path(
     operation_id = "getPurl",
     tag = "purl",
@@ -48,13 +82,22 @@ pub async fn get(
     key: web::Path<String>,
     web::Query(Deprecation { deprecated }): web::Query<Deprecation>,
     _: Require<ReadSbom>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
     if key.starts_with("pkg") {
         let purl = Purl::from_str(&key).map_err(Error::Purl)?;
-        Ok(HttpResponse::Ok().json(service.purl_by_purl(&purl, deprecated, db.as_ref()).await?))
+        Ok(HttpResponse::Ok().json(
+            service
+                .purl_by_purl(&purl, deprecated, &tenant, db.read())
+                .await?,
+        ))
     } else {
         let id = Uuid::from_str(&key).map_err(|e| Error::IdKey(IdError::InvalidUuid(e)))?;
-        Ok(HttpResponse::Ok().json(service.purl_by_uuid(&id, deprecated, db.as_ref()).await?))
+        Ok(HttpResponse::Ok().json(
+            service
+                .purl_by_uuid(&id, deprecated, &tenant, db.read())
+                .await?,
+        ))
     }
 }
 
@@ -64,6 +107,7 @@ pub async fn get(
     params(
         Query,
         Paginated,
+        Fields,
     ),
     responses(
         (status = 200, description = "All relevant matching qualified PURLs", body = PaginatedResults<PurlSummary>),
@@ -76,9 +120,11 @@ pub async fn all(
     db: web::Data<Database>,
     web::Query(search): web::Query<Query>,
     web::Query(paginated): web::Query<Paginated>,
+    web::Query(fields): web::Query<Fields>,
     _: Require<ReadSbom>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
-    Ok(HttpResponse::Ok().json(service.purls(search, paginated, db.as_ref()).await?))
+    Ok(fields.respond(service.purls(search, paginated, &tenant, db.read()).await?)?)
 }
 
 #[cfg(test)]
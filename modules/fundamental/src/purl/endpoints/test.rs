@@ -67,6 +67,47 @@ async fn setup(db: &Database, graph: &Graph) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn lookup_by_coordinates(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    setup(&ctx.db, &ctx.graph).await?;
+    let app = caller(ctx).await?;
+
+    let uri = "/api/v2/purl/lookup?type=maven&namespace=org.apache&name=log4j";
+    let request = TestRequest::get().uri(uri).to_request();
+    let log4j: PaginatedResults<BasePurlDetails> = app.call_and_read_body_json(request).await;
+
+    assert_eq!(1, log4j.total);
+    assert_eq!(
+        "pkg:maven/org.apache/log4j",
+        log4j.items[0].head.purl.to_string()
+    );
+    assert_eq!(2, log4j.items[0].versions.len());
+
+    // exact names only, unless fuzzy
+    let uri = "/api/v2/purl/lookup?name=LOG4";
+    let request = TestRequest::get().uri(uri).to_request();
+    let none: PaginatedResults<BasePurlDetails> = app.call_and_read_body_json(request).await;
+    assert_eq!(0, none.total);
+
+    let uri = "/api/v2/purl/lookup?name=LOG4&fuzzy=true&version=1.2.3";
+    let request = TestRequest::get().uri(uri).to_request();
+    let log4j: PaginatedResults<BasePurlDetails> = app.call_and_read_body_json(request).await;
+
+    assert_eq!(1, log4j.total);
+    assert_eq!(1, log4j.items[0].versions.len());
+    assert_eq!("1.2.3", log4j.items[0].versions[0].head.version);
+    assert_eq!(2, log4j.items[0].versions[0].purls.len());
+
+    // unknown versions don't match
+    let uri = "/api/v2/purl/lookup?name=log4j&version=9.9.9";
+    let request = TestRequest::get().uri(uri).to_request();
+    let none: PaginatedResults<BasePurlDetails> = app.call_and_read_body_json(request).await;
+    assert_eq!(0, none.total);
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn base_purls(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
@@ -128,6 +169,37 @@ async fn qualified_packages_filtering(ctx: &TrustifyContext) -> Result<(), anyho
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn ecosystems(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    setup(&ctx.db, &ctx.graph).await?;
+    ctx.graph
+        .ingest_qualified_package(&Purl::from_str("pkg:docker/library/nginx@1.27")?, &ctx.db)
+        .await?;
+    let app = caller(ctx).await?;
+
+    let uri = format!("/api/v2/purl/base?q={}", encode("ecosystem=rpm"));
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response: PaginatedResults<BasePurlSummary> = app.call_and_read_body_json(request).await;
+    assert_eq!(1, response.items.len());
+    assert_eq!("sendmail", response.items[0].head.purl.name);
+    assert_eq!("rpm", response.items[0].ecosystem);
+
+    // docker images belong to the oci ecosystem
+    let uri = format!("/api/v2/purl/base?q={}", encode("ecosystem=oci"));
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response: PaginatedResults<BasePurlSummary> = app.call_and_read_body_json(request).await;
+    assert_eq!(1, response.items.len());
+    assert_eq!("docker", response.items[0].head.purl.ty);
+
+    let uri = format!("/api/v2/purl?q={}", encode("ecosystem=maven"));
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response: PaginatedResults<PurlSummary> = app.call_and_read_body_json(request).await;
+    assert_eq!(3, response.items.len());
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn package_with_status(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
@@ -1,7 +1,8 @@
 use crate::Error;
 use crate::purl::model::BasePurlHead;
 use crate::purl::model::summary::versioned_purl::VersionedPurlSummary;
-use sea_orm::{ConnectionTrait, ModelTrait};
+use crate::tenant::Tenant;
+use sea_orm::{ColumnTrait, ConnectionTrait, ModelTrait, QueryFilter};
 use serde::{Deserialize, Serialize};
 use trustify_entity::{base_purl, versioned_purl};
 use utoipa::ToSchema;
@@ -16,15 +17,21 @@ pub struct BasePurlDetails {
 impl BasePurlDetails {
     pub async fn from_entity<C: ConnectionTrait>(
         package: &base_purl::Model,
+        tenant: &Tenant,
         tx: &C,
     ) -> Result<Self, Error> {
-        let package_versions = package.find_related(versioned_purl::Entity).all(tx).await?;
+        let package_versions = package
+            .find_related(versioned_purl::Entity)
+            .filter(tenant.versioned_purl_condition(versioned_purl::Column::Id))
+            .all(tx)
+            .await?;
 
         Ok(Self {
             head: BasePurlHead::from_entity(package),
             versions: VersionedPurlSummary::from_entities_with_common_package(
                 package,
                 &package_versions,
+                tenant,
                 tx,
             )
             .await?,
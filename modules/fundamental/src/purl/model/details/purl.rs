@@ -3,6 +3,7 @@ use crate::{
     advisory::model::AdvisoryHead,
     purl::model::{BasePurlHead, PurlHead, VersionedPurlHead},
     sbom::model::SbomHead,
+    tenant::Tenant,
     vulnerability::model::VulnerabilityHead,
 };
 use sea_orm::{
@@ -35,6 +36,7 @@ pub struct PurlDetails {
     pub head: PurlHead,
     pub version: VersionedPurlHead,
     pub base: BasePurlHead,
+    /// Advisories for the PURL, ordered by their identifier.
     pub advisories: Vec<PurlAdvisory>,
     pub licenses: Vec<PurlLicenseSummary>,
 }
@@ -45,6 +47,7 @@ impl PurlDetails {
         package_version: Option<versioned_purl::Model>,
         qualified_package: &qualified_purl::Model,
         deprecation: Deprecation,
+        tenant: &Tenant,
         tx: &C,
     ) -> Result<Self, Error> {
         let package_version = if let Some(package_version) = package_version {
@@ -71,6 +74,7 @@ impl PurlDetails {
 
         let purl_statuses = purl_status::Entity::find()
             .filter(purl_status::Column::BasePurlId.eq(package.id))
+            .filter(tenant.advisory_condition(purl_status::Column::AdvisoryId))
             .left_join(version_range::Entity)
             .columns(version_range::Column::iter())
             .left_join(base_purl::Entity)
@@ -92,6 +96,8 @@ impl PurlDetails {
             qualified_package.id,
             &package.name,
             package.namespace.as_deref(),
+            deprecation,
+            tenant,
         )
         .await?;
 
@@ -99,7 +105,8 @@ impl PurlDetails {
             head: PurlHead::from_entity(&package, &package_version, qualified_package),
             version: VersionedPurlHead::from_entity(&package, &package_version),
             base: BasePurlHead::from_entity(&package),
-            advisories: PurlAdvisory::from_entities(purl_statuses, product_statuses, tx).await?,
+            advisories: PurlAdvisory::from_entities(purl_statuses, product_statuses, tenant, tx)
+                .await?,
             licenses: vec![], // Leave it empty for now and wait to add relevant content later.
         })
     }
@@ -110,12 +117,15 @@ async fn get_product_statuses_for_purl<C: ConnectionTrait>(
     qualified_package_id: Uuid,
     purl_name: &str,
     namespace_name: Option<&str>,
+    deprecation: Deprecation,
+    tenant: &Tenant,
 ) -> Result<Vec<ProductStatusCatcher>, Error> {
     // Subquery to get all SBOM IDs for the given purl
     let sbom_ids_query = sbom::Entity::find()
         .join(JoinType::Join, sbom::Relation::Packages.def())
         .join(JoinType::Join, sbom_package::Relation::Purl.def())
         .filter(sbom_package_purl_ref::Column::QualifiedPurlId.eq(qualified_package_id))
+        .filter(tenant.sbom_condition(sbom::Column::SbomId))
         .select_only()
         .column(sbom::Column::SbomId)
         .into_query();
@@ -135,6 +145,8 @@ async fn get_product_statuses_for_purl<C: ConnectionTrait>(
         .join(JoinType::LeftJoin, product::Relation::ProductVersion.def())
         .join(JoinType::Join, product_status::Relation::Status.def())
         .join(JoinType::Join, product_status::Relation::Advisory.def())
+        .filter(deprecation.condition())
+        .filter(tenant.advisory_condition(product_status::Column::AdvisoryId))
         .join(
             JoinType::Join,
             product_status::Relation::Vulnerability.def(),
@@ -171,6 +183,7 @@ async fn get_product_statuses_for_purl<C: ConnectionTrait>(
 pub struct PurlAdvisory {
     #[serde(flatten)]
     pub head: AdvisoryHead,
+    /// Statuses of the advisory, ordered by vulnerability identifier, status, and context.
     pub status: Vec<PurlStatus>,
 }
 
@@ -178,6 +191,7 @@ impl PurlAdvisory {
     pub async fn from_entities<C: ConnectionTrait>(
         purl_statuses: Vec<purl_status::Model>,
         product_statuses: Vec<ProductStatusCatcher>,
+        tenant: &Tenant,
         tx: &C,
     ) -> Result<Vec<Self>, Error> {
         let vulns = purl_statuses.load_one(vulnerability::Entity, tx).await?;
@@ -203,7 +217,7 @@ impl PurlAdvisory {
 
             if let Some(advisory) = advisory {
                 let qualified_package_status =
-                    PurlStatus::from_entity(&vulnerability, status, tx).await?;
+                    PurlStatus::from_entity(&vulnerability, status, tenant, tx).await?;
 
                 if let Some(entry) = results.iter_mut().find(|e| e.head.uuid == advisory.id) {
                     entry.status.push(qualified_package_status)
@@ -228,6 +242,7 @@ impl PurlAdvisory {
                 &product_status.vulnerability,
                 product_status.status.slug,
                 Some(product_status.cpe.to_string()),
+                tenant,
                 tx,
             )
             .await?;
@@ -256,6 +271,19 @@ impl PurlAdvisory {
             }
         }
 
+        // statuses are collected from different queries, so order them once they are complete
+
+        results.sort_by(|a, b| a.head.cmp_identifier(&b.head));
+        for result in &mut results {
+            result.status.sort_by_cached_key(|status| {
+                (
+                    status.vulnerability.identifier.clone(),
+                    status.status.clone(),
+                    status.context.as_ref().map(StatusContext::sort_key),
+                )
+            });
+        }
+
         Ok(results)
     }
 }
@@ -277,14 +305,29 @@ pub enum StatusContext {
     Cpe(String),
 }
 
+impl StatusContext {
+    /// A key for ordering statuses by their context.
+    pub fn sort_key(&self) -> String {
+        match self {
+            Self::Purl(purl) => purl.to_string(),
+            Self::Cpe(cpe) => cpe.clone(),
+        }
+    }
+}
+
 impl PurlStatus {
     pub async fn new<C: ConnectionTrait>(
         vuln: &vulnerability::Model,
         status: String,
         cpe: Option<String>,
+        tenant: &Tenant,
         tx: &C,
     ) -> Result<Self, Error> {
-        let cvss3 = vuln.find_related(cvss3::Entity).all(tx).await?;
+        let cvss3 = vuln
+            .find_related(cvss3::Entity)
+            .filter(tenant.advisory_condition(cvss3::Column::AdvisoryId))
+            .all(tx)
+            .await?;
         let average_score = Score::from_iter(cvss3.iter().map(Cvss3Base::from));
         Ok(Self {
             vulnerability: VulnerabilityHead::from_vulnerability_entity(
@@ -303,6 +346,7 @@ impl PurlStatus {
     pub async fn from_entity<C: ConnectionTrait>(
         vuln: &vulnerability::Model,
         package_status: &purl_status::Model,
+        tenant: &Tenant,
         tx: &C,
     ) -> Result<Self, Error> {
         let status = status::Entity::find_by_id(package_status.status_id)
@@ -317,7 +361,7 @@ impl PurlStatus {
             }
             _ => None,
         };
-        PurlStatus::new(vuln, status, cpe, tx).await
+        PurlStatus::new(vuln, status, cpe, tenant, tx).await
     }
 }
 
@@ -2,6 +2,7 @@ use crate::{
     Error,
     advisory::model::AdvisoryHead,
     purl::model::{BasePurlHead, PurlHead, VersionedPurlHead},
+    tenant::Tenant,
     vulnerability::model::VulnerabilityHead,
 };
 use sea_orm::{
@@ -15,6 +16,7 @@ use trustify_entity::{
     advisory, base_purl, organization, purl_status, qualified_purl, status, version_range,
     versioned_purl, vulnerability,
 };
+use trustify_module_ingestor::common::{Deprecation, DeprecationForExt};
 use utoipa::ToSchema;
 
 #[derive(Serialize, Deserialize, Debug, ToSchema)]
@@ -23,6 +25,7 @@ pub struct VersionedPurlDetails {
     pub head: VersionedPurlHead,
     pub base: BasePurlHead,
     pub purls: Vec<PurlHead>,
+    /// Advisories for the version, ordered by their identifier.
     pub advisories: Vec<VersionedPurlAdvisory>,
 }
 
@@ -30,6 +33,8 @@ impl VersionedPurlDetails {
     pub async fn from_entity<C: ConnectionTrait>(
         package: Option<base_purl::Model>,
         package_version: &versioned_purl::Model,
+        deprecation: Deprecation,
+        tenant: &Tenant,
         tx: &C,
     ) -> Result<Self, Error> {
         let package = if let Some(package) = package {
@@ -44,6 +49,7 @@ impl VersionedPurlDetails {
 
         let qualified_packages = package_version
             .find_related(qualified_purl::Entity)
+            .filter(tenant.qualified_purl_condition(qualified_purl::Column::Id))
             .all(tx)
             .await?;
 
@@ -65,11 +71,13 @@ impl VersionedPurlDetails {
             )
             .left_join(version_range::Entity)
             .filter(purl_status::Column::BasePurlId.eq(package.id))
+            .filter(tenant.advisory_condition(purl_status::Column::AdvisoryId))
             .filter(SimpleExpr::FunctionCall(
                 Func::cust(VersionMatches)
                     .arg(Expr::col(versioned_purl::Column::Version))
                     .arg(Expr::col((version_range::Entity, Asterisk))),
             ))
+            .with_deprecation_related(deprecation)
             .all(tx)
             .await?;
 
@@ -86,6 +94,7 @@ impl VersionedPurlDetails {
 pub struct VersionedPurlAdvisory {
     #[serde(flatten)]
     pub head: AdvisoryHead,
+    /// Statuses of the advisory, ordered by vulnerability identifier and status.
     pub status: Vec<VersionedPurlStatus>,
 }
 
@@ -123,6 +132,16 @@ impl VersionedPurlAdvisory {
             }
         }
 
+        results.sort_by(|a, b| a.head.cmp_identifier(&b.head));
+        for result in &mut results {
+            result.status.sort_by(|a, b| {
+                a.vulnerability
+                    .identifier
+                    .cmp(&b.vulnerability.identifier)
+                    .then_with(|| a.status.cmp(&b.status))
+            });
+        }
+
         Ok(results)
     }
 }
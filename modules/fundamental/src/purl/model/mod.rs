@@ -3,9 +3,10 @@ use sea_orm::prelude::Uuid;
 use serde::{Deserialize, Serialize};
 use trustify_common::purl::Purl;
 use trustify_entity::{base_purl, qualified_purl, versioned_purl};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 pub mod details;
+pub mod remediation;
 pub mod summary;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema, Hash)]
@@ -121,3 +122,20 @@ impl PurlHead {
 pub struct TypeHead {
     pub name: String,
 }
+
+/// The coordinates of a package, looking up PURLs without constructing a full PURL string.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, IntoParams)]
+pub struct PurlCoordinates {
+    /// The type of the PURL, like `maven` or `npm`
+    #[serde(rename = "type")]
+    pub r#type: Option<String>,
+    /// The namespace of the PURL, like `io.quarkus`
+    pub namespace: Option<String>,
+    /// The name of the package
+    pub name: String,
+    /// Only return PURLs of this version
+    pub version: Option<String>,
+    /// Match names containing `name`, ignoring the case, instead of exact ones
+    #[serde(default)]
+    pub fuzzy: bool,
+}
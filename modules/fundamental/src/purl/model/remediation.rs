@@ -0,0 +1,30 @@
+use sea_orm::{FromQueryResult, prelude::Uuid};
+use serde::{Deserialize, Serialize};
+use trustify_common::purl::Purl;
+use trustify_entity::version_scheme::VersionScheme;
+
+/// How to remediate a vulnerability of a package, according to the ingested advisories.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PurlRemediation {
+    /// The package to remediate
+    pub purl: Purl,
+    /// The identifier of the vulnerability
+    pub vulnerability: String,
+    /// Versions stated as fixed, ordered by advisory
+    pub fixed: Vec<FixedVersion>,
+    /// The lowest fixed version above the version of the package, if any
+    pub upgrade: Option<String>,
+}
+
+/// A version of a package stated as fixed by an advisory.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, FromQueryResult)]
+pub struct FixedVersion {
+    /// The ID of the advisory
+    pub advisory_id: Uuid,
+    /// The identifier of the advisory
+    pub advisory_identifier: String,
+    /// The version scheme the version is compared by
+    pub scheme: VersionScheme,
+    /// The fixed version
+    pub version: String,
+}
@@ -8,6 +8,8 @@ use utoipa::ToSchema;
 pub struct BasePurlSummary {
     #[serde(flatten)]
     pub head: BasePurlHead,
+    /// The ecosystem of the package, derived from the type of the purl, like `npm` or `oci`
+    pub ecosystem: String,
 }
 
 impl BasePurlSummary {
@@ -17,6 +19,7 @@ impl BasePurlSummary {
         for entity in entities {
             summaries.push(BasePurlSummary {
                 head: BasePurlHead::from_entity(entity),
+                ecosystem: entity.ecosystem.clone(),
             })
         }
 
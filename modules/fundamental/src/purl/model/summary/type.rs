@@ -1,5 +1,6 @@
 use crate::Error;
 use crate::purl::model::TypeHead;
+use crate::tenant::Tenant;
 use sea_orm::{
     ColumnTrait, ConnectionTrait, DeriveColumn, EntityTrait, EnumIter, QueryFilter, QuerySelect,
 };
@@ -24,6 +25,7 @@ pub struct TypeCounts {
 impl TypeSummary {
     pub async fn from_names<C: ConnectionTrait>(
         names: &Vec<String>,
+        tenant: &Tenant,
         tx: &C,
     ) -> Result<Vec<Self>, Error> {
         #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -36,6 +38,7 @@ impl TypeSummary {
         for name in names {
             let base: Option<i64> = base_purl::Entity::find()
                 .filter(base_purl::Column::Type.eq(name))
+                .filter(tenant.base_purl_condition(base_purl::Column::Id))
                 .select_only()
                 .column_as(base_purl::Column::Id.count(), "count")
                 .into_values::<_, QueryAs>()
@@ -45,6 +48,7 @@ impl TypeSummary {
             let version: Option<i64> = versioned_purl::Entity::find()
                 .left_join(base_purl::Entity)
                 .filter(base_purl::Column::Type.eq(name))
+                .filter(tenant.versioned_purl_condition(versioned_purl::Column::Id))
                 .select_only()
                 .column_as(versioned_purl::Column::Id.count(), "count")
                 .into_values::<_, QueryAs>()
@@ -55,6 +59,7 @@ impl TypeSummary {
                 .left_join(versioned_purl::Entity)
                 .left_join(base_purl::Entity)
                 .filter(base_purl::Column::Type.eq(name))
+                .filter(tenant.qualified_purl_condition(qualified_purl::Column::Id))
                 .select_only()
                 .column_as(versioned_purl::Column::Id.count(), "count")
                 .into_values::<_, QueryAs>()
@@ -1,6 +1,7 @@
 use crate::Error;
 use crate::purl::model::{BasePurlHead, PurlHead, VersionedPurlHead};
-use sea_orm::{ConnectionTrait, LoaderTrait};
+use crate::tenant::Tenant;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, LoaderTrait, QueryFilter};
 use serde::{Deserialize, Serialize};
 use trustify_entity::{base_purl, qualified_purl, versioned_purl};
 use utoipa::ToSchema;
@@ -17,12 +18,17 @@ impl VersionedPurlSummary {
     pub async fn from_entities_with_common_package<C: ConnectionTrait>(
         package: &base_purl::Model,
         package_versions: &Vec<versioned_purl::Model>,
+        tenant: &Tenant,
         tx: &C,
     ) -> Result<Vec<Self>, Error> {
         let mut summaries = Vec::new();
 
         let qualified_packages = package_versions
-            .load_many(qualified_purl::Entity, tx)
+            .load_many(
+                qualified_purl::Entity::find()
+                    .filter(tenant.qualified_purl_condition(qualified_purl::Column::Id)),
+                tx,
+            )
             .await?;
 
         for (package_version, qualified_packages) in
@@ -1,9 +1,16 @@
 WITH
     alive_qualified_purl AS (
-        SELECT t2.id, t2.versioned_purl_id
-        FROM sbom_package_purl_ref AS t1
-                 INNER JOIN qualified_purl AS t2
-                            ON t2.id = t1.qualified_purl_id
+        (
+            SELECT t2.id, t2.versioned_purl_id
+            FROM sbom_package_purl_ref AS t1
+                     INNER JOIN qualified_purl AS t2
+                                ON t2.id = t1.qualified_purl_id
+        ) UNION (
+            SELECT t2.id, t2.versioned_purl_id
+            FROM internal_package AS t1
+                     INNER JOIN qualified_purl AS t2
+                                ON t2.id = t1.qualified_purl_id
+        )
     ),
     alive_versioned_purl AS (
         SELECT t2.id, t2.base_purl_id
@@ -1,20 +1,24 @@
 use crate::{
     Error,
     purl::model::{
+        PurlCoordinates,
         details::{
             base_purl::BasePurlDetails, purl::PurlDetails, versioned_purl::VersionedPurlDetails,
         },
+        remediation::{FixedVersion, PurlRemediation},
         summary::{base_purl::BasePurlSummary, purl::PurlSummary, r#type::TypeSummary},
     },
+    tenant::Tenant,
 };
 use sea_orm::{
-    ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter, QueryOrder,
-    QuerySelect, prelude::Uuid,
+    ColumnTrait, ColumnType, ConnectionTrait, DbBackend, EntityTrait, FromQueryResult, QueryFilter,
+    QueryOrder, QuerySelect, QueryTrait, Statement, prelude::Uuid,
 };
-use sea_query::Order;
+use sea_query::{Expr, Func, Order, SimpleExpr, extension::postgres::PgExpr};
 use tracing::instrument;
 use trustify_common::{
     db::{
+        PurlEcosystem,
         limiter::LimiterTrait,
         query::{Filtering, IntoColumns, Query},
     },
@@ -38,6 +42,7 @@ impl PurlService {
 
     pub async fn purl_types<C: ConnectionTrait>(
         &self,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<Vec<TypeSummary>, Error> {
         #[derive(FromQueryResult)]
@@ -48,6 +53,7 @@ impl PurlService {
         let ecosystems: Vec<_> = base_purl::Entity::find()
             .select_only()
             .column(base_purl::Column::Type)
+            .filter(tenant.base_purl_condition(base_purl::Column::Id))
             .group_by(base_purl::Column::Type)
             .distinct()
             .order_by(base_purl::Column::Type, Order::Asc)
@@ -58,7 +64,7 @@ impl PurlService {
             .map(|e| e.r#type)
             .collect();
 
-        TypeSummary::from_names(&ecosystems, connection).await
+        TypeSummary::from_names(&ecosystems, tenant, connection).await
     }
 
     pub async fn base_purls_by_type<C: ConnectionTrait>(
@@ -66,12 +72,15 @@ impl PurlService {
         r#type: &str,
         query: Query,
         paginated: Paginated,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<PaginatedResults<BasePurlSummary>, Error> {
         let limiter = base_purl::Entity::find()
             .filter(base_purl::Column::Type.eq(r#type))
+            .filter(tenant.base_purl_condition(base_purl::Column::Id))
             .filtering(query)?
-            .limiting(connection, paginated.offset, paginated.limit);
+            .limiting(connection, paginated.offset, paginated.limit)
+            .counting(paginated.count);
 
         let total = limiter.total().await?;
 
@@ -86,11 +95,13 @@ impl PurlService {
         r#type: &str,
         namespace: Option<String>,
         name: &str,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<Option<BasePurlDetails>, Error> {
         let mut query = base_purl::Entity::find()
             .filter(base_purl::Column::Type.eq(r#type))
-            .filter(base_purl::Column::Name.eq(name));
+            .filter(base_purl::Column::Name.eq(name))
+            .filter(tenant.base_purl_condition(base_purl::Column::Id));
 
         if let Some(ns) = namespace {
             query = query.filter(base_purl::Column::Namespace.eq(ns));
@@ -100,7 +111,7 @@ impl PurlService {
 
         if let Some(package) = query.one(connection).await? {
             Ok(Some(
-                BasePurlDetails::from_entity(&package, connection).await?,
+                BasePurlDetails::from_entity(&package, tenant, connection).await?,
             ))
         } else {
             Ok(None)
@@ -113,13 +124,16 @@ impl PurlService {
         namespace: Option<String>,
         name: &str,
         version: &str,
+        deprecation: Deprecation,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<Option<VersionedPurlDetails>, Error> {
         let mut query = versioned_purl::Entity::find()
             .left_join(base_purl::Entity)
             .filter(base_purl::Column::Type.eq(r#type))
             .filter(base_purl::Column::Name.eq(name))
-            .filter(versioned_purl::Column::Version.eq(version));
+            .filter(versioned_purl::Column::Version.eq(version))
+            .filter(tenant.versioned_purl_condition(versioned_purl::Column::Id));
 
         if let Some(ns) = namespace {
             query = query.filter(base_purl::Column::Namespace.eq(ns));
@@ -131,7 +145,14 @@ impl PurlService {
 
         if let Some(package_version) = package_version {
             Ok(Some(
-                VersionedPurlDetails::from_entity(None, &package_version, connection).await?,
+                VersionedPurlDetails::from_entity(
+                    None,
+                    &package_version,
+                    deprecation,
+                    tenant,
+                    connection,
+                )
+                .await?,
             ))
         } else {
             Ok(None)
@@ -141,14 +162,16 @@ impl PurlService {
     pub async fn base_purl_by_uuid<C: ConnectionTrait>(
         &self,
         base_purl_uuid: &Uuid,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<Option<BasePurlDetails>, Error> {
         if let Some(package) = base_purl::Entity::find_by_id(*base_purl_uuid)
+            .filter(tenant.base_purl_condition(base_purl::Column::Id))
             .one(connection)
             .await?
         {
             Ok(Some(
-                BasePurlDetails::from_entity(&package, connection).await?,
+                BasePurlDetails::from_entity(&package, tenant, connection).await?,
             ))
         } else {
             Ok(None)
@@ -158,11 +181,28 @@ impl PurlService {
     pub async fn base_purl_by_purl<C: ConnectionTrait>(
         &self,
         purl: &Purl,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<Option<BasePurlDetails>, Error> {
+        if let Some(base_purl) = self.base_purl_entity(purl, tenant, connection).await? {
+            Ok(Some(
+                BasePurlDetails::from_entity(&base_purl, tenant, connection).await?,
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn base_purl_entity<C: ConnectionTrait>(
+        &self,
+        purl: &Purl,
+        tenant: &Tenant,
+        connection: &C,
+    ) -> Result<Option<base_purl::Model>, Error> {
         let mut query = base_purl::Entity::find()
             .filter(base_purl::Column::Type.eq(&purl.ty))
-            .filter(base_purl::Column::Name.eq(&purl.name));
+            .filter(base_purl::Column::Name.eq(&purl.name))
+            .filter(tenant.base_purl_condition(base_purl::Column::Id));
 
         if let Some(ns) = &purl.namespace {
             query = query.filter(base_purl::Column::Namespace.eq(ns));
@@ -170,26 +210,30 @@ impl PurlService {
             query = query.filter(base_purl::Column::Namespace.is_null());
         }
 
-        if let Some(base_purl) = query.one(connection).await? {
-            Ok(Some(
-                BasePurlDetails::from_entity(&base_purl, connection).await?,
-            ))
-        } else {
-            Ok(None)
-        }
+        Ok(query.one(connection).await?)
     }
 
     pub async fn versioned_purl_by_uuid<C: ConnectionTrait>(
         &self,
         purl_version_uuid: &Uuid,
+        deprecation: Deprecation,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<Option<VersionedPurlDetails>, Error> {
         if let Some(package_version) = versioned_purl::Entity::find_by_id(*purl_version_uuid)
+            .filter(tenant.versioned_purl_condition(versioned_purl::Column::Id))
             .one(connection)
             .await?
         {
             Ok(Some(
-                VersionedPurlDetails::from_entity(None, &package_version, connection).await?,
+                VersionedPurlDetails::from_entity(
+                    None,
+                    &package_version,
+                    deprecation,
+                    tenant,
+                    connection,
+                )
+                .await?,
             ))
         } else {
             Ok(None)
@@ -199,6 +243,8 @@ impl PurlService {
     pub async fn versioned_purl_by_purl<C: ConnectionTrait>(
         &self,
         purl: &Purl,
+        deprecation: Deprecation,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<Option<VersionedPurlDetails>, Error> {
         if let Some(version) = &purl.version {
@@ -206,7 +252,8 @@ impl PurlService {
                 .left_join(base_purl::Entity)
                 .filter(base_purl::Column::Type.eq(&purl.ty))
                 .filter(base_purl::Column::Name.eq(&purl.name))
-                .filter(versioned_purl::Column::Version.eq(version));
+                .filter(versioned_purl::Column::Version.eq(version))
+                .filter(tenant.versioned_purl_condition(versioned_purl::Column::Id));
 
             if let Some(ns) = &purl.namespace {
                 query = query.filter(base_purl::Column::Namespace.eq(ns));
@@ -218,7 +265,14 @@ impl PurlService {
 
             if let Some(package_version) = package_version {
                 Ok(Some(
-                    VersionedPurlDetails::from_entity(None, &package_version, connection).await?,
+                    VersionedPurlDetails::from_entity(
+                        None,
+                        &package_version,
+                        deprecation,
+                        tenant,
+                        connection,
+                    )
+                    .await?,
                 ))
             } else {
                 Ok(None)
@@ -234,16 +288,19 @@ impl PurlService {
         &self,
         purl: &Purl,
         deprecation: Deprecation,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<Option<PurlDetails>, Error> {
         let canonical = CanonicalPurl::from(purl.clone());
         match qualified_purl::Entity::find()
             .filter(qualified_purl::Column::Purl.eq(canonical))
+            .filter(tenant.qualified_purl_condition(qualified_purl::Column::Id))
             .one(connection)
             .await?
         {
             Some(purl) => Ok(Some(
-                PurlDetails::from_entity(None, None, &purl, deprecation, connection).await?,
+                PurlDetails::from_entity(None, None, &purl, deprecation, tenant, connection)
+                    .await?,
             )),
             None => Ok(None),
         }
@@ -254,14 +311,16 @@ impl PurlService {
         &self,
         purl_uuid: &Uuid,
         deprecation: Deprecation,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<Option<PurlDetails>, Error> {
         match qualified_purl::Entity::find_by_id(*purl_uuid)
+            .filter(tenant.qualified_purl_condition(qualified_purl::Column::Id))
             .one(connection)
             .await?
         {
             Some(pkg) => Ok(Some(
-                PurlDetails::from_entity(None, None, &pkg, deprecation, connection).await?,
+                PurlDetails::from_entity(None, None, &pkg, deprecation, tenant, connection).await?,
             )),
             None => Ok(None),
         }
@@ -271,13 +330,14 @@ impl PurlService {
         &self,
         query: Query,
         paginated: Paginated,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<PaginatedResults<BasePurlSummary>, Error> {
-        let limiter = base_purl::Entity::find().filtering(query)?.limiting(
-            connection,
-            paginated.offset,
-            paginated.limit,
-        );
+        let limiter = base_purl::Entity::find()
+            .filter(tenant.base_purl_condition(base_purl::Column::Id))
+            .filtering(query)?
+            .limiting(connection, paginated.offset, paginated.limit)
+            .counting(paginated.count);
 
         let total = limiter.total().await?;
 
@@ -292,17 +352,27 @@ impl PurlService {
         &self,
         query: Query,
         paginated: Paginated,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<PaginatedResults<PurlSummary>, Error> {
         // use sea_orm::{ColumnType, IntoIdentity};
         // use sea_query::{Expr, Func, SimpleExpr};
         let limiter = qualified_purl::Entity::find()
+            .filter(tenant.qualified_purl_condition(qualified_purl::Column::Id))
             .filtering_with(
                 query,
                 qualified_purl::Entity
                     .columns()
                     .json_keys("purl", &["ty", "namespace", "name", "version"])
                     .json_keys("qualifiers", &["arch", "distro", "repository_url"])
+                    .add_expr(
+                        "ecosystem",
+                        SimpleExpr::FunctionCall(
+                            Func::cust(PurlEcosystem)
+                                .arg(Expr::col(qualified_purl::Column::Purl).cast_json_field("ty")),
+                        ),
+                        ColumnType::Text,
+                    )
                     .translator(|f, op, v| match f {
                         "type" => Some(format!("ty{op}{v}")),
                         _ => None,
@@ -316,7 +386,8 @@ impl PurlService {
                 //     ColumnType::Text,
                 // ),
             )?
-            .limiting(connection, paginated.offset, paginated.limit);
+            .limiting(connection, paginated.offset, paginated.limit)
+            .counting(paginated.count);
 
         let total = limiter.total().await?;
 
@@ -326,6 +397,117 @@ impl PurlService {
         })
     }
 
+    /// Look up base PURLs by their coordinates, with their versioned and qualified PURLs.
+    ///
+    /// If a version is requested, only base PURLs having that version are returned, and only with
+    /// that version.
+    #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
+    pub async fn lookup<C: ConnectionTrait>(
+        &self,
+        coordinates: &PurlCoordinates,
+        paginated: Paginated,
+        tenant: &Tenant,
+        connection: &C,
+    ) -> Result<PaginatedResults<BasePurlDetails>, Error> {
+        let mut query =
+            base_purl::Entity::find().filter(tenant.base_purl_condition(base_purl::Column::Id));
+
+        if coordinates.fuzzy {
+            let name = coordinates.name.replace('%', r"\%").replace('_', r"\_");
+            query = query.filter(Expr::col(base_purl::Column::Name).ilike(format!("%{name}%")));
+        } else {
+            query = query.filter(base_purl::Column::Name.eq(&coordinates.name));
+        }
+
+        if let Some(r#type) = &coordinates.r#type {
+            query = query.filter(base_purl::Column::Type.eq(r#type));
+        }
+        if let Some(namespace) = &coordinates.namespace {
+            query = query.filter(base_purl::Column::Namespace.eq(namespace));
+        }
+        if let Some(version) = &coordinates.version {
+            query = query.filter(
+                base_purl::Column::Id.in_subquery(
+                    versioned_purl::Entity::find()
+                        .select_only()
+                        .column(versioned_purl::Column::BasePurlId)
+                        .filter(versioned_purl::Column::Version.eq(version))
+                        .into_query(),
+                ),
+            );
+        }
+
+        let limiter = query
+            .order_by_asc(base_purl::Column::Type)
+            .order_by_asc(base_purl::Column::Namespace)
+            .order_by_asc(base_purl::Column::Name)
+            .order_by_asc(base_purl::Column::Id)
+            .limiting(connection, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        let total = limiter.total().await?;
+
+        let mut items = Vec::new();
+        for base_purl in limiter.fetch().await? {
+            let mut details = BasePurlDetails::from_entity(&base_purl, tenant, connection).await?;
+            if let Some(version) = &coordinates.version {
+                details.versions.retain(|v| &v.head.version == version);
+            }
+            items.push(details);
+        }
+
+        Ok(PaginatedResults { items, total })
+    }
+
+    /// Look up how to remediate a vulnerability of a package.
+    ///
+    /// Returns `None` if the package is unknown. The upgrade target is the lowest version stated
+    /// as fixed which is above the version of the PURL, compared according to the version scheme
+    /// of the advisory.
+    #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
+    pub async fn remediation<C: ConnectionTrait>(
+        &self,
+        purl: &Purl,
+        vulnerability: &str,
+        tenant: &Tenant,
+        connection: &C,
+    ) -> Result<Option<PurlRemediation>, Error> {
+        let Some(base_purl) = self.base_purl_entity(purl, tenant, connection).await? else {
+            return Ok(None);
+        };
+
+        let rows = connection
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                include_str!("remediation.sql"),
+                [
+                    base_purl.id.into(),
+                    vulnerability.into(),
+                    purl.version.clone().into(),
+                    tenant.name().map(ToString::to_string).into(),
+                ],
+            ))
+            .await?;
+
+        let mut fixed = Vec::with_capacity(rows.len());
+        let mut upgrade = None;
+
+        for row in &rows {
+            let version = FixedVersion::from_query_result(row, "")?;
+            if upgrade.is_none() && row.try_get::<bool>("", "upgrade")? {
+                upgrade = Some(version.version.clone());
+            }
+            fixed.push(version);
+        }
+
+        Ok(Some(PurlRemediation {
+            purl: purl.clone(),
+            vulnerability: vulnerability.to_string(),
+            fixed,
+            upgrade,
+        }))
+    }
+
     #[instrument(skip(self, connection), err)]
     pub async fn gc_purls<C: ConnectionTrait>(&self, connection: &C) -> Result<u64, Error> {
         let res = connection
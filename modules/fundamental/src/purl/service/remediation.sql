@@ -0,0 +1,44 @@
+-- versions stated as fixed by current advisories
+WITH fixed AS (
+    SELECT DISTINCT
+        "advisory"."id" AS "advisory_id",
+        "advisory"."identifier" AS "advisory_identifier",
+        "version_range"."version_scheme_id" AS "scheme",
+        "version_range"."low_version" AS "version"
+    FROM "purl_status"
+    JOIN "status" ON "purl_status"."status_id" = "status"."id"
+    JOIN "advisory" ON "purl_status"."advisory_id" = "advisory"."id"
+    JOIN "version_range" ON "purl_status"."version_range_id" = "version_range"."id"
+    WHERE
+        "purl_status"."base_purl_id" = $1
+        AND "purl_status"."vulnerability_id" = $2
+        AND "status"."slug" = 'fixed'
+        AND "version_range"."low_version" IS NOT NULL
+        AND "advisory"."deleted_at" IS NULL
+        AND NOT "advisory"."deprecated"
+        AND ($4::text IS NULL OR "advisory"."labels" ->> 'tenant' = $4)
+),
+
+-- fixed versions above the current version, according to their version scheme
+candidate AS (
+    SELECT DISTINCT "scheme", "version"
+    FROM fixed
+    WHERE
+        $3::text IS NOT NULL
+        AND version_matches("version", ROW(NULL, "scheme", $3, false, NULL, NULL)::version_range)
+)
+
+SELECT
+    fixed.*,
+    (
+        candidate."version" IS NOT NULL
+        AND NOT EXISTS (
+            SELECT 1 FROM candidate AS lower
+            WHERE
+                lower."scheme" = candidate."scheme"
+                AND version_matches(lower."version", ROW(NULL, candidate."scheme", NULL, NULL, candidate."version", false)::version_range)
+        )
+    ) AS "upgrade"
+FROM fixed
+LEFT JOIN candidate ON candidate."scheme" = fixed."scheme" AND candidate."version" = fixed."version"
+ORDER BY fixed."advisory_identifier", fixed."version"
@@ -2,6 +2,7 @@ use crate::{
     advisory::service::AdvisoryService,
     purl::{model::details::purl::StatusContext, service::PurlService},
     sbom::service::SbomService,
+    tenant::Tenant,
 };
 use std::str::FromStr;
 use test_context::test_context;
@@ -58,7 +59,7 @@ async fn types(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     ingest_extra_packages(ctx).await?;
 
-    let types = service.purl_types(&ctx.db).await?;
+    let types = service.purl_types(&Tenant::Any, &ctx.db).await?;
 
     assert_eq!(2, types.len());
 
@@ -116,7 +117,13 @@ async fn packages_for_type(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     ingest_extra_packages(ctx).await?;
 
     let packages = service
-        .base_purls_by_type("maven", Query::default(), Paginated::default(), &ctx.db)
+        .base_purls_by_type(
+            "maven",
+            Query::default(),
+            Paginated::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     assert_eq!(packages.total, 2);
@@ -172,7 +179,13 @@ async fn packages_for_type_with_filtering(ctx: &TrustifyContext) -> Result<(), a
     ingest_extra_packages(ctx).await?;
 
     let packages = service
-        .base_purls_by_type("maven", q("myspace"), Paginated::default(), &ctx.db)
+        .base_purls_by_type(
+            "maven",
+            q("myspace"),
+            Paginated::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     assert_eq!(packages.total, 1);
@@ -256,7 +269,13 @@ async fn package(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .await?;
 
     let results = service
-        .base_purl("maven", Some("org.apache".to_string()), "log4j", &ctx.db)
+        .base_purl(
+            "maven",
+            Some("org.apache".to_string()),
+            "log4j",
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     assert!(results.is_some());
@@ -326,6 +345,8 @@ async fn package_version(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             Some("org.apache".to_string()),
             "log4j",
             "1.2.3",
+            Default::default(),
+            &Tenant::Any,
             &ctx.db,
         )
         .await?;
@@ -411,7 +432,12 @@ async fn package_version_by_uuid(ctx: &TrustifyContext) -> Result<(), anyhow::Er
         .await?;
 
     let result = service
-        .versioned_purl_by_uuid(&log4j_123.package_version.id, &ctx.db)
+        .versioned_purl_by_uuid(
+            &log4j_123.package_version.id,
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     assert!(result.is_some());
@@ -514,25 +540,25 @@ async fn packages(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .await?;
 
     let results = service
-        .base_purls(q("log4j"), Paginated::default(), &ctx.db)
+        .base_purls(q("log4j"), Paginated::default(), &Tenant::Any, &ctx.db)
         .await?;
 
     assert_eq!(1, results.items.len());
 
     let results = service
-        .base_purls(q("quarkus"), Paginated::default(), &ctx.db)
+        .base_purls(q("quarkus"), Paginated::default(), &Tenant::Any, &ctx.db)
         .await?;
 
     assert_eq!(1, results.items.len());
 
     let results = service
-        .base_purls(q("jboss"), Paginated::default(), &ctx.db)
+        .base_purls(q("jboss"), Paginated::default(), &Tenant::Any, &ctx.db)
         .await?;
 
     assert_eq!(1, results.items.len());
 
     let results = service
-        .base_purls(q("maven"), Paginated::default(), &ctx.db)
+        .base_purls(q("maven"), Paginated::default(), &Tenant::Any, &ctx.db)
         .await?;
 
     assert_eq!(2, results.items.len());
@@ -612,7 +638,7 @@ async fn qualified_packages(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
         .await?;
 
     let results = service
-        .purls(q("log4j"), Paginated::default(), &ctx.db)
+        .purls(q("log4j"), Paginated::default(), &Tenant::Any, &ctx.db)
         .await?;
 
     log::debug!("{:#?}", results);
@@ -633,7 +659,12 @@ async fn statuses(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .await?;
 
     let results = service
-        .purls(Query::default(), Paginated::default(), &ctx.db)
+        .purls(
+            Query::default(),
+            Paginated::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     assert_eq!(1, results.items.len());
@@ -641,7 +672,7 @@ async fn statuses(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     let uuid = results.items[0].head.uuid;
 
     let results = service
-        .purl_by_uuid(&uuid, Default::default(), &ctx.db)
+        .purl_by_uuid(&uuid, Default::default(), &Tenant::Any, &ctx.db)
         .await?;
 
     assert_eq!(uuid, results.unwrap().head.uuid);
@@ -657,7 +688,12 @@ async fn contextual_status(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     ctx.ingest_document("csaf/rhsa-2024_3666.json").await?;
 
     let results = service
-        .purls(Query::default(), Paginated::default(), &ctx.db)
+        .purls(
+            Query::default(),
+            Paginated::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     let tomcat_jsp = results
@@ -672,7 +708,7 @@ async fn contextual_status(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     let uuid = tomcat_jsp.head.uuid;
 
     let tomcat_jsp = service
-        .purl_by_uuid(&uuid, Default::default(), &ctx.db)
+        .purl_by_uuid(&uuid, Default::default(), &Tenant::Any, &ctx.db)
         .await?;
 
     assert!(tomcat_jsp.is_some());
@@ -707,7 +743,12 @@ async fn gc_purls(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     assert_eq!(
         0,
         purl_service
-            .purls(Query::default(), Paginated::default(), &ctx.db)
+            .purls(
+                Query::default(),
+                Paginated::default(),
+                &Tenant::Any,
+                &ctx.db
+            )
             .await?
             .items
             .len()
@@ -722,7 +763,12 @@ async fn gc_purls(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     assert_eq!(
         880,
         purl_service
-            .purls(Query::default(), Paginated::default(), &ctx.db)
+            .purls(
+                Query::default(),
+                Paginated::default(),
+                &Tenant::Any,
+                &ctx.db
+            )
             .await?
             .items
             .len()
@@ -735,7 +781,12 @@ async fn gc_purls(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     assert_eq!(
         1490,
         purl_service
-            .purls(Query::default(), Paginated::default(), &ctx.db)
+            .purls(
+                Query::default(),
+                Paginated::default(),
+                &Tenant::Any,
+                &ctx.db
+            )
             .await?
             .items
             .len()
@@ -748,7 +799,7 @@ async fn gc_purls(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     ) -> Result<(), anyhow::Error> {
         let sbom_service = SbomService::new(ctx.db.clone());
         let sbom = sbom_service
-            .fetch_sbom_details(id, vec![], &ctx.db)
+            .fetch_sbom_details(id, Default::default(), &Tenant::Any, &ctx.db)
             .await?
             .expect("fetch_sbom");
         assert_eq!(
@@ -776,7 +827,12 @@ async fn gc_purls(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     // it should leave behind orphaned purls
     let result = purl_service
-        .purls(Query::default(), Paginated::default(), &ctx.db)
+        .purls(
+            Query::default(),
+            Paginated::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
     assert_eq!(1490, result.items.len());
 
@@ -785,7 +841,12 @@ async fn gc_purls(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     assert_eq!(792, deleted_records_count);
 
     let result = purl_service
-        .purls(Query::default(), Paginated::default(), &ctx.db)
+        .purls(
+            Query::default(),
+            Paginated::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     assert_eq!(880, result.items.len());
@@ -795,7 +856,12 @@ async fn gc_purls(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     // it should leave behind orphaned purls
     let result = purl_service
-        .purls(Query::default(), Paginated::default(), &ctx.db)
+        .purls(
+            Query::default(),
+            Paginated::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
     assert_eq!(880, result.items.len());
 
@@ -804,7 +870,12 @@ async fn gc_purls(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     assert_eq!(1759, deleted_records_count);
 
     let result = purl_service
-        .purls(Query::default(), Paginated::default(), &ctx.db)
+        .purls(
+            Query::default(),
+            Paginated::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     assert_eq!(0, result.items.len());
@@ -850,7 +921,12 @@ async fn unqualified_purl_by_purl(ctx: &TrustifyContext) -> Result<(), anyhow::E
     let purl = "pkg:maven/org.apache/log4j@1.2.3";
 
     let results = service
-        .purl_by_purl(&Purl::from_str(purl)?, Default::default(), &ctx.db)
+        .purl_by_purl(
+            &Purl::from_str(purl)?,
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .unwrap();
 
@@ -871,6 +947,7 @@ async fn base_purl_by_purl(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     let results = service
         .base_purl_by_purl(
             &Purl::from_str("pkg:maven/org.apache/log4j@1.2.3")?,
+            &Tenant::Any,
             &ctx.db,
         )
         .await?;
@@ -890,6 +967,8 @@ async fn versioned_base_purl_by_purl(ctx: &TrustifyContext) -> Result<(), anyhow
     let results = service
         .versioned_purl_by_purl(
             &Purl::from_str("pkg:maven/org.apache/log4j@1.2.3")?,
+            Default::default(),
+            &Tenant::Any,
             &ctx.db,
         )
         .await?;
@@ -898,3 +977,63 @@ async fn versioned_base_purl_by_purl(ctx: &TrustifyContext) -> Result<(), anyhow
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn remediation(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    ctx.ingest_document("csaf/rhsa-2024-2705.json").await?;
+
+    let service = PurlService::new();
+
+    let affected = Purl::from_str(
+        "pkg:maven/io.netty/netty-codec-http@4.1.100.Final-redhat-00001?repository_url=https://maven.repository.redhat.com/ga/&type=jar",
+    )?;
+
+    let remediation = service
+        .remediation(&affected, "CVE-2024-29025", &Tenant::Any, &ctx.db)
+        .await?
+        .expect("must be found");
+
+    assert_eq!(remediation.fixed.len(), 1);
+    assert_eq!(remediation.fixed[0].advisory_identifier, "RHSA-2024:2705");
+    assert_eq!(remediation.fixed[0].version, "4.1.108.Final-redhat-00001");
+    assert_eq!(
+        remediation.upgrade.as_deref(),
+        Some("4.1.108.Final-redhat-00001")
+    );
+
+    // a fixed version doesn't need an upgrade
+    let fixed = Purl {
+        version: Some("4.1.108.Final-redhat-00001".into()),
+        ..affected.clone()
+    };
+    let remediation = service
+        .remediation(&fixed, "CVE-2024-29025", &Tenant::Any, &ctx.db)
+        .await?
+        .expect("must be found");
+    assert_eq!(remediation.fixed.len(), 1);
+    assert_eq!(remediation.upgrade, None);
+
+    // other vulnerabilities are not fixed
+    let remediation = service
+        .remediation(&affected, "CVE-2024-2700", &Tenant::Any, &ctx.db)
+        .await?
+        .expect("must be found");
+    assert!(remediation.fixed.is_empty());
+    assert_eq!(remediation.upgrade, None);
+
+    // unknown packages
+    assert!(
+        service
+            .remediation(
+                &Purl::from_str("pkg:maven/org.example/unknown@1.0.0")?,
+                "CVE-2024-29025",
+                &Tenant::Any,
+                &ctx.db
+            )
+            .await?
+            .is_none()
+    );
+
+    Ok(())
+}
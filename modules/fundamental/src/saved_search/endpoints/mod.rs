@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    saved_search::{
+        model::{NewSavedSearch, SavedSearch, SavedSearchKind, SavedSearchResults},
+        service::SavedSearchService,
+    },
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, delete, get, http::header, post, web};
+use trustify_auth::{
+    Permission,
+    authenticator::user::{UserDetails, UserInformation},
+    authorizer::Authorizer,
+};
+use trustify_common::{
+    db::Database,
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults},
+};
+use uuid::Uuid;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = SavedSearchService::new(db);
+
+    config
+        .app_data(web::Data::new(service))
+        .service(list)
+        .service(create)
+        .service(get)
+        .service(delete)
+        .service(results);
+}
+
+#[utoipa::path(
+    tag = "savedSearch",
+    operation_id = "listSavedSearches",
+    params(
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "The searches of the current user, ordered by name", body = PaginatedResults<SavedSearch>),
+    ),
+)]
+#[get("/v2/saved-search")]
+/// List the saved searches of the current user
+pub async fn list(
+    service: web::Data<SavedSearchService>,
+    web::Query(paginated): web::Query<Paginated>,
+    user: UserDetails,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(service.list(&user.id, paginated).await?))
+}
+
+#[utoipa::path(
+    tag = "savedSearch",
+    operation_id = "createSavedSearch",
+    request_body = NewSavedSearch,
+    responses(
+        (status = 201, description = "Saved the search", body = SavedSearch),
+        (status = 400, description = "The label filters are invalid", body = ErrorInformation),
+        (status = 409, description = "The current user already saved a search with this name", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/saved-search")]
+/// Save a search for the current user
+pub async fn create(
+    service: web::Data<SavedSearchService>,
+    user: UserDetails,
+    web::Json(request): web::Json<NewSavedSearch>,
+) -> actix_web::Result<impl Responder> {
+    let result = service.create(user.id, request).await?;
+    Ok(HttpResponse::Created()
+        .insert_header((
+            header::LOCATION,
+            format!("/api/v2/saved-search/{}", result.id),
+        ))
+        .json(result))
+}
+
+#[utoipa::path(
+    tag = "savedSearch",
+    operation_id = "getSavedSearch",
+    params(
+        ("id" = Uuid, Path, description = "ID of the saved search"),
+    ),
+    responses(
+        (status = 200, description = "The saved search", body = SavedSearch),
+        (status = 404, description = "The current user has no search with this ID", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/saved-search/{id}")]
+/// Retrieve a saved search of the current user
+pub async fn get(
+    service: web::Data<SavedSearchService>,
+    id: web::Path<Uuid>,
+    user: UserDetails,
+) -> actix_web::Result<impl Responder> {
+    Ok(match service.fetch(&user.id, id.into_inner()).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "savedSearch",
+    operation_id = "deleteSavedSearch",
+    params(
+        ("id" = Uuid, Path, description = "ID of the saved search"),
+    ),
+    responses(
+        (status = 204, description = "The saved search was deleted"),
+        (status = 404, description = "The current user has no search with this ID", body = ErrorInformation),
+    ),
+)]
+#[delete("/v2/saved-search/{id}")]
+/// Delete a saved search of the current user
+pub async fn delete(
+    service: web::Data<SavedSearchService>,
+    id: web::Path<Uuid>,
+    user: UserDetails,
+) -> actix_web::Result<impl Responder> {
+    Ok(match service.delete(&user.id, id.into_inner()).await? {
+        true => HttpResponse::NoContent().finish(),
+        false => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "savedSearch",
+    operation_id = "runSavedSearch",
+    params(
+        ("id" = Uuid, Path, description = "ID of the saved search"),
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "The SBOMs or advisories matching the search", body = SavedSearchResults),
+        (status = 404, description = "The current user has no search with this ID", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/saved-search/{id}/results")]
+/// Run a saved search of the current user
+pub async fn results(
+    service: web::Data<SavedSearchService>,
+    id: web::Path<Uuid>,
+    web::Query(paginated): web::Query<Paginated>,
+    details: UserDetails,
+    user: UserInformation,
+    authorizer: web::Data<Authorizer>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let Some(search) = service.fetch(&details.id, id.into_inner()).await? else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    authorizer.require(
+        &user,
+        match search.kind {
+            SavedSearchKind::Sbom => Permission::ReadSbom,
+            SavedSearchKind::Advisory => Permission::ReadAdvisory,
+        },
+    )?;
+
+    Ok(HttpResponse::Ok().json(service.run(&search, &tenant, paginated).await?))
+}
@@ -0,0 +1,142 @@
+use crate::{
+    advisory::model::AdvisorySummary, saved_search::model::SavedSearch, sbom::model::SbomSummary,
+    test::caller,
+};
+use actix_http::StatusCode;
+use actix_web::test::{TestRequest, read_body_json};
+use serde_json::json;
+use test_context::test_context;
+use test_log::test;
+use trustify_common::model::PaginatedResults;
+use trustify_test_context::{TrustifyContext, auth::TestAuthentication, call::CallService};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn save_and_run(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_documents([
+        "spdx/quarkus-bom-3.2.11.Final-redhat-00001.json",
+        "zookeeper-3.9.2-cyclonedx.json",
+        "mitre/CVE-2024-27088.json",
+    ])
+    .await?;
+    let app = caller(ctx).await?;
+
+    let mut ids = vec![];
+    for (name, kind, labels) in [
+        ("cyclonedx", "sbom", "type=cyclone*"),
+        ("cves", "advisory", "type=cve"),
+    ] {
+        let request = TestRequest::post()
+            .uri("/api/v2/saved-search")
+            .set_json(json!({"name": name, "kind": kind, "labels": labels}))
+            .to_request()
+            .test_auth("user-a");
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let result: SavedSearch = read_body_json(response).await;
+        ids.push(result.id);
+    }
+
+    // names are unique per user
+
+    let request = TestRequest::post()
+        .uri("/api/v2/saved-search")
+        .set_json(json!({"name": "cves", "kind": "advisory"}))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let request = TestRequest::post()
+        .uri("/api/v2/saved-search")
+        .set_json(json!({"name": "cves", "kind": "advisory", "q": "CVE"}))
+        .to_request()
+        .test_auth("user-b");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // searches which can't be run are rejected
+
+    let request = TestRequest::post()
+        .uri("/api/v2/saved-search")
+        .set_json(json!({"name": "invalid", "kind": "sbom", "labels": "type"}))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // the searches of user-a
+
+    let request = TestRequest::get()
+        .uri("/api/v2/saved-search")
+        .to_request()
+        .test_auth("user-a");
+    let result: PaginatedResults<SavedSearch> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 2);
+    assert_eq!(result.items[0].name, "cves");
+    assert_eq!(result.items[1].name, "cyclonedx");
+
+    // run them
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/saved-search/{}/results", ids[0]))
+        .to_request()
+        .test_auth("user-a");
+    let result: PaginatedResults<SbomSummary> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 1);
+    assert_eq!(
+        result.items[0].head.labels.get("type").unwrap(),
+        "cyclonedx"
+    );
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/saved-search/{}/results", ids[1]))
+        .to_request()
+        .test_auth("user-a");
+    let result: PaginatedResults<AdvisorySummary> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].head.identifier, "CVE-2024-27088");
+
+    // other users can't see, run, or delete them
+
+    for request in [
+        TestRequest::get().uri(&format!("/api/v2/saved-search/{}", ids[0])),
+        TestRequest::get().uri(&format!("/api/v2/saved-search/{}/results", ids[0])),
+        TestRequest::delete().uri(&format!("/api/v2/saved-search/{}", ids[0])),
+    ] {
+        let response = app
+            .call_service(request.to_request().test_auth("user-b"))
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    // but the owner can delete them
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/saved-search/{}", ids[0]))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/saved-search/{}", ids[0]))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn anonymous(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::get().uri("/api/v2/saved-search").to_request();
+    let response = app.call_service(request).await;
+    assert!(response.status().is_client_error());
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use crate::{advisory::model::AdvisorySummary, sbom::model::SbomSummary};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_common::model::PaginatedResults;
+use trustify_entity::saved_search;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub use saved_search::Kind as SavedSearchKind;
+
+/// A search, saved by the current user.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SavedSearch {
+    pub id: Uuid,
+    pub name: String,
+    pub kind: SavedSearchKind,
+    /// The query, as accepted by the `q` parameter of the search
+    pub q: String,
+    /// The sort order, as accepted by the `sort` parameter of the search
+    pub sort: String,
+    /// The label filters, as accepted by the `labels` parameter of the search
+    pub labels: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    /// The time of the last change
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified: OffsetDateTime,
+}
+
+impl From<saved_search::Model> for SavedSearch {
+    fn from(value: saved_search::Model) -> Self {
+        let saved_search::Model {
+            id,
+            user_id: _,
+            name,
+            kind,
+            q,
+            sort,
+            labels,
+            created,
+            modified,
+        } = value;
+
+        Self {
+            id,
+            name,
+            kind,
+            q,
+            sort,
+            labels,
+            created,
+            modified,
+        }
+    }
+}
+
+/// A search to save.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct NewSavedSearch {
+    /// The name of the search, unique for the current user
+    pub name: String,
+    pub kind: SavedSearchKind,
+    #[serde(default)]
+    pub q: String,
+    #[serde(default)]
+    pub sort: String,
+    #[serde(default)]
+    pub labels: String,
+}
+
+/// The results of running a saved search, depending on its kind.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum SavedSearchResults {
+    Sbom(PaginatedResults<SbomSummary>),
+    Advisory(PaginatedResults<AdvisorySummary>),
+}
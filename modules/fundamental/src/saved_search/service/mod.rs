@@ -0,0 +1,179 @@
+use crate::{
+    Error,
+    advisory::service::AdvisoryService,
+    saved_search::model::{NewSavedSearch, SavedSearch, SavedSearchKind, SavedSearchResults},
+    sbom::service::SbomService,
+    tenant::Tenant,
+};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, SqlErr, prelude::Uuid,
+};
+use time::OffsetDateTime;
+use trustify_common::{
+    db::{
+        Database,
+        limiter::LimiterTrait,
+        query::{LabelFilters, Query},
+    },
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::saved_search;
+
+pub struct SavedSearchService {
+    db: Database,
+}
+
+impl SavedSearchService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Save a search for a user. Fails if the user already saved a search with the same name.
+    pub async fn create(
+        &self,
+        user_id: String,
+        request: NewSavedSearch,
+    ) -> Result<SavedSearch, Error> {
+        let NewSavedSearch {
+            name,
+            kind,
+            q,
+            sort,
+            labels,
+        } = request;
+
+        // reject searches which can't be run
+        labels.parse::<LabelFilters>()?;
+
+        let now = OffsetDateTime::now_utc();
+        let result = saved_search::Entity::insert(saved_search::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            user_id: Set(user_id),
+            name: Set(name.clone()),
+            kind: Set(kind),
+            q: Set(q),
+            sort: Set(sort),
+            labels: Set(labels),
+            created: Set(now),
+            modified: Set(now),
+        })
+        .exec_with_returning(&self.db)
+        .await
+        .map_err(|err| match err.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => {
+                Error::Conflict(format!("A search named '{name}' already exists"))
+            }
+            _ => err.into(),
+        })?;
+
+        Ok(result.into())
+    }
+
+    /// Get a search of a user. Returns `None` if the search was not found, or belongs to another
+    /// user.
+    pub async fn fetch(&self, user_id: &str, id: Uuid) -> Result<Option<SavedSearch>, Error> {
+        Ok(saved_search::Entity::find_by_id(id)
+            .filter(saved_search::Column::UserId.eq(user_id))
+            .one(&self.db)
+            .await?
+            .map(SavedSearch::from))
+    }
+
+    /// List the searches of a user, ordered by their name.
+    pub async fn list(
+        &self,
+        user_id: &str,
+        paginated: Paginated,
+    ) -> Result<PaginatedResults<SavedSearch>, Error> {
+        let limiter = saved_search::Entity::find()
+            .filter(saved_search::Column::UserId.eq(user_id))
+            .order_by_asc(saved_search::Column::Name)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        let total = limiter.total().await?;
+        let items = limiter.fetch().await?;
+
+        Ok(PaginatedResults {
+            items: items.into_iter().map(SavedSearch::from).collect(),
+            total,
+        })
+    }
+
+    /// Delete a search of a user, returning `false` if it was not found.
+    pub async fn delete(&self, user_id: &str, id: Uuid) -> Result<bool, Error> {
+        let result = saved_search::Entity::delete_many()
+            .filter(saved_search::Column::Id.eq(id))
+            .filter(saved_search::Column::UserId.eq(user_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Run a saved search, limited to the documents of a tenant.
+    pub async fn run(
+        &self,
+        search: &SavedSearch,
+        tenant: &Tenant,
+        paginated: Paginated,
+    ) -> Result<SavedSearchResults, Error> {
+        let query = Query {
+            q: search.q.clone(),
+            sort: search.sort.clone(),
+        };
+        let labels = tenant.restrict(search.labels.parse::<LabelFilters>()?);
+
+        Ok(match search.kind {
+            SavedSearchKind::Sbom => SavedSearchResults::Sbom(
+                SbomService::new(self.db.clone())
+                    .fetch_sboms(query, paginated, false, labels, &self.db)
+                    .await?,
+            ),
+            SavedSearchKind::Advisory => SavedSearchResults::Advisory(
+                AdvisoryService::new(self.db.clone())
+                    .fetch_advisories(query, paginated, Default::default(), labels, &self.db)
+                    .await?,
+            ),
+        })
+    }
+
+    /// Check if a single SBOM or advisory, matching the kind of the search, is a result of a
+    /// saved search of a tenant.
+    pub async fn matches(
+        &self,
+        search: &SavedSearch,
+        tenant: &Tenant,
+        id: Uuid,
+    ) -> Result<bool, Error> {
+        // narrow down the search to the document, instead of running it in full
+        let filter = match search.kind {
+            SavedSearchKind::Sbom => format!("sbom_id={id}"),
+            SavedSearchKind::Advisory => format!("id={id}"),
+        };
+        let q = match search.q.trim() {
+            "" => filter,
+            q => format!("{q}&{filter}"),
+        };
+
+        let results = self
+            .run(
+                &SavedSearch {
+                    q,
+                    ..search.clone()
+                },
+                tenant,
+                Paginated {
+                    offset: 0,
+                    limit: 1,
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        Ok(match results {
+            SavedSearchResults::Sbom(results) => !results.items.is_empty(),
+            SavedSearchResults::Advisory(results) => !results.items.is_empty(),
+        })
+    }
+}
@@ -1,5 +1,26 @@
+use trustify_common::decompress::Limits;
+use trustify_module_settings::model::Settings;
+
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 pub struct Config {
-    /// An upload limit in bytes. Zero meaning "unlimited".
-    pub upload_limit: usize,
+    /// Limits of an upload, after decompression.
+    pub upload_limit: Limits,
+    /// Maximum number of items of collections embedded in detail responses. Zero meaning
+    /// "unlimited".
+    pub embedded_item_limit: usize,
+}
+
+impl Config {
+    /// The configuration, with the runtime settings applied.
+    pub fn apply(&self, settings: &Settings) -> Self {
+        Self {
+            upload_limit: Limits {
+                size: settings.sbom_upload_limit.unwrap_or(self.upload_limit.size),
+                ..self.upload_limit
+            },
+            embedded_item_limit: settings
+                .embedded_item_limit
+                .unwrap_or(self.embedded_item_limit),
+        }
+    }
 }
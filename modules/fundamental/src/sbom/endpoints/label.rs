@@ -1,9 +1,19 @@
-use crate::sbom::service::SbomService;
+use crate::{
+    audit::{
+        model::{Action, DocumentKind},
+        service::AuditService,
+    },
+    sbom::service::SbomService,
+    tenant::Tenant,
+};
 use actix_web::{HttpResponse, Responder, patch, put, web};
+use serde_json::json;
 use trustify_auth::{UpdateSbom, authorizer::Require};
 use trustify_common::db::Database;
+use trustify_common::error::ErrorInformation;
 use trustify_common::id::Id;
 use trustify_entity::labels::Labels;
+use trustify_module_ingestor::service::audit::Actor;
 
 /// Modify existing labels of an SBOM
 #[utoipa::path(
@@ -15,22 +25,43 @@ use trustify_entity::labels::Labels;
     ),
     responses(
         (status = 204, description = "Modified the labels of the SBOM"),
-        (status = 404, description = "The SBOM could not be found"),
+        (status = 404, description = "The SBOM could not be found", body = ErrorInformation),
     ),
 )]
 #[patch("/v2/sbom/{id}/label")]
 pub async fn update(
     sbom: web::Data<SbomService>,
+    db: web::Data<Database>,
     id: web::Path<Id>,
     web::Json(update): web::Json<Labels>,
+    audit: web::Data<AuditService>,
+    actor: Actor,
     _: Require<UpdateSbom>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
+    let id = id.into_inner();
+    let id = tenant.require_sbom(&id, db.as_ref()).await?;
+    let update = tenant.protect(update);
+    let details = json!({ "patch": &update });
+
     Ok(
         match sbom
-            .update_labels(id.into_inner(), |labels| labels.apply(update))
+            .update_labels(id, |labels| labels.apply(update))
             .await?
         {
-            Some(()) => HttpResponse::NoContent(),
+            Some(id) => {
+                audit
+                    .record(
+                        &actor,
+                        Action::LabelsChanged,
+                        DocumentKind::Sbom,
+                        id,
+                        Some(details),
+                        db.as_ref(),
+                    )
+                    .await?;
+                HttpResponse::NoContent()
+            }
             None => HttpResponse::NotFound(),
         },
     )
@@ -46,7 +77,7 @@ pub async fn update(
     ),
     responses(
         (status = 204, description = "Replaced the labels of the SBOM"),
-        (status = 404, description = "The SBOM could not be found"),
+        (status = 404, description = "The SBOM could not be found", body = ErrorInformation),
     ),
 )]
 #[put("/v2/sbom/{id}/label")]
@@ -55,14 +86,31 @@ pub async fn set(
     db: web::Data<Database>,
     id: web::Path<Id>,
     web::Json(labels): web::Json<Labels>,
+    audit: web::Data<AuditService>,
+    actor: Actor,
     _: Require<UpdateSbom>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
+    let id = id.into_inner();
+    let id = tenant.require_sbom(&id, db.as_ref()).await?;
+    let labels = tenant.stamp(labels);
+
     Ok(
-        match sbom
-            .set_labels(id.into_inner(), labels, db.as_ref())
-            .await?
-        {
-            Some(()) => HttpResponse::NoContent(),
+        match sbom.set_labels(id, labels.clone(), db.as_ref()).await? {
+            Some(id) => {
+                let details = json!({ "labels": labels });
+                audit
+                    .record(
+                        &actor,
+                        Action::LabelsChanged,
+                        DocumentKind::Sbom,
+                        id,
+                        Some(details),
+                        db.as_ref(),
+                    )
+                    .await?;
+                HttpResponse::NoContent()
+            }
             None => HttpResponse::NotFound(),
         },
     )
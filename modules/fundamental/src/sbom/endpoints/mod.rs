@@ -12,19 +12,28 @@ use crate::license::{
 };
 use crate::{
     Error::{self, Internal},
+    audit::{
+        model::{Action, DocumentKind},
+        service::AuditService,
+    },
+    endpoints::{Deprecation, Fields, Precheck, Withdrawal},
+    listing::ListFormat,
     purl::service::PurlService,
     sbom::{
         model::{
-            SbomExternalPackageReference, SbomNodeReference, SbomPackage, SbomPackageRelation,
-            SbomSummary, Which, details::SbomAdvisory,
+            DirectedRelationship, RelationshipFilter, SbomChecksumMatch,
+            SbomExternalPackageReference, SbomFile, SbomNodeReference, SbomPackage,
+            SbomPackageOccurrence, SbomPackageRelation, SbomSummary, Which, details::SbomAdvisory,
         },
-        service::SbomService,
+        service::{DetailsOptions, SbomService},
     },
+    source_document::conditional::{Preconditions, download},
+    tenant::Tenant,
 };
-use actix_web::{HttpResponse, Responder, delete, get, http::header, post, web};
+use actix_web::{HttpRequest, HttpResponse, Responder, delete, get, head, http::header, post, web};
 use config::Config;
-use futures_util::TryStreamExt;
 use sea_orm::{TransactionTrait, prelude::Uuid};
+use serde_json::json;
 use std::str::FromStr;
 use trustify_auth::{
     CreateSbom, DeleteSbom, Permission, ReadAdvisory, ReadSbom, all,
@@ -32,38 +41,59 @@ use trustify_auth::{
     authorizer::{Authorizer, Require},
 };
 use trustify_common::{
-    db::{Database, query::Query},
-    decompress::decompress_async,
+    db::{
+        Database,
+        query::{LabelFilters, LabelQuery, Query},
+    },
+    decompress::{Limits, decompress_async},
+    digest::VerifiedBytes,
+    error::ErrorInformation,
     id::Id,
     model::{BinaryData, Paginated, PaginatedResults},
+    signing::ResponseSigner,
 };
 use trustify_entity::{labels::Labels, relationship::Relationship};
 use trustify_module_ingestor::{
-    model::IngestResult,
-    service::{Format, IngestorService},
+    model::{IngestResult, IngestionJobAccepted, IngestionMode},
+    service::{
+        Format, IngestorService,
+        audit::{Actor, Upload},
+    },
 };
-use trustify_module_storage::service::StorageBackend;
+use trustify_module_settings::service::SettingsService;
 
 pub fn configure(
     config: &mut utoipa_actix_web::service_config::ServiceConfig,
     db: Database,
-    upload_limit: usize,
+    upload_limit: Limits,
+    embedded_item_limit: usize,
+    soft_delete: bool,
 ) {
-    let sbom_service = SbomService::new(db.clone());
+    let sbom_service = SbomService::new(db.clone()).with_soft_delete(soft_delete);
     let purl_service = PurlService::new();
 
     config
         .app_data(web::Data::new(db))
         .app_data(web::Data::new(sbom_service))
         .app_data(web::Data::new(purl_service))
-        .app_data(web::Data::new(Config { upload_limit }))
+        .app_data(web::Data::new(Config {
+            upload_limit,
+            embedded_item_limit,
+        }))
         .service(all)
+        .service(precheck)
         .service(all_related)
         .service(count_related)
+        .service(lookup)
         .service(get)
         .service(get_sbom_advisories)
+        .service(get_sbom_advisory_packages)
+        .service(get_sbom_csaf)
         .service(delete)
+        .service(restore)
+        .service(search_packages)
         .service(packages)
+        .service(package_files)
         .service(related)
         .service(upload)
         .service(download)
@@ -82,7 +112,7 @@ const CONTENT_TYPE_GZIP: &str = "application/gzip";
     ),
     responses(
         (status = 200, description = "license gzip files", body = Vec<u8>, content_type = CONTENT_TYPE_GZIP),
-        (status = 404, description = "The document could not be found"),
+        (status = 404, description = "The document could not be found", body = ErrorInformation),
     ),
 )]
 #[get("/v2/sbom/{id}/license-export")]
@@ -90,8 +120,10 @@ pub async fn get_license_export(
     fetcher: web::Data<LicenseService>,
     db: web::Data<Database>,
     id: web::Path<String>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
     let id = Id::from_str(&id).map_err(Error::IdKey)?;
+    let id = tenant.require_sbom(&id, db.as_ref()).await?;
 
     let license_export_result = fetcher.license_export(id, db.as_ref()).await?;
     if let Some(name_group_version) = license_export_result.sbom_name_group_version.clone() {
@@ -125,27 +157,83 @@ pub async fn get_license_export(
     params(
         Query,
         Paginated,
+        LatestQuery,
+        LabelQuery,
+        Fields,
     ),
     responses(
-        (status = 200, description = "Matching SBOMs", body = PaginatedResults<SbomSummary>),
+        (status = 200, description = "Matching SBOMs, as JSON, or streamed as CSV or NDJSON, depending on the Accept header", content(
+            (PaginatedResults<SbomSummary> = "application/json"),
+            (String = "text/csv"),
+            (String = "application/x-ndjson"),
+        )),
     ),
 )]
 #[get("/v2/sbom")]
 pub async fn all(
+    req: HttpRequest,
     fetch: web::Data<SbomService>,
     db: web::Data<Database>,
     web::Query(search): web::Query<Query>,
     web::Query(paginated): web::Query<Paginated>,
+    web::Query(LatestQuery { latest }): web::Query<LatestQuery>,
+    web::Query(labels): web::Query<LabelQuery>,
+    web::Query(fields): web::Query<Fields>,
     authorizer: web::Data<Authorizer>,
     user: UserInformation,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
     authorizer.require(&user, Permission::ReadSbom)?;
 
+    let labels = tenant.restrict(LabelFilters::try_from(labels).map_err(Error::from)?);
+
+    let format = ListFormat::negotiate(&req);
+    if format != ListFormat::Json {
+        return Ok(format.stream(fields, paginated, move |paginated| {
+            let (fetch, db, search, labels) =
+                (fetch.clone(), db.clone(), search.clone(), labels.clone());
+            async move {
+                fetch
+                    .fetch_sboms(search, paginated, latest, labels, db.read())
+                    .await
+            }
+        }));
+    }
+
     let result = fetch
-        .fetch_sboms(search, paginated, (), db.as_ref())
+        .fetch_sboms(search, paginated, latest, labels, db.read())
         .await?;
 
-    Ok(HttpResponse::Ok().json(result))
+    Ok(fields.respond(result)?)
+}
+
+/// Check if an SBOM exists, before uploading it
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "precheckSbom",
+    params(
+        Precheck,
+    ),
+    responses(
+        (status = 200, description = "An SBOM with this digest exists"),
+        (status = 404, description = "No SBOM with this digest exists", body = ErrorInformation),
+    ),
+)]
+#[head("/v2/sbom")]
+pub async fn precheck(
+    service: web::Data<SbomService>,
+    db: web::Data<Database>,
+    web::Query(Precheck { sha256 }): web::Query<Precheck>,
+    _: Require<ReadSbom>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let id = Id::Sha256(sha256.to_lowercase());
+    let id = tenant.require_sbom(&id, db.as_ref()).await?;
+    if service.exists(id, db.as_ref()).await? {
+        Ok(HttpResponse::Ok().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
 }
 
 /// Find all SBOMs containing the provided package.
@@ -173,13 +261,14 @@ pub async fn all_related(
     web::Query(all_related): web::Query<ExternalReferenceQuery>,
     authorizer: web::Data<Authorizer>,
     user: UserInformation,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
     authorizer.require(&user, Permission::ReadSbom)?;
 
     let id = (&all_related).try_into()?;
 
     let result = sbom
-        .find_related_sboms(id, paginated, search, db.as_ref())
+        .find_related_sboms(id, paginated, search, &tenant, db.as_ref())
         .await?;
 
     Ok(HttpResponse::Ok().json(result))
@@ -205,13 +294,56 @@ pub async fn count_related(
     db: web::Data<Database>,
     web::Json(ids): web::Json<Vec<ExternalReferenceQuery>>,
     _: Require<ReadSbom>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
     let ids = ids
         .iter()
         .map(SbomExternalPackageReference::try_from)
         .collect::<Result<Vec<_>, _>>()?;
 
-    let result = sbom.count_related_sboms(ids, db.as_ref()).await?;
+    let result = sbom.count_related_sboms(ids, &tenant, db.as_ref()).await?;
+
+    Ok(HttpResponse::Ok().json(result))
+}
+
+/// Find packages of all SBOMs by their checksums.
+///
+/// At least one checksum must be provided. A package matches if it has any of the provided
+/// checksums. Checksums are compared case-insensitively.
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "lookupSbomChecksums",
+    params(
+        ChecksumQuery,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Matching packages", body = PaginatedResults<SbomChecksumMatch>),
+        (status = 400, description = "No checksum was provided", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/sbom/lookup")]
+pub async fn lookup(
+    fetch: web::Data<SbomService>,
+    db: web::Data<Database>,
+    web::Query(checksums): web::Query<ChecksumQuery>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadSbom>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let checksums = checksums.checksums();
+    if checksums.is_empty() {
+        return Err(Error::BadRequest("Requires at least one checksum".into()).into());
+    }
+
+    let result = fetch
+        .lookup_checksums(
+            &checksums,
+            paginated,
+            tenant.restrict(LabelFilters::default()),
+            db.read(),
+        )
+        .await?;
 
     Ok(HttpResponse::Ok().json(result))
 }
@@ -225,7 +357,7 @@ pub async fn count_related(
     ),
     responses(
         (status = 200, description = "Matching SBOM", body = SbomSummary),
-        (status = 404, description = "Matching SBOM not found"),
+        (status = 404, description = "Matching SBOM not found", body = ErrorInformation),
     ),
 )]
 #[get("/v2/sbom/{id}")]
@@ -234,44 +366,172 @@ pub async fn get(
     db: web::Data<Database>,
     id: web::Path<String>,
     _: Require<ReadSbom>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
     let id = Id::from_str(&id).map_err(Error::IdKey)?;
-    match fetcher.fetch_sbom_summary(id, db.as_ref()).await? {
+    let tx = db.begin_read().await.map_err(Error::from)?;
+    let id = tenant.require_sbom(&id, &tx).await?;
+    match fetcher.fetch_sbom_summary(id, &tx).await? {
         Some(v) => Ok(HttpResponse::Ok().json(v)),
         None => Ok(HttpResponse::NotFound().finish()),
     }
 }
 
 /// Get advisories for an SBOM
+///
+/// If the number of packages of a status exceeds the configured limit, the list of packages is
+/// truncated. The full list is available using the link provided in `packages_link`.
+///
+/// If response signing is enabled, the response carries a detached JWS over its body.
 #[utoipa::path(
     tag = "sbom",
     operation_id = "getSbomAdvisories",
     params(
         ("id" = Id, Path),
+        Deprecation,
+        Withdrawal,
     ),
     responses(
-        (status = 200, description = "Matching SBOM", body = Vec<SbomAdvisory>),
-        (status = 404, description = "Matching SBOM not found"),
+        (status = 200, description = "Matching SBOM", body = Vec<SbomAdvisory>, headers(
+            ("x-jws-signature" = String, description = "Detached JWS over the body, if signing is enabled")
+        )),
+        (status = 404, description = "Matching SBOM not found", body = ErrorInformation),
     ),
 )]
 #[get("/v2/sbom/{id}/advisory")]
 pub async fn get_sbom_advisories(
     fetcher: web::Data<SbomService>,
     db: web::Data<Database>,
+    config: web::Data<Config>,
+    settings: web::Data<SettingsService>,
+    signer: web::Data<ResponseSigner>,
     id: web::Path<String>,
+    web::Query(Deprecation { deprecated }): web::Query<Deprecation>,
+    web::Query(Withdrawal { include_withdrawn }): web::Query<Withdrawal>,
     _: Require<GetSbomAdvisories>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
     let id = Id::from_str(&id).map_err(Error::IdKey)?;
+    let config = config.apply(&settings.current().await.map_err(Error::from)?);
     let statuses: Vec<String> = vec!["affected".to_string()];
+    let tx = db.begin_read().await.map_err(Error::from)?;
+    let id = tenant.require_sbom(&id, &tx).await?;
     match fetcher
-        .fetch_sbom_details(id, statuses, db.as_ref())
+        .fetch_sbom_details(
+            id,
+            DetailsOptions {
+                statuses,
+                deprecation: deprecated,
+                withdrawal: include_withdrawn.into(),
+            },
+            &tenant,
+            &tx,
+        )
         .await?
     {
-        Some(v) => Ok(HttpResponse::Ok().json(v.advisories)),
+        Some(v) => {
+            let mut advisories = v.advisories;
+            for advisory in &mut advisories {
+                advisory.truncate(v.summary.head.id, config.embedded_item_limit);
+            }
+            Ok(signer.json(HttpResponse::Ok(), advisories))
+        }
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+/// Export the vulnerability statuses of an SBOM as a CSAF VEX document
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "getSbomCsaf",
+    params(
+        ("id" = Id, Path, description = "ID of the SBOM"),
+    ),
+    responses(
+        (status = 200, description = "CSAF VEX document", body = serde_json::Value),
+        (status = 404, description = "Matching SBOM not found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/sbom/{id}/csaf")]
+pub async fn get_sbom_csaf(
+    fetcher: web::Data<SbomService>,
+    db: web::Data<Database>,
+    id: web::Path<String>,
+    _: Require<GetSbomAdvisories>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let id = Id::from_str(&id).map_err(Error::IdKey)?;
+    let tx = db.begin_read().await.map_err(Error::from)?;
+    let id = tenant.require_sbom(&id, &tx).await?;
+    match fetcher.export_csaf(id, &tenant, &tx).await? {
+        Some((name, document)) => Ok(HttpResponse::Ok()
+            .append_header((
+                "Content-Disposition",
+                format!(
+                    "attachment; filename=\"{}_vex.json\"",
+                    get_sanitize_filename(name)
+                ),
+            ))
+            .json(document)),
         None => Ok(HttpResponse::NotFound().finish()),
     }
 }
 
+/// Get the affected packages of an SBOM, for a status of an advisory
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "getSbomAdvisoryPackages",
+    params(
+        ("id" = Id, Path, description = "ID of the SBOM"),
+        ("advisory" = Uuid, Path, description = "UUID of the advisory"),
+        StatusPackagesQuery,
+        Deprecation,
+        Withdrawal,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Affected packages", body = PaginatedResults<SbomPackage>),
+        (status = 404, description = "Matching SBOM, advisory, or status not found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/sbom/{id}/advisory/{advisory}/package")]
+pub async fn get_sbom_advisory_packages(
+    fetcher: web::Data<SbomService>,
+    db: web::Data<Database>,
+    path: web::Path<(String, Uuid)>,
+    web::Query(query): web::Query<StatusPackagesQuery>,
+    web::Query(Deprecation { deprecated }): web::Query<Deprecation>,
+    web::Query(Withdrawal { include_withdrawn }): web::Query<Withdrawal>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<GetSbomAdvisories>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (id, advisory) = path.into_inner();
+    let id = Id::from_str(&id).map_err(Error::IdKey)?;
+    let tx = db.begin_read().await.map_err(Error::from)?;
+    let id = tenant.require_sbom(&id, &tx).await?;
+    tenant.require_advisory(&Id::Uuid(advisory), &tx).await?;
+
+    match fetcher
+        .fetch_status_packages(
+            id,
+            advisory,
+            &query.vulnerability,
+            &query.status,
+            query.context.as_deref(),
+            deprecated,
+            include_withdrawn.into(),
+            paginated,
+            &tx,
+        )
+        .await?
+    {
+        // no package means the status doesn't exist for this SBOM
+        Some(result) if result.total > 0 => Ok(HttpResponse::Ok().json(result)),
+        _ => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
 all!(GetSbomAdvisories -> ReadSbom, ReadAdvisory);
 
 /// Delete an SBOM
@@ -283,7 +543,7 @@ all!(GetSbomAdvisories -> ReadSbom, ReadAdvisory);
     ),
     responses(
         (status = 200, description = "Matching SBOM", body = SbomSummary),
-        (status = 404, description = "Matching SBOM not found"),
+        (status = 404, description = "Matching SBOM not found", body = ErrorInformation),
     ),
 )]
 #[delete("/v2/sbom/{id}")]
@@ -291,12 +551,16 @@ pub async fn delete(
     service: web::Data<SbomService>,
     db: web::Data<Database>,
     purl_service: web::Data<PurlService>,
+    audit: web::Data<AuditService>,
     id: web::Path<String>,
+    actor: Actor,
     _: Require<DeleteSbom>,
+    tenant: Tenant,
 ) -> Result<impl Responder, Error> {
     let tx = db.begin().await?;
 
     let id = Id::from_str(&id)?;
+    let id = tenant.require_sbom(&id, &tx).await?;
     match service.fetch_sbom_summary(id.clone(), &tx).await? {
         Some(v) => {
             let rows_affected = service.delete_sbom(v.head.id, &tx).await?;
@@ -304,6 +568,16 @@ pub async fn delete(
                 0 => Ok(HttpResponse::NotFound().finish()),
                 1 => {
                     let _ = purl_service.gc_purls(&tx).await; // ignore gc failure..
+                    audit
+                        .record(
+                            &actor,
+                            Action::Deleted,
+                            DocumentKind::Sbom,
+                            v.head.id,
+                            None,
+                            &tx,
+                        )
+                        .await?;
                     tx.commit().await?;
                     Ok(HttpResponse::Ok().json(v))
                 }
@@ -314,6 +588,86 @@ pub async fn delete(
     }
 }
 
+/// Restore a deleted SBOM
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "restoreSbom",
+    params(
+        ("id" = Uuid, Path, description = "ID of the SBOM"),
+    ),
+    responses(
+        (status = 200, description = "The restored SBOM", body = SbomSummary),
+        (status = 404, description = "No deleted SBOM with this ID found", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/sbom/{id}/restore")]
+pub async fn restore(
+    service: web::Data<SbomService>,
+    db: web::Data<Database>,
+    audit: web::Data<AuditService>,
+    id: web::Path<Uuid>,
+    actor: Actor,
+    _: Require<DeleteSbom>,
+    tenant: Tenant,
+) -> Result<impl Responder, Error> {
+    let tx = db.begin().await?;
+
+    let id = id.into_inner();
+    tenant.require_sbom(&Id::Uuid(id), &tx).await?;
+    if service.restore_sbom(id, &tx).await? == 0 {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    audit
+        .record(&actor, Action::Restored, DocumentKind::Sbom, id, None, &tx)
+        .await?;
+
+    let fetched = service.fetch_sbom_summary(Id::Uuid(id), &tx).await?;
+    tx.commit().await?;
+
+    Ok(match fetched {
+        Some(fetched) => HttpResponse::Ok().json(fetched),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+/// Search packages across all SBOMs
+///
+/// Each package is reported together with the SBOM it was found in. Packages can be searched
+/// using fields of the package (like `name` or `version`) as well as fields of the SBOM (like
+/// `document_id` or `published`). Versions are compared as text.
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "searchPackages",
+    params(
+        Query,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Matching packages", body = PaginatedResults<SbomPackageOccurrence>),
+        (status = 400, description = "The query could not be parsed", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/package")]
+pub async fn search_packages(
+    fetch: web::Data<SbomService>,
+    db: web::Data<Database>,
+    web::Query(search): web::Query<Query>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadSbom>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let result = fetch
+        .search_packages(
+            search,
+            paginated,
+            tenant.restrict(LabelFilters::default()),
+            db.read(),
+        )
+        .await?;
+
+    Ok(HttpResponse::Ok().json(result))
+}
+
 /// Search for packages of an SBOM
 #[utoipa::path(
     tag = "sbom",
@@ -335,14 +689,55 @@ pub async fn packages(
     web::Query(search): web::Query<Query>,
     web::Query(paginated): web::Query<Paginated>,
     _: Require<ReadSbom>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
+    let id = id.into_inner();
+    tenant.require_sbom(&Id::Uuid(id), db.read()).await?;
+
     let result = fetch
-        .fetch_sbom_packages(id.into_inner(), search, paginated, db.as_ref())
+        .fetch_sbom_packages(id, search, paginated, db.read())
         .await?;
 
     Ok(HttpResponse::Ok().json(result))
 }
 
+/// List the files contained in a package of an SBOM
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "listPackageFiles",
+    params(
+        ("id", Path, description = "ID of the SBOM"),
+        ("node", Path, description = "The SBOM internal ID of the package, URL encoded"),
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Files of the package", body = PaginatedResults<SbomFile>),
+        (status = 404, description = "The SBOM, or the package, could not be found"),
+    ),
+)]
+#[get("/v2/sbom/{id}/packages/{node}/files")]
+pub async fn package_files(
+    fetch: web::Data<SbomService>,
+    db: web::Data<Database>,
+    path: web::Path<(Uuid, String)>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadSbom>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (id, node) = path.into_inner();
+    tenant.require_sbom(&Id::Uuid(id), db.read()).await?;
+
+    Ok(
+        match fetch
+            .fetch_package_files(id, &node, paginated, db.read())
+            .await?
+        {
+            Some(files) => HttpResponse::Ok().json(files),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
 #[derive(Clone, Debug, serde::Deserialize, utoipa::IntoParams)]
 struct RelatedQuery {
     /// The Package to use as reference
@@ -354,6 +749,65 @@ struct RelatedQuery {
     /// Optional relationship filter
     #[serde(default)]
     pub relationship: Option<Relationship>,
+    /// Relationships to include, comma separated.
+    ///
+    /// Inverse names (like `dependency_of` or `contained_by`) select the relationship as seen
+    /// from the other side, no matter in which direction the SBOM encoded it. All relationships
+    /// must be of the same direction.
+    #[serde(default)]
+    pub relationships: Option<String>,
+    /// Relationships to exclude, comma separated
+    #[serde(default)]
+    pub exclude: Option<String>,
+    /// Follow references to packages of other, ingested SBOMs
+    #[serde(default)]
+    pub resolve_external: bool,
+}
+
+impl RelatedQuery {
+    /// Evaluate the relationship filters, along with the side the reference is on.
+    ///
+    /// Using inverse relationship names switches the side of the reference.
+    fn relationships(&self) -> Result<(Which, RelationshipFilter), Error> {
+        fn parse(value: &Option<String>) -> Result<Vec<DirectedRelationship>, Error> {
+            value
+                .iter()
+                .flat_map(|value| value.split(','))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(DirectedRelationship::from_str)
+                .collect()
+        }
+
+        let include = parse(&self.relationships)?;
+
+        let inverse = include.iter().any(|r| r.inverse);
+        if include.iter().any(|r| r.inverse != inverse) {
+            return Err(Error::BadRequest(
+                "Relationships must all be of the same direction".into(),
+            ));
+        }
+
+        let filter = RelationshipFilter {
+            include: self
+                .relationship
+                .into_iter()
+                .chain(include.into_iter().map(|r| r.relationship))
+                .collect(),
+            exclude: parse(&self.exclude)?
+                .into_iter()
+                .map(|r| r.relationship)
+                .collect(),
+        };
+
+        let which = if inverse {
+            self.which.inverse()
+        } else {
+            self.which.clone()
+        };
+
+        Ok((which, filter))
+    }
 }
 
 /// Search for related packages in an SBOM
@@ -379,21 +833,26 @@ pub async fn related(
     web::Query(paginated): web::Query<Paginated>,
     web::Query(related): web::Query<RelatedQuery>,
     _: Require<ReadSbom>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
     let id = id.into_inner();
+    tenant.require_sbom(&Id::Uuid(id), db.read()).await?;
+
+    let (which, relationships) = related.relationships()?;
 
     let result = fetch
         .fetch_related_packages(
             id,
             search,
             paginated,
-            related.which,
+            which,
             match &related.reference {
                 None => SbomNodeReference::All,
                 Some(id) => SbomNodeReference::Package(id),
             },
-            related.relationship,
-            db.as_ref(),
+            relationships,
+            related.resolve_external,
+            db.read(),
         )
         .await?;
 
@@ -402,11 +861,26 @@ pub async fn related(
 
 #[derive(Clone, Debug, serde::Deserialize, utoipa::IntoParams)]
 struct UploadQuery {
+    /// Ingest the SBOM while handling the request, or queue it as a job.
+    #[serde(default)]
+    mode: IngestionMode,
+    /// Optional external ID, like a build or ticket ID, for looking up the SBOM later on.
+    #[serde(default)]
+    external_id: Option<String>,
+    /// Optional format of the document, skipping the detection of the format, e.g. `cycloneDx`.
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    format: Option<Format>,
     /// Optional labels.
     ///
     /// Only use keys with a prefix of `labels.`
     #[serde(flatten, with = "trustify_entity::labels::prefixed")]
     labels: Labels,
+    /// Upload the SBOM as an update of the most recently ingested SBOM with the same document
+    /// namespace (SPDX) or serial number (CycloneDX), taking over its labels and product links.
+    /// Only supported for synchronous ingestion.
+    #[serde(default)]
+    update: bool,
 }
 
 #[utoipa::path(
@@ -415,24 +889,78 @@ struct UploadQuery {
     request_body = Vec <u8>,
     params(
         UploadQuery,
+        ("digest" = Option<String>, Header, description = "Digests of the body, like `sha-256=<base64>`, rejecting the upload if it doesn't match"),
     ),
     responses(
         (status = 201, description = "Upload an SBOM", body = IngestResult),
-        (status = 400, description = "The file could not be parsed as an SBOM"),
+        (status = 202, description = "Accepted the SBOM for asynchronous ingestion", body = IngestionJobAccepted),
+        (status = 400, description = "The file could not be parsed as an SBOM, or an update was requested for asynchronous ingestion", body = ErrorInformation),
+        (status = 409, description = "The SBOM was already ingested, reporting the existing one", body = IngestResult),
+        (status = 413, description = "The file exceeds the size limit", body = ErrorInformation),
     )
 )]
 #[post("/v2/sbom")]
 /// Upload a new SBOM
+///
+/// Uploading an SBOM which was already ingested doesn't ingest it again, but responds with the
+/// existing SBOM, flagged as a duplicate.
+///
+/// Uploading an SBOM as an update keeps the superseded SBOM, which is recorded as the predecessor
+/// of the uploaded one.
 pub async fn upload(
     service: web::Data<IngestorService>,
     config: web::Data<Config>,
-    web::Query(UploadQuery { labels }): web::Query<UploadQuery>,
+    settings: web::Data<SettingsService>,
+    web::Query(UploadQuery {
+        mode,
+        external_id,
+        format,
+        labels,
+        update,
+    }): web::Query<UploadQuery>,
     content_type: Option<web::Header<header::ContentType>>,
-    bytes: web::Bytes,
+    VerifiedBytes(bytes): VerifiedBytes,
+    actor: Actor,
     _: Require<CreateSbom>,
+    tenant: Tenant,
 ) -> Result<impl Responder, Error> {
+    let format = Format::SBOM.with_override(format)?;
+    let config = config.apply(&settings.current().await?);
+    let labels = tenant.stamp(labels);
     let bytes = decompress_async(bytes, content_type.map(|ct| ct.0), config.upload_limit).await??;
-    let result = service.ingest(&bytes, Format::SBOM, labels, None).await?;
+    let upload = match update {
+        true => Upload::new(actor).details(json!({ "update": true })),
+        false => Upload::new(actor),
+    };
+
+    if mode == IngestionMode::Async {
+        if update {
+            return Err(Error::BadRequest(
+                "Updating an SBOM is only supported for synchronous ingestion".into(),
+            ));
+        }
+        let job_id = service
+            .submit(&bytes, format, labels, external_id, Some(&upload))
+            .await?;
+        log::info!("Submitted SBOM for ingestion: {job_id}");
+        return Ok(HttpResponse::Accepted()
+            .insert_header((header::LOCATION, format!("/api/v2/job/{job_id}")))
+            .json(IngestionJobAccepted { job_id }));
+    }
+
+    let result = if update {
+        service
+            .update_sbom(&bytes, format, labels, external_id, Some(&upload))
+            .await?
+    } else {
+        service
+            .ingest_with_external_id(&bytes, format, labels, None, external_id, Some(&upload))
+            .await?
+    };
+    if result.duplicate {
+        log::info!("SBOM already ingested: {}", result.id);
+        return Ok(HttpResponse::Conflict().json(result));
+    }
     log::info!("Uploaded SBOM: {}", result.id);
     Ok(HttpResponse::Created().json(result))
 }
@@ -446,7 +974,10 @@ pub async fn upload(
     ),
     responses(
         (status = 200, description = "Download a an SBOM", body = inline(BinaryData)),
-        (status = 404, description = "The document could not be found"),
+        (status = 206, description = "Download a range of the document", body = inline(BinaryData)),
+        (status = 304, description = "The document was not modified"),
+        (status = 404, description = "The document could not be found", body = ErrorInformation),
+        (status = 416, description = "The requested range can't be satisfied", body = ErrorInformation),
     )
 )]
 #[get("/v2/sbom/{key}/download")]
@@ -454,31 +985,34 @@ pub async fn download(
     ingestor: web::Data<IngestorService>,
     db: web::Data<Database>,
     sbom: web::Data<SbomService>,
+    audit: web::Data<AuditService>,
     key: web::Path<String>,
+    preconditions: Preconditions,
+    user: UserInformation,
     _: Require<ReadSbom>,
+    tenant: Tenant,
 ) -> Result<impl Responder, Error> {
     let id = Id::from_str(&key).map_err(Error::IdKey)?;
+    let id = tenant.require_sbom(&id, db.read()).await?;
 
-    let Some(sbom) = sbom.fetch_sbom_summary(id, db.as_ref()).await? else {
+    let Some(sbom) = sbom.fetch_sbom_summary(id, db.read()).await? else {
         return Ok(HttpResponse::NotFound().finish());
     };
 
-    if let Some(doc) = &sbom.source_document {
-        let storage_key = doc.try_into()?;
-
-        let stream = ingestor
-            .storage()
-            .clone()
-            .retrieve(storage_key)
-            .await
-            .map_err(Error::Storage)?
-            .map(|stream| stream.map_err(Error::Storage));
+    let Some(doc) = &sbom.source_document else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
 
-        Ok(match stream {
-            Some(s) => HttpResponse::Ok().streaming(s),
-            None => HttpResponse::NotFound().finish(),
-        })
-    } else {
-        Ok(HttpResponse::NotFound().finish())
+    let response = download(ingestor.storage(), doc, &preconditions).await?;
+    // not modified or failed responses don't count as downloads
+    if let Some(bytes) = preconditions
+        .served_bytes(doc)
+        .filter(|_| response.status().is_success())
+    {
+        audit
+            .record_download(user.id(), DocumentKind::Sbom, sbom.head.id, bytes)
+            .await?;
     }
+
+    Ok(response)
 }
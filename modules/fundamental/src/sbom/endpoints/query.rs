@@ -14,6 +14,60 @@ pub struct ExternalReferenceQuery {
     pub cpe: Option<Cpe>,
 }
 
+#[derive(Clone, Debug, serde::Deserialize, utoipa::IntoParams)]
+pub struct StatusPackagesQuery {
+    /// The identifier of the vulnerability
+    pub vulnerability: String,
+    /// The status of the vulnerability
+    pub status: String,
+    /// The context of the status
+    #[serde(default)]
+    pub context: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize, utoipa::IntoParams)]
+pub struct LatestQuery {
+    /// Only return the newest SBOM of each product, or of each name if the SBOM isn't linked to
+    /// a product
+    #[serde(default)]
+    pub latest: bool,
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize, utoipa::IntoParams)]
+pub struct ChecksumQuery {
+    /// Find by SHA-256 checksum
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Find by SHA-384 checksum
+    #[serde(default)]
+    pub sha384: Option<String>,
+    /// Find by SHA-512 checksum
+    #[serde(default)]
+    pub sha512: Option<String>,
+    /// Find by SHA-1 checksum
+    #[serde(default)]
+    pub sha1: Option<String>,
+    /// Find by MD5 checksum
+    #[serde(default)]
+    pub md5: Option<String>,
+}
+
+impl ChecksumQuery {
+    /// The provided checksums, as pairs of algorithm and value.
+    pub fn checksums(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("SHA-256", &self.sha256),
+            ("SHA-384", &self.sha384),
+            ("SHA-512", &self.sha512),
+            ("SHA-1", &self.sha1),
+            ("MD5", &self.md5),
+        ]
+        .into_iter()
+        .filter_map(|(algorithm, value)| value.as_deref().map(|value| (algorithm, value)))
+        .collect()
+    }
+}
+
 #[derive(Debug)]
 pub struct ExternalReferenceQueryParseError(ExternalReferenceQuery);
 
@@ -1,18 +1,37 @@
 use crate::{
-    sbom::model::{SbomPackage, SbomSummary},
-    test::caller,
+    endpoints::Config,
+    sbom::model::{
+        SbomChecksumMatch, SbomFile, SbomPackage, SbomPackageOccurrence, SbomPackageRelation,
+        SbomSummary,
+    },
+    test::{caller, caller_with, tenant_isolation, tenant_user},
 };
 use actix_http::StatusCode;
 use actix_web::test::TestRequest;
+use base64::{
+    Engine,
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+};
 use flate2::bufread::GzDecoder;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, sea_query::Expr};
 use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
 use std::io::Read;
 use test_context::test_context;
 use test_log::test;
-use trustify_common::{id::Id, model::PaginatedResults};
-use trustify_entity::labels::Labels;
+use time::OffsetDateTime;
+use trustify_auth::authenticator::user::UserDetails;
+use trustify_common::{
+    digest::DIGEST,
+    id::Id,
+    model::PaginatedResults,
+    signing::{ResponseSigner, SIGNATURE_HEADER, SigningKey, canonical_json},
+};
+use trustify_entity::{advisory, labels::Labels, product_version, relationship::Relationship};
 use trustify_module_ingestor::model::IngestResult;
-use trustify_test_context::{TrustifyContext, call::CallService, document_bytes};
+use trustify_test_context::{
+    TrustifyContext, auth::TestAuthentication, call::CallService, document_bytes,
+};
 use urlencoding::encode;
 use uuid::Uuid;
 
@@ -64,6 +83,136 @@ async fn upload(ctx: &TrustifyContext) -> anyhow::Result<()> {
     let result: IngestResult = actix_web::test::read_body_json(response).await;
     log::debug!("ID: {result:?}");
     assert!(matches!(result.id, Id::Uuid(_)));
+    assert!(!result.duplicate);
+
+    // uploading it again reports the existing SBOM
+    let request = TestRequest::post()
+        .uri("/api/v2/sbom")
+        .set_payload(document_bytes("quarkus-bom-2.13.8.Final-redhat-00004.json").await?)
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let duplicate: IngestResult = actix_web::test::read_body_json(response).await;
+    assert!(duplicate.duplicate);
+    assert_eq!(duplicate.id, result.id);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn upload_digest(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller(ctx).await?;
+    let bytes = document_bytes("zookeeper-3.9.2-cyclonedx.json").await?;
+
+    // a digest not matching the content rejects the upload
+
+    let request = TestRequest::post()
+        .uri("/api/v2/sbom")
+        .insert_header((
+            DIGEST,
+            format!("sha-256={}", STANDARD.encode(Sha256::digest(b"other"))),
+        ))
+        .set_payload(bytes.clone())
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let error: Value = actix_web::test::read_body_json(response).await;
+    assert_eq!(error["error"], "DigestMismatch");
+
+    // a matching digest accepts it
+
+    let request = TestRequest::post()
+        .uri("/api/v2/sbom")
+        .insert_header((
+            DIGEST,
+            format!("sha-256={}", STANDARD.encode(Sha256::digest(&bytes))),
+        ))
+        .set_payload(bytes)
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn upload_update(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller(ctx).await?;
+    let mut document: Value =
+        serde_json::from_slice(&document_bytes("cyclonedx/maven.cdx.json").await?)?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/sbom?labels.team=a")
+        .set_payload(serde_json::to_vec(&document)?)
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let first: IngestResult = actix_web::test::read_body_json(response).await;
+    let Id::Uuid(first_id) = first.id else {
+        anyhow::bail!("must be a UUID");
+    };
+
+    // link it to a version of a product
+
+    let product = ctx
+        .graph
+        .ingest_product("Test product", (), &ctx.db)
+        .await?;
+    product
+        .ingest_product_version("1.0.0".into(), Some(first_id), &ctx.db)
+        .await?;
+
+    // updating it asynchronously is not supported
+
+    document["version"] = json!(2);
+    let request = TestRequest::post()
+        .uri("/api/v2/sbom?mode=async&update=true")
+        .set_payload(serde_json::to_vec(&document)?)
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // upload the next version as an update
+
+    let request = TestRequest::post()
+        .uri("/api/v2/sbom?update=true&labels.build=2")
+        .set_payload(serde_json::to_vec(&document)?)
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let second: IngestResult = actix_web::test::read_body_json(response).await;
+    assert_ne!(first.id, second.id);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{}", second.id))
+        .to_request();
+    let sbom: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(sbom["predecessor"], first.id.to_string());
+    assert_eq!(sbom["labels"]["team"], "a");
+    assert_eq!(sbom["labels"]["build"], "2");
+
+    // the product version now links to the update
+
+    let version = product_version::Entity::find()
+        .filter(product_version::Column::ProductId.eq(product.product.id))
+        .one(&ctx.db)
+        .await?
+        .expect("must exist");
+    assert_eq!(Some(second.id.clone()), version.sbom_id.map(Id::Uuid));
+
+    // the predecessor is kept
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{}", first.id))
+        .to_request();
+    let sbom: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(sbom["labels"]["team"], "a");
+    assert_eq!(sbom["predecessor"], Value::Null);
 
     Ok(())
 }
@@ -89,6 +238,74 @@ async fn get_sbom(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn list_sparse_fields(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let id = ctx
+        .ingest_document("spdx/quarkus-bom-3.2.11.Final-redhat-00001.json")
+        .await?
+        .id
+        .to_string();
+
+    let req = TestRequest::get()
+        .uri("/api/v2/sbom?fields=id,name,unknown")
+        .to_request();
+    let result: Value = app.call_and_read_body_json(req).await;
+    log::debug!("{result:#?}");
+
+    assert_eq!(result["total"], 1);
+    assert_eq!(
+        result["items"][0],
+        json!({
+            "id": id,
+            "name": "quarkus-bom-3.2.11.Final-redhat-00001",
+        })
+    );
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn list_as_csv_and_ndjson(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let id = ctx
+        .ingest_document("spdx/quarkus-bom-3.2.11.Final-redhat-00001.json")
+        .await?
+        .id
+        .to_string();
+
+    let req = TestRequest::get()
+        .uri("/api/v2/sbom?fields=id,name")
+        .insert_header(("Accept", "text/csv"))
+        .to_request();
+    let response = app.call_service(req).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("Content-Type").unwrap(), "text/csv");
+    let body = actix_web::test::read_body(response).await;
+    assert_eq!(
+        String::from_utf8(body.to_vec())?,
+        format!("id,name\n{id},quarkus-bom-3.2.11.Final-redhat-00001\n")
+    );
+
+    let req = TestRequest::get()
+        .uri("/api/v2/sbom?fields=id")
+        .insert_header(("Accept", "application/x-ndjson"))
+        .to_request();
+    let response = app.call_service(req).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = actix_web::test::read_body(response).await;
+    let lines = body
+        .split(|b| *b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(serde_json::from_slice)
+        .collect::<Result<Vec<Value>, _>>()?;
+    assert_eq!(lines, vec![json!({ "id": id })]);
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn filter_packages(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
@@ -268,6 +485,259 @@ async fn get_advisories(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn get_csaf(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let id = ctx
+        .ingest_documents([
+            "quarkus-bom-2.13.8.Final-redhat-00004.json",
+            "csaf/cve-2023-0044.json",
+        ])
+        .await?[0]
+        .id
+        .to_string();
+
+    let app = caller(ctx).await?;
+    let response = app
+        .call_service(
+            TestRequest::get()
+                .uri(&format!("/api/v2/sbom/{id}/csaf"))
+                .to_request(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-disposition").unwrap(),
+        "attachment; filename=\"quarkus-bom_vex.json\""
+    );
+
+    let v: Value = actix_web::test::read_body_json(response).await;
+    log::debug!("{v:#?}");
+
+    assert_eq!(v["document"]["category"], "csaf_vex");
+    assert_eq!(v["document"]["csaf_version"], "2.0");
+    assert_eq!(v["document"]["title"], "VEX for quarkus-bom");
+
+    let vulnerabilities = v["vulnerabilities"].as_array().unwrap();
+    assert_eq!(vulnerabilities.len(), 1);
+    assert_eq!(vulnerabilities[0]["cve"], "CVE-2023-0044");
+
+    // all products referenced by the statuses are part of the product tree
+    let products = v["product_tree"]["full_product_names"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|product| product["product_id"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    let affected = vulnerabilities[0]["product_status"]["known_affected"]
+        .as_array()
+        .unwrap();
+    assert!(!affected.is_empty());
+    for product in affected {
+        assert!(products.contains(&product.as_str().unwrap()));
+    }
+
+    // unknown SBOMs can't be exported
+    let response = app
+        .call_service(
+            TestRequest::get()
+                .uri(&format!("/api/v2/sbom/{}/csaf", Uuid::now_v7()))
+                .to_request(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn get_advisories_signed(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let id = ctx
+        .ingest_documents([
+            "quarkus-bom-2.13.8.Final-redhat-00004.json",
+            "csaf/cve-2023-0044.json",
+        ])
+        .await?[0]
+        .id
+        .to_string();
+
+    let signer = ResponseSigner::new(Some(SigningKey::generate()?));
+    let app = caller_with(
+        ctx,
+        Config {
+            signer: signer.clone(),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let response = app
+        .call_service(
+            TestRequest::get()
+                .uri(&format!("/api/v2/sbom/{id}/advisory"))
+                .to_request(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let jws = response
+        .headers()
+        .get(SIGNATURE_HEADER)
+        .expect("must be signed")
+        .to_str()?
+        .to_string();
+    let (header, _) = jws.split_once("..").expect("must be detached");
+    let header: Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header)?)?;
+    assert_eq!(header["kid"], signer.kid().expect("must have a key"));
+
+    // the body must already be canonical
+    let body = actix_web::test::read_body(response).await;
+    let v: Value = serde_json::from_slice(&body)?;
+    assert_eq!(canonical_json(&v)?, body.as_ref());
+    assert_eq!(v[0]["identifier"], "https://www.redhat.com/#CVE-2023-0044");
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn get_advisories_withdrawn(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let result = ctx
+        .ingest_documents([
+            "quarkus-bom-2.13.8.Final-redhat-00004.json",
+            "csaf/cve-2023-0044.json",
+        ])
+        .await?;
+    let id = result[0].id.to_string();
+    let Id::Uuid(advisory) = result[1].id else {
+        panic!("unexpected ID: {}", result[1].id);
+    };
+
+    advisory::Entity::update_many()
+        .col_expr(
+            advisory::Column::Withdrawn,
+            Expr::value(OffsetDateTime::now_utc()),
+        )
+        .filter(advisory::Column::Id.eq(advisory))
+        .exec(&ctx.db)
+        .await?;
+
+    let app = caller(ctx).await?;
+
+    let uri = format!("/api/v2/sbom/{id}/advisory");
+    let v: Value = app
+        .call_and_read_body_json(TestRequest::get().uri(&uri).to_request())
+        .await;
+    assert_eq!(v, json!([]));
+
+    let uri = format!("/api/v2/sbom/{id}/advisory?include_withdrawn=true");
+    let v: Value = app
+        .call_and_read_body_json(TestRequest::get().uri(&uri).to_request())
+        .await;
+    assert_eq!(v[0]["identifier"], "https://www.redhat.com/#CVE-2023-0044");
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn get_advisory_packages(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let id = ctx
+        .ingest_documents([
+            "quarkus-bom-2.13.8.Final-redhat-00004.json",
+            "csaf/cve-2023-0044.json",
+        ])
+        .await?[0]
+        .id
+        .to_string();
+
+    let app = caller(ctx).await?;
+    let v: Value = app
+        .call_and_read_body_json(
+            TestRequest::get()
+                .uri(&format!("/api/v2/sbom/{id}/advisory"))
+                .to_request(),
+        )
+        .await;
+
+    let advisory = v[0]["uuid"].as_str().expect("must have a UUID");
+    let status = &v[0]["status"][0];
+    let mut expected: Vec<String> = status["packages"]
+        .as_array()
+        .expect("must have packages")
+        .iter()
+        .filter_map(|package| package["id"].as_str().map(ToString::to_string))
+        .collect();
+    expected.dedup();
+
+    let mut uri = format!(
+        "/api/v2/sbom/{id}/advisory/{advisory}/package?vulnerability={}&status={}",
+        encode(
+            status["vulnerability"]["identifier"]
+                .as_str()
+                .unwrap_or_default()
+        ),
+        encode(status["status"].as_str().unwrap_or_default()),
+    );
+    if let Some(context) = status["context"]["cpe"].as_str() {
+        uri.push_str(&format!("&context={}", encode(context)));
+    }
+
+    // all packages, the same as embedded in the details
+
+    let result: PaginatedResults<SbomPackage> = app
+        .call_and_read_body_json(
+            TestRequest::get()
+                .uri(&format!("{uri}&limit=0"))
+                .to_request(),
+        )
+        .await;
+    let mut ids: Vec<String> = result.items.iter().map(|p| p.id.clone()).collect();
+    ids.dedup();
+    assert_eq!(ids, expected);
+
+    // a single page
+
+    let page: PaginatedResults<SbomPackage> = app
+        .call_and_read_body_json(
+            TestRequest::get()
+                .uri(&format!("{uri}&limit=1"))
+                .to_request(),
+        )
+        .await;
+    assert_eq!(page.total, result.total);
+    assert_eq!(page.items, result.items[..1]);
+
+    // unknown advisories and statuses
+
+    let request = TestRequest::get()
+        .uri(&uri.replace(advisory, &Uuid::now_v7().to_string()))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = TestRequest::get()
+        .uri(&format!(
+            "/api/v2/sbom/{id}/advisory/{advisory}/package?vulnerability=CVE-0000-0000&status=affected"
+        ))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // an advisory ID which isn't a UUID
+
+    let request = TestRequest::get()
+        .uri(&format!(
+            "/api/v2/sbom/{id}/advisory/foo/package?vulnerability=CVE-0000-0000&status=affected"
+        ))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn query_sboms_by_ingested_time(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
@@ -341,3 +811,437 @@ async fn query_sboms_by_package(ctx: &TrustifyContext) -> Result<(), anyhow::Err
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn count_sboms(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    ctx.ingest_documents([
+        "spdx/simple-ext-a.json",
+        "spdx/simple-ext-b.json",
+        "zookeeper-3.9.2-cyclonedx.json",
+    ])
+    .await?;
+    let app = caller(ctx).await?;
+
+    let query = async |query: &str| {
+        let uri = format!("/api/v2/sbom?{query}");
+        let request = TestRequest::get().uri(&uri).to_request();
+        let response: PaginatedResults<SbomSummary> = app.call_and_read_body_json(request).await;
+        response
+    };
+
+    // exact, the default
+
+    let result = query("limit=2").await;
+    assert_eq!(result.items.len(), 2);
+    assert_eq!(result.total, 3);
+
+    let result = query("limit=2&count=exact").await;
+    assert_eq!(result.total, 3);
+
+    // not counting, only reports the items up to the end of the page
+
+    let result = query("limit=2&count=none").await;
+    assert_eq!(result.items.len(), 2);
+    assert_eq!(result.total, 2);
+
+    let result = query("offset=2&limit=2&count=none").await;
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.total, 3);
+
+    let result = query("offset=5&limit=2&count=none").await;
+    assert_eq!(result.items.len(), 0);
+    assert_eq!(result.total, 3);
+
+    // the estimate depends on the table statistics, but must not change the page
+
+    let result = query("limit=2&count=estimated").await;
+    assert_eq!(result.items.len(), 2);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn tenant_isolation(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller_with(
+        ctx,
+        Config {
+            tenant_isolation: true,
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let tenant = |tenant: &str| UserDetails {
+        id: format!("user-{tenant}"),
+        permissions: vec![],
+        tenant: Some(tenant.into()),
+    };
+
+    // the tenant label of the upload gets replaced with the tenant of the user
+    let request = TestRequest::post()
+        .uri("/api/v2/sbom?labels.tenant=b")
+        .set_payload(document_bytes("zookeeper-3.9.2-cyclonedx.json").await?)
+        .to_request()
+        .test_auth_details(tenant("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let result: IngestResult = actix_web::test::read_body_json(response).await;
+    let uri = format!("/api/v2/sbom/{}", result.id);
+
+    let request = TestRequest::get()
+        .uri(&uri)
+        .to_request()
+        .test_auth_details(tenant("a"));
+    let sbom: SbomSummary = app.call_and_read_body_json(request).await;
+    assert_eq!(sbom.head.labels.0.get("tenant"), Some(&"a".to_string()));
+
+    // other tenants can neither list nor access it
+    let request = TestRequest::get()
+        .uri("/api/v2/sbom")
+        .to_request()
+        .test_auth_details(tenant("b"));
+    let result: PaginatedResults<SbomSummary> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 0);
+
+    let request = TestRequest::get()
+        .uri(&uri)
+        .to_request()
+        .test_auth_details(tenant("b"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // nor change its tenant
+    let request = TestRequest::patch()
+        .uri(&format!("{uri}/label"))
+        .set_json(json!({"tenant": "b"}))
+        .to_request()
+        .test_auth_details(tenant("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::get()
+        .uri("/api/v2/sbom")
+        .to_request()
+        .test_auth_details(tenant("a"));
+    let result: PaginatedResults<SbomSummary> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 1);
+
+    // users without a tenant are rejected
+    let request = TestRequest::get()
+        .uri("/api/v2/sbom")
+        .to_request()
+        .test_auth("user-c");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn tenant_duplicates(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller_with(ctx, tenant_isolation()).await?;
+    let bytes = document_bytes("zookeeper-3.9.2-cyclonedx.json").await?;
+
+    let upload = async |tenant: &str| {
+        let request = TestRequest::post()
+            .uri("/api/v2/sbom")
+            .set_payload(bytes.clone())
+            .to_request()
+            .test_auth_details(tenant_user(tenant));
+        let response = app.call_service(request).await;
+        let status = response.status();
+        let result: IngestResult = actix_web::test::read_body_json(response).await;
+        (status, result.id)
+    };
+
+    // the same document, uploaded twice by tenant a, is a duplicate
+    let (status, a) = upload("a").await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(upload("a").await, (StatusCode::CONFLICT, a.clone()));
+
+    // but a new document for tenant b, not revealing the one of tenant a
+    let (status, b) = upload("b").await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_ne!(a, b);
+    assert_eq!(upload("b").await, (StatusCode::CONFLICT, b.clone()));
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{b}"))
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn tenant_same_document(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller_with(ctx, tenant_isolation()).await?;
+    let bytes = document_bytes("zookeeper-3.9.2-cyclonedx.json").await?;
+    let sha256 = hex::encode(Sha256::digest(&bytes));
+
+    let mut ids = vec![];
+    for tenant in ["a", "b"] {
+        let request = TestRequest::post()
+            .uri("/api/v2/sbom")
+            .set_payload(bytes.clone())
+            .to_request()
+            .test_auth_details(tenant_user(tenant));
+        let result: IngestResult = app.call_and_read_body_json(request).await;
+        ids.push(result.id);
+    }
+
+    // each tenant finds its own copy by digest, despite the copy of the other tenant
+
+    for (tenant, id) in ["a", "b"].into_iter().zip(ids) {
+        let request = TestRequest::default()
+            .method(actix_web::http::Method::HEAD)
+            .uri(&format!("/api/v2/sbom?sha256={sha256}"))
+            .to_request()
+            .test_auth_details(tenant_user(tenant));
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = TestRequest::get()
+            .uri(&format!("/api/v2/sbom/sha256:{sha256}"))
+            .to_request()
+            .test_auth_details(tenant_user(tenant));
+        let sbom: SbomSummary = app.call_and_read_body_json(request).await;
+        assert_eq!(Id::Uuid(sbom.head.id), id);
+
+        let request = TestRequest::get()
+            .uri(&format!("/api/v2/sbom/sha256:{sha256}/download"))
+            .to_request()
+            .test_auth_details(tenant_user(tenant));
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn package_files(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let id = ctx
+        .ingest_document("spdx/issue-552.json")
+        .await?
+        .id
+        .to_string();
+
+    let uri = format!("/api/v2/sbom/{id}/packages/SPDXRef-Package/files");
+    let request = TestRequest::get().uri(&uri).to_request();
+    let result: PaginatedResults<SbomFile> = app.call_and_read_body_json(request).await;
+
+    assert_eq!(result.total, 1);
+    let file = &result.items[0];
+    assert_eq!(file.id, "SPDXRef-JenaLib");
+    assert_eq!(file.name, "./lib-source/jena-2.6.3-sources.jar");
+    assert!(file.checksums.contains_key("SHA-1"));
+    assert_eq!(file.concluded_licenses.len(), 1);
+    assert_eq!(file.declared_licenses.len(), 1);
+
+    // unknown packages are reported as such
+    let uri = format!("/api/v2/sbom/{id}/packages/SPDXRef-Unknown/files");
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn lookup_checksum(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let id = ctx
+        .ingest_document("cyclonedx/openssl-3.0.7-18.el9_2.cdx_1.6.sbom.json")
+        .await?
+        .id;
+
+    // checksums are compared case-insensitively
+    let uri = "/api/v2/sbom/lookup?sha256=9215C64E7289A058248728089E4D98ED1CC392BB5EB9B8FCBE661D57E8145BBD";
+    let request = TestRequest::get().uri(uri).to_request();
+    let result: PaginatedResults<SbomChecksumMatch> = app.call_and_read_body_json(request).await;
+
+    // the metadata component may carry the same checksum
+    assert!(result.total >= 1);
+    for item in &result.items {
+        assert_eq!(Id::Uuid(item.sbom_id), id);
+        assert_eq!(item.name, "openssl");
+        assert_eq!(item.algorithm, "SHA-256");
+    }
+    assert!(
+        result
+            .items
+            .iter()
+            .any(|item| item.node_id == "pkg:rpm/redhat/openssl@3.0.7-18.el9_2?arch=src")
+    );
+
+    // at least one checksum is required
+    let request = TestRequest::get().uri("/api/v2/sbom/lookup").to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn search_packages(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let spdx = ctx
+        .ingest_document("spdx/openssl-3.0.7-18.el9_2.spdx.json")
+        .await?;
+    let cyclonedx = ctx
+        .ingest_document("cyclonedx/openssl-3.0.7-18.el9_2.cdx_1.6.sbom.json")
+        .await?;
+
+    let uri = format!(
+        "/api/v2/package?q={}",
+        urlencoding::encode("name=openssl&version=3.0.7")
+    );
+    let request = TestRequest::get().uri(&uri).to_request();
+    let result: PaginatedResults<SbomPackageOccurrence> =
+        app.call_and_read_body_json(request).await;
+
+    assert!(result.total >= 2);
+    for item in &result.items {
+        assert_eq!(item.package.name, "openssl");
+        assert_eq!(item.package.version.as_deref(), Some("3.0.7"));
+    }
+
+    // packages of both SBOMs are found
+    let sboms = result
+        .items
+        .iter()
+        .map(|item| Id::Uuid(item.sbom_id))
+        .collect::<Vec<_>>();
+    assert!(sboms.contains(&spdx.id));
+    assert!(sboms.contains(&cyclonedx.id));
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn related_inverse(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let id = ctx
+        .ingest_document("spdx/simple.json")
+        .await?
+        .id
+        .to_string();
+
+    let related = async |query: &str| {
+        let uri = format!("/api/v2/sbom/{id}/related?{query}");
+        let request = TestRequest::get().uri(&uri).to_request();
+        let result: PaginatedResults<SbomPackageRelation> =
+            app.call_and_read_body_json(request).await;
+        let mut ids = result
+            .items
+            .into_iter()
+            .map(|item| item.package.id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids
+    };
+
+    assert_eq!(
+        related("reference=SPDXRef-BB&relationships=contains").await,
+        vec!["SPDXRef-CC", "SPDXRef-DD"]
+    );
+    // the SBOM encoded the relationship as "CC contained by BB"
+    assert_eq!(
+        related("reference=SPDXRef-CC&relationships=contained_by").await,
+        vec!["SPDXRef-BB"]
+    );
+    assert!(
+        related("reference=SPDXRef-BB&exclude=contains")
+            .await
+            .is_empty()
+    );
+
+    // mixing directions, or using unknown relationships, is rejected
+    for query in [
+        "relationships=contains,contained_by",
+        "relationships=unknown",
+        "exclude=unknown",
+    ] {
+        let uri = format!("/api/v2/sbom/{id}/related?{query}");
+        let request = TestRequest::get().uri(&uri).to_request();
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{query}");
+    }
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn related_original(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let [simple, issue_552] = [
+        ctx.ingest_document("spdx/simple.json").await?.id,
+        ctx.ingest_document("spdx/issue-552.json").await?.id,
+    ];
+
+    let related = async |id: &Id, query: &str| {
+        let uri = format!("/api/v2/sbom/{id}/related?{query}");
+        let request = TestRequest::get().uri(&uri).to_request();
+        let result: PaginatedResults<SbomPackageRelation> =
+            app.call_and_read_body_json(request).await;
+        let mut related = result
+            .items
+            .into_iter()
+            .map(|item| {
+                (
+                    item.package.id,
+                    item.relationship,
+                    item.original_relationship,
+                )
+            })
+            .collect::<Vec<_>>();
+        related.sort_by(|a, b| a.0.cmp(&b.0));
+        related
+    };
+
+    // relationships are normalized, but keep the name they had in the SBOM
+    assert_eq!(
+        related(&simple, "reference=SPDXRef-BB").await,
+        vec![
+            (
+                "SPDXRef-CC".to_string(),
+                Relationship::Contains,
+                Some("CONTAINED_BY".to_string())
+            ),
+            (
+                "SPDXRef-DD".to_string(),
+                Relationship::Contains,
+                Some("CONTAINED_BY".to_string())
+            ),
+        ]
+    );
+
+    // types we don't know are stored as "undefined"
+    assert_eq!(
+        related(
+            &issue_552,
+            "reference=SPDXRef-Package&relationships=undefined"
+        )
+        .await,
+        vec![(
+            "SPDXRef-Saxon".to_string(),
+            Relationship::Undefined,
+            Some("DYNAMIC_LINK".to_string())
+        )]
+    );
+
+    Ok(())
+}
@@ -2,18 +2,22 @@ use super::SbomSummary;
 use crate::{
     Error,
     advisory::model::AdvisoryHead,
+    attachment::model::Attachment,
     purl::model::{details::purl::StatusContext, summary::purl::PurlSummary},
     sbom::{
         model::SbomPackage,
-        service::{SbomService, sbom::QueryCatcher},
+        service::{DetailsOptions, SbomService, sbom::QueryCatcher},
     },
+    tenant::Tenant,
+    triage::model::TriageState,
     vulnerability::model::VulnerabilityHead,
 };
 use cpe::{cpe::Cpe, uri::OwnedUri};
 use futures_util::{Stream, pin_mut, stream::StreamExt};
 use sea_orm::{
-    Condition, ConnectionTrait, DbBackend, DbErr, FromQueryResult, JoinType, ModelTrait,
-    QueryFilter, QuerySelect, RelationTrait, Statement, StreamTrait,
+    ColumnTrait, Condition, ConnectionTrait, DbBackend, DbErr, EntityTrait, FromQueryResult,
+    JoinType, ModelTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, Statement,
+    StreamTrait,
 };
 use sea_query::{Asterisk, Expr, Func, Query, SimpleExpr};
 use serde::{Deserialize, Serialize};
@@ -27,18 +31,38 @@ use trustify_common::{
 };
 use trustify_cvss::cvss3::{Cvss3Base, score::Score, severity::Severity};
 use trustify_entity::{
-    advisory, advisory_vulnerability, base_purl, cvss3, purl_status, qualified_purl, sbom,
-    sbom_node, sbom_package, sbom_package_cpe_ref, sbom_package_purl_ref, status, version_range,
-    versioned_purl, vulnerability,
+    advisory, advisory_vulnerability, attachment, base_purl, cvss3, ingestion_warning, purl_status,
+    qualified_purl, sbom, sbom_node, sbom_package, sbom_package_cpe_ref, sbom_package_purl_ref,
+    sbom_predecessor, severity_override, status, triage, version_range, versioned_purl,
+    vulnerability,
 };
+use trustify_module_ingestor::common::{Deprecation, Withdrawal};
+use urlencoding::encode;
 use utoipa::ToSchema;
+use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SbomDetails {
     #[serde(flatten)]
     pub summary: SbomSummary,
 
+    /// Advisories affecting the SBOM, ordered by their identifier.
     pub advisories: Vec<SbomAdvisory>,
+
+    /// Warnings reported while ingesting the SBOM.
+    pub warnings: Vec<String>,
+
+    /// Files attached to the SBOM, oldest first.
+    pub attachments: Vec<Attachment>,
+
+    /// The SBOM superseded by this one, in case it got uploaded as an update.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "trustify_common::uuid::serde::urn"
+    )]
+    #[schema(value_type = String)]
+    pub predecessor: Option<Uuid>,
 }
 
 impl SbomDetails {
@@ -48,11 +72,18 @@ impl SbomDetails {
         (sbom, node): (sbom::Model, Option<sbom_node::Model>),
         service: &SbomService,
         tx: &C,
-        statuses: Vec<String>,
+        options: DetailsOptions,
+        tenant: &Tenant,
     ) -> Result<Option<SbomDetails>, Error>
     where
         C: ConnectionTrait + StreamTrait,
     {
+        let DetailsOptions {
+            statuses,
+            deprecation,
+            withdrawal,
+        } = options;
+
         let summary = match SbomSummary::from_entity((sbom.clone(), node), service, tx).await? {
             Some(summary) => summary,
             None => return Ok(None),
@@ -111,6 +142,10 @@ impl SbomDetails {
             ))
             .join(JoinType::LeftJoin, purl_status::Relation::ContextCpe.def())
             .join(JoinType::Join, purl_status::Relation::Advisory.def())
+            .filter(advisory::Column::DeletedAt.is_null())
+            .filter(deprecation.condition())
+            .filter(withdrawal.condition(purl_status::Column::AdvisoryId))
+            .filter(tenant.advisory_condition(purl_status::Column::AdvisoryId))
             .join(JoinType::LeftJoin, advisory::Relation::Issuer.def())
             .join(
                 JoinType::Join,
@@ -147,6 +182,8 @@ impl SbomDetails {
                 "advisory"."title" AS "advisory$title",
                 "advisory"."labels" AS "advisory$labels",
                 "advisory"."source_document_id" AS "advisory$source_document_id",
+                "advisory"."external_id" AS "advisory$external_id",
+                "advisory"."deleted_at" AS "advisory$deleted_at",
                 "advisory_vulnerability"."advisory_id" AS "advisory_vulnerability$advisory_id",
                 "advisory_vulnerability"."vulnerability_id" AS "advisory_vulnerability$vulnerability_id",
                 "advisory_vulnerability"."title" AS "advisory_vulnerability$title",
@@ -215,14 +252,22 @@ impl SbomDetails {
             JOIN "vulnerability" ON "advisory_vulnerability"."vulnerability_id" = "vulnerability"."id"
             WHERE
             "sbom"."sbom_id" = $1
+            AND "advisory"."deleted_at" IS NULL
             AND ($2::text[] = ARRAY[]::text[] OR "status"."slug" = ANY($2::text[]))
+            AND ($3 OR "advisory"."withdrawn" IS NULL)
+            AND ($4 OR NOT "advisory"."deprecated")
             "#;
 
         let result = tx
             .stream(Statement::from_sql_and_values(
                 DbBackend::Postgres,
                 product_advisory_info,
-                [sbom.sbom_id.into(), statuses.into()],
+                [
+                    sbom.sbom_id.into(),
+                    statuses.into(),
+                    matches!(withdrawal, Withdrawal::Consider).into(),
+                    matches!(deprecation, Deprecation::Consider).into(),
+                ],
             ))
             .await?
             .map(|row| match row {
@@ -232,12 +277,68 @@ impl SbomDetails {
 
         let relevant_advisory_info = relevant_advisory_info.chain(result);
 
-        let advisories =
+        let mut advisories =
             SbomAdvisory::from_models(&summary.described_by, relevant_advisory_info, tx).await?;
+        // advisories of other tenants match the product statuses as well
+        advisories.retain(|advisory| tenant.owns(&advisory.head.labels));
+
+        let triage: HashMap<String, TriageState> = triage::Entity::find()
+            .filter(triage::Column::SbomId.eq(sbom.sbom_id))
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|triage| (triage.vulnerability_id, triage.state))
+            .collect();
+        let severity_overrides: HashMap<String, Severity> = severity_override::Entity::find()
+            .filter(
+                severity_override::Column::VulnerabilityId.is_in(
+                    advisories
+                        .iter()
+                        .flat_map(|advisory| &advisory.status)
+                        .map(SbomStatus::identifier),
+                ),
+            )
+            .filter(severity_override::Column::Tenant.eq(tenant.name().unwrap_or_default()))
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|severity_override| {
+                (
+                    severity_override.vulnerability_id,
+                    severity_override.severity.into(),
+                )
+            })
+            .collect();
+        for status in advisories
+            .iter_mut()
+            .flat_map(|advisory| &mut advisory.status)
+        {
+            status.triage = triage.get(status.identifier()).copied();
+            status.severity_override = severity_overrides.get(status.identifier()).copied();
+        }
+
+        let warnings = sbom
+            .find_related(ingestion_warning::Entity)
+            .order_by_asc(ingestion_warning::Column::Id)
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|warning| warning.message)
+            .collect();
+
+        let attachments = Attachment::find(attachment::Column::SbomId.eq(sbom.sbom_id), tx).await?;
+
+        let predecessor = sbom_predecessor::Entity::find_by_id(sbom.sbom_id)
+            .one(tx)
+            .await?
+            .map(|predecessor| predecessor.predecessor_id);
 
         Ok(Some(SbomDetails {
             summary,
             advisories,
+            warnings,
+            attachments,
+            predecessor,
         }))
     }
 }
@@ -246,6 +347,7 @@ impl SbomDetails {
 pub struct SbomAdvisory {
     #[serde(flatten)]
     pub head: AdvisoryHead,
+    /// Statuses of the advisory, ordered by vulnerability identifier, status, and context.
     pub status: Vec<SbomStatus>,
 }
 
@@ -264,16 +366,7 @@ impl SbomAdvisory {
 
         let mut advisories = HashMap::new();
 
-        let sbom_cpes = described_by
-            .iter()
-            .flat_map(|each| each.cpe.iter())
-            .flat_map(|e| {
-                let e = e.replace(":*:", "::");
-                let e = e.replace(":*", "");
-                let result = cpe::uri::Uri::parse(&e);
-                result.ok().map(|wfn| wfn.as_uri().to_owned())
-            })
-            .collect::<Vec<_>>();
+        let sbom_cpes = sbom_cpes(described_by);
 
         log::info!("CPEs: {}", sbom_cpes.len());
 
@@ -284,15 +377,7 @@ impl SbomAdvisory {
             let status_cpe = if let Some(status_cpe) = &each.context_cpe {
                 let status_cpe: Result<OwnedUri, _> = status_cpe.try_into();
                 if let Ok(status_cpe) = status_cpe {
-                    if sbom_cpes.iter().any(|sbom_cpe| {
-                        let status_version = status_cpe.version().to_string();
-                        let sbom_version = sbom_cpe.version().to_string();
-                        // This is a bit simplified logic, but it is tune with v1 parity.
-                        // We need to investigate this more and apply proper version matching in the future
-                        status_cpe.is_superset(sbom_cpe)
-                            || status_version == "*"
-                            || sbom_version.starts_with(&status_version)
-                    }) {
+                    if context_applies(&sbom_cpes, &status_cpe) {
                         // status context is applicable, keep truckin'
                     } else {
                         // status context excludes this one, skip over
@@ -388,19 +473,95 @@ impl SbomAdvisory {
             );
         }
 
-        Ok(advisories.into_values().collect::<Vec<_>>())
+        // statuses are collected from different queries, so order them once they are complete
+
+        let mut advisories = advisories.into_values().collect::<Vec<_>>();
+        advisories.sort_by(|a, b| a.head.cmp_identifier(&b.head));
+
+        for advisory in &mut advisories {
+            advisory.status.sort_by_cached_key(|status| {
+                (
+                    status.vulnerability.identifier.clone(),
+                    status.status.clone(),
+                    status.context.as_ref().map(StatusContext::sort_key),
+                )
+            });
+            for status in &mut advisory.status {
+                status.packages.sort_by(|a, b| a.id.cmp(&b.id));
+            }
+        }
+
+        Ok(advisories)
+    }
+
+    /// Limit the number of packages of each status, linking to the full list instead.
+    ///
+    /// A limit of zero means "unlimited".
+    pub fn truncate(&mut self, sbom_id: Uuid, limit: usize) {
+        if limit == 0 {
+            return;
+        }
+
+        for status in &mut self.status {
+            if status.packages.len() > limit {
+                status.packages.truncate(limit);
+                status.truncated = true;
+                status.packages_link = Some(status.packages_link(sbom_id, self.head.uuid));
+            }
+        }
     }
 }
 
+/// The CPEs of the packages describing an SBOM, used for checking the context of statuses.
+pub(crate) fn sbom_cpes(described_by: &[SbomPackage]) -> Vec<OwnedUri> {
+    described_by
+        .iter()
+        .flat_map(|each| each.cpe.iter())
+        .flat_map(|e| {
+            let e = e.replace(":*:", "::");
+            let e = e.replace(":*", "");
+            let result = cpe::uri::Uri::parse(&e);
+            result.ok().map(|wfn| wfn.as_uri().to_owned())
+        })
+        .collect()
+}
+
+/// Check if the context of a status applies to an SBOM, described by the provided CPEs.
+pub(crate) fn context_applies(sbom_cpes: &[OwnedUri], status_cpe: &OwnedUri) -> bool {
+    sbom_cpes.iter().any(|sbom_cpe| {
+        let status_version = status_cpe.version().to_string();
+        let sbom_version = sbom_cpe.version().to_string();
+        // This is a bit simplified logic, but it is tune with v1 parity.
+        // We need to investigate this more and apply proper version matching in the future
+        status_cpe.is_superset(sbom_cpe)
+            || status_version == "*"
+            || sbom_version.starts_with(&status_version)
+    })
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct SbomStatus {
     #[serde(flatten)]
     pub vulnerability: VulnerabilityHead,
     pub average_severity: Severity,
     pub average_score: f64,
+    /// The severity of the vulnerability as defined by an admin, overriding the average severity,
+    /// if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity_override: Option<Severity>,
     pub status: String,
     pub context: Option<StatusContext>,
+    /// Affected packages of the SBOM, ordered by their ID.
     pub packages: Vec<SbomPackage>,
+    /// If the list of packages was truncated, because it exceeded the configured limit.
+    #[serde(default)]
+    pub truncated: bool,
+    /// Link to the paginated list of all affected packages, if the list was truncated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub packages_link: Option<String>,
+    /// The state of the assessment of the vulnerability in the SBOM, if it was triaged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub triage: Option<TriageState>,
 }
 
 impl SbomStatus {
@@ -432,12 +593,104 @@ impl SbomStatus {
             context: cpe.as_ref().map(|e| StatusContext::Cpe(e.to_string())),
             average_severity: average.severity(),
             average_score: average.value(),
+            severity_override: None,
             status,
             packages,
+            truncated: false,
+            packages_link: None,
+            triage: None,
         })
     }
 
     pub fn identifier(&self) -> &str {
         &self.vulnerability.identifier
     }
+
+    /// The path of the paginated list of affected packages of this status.
+    fn packages_link(&self, sbom_id: Uuid, advisory_id: Uuid) -> String {
+        let mut link = format!(
+            "/api/v2/sbom/{}/advisory/{}/package?vulnerability={}&status={}",
+            sbom_id.urn(),
+            advisory_id.urn(),
+            encode(&self.vulnerability.identifier),
+            encode(&self.status),
+        );
+        if let Some(context) = &self.context {
+            link.push_str("&context=");
+            link.push_str(&encode(&context.sort_key()));
+        }
+        link
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+    use trustify_entity::labels::Labels;
+
+    fn advisory(packages: usize) -> SbomAdvisory {
+        SbomAdvisory {
+            head: AdvisoryHead {
+                uuid: Uuid::nil(),
+                identifier: "GHSA-0000-0000-0000".into(),
+                document_id: "GHSA-0000-0000-0000".into(),
+                issuer: None,
+                published: None,
+                modified: None,
+                withdrawn: None,
+                title: None,
+                labels: Labels::default(),
+                external_id: None,
+            },
+            status: vec![SbomStatus {
+                vulnerability: VulnerabilityHead {
+                    identifier: "CVE-2024-0001".into(),
+                    ..Default::default()
+                },
+                average_severity: Severity::None,
+                average_score: 0.0,
+                severity_override: None,
+                status: "affected".into(),
+                context: Some(StatusContext::Cpe("cpe:/a:redhat:quarkus:3".into())),
+                packages: (0..packages)
+                    .map(|i| SbomPackage {
+                        id: format!("pkg-{i}"),
+                        ..Default::default()
+                    })
+                    .collect(),
+                truncated: false,
+                packages_link: None,
+                triage: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn truncate() {
+        let sbom_id = Uuid::nil();
+
+        let mut unlimited = advisory(3);
+        unlimited.truncate(sbom_id, 0);
+        assert_eq!(unlimited.status[0].packages.len(), 3);
+        assert!(!unlimited.status[0].truncated);
+
+        let mut within = advisory(3);
+        within.truncate(sbom_id, 3);
+        assert_eq!(within.status[0].packages.len(), 3);
+        assert!(!within.status[0].truncated);
+        assert_eq!(within.status[0].packages_link, None);
+
+        let mut exceeding = advisory(3);
+        exceeding.truncate(sbom_id, 2);
+        let status = &exceeding.status[0];
+        assert_eq!(status.packages.len(), 2);
+        assert!(status.truncated);
+        assert_eq!(
+            status.packages_link.as_deref(),
+            Some(
+                "/api/v2/sbom/urn:uuid:00000000-0000-0000-0000-000000000000/advisory/urn:uuid:00000000-0000-0000-0000-000000000000/package?vulnerability=CVE-2024-0001&status=affected&context=cpe%3A%2Fa%3Aredhat%3Aquarkus%3A3"
+            )
+        );
+    }
 }
@@ -0,0 +1,18 @@
+use super::{SbomHead, SbomPackage};
+use crate::vulnerability::model::VulnerabilityHead;
+use serde::{Deserialize, Serialize};
+
+/// The differences between two SBOMs, typically two builds of the same product.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SbomDiff {
+    /// The SBOM compared against
+    pub left: SbomHead,
+    /// The SBOM being compared
+    pub right: SbomHead,
+    /// Packages only present in the right SBOM
+    pub added: Vec<SbomPackage>,
+    /// Packages only present in the left SBOM
+    pub removed: Vec<SbomPackage>,
+    /// Vulnerabilities affecting the right SBOM, but not the left one
+    pub new_vulnerabilities: Vec<VulnerabilityHead>,
+}
@@ -1,17 +1,23 @@
 pub mod details;
+pub mod diff;
 
 use super::service::SbomService;
 use crate::{
     Error, purl::model::summary::purl::PurlSummary, source_document::model::SourceDocument,
 };
 use async_graphql::SimpleObject;
-use sea_orm::{ConnectionTrait, ModelTrait, PaginatorTrait, prelude::Uuid};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, FromQueryResult, ModelTrait, PaginatorTrait,
+    prelude::Uuid,
+};
 use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, str::FromStr};
 use time::OffsetDateTime;
 use tracing::instrument;
 use trustify_common::{cpe::Cpe, model::Paginated, purl::Purl};
 use trustify_entity::{
-    labels::Labels, relationship::Relationship, sbom, sbom_node, sbom_package, source_document,
+    labels::Labels, product_version, relationship::Relationship, sbom, sbom_node, sbom_package,
+    source_document,
 };
 use utoipa::ToSchema;
 
@@ -38,6 +44,10 @@ pub struct SbomHead {
 
     /// The number of packages this SBOM has
     pub number_of_packages: u64,
+
+    /// The external ID, as provided when uploading the SBOM.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
 }
 
 impl SbomHead {
@@ -59,6 +69,7 @@ impl SbomHead {
                 .unwrap_or("".to_string()),
             data_licenses: sbom.data_licenses.clone(),
             number_of_packages,
+            external_id: sbom.external_id.clone(),
         })
     }
 }
@@ -72,6 +83,9 @@ pub struct SbomSummary {
     pub source_document: Option<SourceDocument>,
 
     pub described_by: Vec<SbomPackage>,
+
+    /// If the SBOM describes a product version which reached its end of life
+    pub end_of_life: bool,
 }
 
 impl SbomSummary {
@@ -89,11 +103,20 @@ impl SbomSummary {
 
         let source_document = sbom.find_related(source_document::Entity).one(db).await?;
 
+        let now = OffsetDateTime::now_utc();
+        let end_of_life = sbom
+            .find_related(product_version::Entity)
+            .all(db)
+            .await?
+            .iter()
+            .any(|version| version.is_eol(now));
+
         Ok(match node {
             Some(_) => Some(SbomSummary {
                 head: SbomHead::from_entity(&sbom, node, db).await?,
                 source_document: source_document.as_ref().map(SourceDocument::from_entity),
                 described_by,
+                end_of_life,
             }),
             None => None,
         })
@@ -118,6 +141,55 @@ pub struct SbomPackage {
     pub cpe: Vec<String>,
 }
 
+/// A package, together with the SBOM it was found in
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct SbomPackageOccurrence {
+    #[serde(flatten)]
+    pub package: SbomPackage,
+    /// The ID of the SBOM
+    pub sbom_id: Uuid,
+    /// The document ID of the SBOM
+    pub document_id: Option<String>,
+    /// The publication date of the SBOM
+    #[serde(with = "time::serde::rfc3339::option")]
+    #[schema(required)]
+    pub published: Option<OffsetDateTime>,
+}
+
+/// A file of an SBOM, like a file contained in a package
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema, Default)]
+pub struct SbomFile {
+    /// The SBOM internal ID of the file
+    pub id: String,
+    /// The name of the file in the SBOM, usually its path
+    pub name: String,
+    /// Checksums of the file, by their algorithm, like `SHA-256`
+    pub checksums: BTreeMap<String, String>,
+    /// Licenses concluded for the file
+    pub concluded_licenses: Vec<String>,
+    /// Licenses found in the file itself
+    pub declared_licenses: Vec<String>,
+}
+
+/// A package of an SBOM, matching a checksum
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema, FromQueryResult)]
+pub struct SbomChecksumMatch {
+    /// The ID of the SBOM
+    pub sbom_id: Uuid,
+    /// The document ID of the SBOM
+    pub document_id: Option<String>,
+    /// The SBOM internal ID of the package
+    pub node_id: String,
+    /// The name of the package
+    pub name: String,
+    /// The version of the package
+    pub version: Option<String>,
+    /// The algorithm of the matching checksum, like `SHA-256`
+    pub algorithm: String,
+    /// The value of the matching checksum, as stored in the SBOM
+    pub value: String,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum SbomPackageReference<'a> {
     Internal(&'a str),
@@ -166,9 +238,105 @@ impl<'a> From<&'a SbomPackage> for SbomNodeReference<'a> {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
 pub struct SbomPackageRelation {
     pub relationship: Relationship,
+    /// The relationship type, as it was named in the original SBOM
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_relationship: Option<String>,
     pub package: SbomPackage,
 }
 
+/// A filter on the types of relationships
+#[derive(Clone, Eq, PartialEq, Default, Debug)]
+pub struct RelationshipFilter {
+    /// Relationships to include, all if empty
+    pub include: Vec<Relationship>,
+    /// Relationships to exclude
+    pub exclude: Vec<Relationship>,
+}
+
+impl RelationshipFilter {
+    /// Turn the filter into a condition on a relationship column.
+    pub fn into_condition(self, column: impl ColumnTrait) -> Condition {
+        let mut condition = Condition::all();
+        if !self.include.is_empty() {
+            condition = condition.add(column.is_in(self.include));
+        }
+        if !self.exclude.is_empty() {
+            condition = condition.add(column.is_not_in(self.exclude));
+        }
+        condition
+    }
+}
+
+impl From<Relationship> for RelationshipFilter {
+    fn from(value: Relationship) -> Self {
+        Self {
+            include: vec![value],
+            exclude: vec![],
+        }
+    }
+}
+
+impl From<Option<Relationship>> for RelationshipFilter {
+    fn from(value: Option<Relationship>) -> Self {
+        value.map(Self::from).unwrap_or_default()
+    }
+}
+
+/// A type of relationship, as read from one of its sides.
+///
+/// SBOMs may encode the same relationship in either direction, like SPDX does with `DEPENDS_ON`
+/// and `DEPENDENCY_OF`. Relationships are stored in one direction only, so the inverse names
+/// refer to a stored relationship, read from the other side.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct DirectedRelationship {
+    pub relationship: Relationship,
+    /// If the relationship is read from the other side
+    pub inverse: bool,
+}
+
+impl DirectedRelationship {
+    /// The names of relationships, read from the other side.
+    const INVERSE: [(&'static str, Relationship); 15] = [
+        ("contained_by", Relationship::Contains),
+        ("dependency_of", Relationship::Dependency),
+        ("dev_dependency_of", Relationship::DevDependency),
+        ("optional_dependency_of", Relationship::OptionalDependency),
+        ("provided_dependency_of", Relationship::ProvidedDependency),
+        ("test_dependency_of", Relationship::TestDependency),
+        ("runtime_dependency_of", Relationship::RuntimeDependency),
+        ("example_of", Relationship::Example),
+        ("generated_from", Relationship::Generates),
+        ("descendant_of", Relationship::AncestorOf),
+        ("variant_of", Relationship::Variant),
+        ("build_tool_of", Relationship::BuildTool),
+        ("dev_tool_of", Relationship::DevTool),
+        ("described_by", Relationship::Describes),
+        ("package_of", Relationship::Package),
+    ];
+}
+
+impl FromStr for DirectedRelationship {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(relationship) = Relationship::from_str(s) {
+            return Ok(Self {
+                relationship,
+                inverse: false,
+            });
+        }
+
+        Self::INVERSE
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, relationship)| Self {
+                relationship: *relationship,
+                inverse: true,
+            })
+            .ok_or_else(|| Error::BadRequest(format!("Unknown relationship: {s}")))
+    }
+}
+
 #[derive(Clone, Eq, PartialEq, Default, Debug, serde::Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Which {
@@ -178,3 +346,13 @@ pub enum Which {
     /// Target side
     Right,
 }
+
+impl Which {
+    /// The other side.
+    pub fn inverse(&self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+}
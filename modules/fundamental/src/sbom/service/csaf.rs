@@ -0,0 +1,216 @@
+use super::SbomService;
+use crate::{
+    Error,
+    sbom::model::{SbomPackage, details::SbomDetails},
+    tenant::Tenant,
+    vulnerability::model::VulnerabilityHead,
+};
+use sea_orm::{ConnectionTrait, StreamTrait};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use tracing::instrument;
+use trustify_common::{db::query::Query, id::Id, model::Paginated};
+
+/// The product status groups of CSAF which must not contradict each other, in the order of their
+/// precedence when advisories state different statuses for the same package.
+const EXCLUSIVE_STATUSES: [(&str, &str); 4] = [
+    ("affected", "known_affected"),
+    ("under_investigation", "under_investigation"),
+    ("fixed", "fixed"),
+    ("not_affected", "known_not_affected"),
+];
+
+impl SbomService {
+    /// Export the stored statuses of the vulnerabilities affecting an SBOM as a CSAF VEX
+    /// document, along with the name of the SBOM. Returns `None` if the SBOM could not be found.
+    ///
+    /// The product tree consists of the packages of the SBOM. When advisories state different
+    /// statuses for the same package, the most severe one is being reported.
+    #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
+    pub async fn export_csaf<C>(
+        &self,
+        id: Id,
+        tenant: &Tenant,
+        connection: &C,
+    ) -> Result<Option<(String, Value)>, Error>
+    where
+        C: ConnectionTrait + StreamTrait,
+    {
+        let Some(details) = self
+            .fetch_sbom_details(id, Default::default(), tenant, connection)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let packages = self
+            .fetch_sbom_packages(
+                details.summary.head.id,
+                Query::default(),
+                Paginated {
+                    offset: 0,
+                    limit: 0,
+                    ..Default::default()
+                },
+                connection,
+            )
+            .await?;
+
+        let document = csaf_document(&details, &packages.items, OffsetDateTime::now_utc())?;
+
+        Ok(Some((details.summary.head.name, document)))
+    }
+}
+
+/// The statuses of the packages, by the vulnerability and the CSAF product status
+type Statuses<'a> = BTreeMap<
+    &'a str,
+    (
+        &'a VulnerabilityHead,
+        BTreeMap<&'static str, BTreeSet<&'a str>>,
+    ),
+>;
+
+fn csaf_document(
+    sbom: &SbomDetails,
+    packages: &[SbomPackage],
+    now: OffsetDateTime,
+) -> Result<Value, Error> {
+    let now = now
+        .format(&Rfc3339)
+        .map_err(|err| Error::Internal(err.to_string()))?;
+
+    let mut statuses = Statuses::new();
+    for status in sbom.advisories.iter().flat_map(|advisory| &advisory.status) {
+        let product_status = match status.status.as_str() {
+            "recommended" => "recommended",
+            other => match EXCLUSIVE_STATUSES.iter().find(|(slug, _)| *slug == other) {
+                Some((_, product_status)) => *product_status,
+                None => continue,
+            },
+        };
+
+        statuses
+            .entry(&status.vulnerability.identifier)
+            .or_insert_with(|| (&status.vulnerability, BTreeMap::new()))
+            .1
+            .entry(product_status)
+            .or_default()
+            .extend(status.packages.iter().map(|package| package.id.as_str()));
+    }
+
+    let vulnerabilities = statuses
+        .into_values()
+        .map(|(vulnerability, mut product_status)| {
+            // only report the status of the highest precedence for each package
+            let mut reported = BTreeSet::new();
+            for (_, name) in EXCLUSIVE_STATUSES {
+                if let Some(products) = product_status.get_mut(name) {
+                    products.retain(|product| !reported.contains(product));
+                    reported.extend(products.iter().copied());
+                    if products.is_empty() {
+                        product_status.remove(name);
+                    }
+                }
+            }
+
+            csaf_vulnerability(vulnerability, product_status)
+        })
+        .collect::<Vec<_>>();
+
+    let head = &sbom.summary.head;
+
+    Ok(json!({
+        "document": {
+            "category": "csaf_vex",
+            "csaf_version": "2.0",
+            "title": format!("VEX for {}", head.name),
+            "publisher": {
+                "category": "other",
+                "name": "Trustify",
+                "namespace": "https://trustify.dev",
+            },
+            "tracking": {
+                "id": head.id.urn().to_string(),
+                "status": "final",
+                "version": "1",
+                "initial_release_date": now,
+                "current_release_date": now,
+                "revision_history": [{
+                    "number": "1",
+                    "date": now,
+                    "summary": "Exported from the stored vulnerability statuses",
+                }],
+                "generator": {
+                    "engine": {
+                        "name": "trustify",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                },
+            },
+        },
+        "product_tree": {
+            "full_product_names": packages.iter().map(csaf_product).collect::<Vec<_>>(),
+        },
+        "vulnerabilities": vulnerabilities,
+    }))
+}
+
+fn csaf_product(package: &SbomPackage) -> Value {
+    let name = match &package.version {
+        Some(version) => format!("{} {version}", package.name),
+        None => package.name.clone(),
+    };
+
+    let mut helper = serde_json::Map::new();
+    if let Some(purl) = package
+        .purl
+        .iter()
+        .map(|purl| purl.head.purl.to_string())
+        .min()
+    {
+        helper.insert("purl".into(), purl.into());
+    }
+    if let Some(cpe) = package.cpe.iter().min() {
+        helper.insert("cpe".into(), cpe.clone().into());
+    }
+
+    let mut product = json!({
+        "product_id": package.id,
+        "name": name,
+    });
+    if !helper.is_empty() {
+        product["product_identification_helper"] = helper.into();
+    }
+    product
+}
+
+fn csaf_vulnerability(
+    vulnerability: &VulnerabilityHead,
+    product_status: BTreeMap<&str, BTreeSet<&str>>,
+) -> Value {
+    let mut result = json!({
+        "product_status": product_status,
+    });
+
+    if vulnerability.identifier.starts_with("CVE-") {
+        result["cve"] = vulnerability.identifier.clone().into();
+    } else {
+        result["ids"] = json!([{
+            "system_name": "Trustify",
+            "text": vulnerability.identifier,
+        }]);
+    }
+    if let Some(title) = &vulnerability.title {
+        result["title"] = title.clone().into();
+    }
+    if let Some(description) = &vulnerability.description {
+        result["notes"] = json!([{
+            "category": "description",
+            "text": description,
+        }]);
+    }
+
+    result
+}
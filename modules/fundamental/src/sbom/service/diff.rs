@@ -0,0 +1,135 @@
+use super::{DetailsOptions, SbomService};
+use crate::{
+    Error,
+    sbom::model::{SbomPackage, details::SbomDetails, diff::SbomDiff},
+    tenant::Tenant,
+    vulnerability::model::VulnerabilityHead,
+};
+use sea_orm::{ConnectionTrait, StreamTrait};
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::instrument;
+use trustify_common::{db::query::Query, id::Id, model::Paginated};
+
+impl SbomService {
+    /// Compare two SBOMs, returning `None` if either of them could not be found.
+    ///
+    /// Packages are matched by their PURLs, or by their name and version if they don't have any.
+    /// Vulnerabilities are considered if an advisory states the SBOM as being affected.
+    #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
+    pub async fn diff_sboms<C>(
+        &self,
+        left: Id,
+        right: Id,
+        tenant: &Tenant,
+        connection: &C,
+    ) -> Result<Option<SbomDiff>, Error>
+    where
+        C: ConnectionTrait + StreamTrait,
+    {
+        let statuses = vec!["affected".to_string()];
+
+        let Some(left) = self
+            .fetch_sbom_details(
+                left,
+                DetailsOptions {
+                    statuses: statuses.clone(),
+                    ..Default::default()
+                },
+                tenant,
+                connection,
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+        let Some(right) = self
+            .fetch_sbom_details(
+                right,
+                DetailsOptions {
+                    statuses,
+                    ..Default::default()
+                },
+                tenant,
+                connection,
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut left_packages = self.packages_by_key(&left, connection).await?;
+        let mut right_packages = self.packages_by_key(&right, connection).await?;
+
+        left_packages.retain(|key, _| right_packages.remove(key).is_none());
+
+        let known = affected_vulnerabilities(&left)
+            .map(|vulnerability| vulnerability.identifier.clone())
+            .collect::<BTreeSet<_>>();
+        let new_vulnerabilities = affected_vulnerabilities(&right)
+            .filter(|vulnerability| !known.contains(&vulnerability.identifier))
+            .map(|vulnerability| (vulnerability.identifier.clone(), vulnerability.clone()))
+            .collect::<BTreeMap<_, _>>();
+
+        Ok(Some(SbomDiff {
+            left: left.summary.head,
+            right: right.summary.head,
+            added: right_packages.into_values().collect(),
+            removed: left_packages.into_values().collect(),
+            new_vulnerabilities: new_vulnerabilities.into_values().collect(),
+        }))
+    }
+
+    /// Get all packages of an SBOM, keyed by what identifies them across SBOMs.
+    async fn packages_by_key<C: ConnectionTrait>(
+        &self,
+        sbom: &SbomDetails,
+        connection: &C,
+    ) -> Result<BTreeMap<String, SbomPackage>, Error> {
+        let packages = self
+            .fetch_sbom_packages(
+                sbom.summary.head.id,
+                Query::default(),
+                Paginated {
+                    offset: 0,
+                    limit: 0,
+                    ..Default::default()
+                },
+                connection,
+            )
+            .await?;
+
+        Ok(packages
+            .items
+            .into_iter()
+            .map(|package| (package_key(&package), package))
+            .collect())
+    }
+}
+
+fn package_key(package: &SbomPackage) -> String {
+    package
+        .purl
+        .iter()
+        .map(|purl| purl.head.purl.to_string())
+        .min()
+        .unwrap_or_else(|| {
+            format!(
+                "{}{}@{}",
+                package
+                    .group
+                    .as_deref()
+                    .map(|group| format!("{group}/"))
+                    .unwrap_or_default(),
+                package.name,
+                package.version.as_deref().unwrap_or_default()
+            )
+        })
+}
+
+fn affected_vulnerabilities(sbom: &SbomDetails) -> impl Iterator<Item = &VulnerabilityHead> {
+    sbom.advisories
+        .iter()
+        .flat_map(|advisory| &advisory.status)
+        .filter(|status| status.status == "affected")
+        .map(|status| &status.vulnerability)
+}
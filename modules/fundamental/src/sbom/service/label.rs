@@ -6,34 +6,35 @@ use sea_orm::{
 use sea_query::Expr;
 use trustify_common::id::{Id, TrySelectForId};
 use trustify_entity::{labels::Labels, sbom};
+use uuid::Uuid;
 
 impl SbomService {
     /// Set the labels of an SBOM
     ///
-    /// Returns `Ok(Some(()))` if a document was found and updated. If no document was found, it will
-    /// return `Ok(None)`.
+    /// Returns the ID of the document if it was found and updated. If no document was found, it
+    /// will return `Ok(None)`.
     pub async fn set_labels<C: ConnectionTrait>(
         &self,
         id: Id,
         labels: Labels,
         connection: &C,
-    ) -> Result<Option<()>, Error> {
+    ) -> Result<Option<Uuid>, Error> {
         let result = sbom::Entity::update_many()
             .try_filter(id)?
             .col_expr(sbom::Column::Labels, Expr::value(labels))
-            .exec(connection)
+            .exec_with_returning(connection)
             .await?;
 
-        Ok((result.rows_affected > 0).then_some(()))
+        Ok(result.into_iter().next().map(|sbom| sbom.sbom_id))
     }
 
     /// Update the labels of an SBOM
     ///
-    /// Returns `Ok(Some(()))` if a document was found and updated. If no document was found, it will
-    /// return `Ok(None)`.
+    /// Returns the ID of the document if it was found and updated. If no document was found, it
+    /// will return `Ok(None)`.
     ///
     /// The function will handle its own transaction.
-    pub async fn update_labels<F>(&self, id: Id, mutator: F) -> Result<Option<()>, Error>
+    pub async fn update_labels<F>(&self, id: Id, mutator: F) -> Result<Option<Uuid>, Error>
     where
         F: FnOnce(Labels) -> Labels,
     {
@@ -62,7 +63,7 @@ impl SbomService {
 
         // store
 
-        result.update(&tx).await?;
+        let result = result.update(&tx).await?;
 
         // commit
 
@@ -70,6 +71,6 @@ impl SbomService {
 
         // return
 
-        Ok(Some(()))
+        Ok(Some(result.sbom_id))
     }
 }
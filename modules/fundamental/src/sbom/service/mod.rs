@@ -1,4 +1,6 @@
 pub mod assertion;
+pub mod csaf;
+pub mod diff;
 pub mod label;
 pub mod sbom;
 
@@ -6,13 +8,35 @@ pub mod sbom;
 mod test;
 
 use trustify_common::db::Database;
+use trustify_module_ingestor::common::{Deprecation, Withdrawal};
 
 pub struct SbomService {
     db: Database,
+    soft_delete: bool,
 }
 
 impl SbomService {
     pub fn new(db: Database) -> Self {
-        Self { db }
+        Self {
+            db,
+            soft_delete: false,
+        }
     }
+
+    /// Mark SBOMs as deleted when deleting them, instead of removing them.
+    pub fn with_soft_delete(mut self, soft_delete: bool) -> Self {
+        self.soft_delete = soft_delete;
+        self
+    }
+}
+
+/// Options when fetching the details of an SBOM.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DetailsOptions {
+    /// The slugs of the statuses to include, all statuses if empty
+    pub statuses: Vec<String>,
+    /// Whether to consider deprecated advisories
+    pub deprecation: Deprecation,
+    /// Whether to consider the statuses of withdrawn advisories
+    pub withdrawal: Withdrawal,
 }
@@ -1,27 +1,33 @@
-use super::SbomService;
+use super::{DetailsOptions, SbomService};
 use crate::{
     Error,
+    purl::model::summary::purl::PurlSummary,
     sbom::model::{
-        SbomExternalPackageReference, SbomNodeReference, SbomPackage, SbomPackageRelation,
-        SbomSummary, Which, details::SbomDetails,
+        RelationshipFilter, SbomChecksumMatch, SbomExternalPackageReference, SbomFile,
+        SbomNodeReference, SbomPackage, SbomPackageOccurrence, SbomPackageRelation, SbomSummary,
+        Which,
+        details::{SbomDetails, context_applies, sbom_cpes},
     },
+    tenant::Tenant,
 };
+use ::cpe::uri::OwnedUri;
 use futures_util::{StreamExt, TryStreamExt, stream};
 use sea_orm::{
-    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, FromQueryResult, IntoSimpleExpr, QueryFilter,
-    QueryOrder, QueryResult, QuerySelect, RelationTrait, Select, SelectColumns, StreamTrait,
-    prelude::Uuid,
+    ColumnTrait, Condition, ConnectionTrait, DbBackend, DbErr, EntityTrait, FromQueryResult,
+    IntoSimpleExpr, PaginatorTrait, QueryFilter, QueryOrder, QueryResult, QuerySelect, QueryTrait,
+    RelationTrait, Select, SelectColumns, Statement, StreamTrait, prelude::Uuid,
 };
-use sea_query::{Expr, JoinType, extension::postgres::PgExpr};
+use sea_query::{Alias, ColumnType, Expr, JoinType, SelectStatement};
 use serde_json::Value;
-use std::{collections::HashMap, fmt::Debug};
-use tracing::instrument;
+use std::{collections::HashMap, fmt::Debug, num::NonZeroU64};
+use time::OffsetDateTime;
+use tracing::{Instrument, Span, field::Empty, info_span, instrument};
 use trustify_common::{
     cpe::Cpe,
     db::{
-        limiter::{LimiterTrait, limit_selector},
+        limiter::{LimiterAsModelTrait, LimiterTrait, limit_selector},
         multi_model::{FromQueryResultMultiModel, SelectIntoMultiModel},
-        query::{Columns, Filtering, IntoColumns, Query},
+        query::{Columns, Filtering, IntoColumns, LabelFilters, Query},
     },
     id::{Id, TrySelectForId},
     model::{Paginated, PaginatedResults},
@@ -30,14 +36,16 @@ use trustify_common::{
 use trustify_entity::{
     advisory, advisory_vulnerability, base_purl,
     cpe::{self, CpeDto},
-    labels::Labels,
-    organization, package_relates_to_package,
+    license, organization, package_relates_to_package, product_status, purl_status,
     qualified_purl::{self, CanonicalPurl},
     relationship::Relationship,
     sbom::{self, SbomNodeLink},
-    sbom_node, sbom_package, sbom_package_cpe_ref, sbom_package_purl_ref, source_document, status,
-    versioned_purl, vulnerability,
+    sbom_external_node, sbom_file_license, sbom_node, sbom_node_checksum, sbom_package,
+    sbom_package_cpe_ref,
+    sbom_package_license::LicenseCategory,
+    sbom_package_purl_ref, source_document, status, versioned_purl, vulnerability,
 };
+use trustify_module_ingestor::common::{Deprecation, Withdrawal};
 
 impl SbomService {
     #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
@@ -47,6 +55,7 @@ impl SbomService {
         connection: &C,
     ) -> Result<Option<(sbom::Model, Option<sbom_node::Model>)>, Error> {
         let select = sbom::Entity::find()
+            .filter(sbom::Column::DeletedAt.is_null())
             .join(JoinType::LeftJoin, sbom::Relation::SourceDocument.def())
             .try_filter(id)?;
 
@@ -61,14 +70,15 @@ impl SbomService {
     pub async fn fetch_sbom_details<C>(
         &self,
         id: Id,
-        statuses: Vec<String>,
+        options: DetailsOptions,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<Option<SbomDetails>, Error>
     where
         C: ConnectionTrait + StreamTrait,
     {
         Ok(match self.fetch_sbom(id, connection).await? {
-            Some(row) => SbomDetails::from_entity(row, self, connection, statuses).await?,
+            Some(row) => SbomDetails::from_entity(row, self, connection, options, tenant).await?,
             None => None,
         })
     }
@@ -85,35 +95,240 @@ impl SbomService {
         })
     }
 
+    /// Fetch the packages of an SBOM, affected by a status of an advisory.
+    ///
+    /// Returns `None` if the SBOM doesn't exist, or the context of the status doesn't apply to
+    /// it. The packages are ordered by their ID.
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
+    pub async fn fetch_status_packages<C: ConnectionTrait>(
+        &self,
+        id: Id,
+        advisory_id: Uuid,
+        vulnerability: &str,
+        status: &str,
+        context: Option<&str>,
+        deprecation: Deprecation,
+        withdrawal: Withdrawal,
+        paginated: Paginated,
+        connection: &C,
+    ) -> Result<Option<PaginatedResults<SbomPackage>>, Error> {
+        let Some((sbom, _)) = self.fetch_sbom(id, connection).await? else {
+            return Ok(None);
+        };
+
+        // resolve the context to the CPEs it was stated for, if they apply to the SBOM
+
+        let context_cpes = match context {
+            Some(context) => {
+                let described_by = self
+                    .describes_packages(sbom.sbom_id, Paginated::default(), connection)
+                    .await?
+                    .items;
+                let sbom_cpes = sbom_cpes(&described_by);
+
+                let cpes = cpe::Entity::find()
+                    .filter(
+                        Condition::any()
+                            .add(
+                                cpe::Column::Id.in_subquery(
+                                    purl_status::Entity::find()
+                                        .select_only()
+                                        .column(purl_status::Column::ContextCpeId)
+                                        .filter(purl_status::Column::AdvisoryId.eq(advisory_id))
+                                        .filter(
+                                            purl_status::Column::VulnerabilityId.eq(vulnerability),
+                                        )
+                                        .into_query(),
+                                ),
+                            )
+                            .add(
+                                cpe::Column::Id.in_subquery(
+                                    product_status::Entity::find()
+                                        .select_only()
+                                        .column(product_status::Column::ContextCpeId)
+                                        .filter(product_status::Column::AdvisoryId.eq(advisory_id))
+                                        .filter(
+                                            product_status::Column::VulnerabilityId
+                                                .eq(vulnerability),
+                                        )
+                                        .into_query(),
+                                ),
+                            ),
+                    )
+                    .all(connection)
+                    .await?;
+
+                let ids = cpes
+                    .iter()
+                    .filter(|cpe| {
+                        OwnedUri::try_from(*cpe).is_ok_and(|uri| {
+                            uri.to_string() == context && context_applies(&sbom_cpes, &uri)
+                        })
+                    })
+                    .map(|cpe| cpe.id)
+                    .collect::<Vec<_>>();
+
+                if ids.is_empty() {
+                    return Ok(None);
+                }
+
+                ids
+            }
+            None => vec![],
+        };
+
+        let sql = include_str!("status_packages.sql");
+        let values: [sea_orm::Value; 8] = [
+            sbom.sbom_id.into(),
+            advisory_id.into(),
+            vulnerability.into(),
+            status.into(),
+            context.is_some().into(),
+            context_cpes.into(),
+            matches!(withdrawal, Withdrawal::Consider).into(),
+            matches!(deprecation, Deprecation::Consider).into(),
+        ];
+
+        let total = connection
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!(r#"SELECT COUNT(*) AS "num_items" FROM ({sql}) AS "package""#),
+                values.clone(),
+            ))
+            .await?
+            .map(|row| row.try_get::<i64>("", "num_items"))
+            .transpose()?
+            .unwrap_or_default() as u64;
+
+        let limit = NonZeroU64::new(paginated.limit).map(|limit| limit.get() as i64);
+        let rows = connection
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!("{sql} LIMIT $9 OFFSET $10"),
+                values
+                    .into_iter()
+                    .chain([limit.into(), (paginated.offset as i64).into()]),
+            ))
+            .await?;
+
+        let items = rows
+            .iter()
+            .map(|row| {
+                Ok(SbomPackage {
+                    id: row.try_get("", "id")?,
+                    name: row.try_get("", "name")?,
+                    group: row.try_get("", "group")?,
+                    version: row.try_get("", "version")?,
+                    purl: vec![PurlSummary::from_entity(
+                        &qualified_purl::Model::from_query_result(row, "qualified_purl$")?,
+                    )],
+                    cpe: vec![],
+                })
+            })
+            .collect::<Result<_, DbErr>>()?;
+
+        Ok(Some(PaginatedResults { items, total }))
+    }
+
+    /// Check if an SBOM exists.
+    ///
+    /// This excludes SBOMs marked as deleted, as uploading them again restores them.
+    pub async fn exists<C: ConnectionTrait>(&self, id: Id, connection: &C) -> Result<bool, Error> {
+        Ok(sbom::Entity::find()
+            .join(JoinType::LeftJoin, sbom::Relation::SourceDocument.def())
+            .filter(sbom::Column::DeletedAt.is_null())
+            .try_filter(id)?
+            .count(connection)
+            .await?
+            > 0)
+    }
+
     /// delete one sbom
+    ///
+    /// If soft deletion is enabled, the SBOM is only marked as deleted. It can then be restored
+    /// using [`Self::restore_sbom`].
+    #[instrument(
+        skip(self, connection),
+        fields(db.operation = "DELETE", db.collection.name = "sbom", rows = Empty),
+        err(level=tracing::Level::INFO)
+    )]
     pub async fn delete_sbom<C: ConnectionTrait>(
         &self,
         id: Uuid,
         connection: &C,
     ) -> Result<u64, Error> {
-        let query = sbom::Entity::delete_by_id(id);
+        let rows_affected = if self.soft_delete {
+            sbom::Entity::update_many()
+                .col_expr(sbom::Column::DeletedAt, Expr::current_timestamp().into())
+                .filter(sbom::Column::SbomId.eq(id))
+                .filter(sbom::Column::DeletedAt.is_null())
+                .exec(connection)
+                .await?
+                .rows_affected
+        } else {
+            sbom::Entity::delete_by_id(id)
+                .exec(connection)
+                .await?
+                .rows_affected
+        };
+
+        Span::current().record("rows", rows_affected);
 
-        let result = query.exec(connection).await?;
+        Ok(rows_affected)
+    }
+
+    /// restore one sbom, which was marked as deleted
+    #[instrument(
+        skip(self, connection),
+        fields(db.operation = "UPDATE", db.collection.name = "sbom", rows = Empty),
+        err(level=tracing::Level::INFO)
+    )]
+    pub async fn restore_sbom<C: ConnectionTrait>(
+        &self,
+        id: Uuid,
+        connection: &C,
+    ) -> Result<u64, Error> {
+        let result = sbom::Entity::update_many()
+            .col_expr(sbom::Column::DeletedAt, Expr::value(None::<OffsetDateTime>))
+            .filter(sbom::Column::SbomId.eq(id))
+            .filter(sbom::Column::DeletedAt.is_not_null())
+            .exec(connection)
+            .await?;
+
+        Span::current().record("rows", result.rows_affected);
 
         Ok(result.rows_affected)
     }
 
     /// fetch all SBOMs
+    ///
+    /// If `latest` is set, only the newest SBOM of each product is returned. SBOMs which are not
+    /// linked to a product are grouped by their name instead.
+    #[instrument(
+        skip(self, labels, connection),
+        fields(db.operation = "SELECT", db.collection.name = "sbom", total = Empty, rows = Empty),
+        err(level=tracing::Level::INFO)
+    )]
     pub async fn fetch_sboms<C: ConnectionTrait>(
         &self,
         search: Query,
         paginated: Paginated,
-        labels: impl Into<Labels>,
+        latest: bool,
+        labels: impl Into<LabelFilters>,
         connection: &C,
     ) -> Result<PaginatedResults<SbomSummary>, Error> {
         let labels = labels.into();
 
-        let query = if labels.is_empty() {
-            sbom::Entity::find()
-        } else {
-            sbom::Entity::find().filter(Expr::col(sbom::Column::Labels).contains(labels))
-        };
+        let mut query = sbom::Entity::find();
+        if latest {
+            query = query.filter(sbom::Column::SbomId.in_subquery(latest_sboms(&labels)));
+        }
+        if !labels.is_empty() {
+            query = query.filter(labels.into_condition(sbom::Column::Labels));
+        }
         let limiter = query
+            .filter(sbom::Column::DeletedAt.is_null())
             .join(JoinType::Join, sbom::Relation::SourceDocument.def())
             .find_also_linked(SbomNodeLink)
             .filtering_with(
@@ -121,12 +336,25 @@ impl SbomService {
                 Columns::from_entity::<sbom::Entity>()
                     .add_columns(sbom_node::Entity)
                     .add_columns(source_document::Entity)
-                    .alias("sbom_node", "r0"),
+                    .alias("sbom_node", "r0")
+                    // the number of warnings, reported while ingesting the SBOM
+                    .add_expr(
+                        "warnings",
+                        Expr::cust(
+                            r#"(SELECT COUNT(*) FROM "ingestion_warning" WHERE "ingestion_warning"."sbom_id" = "sbom"."sbom_id")::integer"#,
+                        ),
+                        ColumnType::Integer,
+                    ),
             )?
-            .limiting(connection, paginated.offset, paginated.limit);
+            .limiting(connection, paginated.offset, paginated.limit)
+            .counting(paginated.count);
 
-        let total = limiter.total().await?;
-        let sboms = limiter.fetch().await?;
+        let total = limiter.total().instrument(info_span!("count")).await?;
+        let sboms = limiter.fetch().instrument(info_span!("fetch")).await?;
+
+        let span = Span::current();
+        span.record("total", total);
+        span.record("rows", sboms.len());
 
         let items = stream::iter(sboms.into_iter())
             .then(|row| async { SbomSummary::from_entity(row, self, connection).await })
@@ -141,7 +369,11 @@ impl SbomService {
     ///
     /// If you need to find packages based on their relationship, even in the relationship to
     /// SBOM itself, use [`Self::fetch_related_packages`].
-    #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
+    #[instrument(
+        skip(self, connection),
+        fields(db.operation = "SELECT", db.collection.name = "sbom_package", total = Empty, rows = Empty),
+        err(level=tracing::Level::INFO)
+    )]
     pub async fn fetch_sbom_packages<C: ConnectionTrait>(
         &self,
         sbom_id: Uuid,
@@ -185,8 +417,12 @@ impl SbomService {
             paginated.limit,
         );
 
-        let total = limiter.total().await?;
-        let packages = limiter.fetch().await?;
+        let total = limiter.total().instrument(info_span!("count")).await?;
+        let packages = limiter.fetch().instrument(info_span!("fetch")).await?;
+
+        let span = Span::current();
+        span.record("total", total);
+        span.record("rows", packages.len());
 
         // collect results
 
@@ -199,6 +435,267 @@ impl SbomService {
         Ok(PaginatedResults { items, total })
     }
 
+    /// Search packages across all SBOMs.
+    ///
+    /// Each package is reported together with the SBOM it was found in. Deleted SBOMs are
+    /// excluded.
+    #[instrument(
+        skip(self, labels, connection),
+        fields(db.operation = "SELECT", db.collection.name = "sbom_package", total = Empty, rows = Empty),
+        err(level=tracing::Level::INFO)
+    )]
+    pub async fn search_packages<C: ConnectionTrait>(
+        &self,
+        search: Query,
+        paginated: Paginated,
+        labels: impl Into<LabelFilters>,
+        connection: &C,
+    ) -> Result<PaginatedResults<SbomPackageOccurrence>, Error> {
+        let labels = labels.into();
+
+        let mut query = sbom_package::Entity::find()
+            .join(JoinType::Join, sbom_package::Relation::Sbom.def())
+            .filter(sbom::Column::DeletedAt.is_null())
+            .join(JoinType::Join, sbom_package::Relation::Node.def())
+            .select_only()
+            .column_as(sbom_package::Column::SbomId, "sbom_id")
+            .group_by(sbom_package::Column::SbomId)
+            .column_as(sbom::Column::DocumentId, "document_id")
+            .group_by(sbom::Column::DocumentId)
+            .column_as(sbom::Column::Published, "published")
+            .group_by(sbom::Column::Published)
+            .column_as(sbom_package::Column::NodeId, "id")
+            .group_by(sbom_package::Column::NodeId)
+            .column_as(sbom_package::Column::Version, "version")
+            .group_by(sbom_package::Column::Version)
+            .column_as(sbom_node::Column::Name, "name")
+            .group_by(sbom_node::Column::Name)
+            .join(JoinType::LeftJoin, sbom_package::Relation::Purl.def())
+            .join(JoinType::LeftJoin, sbom_package::Relation::Cpe.def());
+
+        if !labels.is_empty() {
+            query = query.filter(labels.into_condition(sbom::Column::Labels));
+        }
+
+        query = join_purls_and_cpes(query)
+            .filtering_with(
+                search,
+                sbom_package::Entity
+                    .columns()
+                    .add_columns(sbom_node::Entity)
+                    .add_columns(sbom::Entity)
+                    .add_columns(base_purl::Entity)
+                    .add_columns(sbom_package_cpe_ref::Entity)
+                    .add_columns(sbom_package_purl_ref::Entity),
+            )?
+            // default order
+            .order_by_asc(sbom_node::Column::Name)
+            .order_by_asc(sbom_package::Column::Version)
+            .order_by_asc(sbom_package::Column::SbomId)
+            .order_by_asc(sbom_package::Column::NodeId);
+
+        // limit and execute
+
+        let limiter = limit_selector::<'_, _, _, _, OccurrenceCatcher>(
+            connection,
+            query,
+            paginated.offset,
+            paginated.limit,
+        );
+
+        let total = limiter.total().instrument(info_span!("count")).await?;
+        let packages = limiter.fetch().instrument(info_span!("fetch")).await?;
+
+        let span = Span::current();
+        span.record("total", total);
+        span.record("rows", packages.len());
+
+        // collect results
+
+        let items = packages
+            .into_iter()
+            .map(|row| SbomPackageOccurrence {
+                sbom_id: row.sbom_id,
+                document_id: row.document_id,
+                published: row.published,
+                package: package_from_row(PackageCatcher {
+                    id: row.id,
+                    name: row.name,
+                    group: row.group,
+                    version: row.version,
+                    purls: row.purls,
+                    cpes: row.cpes,
+                    relationship: None,
+                    original_relationship: None,
+                }),
+            })
+            .collect();
+
+        Ok(PaginatedResults { items, total })
+    }
+
+    /// Fetch the files contained in a package of an SBOM.
+    ///
+    /// Returns `None` if the SBOM doesn't have such a package.
+    #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
+    pub async fn fetch_package_files<C: ConnectionTrait>(
+        &self,
+        sbom_id: Uuid,
+        node_id: &str,
+        paginated: Paginated,
+        connection: &C,
+    ) -> Result<Option<PaginatedResults<SbomFile>>, Error> {
+        if sbom_package::Entity::find_by_id((sbom_id, node_id.to_string()))
+            .one(connection)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let contained = package_relates_to_package::Entity::find()
+            .select_only()
+            .column(package_relates_to_package::Column::RightNodeId)
+            .filter(package_relates_to_package::Column::SbomId.eq(sbom_id))
+            .filter(package_relates_to_package::Column::LeftNodeId.eq(node_id))
+            .filter(package_relates_to_package::Column::Relationship.eq(Relationship::Contains))
+            .into_query();
+
+        let limiter = sbom_node::Entity::find()
+            .join(JoinType::Join, sbom_node::Relation::File.def())
+            .filter(sbom_node::Column::SbomId.eq(sbom_id))
+            .filter(sbom_node::Column::NodeId.in_subquery(contained))
+            .order_by_asc(sbom_node::Column::Name)
+            .order_by_asc(sbom_node::Column::NodeId)
+            .limiting(connection, paginated.offset, paginated.limit);
+
+        let total = limiter.total().await?;
+        let nodes = limiter.fetch().await?;
+
+        let ids = nodes
+            .iter()
+            .map(|node| node.node_id.clone())
+            .collect::<Vec<_>>();
+
+        let checksums = sbom_node_checksum::Entity::find()
+            .filter(sbom_node_checksum::Column::SbomId.eq(sbom_id))
+            .filter(sbom_node_checksum::Column::NodeId.is_in(ids.clone()))
+            .all(connection)
+            .await?;
+
+        let licenses = sbom_file_license::Entity::find()
+            .find_also_related(license::Entity)
+            .filter(sbom_file_license::Column::SbomId.eq(sbom_id))
+            .filter(sbom_file_license::Column::NodeId.is_in(ids))
+            .order_by_asc(license::Column::Text)
+            .all(connection)
+            .await?;
+
+        let items = nodes
+            .into_iter()
+            .map(|node| {
+                let mut file = SbomFile {
+                    checksums: checksums
+                        .iter()
+                        .filter(|checksum| checksum.node_id == node.node_id)
+                        .map(|checksum| (checksum.r#type.clone(), checksum.value.clone()))
+                        .collect(),
+                    ..Default::default()
+                };
+
+                for (assignment, license) in &licenses {
+                    let Some(license) = license else {
+                        continue;
+                    };
+                    if assignment.node_id != node.node_id {
+                        continue;
+                    }
+                    match assignment.license_type {
+                        LicenseCategory::Concluded => {
+                            file.concluded_licenses.push(license.text.clone())
+                        }
+                        LicenseCategory::Declared => {
+                            file.declared_licenses.push(license.text.clone())
+                        }
+                    }
+                }
+
+                SbomFile {
+                    id: node.node_id,
+                    name: node.name,
+                    ..file
+                }
+            })
+            .collect();
+
+        Ok(Some(PaginatedResults { items, total }))
+    }
+
+    /// Find packages of all SBOMs by their checksums.
+    ///
+    /// Checksums are provided as pairs of algorithm (like `SHA-256`) and value. A package matches
+    /// if it has any of the provided checksums. Values are compared case-insensitively.
+    #[instrument(
+        skip(self, labels, connection),
+        fields(db.operation = "SELECT", db.collection.name = "sbom_node_checksum", total = Empty, rows = Empty),
+        err(level=tracing::Level::INFO)
+    )]
+    pub async fn lookup_checksums<C: ConnectionTrait>(
+        &self,
+        checksums: &[(&str, &str)],
+        paginated: Paginated,
+        labels: impl Into<LabelFilters>,
+        connection: &C,
+    ) -> Result<PaginatedResults<SbomChecksumMatch>, Error> {
+        let labels = labels.into();
+
+        let mut condition = Condition::any();
+        for (algorithm, value) in checksums {
+            condition = condition.add(
+                Condition::all()
+                    .add(sbom_node_checksum::Column::Type.eq(*algorithm))
+                    .add(Expr::cust_with_values(
+                        r#"lower("sbom_node_checksum"."value") = lower($1)"#,
+                        [value.to_string()],
+                    )),
+            );
+        }
+
+        let mut query = sbom_node_checksum::Entity::find()
+            .select_only()
+            .column(sbom_node_checksum::Column::SbomId)
+            .column(sbom::Column::DocumentId)
+            .column(sbom_node_checksum::Column::NodeId)
+            .column(sbom_node::Column::Name)
+            .column(sbom_package::Column::Version)
+            .column_as(sbom_node_checksum::Column::Type, "algorithm")
+            .column(sbom_node_checksum::Column::Value)
+            .join(JoinType::Join, sbom_node_checksum::Relation::Node.def())
+            .join(JoinType::Join, sbom_node::Relation::Package.def())
+            .join(JoinType::Join, sbom_node::Relation::Sbom.def())
+            .filter(sbom::Column::DeletedAt.is_null())
+            .filter(condition);
+
+        if !labels.is_empty() {
+            query = query.filter(labels.into_condition(sbom::Column::Labels));
+        }
+
+        let limiter = query
+            .order_by_asc(sbom_node_checksum::Column::SbomId)
+            .order_by_asc(sbom_node_checksum::Column::NodeId)
+            .order_by_asc(sbom_node_checksum::Column::Type)
+            .limiting_as::<SbomChecksumMatch>(connection, paginated.offset, paginated.limit);
+
+        let total = limiter.total().await?;
+        let items = limiter.fetch().await?;
+
+        let span = Span::current();
+        span.record("total", total);
+        span.record("rows", items.len());
+
+        Ok(PaginatedResults { items, total })
+    }
+
     /// Get all packages describing the SBOM.
     #[instrument(skip(self, db), err(level=tracing::Level::INFO))]
     pub async fn describes_packages<C: ConnectionTrait>(
@@ -214,6 +711,7 @@ impl SbomService {
             Which::Left,
             SbomNodeReference::All,
             Some(Relationship::Describes),
+            false,
             db,
         )
         .await
@@ -224,6 +722,7 @@ impl SbomService {
     pub async fn count_related_sboms<C: ConnectionTrait>(
         &self,
         references: Vec<SbomExternalPackageReference<'_>>,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<Vec<i64>, Error> {
         #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -255,6 +754,8 @@ impl SbomService {
                 .join(JoinType::Join, sbom::Relation::Packages.def())
                 .join(JoinType::Join, sbom_package::Relation::Cpe.def())
                 .filter(sbom_package_cpe_ref::Column::CpeId.is_in(cpes))
+                .filter(sbom::Column::DeletedAt.is_null())
+                .filter(tenant.sbom_condition(sbom::Column::SbomId))
                 .group_by(sbom_package_cpe_ref::Column::CpeId)
                 .select_only()
                 .column(sbom_package_cpe_ref::Column::CpeId)
@@ -279,6 +780,8 @@ impl SbomService {
                 .join(JoinType::Join, sbom::Relation::Packages.def())
                 .join(JoinType::Join, sbom_package::Relation::Purl.def())
                 .filter(sbom_package_purl_ref::Column::QualifiedPurlId.is_in(purls))
+                .filter(sbom::Column::DeletedAt.is_null())
+                .filter(tenant.sbom_condition(sbom::Column::SbomId))
                 .group_by(sbom_package_purl_ref::Column::QualifiedPurlId)
                 .select_only()
                 .column(sbom_package_purl_ref::Column::QualifiedPurlId)
@@ -308,9 +811,13 @@ impl SbomService {
         package_ref: SbomExternalPackageReference<'_>,
         paginated: Paginated,
         query: Query,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<PaginatedResults<SbomSummary>, Error> {
-        let select = sbom::Entity::find().join(JoinType::Join, sbom::Relation::Packages.def());
+        let select = sbom::Entity::find()
+            .filter(sbom::Column::DeletedAt.is_null())
+            .filter(tenant.sbom_condition(sbom::Column::SbomId))
+            .join(JoinType::Join, sbom::Relation::Packages.def());
 
         let select = match package_ref {
             SbomExternalPackageReference::Purl(purl) => select
@@ -330,7 +837,9 @@ impl SbomService {
 
         // limit and execute
 
-        let limiter = query.limiting(connection, paginated.offset, paginated.limit);
+        let limiter = query
+            .limiting(connection, paginated.offset, paginated.limit)
+            .counting(paginated.count);
 
         let total = limiter.total().await?;
         let sboms = limiter.fetch().await?;
@@ -347,6 +856,10 @@ impl SbomService {
     }
 
     /// Fetch all related packages in the context of an SBOM.
+    ///
+    /// If `resolve_external` is set, relationships pointing to external nodes, which could be
+    /// resolved to other ingested SBOMs, will be followed and the referenced packages will be
+    /// part of the result.
     #[allow(clippy::too_many_arguments)]
     #[instrument(skip(self, db), err(level=tracing::Level::INFO))]
     pub async fn fetch_related_packages<C: ConnectionTrait>(
@@ -356,31 +869,76 @@ impl SbomService {
         paginated: Paginated,
         which: Which,
         reference: impl Into<SbomNodeReference<'_>> + Debug,
-        relationship: Option<Relationship>,
+        relationships: impl Into<RelationshipFilter> + Debug,
+        resolve_external: bool,
         db: &C,
     ) -> Result<PaginatedResults<SbomPackageRelation>, Error> {
+        let reference = reference.into();
+        let relationships = relationships.into();
         // which way
 
         log::debug!("Which: {which:?}");
 
         // select all qualified packages for which we have relationships
 
-        let (filter, join) = match which {
+        let (filter, other, external) = match &which {
             Which::Left => (
                 package_relates_to_package::Column::LeftNodeId,
-                package_relates_to_package::Relation::Right,
+                package_relates_to_package::Column::RightNodeId,
+                package_relates_to_package::Relation::RightExternal,
             ),
             Which::Right => (
                 package_relates_to_package::Column::RightNodeId,
-                package_relates_to_package::Relation::Left,
+                package_relates_to_package::Column::LeftNodeId,
+                package_relates_to_package::Relation::LeftExternal,
             ),
         };
 
+        // the package on the other side, which is part of the same SBOM
+
+        let mut package = Condition::all()
+            .add(
+                Expr::col((sbom_package::Entity, sbom_package::Column::SbomId)).equals((
+                    package_relates_to_package::Entity,
+                    package_relates_to_package::Column::SbomId,
+                )),
+            )
+            .add(
+                Expr::col((sbom_package::Entity, sbom_package::Column::NodeId))
+                    .equals((package_relates_to_package::Entity, other)),
+            );
+
         let mut query = package_relates_to_package::Entity::find()
-            .filter(package_relates_to_package::Column::SbomId.eq(sbom_id))
+            .filter(package_relates_to_package::Column::SbomId.eq(sbom_id));
+
+        if resolve_external {
+            // or the package an external node of the SBOM got resolved to
+
+            query = query.join(JoinType::LeftJoin, external.def());
+            package = Condition::any().add(package).add(
+                Condition::all()
+                    .add(
+                        Expr::col((sbom_package::Entity, sbom_package::Column::SbomId)).equals((
+                            sbom_external_node::Entity,
+                            sbom_external_node::Column::TargetSbomId,
+                        )),
+                    )
+                    .add(
+                        Expr::col((sbom_package::Entity, sbom_package::Column::NodeId)).equals((
+                            sbom_external_node::Entity,
+                            sbom_external_node::Column::ExternalNodeRef,
+                        )),
+                    ),
+            );
+        }
+
+        QueryTrait::query(&mut query).join(JoinType::Join, sbom_package::Entity, package);
+
+        let mut query = query
             .select_only()
             .select_column_as(sbom_node::Column::NodeId, "id")
             .group_by(sbom_node::Column::NodeId)
+            .group_by(sbom_package::Column::SbomId)
             .select_column_as(sbom_node::Column::Name, "name")
             .group_by(sbom_node::Column::Name)
             .select_column_as(
@@ -388,13 +946,17 @@ impl SbomService {
                 "relationship",
             )
             .group_by(package_relates_to_package::Column::Relationship)
+            .select_column_as(
+                package_relates_to_package::Column::OriginalRelationship,
+                "original_relationship",
+            )
+            .group_by(package_relates_to_package::Column::OriginalRelationship)
             .select_column_as(sbom_package::Column::Group, "group")
             .group_by(sbom_package::Column::Group)
             .select_column_as(sbom_package::Column::Version, "version")
             .group_by(sbom_package::Column::Version)
             // join the other side
-            .join(JoinType::Join, join.def())
-            .join(JoinType::Join, sbom_node::Relation::Package.def())
+            .join(JoinType::Join, sbom_package::Relation::Node.def())
             .join(JoinType::LeftJoin, sbom_package::Relation::Purl.def())
             .join(JoinType::LeftJoin, sbom_package::Relation::Cpe.def());
 
@@ -404,14 +966,17 @@ impl SbomService {
 
         // filter for reference
 
-        query = match reference.into() {
+        query = match &reference {
             SbomNodeReference::All => {
                 // sbom - add join to sbom table
-                query.join(JoinType::Join, sbom_node::Relation::Sbom.def())
+                query.join(
+                    JoinType::Join,
+                    package_relates_to_package::Relation::Sbom.def(),
+                )
             }
             SbomNodeReference::Package(node_id) => {
                 // package - set node id filter
-                query.filter(filter.eq(node_id))
+                query.filter(filter.eq(*node_id))
             }
         };
 
@@ -421,9 +986,11 @@ impl SbomService {
 
         // add relationship type filter
 
-        if let Some(relationship) = relationship {
-            query = query.filter(package_relates_to_package::Column::Relationship.eq(relationship));
-        }
+        query = query.filter(
+            relationships
+                .clone()
+                .into_condition(package_relates_to_package::Column::Relationship),
+        );
 
         // limit and execute
 
@@ -445,6 +1012,7 @@ impl SbomService {
             if let Some(relationship) = row.relationship {
                 items.push(SbomPackageRelation {
                     relationship,
+                    original_relationship: row.original_relationship.clone(),
                     package: package_from_row(row),
                 });
             }
@@ -463,6 +1031,8 @@ impl SbomService {
         pkg: impl Into<SbomNodeReference<'_>> + Debug,
         tx: &C,
     ) -> Result<Vec<SbomPackage>, Error> {
+        let relationship: Option<Relationship> = relationship.into();
+
         let result = self
             .fetch_related_packages(
                 sbom_id,
@@ -470,7 +1040,8 @@ impl SbomService {
                 Default::default(),
                 Which::Left,
                 pkg,
-                relationship.into(),
+                relationship,
+                false,
                 tx,
             )
             .await?;
@@ -491,6 +1062,36 @@ impl SbomService {
     }
 }
 
+/// Select the IDs of the newest SBOM of each product, or of each name if the SBOM isn't linked
+/// to a product.
+///
+/// SBOMs are ranked by their publication date, falling back to the time they got ingested.
+fn latest_sboms(labels: &LabelFilters) -> SelectStatement {
+    let mut ranked = sbom::Entity::find()
+        .select_only()
+        .column(sbom::Column::SbomId)
+        .expr_as(
+            Expr::cust(
+                r#"row_number() OVER (PARTITION BY coalesce("product_version"."product_id"::text, "sbom_node"."name") ORDER BY "sbom"."published" DESC NULLS LAST, "source_document"."ingested" DESC)"#,
+            ),
+            "rank",
+        )
+        .join(JoinType::Join, sbom::Relation::SourceDocument.def())
+        .join(JoinType::Join, sbom::Relation::SbomNode.def())
+        .join(JoinType::LeftJoin, sbom::Relation::ProductVersion.def())
+        .filter(sbom::Column::DeletedAt.is_null());
+
+    if !labels.is_empty() {
+        ranked = ranked.filter(labels.clone().into_condition(sbom::Column::Labels));
+    }
+
+    SelectStatement::new()
+        .column(sbom::Column::SbomId)
+        .from_subquery(ranked.into_query(), Alias::new("ranked"))
+        .and_where(Expr::col(Alias::new("rank")).eq(1))
+        .to_owned()
+}
+
 /// Join CPE and PURL information.
 ///
 /// Given a select over something which already joins sbom_package_purl_ref and
@@ -551,6 +1152,21 @@ struct PackageCatcher {
     purls: Vec<Value>,
     cpes: Value,
     relationship: Option<Relationship>,
+    original_relationship: Option<String>,
+}
+
+/// A "package row", together with the SBOM the package belongs to
+#[derive(FromQueryResult)]
+struct OccurrenceCatcher {
+    sbom_id: Uuid,
+    document_id: Option<String>,
+    published: Option<OffsetDateTime>,
+    id: String,
+    name: String,
+    group: Option<String>,
+    version: Option<String>,
+    purls: Vec<Value>,
+    cpes: Value,
 }
 
 /// Convert values from a "package row" into an SBOM package
@@ -660,9 +1276,11 @@ mod test {
     use super::*;
     use test_context::test_context;
     use test_log::test;
+    use time::macros::datetime;
     use trustify_common::db::query::q;
     use trustify_common::hashing::Digests;
     use trustify_entity::labels::Labels;
+    use trustify_module_ingestor::graph::sbom::SbomInformation;
     use trustify_test_context::TrustifyContext;
 
     #[test_context(TrustifyContext)]
@@ -719,6 +1337,7 @@ mod test {
             .fetch_sboms(
                 q("MySpAcE").sort("name,authors,published"),
                 Paginated::default(),
+                false,
                 (),
                 &ctx.db,
             )
@@ -781,6 +1400,7 @@ mod test {
             .fetch_sboms(
                 Query::default(),
                 Paginated::default(),
+                false,
                 ("ci", "job1"),
                 &ctx.db,
             )
@@ -791,6 +1411,7 @@ mod test {
             .fetch_sboms(
                 Query::default(),
                 Paginated::default(),
+                false,
                 ("ci", "job2"),
                 &ctx.db,
             )
@@ -801,6 +1422,7 @@ mod test {
             .fetch_sboms(
                 Query::default(),
                 Paginated::default(),
+                false,
                 ("ci", "job3"),
                 &ctx.db,
             )
@@ -811,6 +1433,7 @@ mod test {
             .fetch_sboms(
                 Query::default(),
                 Paginated::default(),
+                false,
                 ("foo", "bar"),
                 &ctx.db,
             )
@@ -818,7 +1441,7 @@ mod test {
         assert_eq!(0, fetched.total);
 
         let fetched = service
-            .fetch_sboms(Query::default(), Paginated::default(), (), &ctx.db)
+            .fetch_sboms(Query::default(), Paginated::default(), false, (), &ctx.db)
             .await?;
         assert_eq!(3, fetched.total);
 
@@ -826,12 +1449,40 @@ mod test {
             .fetch_sboms(
                 Query::default(),
                 Paginated::default(),
+                false,
                 [("ci", "job2"), ("team", "a")],
                 &ctx.db,
             )
             .await?;
         assert_eq!(1, fetched.total);
 
+        // wildcards, existence, and negation
+
+        for (filters, expected) in [
+            ("ci=job*", 3),
+            ("ci=*1", 1),
+            ("ci=j*b*2", 2),
+            ("ci=job", 0),
+            ("has:team", 3),
+            ("has:foo", 0),
+            ("!has:foo", 3),
+            ("!ci=job1", 2),
+            ("!ci=*1", 2),
+            ("ci=job2,!team=b", 1),
+            ("!team=a*,has:ci", 1),
+        ] {
+            let fetched = service
+                .fetch_sboms(
+                    Query::default(),
+                    Paginated::default(),
+                    false,
+                    filters.parse::<LabelFilters>()?,
+                    &ctx.db,
+                )
+                .await?;
+            assert_eq!(expected, fetched.total, "{filters}");
+        }
+
         Ok(())
     }
 
@@ -863,4 +1514,99 @@ mod test {
 
         Ok(())
     }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn soft_delete_sbom(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        let sbom = ctx
+            .graph
+            .ingest_sbom(
+                Labels::default(),
+                &Digests::digest("RHSA-1"),
+                Some("http://redhat.com/test.json".to_string()),
+                (),
+                &ctx.db,
+            )
+            .await?;
+        let id = sbom.sbom.sbom_id;
+
+        let service = SbomService::new(ctx.db.clone()).with_soft_delete(true);
+
+        assert_eq!(1, service.delete_sbom(id, &ctx.db).await?);
+        assert_eq!(0, service.delete_sbom(id, &ctx.db).await?);
+        assert!(
+            service
+                .fetch_sbom_summary(Id::Uuid(id), &ctx.db)
+                .await?
+                .is_none()
+        );
+
+        assert_eq!(1, service.restore_sbom(id, &ctx.db).await?);
+        assert_eq!(0, service.restore_sbom(id, &ctx.db).await?);
+        assert!(
+            service
+                .fetch_sbom_summary(Id::Uuid(id), &ctx.db)
+                .await?
+                .is_some()
+        );
+
+        Ok(())
+    }
+
+    async fn ingest(
+        ctx: &TrustifyContext,
+        digest: &str,
+        name: &str,
+        published: OffsetDateTime,
+    ) -> Result<Uuid, anyhow::Error> {
+        Ok(ctx
+            .graph
+            .ingest_sbom(
+                Labels::default(),
+                &Digests::digest(digest),
+                Some(format!("http://example.com/{digest}.json")),
+                SbomInformation {
+                    node_id: "root".to_string(),
+                    name: name.to_string(),
+                    published: Some(published),
+                    ..Default::default()
+                },
+                &ctx.db,
+            )
+            .await?
+            .sbom
+            .sbom_id)
+    }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn latest_sboms(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        let _old = ingest(ctx, "app-1", "app", datetime!(2024-01-01 0:00 UTC)).await?;
+        let new = ingest(ctx, "app-2", "app", datetime!(2024-06-01 0:00 UTC)).await?;
+        let lib = ingest(ctx, "lib-1", "lib", datetime!(2024-02-01 0:00 UTC)).await?;
+
+        let service = SbomService::new(ctx.db.clone());
+
+        let fetched = service
+            .fetch_sboms(Query::default(), Paginated::default(), false, (), &ctx.db)
+            .await?;
+        assert_eq!(3, fetched.total);
+
+        let fetched = service
+            .fetch_sboms(Query::default(), Paginated::default(), true, (), &ctx.db)
+            .await?;
+        assert_eq!(2, fetched.total);
+
+        let mut ids = fetched
+            .items
+            .iter()
+            .map(|sbom| sbom.head.id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        let mut expected = vec![new, lib];
+        expected.sort();
+        assert_eq!(ids, expected);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,77 @@
+-- packages of an SBOM, affected by a status of an advisory
+WITH package AS (
+    -- statuses of the purls of the SBOM
+    SELECT
+        "sbom_package"."node_id" AS "id",
+        "sbom_node"."name" AS "name",
+        "sbom_package"."group" AS "group",
+        "sbom_package"."version" AS "version",
+        "qualified_purl"."id" AS "qualified_purl$id",
+        "qualified_purl"."versioned_purl_id" AS "qualified_purl$versioned_purl_id",
+        "qualified_purl"."qualifiers" AS "qualified_purl$qualifiers",
+        "qualified_purl"."purl" AS "qualified_purl$purl"
+    FROM "sbom_package"
+    JOIN "sbom_node" ON "sbom_node"."sbom_id" = "sbom_package"."sbom_id" AND "sbom_node"."node_id" = "sbom_package"."node_id"
+    JOIN "sbom_package_purl_ref" ON "sbom_package_purl_ref"."sbom_id" = "sbom_package"."sbom_id" AND "sbom_package_purl_ref"."node_id" = "sbom_package"."node_id"
+    JOIN "qualified_purl" ON "qualified_purl"."id" = "sbom_package_purl_ref"."qualified_purl_id"
+    JOIN "versioned_purl" ON "versioned_purl"."id" = "qualified_purl"."versioned_purl_id"
+    JOIN "purl_status" ON "purl_status"."base_purl_id" = "versioned_purl"."base_purl_id"
+    JOIN "status" ON "purl_status"."status_id" = "status"."id"
+    LEFT JOIN "version_range" ON "purl_status"."version_range_id" = "version_range"."id"
+    JOIN "advisory" ON "purl_status"."advisory_id" = "advisory"."id"
+    WHERE
+        "sbom_package"."sbom_id" = $1
+        AND "purl_status"."advisory_id" = $2
+        AND "purl_status"."vulnerability_id" = $3
+        AND "status"."slug" = $4
+        AND (
+            ($5 AND "purl_status"."context_cpe_id" = ANY($6::uuid[]) AND "purl_status"."context_cpe_id" IN (
+                SELECT "cpe_id" FROM "sbom_package_cpe_ref" WHERE "sbom_id" = $1
+            ))
+            OR (NOT $5 AND "purl_status"."context_cpe_id" IS NULL)
+        )
+        AND version_matches("versioned_purl"."version", "version_range".*)
+        AND "advisory"."deleted_at" IS NULL
+        AND ($7 OR "advisory"."withdrawn" IS NULL)
+        AND ($8 OR NOT "advisory"."deprecated")
+
+    UNION
+
+    -- statuses of the product the SBOM belongs to
+    SELECT
+        "sbom_package"."node_id" AS "id",
+        "sbom_node"."name" AS "name",
+        "sbom_package"."group" AS "group",
+        "sbom_package"."version" AS "version",
+        "qualified_purl"."id" AS "qualified_purl$id",
+        "qualified_purl"."versioned_purl_id" AS "qualified_purl$versioned_purl_id",
+        "qualified_purl"."qualifiers" AS "qualified_purl$qualifiers",
+        "qualified_purl"."purl" AS "qualified_purl$purl"
+    FROM "sbom"
+    JOIN "product_version" ON "product_version"."sbom_id" = "sbom"."sbom_id"
+    JOIN "product" ON "product_version"."product_id" = "product"."id"
+    JOIN "cpe" ON "product"."cpe_key" = "cpe"."product"
+    JOIN "product_status" ON "cpe"."id" = "product_status"."context_cpe_id" AND "product_status"."package" IS NOT NULL
+    JOIN "product_version_range" ON "product_status"."product_version_range_id" = "product_version_range"."id"
+    JOIN "version_range" ON "product_version_range"."version_range_id" = "version_range"."id" AND version_matches("product_version"."version", "version_range".*)
+    JOIN "base_purl" ON "product_status"."package" = "base_purl"."name" OR "product_status"."package" LIKE CONCAT("base_purl"."namespace", '/', "base_purl"."name")
+    JOIN "versioned_purl" ON "versioned_purl"."base_purl_id" = "base_purl"."id"
+    JOIN "qualified_purl" ON "qualified_purl"."versioned_purl_id" = "versioned_purl"."id"
+    JOIN "sbom_package_purl_ref" ON "sbom_package_purl_ref"."qualified_purl_id" = "qualified_purl"."id" AND "sbom_package_purl_ref"."sbom_id" = "sbom"."sbom_id"
+    JOIN "sbom_package" ON "sbom_package"."sbom_id" = "sbom_package_purl_ref"."sbom_id" AND "sbom_package"."node_id" = "sbom_package_purl_ref"."node_id"
+    JOIN "sbom_node" ON "sbom_node"."sbom_id" = "sbom_package_purl_ref"."sbom_id" AND "sbom_node"."node_id" = "sbom_package_purl_ref"."node_id"
+    JOIN "status" ON "product_status"."status_id" = "status"."id"
+    JOIN "advisory" ON "product_status"."advisory_id" = "advisory"."id"
+    WHERE
+        "sbom"."sbom_id" = $1
+        AND "product_status"."advisory_id" = $2
+        AND "product_status"."vulnerability_id" = $3
+        AND "status"."slug" = $4
+        AND $5 AND "cpe"."id" = ANY($6::uuid[])
+        AND "advisory"."deleted_at" IS NULL
+        AND ($7 OR "advisory"."withdrawn" IS NULL)
+        AND ($8 OR NOT "advisory"."deprecated")
+)
+
+SELECT * FROM package
+ORDER BY "id", "qualified_purl$id"
@@ -1,4 +1,8 @@
-use crate::{sbom::model::SbomExternalPackageReference, sbom::service::SbomService};
+use crate::{
+    sbom::model::{SbomExternalPackageReference, SbomPackage},
+    sbom::service::SbomService,
+    tenant::Tenant,
+};
 use std::str::FromStr;
 use test_context::test_context;
 use test_log::test;
@@ -23,7 +27,7 @@ async fn sbom_details_status(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
     let id_3_2_12 = results[3].id.clone();
 
     let details = service
-        .fetch_sbom_details(id_3_2_12, vec![], &ctx.db)
+        .fetch_sbom_details(id_3_2_12, Default::default(), &Tenant::Any, &ctx.db)
         .await?;
 
     assert!(details.is_some());
@@ -33,7 +37,12 @@ async fn sbom_details_status(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
     log::debug!("{details:#?}");
 
     let details = service
-        .fetch_sbom_details(Id::Uuid(details.summary.head.id), vec![], &ctx.db)
+        .fetch_sbom_details(
+            Id::Uuid(details.summary.head.id),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     assert!(details.is_some());
@@ -77,6 +86,7 @@ async fn count_sboms(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
                 SbomExternalPackageReference::Purl(&both_purl),
                 SbomExternalPackageReference::Purl(&one_purl),
             ],
+            &Tenant::Any,
             &ctx.db,
         )
         .await?;
@@ -85,3 +95,60 @@ async fn count_sboms(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn diff_sboms(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let results = ctx
+        .ingest_documents([
+            "spdx/quarkus-bom-3.2.11.Final-redhat-00001.json",
+            "spdx/quarkus-bom-3.2.12.Final-redhat-00002.json",
+        ])
+        .await?;
+
+    let service = SbomService::new(ctx.db.clone());
+
+    let diff = service
+        .diff_sboms(
+            results[0].id.clone(),
+            results[1].id.clone(),
+            &Tenant::Any,
+            &ctx.db,
+        )
+        .await?
+        .expect("must be found");
+
+    assert_eq!(diff.added.len(), 424);
+    assert_eq!(diff.removed.len(), 424);
+
+    let has_purl = |packages: &[SbomPackage], purl: &str| {
+        packages
+            .iter()
+            .any(|package| package.purl.iter().any(|p| p.head.purl.to_string() == purl))
+    };
+
+    assert!(has_purl(
+        &diff.added,
+        "pkg:maven/io.netty/netty-codec-http@4.1.108.Final-redhat-00001?repository_url=https://maven.repository.redhat.com/ga/&type=jar"
+    ));
+    assert!(has_purl(
+        &diff.removed,
+        "pkg:maven/io.netty/netty-codec-http@4.1.100.Final-redhat-00001?repository_url=https://maven.repository.redhat.com/ga/&type=jar"
+    ));
+    assert!(diff.new_vulnerabilities.is_empty());
+
+    // unknown SBOMs can't be compared
+    assert!(
+        service
+            .diff_sboms(
+                results[0].id.clone(),
+                Id::Uuid(Default::default()),
+                &Tenant::Any,
+                &ctx.db
+            )
+            .await?
+            .is_none()
+    );
+
+    Ok(())
+}
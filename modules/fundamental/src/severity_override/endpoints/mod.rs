@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    severity_override::{
+        model::{SeverityOverride, SeverityOverrideRequest},
+        service::SeverityOverrideService,
+    },
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, delete, get, put, web};
+use trustify_auth::{
+    ReadAdvisory, UpdateSettings, authenticator::user::UserInformation, authorizer::Require,
+};
+use trustify_common::{
+    db::Database,
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults},
+};
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = SeverityOverrideService::new(db);
+
+    config
+        .app_data(web::Data::new(service))
+        .service(list)
+        .service(get)
+        .service(set)
+        .service(delete);
+}
+
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "listSeverityOverrides",
+    params(
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Severity overrides, ordered by vulnerability", body = PaginatedResults<SeverityOverride>),
+    ),
+)]
+#[get("/v2/severity-override")]
+/// List the severity overrides of all vulnerabilities
+pub async fn list(
+    service: web::Data<SeverityOverrideService>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadAdvisory>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(service.list(&tenant, paginated).await?))
+}
+
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "getSeverityOverride",
+    params(
+        ("id", Path, description = "ID of the vulnerability"),
+    ),
+    responses(
+        (status = 200, description = "The severity override of the vulnerability", body = SeverityOverride),
+        (status = 404, description = "The severity of the vulnerability is not overridden", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/vulnerability/{id}/severity-override")]
+/// Retrieve the severity override of a vulnerability
+pub async fn get(
+    service: web::Data<SeverityOverrideService>,
+    id: web::Path<String>,
+    _: Require<ReadAdvisory>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(match service.fetch(&id, &tenant).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "setSeverityOverride",
+    request_body = SeverityOverrideRequest,
+    params(
+        ("id", Path, description = "ID of the vulnerability"),
+    ),
+    responses(
+        (status = 200, description = "The severity override of the vulnerability", body = SeverityOverride),
+        (status = 404, description = "The vulnerability could not be found", body = ErrorInformation),
+    ),
+)]
+#[put("/v2/vulnerability/{id}/severity-override")]
+/// Override the severity of a vulnerability, applied to vulnerability and SBOM details
+pub async fn set(
+    service: web::Data<SeverityOverrideService>,
+    id: web::Path<String>,
+    user: UserInformation,
+    web::Json(request): web::Json<SeverityOverrideRequest>,
+    _: Require<UpdateSettings>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(
+        match service
+            .set(&id, &tenant, request, user.id().map(ToString::to_string))
+            .await?
+        {
+            Some(result) => HttpResponse::Ok().json(result),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "deleteSeverityOverride",
+    params(
+        ("id", Path, description = "ID of the vulnerability"),
+    ),
+    responses(
+        (status = 204, description = "The severity override was deleted"),
+        (status = 404, description = "The severity of the vulnerability is not overridden", body = ErrorInformation),
+    ),
+)]
+#[delete("/v2/vulnerability/{id}/severity-override")]
+/// Delete the severity override of a vulnerability, reverting to the severity of the advisories
+pub async fn delete(
+    service: web::Data<SeverityOverrideService>,
+    id: web::Path<String>,
+    _: Require<UpdateSettings>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(match service.delete(&id, &tenant).await? {
+        true => HttpResponse::NoContent().finish(),
+        false => HttpResponse::NotFound().finish(),
+    })
+}
@@ -0,0 +1,81 @@
+use crate::{severity_override::model::SeverityOverride, test::caller};
+use actix_http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::{Value, json};
+use test_context::test_context;
+use test_log::test;
+use trustify_common::model::PaginatedResults;
+use trustify_cvss::cvss3::severity::Severity;
+use trustify_test_context::{TrustifyContext, call::CallService};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn overlay(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let sbom = ctx
+        .ingest_documents([
+            "quarkus-bom-2.13.8.Final-redhat-00004.json",
+            "csaf/cve-2023-0044.json",
+        ])
+        .await?[0]
+        .id
+        .to_string();
+    let app = caller(ctx).await?;
+
+    // CVE-2023-0044 is of medium severity, treat it as low
+
+    let request = TestRequest::put()
+        .uri("/api/v2/vulnerability/CVE-2023-0044/severity-override")
+        .set_json(json!({"severity": "low", "reason": "Not exposed"}))
+        .to_request();
+    let result: SeverityOverride = app.call_and_read_body_json(request).await;
+    assert_eq!(result.severity, Severity::Low);
+    assert_eq!(result.reason.as_deref(), Some("Not exposed"));
+
+    let request = TestRequest::get()
+        .uri("/api/v2/severity-override")
+        .to_request();
+    let result: PaginatedResults<SeverityOverride> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].vulnerability, "CVE-2023-0044");
+
+    // the original severity is still present
+
+    let request = TestRequest::get()
+        .uri("/api/v2/vulnerability/CVE-2023-0044")
+        .to_request();
+    let v: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(v["average_severity"], "medium");
+    assert_eq!(v["severity_override"]["severity"], "low");
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{sbom}/advisory"))
+        .to_request();
+    let v: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(v[0]["status"][0]["average_severity"], "medium");
+    assert_eq!(v[0]["status"][0]["severity_override"], "low");
+
+    // removing the override reverts to the original severity
+
+    let request = TestRequest::delete()
+        .uri("/api/v2/vulnerability/CVE-2023-0044/severity-override")
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::get()
+        .uri("/api/v2/vulnerability/CVE-2023-0044")
+        .to_request();
+    let v: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(v.get("severity_override"), None);
+
+    // unknown vulnerabilities can't be overridden
+
+    let request = TestRequest::put()
+        .uri("/api/v2/vulnerability/CVE-0000-0000/severity-override")
+        .set_json(json!({"severity": "low"}))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_cvss::cvss3::severity::Severity;
+use trustify_entity::severity_override;
+use utoipa::ToSchema;
+
+/// The severity of a vulnerability as defined by an admin, overriding the severity of the
+/// advisories.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SeverityOverride {
+    /// The identifier of the vulnerability
+    pub vulnerability: String,
+    pub severity: Severity,
+    /// The reasoning behind the override, like why the vulnerability is irrelevant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// The ID of the user defining the override, absent if authentication is disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    /// The time of the last change
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified: OffsetDateTime,
+}
+
+impl From<severity_override::Model> for SeverityOverride {
+    fn from(value: severity_override::Model) -> Self {
+        let severity_override::Model {
+            vulnerability_id,
+            tenant: _,
+            severity,
+            reason,
+            user_id,
+            created,
+            modified,
+        } = value;
+
+        Self {
+            vulnerability: vulnerability_id,
+            severity: severity.into(),
+            reason,
+            user: user_id,
+            created,
+            modified,
+        }
+    }
+}
+
+/// The changeable content of a severity override.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SeverityOverrideRequest {
+    pub severity: Severity,
+    /// The reasoning behind the override, like why the vulnerability is irrelevant
+    #[serde(default)]
+    pub reason: Option<String>,
+}
@@ -0,0 +1,135 @@
+use crate::{
+    Error,
+    severity_override::model::{SeverityOverride, SeverityOverrideRequest},
+    tenant::Tenant,
+};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+    TransactionTrait,
+};
+use time::OffsetDateTime;
+use trustify_common::{
+    db::{Database, limiter::LimiterTrait},
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{severity_override, vulnerability};
+
+pub struct SeverityOverrideService {
+    db: Database,
+}
+
+impl SeverityOverrideService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// List all severity overrides of a tenant, ordered by vulnerability.
+    pub async fn list(
+        &self,
+        tenant: &Tenant,
+        paginated: Paginated,
+    ) -> Result<PaginatedResults<SeverityOverride>, Error> {
+        let limiter = severity_override::Entity::find()
+            .filter(severity_override::Column::Tenant.eq(tenant.name().unwrap_or_default()))
+            .order_by_asc(severity_override::Column::VulnerabilityId)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        Ok(PaginatedResults {
+            total: limiter.total().await?,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(SeverityOverride::from)
+                .collect(),
+        })
+    }
+
+    /// Get the severity override of a vulnerability for a tenant, `None` if there is none.
+    pub async fn fetch(
+        &self,
+        vulnerability: &str,
+        tenant: &Tenant,
+    ) -> Result<Option<SeverityOverride>, Error> {
+        Ok(severity_override::Entity::find_by_id((
+            vulnerability.to_string(),
+            tenant.name().unwrap_or_default().to_string(),
+        ))
+        .one(&self.db)
+        .await?
+        .map(SeverityOverride::from))
+    }
+
+    /// Override the severity of a vulnerability for a tenant, creating or replacing its override.
+    /// Returns `None` if the vulnerability was not found.
+    ///
+    /// The time of creation is kept when replacing an override.
+    pub async fn set(
+        &self,
+        vulnerability: &str,
+        tenant: &Tenant,
+        request: SeverityOverrideRequest,
+        user_id: Option<String>,
+    ) -> Result<Option<SeverityOverride>, Error> {
+        let SeverityOverrideRequest { severity, reason } = request;
+        let key = (
+            vulnerability.to_string(),
+            tenant.name().unwrap_or_default().to_string(),
+        );
+
+        let tx = self.db.begin().await?;
+
+        if vulnerability::Entity::find_by_id(vulnerability)
+            .one(&tx)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let now = OffsetDateTime::now_utc();
+        let model = severity_override::ActiveModel {
+            vulnerability_id: Set(key.0.clone()),
+            tenant: Set(key.1.clone()),
+            severity: Set(severity.into()),
+            reason: Set(reason),
+            user_id: Set(user_id),
+            modified: Set(now),
+            ..Default::default()
+        };
+
+        let result = match severity_override::Entity::find_by_id(key)
+            .lock_exclusive()
+            .one(&tx)
+            .await?
+        {
+            Some(_) => severity_override::Entity::update(model).exec(&tx).await?,
+            None => {
+                severity_override::Entity::insert(severity_override::ActiveModel {
+                    created: Set(now),
+                    ..model
+                })
+                .exec_with_returning(&tx)
+                .await?
+            }
+        };
+
+        tx.commit().await?;
+
+        Ok(Some(result.into()))
+    }
+
+    /// Delete the severity override of a vulnerability for a tenant, returning `false` if there
+    /// was none.
+    pub async fn delete(&self, vulnerability: &str, tenant: &Tenant) -> Result<bool, Error> {
+        let result = severity_override::Entity::delete_by_id((
+            vulnerability.to_string(),
+            tenant.name().unwrap_or_default().to_string(),
+        ))
+        .exec(&self.db)
+        .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
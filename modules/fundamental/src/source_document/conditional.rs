@@ -0,0 +1,361 @@
+//! Conditional and range requests for downloading source documents.
+
+use super::model::SourceDocument;
+use crate::Error;
+use actix_web::{
+    FromRequest, HttpRequest, HttpResponse, HttpResponseBuilder,
+    dev::Payload,
+    http::{
+        StatusCode,
+        header::{
+            self, ContentRangeSpec, EntityTag, Header, HttpDate, IfModifiedSince, IfNoneMatch,
+            IfRange,
+        },
+    },
+};
+use futures_util::TryStreamExt;
+use std::{
+    future::{Ready, ready},
+    ops::Range,
+    time::SystemTime,
+};
+use time::OffsetDateTime;
+use trustify_module_storage::service::{StorageBackend, dispatch::DispatchBackend};
+
+/// Conditional request headers, evaluated against a source document.
+#[derive(Clone, Debug, Default)]
+pub struct Preconditions {
+    if_none_match: Option<IfNoneMatch>,
+    if_modified_since: Option<IfModifiedSince>,
+    range: Option<header::Range>,
+    if_range: Option<IfRange>,
+}
+
+/// The outcome of evaluating a range request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ByteRange {
+    /// Serve the full document.
+    Full,
+    /// Serve only a part of the document.
+    Partial(Range<u64>),
+    /// The requested range can't be served.
+    Unsatisfiable,
+}
+
+impl FromRequest for Preconditions {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        // invalid header values are ignored, the same as if they were absent
+
+        let if_none_match = if req.headers().contains_key(header::IF_NONE_MATCH) {
+            IfNoneMatch::parse(req).ok()
+        } else {
+            None
+        };
+        let if_modified_since = if req.headers().contains_key(header::IF_MODIFIED_SINCE) {
+            IfModifiedSince::parse(req).ok()
+        } else {
+            None
+        };
+        let range = if req.headers().contains_key(header::RANGE) {
+            header::Range::parse(req).ok()
+        } else {
+            None
+        };
+        let if_range = if req.headers().contains_key(header::IF_RANGE) {
+            IfRange::parse(req).ok()
+        } else {
+            None
+        };
+
+        ready(Ok(Self {
+            if_none_match,
+            if_modified_since,
+            range,
+            if_range,
+        }))
+    }
+}
+
+impl Preconditions {
+    /// Check if the copy of the document, held by the client, is still current.
+    ///
+    /// If present, `If-None-Match` takes precedence over `If-Modified-Since`.
+    pub fn not_modified(&self, document: &SourceDocument) -> bool {
+        match (&self.if_none_match, &self.if_modified_since) {
+            (Some(IfNoneMatch::Any), _) => true,
+            (Some(IfNoneMatch::Items(items)), _) => {
+                let etag = document.etag();
+                items.iter().any(|item| item.weak_eq(&etag))
+            }
+            (None, Some(IfModifiedSince(since))) => {
+                let since = OffsetDateTime::from(SystemTime::from(*since));
+                // HTTP dates only have a precision of seconds
+                document.ingested.unix_timestamp() <= since.unix_timestamp()
+            }
+            (None, None) => false,
+        }
+    }
+
+    /// Evaluate the requested range of the document.
+    ///
+    /// Only a single range is supported, requesting multiple ranges will serve the full document.
+    pub fn range(&self, document: &SourceDocument) -> ByteRange {
+        let Some(header::Range::Bytes(ranges)) = &self.range else {
+            return ByteRange::Full;
+        };
+
+        // only serve a range if the client still has the same document
+
+        match &self.if_range {
+            Some(IfRange::EntityTag(etag)) if !etag.strong_eq(&document.etag()) => {
+                return ByteRange::Full;
+            }
+            Some(IfRange::Date(date)) => {
+                let date = OffsetDateTime::from(SystemTime::from(*date));
+                if document.ingested.unix_timestamp() > date.unix_timestamp() {
+                    return ByteRange::Full;
+                }
+            }
+            _ => {}
+        }
+
+        let [range] = ranges.as_slice() else {
+            return ByteRange::Full;
+        };
+
+        match range.to_satisfiable_range(document.size) {
+            Some((start, end)) => ByteRange::Partial(start..end + 1),
+            None => ByteRange::Unsatisfiable,
+        }
+    }
+
+    /// The number of bytes of the document served for the request, if any.
+    pub fn served_bytes(&self, document: &SourceDocument) -> Option<u64> {
+        if self.not_modified(document) {
+            return None;
+        }
+
+        match self.range(document) {
+            ByteRange::Full => Some(document.size),
+            ByteRange::Partial(range) => Some(range.end - range.start),
+            ByteRange::Unsatisfiable => None,
+        }
+    }
+}
+
+/// Download a source document from the storage, evaluating the preconditions of the request.
+pub async fn download(
+    storage: &DispatchBackend,
+    document: &SourceDocument,
+    preconditions: &Preconditions,
+) -> Result<HttpResponse, Error> {
+    if preconditions.not_modified(document) {
+        return Ok(document.response(StatusCode::NOT_MODIFIED).finish());
+    }
+
+    let storage_key = document.try_into()?;
+
+    Ok(match preconditions.range(document) {
+        ByteRange::Full => match storage
+            .retrieve(storage_key)
+            .await
+            .map_err(Error::Storage)?
+        {
+            Some(stream) => document
+                .response(StatusCode::OK)
+                .streaming(stream.map_err(Error::Storage)),
+            None => HttpResponse::NotFound().finish(),
+        },
+        ByteRange::Partial(range) => match storage
+            .retrieve_range(storage_key, range.clone())
+            .await
+            .map_err(Error::Storage)?
+        {
+            Some(stream) => document
+                .response(StatusCode::PARTIAL_CONTENT)
+                .insert_header(header::ContentRange(ContentRangeSpec::Bytes {
+                    range: Some((range.start, range.end - 1)),
+                    instance_length: Some(document.size),
+                }))
+                .no_chunking(range.end - range.start)
+                .streaming(stream.map_err(Error::Storage)),
+            None => HttpResponse::NotFound().finish(),
+        },
+        ByteRange::Unsatisfiable => document
+            .response(StatusCode::RANGE_NOT_SATISFIABLE)
+            .insert_header(header::ContentRange(ContentRangeSpec::Unsatisfied {
+                instance_length: document.size,
+            }))
+            .finish(),
+    })
+}
+
+impl SourceDocument {
+    /// The entity tag of the document, based on its SHA256 digest.
+    pub fn etag(&self) -> EntityTag {
+        let digest = self.sha256.strip_prefix("sha256:").unwrap_or(&self.sha256);
+        EntityTag::new_strong(digest.to_string())
+    }
+
+    /// The last modification of the document, which is the time it was ingested.
+    pub fn last_modified(&self) -> HttpDate {
+        HttpDate::from(SystemTime::from(self.ingested))
+    }
+
+    /// Start a response for the document, carrying its validators.
+    pub fn response(&self, status: StatusCode) -> HttpResponseBuilder {
+        let mut response = HttpResponse::build(status);
+        response
+            .insert_header(header::ETag(self.etag()))
+            .insert_header(header::LastModified(self.last_modified()))
+            .insert_header((header::ACCEPT_RANGES, "bytes"));
+        response
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use std::time::Duration;
+    use test_log::test;
+    use time::macros::datetime;
+
+    fn document() -> SourceDocument {
+        SourceDocument {
+            sha256: "sha256:abcd".into(),
+            sha384: "sha384:abcd".into(),
+            sha512: "sha512:abcd".into(),
+            size: 4,
+            ingested: datetime!(2025-01-02 03:04:05.678 UTC),
+        }
+    }
+
+    async fn preconditions(req: TestRequest) -> Preconditions {
+        let (req, mut payload) = req.to_http_parts();
+        Preconditions::from_request(&req, &mut payload)
+            .await
+            .expect("must not fail")
+    }
+
+    #[test(actix_web::test)]
+    async fn none() {
+        assert!(
+            !preconditions(TestRequest::get())
+                .await
+                .not_modified(&document())
+        );
+    }
+
+    #[test(actix_web::test)]
+    async fn if_none_match() {
+        let doc = document();
+
+        for (value, expected) in [
+            (r#""abcd""#, true),
+            (r#"W/"abcd""#, true),
+            (r#""ef01", "abcd""#, true),
+            (r#""ef01""#, false),
+            ("*", true),
+        ] {
+            let req = TestRequest::get().insert_header((header::IF_NONE_MATCH, value));
+            assert_eq!(
+                preconditions(req).await.not_modified(&doc),
+                expected,
+                "{value}"
+            );
+        }
+    }
+
+    #[test(actix_web::test)]
+    async fn if_modified_since() {
+        let doc = document();
+        let ingested = SystemTime::from(doc.ingested);
+
+        for (since, expected) in [
+            (ingested, true),
+            (ingested + Duration::from_secs(60), true),
+            (ingested - Duration::from_secs(60), false),
+        ] {
+            let req =
+                TestRequest::get().insert_header(header::IfModifiedSince(HttpDate::from(since)));
+            assert_eq!(preconditions(req).await.not_modified(&doc), expected);
+        }
+    }
+
+    #[test(actix_web::test)]
+    async fn range() {
+        let doc = document();
+
+        for (value, expected) in [
+            (None, ByteRange::Full),
+            (Some("bytes=0-1"), ByteRange::Partial(0..2)),
+            (Some("bytes=1-"), ByteRange::Partial(1..4)),
+            (Some("bytes=-2"), ByteRange::Partial(2..4)),
+            (Some("bytes=2-10"), ByteRange::Partial(2..4)),
+            (Some("bytes=0-0,2-3"), ByteRange::Full),
+            (Some("bytes=4-"), ByteRange::Unsatisfiable),
+        ] {
+            let mut req = TestRequest::get();
+            if let Some(value) = value {
+                req = req.insert_header((header::RANGE, value));
+            }
+            assert_eq!(preconditions(req).await.range(&doc), expected, "{value:?}");
+        }
+    }
+
+    #[test(actix_web::test)]
+    async fn if_range() {
+        let doc = document();
+
+        for (value, expected) in [
+            (r#""abcd""#, ByteRange::Partial(0..2)),
+            (r#""ef01""#, ByteRange::Full),
+            (r#"W/"abcd""#, ByteRange::Full),
+            ("Thu, 02 Jan 2025 03:04:05 GMT", ByteRange::Partial(0..2)),
+            ("Thu, 02 Jan 2025 03:04:04 GMT", ByteRange::Full),
+        ] {
+            let req = TestRequest::get()
+                .insert_header((header::RANGE, "bytes=0-1"))
+                .insert_header((header::IF_RANGE, value));
+            assert_eq!(preconditions(req).await.range(&doc), expected, "{value}");
+        }
+    }
+
+    #[test(actix_web::test)]
+    async fn served_bytes() {
+        let doc = document();
+
+        for (req, expected) in [
+            (TestRequest::get(), Some(4)),
+            (
+                TestRequest::get().insert_header((header::RANGE, "bytes=1-2")),
+                Some(2),
+            ),
+            (
+                TestRequest::get().insert_header((header::RANGE, "bytes=4-")),
+                None,
+            ),
+            (
+                TestRequest::get().insert_header((header::IF_NONE_MATCH, r#""abcd""#)),
+                None,
+            ),
+        ] {
+            assert_eq!(preconditions(req).await.served_bytes(&doc), expected);
+        }
+    }
+
+    #[test(actix_web::test)]
+    async fn if_none_match_takes_precedence() {
+        let req = TestRequest::get()
+            .insert_header((header::IF_NONE_MATCH, r#""ef01""#))
+            .insert_header(header::IfModifiedSince(HttpDate::from(
+                SystemTime::from(document().ingested) + Duration::from_secs(60),
+            )));
+
+        assert!(!preconditions(req).await.not_modified(&document()));
+    }
+}
@@ -1 +1,30 @@
+pub mod conditional;
 pub mod model;
+
+use crate::Error;
+use futures_util::TryStreamExt;
+use model::SourceDocument;
+use trustify_module_storage::service::{StorageBackend, dispatch::DispatchBackend};
+
+/// Retrieve the full content of a source document. Returns `None` if it is missing from the
+/// storage.
+pub async fn retrieve(
+    storage: &DispatchBackend,
+    document: &SourceDocument,
+) -> Result<Option<Vec<u8>>, Error> {
+    let Some(stream) = storage
+        .retrieve(document.try_into()?)
+        .await
+        .map_err(Error::Storage)?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(
+        stream
+            .map_ok(|chunk| chunk.to_vec())
+            .try_concat()
+            .await
+            .map_err(Error::Storage)?,
+    ))
+}
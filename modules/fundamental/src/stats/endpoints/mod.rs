@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    stats::{model::Stats, service::StatsService},
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, get, web};
+use trustify_auth::{ReadAdvisory, ReadSbom, authorizer::Require};
+use trustify_common::db::Database;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = StatsService::new(db);
+
+    config.app_data(web::Data::new(service)).service(get);
+}
+
+#[utoipa::path(
+    tag = "stats",
+    operation_id = "getStats",
+    responses(
+        (status = 200, description = "Summary counts, as of their last refresh", body = Stats),
+    ),
+)]
+#[get("/v2/stats")]
+/// Get summary counts of advisories, SBOMs, vulnerabilities, and purls
+pub async fn get(
+    service: web::Data<StatsService>,
+    _: Require<ReadAdvisory>,
+    _: Require<ReadSbom>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(service.fetch(&tenant).await?))
+}
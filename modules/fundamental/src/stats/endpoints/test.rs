@@ -0,0 +1,88 @@
+use crate::{
+    stats::{model::Stats, service::StatsService},
+    test::{caller, caller_with, tenant_isolation, tenant_user},
+};
+use actix_http::StatusCode;
+use actix_web::test::TestRequest;
+use test_context::test_context;
+use test_log::test;
+use trustify_test_context::{
+    TrustifyContext, auth::TestAuthentication, call::CallService, document_bytes,
+};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn counts(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_documents([
+        "zookeeper-3.9.2-cyclonedx.json",
+        "mitre/CVE-2024-27088.json",
+    ])
+    .await?;
+    let app = caller(ctx).await?;
+
+    // nothing is counted before the view got refreshed
+
+    let request = TestRequest::get().uri("/api/v2/stats").to_request();
+    let result: Stats = app.call_and_read_body_json(request).await;
+    assert_eq!(result.sboms, 0);
+    assert_eq!(result.advisories.total, 0);
+
+    StatsService::new(ctx.db.clone()).refresh().await?;
+
+    let request = TestRequest::get().uri("/api/v2/stats").to_request();
+    let result: Stats = app.call_and_read_body_json(request).await;
+    assert!(result.refreshed.is_some());
+    assert_eq!(result.sboms, 1);
+    assert_eq!(result.vulnerabilities, 1);
+    assert_eq!(result.advisories.total, 1);
+    assert_eq!(result.advisories.by_type.get("cve"), Some(&1));
+    assert_eq!(result.advisories.by_severity.values().sum::<u64>(), 1);
+    assert!(result.purls.base > 0);
+    assert!(result.purls.qualified >= result.purls.base);
+    assert_eq!(
+        result.ecosystems.values().map(|e| e.packages).sum::<u64>(),
+        result.purls.base
+    );
+    assert!(result.ecosystems.contains_key("maven"));
+    assert_eq!(result.ingested.day.sboms, 1);
+    assert_eq!(result.ingested.day.advisories, 1);
+    assert_eq!(result.ingested.week.sboms, 1);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn tenant_counts(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_with(ctx, tenant_isolation()).await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/sbom")
+        .set_payload(document_bytes("zookeeper-3.9.2-cyclonedx.json").await?)
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    StatsService::new(ctx.db.clone()).refresh().await?;
+
+    let request = TestRequest::get()
+        .uri("/api/v2/stats")
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let result: Stats = app.call_and_read_body_json(request).await;
+    assert_eq!(result.sboms, 1);
+    assert!(result.purls.base > 0);
+
+    // the documents of other tenants are not counted
+
+    let request = TestRequest::get()
+        .uri("/api/v2/stats")
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let result: Stats = app.call_and_read_body_json(request).await;
+    assert_eq!(result.sboms, 0);
+    assert_eq!(result.purls.base, 0);
+
+    Ok(())
+}
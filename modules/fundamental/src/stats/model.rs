@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+/// Summary counts of the stored information, refreshed periodically.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Stats {
+    /// When the counts were last refreshed
+    #[serde(with = "time::serde::rfc3339::option")]
+    #[schema(required)]
+    pub refreshed: Option<OffsetDateTime>,
+    pub advisories: AdvisoryStats,
+    pub sboms: u64,
+    pub vulnerabilities: u64,
+    pub purls: PurlStats,
+    /// Packages and vulnerabilities by the ecosystem of the packages, like `npm` or `oci`
+    pub ecosystems: BTreeMap<String, EcosystemStats>,
+    /// Documents ingested recently
+    pub ingested: IngestionStats,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AdvisoryStats {
+    pub total: u64,
+    /// Advisories by their type, like `csaf` or `cve`
+    pub by_type: BTreeMap<String, u64>,
+    /// Advisories by the highest CVSS v3 severity they report, `unknown` if they report none
+    pub by_severity: BTreeMap<String, u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PurlStats {
+    /// Packages, without version and qualifiers
+    pub base: u64,
+    /// Fully qualified purls
+    pub qualified: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct EcosystemStats {
+    /// Packages, without version and qualifiers
+    pub packages: u64,
+    /// Vulnerabilities advisories state a status of packages of the ecosystem for
+    pub vulnerabilities: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct IngestionStats {
+    /// During the last 24 hours
+    pub day: IngestedDocuments,
+    /// During the last 7 days
+    pub week: IngestedDocuments,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct IngestedDocuments {
+    pub advisories: u64,
+    pub sboms: u64,
+}
@@ -0,0 +1,100 @@
+use crate::{Error, stats::model::Stats, tenant::Tenant};
+use sea_orm::{ConnectionTrait, DbBackend, FromQueryResult, Statement};
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::time::{Instant, interval_at};
+use trustify_common::db::Database;
+
+pub struct StatsService {
+    db: Database,
+}
+
+impl StatsService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Get the counts of the documents of the tenant, as of the last refresh.
+    ///
+    /// Without tenant isolation, the counts cover all documents.
+    pub async fn fetch(&self, tenant: &Tenant) -> Result<Stats, Error> {
+        let rows = StatsRow::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT "category", "key", "count", "refreshed" FROM "summary_stats" WHERE "tenant" = $1"#,
+            [tenant.name().unwrap_or_default().into()],
+        ))
+        .all(&self.db)
+        .await?;
+
+        let mut stats = Stats::default();
+
+        for row in rows {
+            let count = row.count as u64;
+            stats.refreshed = Some(row.refreshed);
+
+            match (row.category.as_str(), row.key) {
+                ("advisory_type", key) => {
+                    stats.advisories.total += count;
+                    stats.advisories.by_type.insert(key, count);
+                }
+                ("advisory_severity", key) => {
+                    stats.advisories.by_severity.insert(key, count);
+                }
+                ("sbom", _) => stats.sboms = count,
+                ("vulnerability", _) => stats.vulnerabilities = count,
+                ("base_purl", _) => stats.purls.base = count,
+                ("qualified_purl", _) => stats.purls.qualified = count,
+                ("ecosystem_base_purl", key) => {
+                    stats.ecosystems.entry(key).or_default().packages = count
+                }
+                ("ecosystem_vulnerability", key) => {
+                    stats.ecosystems.entry(key).or_default().vulnerabilities = count
+                }
+                ("ingested_advisory", key) if key == "day" => stats.ingested.day.advisories = count,
+                ("ingested_advisory", key) if key == "week" => {
+                    stats.ingested.week.advisories = count
+                }
+                ("ingested_sbom", key) if key == "day" => stats.ingested.day.sboms = count,
+                ("ingested_sbom", key) if key == "week" => stats.ingested.week.sboms = count,
+                (category, key) => log::debug!("Ignoring unknown count: {category}/{key}"),
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Refresh the counts, without blocking readers.
+    pub async fn refresh(&self) -> Result<(), Error> {
+        self.db
+            .execute_unprepared(r#"REFRESH MATERIALIZED VIEW CONCURRENTLY "summary_stats""#)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Keep refreshing the counts, until the future gets dropped.
+    pub async fn run(self, period: Duration) -> anyhow::Result<()> {
+        log::info!("Refreshing summary counts every {period:?}");
+
+        // the counts got computed when creating the view, or by a previous instance
+        let mut interval = interval_at(Instant::now() + period, period);
+
+        loop {
+            interval.tick().await;
+
+            let start = Instant::now();
+            match self.refresh().await {
+                Ok(()) => log::debug!("Refreshed summary counts in {:?}", start.elapsed()),
+                Err(err) => log::warn!("Failed to refresh summary counts: {err}"),
+            }
+        }
+    }
+}
+
+#[derive(FromQueryResult)]
+struct StatsRow {
+    category: String,
+    key: String,
+    count: i64,
+    refreshed: OffsetDateTime,
+}
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    subscription::{
+        model::{NewSubscription, Notification, Subscription},
+        service::{SubscriptionService, WebhookAllowlist},
+    },
+    tenant::Tenant,
+};
+use actix_web::{HttpResponse, Responder, delete, get, post, web};
+use trustify_auth::authenticator::user::UserDetails;
+use trustify_common::{
+    db::Database,
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults},
+};
+use uuid::Uuid;
+
+pub fn configure(
+    config: &mut utoipa_actix_web::service_config::ServiceConfig,
+    db: Database,
+    allowlist: WebhookAllowlist,
+) {
+    let service = SubscriptionService::new(db, allowlist);
+
+    config
+        .app_data(web::Data::new(service))
+        .service(list)
+        .service(create)
+        .service(delete)
+        .service(notifications);
+}
+
+#[utoipa::path(
+    tag = "subscription",
+    operation_id = "listSubscriptions",
+    params(
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "The subscriptions of the current user, the most recent first", body = PaginatedResults<Subscription>),
+    ),
+)]
+#[get("/v2/subscription")]
+/// List the subscriptions of the current user
+pub async fn list(
+    service: web::Data<SubscriptionService>,
+    web::Query(paginated): web::Query<Paginated>,
+    user: UserDetails,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(service.list(&user.id, paginated).await?))
+}
+
+#[utoipa::path(
+    tag = "subscription",
+    operation_id = "createSubscription",
+    request_body = NewSubscription,
+    responses(
+        (status = 201, description = "Subscribed to the saved search", body = Subscription),
+        (status = 400, description = "The saved search doesn't exist, or the webhook is invalid", body = ErrorInformation),
+        (status = 409, description = "The current user is already subscribed to the saved search", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/subscription")]
+/// Subscribe the current user to one of their saved searches
+///
+/// Each SBOM or advisory matching the search, which gets ingested afterwards, results in a
+/// notification.
+pub async fn create(
+    service: web::Data<SubscriptionService>,
+    user: UserDetails,
+    tenant: Tenant,
+    web::Json(request): web::Json<NewSubscription>,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Created().json(service.create(user.id, &tenant, request).await?))
+}
+
+#[utoipa::path(
+    tag = "subscription",
+    operation_id = "deleteSubscription",
+    params(
+        ("id" = Uuid, Path, description = "ID of the subscription"),
+    ),
+    responses(
+        (status = 204, description = "The subscription and its notifications were deleted"),
+        (status = 404, description = "The current user has no subscription with this ID", body = ErrorInformation),
+    ),
+)]
+#[delete("/v2/subscription/{id}")]
+/// Delete a subscription of the current user
+pub async fn delete(
+    service: web::Data<SubscriptionService>,
+    id: web::Path<Uuid>,
+    user: UserDetails,
+) -> actix_web::Result<impl Responder> {
+    Ok(match service.delete(&user.id, id.into_inner()).await? {
+        true => HttpResponse::NoContent().finish(),
+        false => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "subscription",
+    operation_id = "listNotifications",
+    params(
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "The notifications of the current user, the most recent first", body = PaginatedResults<Notification>),
+    ),
+)]
+#[get("/v2/notification")]
+/// List the notifications of the current user
+pub async fn notifications(
+    service: web::Data<SubscriptionService>,
+    web::Query(paginated): web::Query<Paginated>,
+    user: UserDetails,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(service.notifications(&user.id, paginated).await?))
+}
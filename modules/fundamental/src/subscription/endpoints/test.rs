@@ -0,0 +1,174 @@
+use crate::{
+    endpoints::Config,
+    saved_search::model::{SavedSearch, SavedSearchKind},
+    subscription::{
+        model::{Notification, Subscription},
+        service::{SubscriptionListener, WebhookAllowlist},
+    },
+    test::{caller, caller_with},
+};
+use actix_http::StatusCode;
+use actix_web::test::{TestRequest, read_body_json};
+use serde_json::json;
+use test_context::test_context;
+use test_log::test;
+use trustify_common::{id::Id, model::PaginatedResults};
+use trustify_module_ingestor::{model::IngestResult, service::listener::IngestListeners};
+use trustify_test_context::{
+    TrustifyContext, auth::TestAuthentication, call::CallService, document_bytes,
+};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn notify(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    // the test webhook is local
+    let webhook_allowlist = WebhookAllowlist(vec!["localhost".into()]);
+    let app = caller_with(
+        ctx,
+        Config {
+            listeners: IngestListeners::new().with(SubscriptionListener::new(
+                ctx.db.clone(),
+                webhook_allowlist.clone(),
+            )?),
+            webhook_allowlist,
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/saved-search")
+        .set_json(json!({"name": "cyclonedx", "kind": "sbom", "labels": "type=cyclonedx"}))
+        .to_request()
+        .test_auth("user-a");
+    let search: SavedSearch = app.call_and_read_body_json(request).await;
+
+    // only the owner of a search can subscribe to it, and only once
+
+    let request = TestRequest::post()
+        .uri("/api/v2/subscription")
+        .set_json(json!({"saved_search_id": search.id}))
+        .to_request()
+        .test_auth("user-b");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = TestRequest::post()
+        .uri("/api/v2/subscription")
+        .set_json(json!({"saved_search_id": search.id, "webhook": "ftp://localhost"}))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // internal hosts, which are not on the allowlist, are rejected
+    let request = TestRequest::post()
+        .uri("/api/v2/subscription")
+        .set_json(json!({"saved_search_id": search.id, "webhook": "http://169.254.169.254/"}))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // nothing listens on port 1, so delivering to the webhook fails
+    let request = TestRequest::post()
+        .uri("/api/v2/subscription")
+        .set_json(json!({"saved_search_id": search.id, "webhook": "http://localhost:1/"}))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let subscription: Subscription = read_body_json(response).await;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/subscription")
+        .set_json(json!({"saved_search_id": search.id}))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // ingest a matching and a non-matching SBOM
+
+    let mut ids = vec![];
+    for path in [
+        "zookeeper-3.9.2-cyclonedx.json",
+        "spdx/quarkus-bom-3.2.11.Final-redhat-00001.json",
+    ] {
+        let request = TestRequest::post()
+            .uri("/api/v2/sbom")
+            .set_payload(document_bytes(path).await?)
+            .to_request();
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let result: IngestResult = read_body_json(response).await;
+        let Id::Uuid(id) = result.id else {
+            panic!("unexpected ID: {}", result.id);
+        };
+        ids.push(id);
+    }
+
+    // the webhook gets called in the background, wait for the outcome
+    let mut result: PaginatedResults<Notification>;
+    let mut attempts = 0;
+    loop {
+        let request = TestRequest::get()
+            .uri("/api/v2/notification")
+            .to_request()
+            .test_auth("user-a");
+        result = app.call_and_read_body_json(request).await;
+        assert_eq!(result.total, 1);
+        attempts += 1;
+        if result.items[0].error.is_some() || attempts == 50 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    let notification = &result.items[0];
+    assert_eq!(notification.subscription_id, subscription.id);
+    assert_eq!(notification.kind, SavedSearchKind::Sbom);
+    assert_eq!(notification.document_id, ids[0]);
+    assert_eq!(notification.delivered, None);
+    assert!(notification.error.is_some());
+
+    // other users don't get notified
+
+    let request = TestRequest::get()
+        .uri("/api/v2/notification")
+        .to_request()
+        .test_auth("user-b");
+    let result: PaginatedResults<Notification> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 0);
+
+    // unsubscribing removes the notifications
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/subscription/{}", subscription.id))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::get()
+        .uri("/api/v2/notification")
+        .to_request()
+        .test_auth("user-a");
+    let result: PaginatedResults<Notification> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 0);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn anonymous(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller(ctx).await?;
+
+    for uri in ["/api/v2/subscription", "/api/v2/notification"] {
+        let request = TestRequest::get().uri(uri).to_request();
+        let response = app.call_service(request).await;
+        assert!(response.status().is_client_error());
+    }
+
+    Ok(())
+}
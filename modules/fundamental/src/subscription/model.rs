@@ -0,0 +1,103 @@
+use crate::saved_search::model::{SavedSearch, SavedSearchKind};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::{notification, subscription};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A subscription of the current user to one of their saved searches.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Subscription {
+    pub id: Uuid,
+    pub saved_search_id: Uuid,
+    /// The URL notifications get posted to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+}
+
+impl From<subscription::Model> for Subscription {
+    fn from(value: subscription::Model) -> Self {
+        let subscription::Model {
+            id,
+            user_id: _,
+            saved_search_id,
+            webhook,
+            tenant: _,
+            created,
+        } = value;
+
+        Self {
+            id,
+            saved_search_id,
+            webhook,
+            created,
+        }
+    }
+}
+
+/// A subscription to create.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct NewSubscription {
+    /// The saved search of the current user to subscribe to
+    pub saved_search_id: Uuid,
+    /// An `http` or `https` URL, which each notification gets posted to as JSON
+    #[serde(default)]
+    pub webhook: Option<String>,
+}
+
+/// A notification about an ingested SBOM or advisory, matching the search of a subscription.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Notification {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub kind: SavedSearchKind,
+    /// The ID of the SBOM or advisory
+    pub document_id: Uuid,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    /// When the notification got delivered to the webhook of the subscription
+    #[serde(
+        default,
+        with = "time::serde::rfc3339::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub delivered: Option<OffsetDateTime>,
+    /// Why delivering the notification to the webhook of the subscription failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<notification::Model> for Notification {
+    fn from(value: notification::Model) -> Self {
+        let notification::Model {
+            id,
+            subscription_id,
+            user_id: _,
+            kind,
+            document_id,
+            created,
+            delivered,
+            error,
+        } = value;
+
+        Self {
+            id,
+            subscription_id,
+            kind,
+            document_id,
+            created,
+            delivered,
+            error,
+        }
+    }
+}
+
+/// The payload posted to the webhook of a subscription.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct NotificationEvent {
+    pub notification: Notification,
+    /// The search the document matched
+    pub saved_search: SavedSearch,
+}
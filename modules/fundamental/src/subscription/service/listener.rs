@@ -0,0 +1,180 @@
+use crate::{
+    Error,
+    saved_search::{
+        model::{SavedSearch, SavedSearchKind},
+        service::SavedSearchService,
+    },
+    subscription::{
+        model::{Notification, NotificationEvent},
+        service::WebhookAllowlist,
+    },
+    tenant::Tenant,
+};
+use async_trait::async_trait;
+use sea_orm::{ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, prelude::Uuid};
+use std::time::Duration;
+use time::OffsetDateTime;
+use trustify_common::{db::Database, id::Id};
+use trustify_entity::{notification, saved_search, subscription};
+use trustify_module_ingestor::{
+    model::IngestResult,
+    service::{Format, listener::IngestListener},
+};
+
+/// The timeout of posting a notification to a webhook.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Notifies the subscribers of saved searches about ingested documents matching their searches.
+///
+/// Only the ingested document is evaluated against each search, instead of re-running the
+/// searches in full. Failing to notify subscribers is logged, but doesn't fail the ingestion.
+///
+/// The notifications are recorded while ingesting, but delivered to webhooks in the background,
+/// so that slow webhooks don't delay the ingestion.
+#[derive(Clone)]
+pub struct SubscriptionListener {
+    db: Database,
+    client: reqwest::Client,
+    allowlist: WebhookAllowlist,
+}
+
+impl SubscriptionListener {
+    pub fn new(db: Database, allowlist: WebhookAllowlist) -> anyhow::Result<Self> {
+        Ok(Self {
+            db,
+            client: allowlist.client(WEBHOOK_TIMEOUT)?,
+            allowlist,
+        })
+    }
+
+    async fn notify(&self, kind: SavedSearchKind, id: Uuid) -> Result<(), Error> {
+        let subscriptions = subscription::Entity::find()
+            .find_also_related(saved_search::Entity)
+            .filter(saved_search::Column::Kind.eq(kind))
+            .all(&self.db)
+            .await?;
+
+        let searches = SavedSearchService::new(self.db.clone());
+        let mut deliveries = vec![];
+
+        for (subscription, search) in subscriptions {
+            let Some(search) = search.map(SavedSearch::from) else {
+                continue;
+            };
+
+            let tenant = match &subscription.tenant {
+                Some(tenant) => Tenant::Of(tenant.clone()),
+                None => Tenant::Any,
+            };
+
+            match searches.matches(&search, &tenant, id).await {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(err) => {
+                    log::warn!(
+                        "Failed to evaluate the saved search {} of subscription {}: {err}",
+                        search.id,
+                        subscription.id
+                    );
+                    continue;
+                }
+            }
+
+            let notification = notification::Entity::insert(notification::ActiveModel {
+                id: Set(Uuid::now_v7()),
+                subscription_id: Set(subscription.id),
+                user_id: Set(subscription.user_id),
+                kind: Set(kind),
+                document_id: Set(id),
+                created: Set(OffsetDateTime::now_utc()),
+                delivered: Set(None),
+                error: Set(None),
+            })
+            .exec_with_returning(&self.db)
+            .await?;
+
+            if let Some(webhook) = subscription.webhook {
+                deliveries.push((webhook, Notification::from(notification), search));
+            }
+        }
+
+        if !deliveries.is_empty() {
+            let listener = self.clone();
+            tokio::spawn(async move {
+                for (webhook, notification, search) in deliveries {
+                    let id = notification.id;
+                    if let Err(err) = listener.deliver(&webhook, notification, search).await {
+                        log::warn!("Failed to record the delivery of notification {id}: {err}");
+                    }
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Post a notification to a webhook, recording the outcome with the notification.
+    ///
+    /// The webhook is checked again, as the allowlist might have changed since subscribing.
+    async fn deliver(
+        &self,
+        webhook: &str,
+        notification: Notification,
+        saved_search: SavedSearch,
+    ) -> Result<(), Error> {
+        let id = notification.id;
+
+        let result = match self.allowlist.validate(webhook) {
+            Ok(url) => self
+                .client
+                .post(url)
+                .json(&NotificationEvent {
+                    notification,
+                    saved_search,
+                })
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+
+        let (delivered, error) = match result {
+            Ok(_) => (Some(OffsetDateTime::now_utc()), None),
+            Err(err) => {
+                log::info!("Failed to deliver notification {id} to {webhook}: {err}");
+                (None, Some(err))
+            }
+        };
+
+        notification::Entity::update_many()
+            .filter(notification::Column::Id.eq(id))
+            .set(notification::ActiveModel {
+                delivered: Set(delivered),
+                error: Set(error),
+                ..Default::default()
+            })
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl IngestListener for SubscriptionListener {
+    async fn ingested(&self, format: Format, result: &IngestResult) {
+        let kind = match format {
+            Format::SPDX | Format::CycloneDX => SavedSearchKind::Sbom,
+            Format::CSAF | Format::OSV | Format::CVE => SavedSearchKind::Advisory,
+            _ => return,
+        };
+        let Id::Uuid(id) = result.id else {
+            return;
+        };
+
+        if let Err(err) = self.notify(kind, id).await {
+            log::warn!("Failed to notify subscribers about {}: {err}", result.id);
+        }
+    }
+}
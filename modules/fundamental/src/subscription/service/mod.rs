@@ -0,0 +1,139 @@
+mod listener;
+mod webhook;
+
+pub use listener::SubscriptionListener;
+pub use webhook::WebhookAllowlist;
+
+use crate::{
+    Error,
+    subscription::model::{NewSubscription, Notification, Subscription},
+    tenant::Tenant,
+};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, SqlErr, prelude::Uuid,
+};
+use time::OffsetDateTime;
+use trustify_common::{
+    db::{Database, limiter::LimiterTrait},
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{notification, saved_search, subscription};
+
+pub struct SubscriptionService {
+    db: Database,
+    allowlist: WebhookAllowlist,
+}
+
+impl SubscriptionService {
+    pub fn new(db: Database, allowlist: WebhookAllowlist) -> Self {
+        Self { db, allowlist }
+    }
+
+    /// Subscribe a user to one of their saved searches. Fails if the user is already subscribed
+    /// to it.
+    ///
+    /// Only documents of the tenant of the user will result in notifications. The webhook must not
+    /// refer to an internal host, unless it is on the allowlist.
+    pub async fn create(
+        &self,
+        user_id: String,
+        tenant: &Tenant,
+        request: NewSubscription,
+    ) -> Result<Subscription, Error> {
+        let NewSubscription {
+            saved_search_id,
+            webhook,
+        } = request;
+
+        if let Some(webhook) = &webhook {
+            self.allowlist.validate(webhook)?;
+        }
+
+        let search = saved_search::Entity::find_by_id(saved_search_id)
+            .filter(saved_search::Column::UserId.eq(&user_id))
+            .one(&self.db)
+            .await?;
+        if search.is_none() {
+            return Err(Error::BadRequest(format!(
+                "No saved search with ID '{saved_search_id}'"
+            )));
+        }
+
+        let result = subscription::Entity::insert(subscription::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            user_id: Set(user_id),
+            saved_search_id: Set(saved_search_id),
+            webhook: Set(webhook),
+            tenant: Set(match tenant {
+                Tenant::Any => None,
+                Tenant::Of(tenant) => Some(tenant.clone()),
+            }),
+            created: Set(OffsetDateTime::now_utc()),
+        })
+        .exec_with_returning(&self.db)
+        .await
+        .map_err(|err| match err.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => Error::Conflict(format!(
+                "Already subscribed to the saved search '{saved_search_id}'"
+            )),
+            _ => err.into(),
+        })?;
+
+        Ok(result.into())
+    }
+
+    /// List the subscriptions of a user, the most recent first.
+    pub async fn list(
+        &self,
+        user_id: &str,
+        paginated: Paginated,
+    ) -> Result<PaginatedResults<Subscription>, Error> {
+        let limiter = subscription::Entity::find()
+            .filter(subscription::Column::UserId.eq(user_id))
+            .order_by_desc(subscription::Column::Created)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        let total = limiter.total().await?;
+        let items = limiter.fetch().await?;
+
+        Ok(PaginatedResults {
+            items: items.into_iter().map(Subscription::from).collect(),
+            total,
+        })
+    }
+
+    /// Delete a subscription of a user, along with its notifications. Returns `false` if it was
+    /// not found.
+    pub async fn delete(&self, user_id: &str, id: Uuid) -> Result<bool, Error> {
+        let result = subscription::Entity::delete_many()
+            .filter(subscription::Column::Id.eq(id))
+            .filter(subscription::Column::UserId.eq(user_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// List the notifications of a user, the most recent first.
+    pub async fn notifications(
+        &self,
+        user_id: &str,
+        paginated: Paginated,
+    ) -> Result<PaginatedResults<Notification>, Error> {
+        let limiter = notification::Entity::find()
+            .filter(notification::Column::UserId.eq(user_id))
+            .order_by_desc(notification::Column::Created)
+            .order_by_desc(notification::Column::Id)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        let total = limiter.total().await?;
+        let items = limiter.fetch().await?;
+
+        Ok(PaginatedResults {
+            items: items.into_iter().map(Notification::from).collect(),
+            total,
+        })
+    }
+}
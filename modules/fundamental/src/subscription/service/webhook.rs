@@ -0,0 +1,162 @@
+//! Destinations of webhooks.
+//!
+//! Webhooks are called by the server, so they must not reach services only the server can reach:
+//! loopback, private, and link-local addresses are rejected, unless the host is on the allowlist.
+//! The addresses of host names are checked when delivering, as they may change in between.
+
+use crate::Error;
+use reqwest::{
+    Url,
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect,
+};
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+use trustify_common::reqwest::ClientFactory;
+
+/// Hosts which webhooks may reach, even though they resolve to internal addresses.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WebhookAllowlist(pub Vec<String>);
+
+impl WebhookAllowlist {
+    fn allows(&self, host: &str) -> bool {
+        self.0
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(host))
+    }
+
+    /// Check the URL of a webhook, as far as possible without resolving its host.
+    pub fn validate(&self, webhook: &str) -> Result<Url, Error> {
+        let url = Url::parse(webhook)
+            .map_err(|err| Error::BadRequest(format!("Invalid webhook URL: {err}")))?;
+
+        match url.scheme() {
+            "http" | "https" => {}
+            scheme => {
+                return Err(Error::BadRequest(format!(
+                    "Unsupported scheme of the webhook URL: {scheme}"
+                )));
+            }
+        }
+
+        let Some(host) = url.host_str() else {
+            return Err(Error::BadRequest("The webhook URL has no host".into()));
+        };
+
+        let internal = match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+            Ok(ip) => is_internal(ip),
+            Err(_) => {
+                let domain = host.trim_end_matches('.').to_ascii_lowercase();
+                domain == "localhost" || domain.ends_with(".localhost")
+            }
+        };
+
+        if internal && !self.allows(host) {
+            return Err(Error::BadRequest(format!(
+                "The webhook URL must not refer to an internal host: {host}"
+            )));
+        }
+
+        Ok(url)
+    }
+
+    /// Create a client for calling webhooks, refusing to connect to internal addresses of hosts
+    /// which are not on the allowlist. Redirects are not followed, as they could lead anywhere.
+    pub fn client(&self, timeout: Duration) -> anyhow::Result<reqwest::Client> {
+        Ok(ClientFactory::new()
+            .new_builder()?
+            .timeout(timeout)
+            .redirect(redirect::Policy::none())
+            .dns_resolver(Arc::new(Resolver(self.clone())))
+            .build()?)
+    }
+}
+
+/// Resolves the hosts of webhooks, dropping internal addresses of hosts not on the allowlist.
+struct Resolver(WebhookAllowlist);
+
+impl Resolve for Resolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let allowed = self.0.allows(name.as_str());
+        Box::pin(resolve(name.as_str().to_string(), allowed))
+    }
+}
+
+async fn resolve(
+    host: String,
+    allowed: bool,
+) -> Result<Addrs, Box<dyn std::error::Error + Send + Sync>> {
+    let addrs = tokio::net::lookup_host((host.as_str(), 0))
+        .await?
+        .filter(|addr| allowed || !is_internal(addr.ip()))
+        .collect::<Vec<SocketAddr>>();
+
+    if addrs.is_empty() {
+        return Err(format!("{host} has no public address").into());
+    }
+
+    Ok(Box::new(addrs.into_iter()))
+}
+
+/// Check if an address is only reachable from the host or its local network.
+fn is_internal(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_internal(IpAddr::V4(ip)),
+            None => {
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local()
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        for (webhook, valid) in [
+            ("https://example.com/hook", true),
+            ("http://93.184.215.14:8080/", true),
+            ("ftp://example.com/", false),
+            ("http://localhost:8080/", false),
+            ("http://api.localhost/", false),
+            ("http://127.0.0.1/", false),
+            ("http://10.0.0.1/", false),
+            ("http://192.168.1.1/", false),
+            ("http://169.254.169.254/latest/meta-data/", false),
+            ("http://[::1]/", false),
+            ("http://[fd00::1]/", false),
+            ("http://[fe80::1]/", false),
+            ("http://[::ffff:127.0.0.1]/", false),
+        ] {
+            assert_eq!(
+                WebhookAllowlist::default().validate(webhook).is_ok(),
+                valid,
+                "{webhook}"
+            );
+        }
+    }
+
+    #[test]
+    fn allowlist() {
+        let allowlist = WebhookAllowlist(vec!["localhost".into(), "10.0.0.1".into()]);
+        assert!(allowlist.validate("http://localhost:8080/").is_ok());
+        assert!(allowlist.validate("http://10.0.0.1/").is_ok());
+        assert!(allowlist.validate("http://10.0.0.2/").is_err());
+    }
+}
@@ -0,0 +1,340 @@
+//! Isolation of tenants, sharing one deployment.
+//!
+//! When enabled, the tenant of a user is taken from their access token, using the tenant
+//! selector of the authenticator. Uploaded SBOMs and advisories get stamped with the tenant of
+//! the user as the `tenant` label, and users can only access documents carrying their tenant.
+
+use crate::Error;
+use actix_web::{FromRequest, HttpRequest, dev::Payload};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, EntityTrait, JoinType, QueryFilter, QuerySelect,
+    RelationTrait,
+};
+use sea_query::{Expr, Query, SelectStatement};
+use std::{
+    collections::HashSet,
+    future::{Ready, ready},
+};
+use trustify_common::{
+    db::query::{LabelFilter, LabelFilters},
+    id::{Id, TrySelectForId},
+};
+use trustify_entity::{
+    advisory, labels::Labels, purl_status, qualified_purl, sbom, sbom_package_purl_ref,
+    versioned_purl,
+};
+use uuid::Uuid;
+
+pub use trustify_auth::tenant::{TENANT_LABEL, TenantIsolation};
+
+/// The tenant whose documents a request may access.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Tenant {
+    /// Tenant isolation is disabled, all documents are accessible.
+    Any,
+    /// Only documents of this tenant are accessible.
+    Of(String),
+}
+
+impl Tenant {
+    /// The filter limiting documents to the ones of the tenant.
+    pub fn filter(&self) -> Option<LabelFilter> {
+        match self {
+            Self::Any => None,
+            Self::Of(tenant) => Some(LabelFilter::equal(TENANT_LABEL, tenant)),
+        }
+    }
+
+    /// The name of the tenant, `None` if tenant isolation is disabled.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Any => None,
+            Self::Of(tenant) => Some(tenant),
+        }
+    }
+
+    /// Check if a document with the labels belongs to the tenant.
+    pub fn owns(&self, labels: &Labels) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Of(tenant) => labels.get(TENANT_LABEL) == Some(tenant),
+        }
+    }
+
+    /// A condition on a column referencing an SBOM, ruling out SBOMs of other tenants.
+    pub fn sbom_condition(&self, sbom_id: impl ColumnTrait) -> Condition {
+        match self.filter() {
+            None => Condition::all(),
+            Some(filter) => Condition::all().add(
+                sbom_id.in_subquery(
+                    Query::select()
+                        .column(sbom::Column::SbomId)
+                        .from(sbom::Entity)
+                        .cond_where(filter.into_condition((sbom::Entity, sbom::Column::Labels)))
+                        .to_owned(),
+                ),
+            ),
+        }
+    }
+
+    /// A condition on a column referencing an advisory, ruling out advisories of other tenants.
+    pub fn advisory_condition(&self, advisory_id: impl ColumnTrait) -> Condition {
+        match self.filter() {
+            None => Condition::all(),
+            Some(filter) => Condition::all().add(
+                advisory_id.in_subquery(
+                    Query::select()
+                        .column(advisory::Column::Id)
+                        .from(advisory::Entity)
+                        .cond_where(
+                            filter.into_condition((advisory::Entity, advisory::Column::Labels)),
+                        )
+                        .to_owned(),
+                ),
+            ),
+        }
+    }
+
+    /// A condition on a column referencing a base PURL, ruling out base PURLs only known from the
+    /// documents of other tenants.
+    pub fn base_purl_condition(&self, base_purl_id: impl ColumnTrait) -> Condition {
+        match self {
+            Self::Any => Condition::all(),
+            Self::Of(_) => Condition::any()
+                .add(
+                    base_purl_id.in_subquery(
+                        Query::select()
+                            .column((versioned_purl::Entity, versioned_purl::Column::BasePurlId))
+                            .from(sbom_package_purl_ref::Entity)
+                            .inner_join(
+                                qualified_purl::Entity,
+                                Expr::col((qualified_purl::Entity, qualified_purl::Column::Id))
+                                    .equals((
+                                        sbom_package_purl_ref::Entity,
+                                        sbom_package_purl_ref::Column::QualifiedPurlId,
+                                    )),
+                            )
+                            .inner_join(
+                                versioned_purl::Entity,
+                                Expr::col((versioned_purl::Entity, versioned_purl::Column::Id))
+                                    .equals((
+                                        qualified_purl::Entity,
+                                        qualified_purl::Column::VersionedPurlId,
+                                    )),
+                            )
+                            .cond_where(self.sbom_condition(sbom_package_purl_ref::Column::SbomId))
+                            .to_owned(),
+                    ),
+                )
+                .add(base_purl_id.in_subquery(self.advised_base_purls())),
+        }
+    }
+
+    /// A condition on a column referencing a versioned PURL, ruling out versions only known from
+    /// the documents of other tenants.
+    pub fn versioned_purl_condition(&self, versioned_purl_id: impl ColumnTrait) -> Condition {
+        match self {
+            Self::Any => Condition::all(),
+            Self::Of(_) => Condition::all().add(
+                versioned_purl_id.in_subquery(
+                    Query::select()
+                        .column(qualified_purl::Column::VersionedPurlId)
+                        .from(qualified_purl::Entity)
+                        .cond_where(self.qualified_purl_condition(qualified_purl::Column::Id))
+                        .to_owned(),
+                ),
+            ),
+        }
+    }
+
+    /// A condition on a column referencing a qualified PURL, ruling out PURLs only known from the
+    /// documents of other tenants.
+    ///
+    /// A PURL is known to a tenant if one of its SBOMs contains it, or one of its advisories has a
+    /// status for the base PURL.
+    pub fn qualified_purl_condition(&self, qualified_purl_id: impl ColumnTrait) -> Condition {
+        match self {
+            Self::Any => Condition::all(),
+            Self::Of(_) => Condition::any()
+                .add(
+                    qualified_purl_id.in_subquery(
+                        Query::select()
+                            .column(sbom_package_purl_ref::Column::QualifiedPurlId)
+                            .from(sbom_package_purl_ref::Entity)
+                            .cond_where(self.sbom_condition(sbom_package_purl_ref::Column::SbomId))
+                            .to_owned(),
+                    ),
+                )
+                .add(
+                    qualified_purl_id.in_subquery(
+                        Query::select()
+                            .column((qualified_purl::Entity, qualified_purl::Column::Id))
+                            .from(qualified_purl::Entity)
+                            .inner_join(
+                                versioned_purl::Entity,
+                                Expr::col((versioned_purl::Entity, versioned_purl::Column::Id))
+                                    .equals((
+                                        qualified_purl::Entity,
+                                        qualified_purl::Column::VersionedPurlId,
+                                    )),
+                            )
+                            .and_where(
+                                versioned_purl::Column::BasePurlId
+                                    .in_subquery(self.advised_base_purls()),
+                            )
+                            .to_owned(),
+                    ),
+                ),
+        }
+    }
+
+    /// The base PURLs having a status in one of the advisories of the tenant.
+    fn advised_base_purls(&self) -> SelectStatement {
+        Query::select()
+            .column(purl_status::Column::BasePurlId)
+            .from(purl_status::Entity)
+            .cond_where(self.advisory_condition(purl_status::Column::AdvisoryId))
+            .to_owned()
+    }
+
+    /// A condition on a column storing the tenant of a record, like a policy, ruling out the
+    /// records of other tenants.
+    pub fn column_condition(&self, tenant: impl ColumnTrait) -> Condition {
+        match self {
+            Self::Any => Condition::all(),
+            Self::Of(name) => Condition::all().add(tenant.eq(name)),
+        }
+    }
+
+    /// The SBOMs among some SBOMs which belong to the tenant.
+    pub async fn own_sboms<C: ConnectionTrait>(
+        &self,
+        ids: impl IntoIterator<Item = Uuid>,
+        db: &C,
+    ) -> Result<HashSet<Uuid>, Error> {
+        let ids = ids.into_iter().collect::<HashSet<_>>();
+        if matches!(self, Self::Any) || ids.is_empty() {
+            return Ok(ids);
+        }
+
+        Ok(sbom::Entity::find()
+            .select_only()
+            .column(sbom::Column::SbomId)
+            .filter(sbom::Column::SbomId.is_in(ids))
+            .filter(self.sbom_condition(sbom::Column::SbomId))
+            .into_tuple::<Uuid>()
+            .all(db)
+            .await?
+            .into_iter()
+            .collect())
+    }
+
+    /// The advisories among some advisories which belong to the tenant.
+    pub async fn own_advisories<C: ConnectionTrait>(
+        &self,
+        ids: impl IntoIterator<Item = Uuid>,
+        db: &C,
+    ) -> Result<HashSet<Uuid>, Error> {
+        let ids = ids.into_iter().collect::<HashSet<_>>();
+        if matches!(self, Self::Any) || ids.is_empty() {
+            return Ok(ids);
+        }
+
+        Ok(advisory::Entity::find()
+            .select_only()
+            .column(advisory::Column::Id)
+            .filter(advisory::Column::Id.is_in(ids))
+            .filter(self.advisory_condition(advisory::Column::Id))
+            .into_tuple::<Uuid>()
+            .all(db)
+            .await?
+            .into_iter()
+            .collect())
+    }
+
+    /// Add the filter of the tenant to other label filters.
+    pub fn restrict(&self, mut filters: LabelFilters) -> LabelFilters {
+        filters.0.extend(self.filter());
+        filters
+    }
+
+    /// Stamp the labels of a document with the tenant, replacing any other tenant.
+    pub fn stamp(&self, labels: Labels) -> Labels {
+        match self {
+            Self::Any => labels,
+            Self::Of(tenant) => labels.add(TENANT_LABEL, tenant),
+        }
+    }
+
+    /// Drop changes of the tenant from a label update.
+    pub fn protect(&self, mut update: Labels) -> Labels {
+        if let Self::Of(_) = self {
+            update.remove(TENANT_LABEL);
+        }
+        update
+    }
+
+    /// Resolve the ID of an SBOM among the SBOMs of the tenant.
+    ///
+    /// Other tenants may hold a copy of the same document, so a digest or an external ID is
+    /// resolved to the SBOM of the tenant. Fails with [`Error::NotFound`] if the tenant has no such
+    /// SBOM.
+    pub async fn require_sbom<C: ConnectionTrait>(&self, id: &Id, db: &C) -> Result<Id, Error> {
+        let Some(filter) = self.filter() else {
+            return Ok(id.clone());
+        };
+
+        sbom::Entity::find()
+            .join(JoinType::Join, sbom::Relation::SourceDocument.def())
+            .try_filter(id.clone())?
+            .filter(filter.into_condition(sbom::Column::Labels))
+            .select_only()
+            .column(sbom::Column::SbomId)
+            .into_tuple::<Uuid>()
+            .one(db)
+            .await?
+            .map(Id::Uuid)
+            .ok_or_else(|| Error::NotFound(format!("SBOM {id}")))
+    }
+
+    /// Resolve the ID of an advisory among the advisories of the tenant.
+    ///
+    /// Like [`Self::require_sbom`], fails with [`Error::NotFound`] if the tenant has no such
+    /// advisory.
+    pub async fn require_advisory<C: ConnectionTrait>(&self, id: &Id, db: &C) -> Result<Id, Error> {
+        let Some(filter) = self.filter() else {
+            return Ok(id.clone());
+        };
+
+        advisory::Entity::find()
+            .join(JoinType::Join, advisory::Relation::SourceDocument.def())
+            .try_filter(id.clone())?
+            .filter(filter.into_condition(advisory::Column::Labels))
+            .select_only()
+            .column(advisory::Column::Id)
+            .into_tuple::<Uuid>()
+            .one(db)
+            .await?
+            .map(Id::Uuid)
+            .ok_or_else(|| Error::NotFound(format!("Advisory {id}")))
+    }
+}
+
+/// Extract the tenant of the current user.
+///
+/// With tenant isolation enabled, requests of users without a tenant are rejected.
+impl FromRequest for Tenant {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(
+            trustify_auth::tenant::tenant_of(req)
+                .map(|tenant| match tenant {
+                    Some(tenant) => Self::Of(tenant),
+                    None => Self::Any,
+                })
+                .map_err(Into::into),
+        )
+    }
+}
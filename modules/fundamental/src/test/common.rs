@@ -9,7 +9,7 @@ pub async fn caller(ctx: &TrustifyContext) -> anyhow::Result<impl CallService +
     caller_with(ctx, Config::default()).await
 }
 
-async fn caller_with(
+pub async fn caller_with(
     ctx: &TrustifyContext,
     config: Config,
 ) -> anyhow::Result<impl CallService + '_> {
@@ -1,2 +1,20 @@
 use crate::endpoints::{Config, configure};
+use trustify_auth::authenticator::user::UserDetails;
 include!("common.rs");
+
+/// A user of a tenant, for checking the isolation of tenants.
+pub fn tenant_user(tenant: &str) -> UserDetails {
+    UserDetails {
+        id: format!("user-{tenant}"),
+        permissions: vec![],
+        tenant: Some(tenant.into()),
+    }
+}
+
+/// The configuration isolating tenants.
+pub fn tenant_isolation() -> Config {
+    Config {
+        tenant_isolation: true,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,171 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    tenant::Tenant,
+    triage::{
+        model::{Triage, TriageEvent, TriageUpdate},
+        service::TriageService,
+    },
+};
+use actix_web::{HttpResponse, Responder, get, put, web};
+use trustify_auth::{
+    ReadTriage, UpdateTriage, authenticator::user::UserInformation, authorizer::Require,
+};
+use trustify_common::{
+    db::{Database, query::Query},
+    error::ErrorInformation,
+    id::Id,
+    model::{Paginated, PaginatedResults},
+};
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = TriageService::new(db);
+
+    config
+        .app_data(web::Data::new(service))
+        .service(list)
+        .service(get)
+        .service(set)
+        .service(history);
+}
+
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "listSbomTriage",
+    params(
+        ("id" = Id, Path, description = "Digest/hash of the document, prefixed by hash type, such as 'sha256:<hash>' or 'urn:uuid:<uuid>'"),
+        Query,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Triaged findings of the SBOM, ordered by vulnerability", body = PaginatedResults<Triage>),
+        (status = 404, description = "The SBOM could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/sbom/{id}/triage")]
+/// List the triaged findings of an SBOM
+pub async fn list(
+    service: web::Data<TriageService>,
+    db: web::Data<Database>,
+    id: web::Path<Id>,
+    web::Query(search): web::Query<Query>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadTriage>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let id = id.into_inner();
+    let id = tenant.require_sbom(&id, db.as_ref()).await?;
+
+    Ok(match service.list(id, search, paginated).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "getSbomTriage",
+    params(
+        ("id" = Id, Path, description = "Digest/hash of the document, prefixed by hash type, such as 'sha256:<hash>' or 'urn:uuid:<uuid>'"),
+        ("vulnerability" = String, Path, description = "Identifier of the vulnerability"),
+    ),
+    responses(
+        (status = 200, description = "The assessment of the finding", body = Triage),
+        (status = 404, description = "The SBOM could not be found, or the finding was not triaged", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/sbom/{id}/triage/{vulnerability}")]
+/// Retrieve the assessment of a vulnerability in an SBOM
+pub async fn get(
+    service: web::Data<TriageService>,
+    db: web::Data<Database>,
+    path: web::Path<(Id, String)>,
+    _: Require<ReadTriage>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (id, vulnerability) = path.into_inner();
+    let id = tenant.require_sbom(&id, db.as_ref()).await?;
+
+    Ok(match service.fetch(id, &vulnerability).await? {
+        Some(result) => HttpResponse::Ok().json(result),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "setSbomTriage",
+    request_body = TriageUpdate,
+    params(
+        ("id" = Id, Path, description = "Digest/hash of the document, prefixed by hash type, such as 'sha256:<hash>' or 'urn:uuid:<uuid>'"),
+        ("vulnerability" = String, Path, description = "Identifier of the vulnerability"),
+    ),
+    responses(
+        (status = 200, description = "The assessment of the finding", body = Triage),
+        (status = 404, description = "The SBOM or the vulnerability could not be found", body = ErrorInformation),
+    ),
+)]
+#[put("/v2/sbom/{id}/triage/{vulnerability}")]
+/// Triage a vulnerability in an SBOM, setting its state, assignee, and justification
+pub async fn set(
+    service: web::Data<TriageService>,
+    db: web::Data<Database>,
+    path: web::Path<(Id, String)>,
+    user: UserInformation,
+    web::Json(request): web::Json<TriageUpdate>,
+    _: Require<UpdateTriage>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (id, vulnerability) = path.into_inner();
+    let id = tenant.require_sbom(&id, db.as_ref()).await?;
+
+    Ok(
+        match service
+            .set(
+                id,
+                &vulnerability,
+                request,
+                user.id().map(ToString::to_string),
+            )
+            .await?
+        {
+            Some(result) => HttpResponse::Ok().json(result),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "listSbomTriageHistory",
+    params(
+        ("id" = Id, Path, description = "Digest/hash of the document, prefixed by hash type, such as 'sha256:<hash>' or 'urn:uuid:<uuid>'"),
+        ("vulnerability" = String, Path, description = "Identifier of the vulnerability"),
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "The changes of the assessment, oldest first", body = PaginatedResults<TriageEvent>),
+        (status = 404, description = "The SBOM could not be found, or the finding was not triaged", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/sbom/{id}/triage/{vulnerability}/history")]
+/// Get the changes of the assessment of a vulnerability in an SBOM
+pub async fn history(
+    service: web::Data<TriageService>,
+    db: web::Data<Database>,
+    path: web::Path<(Id, String)>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadTriage>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    let (id, vulnerability) = path.into_inner();
+    let id = tenant.require_sbom(&id, db.as_ref()).await?;
+
+    Ok(
+        match service.history(id, &vulnerability, paginated).await? {
+            Some(result) => HttpResponse::Ok().json(result),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
@@ -0,0 +1,118 @@
+use crate::{
+    test::caller,
+    triage::model::{Triage, TriageEvent, TriageState},
+};
+use actix_http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::{Value, json};
+use test_context::test_context;
+use test_log::test;
+use trustify_common::model::PaginatedResults;
+use trustify_test_context::{TrustifyContext, auth::TestAuthentication, call::CallService};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn triage_finding(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let id = ctx
+        .ingest_documents([
+            "quarkus-bom-2.13.8.Final-redhat-00004.json",
+            "csaf/cve-2023-0044.json",
+        ])
+        .await?[0]
+        .id
+        .to_string();
+    let app = caller(ctx).await?;
+
+    // nothing triaged yet
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{id}/triage/CVE-2023-0044"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // start reviewing, then accept the risk
+
+    for (state, user) in [
+        (
+            json!({"state": "in_review", "assignee": "user-a"}),
+            "user-b",
+        ),
+        (
+            json!({
+                "state": "accepted_risk",
+                "assignee": "user-a",
+                "justification": "only reachable from trusted input",
+            }),
+            "user-a",
+        ),
+        // unchanged, not recorded
+        (
+            json!({
+                "state": "accepted_risk",
+                "assignee": "user-a",
+                "justification": "only reachable from trusted input",
+            }),
+            "user-a",
+        ),
+    ] {
+        let request = TestRequest::put()
+            .uri(&format!("/api/v2/sbom/{id}/triage/CVE-2023-0044"))
+            .set_json(state)
+            .to_request()
+            .test_auth(user);
+        let response = app.call_service(request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{id}/triage/CVE-2023-0044"))
+        .to_request();
+    let result: Triage = app.call_and_read_body_json(request).await;
+    assert_eq!(result.state, TriageState::AcceptedRisk);
+    assert_eq!(result.assignee.as_deref(), Some("user-a"));
+    assert_eq!(
+        result.justification.as_deref(),
+        Some("only reachable from trusted input")
+    );
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{id}/triage"))
+        .to_request();
+    let result: PaginatedResults<Triage> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].vulnerability, "CVE-2023-0044");
+
+    // the history, oldest first
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{id}/triage/CVE-2023-0044/history"))
+        .to_request();
+    let result: PaginatedResults<TriageEvent> = app.call_and_read_body_json(request).await;
+    assert_eq!(result.total, 2);
+    assert_eq!(result.items[0].state, TriageState::InReview);
+    assert_eq!(result.items[0].user.as_deref(), Some("user-b"));
+    assert_eq!(result.items[0].justification, None);
+    assert_eq!(result.items[1].state, TriageState::AcceptedRisk);
+    assert_eq!(result.items[1].user.as_deref(), Some("user-a"));
+
+    // the state shows up in the vulnerabilities of the SBOM
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{id}/advisory"))
+        .to_request();
+    let result: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(result[0]["status"][0]["identifier"], "CVE-2023-0044");
+    assert_eq!(result[0]["status"][0]["triage"], "accepted_risk");
+
+    // unknown vulnerabilities can't be triaged
+
+    let request = TestRequest::put()
+        .uri(&format!("/api/v2/sbom/{id}/triage/CVE-0000-0000"))
+        .set_json(json!({"state": "open"}))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::{triage, triage_event};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub use triage::State as TriageState;
+
+/// The assessment of a vulnerability, found in an SBOM.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Triage {
+    pub id: Uuid,
+    /// The ID of the SBOM
+    pub sbom: Uuid,
+    /// The identifier of the vulnerability
+    pub vulnerability: String,
+    pub state: TriageState,
+    /// The user or team assessing the finding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    /// The reasoning behind the state, like why a risk was accepted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub justification: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    /// The time of the last change
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified: OffsetDateTime,
+}
+
+impl From<triage::Model> for Triage {
+    fn from(value: triage::Model) -> Self {
+        let triage::Model {
+            id,
+            sbom_id,
+            vulnerability_id,
+            state,
+            assignee,
+            justification,
+            created,
+            modified,
+        } = value;
+
+        Self {
+            id,
+            sbom: sbom_id,
+            vulnerability: vulnerability_id,
+            state,
+            assignee,
+            justification,
+            created,
+            modified,
+        }
+    }
+}
+
+/// The changeable assessment of a finding.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TriageUpdate {
+    pub state: TriageState,
+    /// The user or team assessing the finding
+    #[serde(default)]
+    pub assignee: Option<String>,
+    /// The reasoning behind the state, like why a risk was accepted
+    #[serde(default)]
+    pub justification: Option<String>,
+}
+
+/// A change of a triage, with the assessment after the change.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TriageEvent {
+    /// The time of the change
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    /// The ID of the user performing the change, absent if authentication is disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    pub state: TriageState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub justification: Option<String>,
+}
+
+impl From<triage_event::Model> for TriageEvent {
+    fn from(value: triage_event::Model) -> Self {
+        let triage_event::Model {
+            id: _,
+            triage_id: _,
+            timestamp,
+            user_id,
+            state,
+            assignee,
+            justification,
+        } = value;
+
+        Self {
+            timestamp,
+            user: user_id,
+            state,
+            assignee,
+            justification,
+        }
+    }
+}
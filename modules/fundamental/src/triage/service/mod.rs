@@ -0,0 +1,230 @@
+use crate::{
+    Error,
+    triage::model::{Triage, TriageEvent, TriageUpdate},
+};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, JoinType, QueryFilter, QueryOrder,
+    QuerySelect, RelationTrait, TransactionTrait, prelude::Uuid,
+};
+use time::OffsetDateTime;
+use trustify_common::{
+    db::{
+        Database,
+        limiter::LimiterTrait,
+        query::{Filtering, Query},
+    },
+    id::{Id, TrySelectForId},
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{sbom, triage, triage_event, vulnerability};
+
+pub struct TriageService {
+    db: Database,
+}
+
+impl TriageService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// List the triaged findings of an SBOM, ordered by vulnerability. Returns `None` if the SBOM
+    /// was not found.
+    pub async fn list(
+        &self,
+        sbom: Id,
+        query: Query,
+        paginated: Paginated,
+    ) -> Result<Option<PaginatedResults<Triage>>, Error> {
+        let Some(sbom_id) = resolve_sbom(&self.db, sbom).await? else {
+            return Ok(None);
+        };
+
+        let limiter = triage::Entity::find()
+            .filter(triage::Column::SbomId.eq(sbom_id))
+            .filtering(query)?
+            .order_by_asc(triage::Column::VulnerabilityId)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        Ok(Some(PaginatedResults {
+            total: limiter.total().await?,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(Triage::from)
+                .collect(),
+        }))
+    }
+
+    /// Get the assessment of a vulnerability in an SBOM. Returns `None` if the finding was not
+    /// triaged yet.
+    pub async fn fetch(&self, sbom: Id, vulnerability: &str) -> Result<Option<Triage>, Error> {
+        let Some(sbom_id) = resolve_sbom(&self.db, sbom).await? else {
+            return Ok(None);
+        };
+
+        Ok(find(&self.db, sbom_id, vulnerability)
+            .await?
+            .map(Triage::from))
+    }
+
+    /// Triage a vulnerability in an SBOM, creating or changing its assessment and recording the
+    /// change. Returns `None` if the SBOM or the vulnerability was not found.
+    ///
+    /// Requests not changing anything are not recorded.
+    pub async fn set(
+        &self,
+        sbom: Id,
+        vulnerability: &str,
+        update: TriageUpdate,
+        user_id: Option<String>,
+    ) -> Result<Option<Triage>, Error> {
+        let tx = self.db.begin().await?;
+
+        let Some(sbom_id) = resolve_sbom(&tx, sbom).await? else {
+            return Ok(None);
+        };
+        if vulnerability::Entity::find_by_id(vulnerability)
+            .one(&tx)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let TriageUpdate {
+            state,
+            assignee,
+            justification,
+        } = update;
+        let now = OffsetDateTime::now_utc();
+
+        let result = match triage::Entity::find()
+            .filter(triage::Column::SbomId.eq(sbom_id))
+            .filter(triage::Column::VulnerabilityId.eq(vulnerability))
+            .lock_exclusive()
+            .one(&tx)
+            .await?
+        {
+            Some(current)
+                if current.state == state
+                    && current.assignee == assignee
+                    && current.justification == justification =>
+            {
+                return Ok(Some(current.into()));
+            }
+            Some(current) => {
+                triage::Entity::update(triage::ActiveModel {
+                    id: Set(current.id),
+                    state: Set(state),
+                    assignee: Set(assignee),
+                    justification: Set(justification),
+                    modified: Set(now),
+                    ..Default::default()
+                })
+                .exec(&tx)
+                .await?
+            }
+            None => {
+                triage::Entity::insert(triage::ActiveModel {
+                    id: Set(Uuid::now_v7()),
+                    sbom_id: Set(sbom_id),
+                    vulnerability_id: Set(vulnerability.to_string()),
+                    state: Set(state),
+                    assignee: Set(assignee),
+                    justification: Set(justification),
+                    created: Set(now),
+                    modified: Set(now),
+                })
+                .exec_with_returning(&tx)
+                .await?
+            }
+        };
+
+        record(&tx, &result, user_id).await?;
+
+        tx.commit().await?;
+
+        Ok(Some(result.into()))
+    }
+
+    /// Get the changes of the assessment of a vulnerability in an SBOM, oldest first. Returns
+    /// `None` if the finding was not triaged yet.
+    pub async fn history(
+        &self,
+        sbom: Id,
+        vulnerability: &str,
+        paginated: Paginated,
+    ) -> Result<Option<PaginatedResults<TriageEvent>>, Error> {
+        let Some(sbom_id) = resolve_sbom(&self.db, sbom).await? else {
+            return Ok(None);
+        };
+        let Some(triage) = find(&self.db, sbom_id, vulnerability).await? else {
+            return Ok(None);
+        };
+
+        let limiter = triage_event::Entity::find()
+            .filter(triage_event::Column::TriageId.eq(triage.id))
+            .order_by_asc(triage_event::Column::Timestamp)
+            .order_by_asc(triage_event::Column::Id)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        Ok(Some(PaginatedResults {
+            total: limiter.total().await?,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(TriageEvent::from)
+                .collect(),
+        }))
+    }
+}
+
+/// Look up the ID of a (not deleted) SBOM.
+async fn resolve_sbom(db: &impl ConnectionTrait, id: Id) -> Result<Option<Uuid>, Error> {
+    Ok(sbom::Entity::find()
+        .select_only()
+        .column(sbom::Column::SbomId)
+        .join(JoinType::LeftJoin, sbom::Relation::SourceDocument.def())
+        .try_filter(id)?
+        .filter(sbom::Column::DeletedAt.is_null())
+        .into_tuple()
+        .one(db)
+        .await?)
+}
+
+async fn find(
+    db: &impl ConnectionTrait,
+    sbom_id: Uuid,
+    vulnerability: &str,
+) -> Result<Option<triage::Model>, Error> {
+    Ok(triage::Entity::find()
+        .filter(triage::Column::SbomId.eq(sbom_id))
+        .filter(triage::Column::VulnerabilityId.eq(vulnerability))
+        .one(db)
+        .await?)
+}
+
+/// Record the current assessment of a finding in its history.
+async fn record(
+    db: &impl ConnectionTrait,
+    triage: &triage::Model,
+    user_id: Option<String>,
+) -> Result<(), Error> {
+    triage_event::Entity::insert(triage_event::ActiveModel {
+        id: Set(Uuid::now_v7()),
+        triage_id: Set(triage.id),
+        timestamp: Set(triage.modified),
+        user_id: Set(user_id),
+        state: Set(triage.state),
+        assignee: Set(triage.assignee.clone()),
+        justification: Set(triage.justification.clone()),
+    })
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(())
+}
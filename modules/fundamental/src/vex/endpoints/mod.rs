@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    Error,
+    advisory::service::AdvisoryService,
+    source_document::retrieve,
+    tenant::Tenant,
+    vex::{model::NewVex, service::VexService},
+};
+use actix_web::{HttpResponse, Responder, get, http::header, post, web};
+use serde_json::{Value, json};
+use std::str::FromStr;
+use time::OffsetDateTime;
+use trustify_auth::{CreateAdvisory, ReadAdvisory, authorizer::Require};
+use trustify_common::{db::Database, error::ErrorInformation, id::Id};
+use trustify_module_ingestor::{
+    model::IngestResult,
+    service::{
+        Format, IngestorService,
+        audit::{Actor, Upload},
+    },
+};
+use uuid::Uuid;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig) {
+    let service = VexService::new();
+
+    config
+        .app_data(web::Data::new(service))
+        .service(create)
+        .service(openvex);
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "createVex",
+    request_body = NewVex,
+    responses(
+        (status = 201, description = "Created an advisory from the statements", body = IngestResult),
+        (status = 400, description = "The statements are invalid", body = ErrorInformation),
+    ),
+)]
+#[post("/v2/vex")]
+/// Author VEX statements
+///
+/// The statements are stored as a CSAF VEX advisory, issued by the publisher of the statements.
+/// The CSAF document can be downloaded like any other advisory.
+pub async fn create(
+    service: web::Data<VexService>,
+    ingestor: web::Data<IngestorService>,
+    web::Json(request): web::Json<NewVex>,
+    actor: Actor,
+    _: Require<CreateAdvisory>,
+    tenant: Tenant,
+) -> Result<impl Responder, Error> {
+    let document = service.csaf_document(&request, Uuid::now_v7(), OffsetDateTime::now_utc())?;
+    let bytes = serde_json::to_vec(&document).map_err(|err| Error::Internal(err.to_string()))?;
+
+    let upload = Upload::new(actor).details(json!({ "vex": true }));
+
+    let result = ingestor
+        .ingest_with_external_id(
+            &bytes,
+            Format::CSAF,
+            tenant.stamp(request.labels),
+            None,
+            None,
+            Some(&upload),
+        )
+        .await?;
+    log::info!("Authored VEX: {}", result.id);
+
+    Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("/api/v2/advisory/{}", result.id)))
+        .json(result))
+}
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "getAdvisoryOpenVex",
+    params(
+        ("key" = Id, Path),
+    ),
+    responses(
+        (status = 200, description = "The statements of the advisory, as OpenVEX document", body = Value),
+        (status = 400, description = "The advisory is not a CSAF document", body = ErrorInformation),
+        (status = 404, description = "The document could not be found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/advisory/{key}/openvex")]
+/// Export the statements of a CSAF advisory as OpenVEX document
+pub async fn openvex(
+    service: web::Data<VexService>,
+    db: web::Data<Database>,
+    ingestor: web::Data<IngestorService>,
+    advisory: web::Data<AdvisoryService>,
+    key: web::Path<String>,
+    _: Require<ReadAdvisory>,
+    tenant: Tenant,
+) -> Result<impl Responder, Error> {
+    let id = Id::from_str(&key).map_err(Error::IdKey)?;
+    let id = tenant.require_advisory(&id, db.read()).await?;
+
+    let Some(advisory) = advisory.fetch_advisory(id, db.read()).await? else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    let Some(doc) = &advisory.source_document else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    let Some(bytes) = retrieve(ingestor.storage(), doc).await? else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    if !Format::is_csaf(&bytes)? {
+        return Err(Error::BadRequest(format!(
+            "Not a CSAF document: {}",
+            advisory.head.identifier
+        )));
+    }
+    let document: Value =
+        serde_json::from_slice(&bytes).map_err(|err| Error::Data(err.to_string()))?;
+
+    Ok(HttpResponse::Ok().json(service.openvex_document(advisory.head.uuid, &document)))
+}
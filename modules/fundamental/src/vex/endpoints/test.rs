@@ -0,0 +1,99 @@
+use crate::test::caller;
+use actix_http::StatusCode;
+use actix_web::test::{TestRequest, read_body_json};
+use serde_json::{Value, json};
+use test_context::test_context;
+use test_log::test;
+use trustify_module_ingestor::model::IngestResult;
+use trustify_test_context::{TrustifyContext, call::CallService};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn author_and_export(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/vex")
+        .set_json(json!({
+            "title": "Findings of ACME",
+            "publisher": {
+                "name": "ACME",
+                "namespace": "https://acme.example.com",
+            },
+            "statements": [
+                {
+                    "vulnerability": "CVE-2023-0044",
+                    "products": ["pkg:maven/io.quarkus/quarkus-vertx-http@2.13.8"],
+                    "status": "not_affected",
+                    "justification": "vulnerable_code_not_in_execute_path",
+                },
+                {
+                    "vulnerability": "CVE-2023-0044",
+                    "products": ["pkg:maven/io.quarkus/quarkus-vertx-http@2.13.7"],
+                    "status": "affected",
+                    "action_statement": "Update to 2.13.9",
+                },
+            ],
+            "labels": { "team": "a" },
+        }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let result: IngestResult = read_body_json(response).await;
+    let id = result.id;
+
+    // a regular advisory, issued by the author
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/advisory/{id}"))
+        .to_request();
+    let advisory: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(advisory["issuer"]["name"], "ACME");
+    assert_eq!(advisory["title"], "Findings of ACME");
+    assert_eq!(advisory["labels"]["team"], "a");
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/advisory/{id}/download"))
+        .to_request();
+    let csaf: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(csaf["document"]["category"], "csaf_vex");
+
+    // exported as OpenVEX
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/advisory/{id}/openvex"))
+        .to_request();
+    let openvex: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(openvex["author"], "ACME");
+    assert_eq!(openvex["statements"][0]["status"], "affected");
+    assert_eq!(
+        openvex["statements"][1]["products"][0]["@id"],
+        "pkg:maven/io.quarkus/quarkus-vertx-http@2.13.8"
+    );
+    assert_eq!(
+        openvex["statements"][1]["justification"],
+        "vulnerable_code_not_in_execute_path"
+    );
+
+    // statements must be valid
+
+    let request = TestRequest::post()
+        .uri("/api/v2/vex")
+        .set_json(json!({
+            "title": "Findings of ACME",
+            "publisher": {
+                "name": "ACME",
+                "namespace": "https://acme.example.com",
+            },
+            "statements": [{
+                "vulnerability": "CVE-2023-0044",
+                "products": ["pkg:maven/io.quarkus/quarkus-vertx-http@2.13.8"],
+                "status": "not_affected",
+            }],
+        }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
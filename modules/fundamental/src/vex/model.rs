@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use trustify_entity::labels::Labels;
+use utoipa::ToSchema;
+
+/// VEX statements, authored by an organization rather than an upstream publisher.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct NewVex {
+    /// The title of the resulting advisory
+    pub title: String,
+    /// The organization authoring the statements, becoming the issuer of the advisory
+    pub publisher: VexPublisher,
+    pub statements: Vec<VexStatement>,
+    /// Labels of the resulting advisory
+    #[serde(default)]
+    pub labels: Labels,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct VexPublisher {
+    pub name: String,
+    /// The URL of the organization, like `https://example.com`
+    pub namespace: String,
+}
+
+/// The status of a vulnerability in a set of products.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct VexStatement {
+    /// The CVE identifier of the vulnerability
+    pub vulnerability: String,
+    /// The pURLs of the products
+    pub products: Vec<String>,
+    pub status: VexStatus,
+    /// Why the products are not affected. Statements of products which are not affected require
+    /// a justification or an impact statement.
+    #[serde(default)]
+    pub justification: Option<VexJustification>,
+    /// A free-form description of why the products are not affected
+    #[serde(default)]
+    pub impact_statement: Option<String>,
+    /// What to do about affected products
+    #[serde(default)]
+    pub action_statement: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VexStatus {
+    NotAffected,
+    Affected,
+    Fixed,
+    UnderInvestigation,
+}
+
+impl VexStatus {
+    /// The name of the matching CSAF product status.
+    pub fn csaf(&self) -> &'static str {
+        match self {
+            Self::NotAffected => "known_not_affected",
+            Self::Affected => "known_affected",
+            Self::Fixed => "fixed",
+            Self::UnderInvestigation => "under_investigation",
+        }
+    }
+}
+
+/// The justifications of OpenVEX, which are the same as the labels of CSAF flags.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    strum::AsRefStr,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum VexJustification {
+    ComponentNotPresent,
+    VulnerableCodeNotPresent,
+    VulnerableCodeNotInExecutePath,
+    VulnerableCodeCannotBeControlledByAdversary,
+    InlineMitigationsAlreadyExist,
+}
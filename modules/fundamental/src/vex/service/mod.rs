@@ -0,0 +1,391 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    Error,
+    vex::model::{NewVex, VexStatement, VexStatus},
+};
+use serde_json::{Map, Value, json};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    str::FromStr,
+};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use trustify_common::purl::Purl;
+use uuid::Uuid;
+
+/// The CSAF product statuses, by their OpenVEX status.
+const OPENVEX_STATUSES: [(&str, &str); 7] = [
+    ("known_not_affected", "not_affected"),
+    ("known_affected", "affected"),
+    ("first_affected", "affected"),
+    ("last_affected", "affected"),
+    ("fixed", "fixed"),
+    ("first_fixed", "fixed"),
+    ("under_investigation", "under_investigation"),
+];
+
+#[derive(Default)]
+pub struct VexService {}
+
+impl VexService {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Turn the statements into a CSAF VEX document, which can be ingested as an advisory.
+    ///
+    /// The products are placed into the product tree below the publisher, by their package
+    /// name.
+    pub fn csaf_document(
+        &self,
+        vex: &NewVex,
+        id: Uuid,
+        now: OffsetDateTime,
+    ) -> Result<Value, Error> {
+        let now = now
+            .format(&Rfc3339)
+            .map_err(|err| Error::Internal(err.to_string()))?;
+
+        if vex.statements.is_empty() {
+            return Err(Error::BadRequest("No statements".into()));
+        }
+
+        // the products, by package name and pURL
+        let mut products = BTreeMap::<String, BTreeMap<String, Purl>>::new();
+        // the statements, by vulnerability, and the status of each of their products
+        let mut vulnerabilities =
+            BTreeMap::<&str, (Vec<&VexStatement>, HashMap<&str, VexStatus>)>::new();
+
+        for statement in &vex.statements {
+            validate(statement)?;
+
+            let (statements, statuses) =
+                vulnerabilities.entry(&statement.vulnerability).or_default();
+            statements.push(statement);
+
+            for product in &statement.products {
+                if statuses.insert(product, statement.status).is_some() {
+                    return Err(Error::BadRequest(format!(
+                        "Conflicting statements for {product} and {}",
+                        statement.vulnerability
+                    )));
+                }
+
+                let purl = Purl::from_str(product)?;
+                let name = match &purl.namespace {
+                    Some(namespace) => format!("{namespace}/{}", purl.name),
+                    None => purl.name.clone(),
+                };
+                products
+                    .entry(name)
+                    .or_default()
+                    .insert(product.clone(), purl);
+            }
+        }
+
+        let branches = products
+            .into_iter()
+            .map(|(name, purls)| {
+                json!({
+                    "category": "product_name",
+                    "name": name,
+                    "branches": purls.into_iter().map(|(id, purl)| json!({
+                        "category": "product_version",
+                        "name": purl.version.as_deref().unwrap_or("*"),
+                        "product": {
+                            "product_id": id,
+                            "name": id,
+                            "product_identification_helper": {
+                                "purl": id,
+                            },
+                        },
+                    })).collect::<Vec<_>>(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let vulnerabilities = vulnerabilities
+            .into_iter()
+            .map(|(cve, (statements, _))| csaf_vulnerability(cve, &statements))
+            .collect::<Vec<_>>();
+
+        Ok(json!({
+            "document": {
+                "category": "csaf_vex",
+                "csaf_version": "2.0",
+                "title": vex.title,
+                "publisher": {
+                    "category": "vendor",
+                    "name": vex.publisher.name,
+                    "namespace": vex.publisher.namespace,
+                },
+                "tracking": {
+                    "id": id.to_string(),
+                    "status": "final",
+                    "version": "1",
+                    "initial_release_date": now,
+                    "current_release_date": now,
+                    "revision_history": [{
+                        "number": "1",
+                        "date": now,
+                        "summary": "Authored statements",
+                    }],
+                    "generator": {
+                        "engine": {
+                            "name": "trustify",
+                            "version": env!("CARGO_PKG_VERSION"),
+                        },
+                    },
+                },
+            },
+            "product_tree": {
+                "branches": [{
+                    "category": "vendor",
+                    "name": vex.publisher.name,
+                    "branches": branches,
+                }],
+            },
+            "vulnerabilities": vulnerabilities,
+        }))
+    }
+
+    /// Convert a CSAF document into an OpenVEX document.
+    ///
+    /// Products are identified by their pURL, falling back to their CSAF product ID. Statements
+    /// are grouped by vulnerability, status, justification, and impact and action statements.
+    pub fn openvex_document(&self, id: Uuid, csaf: &Value) -> Value {
+        let header = &csaf["document"];
+        let purls = csaf_purls(&csaf["product_tree"]);
+
+        let mut statements = Vec::new();
+        for vulnerability in array(&csaf["vulnerabilities"]) {
+            let name = string(&vulnerability["cve"])
+                .or_else(|| array(&vulnerability["ids"]).find_map(|id| string(&id["text"])));
+            let Some(name) = name else {
+                continue;
+            };
+
+            let justifications = product_details(&vulnerability["flags"], "label", |_| true);
+            let impacts = product_details(&vulnerability["threats"], "details", |threat| {
+                threat["category"] == "impact"
+            });
+            let actions = product_details(&vulnerability["remediations"], "details", |_| true);
+
+            // the products, by status, justification, impact, and action
+            let mut groups =
+                BTreeMap::<(&str, Option<&str>, Option<&str>, Option<&str>), BTreeSet<&str>>::new();
+            for (csaf_status, status) in OPENVEX_STATUSES {
+                for product in
+                    array(&vulnerability["product_status"][csaf_status]).filter_map(Value::as_str)
+                {
+                    let not_affected = status == "not_affected";
+                    let affected = status == "affected";
+                    groups
+                        .entry((
+                            status,
+                            justifications
+                                .get(product)
+                                .copied()
+                                .filter(|_| not_affected),
+                            impacts.get(product).copied().filter(|_| not_affected),
+                            actions.get(product).copied().filter(|_| affected),
+                        ))
+                        .or_default()
+                        .insert(product);
+                }
+            }
+
+            for ((status, justification, impact, action), products) in groups {
+                let mut statement = Map::new();
+                statement.insert("vulnerability".into(), json!({ "name": name }));
+                statement.insert(
+                    "products".into(),
+                    products
+                        .into_iter()
+                        .map(|product| {
+                            json!({ "@id": purls.get(product).copied().unwrap_or(product) })
+                        })
+                        .collect(),
+                );
+                statement.insert("status".into(), status.into());
+                if let Some(justification) = justification {
+                    statement.insert("justification".into(), justification.into());
+                }
+                if let Some(impact) = impact {
+                    statement.insert("impact_statement".into(), impact.into());
+                }
+                if let Some(action) = action {
+                    statement.insert("action_statement".into(), action.into());
+                }
+                statements.push(Value::Object(statement));
+            }
+        }
+
+        let mut document = json!({
+            "@context": "https://openvex.dev/ns/v0.2.0",
+            "@id": id.urn().to_string(),
+            "author": header["publisher"]["name"],
+            "version": 1,
+            "statements": statements,
+        });
+        if let Some(timestamp) = string(&header["tracking"]["current_release_date"]) {
+            document["timestamp"] = timestamp.into();
+        }
+
+        document
+    }
+}
+
+/// Check a statement is a valid VEX statement.
+fn validate(statement: &VexStatement) -> Result<(), Error> {
+    if !statement.vulnerability.starts_with("CVE-") {
+        return Err(Error::BadRequest(format!(
+            "Not a CVE identifier: {}",
+            statement.vulnerability
+        )));
+    }
+    if statement.products.is_empty() {
+        return Err(Error::BadRequest(format!(
+            "No products for {}",
+            statement.vulnerability
+        )));
+    }
+
+    match statement.status {
+        VexStatus::NotAffected
+            if statement.justification.is_none() && statement.impact_statement.is_none() =>
+        {
+            Err(Error::BadRequest(format!(
+                "Missing justification or impact statement for {}",
+                statement.vulnerability
+            )))
+        }
+        VexStatus::NotAffected => Ok(()),
+        _ if statement.justification.is_some() || statement.impact_statement.is_some() => {
+            Err(Error::BadRequest(format!(
+                "Only products which are not affected by {} can have a justification or impact statement",
+                statement.vulnerability
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn csaf_vulnerability(cve: &str, statements: &[&VexStatement]) -> Value {
+    let mut product_status = BTreeMap::<&str, Vec<&str>>::new();
+    let mut flags = BTreeMap::<&str, Vec<&str>>::new();
+    let mut threats = BTreeMap::<&str, Vec<&str>>::new();
+    let mut remediations = BTreeMap::<&str, Vec<&str>>::new();
+
+    for statement in statements {
+        let products = statement.products.iter().map(String::as_str);
+
+        product_status
+            .entry(statement.status.csaf())
+            .or_default()
+            .extend(products.clone());
+        if let Some(justification) = &statement.justification {
+            flags
+                .entry(justification.as_ref())
+                .or_default()
+                .extend(products.clone());
+        }
+        if let Some(impact) = &statement.impact_statement {
+            threats
+                .entry(impact.as_str())
+                .or_default()
+                .extend(products.clone());
+        }
+        if let Some(action) = &statement.action_statement {
+            remediations
+                .entry(action.as_str())
+                .or_default()
+                .extend(products);
+        }
+    }
+
+    let mut result = json!({
+        "cve": cve,
+        "product_status": product_status,
+    });
+    if !flags.is_empty() {
+        result["flags"] = flags
+            .into_iter()
+            .map(|(label, product_ids)| json!({ "label": label, "product_ids": product_ids }))
+            .collect();
+    }
+    if !threats.is_empty() {
+        result["threats"] = threats
+            .into_iter()
+            .map(|(details, product_ids)| {
+                json!({ "category": "impact", "details": details, "product_ids": product_ids })
+            })
+            .collect();
+    }
+    if !remediations.is_empty() {
+        result["remediations"] = remediations
+            .into_iter()
+            .map(|(details, product_ids)| {
+                json!({ "category": "mitigation", "details": details, "product_ids": product_ids })
+            })
+            .collect();
+    }
+
+    result
+}
+
+/// Collect the pURLs of the products of a CSAF product tree, by their product ID.
+fn csaf_purls(product_tree: &Value) -> HashMap<&str, &str> {
+    fn collect<'a>(product: &'a Value, result: &mut HashMap<&'a str, &'a str>) {
+        if let (Some(id), Some(purl)) = (
+            product["product_id"].as_str(),
+            product["product_identification_helper"]["purl"].as_str(),
+        ) {
+            result.insert(id, purl);
+        }
+    }
+
+    fn walk<'a>(branches: &'a Value, result: &mut HashMap<&'a str, &'a str>) {
+        for branch in array(branches) {
+            collect(&branch["product"], result);
+            walk(&branch["branches"], result);
+        }
+    }
+
+    let mut result = HashMap::new();
+    walk(&product_tree["branches"], &mut result);
+    for product in array(&product_tree["full_product_names"]) {
+        collect(product, &mut result);
+    }
+    for relationship in array(&product_tree["relationships"]) {
+        collect(&relationship["full_product_name"], &mut result);
+    }
+    result
+}
+
+/// Collect a text field of a list of entries, like flags, by the product IDs they apply to.
+fn product_details<'a>(
+    entries: &'a Value,
+    field: &str,
+    filter: impl Fn(&Value) -> bool,
+) -> HashMap<&'a str, &'a str> {
+    let mut result = HashMap::new();
+    for entry in array(entries).filter(|entry| filter(entry)) {
+        let Some(details) = entry[field].as_str() else {
+            continue;
+        };
+        for product in array(&entry["product_ids"]).filter_map(Value::as_str) {
+            result.entry(product).or_insert(details);
+        }
+    }
+    result
+}
+
+fn array(value: &Value) -> impl Iterator<Item = &Value> {
+    value.as_array().into_iter().flatten()
+}
+
+fn string(value: &Value) -> Option<String> {
+    value.as_str().map(ToString::to_string)
+}
@@ -0,0 +1,141 @@
+use super::*;
+use crate::vex::model::{VexJustification, VexPublisher};
+use serde_json::json;
+use test_log::test;
+use trustify_test_context::document;
+
+fn statement(vulnerability: &str, products: &[&str], status: VexStatus) -> VexStatement {
+    VexStatement {
+        vulnerability: vulnerability.into(),
+        products: products.iter().map(ToString::to_string).collect(),
+        status,
+        justification: None,
+        impact_statement: None,
+        action_statement: None,
+    }
+}
+
+fn vex(statements: Vec<VexStatement>) -> NewVex {
+    NewVex {
+        title: "Findings of ACME".into(),
+        publisher: VexPublisher {
+            name: "ACME".into(),
+            namespace: "https://acme.example.com".into(),
+        },
+        statements,
+        labels: Default::default(),
+    }
+}
+
+#[test]
+fn round_trip() -> anyhow::Result<()> {
+    let service = VexService::new();
+
+    let csaf = service.csaf_document(
+        &vex(vec![
+            VexStatement {
+                justification: Some(VexJustification::VulnerableCodeNotInExecutePath),
+                ..statement(
+                    "CVE-2023-0044",
+                    &["pkg:maven/io.quarkus/quarkus-vertx-http@2.13.8"],
+                    VexStatus::NotAffected,
+                )
+            },
+            VexStatement {
+                action_statement: Some("Update to 2.13.9".into()),
+                ..statement(
+                    "CVE-2023-0044",
+                    &["pkg:maven/io.quarkus/quarkus-vertx-http@2.13.7"],
+                    VexStatus::Affected,
+                )
+            },
+        ]),
+        Uuid::nil(),
+        OffsetDateTime::UNIX_EPOCH,
+    )?;
+
+    // both versions are below the same package name
+    let branches = &csaf["product_tree"]["branches"][0]["branches"];
+    assert_eq!(branches.as_array().map(Vec::len), Some(1));
+    assert_eq!(branches[0]["name"], "io.quarkus/quarkus-vertx-http");
+    assert_eq!(branches[0]["branches"][0]["name"], "2.13.7");
+
+    let openvex = service.openvex_document(Uuid::nil(), &csaf);
+    assert_eq!(
+        openvex,
+        json!({
+            "@context": "https://openvex.dev/ns/v0.2.0",
+            "@id": "urn:uuid:00000000-0000-0000-0000-000000000000",
+            "author": "ACME",
+            "timestamp": "1970-01-01T00:00:00Z",
+            "version": 1,
+            "statements": [
+                {
+                    "vulnerability": { "name": "CVE-2023-0044" },
+                    "products": [{ "@id": "pkg:maven/io.quarkus/quarkus-vertx-http@2.13.7" }],
+                    "status": "affected",
+                    "action_statement": "Update to 2.13.9",
+                },
+                {
+                    "vulnerability": { "name": "CVE-2023-0044" },
+                    "products": [{ "@id": "pkg:maven/io.quarkus/quarkus-vertx-http@2.13.8" }],
+                    "status": "not_affected",
+                    "justification": "vulnerable_code_not_in_execute_path",
+                },
+            ],
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn invalid_statements() {
+    let service = VexService::new();
+    let purl = "pkg:npm/left-pad@1.0.0";
+
+    for statements in [
+        vec![],
+        // not a CVE
+        vec![statement("GHSA-0000-0000-0000", &[purl], VexStatus::Fixed)],
+        // without products
+        vec![statement("CVE-2023-0044", &[], VexStatus::Fixed)],
+        // not affected, without a reason
+        vec![statement("CVE-2023-0044", &[purl], VexStatus::NotAffected)],
+        // contradicting
+        vec![
+            statement("CVE-2023-0044", &[purl], VexStatus::Fixed),
+            statement("CVE-2023-0044", &[purl], VexStatus::Affected),
+        ],
+    ] {
+        let result =
+            service.csaf_document(&vex(statements), Uuid::nil(), OffsetDateTime::UNIX_EPOCH);
+        assert!(
+            matches!(result, Err(Error::BadRequest(_))),
+            "unexpected result: {result:?}"
+        );
+    }
+}
+
+#[test(tokio::test)]
+async fn openvex_of_upstream_csaf() -> anyhow::Result<()> {
+    let (csaf, _): (Value, _) = document("csaf/cve-2023-0044.json").await?;
+
+    let openvex = VexService::new().openvex_document(Uuid::nil(), &csaf);
+    assert_eq!(openvex["author"], "Red Hat Product Security");
+
+    let statements = openvex["statements"].as_array().expect("must be an array");
+    let not_affected = statements
+        .iter()
+        .find(|statement| statement["status"] == "not_affected")
+        .expect("must have a not affected statement");
+    assert_eq!(not_affected["justification"], "vulnerable_code_not_present");
+    assert_eq!(not_affected["products"].as_array().map(Vec::len), Some(9));
+    assert!(
+        statements
+            .iter()
+            .all(|statement| statement["vulnerability"]["name"] == "CVE-2023-0044")
+    );
+
+    Ok(())
+}
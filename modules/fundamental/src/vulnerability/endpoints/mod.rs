@@ -3,28 +3,59 @@ mod test;
 
 use crate::{
     Error::{self, Internal},
-    endpoints::Deprecation,
+    endpoints::{AsOf, Deprecation, Fields, Withdrawal},
+    listing::ListFormat,
+    sbom::model::SbomHead,
+    tenant::Tenant,
     vulnerability::{
-        model::{AnalysisRequest, AnalysisResponse, VulnerabilityDetails, VulnerabilitySummary},
-        service::VulnerabilityService,
+        model::{
+            AnalysisRequest, AnalysisResponse, VulnerabilityAlias, VulnerabilityDetails,
+            VulnerabilitySummary,
+        },
+        service::{OsvFallback, VulnerabilityService},
     },
 };
-use actix_web::{HttpResponse, Responder, ResponseError, delete, get, post, web};
+use actix_web::{HttpRequest, HttpResponse, Responder, ResponseError, delete, get, post, web};
+use reqwest::Url;
 use sea_orm::TransactionTrait;
-use trustify_auth::{DeleteVulnerability, ReadAdvisory, authorizer::Require};
+use trustify_auth::{
+    DeleteVulnerability, Permission, ReadAdvisory, ReadSbom, all,
+    authenticator::user::UserInformation,
+    authorizer::{Authorizer, Require},
+};
 use trustify_common::{
     db::{Database, query::Query},
+    error::ErrorInformation,
     model::{Paginated, PaginatedResults},
+    signing::ResponseSigner,
 };
+use trustify_module_ingestor::service::IngestorService;
+use trustify_module_settings::service::SettingsService;
 
-pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+pub fn configure(
+    config: &mut utoipa_actix_web::service_config::ServiceConfig,
+    db: Database,
+    osv_fallback: Option<Url>,
+) {
     let service = VulnerabilityService::new();
+
+    if let Some(url) = osv_fallback {
+        match OsvFallback::new(url) {
+            Ok(osv_fallback) => {
+                config.app_data(web::Data::new(osv_fallback));
+            }
+            Err(err) => log::error!("Failed to create OSV fallback, disabling it: {err}"),
+        }
+    }
+
     config
         .app_data(web::Data::new(service))
         .app_data(web::Data::new(db))
         .service(all)
         .service(delete)
         .service(get)
+        .service(aliases)
+        .service(sboms)
         .service(analyze);
 }
 
@@ -34,37 +65,65 @@ pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, d
     params(
         Query,
         Paginated,
+        AsOf,
+        Fields,
     ),
     responses(
-        (status = 200, description = "Matching vulnerabilities", body = PaginatedResults<VulnerabilitySummary>),
+        (status = 200, description = "Matching vulnerabilities, as JSON, or streamed as CSV or NDJSON, depending on the Accept header", content(
+            (PaginatedResults<VulnerabilitySummary> = "application/json"),
+            (String = "text/csv"),
+            (String = "application/x-ndjson"),
+        )),
+        (status = 400, description = "The point in time is invalid", body = ErrorInformation),
     ),
 )]
 #[get("/v2/vulnerability")]
 /// List vulnerabilities
 pub async fn all(
+    req: HttpRequest,
     state: web::Data<VulnerabilityService>,
     db: web::Data<Database>,
     web::Query(search): web::Query<Query>,
     web::Query(paginated): web::Query<Paginated>,
     web::Query(Deprecation { deprecated }): web::Query<Deprecation>,
+    web::Query(as_of): web::Query<AsOf>,
+    web::Query(fields): web::Query<Fields>,
     _: Require<ReadAdvisory>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
-    Ok(HttpResponse::Ok().json(
+    let as_of = as_of.parse()?;
+    let format = ListFormat::negotiate(&req);
+    if format != ListFormat::Json {
+        return Ok(format.stream(fields, paginated, move |paginated| {
+            let (state, db, search, tenant) =
+                (state.clone(), db.clone(), search.clone(), tenant.clone());
+            async move {
+                state
+                    .fetch_vulnerabilities(search, paginated, deprecated, as_of, &tenant, db.read())
+                    .await
+            }
+        }));
+    }
+
+    Ok(fields.respond(
         state
-            .fetch_vulnerabilities(search, paginated, deprecated, db.as_ref())
+            .fetch_vulnerabilities(search, paginated, deprecated, as_of, &tenant, db.read())
             .await?,
-    ))
+    )?)
 }
 
 #[utoipa::path(
     tag = "vulnerability",
     operation_id = "getVulnerability",
     params(
-        ("id", Path, description = "ID of the vulnerability")
+        ("id", Path, description = "ID of the vulnerability"),
+        Withdrawal,
+        AsOf,
     ),
     responses(
         (status = 200, description = "Specified vulnerability", body = VulnerabilityDetails),
-        (status = 404, description = "Specified vulnerability not found"),
+        (status = 400, description = "The point in time is invalid", body = ErrorInformation),
+        (status = 404, description = "Specified vulnerability not found", body = ErrorInformation),
     ),
 )]
 #[get("/v2/vulnerability/{id}")]
@@ -74,10 +133,21 @@ pub async fn get(
     db: web::Data<Database>,
     id: web::Path<String>,
     web::Query(Deprecation { deprecated }): web::Query<Deprecation>,
+    web::Query(Withdrawal { include_withdrawn }): web::Query<Withdrawal>,
+    web::Query(as_of): web::Query<AsOf>,
     _: Require<ReadAdvisory>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
+    let as_of = as_of.parse()?;
     let vuln = state
-        .fetch_vulnerability(&id, deprecated, db.as_ref())
+        .fetch_vulnerability(
+            &id,
+            deprecated,
+            include_withdrawn.into(),
+            as_of,
+            &tenant,
+            db.read(),
+        )
         .await?;
     if let Some(vuln) = vuln {
         Ok(HttpResponse::Ok().json(vuln))
@@ -86,6 +156,75 @@ pub async fn get(
     }
 }
 
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "listVulnerabilitySboms",
+    params(
+        ("id", Path, description = "ID of the vulnerability"),
+        Deprecation,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "SBOMs containing affected packages", body = PaginatedResults<SbomHead>),
+        (status = 404, description = "Specified vulnerability not found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/vulnerability/{id}/sboms")]
+/// List the SBOMs affected by a vulnerability
+///
+/// An SBOM is affected if it contains a package with an affected version, or describes an
+/// affected version of a product.
+pub async fn sboms(
+    state: web::Data<VulnerabilityService>,
+    db: web::Data<Database>,
+    id: web::Path<String>,
+    web::Query(Deprecation { deprecated }): web::Query<Deprecation>,
+    web::Query(paginated): web::Query<Paginated>,
+    tenant: Tenant,
+    _: Require<ListVulnerabilitySboms>,
+) -> actix_web::Result<impl Responder> {
+    Ok(
+        match state
+            .fetch_affected_sboms(&id, deprecated, &tenant, paginated, db.read())
+            .await?
+        {
+            Some(sboms) => HttpResponse::Ok().json(sboms),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+all!(ListVulnerabilitySboms -> ReadSbom, ReadAdvisory);
+
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "listVulnerabilityAliases",
+    params(
+        ("id", Path, description = "ID or alias of the vulnerability")
+    ),
+    responses(
+        (status = 200, description = "Other identifiers of the vulnerability", body = Vec<VulnerabilityAlias>),
+        (status = 404, description = "Specified vulnerability not found", body = ErrorInformation),
+    ),
+)]
+#[get("/v2/vulnerability/{id}/aliases")]
+/// List the other identifiers of a vulnerability
+///
+/// For an alias, like a GHSA ID, this lists the vulnerabilities it belongs to, and their other
+/// aliases.
+pub async fn aliases(
+    state: web::Data<VulnerabilityService>,
+    db: web::Data<Database>,
+    id: web::Path<String>,
+    _: Require<ReadAdvisory>,
+    tenant: Tenant,
+) -> actix_web::Result<impl Responder> {
+    Ok(match state.fetch_aliases(&id, &tenant, db.read()).await? {
+        Some(aliases) => HttpResponse::Ok().json(aliases),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
 #[utoipa::path(
     tag = "vulnerability",
     operation_id = "deleteVulnerability",
@@ -94,7 +233,7 @@ pub async fn get(
     ),
     responses(
         (status = 200, description = "Specified vulnerability", body = VulnerabilityDetails),
-        (status = 404, description = "Specified vulnerability not found"),
+        (status = 404, description = "Specified vulnerability not found", body = ErrorInformation),
     ),
 )]
 #[delete("/v2/vulnerability/{id}")]
@@ -112,6 +251,9 @@ pub async fn delete(
         .fetch_vulnerability(
             &id,
             trustify_module_ingestor::common::Deprecation::Ignore,
+            trustify_module_ingestor::common::Withdrawal::Ignore,
+            Default::default(),
+            &Tenant::Any,
             &tx,
         )
         .await?;
@@ -138,20 +280,55 @@ pub async fn delete(
   tag = "vulnerability",
   request_body = AnalysisRequest,
   responses(
-      (status = 200, description = "Analyze the provided purls to search for known vulnerabilities", body = AnalysisResponse),
+      (status = 200, description = "Analyze the provided purls to search for known vulnerabilities", body = AnalysisResponse, headers(
+          ("x-jws-signature" = String, description = "Detached JWS over the body, if signing is enabled")
+      )),
   ),
 )]
 #[post("/v2/vulnerability/analyze")]
 /// Analyze the provided purls for the known vulnerabilities
+///
+/// If the OSV fallback is enabled, purls without local information get looked up using OSV. Only
+/// users allowed to create advisories wait for the lookup, for others it happens in the background.
+///
+/// If response signing is enabled, the response carries a detached JWS over its body.
 pub async fn analyze(
     service: web::Data<VulnerabilityService>,
     db: web::Data<Database>,
+    ingestor: web::Data<IngestorService>,
+    osv_fallback: Option<web::Data<OsvFallback>>,
+    settings: web::Data<SettingsService>,
+    signer: web::Data<ResponseSigner>,
     request: web::Json<AnalysisRequest>,
+    authorizer: web::Data<Authorizer>,
+    user: UserInformation,
     _: Require<ReadAdvisory>,
+    tenant: Tenant,
 ) -> actix_web::Result<impl Responder> {
+    let settings = settings.current().await.map_err(Error::from)?;
     let purls: Vec<&str> = request.purls.iter().map(|s| s.as_str()).collect();
-    match service.analyze_purls(purls, db.as_ref()).await {
-        Ok(details) => Ok(HttpResponse::Ok().json(details)),
+    let result = match osv_fallback {
+        // the fallback may be disabled at runtime
+        Some(osv_fallback) if settings.osv_fallback != Some(false) => {
+            // only users allowed to ingest advisories wait for the external lookup
+            let inline = authorizer
+                .require(&user, Permission::CreateAdvisory)
+                .is_ok();
+            service
+                .analyze_purls_with_fallback(
+                    purls,
+                    &osv_fallback,
+                    &ingestor,
+                    inline,
+                    &tenant,
+                    db.as_ref(),
+                )
+                .await
+        }
+        _ => service.analyze_purls(purls, &tenant, db.as_ref()).await,
+    };
+    match result {
+        Ok(details) => Ok(signer.json(HttpResponse::Ok(), details)),
         Err(error) => match error {
             Error::BadRequest(_) => Ok(error.error_response()),
             _ => Ok(HttpResponse::InternalServerError()
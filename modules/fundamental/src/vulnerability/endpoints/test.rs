@@ -2,7 +2,7 @@ use crate::test::caller;
 use crate::vulnerability::model::VulnerabilitySummary;
 use actix_http::StatusCode;
 use actix_web::test::TestRequest;
-use serde_json::Value;
+use serde_json::{Value, json};
 use test_context::test_context;
 use test_log::test;
 use time::{OffsetDateTime, macros::datetime};
@@ -14,7 +14,8 @@ use trustify_cvss::cvss3::{
 use trustify_module_ingestor::graph::{
     advisory::AdvisoryInformation, vulnerability::VulnerabilityInformation,
 };
-use trustify_test_context::{TrustifyContext, call::CallService};
+use trustify_test_context::{TrustifyContext, call::CallService, document_read};
+use zip::ZipArchive;
 
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
@@ -340,3 +341,181 @@ async fn get_vulnerability(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn aliases(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let result = ctx.ingest_document("osv/GHSA-2ccf-ffrj-m4qw.json").await?;
+    let advisory = result.id.to_string();
+
+    let app = caller(ctx).await?;
+
+    // the aliases of the CVE
+    let aliases: Value = app
+        .call_and_read_body_json(
+            TestRequest::get()
+                .uri("/api/v2/vulnerability/CVE-2023-29020/aliases")
+                .to_request(),
+        )
+        .await;
+    assert_eq!(
+        aliases,
+        json!([{"identifier": "GHSA-2ccf-ffrj-m4qw", "advisories": [advisory]}])
+    );
+
+    // the vulnerabilities of an alias, case-insensitively
+    let aliases: Value = app
+        .call_and_read_body_json(
+            TestRequest::get()
+                .uri("/api/v2/vulnerability/ghsa-2ccf-ffrj-m4qw/aliases")
+                .to_request(),
+        )
+        .await;
+    assert_eq!(
+        aliases,
+        json!([{"identifier": "CVE-2023-29020", "advisories": [advisory]}])
+    );
+
+    let response = app
+        .call_service(
+            TestRequest::get()
+                .uri("/api/v2/vulnerability/CVE-0000-0000/aliases")
+                .to_request(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // searching for the alias finds the CVE
+    let result: PaginatedResults<VulnerabilitySummary> = app
+        .call_and_read_body_json(
+            TestRequest::get()
+                .uri("/api/v2/vulnerability?q=GHSA-2ccf-ffrj-m4qw")
+                .to_request(),
+        )
+        .await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].head.identifier, "CVE-2023-29020");
+
+    let result: PaginatedResults<VulnerabilitySummary> = app
+        .call_and_read_body_json(
+            TestRequest::get()
+                .uri("/api/v2/vulnerability?q=id%3DGHSA-2ccf-ffrj-m4qw")
+                .to_request(),
+        )
+        .await;
+    assert_eq!(result.total, 1);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn ecosystems(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    ctx.ingest_documents(["osv/GHSA-2ccf-ffrj-m4qw.json", "osv/RUSTSEC-2021-0079.json"])
+        .await?;
+
+    let app = caller(ctx).await?;
+
+    let result: PaginatedResults<VulnerabilitySummary> = app
+        .call_and_read_body_json(
+            TestRequest::get()
+                .uri("/api/v2/vulnerability?q=ecosystem%3Dnpm")
+                .to_request(),
+        )
+        .await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].head.identifier, "CVE-2023-29020");
+
+    let result: PaginatedResults<VulnerabilitySummary> = app
+        .call_and_read_body_json(
+            TestRequest::get()
+                .uri("/api/v2/vulnerability?q=ecosystem%21%3Dnpm")
+                .to_request(),
+        )
+        .await;
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].head.identifier, "CVE-2021-32714");
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn weaknesses(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let zip = document_read("cwec_latest.xml.zip")?;
+    let mut archive = ZipArchive::new(zip)?;
+    ctx.ingest_read(archive.by_index(0)?).await?;
+    ctx.ingest_documents(["cve/CVE-2024-26308.json", "mitre/CVE-2024-27088.json"])
+        .await?;
+
+    let app = caller(ctx).await?;
+
+    let vuln: Value = app
+        .call_and_read_body_json(
+            TestRequest::get()
+                .uri("/api/v2/vulnerability/CVE-2024-26308")
+                .to_request(),
+        )
+        .await;
+    assert_eq!(vuln["weaknesses"][0]["id"], "CWE-770");
+    assert_eq!(
+        vuln["weaknesses"][0]["name"],
+        "Allocation of Resources Without Limits or Throttling"
+    );
+
+    // filter by the CWE itself, or one of its parents
+    for (cwe, expected) in [
+        ("CWE-770", vec!["CVE-2024-26308"]),
+        ("CWE-407", vec!["CVE-2024-27088"]),
+        ("CWE-400", vec!["CVE-2024-26308", "CVE-2024-27088"]),
+        ("CWE-664", vec!["CVE-2024-26308", "CVE-2024-27088"]),
+        ("CWE-79", vec![]),
+    ] {
+        let result: PaginatedResults<VulnerabilitySummary> = app
+            .call_and_read_body_json(
+                TestRequest::get()
+                    .uri(&format!("/api/v2/vulnerability?q=cwe%3D{cwe}&sort=id"))
+                    .to_request(),
+            )
+            .await;
+        let ids = result
+            .items
+            .iter()
+            .map(|item| item.head.identifier.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, expected, "filtering by {cwe}");
+    }
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn affected_sboms(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let id = ctx
+        .ingest_documents([
+            "quarkus-bom-2.13.8.Final-redhat-00004.json",
+            "csaf/cve-2023-0044.json",
+        ])
+        .await?[0]
+        .id
+        .to_string();
+
+    let request = TestRequest::get()
+        .uri("/api/v2/vulnerability/CVE-2023-0044/sboms")
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    log::debug!("{response:#?}");
+
+    assert_eq!(response["total"], 1);
+    assert_eq!(response["items"][0]["id"], id);
+
+    let request = TestRequest::get()
+        .uri("/api/v2/vulnerability/CVE-9999-0001/sboms")
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Another identifier of a vulnerability, like the GHSA or RUSTSEC ID of a CVE.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct VulnerabilityAlias {
+    /// The other identifier.
+    pub identifier: String,
+
+    /// The IDs of the advisories stating the alias, in URN form.
+    pub advisories: Vec<String>,
+}
@@ -0,0 +1,220 @@
+use super::vulnerability_advisory::format_version_range;
+use crate::{
+    Error,
+    product::model::{ProductHead, ProductVersionHead},
+    tenant::Tenant,
+};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbBackend, DbErr, EntityTrait, FromQueryResult, QueryFilter,
+    QueryResult, QuerySelect, Select, Statement,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use trustify_common::db::multi_model::{FromQueryResultMultiModel, SelectIntoMultiModel};
+use trustify_entity::{advisory, product, product_version, version_range, vulnerability};
+use trustify_module_ingestor::common::{AsOf, Deprecation, Withdrawal};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A product affected by a vulnerability, according to the product status of an advisory.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct VulnerabilityAffectedProduct {
+    #[serde(flatten)]
+    pub head: ProductHead,
+
+    /// The name of the vendor of the product, if known.
+    #[schema(required)]
+    pub vendor: Option<String>,
+
+    /// The status of the product, like `affected` or `fixed`.
+    pub status: String,
+
+    /// The range of product versions the status applies to.
+    pub version: String,
+
+    /// The advisory stating the status.
+    #[serde(with = "uuid::serde::urn")]
+    #[schema(value_type = String)]
+    pub advisory: Uuid,
+
+    /// Known versions of the product within the range, ordered by their version.
+    pub versions: Vec<ProductVersionHead>,
+}
+
+impl VulnerabilityAffectedProduct {
+    /// Collect the products for which advisories state a status other than `not_affected`.
+    ///
+    /// The result is ordered by the product name, the version range, and the status.
+    pub async fn from_vulnerability<C: ConnectionTrait>(
+        vulnerability: &vulnerability::Model,
+        deprecation: Deprecation,
+        withdrawal: Withdrawal,
+        as_of: AsOf,
+        tenant: &Tenant,
+        tx: &C,
+    ) -> Result<Vec<Self>, Error> {
+        // Matching product versions against the version ranges is easier to express in SQL,
+        // the same way the SBOM statuses of the vulnerability advisories are being looked up.
+        let query = r#"
+            SELECT DISTINCT
+                "product_status"."advisory_id",
+                "status"."slug" AS "status",
+                "organization"."name" AS "vendor",
+                "product"."id" AS "product$id",
+                "product"."name" AS "product$name",
+                "product"."vendor_id" AS "product$vendor_id",
+                "product"."cpe_key" AS "product$cpe_key",
+                "version_range"."id" AS "version_range$id",
+                "version_range"."version_scheme_id" AS "version_range$version_scheme_id",
+                "version_range"."low_version" AS "version_range$low_version",
+                "version_range"."low_inclusive" AS "version_range$low_inclusive",
+                "version_range"."high_version" AS "version_range$high_version",
+                "version_range"."high_inclusive" AS "version_range$high_inclusive",
+                "product_version"."id" AS "product_version$id",
+                "product_version"."product_id" AS "product_version$product_id",
+                "product_version"."sbom_id" AS "product_version$sbom_id",
+                "product_version"."version" AS "product_version$version"
+            FROM "product_status"
+            JOIN "advisory" ON "product_status"."advisory_id" = "advisory"."id"
+            JOIN "status" ON "product_status"."status_id" = "status"."id"
+            JOIN "product_version_range" ON "product_status"."product_version_range_id" = "product_version_range"."id"
+            JOIN "product" ON "product_version_range"."product_id" = "product"."id"
+            JOIN "version_range" ON "product_version_range"."version_range_id" = "version_range"."id"
+            LEFT JOIN "organization" ON "product"."vendor_id" = "organization"."id"
+
+            -- known versions of the product, possibly linked to an SBOM
+            LEFT JOIN "product_version" ON "product_version"."product_id" = "product"."id" AND version_matches("product_version"."version", "version_range".*)
+
+            WHERE
+            "product_status"."vulnerability_id" = $1 AND "status"."slug" != 'not_affected' AND ($2 OR NOT "advisory"."deprecated")
+            AND ($3 OR "advisory"."withdrawn" IS NULL)
+            "#;
+
+        let result: Vec<QueryResult> = tx
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                query,
+                [
+                    vulnerability.id.clone().into(),
+                    matches!(as_of.deprecation(deprecation), Deprecation::Consider).into(),
+                    matches!(withdrawal, Withdrawal::Consider).into(),
+                ],
+            ))
+            .await?;
+
+        // group the product versions by the statement they belong to
+
+        let mut products = BTreeMap::<_, Self>::new();
+
+        for row in &result {
+            let catcher = AffectedProductCatcher::from_query_result(row, "")?;
+            let version = format_version_range(&catcher.version_range);
+
+            let entry = products
+                .entry((
+                    catcher.product.name.clone(),
+                    catcher.product.id,
+                    version.clone(),
+                    catcher.status.clone(),
+                    catcher.advisory_id,
+                ))
+                .or_insert(Self {
+                    head: ProductHead::from_entity(&catcher.product).await?,
+                    vendor: catcher.vendor,
+                    status: catcher.status,
+                    version,
+                    advisory: catcher.advisory_id,
+                    versions: vec![],
+                });
+
+            if let Some(product_version) = &catcher.product_version {
+                entry
+                    .versions
+                    .push(ProductVersionHead::from_entity(product_version).await?);
+            }
+        }
+
+        // for a point in time, only keep the statements of advisories known at that time
+
+        if as_of.0.is_some() {
+            let known = advisory::Entity::find()
+                .select_only()
+                .column(advisory::Column::Id)
+                .filter(
+                    advisory::Column::Id.is_in(products.values().map(|product| product.advisory)),
+                )
+                .filter(as_of.condition(advisory::Column::Id, deprecation))
+                .into_tuple::<Uuid>()
+                .all(tx)
+                .await?
+                .into_iter()
+                .collect::<HashSet<_>>();
+            products.retain(|_, product| known.contains(&product.advisory));
+        }
+
+        // only keep the statements of advisories of the tenant, and hide SBOMs of other tenants
+
+        let own = tenant
+            .own_advisories(products.values().map(|product| product.advisory), tx)
+            .await?;
+        products.retain(|_, product| own.contains(&product.advisory));
+
+        let own = tenant
+            .own_sboms(
+                products
+                    .values()
+                    .flat_map(|product| &product.versions)
+                    .filter_map(|version| version.sbom_id),
+                tx,
+            )
+            .await?;
+
+        let mut products = products.into_values().collect::<Vec<_>>();
+        for product in &mut products {
+            for version in &mut product.versions {
+                version.sbom_id = version.sbom_id.filter(|id| own.contains(id));
+            }
+            product
+                .versions
+                .sort_by(|a, b| a.version.cmp(&b.version).then_with(|| a.id.cmp(&b.id)));
+        }
+
+        Ok(products)
+    }
+}
+
+#[derive(Debug)]
+struct AffectedProductCatcher {
+    advisory_id: Uuid,
+    status: String,
+    vendor: Option<String>,
+    product: product::Model,
+    version_range: version_range::Model,
+    product_version: Option<product_version::Model>,
+}
+
+impl FromQueryResult for AffectedProductCatcher {
+    fn from_query_result(res: &QueryResult, _pre: &str) -> Result<Self, DbErr> {
+        Ok(Self {
+            advisory_id: res.try_get("", "advisory_id")?,
+            status: res.try_get("", "status")?,
+            vendor: res.try_get("", "vendor")?,
+            product: Self::from_query_result_multi_model(res, "", product::Entity)?,
+            version_range: Self::from_query_result_multi_model(res, "", version_range::Entity)?,
+            product_version: Self::from_query_result_multi_model_optional(
+                res,
+                "",
+                product_version::Entity,
+            )?,
+        })
+    }
+}
+
+impl FromQueryResultMultiModel for AffectedProductCatcher {
+    fn try_into_multi_model<E: EntityTrait>(select: Select<E>) -> Result<Select<E>, DbErr> {
+        select
+            .try_model_columns(product::Entity)?
+            .try_model_columns(version_range::Entity)?
+            .try_model_columns(product_version::Entity)
+    }
+}
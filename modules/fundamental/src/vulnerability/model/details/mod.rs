@@ -1,14 +1,25 @@
+mod affected_product;
 mod vulnerability_advisory;
 
+pub use affected_product::*;
 pub use vulnerability_advisory::*;
 
-use crate::{Error, vulnerability::model::VulnerabilityHead};
-use sea_orm::{ConnectionTrait, ModelTrait};
+use crate::{
+    Error, annotation::model::VulnerabilityAnnotation, comment::model::Comment,
+    severity_override::model::SeverityOverride, tenant::Tenant,
+    vulnerability::model::VulnerabilityHead, weakness::model::WeaknessHead,
+};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, EntityTrait, ModelTrait, QueryFilter, QueryOrder,
+};
 use serde::{Deserialize, Serialize};
 use trustify_common::memo::Memo;
 use trustify_cvss::cvss3::{Cvss3Base, score::Score, severity::Severity};
-use trustify_entity::{advisory_vulnerability, cvss3, vulnerability};
-use trustify_module_ingestor::common::{Deprecation, DeprecationForExt};
+use trustify_entity::{
+    advisory_vulnerability, comment, cvss3, severity_override, vulnerability,
+    vulnerability_annotation,
+};
+use trustify_module_ingestor::common::{AsOf, Deprecation, DeprecationForExt, Withdrawal};
 use utoipa::ToSchema;
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -24,23 +35,55 @@ pub struct VulnerabilityDetails {
     #[schema(required)]
     pub average_score: Option<f64>,
 
-    /// Advisories addressing this vulnerability, if any.
+    /// The severity of the vulnerability as defined by an admin, overriding the average severity
+    /// of the advisories, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity_override: Option<SeverityOverride>,
+
+    /// Advisories addressing this vulnerability, if any, ordered by their identifier.
     pub advisories: Vec<VulnerabilityAdvisorySummary>,
+
+    /// Products affected by this vulnerability according to the advisories, ordered by the
+    /// product name.
+    pub affected_products: Vec<VulnerabilityAffectedProduct>,
+
+    /// CWE entries of the vulnerability and its advisories, ordered by their identifier. The name
+    /// and description are only present for entries known to the CWE catalog.
+    pub weaknesses: Vec<WeaknessHead>,
+
+    /// Threat intelligence annotations of the vulnerability, oldest first.
+    pub annotations: Vec<VulnerabilityAnnotation>,
+
+    /// Comments on the vulnerability, oldest first.
+    pub comments: Vec<Comment>,
 }
 
 impl VulnerabilityDetails {
     pub async fn from_entity<C: ConnectionTrait>(
         vulnerability: &vulnerability::Model,
         deprecation: Deprecation,
+        withdrawal: Withdrawal,
+        as_of: AsOf,
+        tenant: &Tenant,
         tx: &C,
     ) -> Result<Self, Error> {
         let advisory_vulnerabilities = vulnerability
             .find_related(advisory_vulnerability::Entity)
-            .with_deprecation_related(deprecation)
+            .with_deprecation_related(as_of.deprecation(deprecation))
+            .filter(withdrawal.condition(advisory_vulnerability::Column::AdvisoryId))
+            .filter(as_of.condition(advisory_vulnerability::Column::AdvisoryId, deprecation))
+            .filter(tenant.advisory_condition(advisory_vulnerability::Column::AdvisoryId))
             .all(tx)
             .await?;
 
-        let cvss3 = vulnerability.find_related(cvss3::Entity).all(tx).await?;
+        let cvss3 = vulnerability
+            .find_related(cvss3::Entity)
+            .filter(as_of.condition(cvss3::Column::AdvisoryId, Deprecation::Consider))
+            .filter(tenant.advisory_condition(cvss3::Column::AdvisoryId))
+            .order_by_asc(cvss3::Column::AdvisoryId)
+            .order_by_asc(cvss3::Column::MinorVersion)
+            .all(tx)
+            .await?;
         let score = if cvss3.is_empty() {
             None
         } else {
@@ -51,6 +94,61 @@ impl VulnerabilityDetails {
             vulnerability,
             &advisory_vulnerabilities,
             &cvss3,
+            tenant,
+            tx,
+        )
+        .await?;
+
+        let affected_products = VulnerabilityAffectedProduct::from_vulnerability(
+            vulnerability,
+            deprecation,
+            withdrawal,
+            as_of,
+            tenant,
+            tx,
+        )
+        .await?;
+
+        let weaknesses = WeaknessHead::from_ids(
+            vulnerability.cwes.iter().flatten().chain(
+                advisory_vulnerabilities
+                    .iter()
+                    .flat_map(|advisory_vulnerability| {
+                        advisory_vulnerability.cwes.iter().flatten()
+                    }),
+            ),
+            tx,
+        )
+        .await?;
+
+        // annotations of feeds don't belong to any tenant
+        let mut annotations = vulnerability.find_related(vulnerability_annotation::Entity);
+        if let Some(tenant) = tenant.name() {
+            annotations = annotations.filter(
+                Condition::any()
+                    .add(vulnerability_annotation::Column::Tenant.is_null())
+                    .add(vulnerability_annotation::Column::Tenant.eq(tenant)),
+            );
+        }
+        let annotations = annotations
+            .order_by_asc(vulnerability_annotation::Column::Created)
+            .order_by_asc(vulnerability_annotation::Column::Id)
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(VulnerabilityAnnotation::from)
+            .collect();
+
+        let severity_override = severity_override::Entity::find_by_id((
+            vulnerability.id.clone(),
+            tenant.name().unwrap_or_default().to_string(),
+        ))
+        .one(tx)
+        .await?
+        .map(SeverityOverride::from);
+
+        let comments = Comment::find(
+            comment::Column::VulnerabilityId.eq(vulnerability.id.clone()),
             tx,
         )
         .await?;
@@ -64,7 +162,12 @@ impl VulnerabilityDetails {
             .await?,
             average_severity: score.map(|v| v.severity()),
             average_score: score.map(|v| v.value()),
+            severity_override,
             advisories,
+            affected_products,
+            weaknesses,
+            annotations,
+            comments,
         })
     }
 }
@@ -3,16 +3,17 @@ use crate::{
     advisory::model::AdvisoryHead,
     purl::model::{BasePurlHead, details::purl::StatusContext, summary::purl::PurlSummary},
     sbom::model::SbomHead,
+    tenant::Tenant,
 };
 use ::cpe::uri::OwnedUri;
 use sea_orm::{
     ColumnTrait, ConnectionTrait, DbBackend, DbErr, EntityTrait, FromQueryResult, LoaderTrait,
-    ModelTrait, PaginatorTrait, QueryFilter, QueryResult, QuerySelect, RelationTrait, Select,
-    Statement,
+    ModelTrait, PaginatorTrait, QueryFilter, QueryOrder, QueryResult, QuerySelect, RelationTrait,
+    Select, Statement,
 };
 use sea_query::{Asterisk, Expr, Func, JoinType, SimpleExpr};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap};
 use trustify_common::{
     db::{
         VersionMatches,
@@ -112,13 +113,16 @@ pub struct VulnerabilityAdvisorySummary {
     #[serde(flatten)]
     pub head: VulnerabilityAdvisoryHead,
 
-    /// CVSS3 scores from this advisory regarding the vulnerability.
+    /// CVSS3 scores from this advisory regarding the vulnerability, ordered by the minor version
+    /// of CVSS3.
     #[schema(default, value_type = Vec < String >)]
     pub cvss3_scores: Vec<String>,
 
-    pub purls: HashMap<String, Vec<VulnerabilityAdvisoryStatus>>,
+    /// Package statuses by status, each ordered by the base PURL and version range.
+    pub purls: BTreeMap<String, Vec<VulnerabilityAdvisoryStatus>>,
 
-    /// SBOMs claimed by this advisory to be addressed by this vulnerability.
+    /// SBOMs claimed by this advisory to be addressed by this vulnerability, ordered by their
+    /// document ID.
     pub sboms: Vec<VulnerabilitySbomStatus>,
 
     /// The total number of vulnerabilities described by this advisory
@@ -130,6 +134,7 @@ impl VulnerabilityAdvisorySummary {
         vulnerability: &vulnerability::Model,
         advisory_vulnerabilities: &[advisory_vulnerability::Model],
         vuln_cvss3: &[cvss3::Model],
+        tenant: &Tenant,
         tx: &C,
     ) -> Result<Vec<Self>, Error> {
         let purl_status_query = purl_status::Entity::find()
@@ -137,7 +142,13 @@ impl VulnerabilityAdvisorySummary {
             .filter(purl_status::Column::VulnerabilityId.eq(&vulnerability.id))
             .left_join(base_purl::Entity)
             .left_join(version_range::Entity)
-            .left_join(cpe::Entity);
+            .left_join(cpe::Entity)
+            .order_by_asc(base_purl::Column::Type)
+            .order_by_asc(base_purl::Column::Namespace)
+            .order_by_asc(base_purl::Column::Name)
+            .order_by_asc(version_range::Column::LowVersion)
+            .order_by_asc(version_range::Column::HighVersion)
+            .order_by_asc(purl_status::Column::Id);
 
         let sbom_status_query = sbom_package_purl_ref::Entity::find()
             .join(JoinType::Join, sbom_package_purl_ref::Relation::Purl.def())
@@ -195,6 +206,8 @@ impl VulnerabilityAdvisorySummary {
                 "sbom"."data_licenses" AS "sbom$data_licenses",
                 "sbom"."source_document_id" AS "sbom$source_document_id",
                 "sbom"."labels" AS "sbom$labels",
+                "sbom"."external_id" AS "sbom$external_id",
+                "sbom"."deleted_at" AS "sbom$deleted_at",
                 "sbom_package"."sbom_id" AS "sbom_package$sbom_id",
                 "sbom_package"."node_id" AS "sbom_package$node_id",
                 "sbom_package"."version" AS "sbom_package$version",
@@ -254,6 +267,8 @@ impl VulnerabilityAdvisorySummary {
                 .map(|row| SbomStatusCatcher::from_query_result(row, ""))
                 .collect::<Result<Vec<_>, _>>()?,
         );
+        // SBOMs of other tenants
+        vuln_sbom_statuses.retain(|status| tenant.owns(&status.sbom.labels));
 
         // Purl statuses might make sense only when we don't have any sboms
         // so we can show what we know about affected purls
@@ -305,6 +320,8 @@ impl VulnerabilityAdvisorySummary {
             });
         }
 
+        summaries.sort_by(|a, b| a.head.head.cmp_identifier(&b.head.head));
+
         Ok(summaries)
     }
 }
@@ -354,55 +371,57 @@ impl PurlStatusCatcher {
     }
 
     pub fn version(&self) -> String {
-        match (
-            &self.version_range.low_version,
-            &self.version_range.high_version,
-        ) {
-            (Some(low), Some(high)) if low == high => low.clone(),
-            (Some(low), Some(high)) => {
-                let mut v = String::new();
-                v.push(Self::open_delim(self.version_range.low_inclusive));
-                v.push_str(low);
-                v.push(',');
-                v.push_str(high);
-                v.push(Self::close_delim(self.version_range.high_inclusive));
-                v
-            }
+        format_version_range(&self.version_range)
+    }
+}
 
-            (Some(low), None) => {
-                let mut v = String::new();
-                v.push(Self::open_delim(self.version_range.low_inclusive));
-                v.push_str(low);
-                v.push(',');
-                v.push(Self::close_delim(self.version_range.high_inclusive));
-                v
-            }
-            (None, Some(high)) => {
-                let mut v = String::new();
-                v.push(Self::open_delim(self.version_range.low_inclusive));
-                v.push(',');
-                v.push_str(high);
-                v.push(Self::close_delim(self.version_range.high_inclusive));
-                v
-            }
-            (None, None) => "*".to_string(),
+/// Render a version range in interval notation, like `[1.0,2.0)`, or `*` if it is unbounded.
+pub(crate) fn format_version_range(version_range: &version_range::Model) -> String {
+    match (&version_range.low_version, &version_range.high_version) {
+        (Some(low), Some(high)) if low == high => low.clone(),
+        (Some(low), Some(high)) => {
+            let mut v = String::new();
+            v.push(open_delim(version_range.low_inclusive));
+            v.push_str(low);
+            v.push(',');
+            v.push_str(high);
+            v.push(close_delim(version_range.high_inclusive));
+            v
         }
-    }
 
-    fn open_delim(incl: Option<bool>) -> char {
-        if let Some(incl) = incl {
-            if incl { '[' } else { '(' }
-        } else {
-            '('
+        (Some(low), None) => {
+            let mut v = String::new();
+            v.push(open_delim(version_range.low_inclusive));
+            v.push_str(low);
+            v.push(',');
+            v.push(close_delim(version_range.high_inclusive));
+            v
         }
+        (None, Some(high)) => {
+            let mut v = String::new();
+            v.push(open_delim(version_range.low_inclusive));
+            v.push(',');
+            v.push_str(high);
+            v.push(close_delim(version_range.high_inclusive));
+            v
+        }
+        (None, None) => "*".to_string(),
     }
+}
 
-    fn close_delim(incl: Option<bool>) -> char {
-        if let Some(incl) = incl {
-            if incl { ']' } else { ')' }
-        } else {
-            ')'
-        }
+fn open_delim(incl: Option<bool>) -> char {
+    if let Some(incl) = incl {
+        if incl { '[' } else { '(' }
+    } else {
+        '('
+    }
+}
+
+fn close_delim(incl: Option<bool>) -> char {
+    if let Some(incl) = incl {
+        if incl { ']' } else { ')' }
+    } else {
+        ')'
     }
 }
 
@@ -417,8 +436,8 @@ pub struct VulnerabilityAdvisoryStatus {
 impl VulnerabilityAdvisoryStatus {
     async fn from_models<'i, I: Iterator<Item = &'i PurlStatusCatcher>>(
         purls: I,
-    ) -> Result<HashMap<String, Vec<Self>>, Error> {
-        let mut statuses = HashMap::new();
+    ) -> Result<BTreeMap<String, Vec<Self>>, Error> {
+        let mut statuses = BTreeMap::new();
 
         for each in purls {
             let context = each.cpe.as_ref().and_then(|cpe| {
@@ -484,7 +503,8 @@ pub struct VulnerabilitySbomStatus {
 
     pub version: Option<String>,
 
-    pub purl_statuses: HashMap<String, HashSet<PurlSummary>>,
+    /// PURLs of the SBOM by status, each ordered by the PURL.
+    pub purl_statuses: BTreeMap<String, Vec<PurlSummary>>,
 }
 
 impl VulnerabilitySbomStatus {
@@ -518,9 +538,26 @@ impl VulnerabilitySbomStatus {
                 .entry(status.status.slug.clone())
                 .or_insert(Default::default());
 
-            purl_status.insert(PurlSummary::from_entity(&status.qualified_purl));
+            let purl = PurlSummary::from_entity(&status.qualified_purl);
+            if !purl_status.contains(&purl) {
+                purl_status.push(purl);
+            }
+        }
+
+        let mut sboms = sboms.into_values().collect::<Vec<_>>();
+        sboms.sort_by(|a, b| {
+            a.head
+                .document_id
+                .cmp(&b.head.document_id)
+                .then_with(|| a.head.id.cmp(&b.head.id))
+        });
+
+        for sbom in &mut sboms {
+            for purls in sbom.purl_statuses.values_mut() {
+                purls.sort_by_cached_key(|purl| purl.head.purl.to_string());
+            }
         }
 
-        Ok(sboms.into_values().collect())
+        Ok(sboms)
     }
 }
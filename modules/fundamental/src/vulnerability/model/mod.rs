@@ -1,8 +1,10 @@
+mod alias;
 mod details;
 mod summary;
 
 use std::{collections::HashMap, ops::Deref};
 
+pub use alias::*;
 use async_graphql::SimpleObject;
 pub use details::*;
 use sea_orm::{ColumnTrait, ConnectionTrait, ModelTrait, QueryFilter};
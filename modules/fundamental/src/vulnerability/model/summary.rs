@@ -1,5 +1,6 @@
 use crate::{
     Error,
+    tenant::Tenant,
     vulnerability::model::{VulnerabilityAdvisoryHead, VulnerabilityHead},
 };
 use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, LoaderTrait, QueryFilter};
@@ -9,7 +10,7 @@ use trustify_cvss::cvss3::severity::Severity;
 use trustify_entity::{
     advisory, advisory_vulnerability, cvss3, vulnerability, vulnerability_description,
 };
-use trustify_module_ingestor::common::{Deprecation, DeprecationExt};
+use trustify_module_ingestor::common::{AsOf, Deprecation, DeprecationExt};
 use utoipa::ToSchema;
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -34,17 +35,27 @@ impl VulnerabilitySummary {
         vulnerabilities: &[vulnerability::Model],
         averages: &[(Option<f64>, Option<Severity>)],
         deprecation: Deprecation,
+        as_of: AsOf,
+        tenant: &Tenant,
         tx: &C,
     ) -> Result<Vec<Self>, Error> {
         let advisories = vulnerabilities
             .load_many_to_many(
-                advisory::Entity::find().with_deprecation(deprecation),
+                advisory::Entity::find()
+                    .with_deprecation(as_of.deprecation(deprecation))
+                    .filter(as_of.condition(advisory::Column::Id, deprecation))
+                    .filter(tenant.advisory_condition(advisory::Column::Id)),
                 advisory_vulnerability::Entity,
                 tx,
             )
             .await?;
 
-        let vuln_cvss3s = vulnerabilities.load_many(cvss3::Entity, tx).await?;
+        let vuln_cvss3s = vulnerabilities
+            .load_many(
+                cvss3::Entity::find().filter(tenant.advisory_condition(cvss3::Column::AdvisoryId)),
+                tx,
+            )
+            .await?;
 
         let descriptions = vulnerabilities
             .load_many(
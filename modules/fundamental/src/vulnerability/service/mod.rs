@@ -1,18 +1,26 @@
+mod osv;
+
+pub use osv::*;
+
 use std::{collections::HashMap, str::FromStr};
 
 use crate::{
     Error,
-    vulnerability::model::{VulnerabilityDetails, VulnerabilitySummary},
+    sbom::model::SbomHead,
+    tenant::Tenant,
+    vulnerability::model::{VulnerabilityAlias, VulnerabilityDetails, VulnerabilitySummary},
 };
 use futures_util::{TryFutureExt, TryStreamExt};
 use sea_orm::{
-    EntityTrait, FromQueryResult, IntoIdentity, QuerySelect, QueryTrait, Statement, StreamTrait,
-    prelude::*,
+    EntityTrait, FromQueryResult, IntoIdentity, QueryOrder, QuerySelect, QueryTrait, Statement,
+    StreamTrait, Value, prelude::*,
+};
+use sea_query::{
+    ColumnRef, Condition, Expr, Func, IntoColumnRef, IntoIden, JoinType, RcOrArc, SimpleExpr,
 };
-use sea_query::{ColumnRef, Func, IntoColumnRef, IntoIden, SimpleExpr};
 use trustify_common::{
     db::{
-        limiter::LimiterAsModelTrait,
+        limiter::{LimiterAsModelTrait, LimiterTrait},
         multi_model::{FromQueryResultMultiModel, SelectIntoMultiModel},
         query::{Columns, Filtering, Query},
     },
@@ -20,10 +28,15 @@ use trustify_common::{
     purl::{Purl, PurlErr},
 };
 use trustify_entity::{
+    advisory, advisory_vulnerability,
     cvss3::{self, Severity},
-    vulnerability,
+    sbom::{self, SbomNodeLink},
+    vulnerability, vulnerability_alias,
+};
+use trustify_module_ingestor::{
+    common::{AsOf, Deprecation, Withdrawal},
+    service::IngestorService,
 };
-use trustify_module_ingestor::common::Deprecation;
 
 #[derive(Default)]
 pub struct VulnerabilityService {}
@@ -38,10 +51,28 @@ impl VulnerabilityService {
         search: Query,
         paginated: Paginated,
         deprecation: Deprecation,
+        as_of: AsOf,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<PaginatedResults<VulnerabilitySummary>, Error> {
+        let search = Query {
+            q: expand_aliases(&search.q, connection).await?,
+            ..search
+        };
+
+        // only the scores of advisories known at the point in time, and of the tenant
+        let scores = as_of
+            .condition(cvss3::Column::AdvisoryId, Deprecation::Consider)
+            .add(tenant.advisory_condition(cvss3::Column::AdvisoryId));
+
         let inner_query = vulnerability::Entity::find()
-            .left_join(cvss3::Entity)
+            .filter(known(as_of))
+            .join(
+                JoinType::LeftJoin,
+                vulnerability::Relation::Cvss3
+                    .def()
+                    .on_condition(move |_, _| scores.clone()),
+            )
             .expr_as_(
                 SimpleExpr::FunctionCall(Func::avg(SimpleExpr::Column(
                     trustify_entity::cvss3::Column::Score.into_column_ref(),
@@ -98,6 +129,62 @@ impl VulnerabilityService {
                         }
                         .def(),
                     )
+                    // the ecosystems of the packages the advisories state a status for
+                    .add_expr(
+                        "ecosystem",
+                        SimpleExpr::Custom(
+                            r#"ARRAY(
+                                SELECT DISTINCT "base_purl"."ecosystem"
+                                FROM "purl_status"
+                                JOIN "advisory" ON "purl_status"."advisory_id" = "advisory"."id"
+                                JOIN "base_purl" ON "purl_status"."base_purl_id" = "base_purl"."id"
+                                WHERE "purl_status"."vulnerability_id" = "vulnerability"."id" AND "advisory"."deleted_at" IS NULL
+                            )"#
+                            .into(),
+                        ),
+                        ColumnType::Array(RcOrArc::new(ColumnType::Text)),
+                    )
+                    // the CWEs of the vulnerability and its advisories, including their parents
+                    .add_expr(
+                        "cwe",
+                        SimpleExpr::Custom(
+                            r#"ARRAY(
+                                WITH RECURSIVE "cwe"("id") AS (
+                                    SELECT unnest("vulnerability"."cwes")
+                                    UNION
+                                    SELECT unnest("advisory_vulnerability"."cwes")
+                                    FROM "advisory_vulnerability"
+                                    WHERE "advisory_vulnerability"."vulnerability_id" = "vulnerability"."id"
+                                    UNION
+                                    SELECT unnest("weakness"."child_of")
+                                    FROM "weakness"
+                                    JOIN "cwe" ON "weakness"."id" = "cwe"."id"
+                                )
+                                SELECT "id" FROM "cwe"
+                            )"#
+                            .into(),
+                        ),
+                        ColumnType::Array(RcOrArc::new(ColumnType::Text)),
+                    )
+                    // the kinds of threat intelligence annotations of the vulnerability, visible
+                    // to the tenant
+                    .add_expr(
+                        "annotation",
+                        Expr::cust_with_values(
+                            r#"ARRAY(
+                                SELECT DISTINCT "vulnerability_annotation"."kind"::text
+                                FROM "vulnerability_annotation"
+                                WHERE "vulnerability_annotation"."vulnerability_id" = "vulnerability"."id"
+                                AND (
+                                    $1::text IS NULL
+                                    OR "vulnerability_annotation"."tenant" IS NULL
+                                    OR "vulnerability_annotation"."tenant" = $1
+                                )
+                            )"#,
+                            [tenant.name().map(ToString::to_string)],
+                        ),
+                        ColumnType::Array(RcOrArc::new(ColumnType::Text)),
+                    )
                     .translator(|f, op, v| match (f, v) {
                         // v = "" for all sort fields
                         ("average_severity", "") => Some(format!("average_score:{op}")),
@@ -108,7 +195,8 @@ impl VulnerabilityService {
                 connection,
                 paginated.offset,
                 paginated.limit,
-            )?;
+            )?
+            .counting(paginated.count);
 
         let total = limiter.total().await?;
         let caught = limiter.fetch().await?;
@@ -127,6 +215,8 @@ impl VulnerabilityService {
                 &vulnerabilities,
                 &averages,
                 deprecation,
+                as_of,
+                tenant,
                 connection,
             )
             .await?,
@@ -137,20 +227,154 @@ impl VulnerabilityService {
         &self,
         identifier: &str,
         deprecation: Deprecation,
+        withdrawal: Withdrawal,
+        as_of: AsOf,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<Option<VulnerabilityDetails>, Error> {
         if let Some(vulnerability) = vulnerability::Entity::find_by_id(identifier)
+            .filter(known(as_of))
             .one(connection)
             .await?
         {
             Ok(Some(
-                VulnerabilityDetails::from_entity(&vulnerability, deprecation, connection).await?,
+                VulnerabilityDetails::from_entity(
+                    &vulnerability,
+                    deprecation,
+                    withdrawal,
+                    as_of,
+                    tenant,
+                    connection,
+                )
+                .await?,
             ))
         } else {
             Ok(None)
         }
     }
 
+    /// Get the SBOMs containing packages affected by a vulnerability.
+    ///
+    /// Packages are affected either through the status of their PURL, or through the status of
+    /// the product version the SBOM describes. Returns `None` if the vulnerability is not known.
+    pub async fn fetch_affected_sboms<C: ConnectionTrait + Sync + Send>(
+        &self,
+        identifier: &str,
+        deprecation: Deprecation,
+        tenant: &Tenant,
+        paginated: Paginated,
+        connection: &C,
+    ) -> Result<Option<PaginatedResults<SbomHead>>, Error> {
+        if vulnerability::Entity::find_by_id(identifier)
+            .one(connection)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let mut query = sbom::Entity::find()
+            .filter(sbom::Column::DeletedAt.is_null())
+            .filter(Expr::cust_with_values(
+                format!(r#""sbom"."sbom_id" IN ({AFFECTED_SBOMS})"#),
+                [
+                    Value::from(identifier),
+                    Value::from(matches!(deprecation, Deprecation::Consider)),
+                    Value::from(tenant.name().map(ToString::to_string)),
+                ],
+            ));
+        if let Some(filter) = tenant.filter() {
+            query = query.filter(filter.into_condition((sbom::Entity, sbom::Column::Labels)));
+        }
+
+        let limiter = query
+            .order_by_desc(sbom::Column::Published)
+            .order_by_asc(sbom::Column::SbomId)
+            .find_also_linked(SbomNodeLink)
+            .limiting(connection, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        let total = limiter.total().await?;
+        let mut items = vec![];
+        for (sbom, node) in limiter.fetch().await? {
+            items.push(SbomHead::from_entity(&sbom, node, connection).await?);
+        }
+
+        Ok(Some(PaginatedResults { items, total }))
+    }
+
+    /// Get the other identifiers of a vulnerability, or the vulnerabilities an alias belongs to.
+    ///
+    /// Returns `None` if the identifier is neither a known vulnerability nor an alias.
+    pub async fn fetch_aliases<C: ConnectionTrait>(
+        &self,
+        identifier: &str,
+        tenant: &Tenant,
+        connection: &C,
+    ) -> Result<Option<Vec<VulnerabilityAlias>>, Error> {
+        let query = r#"
+            WITH "edge" AS (
+                SELECT "vulnerability_alias".*
+                FROM "vulnerability_alias"
+                JOIN "advisory" ON "vulnerability_alias"."advisory_id" = "advisory"."id"
+                WHERE "advisory"."deleted_at" IS NULL
+                    AND ($2::text IS NULL OR "advisory"."labels" ->> 'tenant' = $2)
+            ), "canonical" AS (
+                SELECT "id" FROM "vulnerability" WHERE "id" = $1
+                UNION
+                SELECT "vulnerability_id" FROM "edge" WHERE lower("alias") = lower($1)
+            )
+            SELECT
+                "related"."identifier",
+                array_agg(DISTINCT "related"."advisory_id" ORDER BY "related"."advisory_id") AS "advisories"
+            FROM (
+                -- the vulnerabilities the identifier is an alias of
+                SELECT "vulnerability_id" AS "identifier", "advisory_id" FROM "edge" WHERE lower("alias") = lower($1)
+                UNION ALL
+                -- all aliases of those vulnerabilities
+                SELECT "alias" AS "identifier", "advisory_id" FROM "edge" WHERE "vulnerability_id" IN (SELECT "id" FROM "canonical")
+            ) AS "related"
+            WHERE lower("related"."identifier") != lower($1)
+            GROUP BY "related"."identifier"
+            ORDER BY "related"."identifier"
+            "#;
+
+        let rows = connection
+            .query_all(Statement::from_sql_and_values(
+                connection.get_database_backend(),
+                query,
+                [
+                    identifier.into(),
+                    tenant.name().map(ToString::to_string).into(),
+                ],
+            ))
+            .await?;
+
+        if rows.is_empty()
+            && vulnerability::Entity::find_by_id(identifier)
+                .one(connection)
+                .await?
+                .is_none()
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            rows.into_iter()
+                .map(|row| {
+                    let advisories: Vec<Uuid> = row.try_get("", "advisories")?;
+                    Ok(VulnerabilityAlias {
+                        identifier: row.try_get("", "identifier")?,
+                        advisories: advisories
+                            .into_iter()
+                            .map(|id| id.urn().to_string())
+                            .collect(),
+                    })
+                })
+                .collect::<Result<_, DbErr>>()?,
+        ))
+    }
+
     pub async fn delete_vulnerability<C: ConnectionTrait + Sync + Send>(
         &self,
         id: &str,
@@ -166,6 +390,7 @@ impl VulnerabilityService {
     pub async fn analyze_purls<C>(
         &self,
         purls: Vec<&str>,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<HashMap<String, Vec<VulnerabilityDetails>>, Error>
     where
@@ -196,6 +421,7 @@ impl VulnerabilityService {
                     purl.name.into(),
                     purl.ty.into(),
                     purl.version.ok_or_else(|| PurlErr::MissingVersion(p.to_string()))?.into(),
+                    tenant.name().map(ToString::to_string).into(),
                 ];
                 let sql = format!(r#"
                   SELECT $1 as requested_purl,
@@ -203,6 +429,7 @@ impl VulnerabilityService {
                     vulnerability.published, vulnerability.modified, vulnerability.withdrawn, vulnerability.cwes
                   FROM base_purl
                     LEFT JOIN purl_status ON base_purl.id = purl_status.base_purl_id
+                    INNER JOIN advisory ON purl_status.advisory_id = advisory.id
                     INNER JOIN version_range ON purl_status.version_range_id = version_range.id
                     LEFT JOIN vulnerability ON purl_status.vulnerability_id = vulnerability.id
                     INNER JOIN status ON purl_status.status_id = status.id
@@ -210,7 +437,9 @@ impl VulnerabilityService {
                     AND base_purl.name = $2
                     AND base_purl.type = $3
                     AND version_matches($4, version_range.*) = TRUE
-                    AND status.slug != 'fixed'"#);
+                    AND status.slug != 'fixed'
+                    AND NOT advisory.deprecated
+                    AND ($5::text IS NULL OR advisory.labels ->> 'tenant' = $5)"#);
                 let query =
                     Statement::from_sql_and_values(connection.get_database_backend(), &sql, values);
                 Ok(query.to_string())
@@ -222,7 +451,7 @@ impl VulnerabilityService {
         let result = connection.stream(stmt).map_err(Error::from).await?;
         let result = result
             .map_err(Error::from)
-            .and_then(|row| self.to_vuln(row, connection))
+            .and_then(|row| self.to_vuln(row, tenant, connection))
             .try_fold(
                 HashMap::new(),
                 |mut acc: HashMap<String, Vec<VulnerabilityDetails>>,
@@ -236,9 +465,59 @@ impl VulnerabilityService {
         Ok(result)
     }
 
+    /// Analyze purls, looking up purls without local information using the OSV fallback.
+    ///
+    /// Vulnerabilities found by the fallback get ingested, so that the next analysis of the same
+    /// purls will be served from the local data. Unless `inline` is set, the lookup happens in
+    /// the background and only the local information is returned.
+    pub async fn analyze_purls_with_fallback<C>(
+        &self,
+        purls: Vec<&str>,
+        osv_fallback: &OsvFallback,
+        ingestor: &IngestorService,
+        inline: bool,
+        tenant: &Tenant,
+        connection: &C,
+    ) -> Result<HashMap<String, Vec<VulnerabilityDetails>>, Error>
+    where
+        C: ConnectionTrait + StreamTrait,
+    {
+        let mut result = self
+            .analyze_purls(purls.clone(), tenant, connection)
+            .await?;
+
+        let missing =
+            osv_fallback.pending(purls.into_iter().filter(|purl| !result.contains_key(*purl)));
+
+        if missing.is_empty() {
+            return Ok(result);
+        }
+
+        if !inline {
+            let osv_fallback = osv_fallback.clone();
+            let ingestor = ingestor.clone();
+            tokio::spawn(async move { osv_fallback.fetch(&missing, &ingestor).await });
+            return Ok(result);
+        }
+
+        if osv_fallback.fetch(&missing, ingestor).await > 0 {
+            result.extend(
+                self.analyze_purls(
+                    missing.iter().map(String::as_str).collect(),
+                    tenant,
+                    connection,
+                )
+                .await?,
+            );
+        }
+
+        Ok(result)
+    }
+
     async fn to_vuln<C: ConnectionTrait>(
         &self,
         row: QueryResult,
+        tenant: &Tenant,
         connection: &C,
     ) -> Result<(String, VulnerabilityDetails), Error> {
         let requested_purl: String = row.try_get("", "requested_purl")?;
@@ -251,9 +530,15 @@ impl VulnerabilityService {
             withdrawn: row.try_get("", "withdrawn")?,
             cwes: row.try_get("", "cwes")?,
         };
-        let vuln_details =
-            VulnerabilityDetails::from_entity(&vulnerability, Deprecation::Ignore, connection)
-                .await;
+        let vuln_details = VulnerabilityDetails::from_entity(
+            &vulnerability,
+            Deprecation::Ignore,
+            Withdrawal::Ignore,
+            AsOf::default(),
+            tenant,
+            connection,
+        )
+        .await;
         match vuln_details {
             Ok(details) => Ok((requested_purl, details)),
             Err(e) => Err(e),
@@ -261,6 +546,104 @@ impl VulnerabilityService {
     }
 }
 
+/// A condition on the vulnerability table, ruling out vulnerabilities without any advisory known
+/// at the point in time, if one is requested.
+fn known(as_of: AsOf) -> Condition {
+    if as_of.0.is_none() {
+        return Condition::all();
+    }
+
+    Condition::all().add(
+        vulnerability::Column::Id.in_subquery(
+            sea_query::Query::select()
+                .column(advisory_vulnerability::Column::VulnerabilityId)
+                .from(advisory_vulnerability::Entity)
+                .cond_where(as_of.condition(
+                    advisory_vulnerability::Column::AdvisoryId,
+                    Deprecation::Consider,
+                ))
+                .to_owned(),
+        ),
+    )
+}
+
+/// Expand searched identifiers by the vulnerabilities they are a known alias of.
+///
+/// Full-text terms and `id` values matching an alias are extended by the IDs of its
+/// vulnerabilities, so that searching for a GHSA ID finds the corresponding CVE.
+async fn expand_aliases<C: ConnectionTrait>(q: &str, connection: &C) -> Result<String, Error> {
+    // leave queries with escaped characters alone, rather than re-escaping them
+    if q.is_empty() || q.contains('\\') {
+        return Ok(q.to_string());
+    }
+
+    let is_candidate = |part: &str| !part.contains(['=', '~', '<', '>']);
+    let candidates = q
+        .split('&')
+        .flat_map(|part| {
+            part.strip_prefix("id=")
+                .or(is_candidate(part).then_some(part))
+        })
+        .flat_map(|values| values.split('|'))
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+
+    let known = vulnerability_alias::Entity::find()
+        .select_only()
+        .column(vulnerability_alias::Column::Alias)
+        .column(vulnerability_alias::Column::VulnerabilityId)
+        .inner_join(advisory::Entity)
+        .filter(advisory::Column::DeletedAt.is_null())
+        .filter(
+            Expr::expr(Func::lower(Expr::col((
+                vulnerability_alias::Entity,
+                vulnerability_alias::Column::Alias,
+            ))))
+            .is_in(candidates),
+        )
+        .distinct()
+        .into_tuple::<(String, String)>()
+        .all(connection)
+        .await?;
+
+    if known.is_empty() {
+        return Ok(q.to_string());
+    }
+
+    let mut vulnerabilities = HashMap::<String, Vec<String>>::new();
+    for (alias, vulnerability_id) in known {
+        vulnerabilities
+            .entry(alias.to_lowercase())
+            .or_default()
+            .push(vulnerability_id);
+    }
+
+    let expand = |values: &str| {
+        let mut values = values.split('|').map(String::from).collect::<Vec<_>>();
+        for value in values.clone() {
+            for id in vulnerabilities
+                .get(&value.to_lowercase())
+                .into_iter()
+                .flatten()
+            {
+                if !values.contains(id) {
+                    values.push(id.clone());
+                }
+            }
+        }
+        values.join("|")
+    };
+
+    Ok(q.split('&')
+        .map(|part| match part.strip_prefix("id=") {
+            Some(values) => format!("id={}", expand(values)),
+            None if is_candidate(part) => expand(part),
+            None => part.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&"))
+}
+
 #[derive(Debug)]
 struct VulnerabilityCatcher {
     pub vulnerability: vulnerability::Model,
@@ -286,3 +669,65 @@ impl FromQueryResultMultiModel for VulnerabilityCatcher {
 
 #[cfg(test)]
 mod test;
+
+/// The IDs of SBOMs with packages affected by the vulnerability `$1`, considering deprecated
+/// advisories if `$2` is true, and only advisories of the tenant `$3` if it is not null.
+const AFFECTED_SBOMS: &str = r#"
+    -- packages, referencing an affected PURL version
+    SELECT "sbom_package_purl_ref"."sbom_id"
+    FROM "purl_status"
+        JOIN "status" ON "status"."id" = "purl_status"."status_id"
+        JOIN "advisory" ON "advisory"."id" = "purl_status"."advisory_id"
+        JOIN "version_range" ON "version_range"."id" = "purl_status"."version_range_id"
+        JOIN "versioned_purl" ON "versioned_purl"."base_purl_id" = "purl_status"."base_purl_id"
+        JOIN "qualified_purl" ON "qualified_purl"."versioned_purl_id" = "versioned_purl"."id"
+        JOIN "sbom_package_purl_ref" ON "sbom_package_purl_ref"."qualified_purl_id" = "qualified_purl"."id"
+    WHERE "purl_status"."vulnerability_id" = $1
+        AND "status"."slug" = 'affected'
+        AND "advisory"."deleted_at" IS NULL
+        AND ($2 OR NOT "advisory"."deprecated")
+        AND ($3::text IS NULL OR "advisory"."labels" ->> 'tenant' = $3)
+        AND version_matches("versioned_purl"."version", "version_range".*)
+        -- a status limited to a product only applies to SBOMs of that product
+        AND (
+            "purl_status"."context_cpe_id" IS NULL
+            OR EXISTS (
+                SELECT 1 FROM "sbom_package_cpe_ref"
+                WHERE "sbom_package_cpe_ref"."sbom_id" = "sbom_package_purl_ref"."sbom_id"
+                    AND "sbom_package_cpe_ref"."cpe_id" = "purl_status"."context_cpe_id"
+            )
+        )
+    UNION
+    -- SBOMs of an affected product version
+    SELECT "product_version"."sbom_id"
+    FROM "product_status"
+        JOIN "status" ON "status"."id" = "product_status"."status_id"
+        JOIN "advisory" ON "advisory"."id" = "product_status"."advisory_id"
+        JOIN "cpe" ON "cpe"."id" = "product_status"."context_cpe_id"
+        JOIN "product" ON "product"."cpe_key" = "cpe"."product"
+        JOIN "product_version" ON "product_version"."product_id" = "product"."id"
+        JOIN "product_version_range" ON "product_version_range"."id" = "product_status"."product_version_range_id"
+        JOIN "version_range" ON "version_range"."id" = "product_version_range"."version_range_id"
+    WHERE "product_status"."vulnerability_id" = $1
+        AND "status"."slug" = 'affected'
+        AND "advisory"."deleted_at" IS NULL
+        AND ($2 OR NOT "advisory"."deprecated")
+        AND ($3::text IS NULL OR "advisory"."labels" ->> 'tenant' = $3)
+        AND "product_version"."sbom_id" IS NOT NULL
+        AND version_matches("product_version"."version", "version_range".*)
+        -- a status limited to a package only applies to SBOMs containing it
+        AND (
+            "product_status"."package" IS NULL
+            OR EXISTS (
+                SELECT 1 FROM "base_purl"
+                    JOIN "versioned_purl" ON "versioned_purl"."base_purl_id" = "base_purl"."id"
+                    JOIN "qualified_purl" ON "qualified_purl"."versioned_purl_id" = "versioned_purl"."id"
+                    JOIN "sbom_package_purl_ref" ON "sbom_package_purl_ref"."qualified_purl_id" = "qualified_purl"."id"
+                WHERE "sbom_package_purl_ref"."sbom_id" = "product_version"."sbom_id"
+                    AND (
+                        "product_status"."package" = "base_purl"."name"
+                        OR "product_status"."package" LIKE CONCAT("base_purl"."namespace", '/', "base_purl"."name")
+                    )
+            )
+        )
+"#;
@@ -0,0 +1,237 @@
+//! Read-through fallback to the OSV API.
+//!
+//! When enabled, purls which don't have any local vulnerability information get looked up using
+//! the public OSV API (<https://api.osv.dev>). Found vulnerabilities get ingested as OSV advisories,
+//! labeled with [`EXTERNAL_LABEL`], so that subsequent lookups are served from the local data.
+//! Purls without any vulnerabilities are remembered for a while, so that they don't get looked up
+//! on every request.
+
+use crate::Error;
+use moka::sync::Cache;
+use reqwest::Url;
+use serde_json::Value;
+use std::{collections::BTreeMap, time::Duration};
+use trustify_common::reqwest::ClientFactory;
+use trustify_entity::labels::Labels;
+use trustify_module_ingestor::service::{Format, IngestorService};
+
+/// The label key, marking advisories ingested from an external source.
+pub const EXTERNAL_LABEL: &str = "external";
+
+/// The number of purls remembered to have been looked up.
+const CACHE_CAPACITY: u64 = 100_000;
+
+/// How long to remember purls which have been looked up.
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone, Debug, serde::Serialize)]
+struct QueryRequest<'a> {
+    package: Package<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page_token: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+struct Package<'a> {
+    purl: &'a str,
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+struct QueryResponse {
+    #[serde(default)]
+    vulns: Vec<Value>,
+    #[serde(default)]
+    next_page_token: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct OsvFallback {
+    client: reqwest::Client,
+    url: Url,
+    /// Purls which have already been looked up.
+    looked_up: Cache<String, ()>,
+}
+
+impl OsvFallback {
+    pub fn new(mut url: Url) -> anyhow::Result<Self> {
+        // make the base URL a "directory", so that joining doesn't replace its last segment
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+
+        Ok(Self {
+            client: ClientFactory::new().build()?,
+            url,
+            looked_up: Cache::builder()
+                .max_capacity(CACHE_CAPACITY)
+                .time_to_live(CACHE_TTL)
+                .build(),
+        })
+    }
+
+    /// Select the purls which haven't been looked up recently.
+    pub fn pending<'a>(&self, purls: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        purls
+            .into_iter()
+            .filter(|purl| !self.looked_up.contains_key(*purl))
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    fn query_url(&self) -> Result<Url, Error> {
+        self.url
+            .join("v1/query")
+            .map_err(|err| Error::Any(err.into()))
+    }
+
+    /// Query the OSV API for vulnerabilities of a single purl, returning the raw OSV records.
+    async fn query(&self, purl: &str) -> Result<Vec<Value>, Error> {
+        let url = self.query_url()?;
+
+        let mut result = vec![];
+        let mut page_token = None;
+
+        loop {
+            let response: QueryResponse = self
+                .client
+                .post(url.clone())
+                .json(&QueryRequest {
+                    package: Package { purl },
+                    page_token,
+                })
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|err| Error::Any(err.into()))?
+                .json()
+                .await
+                .map_err(|err| Error::Any(err.into()))?;
+
+            result.extend(response.vulns);
+
+            match response.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Look up the provided purls, ingesting all found vulnerabilities.
+    ///
+    /// Failing lookups and ingestions get logged and skipped, purls which failed to be looked up
+    /// will be tried again on the next request. Returns the number of ingested OSV records.
+    pub async fn fetch(&self, purls: &[String], ingestor: &IngestorService) -> usize {
+        // the same vulnerability may be reported for multiple purls, only ingest it once
+
+        let mut vulns = BTreeMap::new();
+        for purl in purls {
+            log::debug!("Looking up purl using the OSV API: {purl}");
+            match self.query(purl).await {
+                Ok(result) => {
+                    for vuln in result {
+                        if let Some(id) = vuln["id"].as_str() {
+                            vulns.insert(id.to_string(), vuln);
+                        }
+                    }
+                    self.looked_up.insert(purl.clone(), ());
+                }
+                Err(err) => log::warn!("Failed to look up purl using the OSV API: {purl}: {err}"),
+            }
+        }
+
+        let mut ingested = 0;
+        for (id, vuln) in &vulns {
+            let labels = Labels::new()
+                .add("source", self.url.as_str())
+                .add(EXTERNAL_LABEL, "osv");
+            let result = match serde_json::to_vec(vuln) {
+                Ok(data) => ingestor
+                    .ingest(&data, Format::OSV, labels, None)
+                    .await
+                    .map(|_| ())
+                    .map_err(|err| err.to_string()),
+                Err(err) => Err(err.to_string()),
+            };
+            match result {
+                Ok(()) => ingested += 1,
+                Err(err) => log::warn!("Failed to ingest OSV record {id}: {err}"),
+            }
+        }
+
+        ingested
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use test_log::test;
+
+    #[test]
+    fn request() -> anyhow::Result<()> {
+        let request = QueryRequest {
+            package: Package {
+                purl: "pkg:pypi/jinja2@2.4.1",
+            },
+            page_token: None,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&request)?,
+            json!({"package": {"purl": "pkg:pypi/jinja2@2.4.1"}})
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn response() -> anyhow::Result<()> {
+        let response: QueryResponse = serde_json::from_value(json!({}))?;
+        assert!(response.vulns.is_empty());
+        assert!(response.next_page_token.is_none());
+
+        let response: QueryResponse = serde_json::from_value(json!({
+            "vulns": [{"id": "GHSA-462w-v97r-4m45", "modified": "2024-01-01T00:00:00Z"}],
+            "next_page_token": "abc"
+        }))?;
+        assert_eq!(response.vulns.len(), 1);
+        assert_eq!(response.next_page_token.as_deref(), Some("abc"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn base_url() -> anyhow::Result<()> {
+        let fallback = OsvFallback::new(Url::parse("https://osv.example.com/api")?)?;
+        assert_eq!(
+            fallback.query_url()?.as_str(),
+            "https://osv.example.com/api/v1/query"
+        );
+
+        let fallback = OsvFallback::new(Url::parse("https://osv.example.com")?)?;
+        assert_eq!(
+            fallback.query_url()?.as_str(),
+            "https://osv.example.com/v1/query"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn pending() -> anyhow::Result<()> {
+        let fallback = OsvFallback::new(Url::parse("https://osv.example.com")?)?;
+        fallback
+            .looked_up
+            .insert("pkg:pypi/jinja2@2.4.1".to_string(), ());
+
+        assert_eq!(
+            fallback.pending(["pkg:pypi/jinja2@2.4.1", "pkg:pypi/jinja2@2.4.2"]),
+            vec!["pkg:pypi/jinja2@2.4.2".to_string()]
+        );
+
+        Ok(())
+    }
+}
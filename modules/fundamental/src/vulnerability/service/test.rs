@@ -2,6 +2,7 @@ use std::str::FromStr;
 
 use crate::purl::service::PurlService;
 use crate::sbom::service::SbomService;
+use crate::tenant::Tenant;
 use crate::vulnerability::service::VulnerabilityService;
 use test_context::test_context;
 use test_log::test;
@@ -23,6 +24,8 @@ async fn all_vulnerabilities(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
             Query::default(),
             Paginated::default(),
             Default::default(),
+            Default::default(),
+            &Tenant::Any,
             &ctx.db,
         )
         .await?;
@@ -50,7 +53,14 @@ async fn statuses(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .await?;
 
     let vuln = service
-        .fetch_vulnerability("CVE-2021-32714", Default::default(), &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2021-32714",
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     assert!(vuln.is_some());
@@ -101,7 +111,14 @@ async fn statuses_too(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     .await?;
 
     let vuln = service
-        .fetch_vulnerability("CVE-2024-29025", Default::default(), &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2024-29025",
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     assert!(vuln.is_some());
@@ -112,6 +129,22 @@ async fn statuses_too(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     assert_eq!(2, vuln.advisories.len());
 
+    // the CSAF advisory states the product as fixed
+
+    let product = vuln
+        .affected_products
+        .iter()
+        .find(|product| product.head.name == "Red Hat build of Quarkus 3.2.12.Final")
+        .expect("must find the product");
+    assert_eq!(product.vendor.as_deref(), Some("Red Hat"));
+    assert_eq!(product.status, "fixed");
+    assert_eq!(product.version, "[3.2.0,4.0.0)");
+    assert!(
+        vuln.affected_products
+            .iter()
+            .all(|product| product.status != "not_affected")
+    );
+
     Ok(())
 }
 
@@ -136,7 +169,7 @@ async fn commons_compress(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     let sat_id = ingest_results[1].id.clone();
 
     let sat_sbom = sbom_service
-        .fetch_sbom_details(sat_id, vec![], &ctx.db)
+        .fetch_sbom_details(sat_id, Default::default(), &Tenant::Any, &ctx.db)
         .await?;
     assert!(sat_sbom.is_some());
 
@@ -160,7 +193,7 @@ async fn commons_compress(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     let quarkus_id = ingest_results[3].id.clone();
 
     let quarkus_sbom = sbom_service
-        .fetch_sbom_details(quarkus_id, vec![], &ctx.db)
+        .fetch_sbom_details(quarkus_id, Default::default(), &Tenant::Any, &ctx.db)
         .await?;
 
     assert!(quarkus_sbom.is_some());
@@ -171,7 +204,14 @@ async fn commons_compress(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     assert!(quarkus_sbom.advisories.is_empty());
 
     let vuln = vuln_service
-        .fetch_vulnerability("CVE-2024-26308", Default::default(), &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2024-26308",
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .unwrap();
 
@@ -231,7 +271,7 @@ async fn product_statuses(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     let quarkus_id = ingest_results[1].id.clone();
 
     let quarkus_sbom = sbom_service
-        .fetch_sbom_details(quarkus_id, vec![], &ctx.db)
+        .fetch_sbom_details(quarkus_id, Default::default(), &Tenant::Any, &ctx.db)
         .await?;
 
     assert!(quarkus_sbom.is_some());
@@ -251,7 +291,14 @@ async fn product_statuses(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     assert_eq!(quarkus_adv.packages[0].purl[0].head.purl, Purl::from_str("pkg:maven/io.quarkus/quarkus-vertx-http@2.13.8.Final-redhat-00004?repository_url=https://maven.repository.redhat.com/ga/&type=jar").unwrap());
 
     let vuln = vuln_service
-        .fetch_vulnerability("CVE-2023-0044", Default::default(), &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2023-0044",
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     assert!(vuln.is_some());
@@ -302,6 +349,7 @@ async fn product_statuses(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .purl_by_purl(
             &Purl::try_from("pkg:maven/io.quarkus/quarkus-vertx-http@2.13.8.Final-redhat-00004?repository_url=https://maven.repository.redhat.com/ga/&type=jar")?,
             Default::default(),
+            &Tenant::Any,
             &ctx.db,
         )
         .await?;
@@ -328,7 +376,14 @@ async fn delete_vulnerability(ctx: &TrustifyContext) -> Result<(), anyhow::Error
     ctx.ingest_documents(["cve/CVE-2024-29025.json"]).await?;
 
     let vuln = service
-        .fetch_vulnerability("CVE-2024-29025", Default::default(), &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2024-29025",
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("Vulnerability not found");
 
@@ -341,7 +396,14 @@ async fn delete_vulnerability(ctx: &TrustifyContext) -> Result<(), anyhow::Error
 
     assert!(
         service
-            .fetch_vulnerability("CVE-2024-29025", Default::default(), &ctx.db)
+            .fetch_vulnerability(
+                "CVE-2024-29025",
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                &Tenant::Any,
+                &ctx.db
+            )
             .await?
             .is_none()
     );
@@ -365,7 +427,14 @@ async fn vulnerability_queries(ctx: &TrustifyContext) -> Result<(), anyhow::Erro
     .await?;
 
     let vulns = service
-        .fetch_vulnerabilities(q(""), Paginated::default(), Default::default(), &ctx.db)
+        .fetch_vulnerabilities(
+            q(""),
+            Paginated::default(),
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
     assert_eq!(5, vulns.items.len());
     let vulns = service
@@ -373,6 +442,8 @@ async fn vulnerability_queries(ctx: &TrustifyContext) -> Result<(), anyhow::Erro
             q("average_score>9"),
             Paginated::default(),
             Default::default(),
+            Default::default(),
+            &Tenant::Any,
             &ctx.db,
         )
         .await?;
@@ -383,6 +454,8 @@ async fn vulnerability_queries(ctx: &TrustifyContext) -> Result<(), anyhow::Erro
             q("average_severity=critical"),
             Paginated::default(),
             Default::default(),
+            Default::default(),
+            &Tenant::Any,
             &ctx.db,
         )
         .await?;
@@ -393,6 +466,8 @@ async fn vulnerability_queries(ctx: &TrustifyContext) -> Result<(), anyhow::Erro
             q("average_severity<high"),
             Paginated::default(),
             Default::default(),
+            Default::default(),
+            &Tenant::Any,
             &ctx.db,
         )
         .await?;
@@ -407,6 +482,8 @@ async fn vulnerability_queries(ctx: &TrustifyContext) -> Result<(), anyhow::Erro
             q("average_severity>=high"),
             Paginated::default(),
             Default::default(),
+            Default::default(),
+            &Tenant::Any,
             &ctx.db,
         )
         .await?;
@@ -417,6 +494,8 @@ async fn vulnerability_queries(ctx: &TrustifyContext) -> Result<(), anyhow::Erro
             q("20862"),
             Paginated::default(),
             Default::default(),
+            Default::default(),
+            &Tenant::Any,
             &ctx.db,
         )
         .await?;
@@ -434,12 +513,14 @@ async fn analyze_purls(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     ctx.ingest_documents(["osv/RUSTSEC-2021-0079.json", "osv/GHSA-2ccf-ffrj-m4qw.json"])
         .await?;
 
-    let result = service.analyze_purls(vec![], &ctx.db).await?;
+    let result = service.analyze_purls(vec![], &Tenant::Any, &ctx.db).await?;
 
     assert!(result.is_empty());
 
     for purl in ["this is not valid", "pkg:npm/missing.version"].iter() {
-        let result = service.analyze_purls(vec![purl], &ctx.db).await;
+        let result = service
+            .analyze_purls(vec![purl], &Tenant::Any, &ctx.db)
+            .await;
         assert!(result.is_err());
     }
 
@@ -456,7 +537,7 @@ async fn analyze_purls(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     let items: Vec<&str> = expected.iter().chain(&not_found).copied().collect();
 
-    let result = service.analyze_purls(items, &ctx.db).await?;
+    let result = service.analyze_purls(items, &Tenant::Any, &ctx.db).await?;
 
     expected.iter().for_each(|&item| {
         assert!(
@@ -75,6 +75,10 @@ async fn get_weakness(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     let response: WeaknessDetails = app.call_and_read_body_json(request).await;
 
     assert_eq!(response.head.id, "CWE-1004");
+    assert_eq!(
+        response.head.name.as_deref(),
+        Some("Sensitive Cookie Without 'HttpOnly' Flag")
+    );
     assert!(response.head.description.is_some());
 
     let desc = response.head.description.unwrap();
@@ -1,14 +1,55 @@
 use crate::Error;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
 use trustify_entity::weakness;
 use utoipa::ToSchema;
 
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct WeaknessHead {
     pub id: String,
+    pub name: Option<String>,
     pub description: Option<String>,
 }
 
+impl WeaknessHead {
+    /// Look up CWE identifiers in the catalog, ordered by their identifier.
+    ///
+    /// Identifiers unknown to the catalog are kept, without a name and description.
+    pub async fn from_ids<C: ConnectionTrait>(
+        ids: impl IntoIterator<Item = impl Into<String>>,
+        tx: &C,
+    ) -> Result<Vec<Self>, Error> {
+        let ids = ids
+            .into_iter()
+            .map(Into::into)
+            .collect::<BTreeSet<String>>();
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut found = weakness::Entity::find()
+            .filter(weakness::Column::Id.is_in(ids.iter().cloned()))
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|entity| (entity.id.clone(), entity))
+            .collect::<HashMap<_, _>>();
+
+        Ok(ids
+            .into_iter()
+            .map(|id| {
+                let entity = found.remove(&id);
+                Self {
+                    id,
+                    name: entity.as_ref().and_then(|entity| entity.name.clone()),
+                    description: entity.and_then(|entity| entity.description),
+                }
+            })
+            .collect())
+    }
+}
+
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct WeaknessSummary {
     #[serde(flatten)]
@@ -29,6 +70,7 @@ impl WeaknessSummary {
         Ok(Self {
             head: WeaknessHead {
                 id: entity.id.clone(),
+                name: entity.name.clone(),
                 description: entity.description.clone(),
             },
         })
@@ -56,6 +98,7 @@ impl WeaknessDetails {
         Ok(Self {
             head: WeaknessHead {
                 id: entity.id.clone(),
+                name: entity.name.clone(),
                 description: entity.description.clone(),
             },
             extended_description: entity.extended_description.clone(),
@@ -27,11 +27,10 @@ impl WeaknessService {
         query: Query,
         paginated: Paginated,
     ) -> Result<PaginatedResults<WeaknessSummary>, Error> {
-        let limiter = weakness::Entity::find().filtering(query)?.limiting(
-            &self.db,
-            paginated.offset,
-            paginated.limit,
-        );
+        let limiter = weakness::Entity::find()
+            .filtering(query)?
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
 
         let total = limiter.total().await?;
         let items = limiter.fetch().await?;
@@ -11,6 +11,7 @@ use trustify_module_fundamental::{
         model::details::purl::{PurlStatus, StatusContext},
         service::PurlService,
     },
+    tenant::Tenant,
     vulnerability::{model::VulnerabilityHead, service::VulnerabilityService},
 };
 use trustify_module_ingestor::common::Deprecation;
@@ -38,7 +39,14 @@ async fn simple(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let vuln = VulnerabilityService::new();
     let v = vuln
-        .fetch_vulnerability("CVE-2023-33201", Deprecation::Consider, &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2023-33201",
+            Deprecation::Consider,
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must exist");
 
@@ -46,7 +54,14 @@ async fn simple(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let vuln = VulnerabilityService::new();
     let v = vuln
-        .fetch_vulnerability("CVE-2023-33201", Deprecation::Ignore, &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2023-33201",
+            Deprecation::Ignore,
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must exist");
 
@@ -78,7 +93,12 @@ async fn delete_check_vulns(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let service = PurlService::new();
     let purls = service
-        .purls(Default::default(), Default::default(), &ctx.db)
+        .purls(
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     // pkg:rpm/redhat/eap7-bouncycastle-util@1.76.0-4.redhat_00001.1.el9eap?arch=noarch
@@ -112,7 +132,7 @@ async fn delete_check_vulns(ctx: &TrustifyContext) -> anyhow::Result<()> {
     // get vuln by purl
 
     let mut purl = service
-        .purl_by_uuid(&purl.head.uuid, Deprecation::Ignore, &ctx.db)
+        .purl_by_uuid(&purl.head.uuid, Deprecation::Ignore, &Tenant::Any, &ctx.db)
         .await?
         .expect("must find something");
 
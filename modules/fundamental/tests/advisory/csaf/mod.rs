@@ -21,7 +21,21 @@ where
     M1: FnOnce(Csaf) -> Csaf,
     M2: FnOnce(Csaf) -> Csaf,
 {
-    let data = document_bytes("csaf/cve-2023-33201.json").await?;
+    twice_from(ctx, "csaf/cve-2023-33201.json", m1, m2).await
+}
+
+/// Ingest a test document twice, mutating it using the provided closure.
+async fn twice_from<M1, M2>(
+    ctx: &TrustifyContext,
+    path: &str,
+    m1: M1,
+    m2: M2,
+) -> anyhow::Result<(IngestResult, IngestResult)>
+where
+    M1: FnOnce(Csaf) -> Csaf,
+    M2: FnOnce(Csaf) -> Csaf,
+{
+    let data = document_bytes(path).await?;
     let csaf: Csaf = serde_json::from_slice(&data)?;
 
     let csaf = m1(csaf);
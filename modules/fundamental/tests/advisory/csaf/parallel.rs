@@ -23,6 +23,7 @@ async fn ingest_10(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             Query::default(),
             Paginated::default(),
             Deprecation::Consider,
+            (),
             &ctx.db,
         )
         .await?;
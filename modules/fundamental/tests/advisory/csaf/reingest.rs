@@ -1,18 +1,22 @@
 #![allow(clippy::expect_used)]
 
-use super::{prepare_ps_state_change, twice};
+use super::{prepare_ps_state_change, twice, twice_from, uptick_tracking};
 use test_context::test_context;
 use test_log::test;
-use trustify_common::purl::Purl;
+use time::Duration;
+use trustify_common::{id::Id, purl::Purl};
 use trustify_cvss::cvss3::severity::Severity;
 use trustify_module_fundamental::{
+    advisory::service::AdvisoryService,
     purl::{
         model::details::purl::{PurlStatus, StatusContext},
         service::PurlService,
     },
+    sbom::service::{DetailsOptions, SbomService},
+    tenant::Tenant,
     vulnerability::{model::VulnerabilityHead, service::VulnerabilityService},
 };
-use trustify_module_ingestor::common::Deprecation;
+use trustify_module_ingestor::common::{AsOf, Deprecation};
 use trustify_test_context::TrustifyContext;
 
 /// Ensure that ingesting the same document twice, leads to the same ID.
@@ -29,7 +33,14 @@ async fn equal(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let vuln = VulnerabilityService::new();
     let v = vuln
-        .fetch_vulnerability("CVE-2023-33201", Default::default(), &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2023-33201",
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must exist");
 
@@ -54,7 +65,14 @@ async fn change_ps_num_advisories(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let vuln = VulnerabilityService::new();
     let v = vuln
-        .fetch_vulnerability("CVE-2023-33201", Deprecation::Ignore, &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2023-33201",
+            Deprecation::Ignore,
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must exist");
 
@@ -64,7 +82,14 @@ async fn change_ps_num_advisories(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let vuln = VulnerabilityService::new();
     let v = vuln
-        .fetch_vulnerability("CVE-2023-33201", Deprecation::Consider, &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2023-33201",
+            Deprecation::Consider,
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must exist");
 
@@ -90,7 +115,12 @@ async fn change_ps_list_vulns(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let service = PurlService::new();
     let purls = service
-        .purls(Default::default(), Default::default(), &ctx.db)
+        .purls(
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     // pkg:rpm/redhat/eap7-bouncycastle@1.76.0-4.redhat_00001.1.el9eap?arch=noarch
@@ -134,7 +164,7 @@ async fn change_ps_list_vulns(ctx: &TrustifyContext) -> anyhow::Result<()> {
     // get vuln by purl
 
     let purl = service
-        .purl_by_uuid(&purl.head.uuid, Deprecation::Ignore, &ctx.db)
+        .purl_by_uuid(&purl.head.uuid, Deprecation::Ignore, &Tenant::Any, &ctx.db)
         .await?
         .expect("must find something");
 
@@ -184,7 +214,12 @@ async fn change_ps_list_vulns_all(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let service = PurlService::new();
     let purls = service
-        .purls(Default::default(), Default::default(), &ctx.db)
+        .purls(
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     // pkg:rpm/redhat/eap7-bouncycastle-util@1.76.0-4.redhat_00001.1.el9eap?arch=noarch
@@ -228,7 +263,12 @@ async fn change_ps_list_vulns_all(ctx: &TrustifyContext) -> anyhow::Result<()> {
     // get vuln by purl
 
     let mut purl = service
-        .purl_by_uuid(&purl.head.uuid, Deprecation::Consider, &ctx.db)
+        .purl_by_uuid(
+            &purl.head.uuid,
+            Deprecation::Consider,
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must find something");
 
@@ -288,3 +328,192 @@ async fn change_ps_list_vulns_all(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Ingest an advisory affecting an SBOM, then an update fixing it. The statuses of the
+/// deprecated version must only show up when considering deprecated advisories.
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn change_ps_sbom(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    const CVE: &str = "CVE-2023-0044";
+
+    let sbom = ctx
+        .ingest_document("quarkus-bom-2.13.8.Final-redhat-00004.json")
+        .await?;
+
+    let (r1, r2) = twice_from(
+        ctx,
+        "csaf/cve-2023-0044.json",
+        |csaf| csaf,
+        |mut csaf| {
+            uptick_tracking(&mut csaf);
+            csaf.document.tracking.current_release_date += chrono::Duration::days(1);
+
+            let ps = csaf
+                .vulnerabilities
+                .as_mut()
+                .and_then(|vulns| vulns.iter_mut().find(|v| v.cve.as_deref() == Some(CVE)))
+                .and_then(|v| v.product_status.as_mut())
+                .expect("test data has product status information");
+
+            // move all from known affected to fixed
+
+            let affected = ps.known_affected.take().unwrap_or_default();
+            ps.fixed.get_or_insert_default().extend(affected);
+
+            csaf
+        },
+    )
+    .await?;
+
+    assert_ne!(r1.id, r2.id);
+
+    // check the SBOM
+
+    let service = SbomService::new(ctx.db.clone());
+    let details = service
+        .fetch_sbom_details(
+            sbom.id.clone(),
+            DetailsOptions {
+                statuses: vec!["affected".into()],
+                ..Default::default()
+            },
+            &Tenant::Any,
+            &ctx.db,
+        )
+        .await?
+        .expect("must exist");
+    assert!(details.advisories.is_empty());
+
+    let details = service
+        .fetch_sbom_details(
+            sbom.id.clone(),
+            DetailsOptions {
+                statuses: vec!["affected".into()],
+                deprecation: Deprecation::Consider,
+                ..Default::default()
+            },
+            &Tenant::Any,
+            &ctx.db,
+        )
+        .await?
+        .expect("must exist");
+    assert_eq!(details.advisories.len(), 1);
+    assert_eq!(r1.id, Id::Uuid(details.advisories[0].head.uuid));
+
+    // check the affected SBOMs
+
+    let vuln = VulnerabilityService::new();
+    let sboms = vuln
+        .fetch_affected_sboms(
+            CVE,
+            Deprecation::Ignore,
+            &Tenant::Any,
+            Default::default(),
+            &ctx.db,
+        )
+        .await?
+        .expect("must exist");
+    assert_eq!(sboms.total, 0);
+
+    let sboms = vuln
+        .fetch_affected_sboms(
+            CVE,
+            Deprecation::Consider,
+            &Tenant::Any,
+            Default::default(),
+            &ctx.db,
+        )
+        .await?
+        .expect("must exist");
+    assert_eq!(sboms.total, 1);
+
+    // done
+
+    Ok(())
+}
+
+/// Ingest the same document twice, and reconstruct the state as of the first ingestion.
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn change_ps_as_of(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    const CVE: &str = "CVE-2023-33201";
+
+    let (r1, r2) = prepare_ps_state_change(ctx).await?;
+
+    assert_ne!(r1.id, r2.id);
+
+    let advisories = AdvisoryService::new(ctx.db.clone());
+    let ingested = advisories
+        .fetch_advisory(r1.id.clone(), &ctx.db)
+        .await?
+        .and_then(|advisory| advisory.source_document)
+        .expect("must exist")
+        .ingested;
+
+    let before = AsOf(Some(ingested - Duration::seconds(1)));
+    let first = AsOf(Some(ingested));
+
+    // only the first version was known, and it wasn't deprecated yet
+
+    assert!(
+        advisories
+            .fetch_advisory_as_of(r1.id.clone(), first, &ctx.db)
+            .await?
+            .is_some()
+    );
+    assert!(
+        advisories
+            .fetch_advisory_as_of(r2.id.clone(), first, &ctx.db)
+            .await?
+            .is_none()
+    );
+
+    let vuln = VulnerabilityService::new();
+    let v = vuln
+        .fetch_vulnerability(
+            CVE,
+            Deprecation::Ignore,
+            Default::default(),
+            first,
+            &Tenant::Any,
+            &ctx.db,
+        )
+        .await?
+        .expect("must exist");
+    assert_eq!(v.advisories.len(), 1);
+    assert_eq!(r1.id, Id::Uuid(v.advisories[0].head.head.uuid));
+
+    // now, only the second version isn't deprecated
+
+    let v = vuln
+        .fetch_vulnerability(
+            CVE,
+            Deprecation::Ignore,
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
+        .await?
+        .expect("must exist");
+    assert_eq!(v.advisories.len(), 1);
+    assert_eq!(r2.id, Id::Uuid(v.advisories[0].head.head.uuid));
+
+    // before, nothing was known
+
+    let v = vuln
+        .fetch_vulnerability(
+            CVE,
+            Deprecation::Ignore,
+            Default::default(),
+            before,
+            &Tenant::Any,
+            &ctx.db,
+        )
+        .await?;
+    assert!(v.is_none());
+
+    // done
+
+    Ok(())
+}
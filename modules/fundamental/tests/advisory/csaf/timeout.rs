@@ -32,6 +32,7 @@ async fn timeout(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             Query::default(),
             Paginated::default(),
             Deprecation::Consider,
+            (),
             &ctx.db,
         )
         .await?;
@@ -1,6 +1,7 @@
 use super::{twice, update_mark_rejected};
 use test_context::test_context;
 use test_log::test;
+use trustify_module_fundamental::tenant::Tenant;
 use trustify_module_fundamental::vulnerability::service::VulnerabilityService;
 use trustify_module_ingestor::common::Deprecation;
 use trustify_test_context::TrustifyContext;
@@ -19,7 +20,14 @@ async fn equal(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let vuln = VulnerabilityService::new();
     let v = vuln
-        .fetch_vulnerability("CVE-2021-32714", Default::default(), &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2021-32714",
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must exist");
 
@@ -44,7 +52,14 @@ async fn withdrawn(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let vuln = VulnerabilityService::new();
     let v = vuln
-        .fetch_vulnerability("CVE-2021-32714", Deprecation::Ignore, &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2021-32714",
+            Deprecation::Ignore,
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must exist");
 
@@ -58,7 +73,14 @@ async fn withdrawn(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let vuln = VulnerabilityService::new();
     let v = vuln
-        .fetch_vulnerability("CVE-2021-32714", Deprecation::Consider, &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2021-32714",
+            Deprecation::Consider,
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must exist");
 
@@ -2,7 +2,8 @@ use super::{twice, update_mark_fixed_again, update_unmark_fixed};
 use test_context::test_context;
 use test_log::test;
 use trustify_module_fundamental::{
-    advisory::service::AdvisoryService, vulnerability::service::VulnerabilityService,
+    advisory::service::AdvisoryService, tenant::Tenant,
+    vulnerability::service::VulnerabilityService,
 };
 use trustify_module_ingestor::common::Deprecation;
 use trustify_test_context::TrustifyContext;
@@ -29,7 +30,14 @@ async fn fixed(ctx: &TrustifyContext) -> anyhow::Result<()> {
     // check info
 
     let v = vuln
-        .fetch_vulnerability("CVE-2020-5238", Deprecation::Ignore, &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2020-5238",
+            Deprecation::Ignore,
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must exist");
 
@@ -38,7 +46,14 @@ async fn fixed(ctx: &TrustifyContext) -> anyhow::Result<()> {
     // check with deprecated, should be the same result
 
     let v = vuln
-        .fetch_vulnerability("CVE-2020-5238", Deprecation::Consider, &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2020-5238",
+            Deprecation::Consider,
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must exist");
 
@@ -26,6 +26,7 @@ async fn ingest_10(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             Query::default(),
             Paginated::default(),
             Deprecation::Consider,
+            (),
             &ctx.db,
         )
         .await?;
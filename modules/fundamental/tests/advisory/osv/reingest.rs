@@ -5,6 +5,7 @@ use trustify_common::purl::Purl;
 use trustify_cvss::cvss3::severity::Severity;
 use trustify_module_fundamental::{
     purl::{model::details::purl::PurlStatus, service::PurlService},
+    tenant::Tenant,
     vulnerability::{model::VulnerabilityHead, service::VulnerabilityService},
 };
 use trustify_module_ingestor::common::Deprecation;
@@ -24,7 +25,14 @@ async fn equal(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let vuln = VulnerabilityService::new();
     let v = vuln
-        .fetch_vulnerability("CVE-2020-5238", Default::default(), &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2020-5238",
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must exist");
 
@@ -49,7 +57,14 @@ async fn withdrawn(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let vuln = VulnerabilityService::new();
     let v = vuln
-        .fetch_vulnerability("CVE-2020-5238", Deprecation::Ignore, &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2020-5238",
+            Deprecation::Ignore,
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must exist");
 
@@ -61,7 +76,14 @@ async fn withdrawn(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let vuln = VulnerabilityService::new();
     let v = vuln
-        .fetch_vulnerability("CVE-2020-5238", Deprecation::Consider, &ctx.db)
+        .fetch_vulnerability(
+            "CVE-2020-5238",
+            Deprecation::Consider,
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must exist");
 
@@ -74,7 +96,12 @@ async fn withdrawn(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     let service = PurlService::new();
     let purls = service
-        .purls(Default::default(), Default::default(), &ctx.db)
+        .purls(
+            Default::default(),
+            Default::default(),
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?;
 
     let purl = purls
@@ -99,7 +126,12 @@ async fn withdrawn(ctx: &TrustifyContext) -> anyhow::Result<()> {
     // get vuln by purl
 
     let mut purl = service
-        .purl_by_uuid(&purl.head.uuid, Deprecation::Consider, &ctx.db)
+        .purl_by_uuid(
+            &purl.head.uuid,
+            Deprecation::Consider,
+            &Tenant::Any,
+            &ctx.db,
+        )
         .await?
         .expect("must find something");
 
@@ -4,35 +4,26 @@ use bytes::BytesMut;
 use futures_util::StreamExt;
 use std::{
     io::{Cursor, Write},
+    path::Path,
     time::Instant,
 };
 use test_context::test_context;
 use test_log::test;
 use tracing::instrument;
 use trustify_common::id::Id;
-use trustify_module_fundamental::sbom::service::SbomService;
+use trustify_module_fundamental::{sbom::service::SbomService, tenant::Tenant};
+use trustify_module_ingestor::{graph::Graph, service::IngestorService};
 use trustify_module_storage::service::StorageBackend;
-use trustify_test_context::TrustifyContext;
+use trustify_test_context::{TrustifyContext, document_bytes};
 use zip::write::FileOptions;
 
-/// Test ingesting a dataset.
-#[test_context(TrustifyContext, skip_teardown)]
-#[test(tokio::test)]
-#[instrument]
-async fn ingest(ctx: TrustifyContext) -> anyhow::Result<()> {
-    let service = SbomService::new(ctx.db.clone());
-    let storage = &ctx.storage;
-
-    let start = Instant::now();
-
-    // create dataset ad-hoc
-
-    let base = ctx.absolute_path("../datasets/ds3")?;
+/// Create a dataset archive from the content of a directory.
+fn create_dataset(base: &Path) -> anyhow::Result<Vec<u8>> {
     let mut data = vec![];
     let mut dataset = zip::write::ZipWriter::new(Cursor::new(&mut data));
-    for entry in walkdir::WalkDir::new(&base) {
+    for entry in walkdir::WalkDir::new(base) {
         let entry = entry?;
-        let Ok(path) = entry.path().strip_prefix(&base) else {
+        let Ok(path) = entry.path().strip_prefix(base) else {
             continue;
         };
 
@@ -45,9 +36,26 @@ async fn ingest(ctx: TrustifyContext) -> anyhow::Result<()> {
     }
     dataset.finish()?;
 
+    Ok(data)
+}
+
+/// Test ingesting a dataset.
+#[test_context(TrustifyContext, skip_teardown)]
+#[test(tokio::test)]
+#[instrument]
+async fn ingest(ctx: TrustifyContext) -> anyhow::Result<()> {
+    let service = SbomService::new(ctx.db.clone());
+    let storage = &ctx.storage;
+
+    let start = Instant::now();
+
+    // create dataset ad-hoc
+
+    let data = create_dataset(&ctx.absolute_path("../datasets/ds3")?)?;
+
     // ingest
 
-    let result = ctx.ingestor.ingest_dataset(&data, (), 0).await?;
+    let result = ctx.ingestor.ingest_dataset(&data, (), 0, None).await?;
 
     let ingest_time = start.elapsed();
 
@@ -89,7 +97,7 @@ async fn ingest(ctx: TrustifyContext) -> anyhow::Result<()> {
     assert_eq!(content.len(), 1174356);
 
     let sbom_details = service
-        .fetch_sbom_details(sbom.id.clone(), vec![], &ctx.db)
+        .fetch_sbom_details(sbom.id.clone(), Default::default(), &Tenant::Any, &ctx.db)
         .await?;
     assert!(sbom_details.is_some());
     let sbom_details = sbom_details.unwrap();
@@ -116,7 +124,7 @@ async fn ingest(ctx: TrustifyContext) -> anyhow::Result<()> {
     let ubi = &result.files["spdx/ubi8-8.8-1067.json.bz2"];
 
     let ubi_details = service
-        .fetch_sbom_details(ubi.id.clone(), vec![], &ctx.db)
+        .fetch_sbom_details(ubi.id.clone(), Default::default(), &Tenant::Any, &ctx.db)
         .await?;
     assert!(ubi_details.is_some());
     let ubi_details = ubi_details.unwrap();
@@ -134,3 +142,75 @@ async fn ingest(ctx: TrustifyContext) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Test ingesting a dataset, processing multiple documents in parallel.
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn ingest_parallel(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let ingestor = IngestorService::new(Graph::new(ctx.db.clone()), ctx.storage.clone(), None)
+        .with_dataset_concurrency(8);
+    let service = SbomService::new(ctx.db.clone());
+
+    let data = create_dataset(&ctx.absolute_path("../datasets/ds3")?)?;
+    let result = ingestor.ingest_dataset(&data, (), 0, None).await?;
+
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    assert_eq!(result.files.len(), 72);
+
+    // must be the same outcome as ingesting one document after the other
+
+    let sbom = &result.files["spdx/quarkus-bom-2.13.8.Final-redhat-00004.json.bz2"];
+    let sbom_details = service
+        .fetch_sbom_details(sbom.id.clone(), Default::default(), &Tenant::Any, &ctx.db)
+        .await?
+        .expect("must be found");
+    assert_eq!(sbom_details.advisories.len(), 22);
+
+    Ok(())
+}
+
+/// Test verifying a dataset against its manifest.
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn ingest_with_manifest(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let mut data = vec![];
+    let mut dataset = zip::write::ZipWriter::new(Cursor::new(&mut data));
+    dataset.start_file("manifest.yaml", FileOptions::<()>::default())?;
+    dataset.write_all(
+        br#"
+counts:
+  spdx: 2
+  csaf: 1
+files:
+  spdx/mtv-2.6.json:
+    sha256: "0000"
+  spdx/broken.json:
+    outcome: failed
+"#,
+    )?;
+    dataset.start_file("spdx/mtv-2.6.json", FileOptions::<()>::default())?;
+    dataset.write_all(&document_bytes("spdx/mtv-2.6.json").await?)?;
+    dataset.start_file("spdx/broken.json", FileOptions::<()>::default())?;
+    dataset.write_all(b"{}")?;
+    dataset.finish()?;
+
+    let result = ctx.ingestor.ingest_dataset(&data, (), 0, None).await?;
+
+    // the manifest itself is not a document
+
+    assert_eq!(result.files.len(), 1);
+    assert!(result.files.contains_key("spdx/mtv-2.6.json"));
+
+    assert_eq!(result.mismatches.len(), 2);
+    assert_eq!(
+        result.mismatches[0],
+        "Expected 1 document(s) of type 'csaf', found 0"
+    );
+    assert!(
+        result.mismatches[1].starts_with("Digest mismatch for spdx/mtv-2.6.json"),
+        "{}",
+        result.mismatches[1]
+    );
+
+    Ok(())
+}
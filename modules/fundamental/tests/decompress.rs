@@ -50,25 +50,25 @@ async fn upload_compressed(ctx: &TrustifyContext) -> anyhow::Result<()> {
         ctx,
         "cyclonedx/decompress/simple.json.bz2",
         None,
-        StatusCode::CREATED,
+        StatusCode::CONFLICT,
     )
     .await?;
     assert(
         ctx,
         "cyclonedx/decompress/simple.json.gz",
         None,
-        StatusCode::CREATED,
+        StatusCode::CONFLICT,
     )
     .await?;
     assert(
         ctx,
         "cyclonedx/decompress/simple.json.xz",
         None,
-        StatusCode::CREATED,
+        StatusCode::CONFLICT,
     )
     .await?;
 
-    // must only be one, as all are the same
+    // must only be one, as all are the same, the others being reported as duplicates
 
     assert_eq!(sbom::Entity::find().count(&ctx.db).await?, 1);
 
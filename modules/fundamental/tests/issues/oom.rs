@@ -3,7 +3,8 @@
 use std::str::FromStr;
 use test_context::test_context;
 use trustify_common::id::Id;
-use trustify_module_fundamental::sbom::service::SbomService;
+use trustify_module_fundamental::sbom::service::{DetailsOptions, SbomService};
+use trustify_module_fundamental::tenant::Tenant;
 use trustify_test_context::{TrustifyContext, flame::setup_global_subscriber};
 
 #[test_context(TrustifyContext, skip_teardown)]
@@ -20,7 +21,17 @@ async fn fetch(ctx: TrustifyContext) -> anyhow::Result<()> {
         Id::from_str("sha256:f293eb898192085804419f9dd40a738f20d67dd81846e88c6720f692ec5f3081")?;
     let statuses: Vec<String> = vec!["affected".to_string()];
 
-    let result = service.fetch_sbom_details(id, statuses, &ctx.db).await?;
+    let result = service
+        .fetch_sbom_details(
+            id,
+            DetailsOptions {
+                statuses,
+                ..Default::default()
+            },
+            &Tenant::Any,
+            &ctx.db,
+        )
+        .await?;
 
     assert!(
         result.is_some(),
@@ -1,8 +1,10 @@
 use actix_http::StatusCode;
-use actix_web::test::TestRequest;
+use actix_web::test::{TestRequest, read_body_json};
 use test_context::test_context;
 use test_log::test;
+use trustify_common::error::ErrorInformation;
 use trustify_module_fundamental::{Config, configure};
+use trustify_module_settings::{model::Settings, service::SettingsService};
 use trustify_test_context::document_bytes_raw;
 
 include!("../src/test/common.rs");
@@ -15,6 +17,7 @@ async fn upload_bomb_sbom(ctx: &TrustifyContext) -> anyhow::Result<()> {
         Config {
             sbom_upload_limit: 1024 * 1024,
             advisory_upload_limit: 1024 * 1024,
+            ..Default::default()
         },
     )
     .await?;
@@ -38,6 +41,7 @@ async fn upload_bomb_advisory(ctx: &TrustifyContext) -> anyhow::Result<()> {
         Config {
             sbom_upload_limit: 1024 * 1024,
             advisory_upload_limit: 1024 * 1024,
+            ..Default::default()
         },
     )
     .await?;
@@ -52,3 +56,57 @@ async fn upload_bomb_advisory(ctx: &TrustifyContext) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn upload_bomb_sbom_ratio(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_with(
+        ctx,
+        Config {
+            compression_ratio_limit: 100,
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/sbom")
+        .set_payload(document_bytes_raw("bomb.bz2").await?)
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    let error: ErrorInformation = read_body_json(response).await;
+    assert_eq!(error.error, "PayloadTooLarge");
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn upload_bomb_sbom_setting(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    // nothing configured, but limited by the runtime settings
+    SettingsService::new(ctx.db.clone())
+        .set(
+            Settings {
+                sbom_upload_limit: Some(1024 * 1024),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .await?;
+
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/sbom")
+        .set_payload(document_bytes_raw("bomb.bz2").await?)
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    Ok(())
+}
@@ -5,7 +5,8 @@ use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 
 use test_context::test_context;
 use test_log::test;
-use trustify_entity::sbom_external_node;
+use trustify_entity::{relationship::Relationship, sbom_external_node};
+use trustify_module_fundamental::sbom::{model::Which, service::SbomService};
 use trustify_test_context::TrustifyContext;
 
 mod rh;
@@ -38,3 +39,71 @@ async fn simple_ext_1(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+/// Ensure that external references get resolved and can be followed
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn simple_ext_resolve(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let result = ctx
+        .ingest_documents(["cyclonedx/simple-ext-a.json", "cyclonedx/simple-ext-b.json"])
+        .await?;
+
+    let sbom_a = result[0].id.try_as_uid().expect("must have a uid");
+    let sbom_b = result[1].id.try_as_uid().expect("must have a uid");
+
+    // the reference was ingested before its target, and must still be resolved
+
+    let results = sbom_external_node::Entity::find()
+        .filter(sbom_external_node::Column::SbomId.eq(sbom_a))
+        .all(&ctx.db)
+        .await?;
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].target_sbom_id, Some(sbom_b));
+
+    // without resolving, the external package is not part of the result
+
+    let service = SbomService::new(ctx.db.clone());
+
+    let packages = service
+        .fetch_related_packages(
+            sbom_a,
+            Default::default(),
+            Default::default(),
+            Which::Left,
+            "a",
+            Some(Relationship::Dependency),
+            false,
+            &ctx.db,
+        )
+        .await?;
+
+    assert_eq!(packages.total, 1);
+    assert_eq!(packages.items[0].package.id, "b");
+
+    // with resolving, the package of the other SBOM gets added
+
+    let packages = service
+        .fetch_related_packages(
+            sbom_a,
+            Default::default(),
+            Default::default(),
+            Which::Left,
+            "a",
+            Some(Relationship::Dependency),
+            true,
+            &ctx.db,
+        )
+        .await?;
+
+    assert_eq!(packages.total, 2);
+    let external = packages
+        .items
+        .iter()
+        .find(|item| item.package.id == "a")
+        .expect("must find external package");
+    assert_eq!(external.relationship, Relationship::Dependency);
+    assert_eq!(external.package.name, "A");
+
+    Ok(())
+}
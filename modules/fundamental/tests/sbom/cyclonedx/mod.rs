@@ -72,6 +72,7 @@ async fn test_parse_cyclonedx(ctx: &TrustifyContext) -> Result<(), anyhow::Error
                     Paginated {
                         offset: 0,
                         limit: 1,
+                        ..Default::default()
                     },
                     &ctx.db,
                 )
@@ -115,6 +116,7 @@ async fn parse_cyclonedx_1dot6(ctx: &TrustifyContext) -> Result<(), anyhow::Erro
                     Paginated {
                         offset: 0,
                         limit: 1,
+                        ..Default::default()
                     },
                     &ctx.db,
                 )
@@ -57,6 +57,7 @@ async fn simple_ref(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             Which::Right,
             SbomNodeReference::Package("pkg:rpm/redhat/openssl@3.0.7-18.el9_2?arch=src" /* this is actually the bom-ref value */),
             Some(Relationship::AncestorOf),
+            false,
             &ctx.db,
         )
         .await?;
@@ -3,6 +3,7 @@ use test_log::test;
 use tracing::instrument;
 use trustify_cvss::cvss3::severity::Severity;
 use trustify_module_fundamental::sbom::{model::details::SbomDetails, service::SbomService};
+use trustify_module_fundamental::tenant::Tenant;
 use trustify_test_context::TrustifyContext;
 
 #[test_context(TrustifyContext)]
@@ -77,12 +78,19 @@ async fn sbom_details_cyclonedx_osv(ctx: &TrustifyContext) -> Result<(), anyhow:
     );
 
     let sbom1 = sbom
-        .fetch_sbom_details(result1.id, vec![], &ctx.db)
+        .fetch_sbom_details(result1.id, Default::default(), &Tenant::Any, &ctx.db)
         .await?
         .expect("SBOM details must be found");
     log::info!("SBOM1: {sbom1:?}");
 
     assert_eq!(11, sbom1.advisories.len());
+    assert!(
+        sbom1
+            .advisories
+            .iter()
+            .map(|advisory| &advisory.head.identifier)
+            .is_sorted()
+    );
     check_advisory(
         &sbom1,
         "GHSA-45c4-8wx5-qw6w",
@@ -152,6 +160,47 @@ async fn sbom_details_cyclonedx_osv(ctx: &TrustifyContext) -> Result<(), anyhow:
     Ok(())
 }
 
+/// Ensure fetching the details doesn't observe changes committed in between its queries.
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+#[instrument]
+async fn sbom_details_snapshot(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let sbom = SbomService::new(ctx.db.clone());
+
+    let result = ctx.ingest_document("cyclonedx/ghsa_test.json").await?;
+    ctx.ingest_document("osv/GHSA-45c4-8wx5-qw6w.json").await?;
+
+    let tx = ctx.db.begin_read().await?;
+    let before = sbom
+        .fetch_sbom_details(result.id.clone(), Default::default(), &Tenant::Any, &tx)
+        .await?
+        .expect("SBOM details must be found");
+    assert_eq!(1, before.advisories.len());
+
+    // ingest another advisory, affecting the SBOM, while the snapshot is being held
+
+    ctx.ingest_document("osv/GHSA-c25x-cm9x-qqgx.json").await?;
+
+    let after = sbom
+        .fetch_sbom_details(result.id.clone(), Default::default(), &Tenant::Any, &tx)
+        .await?
+        .expect("SBOM details must be found");
+    assert_eq!(
+        serde_json::to_value(&before)?,
+        serde_json::to_value(&after)?
+    );
+
+    drop(tx);
+
+    let current = sbom
+        .fetch_sbom_details(result.id, Default::default(), &Tenant::Any, &ctx.db)
+        .await?
+        .expect("SBOM details must be found");
+    assert_eq!(2, current.advisories.len());
+
+    Ok(())
+}
+
 fn check_advisory(
     sbom: &SbomDetails,
     advisory_id: &str,
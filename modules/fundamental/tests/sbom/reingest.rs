@@ -9,6 +9,7 @@ use trustify_common::model::Paginated;
 use trustify_common::purl::Purl;
 use trustify_module_fundamental::sbom::model::SbomExternalPackageReference;
 use trustify_module_fundamental::sbom::{model::details::SbomDetails, service::SbomService};
+use trustify_module_fundamental::tenant::Tenant;
 use trustify_module_ingestor::service::Format;
 use trustify_test_context::{TrustifyContext, document_bytes};
 
@@ -56,13 +57,13 @@ async fn quarkus(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     assert_ne!(result1.id, result2.id);
 
     let mut sbom1 = sbom
-        .fetch_sbom_details(result1.id, vec![], &ctx.db)
+        .fetch_sbom_details(result1.id, Default::default(), &Tenant::Any, &ctx.db)
         .await?
         .expect("v1 must be found");
     log::info!("SBOM1: {sbom1:?}");
 
     let mut sbom2 = sbom
-        .fetch_sbom_details(result2.id, vec![], &ctx.db)
+        .fetch_sbom_details(result2.id, Default::default(), &Tenant::Any, &ctx.db)
         .await?
         .expect("v2 must be found");
     log::info!("SBOM2: {sbom2:?}");
@@ -90,6 +91,7 @@ async fn quarkus(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             SbomExternalPackageReference::Purl(&Purl::from_str(purl).expect("must parse")),
             Paginated::default(),
             Query::default(),
+            &Tenant::Any,
             &ctx.db,
         )
         .await?;
@@ -130,13 +132,13 @@ async fn nhc(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     assert_ne!(result1.id, result2.id);
 
     let mut sbom1 = sbom
-        .fetch_sbom_details(result1.id, vec![], &ctx.db)
+        .fetch_sbom_details(result1.id, Default::default(), &Tenant::Any, &ctx.db)
         .await?
         .expect("v1 must be found");
     log::info!("SBOM1: {sbom1:?}");
 
     let mut sbom2 = sbom
-        .fetch_sbom_details(result2.id, vec![], &ctx.db)
+        .fetch_sbom_details(result2.id, Default::default(), &Tenant::Any, &ctx.db)
         .await?
         .expect("v2 must be found");
     log::info!("SBOM2: {sbom2:?}");
@@ -183,13 +185,13 @@ async fn nhc_same(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     assert_eq!(result1.id, result2.id);
 
     let mut sbom1 = sbom
-        .fetch_sbom_details(result1.id, vec![], &ctx.db)
+        .fetch_sbom_details(result1.id, Default::default(), &Tenant::Any, &ctx.db)
         .await?
         .expect("v1 must be found");
     log::info!("SBOM1: {sbom1:?}");
 
     let mut sbom2 = sbom
-        .fetch_sbom_details(result2.id, vec![], &ctx.db)
+        .fetch_sbom_details(result2.id, Default::default(), &Tenant::Any, &ctx.db)
         .await?
         .expect("v2 must be found");
     log::info!("SBOM2: {sbom2:?}");
@@ -241,29 +243,15 @@ async fn nhc_same_content(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     // now start testing
 
-    // in this case, we get a different ID, as the digest doesn't match
+    // in this case, we get the same ID, as the canonical content is the same, although the
+    // digest doesn't match
 
-    assert_ne!(result1.id, result2.id);
+    assert_eq!(result1.id, result2.id);
+    assert!(result2.duplicate);
 
-    let mut sbom1 = sbom
-        .fetch_sbom_details(result1.id, vec![], &ctx.db)
+    sbom.fetch_sbom_details(result1.id, Default::default(), &Tenant::Any, &ctx.db)
         .await?
         .expect("v1 must be found");
-    log::info!("SBOM1: {sbom1:?}");
-
-    let mut sbom2 = sbom
-        .fetch_sbom_details(result2.id, vec![], &ctx.db)
-        .await?
-        .expect("v2 must be found");
-    log::info!("SBOM2: {sbom2:?}");
-
-    // both sboms have the same name
-
-    assert_sboms(&sbom1, &sbom2);
-
-    // clear the ID as that one will be different
-
-    assert_by_cleaning_id(&mut sbom1, &mut sbom2);
 
     // done
 
@@ -303,13 +291,13 @@ async fn syft_rerun(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     assert_ne!(result1.id, result2.id);
 
     let mut sbom1 = sbom
-        .fetch_sbom_details(result1.id, vec![], &ctx.db)
+        .fetch_sbom_details(result1.id, Default::default(), &Tenant::Any, &ctx.db)
         .await?
         .expect("v1 must be found");
     log::info!("SBOM1: {sbom1:?}");
 
     let mut sbom2 = sbom
-        .fetch_sbom_details(result2.id, vec![], &ctx.db)
+        .fetch_sbom_details(result2.id, Default::default(), &Tenant::Any, &ctx.db)
         .await?
         .expect("v2 must be found");
     log::info!("SBOM2: {sbom2:?}");
@@ -101,6 +101,7 @@ async fn test_parse_spdx(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
                     Which::Left,
                     first,
                     Some(Relationship::Contains),
+                    false,
                     &ctx.db,
                 )
                 .await?
@@ -132,7 +133,7 @@ async fn ingest_spdx_broken_refs(ctx: &TrustifyContext) -> Result<(), anyhow::Er
     );
 
     let result = sbom
-        .fetch_sboms(Default::default(), Default::default(), (), &ctx.db)
+        .fetch_sboms(Default::default(), Default::default(), false, (), &ctx.db)
         .await?;
 
     // there must be no traces, everything must be rolled back
@@ -26,6 +26,7 @@ async fn cpe_purl(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             ComponentReference::Id("SPDXRef-SRPM"),
             (),
             Default::default(),
+            None,
             &ctx.db,
         )
         .await?;
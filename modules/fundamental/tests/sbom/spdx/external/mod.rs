@@ -4,7 +4,9 @@ use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 
 use test_context::test_context;
 use test_log::test;
-use trustify_entity::sbom_external_node;
+use trustify_common::model::Paginated;
+use trustify_entity::{relationship::Relationship, sbom_external_node};
+use trustify_module_fundamental::sbom::{model::Which, service::SbomService};
 use trustify_test_context::TrustifyContext;
 
 /// A simple test for ingesting two SPDX SBOMs with external references
@@ -32,3 +34,66 @@ async fn simple_ext_1(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+/// Ensure that external references get resolved and can be followed
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn simple_ext_resolve(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let result = ctx
+        .ingest_documents(["spdx/simple-ext-a.json", "spdx/simple-ext-b.json"])
+        .await?;
+
+    let sbom_a = result[0].id.try_as_uid().expect("must have a uid");
+    let sbom_b = result[1].id.try_as_uid().expect("must have a uid");
+
+    let results = sbom_external_node::Entity::find()
+        .filter(sbom_external_node::Column::NodeId.eq("DocumentRef-ext-b:SPDXRef-A"))
+        .all(&ctx.db)
+        .await?;
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].target_sbom_id, Some(sbom_b));
+
+    let service = SbomService::new(ctx.db.clone());
+
+    let packages = service
+        .fetch_related_packages(
+            sbom_a,
+            Default::default(),
+            Default::default(),
+            Which::Left,
+            "SPDXRef-A",
+            Some(Relationship::Package),
+            true,
+            &ctx.db,
+        )
+        .await?;
+
+    assert_eq!(packages.total, 1);
+    assert_eq!(packages.items[0].package.id, "SPDXRef-A");
+    assert_eq!(packages.items[0].package.name, "A");
+
+    // resolved packages are part of the pagination
+
+    let packages = service
+        .fetch_related_packages(
+            sbom_a,
+            Default::default(),
+            Paginated {
+                offset: 1,
+                limit: 1,
+                ..Default::default()
+            },
+            Which::Left,
+            "SPDXRef-A",
+            Some(Relationship::Package),
+            true,
+            &ctx.db,
+        )
+        .await?;
+
+    assert_eq!(packages.total, 1);
+    assert!(packages.items.is_empty());
+
+    Ok(())
+}
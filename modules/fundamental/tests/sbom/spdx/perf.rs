@@ -39,6 +39,7 @@ async fn ingest_spdx_medium(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
                     Paginated {
                         offset: 0,
                         limit: 1,
+                        ..Default::default()
                     },
                     &ctx.db,
                 )
@@ -110,6 +111,7 @@ async fn ingest_spdx_medium_cpes(ctx: &TrustifyContext) -> Result<(), anyhow::Er
                     Paginated {
                         offset: 0,
                         limit: 1,
+                        ..Default::default()
                     },
                     &ctx.db,
                 )
@@ -46,6 +46,7 @@ async fn simple(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             Paginated {
                 offset: 0,
                 limit: 10,
+                ..Default::default()
             },
         )
         .await?;
@@ -81,6 +82,7 @@ async fn simple(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             Paginated {
                 offset: 0,
                 limit: 10,
+                ..Default::default()
             },
         )
         .await?;
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use async_graphql::{Context, FieldError, FieldResult, Object};
 use trustify_common::db::Database;
 use trustify_entity::advisory::Model as Advisory;
+use trustify_module_fundamental::tenant::Tenant;
 use trustify_module_ingestor::graph::Graph;
 use uuid::Uuid;
 
@@ -14,10 +15,11 @@ impl AdvisoryQuery {
     async fn get_advisory_by_id(&self, ctx: &Context<'_>, id: Uuid) -> FieldResult<Advisory> {
         let db = ctx.data::<Arc<Database>>()?;
         let graph = ctx.data::<Arc<Graph>>()?;
+        let tenant = ctx.data::<Tenant>()?;
         let advisory = graph.get_advisory_by_id(id, db.as_ref()).await;
 
         match advisory {
-            Ok(Some(advisory)) => Ok(Advisory {
+            Ok(Some(advisory)) if tenant.owns(&advisory.advisory.labels) => Ok(Advisory {
                 id: advisory.advisory.id,
                 identifier: advisory.advisory.identifier,
                 deprecated: advisory.advisory.deprecated,
@@ -31,7 +33,7 @@ impl AdvisoryQuery {
                 source_document_id: advisory.advisory.source_document_id,
                 document_id: advisory.advisory.document_id,
             }),
-            Ok(None) => Err(FieldError::new("Advisory not found")),
+            Ok(_) => Err(FieldError::new("Advisory not found")),
             Err(err) => Err(FieldError::from(err)),
         }
     }
@@ -39,6 +41,7 @@ impl AdvisoryQuery {
     async fn get_advisories(&self, ctx: &Context<'_>) -> FieldResult<Vec<Advisory>> {
         let db = ctx.data::<Arc<Database>>()?;
         let graph = ctx.data::<Arc<Graph>>()?;
+        let tenant = ctx.data::<Tenant>()?;
 
         let advisories = graph
             .get_advisories(Default::default(), db.as_ref())
@@ -47,6 +50,7 @@ impl AdvisoryQuery {
 
         advisories
             .into_iter()
+            .filter(|advisory| tenant.owns(&advisory.advisory.labels))
             .map(|advisory| {
                 Ok(Advisory {
                     id: advisory.advisory.id,
@@ -1,27 +1,45 @@
 use crate::RootQuery;
 use actix_web::{HttpResponse, Result, guard, web};
 use async_graphql::{EmptyMutation, EmptySubscription, Schema, http::GraphiQLSource};
-use async_graphql_actix_web::GraphQL;
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
 use std::sync::Arc;
 use trustify_common::db::Database;
+use trustify_module_fundamental::tenant::{Tenant, TenantIsolation};
 use trustify_module_ingestor::graph::Graph;
 
+type RootSchema = Schema<RootQuery, EmptyMutation, EmptySubscription>;
+
+async fn index(
+    schema: web::Data<RootSchema>,
+    tenant: Tenant,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    // the tenant is per request, the resolvers limit their results to its documents
+    schema
+        .execute(request.into_inner().data(tenant))
+        .await
+        .into()
+}
+
 async fn index_graphiql() -> Result<HttpResponse> {
     Ok(HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
         .body(GraphiQLSource::build().endpoint("/graphql").finish()))
 }
 
-pub fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
-    let schema = Schema::build(RootQuery::default(), EmptyMutation, EmptySubscription)
+pub fn configure(
+    svc: &mut utoipa_actix_web::service_config::ServiceConfig,
+    db: Database,
+    isolation: TenantIsolation,
+) {
+    let schema: RootSchema = Schema::build(RootQuery::default(), EmptyMutation, EmptySubscription)
         .data::<Arc<Graph>>(Arc::new(Graph::new(db.clone())))
         .data::<Arc<Database>>(Arc::new(db.clone()))
         .finish();
 
-    svc.route(
-        "/",
-        web::route().guard(guard::Post()).to(GraphQL::new(schema)),
-    );
+    svc.app_data(web::Data::new(schema))
+        .app_data(web::Data::new(isolation))
+        .route("/", web::route().guard(guard::Post()).to(index));
 }
 
 pub fn configure_graphiql(svc: &mut utoipa_actix_web::service_config::ServiceConfig) {
@@ -2,6 +2,7 @@ use async_graphql::{Context, FieldError, FieldResult, Object};
 use std::sync::Arc;
 use trustify_common::db::Database;
 use trustify_entity::{labels::Labels, sbom::Model as Sbom};
+use trustify_module_fundamental::tenant::Tenant;
 use trustify_module_ingestor::graph::Graph;
 use uuid::Uuid;
 
@@ -13,10 +14,11 @@ impl SbomQuery {
     async fn get_sbom_by_id(&self, ctx: &Context<'_>, id: Uuid) -> FieldResult<Sbom> {
         let db = ctx.data::<Arc<Database>>()?;
         let graph = ctx.data::<Arc<Graph>>()?;
+        let tenant = ctx.data::<Tenant>()?;
         let sbom = graph.locate_sbom_by_id(id, db.as_ref()).await;
 
         match sbom {
-            Ok(Some(sbom_context)) => Ok(Sbom {
+            Ok(Some(sbom_context)) if tenant.owns(&sbom_context.sbom.labels) => Ok(Sbom {
                 sbom_id: sbom_context.sbom.sbom_id,
                 node_id: sbom_context.sbom.node_id,
                 labels: sbom_context.sbom.labels,
@@ -27,7 +29,7 @@ impl SbomQuery {
                 source_document_id: sbom_context.sbom.source_document_id,
                 data_licenses: sbom_context.sbom.data_licenses,
             }),
-            Ok(None) => Err(FieldError::new("SBOM not found")),
+            Ok(_) => Err(FieldError::new("SBOM not found")),
             Err(err) => Err(FieldError::from(err)),
         }
     }
@@ -39,6 +41,7 @@ impl SbomQuery {
     ) -> FieldResult<Vec<Sbom>> {
         let db = ctx.data::<Arc<Database>>()?;
         let graph = ctx.data::<Arc<Graph>>()?;
+        let tenant = ctx.data::<Tenant>()?;
 
         let mut local_labels = Labels::new();
         let labs = labels.split(',');
@@ -51,7 +54,7 @@ impl SbomQuery {
         }
 
         let sboms = graph
-            .locate_sboms_by_labels(local_labels, db.as_ref())
+            .locate_sboms_by_labels(tenant.stamp(local_labels), db.as_ref())
             .await
             .unwrap_or_default();
 
@@ -13,6 +13,7 @@ use trustify_module_fundamental::{
         },
         service::SbomService,
     },
+    tenant::Tenant,
 };
 use uuid::Uuid;
 
@@ -27,10 +28,13 @@ impl SbomStatusQuery {
         id: Uuid,
     ) -> FieldResult<Vec<GraphQLSbomStatus>> {
         let db = ctx.data::<Arc<db::Database>>()?;
+        let tenant = ctx.data::<Tenant>()?;
         let sbom_service = SbomService::new(db.deref().clone());
+        let tx = db.begin_read().await?;
+        tenant.require_sbom(&Id::Uuid(id), &tx).await?;
 
         let sbom_details: Option<SbomDetails> = sbom_service
-            .fetch_sbom_details(Id::Uuid(id), vec![], db.as_ref())
+            .fetch_sbom_details(Id::Uuid(id), Default::default(), tenant, &tx)
             .await
             .unwrap_or_default();
 
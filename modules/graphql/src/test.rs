@@ -6,6 +6,7 @@ use serde_json::json;
 use test_context::test_context;
 use test_log::test;
 use trustify_common::db::Database;
+use trustify_module_fundamental::tenant::Tenant;
 use trustify_module_ingestor::graph::Graph;
 use trustify_test_context::TrustifyContext;
 
@@ -94,6 +95,7 @@ fn make_schema(ctx: &TrustifyContext) -> Schema<RootQuery, EmptyMutation, EmptyS
     Schema::build(RootQuery::default(), EmptyMutation, EmptySubscription)
         .data::<Arc<Graph>>(Arc::new(Graph::new(ctx.db.clone())))
         .data::<Arc<Database>>(Arc::new(ctx.db.clone()))
+        .data(Tenant::Any)
         .finish()
 }
 
@@ -145,6 +147,55 @@ async fn get_advisory_by_id(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn tenant_isolation(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let advisory = ctx.ingest_document("cve/CVE-2024-29025.json").await?;
+    let sbom = ctx
+        .ingest_document("spdx/quarkus-bom-3.2.11.Final-redhat-00001.json")
+        .await?;
+
+    let schema = make_schema(ctx);
+    let tenant = || Tenant::Of("other".into());
+
+    // documents without the tenant label don't belong to the tenant
+
+    let result = schema
+        .execute(
+            Request::new(GET_ADVISORY_BY_ID)
+                .variables(Variables::from_json(json!({ "id": advisory.id })))
+                .data(tenant()),
+        )
+        .await;
+    assert!(!result.errors.is_empty());
+
+    let result = schema
+        .execute(Request::new(GET_ADVISORIES).data(tenant()))
+        .await;
+    let data = result.data.into_json()?;
+    assert_eq!(data["getAdvisories"], json!([]));
+
+    let result = schema
+        .execute(
+            Request::new(GET_SBOM_BY_ID)
+                .variables(Variables::from_json(json!({ "id": sbom.id })))
+                .data(tenant()),
+        )
+        .await;
+    assert!(!result.errors.is_empty());
+
+    let result = schema
+        .execute(
+            Request::new(GET_CVES_BY_SBOM)
+                .variables(Variables::from_json(json!({ "id": sbom.id })))
+                .data(tenant()),
+        )
+        .await;
+    assert!(!result.errors.is_empty());
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(tokio::test)]
 async fn get_organization_by_name(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
@@ -11,6 +11,7 @@ use trustify_auth::authorizer::Require;
 use trustify_auth::{CreateImporter, DeleteImporter, ReadImporter, UpdateImporter};
 use trustify_common::{
     db::Database,
+    error::ErrorInformation,
     model::{Paginated, PaginatedResults, Revisioned},
 };
 
@@ -53,7 +54,7 @@ async fn list(
     ),
     responses(
         (status = 201, description = "Created a new importer configuration"),
-        (status = 409, description = "An importer with that name already exists")
+        (status = 409, description = "An importer with that name already exists", body = ErrorInformation)
     )
 )]
 #[post("/v2/importer/{name}")]
@@ -81,7 +82,7 @@ async fn create(
                 ("etag" = String, description = "Revision ID")
             )
         ),
-        (status = 404, description = "An importer with that name could not be found")
+        (status = 404, description = "An importer with that name could not be found", body = ErrorInformation)
     )
 )]
 #[get("/v2/importer/{name}")]
@@ -111,8 +112,8 @@ async fn read(
     ),
     responses(
         (status = 201, description = "Updated the importer configuration"),
-        (status = 409, description = "An importer with that name does not exist"),
-        (status = 412, description = "The provided if-match header did not match the stored revision"),
+        (status = 409, description = "An importer with that name does not exist", body = ErrorInformation),
+        (status = 412, description = "The provided if-match header did not match the stored revision", body = ErrorInformation),
     )
 )]
 #[put("/v2/importer/{name}")]
@@ -149,8 +150,8 @@ async fn update(
     ),
     responses(
         (status = 201, description = "Created a new importer configuration"),
-        (status = 409, description = "An importer with that name does not exist"),
-        (status = 412, description = "The provided if-match header did not match the stored revision"),
+        (status = 409, description = "An importer with that name does not exist", body = ErrorInformation),
+        (status = 412, description = "The provided if-match header did not match the stored revision", body = ErrorInformation),
     )
 )]
 #[patch("/v2/importer/{name}", guard = "guards::json_merge")]
@@ -188,8 +189,8 @@ async fn patch_json_merge(
     ),
     responses(
         (status = 201, description = "Updated the enable state"),
-        (status = 404, description = "An importer with that name does not exist"),
-        (status = 412, description = "The provided if-match header did not match the stored revision"),
+        (status = 404, description = "An importer with that name does not exist", body = ErrorInformation),
+        (status = 412, description = "The provided if-match header did not match the stored revision", body = ErrorInformation),
     )
 )]
 #[put("/v2/importer/{name}/enabled")]
@@ -226,8 +227,8 @@ async fn set_enabled(
     ),
     responses(
         (status = 201, description = "Updated the state"),
-        (status = 404, description = "An importer with that name does not exist"),
-        (status = 412, description = "The provided if-match header did not match the stored revision"),
+        (status = 404, description = "An importer with that name does not exist", body = ErrorInformation),
+        (status = 412, description = "The provided if-match header did not match the stored revision", body = ErrorInformation),
     )
 )]
 #[post("/v2/importer/{name}/force")]
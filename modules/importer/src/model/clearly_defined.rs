@@ -56,6 +56,40 @@ impl ClearlyDefinedPackageType {
     pub fn matches(&self, other: &str) -> bool {
         self.to_str() == other
     }
+
+    /// The provider ClearlyDefined harvests packages of this type from.
+    pub fn provider(&self) -> &str {
+        match self {
+            ClearlyDefinedPackageType::Composer => "packagist",
+            ClearlyDefinedPackageType::Crate => "cratesio",
+            ClearlyDefinedPackageType::Deb => "debian",
+            ClearlyDefinedPackageType::Gem => "rubygems",
+            ClearlyDefinedPackageType::Git => "github",
+            ClearlyDefinedPackageType::Go => "golang",
+            ClearlyDefinedPackageType::Maven => "mavencentral",
+            ClearlyDefinedPackageType::Npm => "npmjs",
+            ClearlyDefinedPackageType::NuGet => "nuget",
+            ClearlyDefinedPackageType::Pod => "cocoapods",
+            ClearlyDefinedPackageType::Pypi => "pypi",
+        }
+    }
+
+    /// The type of the pURLs of packages of this type.
+    pub fn purl_type(&self) -> &str {
+        match self {
+            ClearlyDefinedPackageType::Composer => "composer",
+            ClearlyDefinedPackageType::Crate => "cargo",
+            ClearlyDefinedPackageType::Deb => "deb",
+            ClearlyDefinedPackageType::Gem => "gem",
+            ClearlyDefinedPackageType::Git => "github",
+            ClearlyDefinedPackageType::Go => "golang",
+            ClearlyDefinedPackageType::Maven => "maven",
+            ClearlyDefinedPackageType::Npm => "npm",
+            ClearlyDefinedPackageType::NuGet => "nuget",
+            ClearlyDefinedPackageType::Pod => "cocoapods",
+            ClearlyDefinedPackageType::Pypi => "pypi",
+        }
+    }
 }
 
 #[derive(
@@ -0,0 +1,49 @@
+use super::*;
+use std::collections::HashSet;
+
+/// Harvests the definitions of packages known to the system from the ClearlyDefined API.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    ToSchema,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearlyDefinedApiImporter {
+    #[serde(flatten)]
+    pub common: CommonImporter,
+
+    /// The base URL of the ClearlyDefined API
+    #[serde(default = "default::source")]
+    pub source: String,
+
+    /// The package types to harvest, all if empty
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub types: HashSet<ClearlyDefinedPackageType>,
+}
+
+pub const DEFAULT_SOURCE_CLEARLY_DEFINED_API: &str = "https://api.clearlydefined.io";
+
+mod default {
+    pub fn source() -> String {
+        super::DEFAULT_SOURCE_CLEARLY_DEFINED_API.into()
+    }
+}
+
+impl Deref for ClearlyDefinedApiImporter {
+    type Target = CommonImporter;
+
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
+
+impl DerefMut for ClearlyDefinedApiImporter {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.common
+    }
+}
@@ -1,19 +1,23 @@
 mod clearly_defined_curation;
 
 mod clearly_defined;
+mod clearly_defined_api;
 mod csaf;
 mod cve;
 mod cwe;
 mod osv;
+mod registry;
 mod sbom;
 
 use crate::runner::{common::heartbeat::Heart, report::Report};
 pub use clearly_defined::*;
+pub use clearly_defined_api::*;
 pub use clearly_defined_curation::*;
 pub use csaf::*;
 pub use cve::*;
 pub use cwe::*;
 pub use osv::*;
+pub use registry::*;
 pub use sbom::*;
 
 use num_traits::cast::ToPrimitive;
@@ -179,7 +183,9 @@ pub enum ImporterConfiguration {
     Cve(CveImporter),
     ClearlyDefined(ClearlyDefinedImporter),
     ClearlyDefinedCuration(ClearlyDefinedCurationImporter),
+    ClearlyDefinedApi(ClearlyDefinedApiImporter),
     Cwe(CweImporter),
+    Registry(RegistryImporter),
 }
 
 impl Deref for ImporterConfiguration {
@@ -193,7 +199,9 @@ impl Deref for ImporterConfiguration {
             Self::Cve(importer) => &importer.common,
             Self::ClearlyDefined(importer) => &importer.common,
             Self::ClearlyDefinedCuration(importer) => &importer.common,
+            Self::ClearlyDefinedApi(importer) => &importer.common,
             Self::Cwe(importer) => &importer.common,
+            Self::Registry(importer) => &importer.common,
         }
     }
 }
@@ -207,7 +215,9 @@ impl DerefMut for ImporterConfiguration {
             Self::Cve(importer) => &mut importer.common,
             Self::ClearlyDefined(importer) => &mut importer.common,
             Self::ClearlyDefinedCuration(importer) => &mut importer.common,
+            Self::ClearlyDefinedApi(importer) => &mut importer.common,
             Self::Cwe(importer) => &mut importer.common,
+            Self::Registry(importer) => &mut importer.common,
         }
     }
 }
@@ -0,0 +1,60 @@
+use super::*;
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    ToSchema,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryImporter {
+    #[serde(flatten)]
+    pub common: CommonImporter,
+
+    /// The base URL of the registry, like `https://nexus.example.com`
+    pub source: String,
+
+    /// The kind of API the registry provides
+    pub kind: RegistryKind,
+
+    /// The repositories of the registry to sync the inventory of
+    pub repositories: Vec<String>,
+}
+
+/// The API of an internal package registry.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    ToSchema,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum RegistryKind {
+    /// JFrog Artifactory, listing Maven layout repositories using AQL
+    Artifactory,
+    /// Sonatype Nexus Repository, using its components API
+    Nexus,
+}
+
+impl Deref for RegistryImporter {
+    type Target = CommonImporter;
+
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
+
+impl DerefMut for RegistryImporter {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.common
+    }
+}
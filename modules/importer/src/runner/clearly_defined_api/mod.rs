@@ -0,0 +1,70 @@
+mod walker;
+
+use crate::model::{ClearlyDefinedApiImporter, ClearlyDefinedPackageType};
+use crate::runner::{
+    RunOutput,
+    clearly_defined_api::walker::ClearlyDefinedApiWalker,
+    context::RunContext,
+    report::{ReportBuilder, ScannerError},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::instrument;
+use trustify_module_ingestor::{graph::Graph, service::IngestorService};
+
+impl super::ImportRunner {
+    #[instrument(skip(self), ret)]
+    pub async fn run_once_clearly_defined_api(
+        &self,
+        context: impl RunContext + 'static,
+        clearly_defined: ClearlyDefinedApiImporter,
+    ) -> Result<RunOutput, ScannerError> {
+        let ingestor = IngestorService::new(
+            Graph::new(self.db.clone()),
+            self.storage.clone(),
+            self.analysis.clone(),
+        )
+        .with_listeners(self.listeners.clone());
+
+        let report = Arc::new(Mutex::new(ReportBuilder::new()));
+
+        // the ingested definitions are the state, so no continuation
+
+        let types = match clearly_defined.types.is_empty() {
+            true => ClearlyDefinedPackageType::all(),
+            false => clearly_defined.types.clone(),
+        };
+
+        let walker = ClearlyDefinedApiWalker::new(
+            clearly_defined.source.clone(),
+            types,
+            clearly_defined.labels.clone(),
+            self.db.clone(),
+            ingestor,
+            report.clone(),
+            context,
+        );
+
+        match walker.run().await {
+            Ok(()) => {
+                // extract the report
+                let report = match Arc::try_unwrap(report) {
+                    Ok(report) => report.into_inner(),
+                    Err(report) => report.lock().await.clone(),
+                }
+                .build();
+                Ok(RunOutput {
+                    report,
+                    continuation: None,
+                })
+            }
+            Err(err) => Err(ScannerError::Normal {
+                err: err.into(),
+                output: RunOutput {
+                    report: report.lock().await.clone().build(),
+                    continuation: None,
+                },
+            }),
+        }
+    }
+}
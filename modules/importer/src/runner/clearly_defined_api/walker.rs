@@ -0,0 +1,300 @@
+use crate::{
+    model::ClearlyDefinedPackageType,
+    runner::{
+        common::Error,
+        context::RunContext,
+        progress::{Progress, ProgressInstance},
+        report::{Phase, ReportBuilder},
+    },
+};
+use sea_orm::{
+    ColumnTrait, EntityTrait, JoinType, QueryFilter, QuerySelect, RelationTrait, prelude::Expr,
+};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use tokio::sync::Mutex;
+use tracing::instrument;
+use trustify_common::db::Database;
+use trustify_entity::{base_purl, labels::Labels, sbom, versioned_purl};
+use trustify_module_ingestor::service::{Format, IngestorService};
+
+/// The number of coordinates requested from the API at once.
+const BATCH_SIZE: usize = 100;
+
+/// Harvests the definitions of the packages of the database from the ClearlyDefined API.
+///
+/// Definitions which were already ingested are skipped, as well as definitions without a
+/// declared license, which ClearlyDefined might not have harvested yet. So each run only
+/// requests the coordinates which are still missing.
+pub struct ClearlyDefinedApiWalker<C: RunContext> {
+    source: String,
+    types: HashSet<ClearlyDefinedPackageType>,
+    labels: Labels,
+    db: Database,
+    ingestor: IngestorService,
+    report: Arc<Mutex<ReportBuilder>>,
+    context: C,
+    client: reqwest::Client,
+}
+
+impl<C: RunContext> ClearlyDefinedApiWalker<C> {
+    pub fn new(
+        source: impl Into<String>,
+        types: HashSet<ClearlyDefinedPackageType>,
+        labels: Labels,
+        db: Database,
+        ingestor: IngestorService,
+        report: Arc<Mutex<ReportBuilder>>,
+        context: C,
+    ) -> Self {
+        Self {
+            source: source.into().trim_end_matches('/').to_string(),
+            types,
+            labels,
+            db,
+            ingestor,
+            report,
+            context,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Run the walker
+    #[instrument(skip(self), ret)]
+    pub async fn run(self) -> Result<(), Error> {
+        let coordinates = self.coordinates().await?;
+        log::info!("Found {} coordinates", coordinates.len());
+
+        let mut progress = self
+            .context
+            .progress(format!(
+                "Harvest ClearlyDefined definitions: {}",
+                self.source
+            ))
+            .start(coordinates.len());
+
+        for chunk in coordinates.chunks(BATCH_SIZE) {
+            self.context.check_canceled(|| Error::Canceled).await?;
+
+            let batch = self.pending(chunk).await?;
+
+            if !batch.is_empty() {
+                match self.fetch(&batch).await {
+                    Ok(mut definitions) => {
+                        for coordinate in &batch {
+                            if let Some(definition) = definitions.remove(coordinate) {
+                                self.ingest(coordinate, definition).await;
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        let mut report = self.report.lock().await;
+                        for coordinate in &batch {
+                            report.add_error(Phase::Retrieval, coordinate, err.to_string());
+                        }
+                    }
+                }
+            }
+
+            progress.increment(chunk.len()).await;
+        }
+
+        progress.finish().await;
+
+        Ok(())
+    }
+
+    /// Collect the coordinates of all versioned pURLs of the selected types.
+    async fn coordinates(&self) -> Result<Vec<String>, Error> {
+        let mut result = vec![];
+
+        for ty in &self.types {
+            let purls: Vec<(Option<String>, String, String)> = versioned_purl::Entity::find()
+                .select_only()
+                .column(base_purl::Column::Namespace)
+                .column(base_purl::Column::Name)
+                .column(versioned_purl::Column::Version)
+                .join(JoinType::Join, versioned_purl::Relation::BasePurl.def())
+                .filter(base_purl::Column::Type.eq(ty.purl_type()))
+                .into_tuple()
+                .all(&self.db)
+                .await
+                .map_err(|err| Error::Processing(err.into()))?;
+
+            result.extend(purls.into_iter().map(|(namespace, name, version)| {
+                coordinate(ty, namespace.as_deref(), &name, &version)
+            }));
+        }
+
+        result.sort_unstable();
+        result.dedup();
+
+        Ok(result)
+    }
+
+    /// Drop the coordinates for which a definition was already ingested.
+    async fn pending(&self, coordinates: &[String]) -> Result<Vec<String>, Error> {
+        let existing: HashSet<String> = sbom::Entity::find()
+            .select_only()
+            .column(sbom::Column::DocumentId)
+            .filter(sbom::Column::DocumentId.is_in(coordinates.iter().cloned()))
+            .filter(Expr::cust("'ClearlyDefined Definitions' = ANY(authors)"))
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .map_err(|err| Error::Processing(err.into()))?
+            .into_iter()
+            .collect();
+
+        Ok(coordinates
+            .iter()
+            .filter(|coordinate| !existing.contains(*coordinate))
+            .cloned()
+            .collect())
+    }
+
+    /// Fetch the definitions of a batch of coordinates.
+    async fn fetch(&self, coordinates: &[String]) -> Result<HashMap<String, Value>, Error> {
+        Ok(self
+            .client
+            .post(format!("{}/definitions", self.source))
+            .json(coordinates)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    /// Ingest a single definition, recording failures in the report.
+    async fn ingest(&self, coordinate: &str, mut definition: Value) {
+        // unknown or not yet harvested packages don't have a license, try again next time
+        if definition
+            .pointer("/licensed/declared")
+            .and_then(Value::as_str)
+            .is_none()
+        {
+            log::debug!("No declared license for {coordinate}");
+            return;
+        }
+
+        // the loader uses the ID of the definition as document ID, which the API might not return
+        if let Some(definition) = definition.as_object_mut() {
+            definition.entry("_id").or_insert_with(|| coordinate.into());
+        }
+
+        let result = match serde_json::to_vec(&definition) {
+            Ok(data) => self
+                .ingestor
+                .ingest(
+                    &data,
+                    Format::ClearlyDefined,
+                    self.labels.clone(),
+                    Some("ClearlyDefined".to_string()),
+                )
+                .await
+                .map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+
+        let mut report = self.report.lock().await;
+        if let Err(err) = result {
+            report.add_error(Phase::Upload, coordinate, err);
+        }
+        report.tick();
+    }
+}
+
+/// Build the ClearlyDefined coordinates of a package, like `npm/npmjs/@scope/name/1.0.0`.
+///
+/// Slashes inside a segment, like in the namespace of Go modules, are encoded.
+fn coordinate(
+    ty: &ClearlyDefinedPackageType,
+    namespace: Option<&str>,
+    name: &str,
+    revision: &str,
+) -> String {
+    let encode = |segment: &str| segment.replace('/', "%2f");
+
+    format!(
+        "{}/{}/{}/{}/{}",
+        ty.to_str(),
+        ty.provider(),
+        namespace.map(encode).unwrap_or_else(|| "-".to_string()),
+        encode(name),
+        encode(revision)
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_test_context::TrustifyContext;
+
+    #[derive(Debug)]
+    struct Context;
+
+    impl RunContext for Context {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        async fn is_canceled(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn coordinates() {
+        use ClearlyDefinedPackageType::*;
+
+        assert_eq!(
+            coordinate(&NuGet, None, "microsoft.aspnet.mvc", "4.0.40804"),
+            "nuget/nuget/-/microsoft.aspnet.mvc/4.0.40804"
+        );
+        assert_eq!(
+            coordinate(&Npm, Some("@tacobell"), "taco", "1.2.3"),
+            "npm/npmjs/@tacobell/taco/1.2.3"
+        );
+        assert_eq!(
+            coordinate(&Go, Some("github.com/gorilla"), "mux", "v1.8.0"),
+            "go/golang/github.com%2fgorilla/mux/v1.8.0"
+        );
+    }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn pending_coordinates(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        ctx.ingest_document_as(
+            "clearly-defined/aspnet.mvc-4.0.40804.json",
+            Format::ClearlyDefined,
+        )
+        .await?;
+
+        let walker = ClearlyDefinedApiWalker::new(
+            "https://api.clearlydefined.io",
+            HashSet::from([ClearlyDefinedPackageType::NuGet]),
+            Labels::default(),
+            ctx.db.clone(),
+            ctx.ingestor.clone(),
+            Arc::new(Mutex::new(ReportBuilder::new())),
+            Context,
+        );
+
+        // already ingested definitions are skipped
+        let pending = walker
+            .pending(&[
+                "nuget/nuget/-/microsoft.aspnet.mvc/4.0.40804".to_string(),
+                "nuget/nuget/-/newtonsoft.json/13.0.3".to_string(),
+            ])
+            .await?;
+        assert_eq!(pending, vec!["nuget/nuget/-/newtonsoft.json/13.0.3"]);
+
+        Ok(())
+    }
+}
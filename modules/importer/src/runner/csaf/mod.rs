@@ -67,7 +67,8 @@ impl super::ImportRunner {
             Graph::new(self.db.clone()),
             self.storage.clone(),
             self.analysis.clone(),
-        );
+        )
+        .with_listeners(self.listeners.clone());
 
         let storage = storage::StorageVisitor {
             context,
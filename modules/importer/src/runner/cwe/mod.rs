@@ -24,7 +24,8 @@ impl super::ImportRunner {
             Graph::new(self.db.clone()),
             self.storage.clone(),
             self.analysis.clone(),
-        );
+        )
+        .with_listeners(self.listeners.clone());
 
         let report = Arc::new(Mutex::new(ReportBuilder::new()));
         let continuation = serde_json::from_value(continuation).unwrap_or_default();
@@ -1,6 +1,7 @@
 pub mod clearly_defined_curation;
 
 pub mod clearly_defined;
+pub mod clearly_defined_api;
 pub mod common;
 pub mod context;
 pub mod csaf;
@@ -8,6 +9,7 @@ pub mod cve;
 pub mod cwe;
 pub mod osv;
 pub mod progress;
+pub mod registry;
 pub mod report;
 pub mod sbom;
 
@@ -21,6 +23,7 @@ use time::OffsetDateTime;
 use tracing::instrument;
 use trustify_common::db::Database;
 use trustify_module_analysis::service::AnalysisService;
+use trustify_module_ingestor::service::listener::IngestListeners;
 use trustify_module_storage::service::dispatch::DispatchBackend;
 
 #[derive(Clone)]
@@ -29,6 +32,7 @@ pub struct ImportRunner {
     pub storage: DispatchBackend,
     pub working_dir: Option<PathBuf>,
     pub analysis: Option<AnalysisService>,
+    pub listeners: IngestListeners,
 }
 
 impl ImportRunner {
@@ -59,9 +63,16 @@ impl ImportRunner {
                 self.run_once_clearly_defined_curation(context, clearly_defined, continuation)
                     .await
             }
+            ImporterConfiguration::ClearlyDefinedApi(clearly_defined) => {
+                self.run_once_clearly_defined_api(context, clearly_defined)
+                    .await
+            }
             ImporterConfiguration::Cwe(cwe) => {
                 self.run_once_cwe_catalog(context, cwe, continuation).await
             }
+            ImporterConfiguration::Registry(registry) => {
+                self.run_once_registry(context, registry).await
+            }
         }
     }
 
@@ -0,0 +1,56 @@
+mod walker;
+
+use crate::model::RegistryImporter;
+use crate::runner::{
+    RunOutput,
+    context::RunContext,
+    registry::walker::RegistryWalker,
+    report::{ReportBuilder, ScannerError},
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::instrument;
+
+impl super::ImportRunner {
+    #[instrument(skip(self), ret)]
+    pub async fn run_once_registry(
+        &self,
+        context: impl RunContext + 'static,
+        registry: RegistryImporter,
+    ) -> Result<RunOutput, ScannerError> {
+        let report = Arc::new(Mutex::new(ReportBuilder::new()));
+
+        // always syncing the full inventory, so no continuation
+
+        let walker = RegistryWalker::new(
+            registry.source.clone(),
+            registry.kind,
+            registry.repositories.clone(),
+            self.db.clone(),
+            report.clone(),
+            context,
+        );
+
+        match walker.run().await {
+            Ok(()) => {
+                // extract the report
+                let report = match Arc::try_unwrap(report) {
+                    Ok(report) => report.into_inner(),
+                    Err(report) => report.lock().await.clone(),
+                }
+                .build();
+                Ok(RunOutput {
+                    report,
+                    continuation: None,
+                })
+            }
+            Err(err) => Err(ScannerError::Normal {
+                err: err.into(),
+                output: RunOutput {
+                    report: report.lock().await.clone().build(),
+                    continuation: None,
+                },
+            }),
+        }
+    }
+}
@@ -0,0 +1,378 @@
+use crate::{
+    model::RegistryKind,
+    runner::{
+        common::Error,
+        context::RunContext,
+        report::{Phase, ReportBuilder},
+    },
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, TransactionTrait};
+use std::{
+    collections::{BTreeSet, HashSet},
+    sync::Arc,
+};
+use time::OffsetDateTime;
+use tokio::sync::Mutex;
+use tracing::instrument;
+use trustify_common::{
+    db::{Database, batch::insert_rows},
+    purl::Purl,
+};
+use trustify_entity::internal_package;
+use trustify_module_ingestor::graph::purl::creator::PurlCreator;
+use uuid::Uuid;
+
+const UPSERT_INTERNAL_PACKAGES: &str = r#"
+INSERT INTO internal_package (importer, repository, qualified_purl_id, last_seen)
+SELECT * FROM jsonb_to_recordset($1) AS t(importer text, repository text, qualified_purl_id uuid, last_seen timestamptz)
+ON CONFLICT (importer, repository, qualified_purl_id) DO UPDATE SET last_seen = EXCLUDED.last_seen
+"#;
+
+#[derive(serde::Serialize)]
+struct InternalPackageRow<'a> {
+    importer: &'a str,
+    repository: &'a str,
+    qualified_purl_id: Uuid,
+    #[serde(with = "time::serde::rfc3339")]
+    last_seen: OffsetDateTime,
+}
+
+/// Syncs the inventory of the repositories of a registry into the `internal_package` table.
+pub struct RegistryWalker<C: RunContext> {
+    source: String,
+    kind: RegistryKind,
+    repositories: Vec<String>,
+    db: Database,
+    report: Arc<Mutex<ReportBuilder>>,
+    context: C,
+    client: reqwest::Client,
+}
+
+impl<C: RunContext> RegistryWalker<C> {
+    pub fn new(
+        source: impl Into<String>,
+        kind: RegistryKind,
+        repositories: Vec<String>,
+        db: Database,
+        report: Arc<Mutex<ReportBuilder>>,
+        context: C,
+    ) -> Self {
+        Self {
+            source: source.into().trim_end_matches('/').to_string(),
+            kind,
+            repositories,
+            db,
+            report,
+            context,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Run the walker
+    #[instrument(skip(self), ret)]
+    pub async fn run(self) -> Result<(), Error> {
+        let seen = OffsetDateTime::now_utc();
+        let mut failed = BTreeSet::new();
+
+        for repository in &self.repositories {
+            self.context.check_canceled(|| Error::Canceled).await?;
+
+            let purls = match self.kind {
+                RegistryKind::Artifactory => self.list_artifactory(repository).await,
+                RegistryKind::Nexus => self.list_nexus(repository).await,
+            };
+
+            match purls {
+                Ok(purls) => {
+                    log::info!("Repository {repository}: {} packages", purls.len());
+                    self.store(repository, purls, seen)
+                        .await
+                        .map_err(Error::Processing)?;
+                }
+                Err(err) => {
+                    // keep the previous inventory of the repository
+                    self.report.lock().await.add_error(
+                        Phase::Retrieval,
+                        repository,
+                        err.to_string(),
+                    );
+                    failed.insert(repository.clone());
+                }
+            }
+
+            self.report.lock().await.tick();
+        }
+
+        // drop packages which are gone, including those of repositories no longer being synced
+
+        internal_package::Entity::delete_many()
+            .filter(internal_package::Column::Importer.eq(self.context.name()))
+            .filter(internal_package::Column::LastSeen.lt(seen))
+            .filter(internal_package::Column::Repository.is_not_in(failed))
+            .exec(&self.db)
+            .await
+            .map_err(|err| Error::Processing(err.into()))?;
+
+        Ok(())
+    }
+
+    /// Create the PURLs of a repository, and record them as being seen.
+    async fn store(
+        &self,
+        repository: &str,
+        purls: HashSet<Purl>,
+        seen: OffsetDateTime,
+    ) -> anyhow::Result<()> {
+        let importer = self.context.name();
+        let rows = purls
+            .iter()
+            .map(|purl| InternalPackageRow {
+                importer,
+                repository,
+                qualified_purl_id: purl.qualifier_uuid(),
+                last_seen: seen,
+            })
+            .collect::<Vec<_>>();
+
+        let mut creator = PurlCreator::new();
+        for purl in &purls {
+            creator.add(purl.clone());
+        }
+
+        let tx = self.db.begin().await?;
+        creator.create(&tx).await?;
+        insert_rows(&tx, UPSERT_INTERNAL_PACKAGES, rows).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// List the components of a Nexus repository, following the continuation tokens.
+    async fn list_nexus(&self, repository: &str) -> Result<HashSet<Purl>, Error> {
+        let url = format!("{}/service/rest/v1/components", self.source);
+        let mut result = HashSet::new();
+        let mut continuation: Option<String> = None;
+
+        loop {
+            self.context.check_canceled(|| Error::Canceled).await?;
+
+            let mut query = vec![("repository", repository.to_string())];
+            if let Some(continuation) = continuation.take() {
+                query.push(("continuationToken", continuation));
+            }
+
+            let body = self
+                .client
+                .get(&url)
+                .query(&query)
+                .send()
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await?;
+            let page: NexusPage = serde_json::from_slice(&body)?;
+
+            result.extend(page.items.into_iter().filter_map(NexusComponent::into_purl));
+
+            match page.continuation_token {
+                Some(token) => continuation = Some(token),
+                None => break,
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// List the POM files of a Maven layout Artifactory repository.
+    async fn list_artifactory(&self, repository: &str) -> Result<HashSet<Purl>, Error> {
+        let query = format!(
+            r#"items.find({{"repo":{},"name":{{"$match":"*.pom"}}}}).include("repo","path","name")"#,
+            serde_json::Value::from(repository)
+        );
+
+        let body = self
+            .client
+            .post(format!("{}/api/search/aql", self.source))
+            .header(reqwest::header::CONTENT_TYPE, "text/plain")
+            .body(query)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let result: AqlResult = serde_json::from_slice(&body)?;
+
+        Ok(result
+            .results
+            .iter()
+            .filter_map(|item| maven_purl(&item.path, &item.name))
+            .collect())
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NexusPage {
+    #[serde(default)]
+    items: Vec<NexusComponent>,
+    continuation_token: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct NexusComponent {
+    format: String,
+    group: Option<String>,
+    name: String,
+    version: Option<String>,
+}
+
+impl NexusComponent {
+    /// Map a component to its PURL, if its format is known and it has a version.
+    fn into_purl(self) -> Option<Purl> {
+        let version = self.version.filter(|version| !version.is_empty())?;
+        let group = self.group.filter(|group| !group.is_empty());
+
+        let (ty, namespace, name) = match self.format.as_str() {
+            "maven2" => ("maven", group, self.name),
+            "npm" => (
+                "npm",
+                group.map(|scope| match scope.starts_with('@') {
+                    true => scope,
+                    false => format!("@{scope}"),
+                }),
+                self.name,
+            ),
+            "pypi" => ("pypi", None, self.name),
+            "nuget" => ("nuget", None, self.name),
+            "rubygems" => ("gem", None, self.name),
+            "cargo" => ("cargo", None, self.name),
+            "go" => match self.name.rsplit_once('/') {
+                Some((namespace, name)) => ("golang", Some(namespace.to_string()), name.into()),
+                None => ("golang", None, self.name),
+            },
+            _ => return None,
+        };
+
+        Some(Purl {
+            ty: ty.into(),
+            namespace,
+            name,
+            version: Some(version),
+            qualifiers: Default::default(),
+        })
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct AqlResult {
+    #[serde(default)]
+    results: Vec<AqlItem>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct AqlItem {
+    path: String,
+    name: String,
+}
+
+/// Map the path of a POM file in a Maven layout repository to its PURL.
+///
+/// The path is expected to be `<group path>/<artifact>/<version>`, with a file name starting with
+/// `<artifact>-`.
+fn maven_purl(path: &str, name: &str) -> Option<Purl> {
+    let mut segments = path.split('/').collect::<Vec<_>>();
+    let version = segments.pop()?;
+    let artifact = segments.pop()?;
+    if segments.is_empty() || !name.starts_with(&format!("{artifact}-")) {
+        return None;
+    }
+
+    Some(Purl {
+        ty: "maven".into(),
+        namespace: Some(segments.join(".")),
+        name: artifact.into(),
+        version: Some(version.into()),
+        qualifiers: Default::default(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn nexus_component() {
+        for (format, group, name, version, expected) in [
+            (
+                "maven2",
+                Some("org.apache.commons"),
+                "commons-lang3",
+                Some("3.12.0"),
+                Some("pkg:maven/org.apache.commons/commons-lang3@3.12.0"),
+            ),
+            (
+                "npm",
+                Some("angular"),
+                "core",
+                Some("17.0.0"),
+                Some("pkg:npm/%40angular/core@17.0.0"),
+            ),
+            (
+                "npm",
+                None,
+                "left-pad",
+                Some("1.3.0"),
+                Some("pkg:npm/left-pad@1.3.0"),
+            ),
+            (
+                "go",
+                None,
+                "github.com/google/uuid",
+                Some("v1.6.0"),
+                Some("pkg:golang/github.com/google/uuid@v1.6.0"),
+            ),
+            // unknown format
+            ("raw", None, "file.tar.gz", Some("1"), None),
+            // missing version
+            ("pypi", None, "requests", None, None),
+        ] {
+            let component = NexusComponent {
+                format: format.into(),
+                group: group.map(Into::into),
+                name: name.into(),
+                version: version.map(Into::into),
+            };
+
+            assert_eq!(
+                component.into_purl(),
+                expected.map(|purl| Purl::from_str(purl).expect("must parse")),
+                "{format}: {name}"
+            );
+        }
+    }
+
+    #[test]
+    fn maven_path() {
+        for (path, name, expected) in [
+            (
+                "org/apache/commons/commons-lang3/3.12.0",
+                "commons-lang3-3.12.0.pom",
+                Some("pkg:maven/org.apache.commons/commons-lang3@3.12.0"),
+            ),
+            (
+                "com/example/app/1.0-SNAPSHOT",
+                "app-1.0-20240101.120000-1.pom",
+                Some("pkg:maven/com.example/app@1.0-SNAPSHOT"),
+            ),
+            ("com/example/app", "maven-metadata.pom", None),
+            ("app/1.0", "app-1.0.pom", None),
+        ] {
+            assert_eq!(
+                maven_purl(path, name),
+                expected.map(|purl| Purl::from_str(purl).expect("must parse")),
+                "{path}/{name}"
+            );
+        }
+    }
+}
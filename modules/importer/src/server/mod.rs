@@ -19,6 +19,7 @@ use tokio_util::sync::CancellationToken;
 use tracing::instrument;
 use trustify_common::db::Database;
 use trustify_module_analysis::service::AnalysisService;
+use trustify_module_ingestor::service::listener::IngestListeners;
 use trustify_module_storage::service::dispatch::DispatchBackend;
 
 /// run the importer loop
@@ -27,6 +28,7 @@ pub async fn importer(
     storage: DispatchBackend,
     working_dir: Option<PathBuf>,
     analysis: Option<AnalysisService>,
+    listeners: IngestListeners,
     concurrency: usize,
 ) -> anyhow::Result<()> {
     Server {
@@ -34,6 +36,7 @@ pub async fn importer(
         storage,
         working_dir,
         analysis,
+        listeners,
         concurrency,
     }
     .run()
@@ -61,6 +64,7 @@ struct Server {
     storage: DispatchBackend,
     working_dir: Option<PathBuf>,
     analysis: Option<AnalysisService>,
+    listeners: IngestListeners,
     concurrency: usize,
 }
 
@@ -82,6 +86,7 @@ impl Server {
             storage: self.storage.clone(),
             working_dir: self.working_dir.clone(),
             analysis: self.analysis.clone(),
+            listeners: self.listeners.clone(),
         };
         let mut interval = tokio::time::interval(Duration::from_secs(1));
         interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
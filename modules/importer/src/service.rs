@@ -441,7 +441,8 @@ impl ImporterService {
         let limiting = importer_report::Entity::find()
             .filter(importer_report::Column::Importer.eq(name))
             .order_by_desc(importer_report::Column::Creation)
-            .limiting(&self.db, paginated.offset, paginated.limit);
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
 
         Ok(PaginatedResults {
             total: limiting.total().await?,
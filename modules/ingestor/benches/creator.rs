@@ -0,0 +1,105 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use sea_orm::ConnectionTrait;
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use test_context::AsyncTestContext;
+use trustify_common::purl::Purl;
+use trustify_module_ingestor::graph::{
+    purl::creator::PurlCreator,
+    sbom::{LicenseCreator, LicenseInfo},
+};
+use trustify_test_context::TrustifyContext;
+
+/// The number of items created by each iteration.
+const ITEMS: usize = 50_000;
+
+async fn reset_db(ctx: &TrustifyContext) {
+    for table in ["qualified_purl", "versioned_purl", "base_purl", "license"] {
+        ctx.db
+            .execute_unprepared(&format!("DELETE FROM {table}"))
+            .await
+            .expect("DELETE ok");
+    }
+}
+
+fn purls() -> PurlCreator {
+    let mut creator = PurlCreator::new();
+    for i in 0..ITEMS {
+        creator.add(
+            Purl::from_str(&format!(
+                "pkg:maven/org.example/package-{}@1.0.{i}?type=jar",
+                i % 1_000
+            ))
+            .expect("purl ok"),
+        );
+    }
+    creator
+}
+
+fn licenses() -> LicenseCreator {
+    let mut creator = LicenseCreator::new();
+    for i in 0..ITEMS {
+        creator.add(&LicenseInfo {
+            license: format!("Apache-2.0 OR LicenseRef-{i}"),
+        });
+    }
+    creator
+}
+
+fn creator(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let ctx = Arc::new(runtime.block_on(TrustifyContext::setup()));
+
+    c.bench_function("create_purls", |b| {
+        b.to_async(&runtime).iter_custom(|count| {
+            let ctx = ctx.clone();
+            async move {
+                let mut duration = Duration::default();
+                for _ in 0..count {
+                    reset_db(&ctx).await;
+                    let creator = purls();
+
+                    let start = Instant::now();
+                    creator.create(&ctx.db).await.expect("create ok");
+                    duration += start.elapsed();
+                }
+                duration
+            }
+        });
+    });
+
+    c.bench_function("create_licenses", |b| {
+        b.to_async(&runtime).iter_custom(|count| {
+            let ctx = ctx.clone();
+            async move {
+                let mut duration = Duration::default();
+                for _ in 0..count {
+                    reset_db(&ctx).await;
+                    let creator = licenses();
+
+                    let start = Instant::now();
+                    creator.create(&ctx.db).await.expect("create ok");
+                    duration += start.elapsed();
+                }
+                duration
+            }
+        });
+    });
+}
+
+criterion_group! {
+  name = benches;
+  config = Criterion::default()
+    .measurement_time(Duration::from_secs(30))
+    .sample_size(10);
+  targets = creator
+}
+criterion_main!(benches);
@@ -1,5 +1,7 @@
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, Related, Select};
-use trustify_entity::advisory;
+use sea_orm::{ColumnTrait, Condition, EntityTrait, QueryFilter, Related, Select};
+use sea_query::{Expr, Query};
+use time::OffsetDateTime;
+use trustify_entity::{advisory, source_document};
 use utoipa::ToSchema;
 
 #[derive(
@@ -14,14 +16,21 @@ pub enum Deprecation {
 }
 
 impl Deprecation {
-    pub fn filter(&self, advisories: Select<advisory::Entity>) -> Select<advisory::Entity> {
-        // rule out deprecated advisories, if requested to
+    /// A condition on the advisory table, ruling out deprecated advisories if requested to.
+    ///
+    /// The query must already include the advisory table.
+    pub fn condition(&self) -> Condition {
         match self {
-            Deprecation::Ignore => advisories.filter(advisory::Column::Deprecated.eq(false)),
-            Deprecation::Consider => advisories,
+            Deprecation::Ignore => Condition::all().add(advisory::Column::Deprecated.eq(false)),
+            Deprecation::Consider => Condition::all(),
         }
     }
 
+    pub fn filter(&self, advisories: Select<advisory::Entity>) -> Select<advisory::Entity> {
+        // rule out deprecated advisories, if requested to
+        advisories.filter(self.condition())
+    }
+
     pub fn filter_for<E>(&self, other: Select<E>) -> Select<E>
     where
         E: EntityTrait + Related<advisory::Entity>,
@@ -61,3 +70,112 @@ where
         deprecation.filter_for(self)
     }
 }
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Withdrawal {
+    /// Ignore the statuses of withdrawn advisories
+    #[default]
+    Ignore,
+    /// Consider the statuses of withdrawn advisories
+    Consider,
+}
+
+impl Withdrawal {
+    /// A condition on a column referencing an advisory, ruling out withdrawn advisories if
+    /// requested to.
+    pub fn condition(&self, advisory_id: impl ColumnTrait) -> Condition {
+        match self {
+            Withdrawal::Ignore => Condition::all().add(
+                advisory_id.not_in_subquery(
+                    Query::select()
+                        .column(advisory::Column::Id)
+                        .from(advisory::Entity)
+                        .and_where(advisory::Column::Withdrawn.is_not_null())
+                        .to_owned(),
+                ),
+            ),
+            Withdrawal::Consider => Condition::all(),
+        }
+    }
+}
+
+impl From<bool> for Withdrawal {
+    fn from(include_withdrawn: bool) -> Self {
+        match include_withdrawn {
+            true => Withdrawal::Consider,
+            false => Withdrawal::Ignore,
+        }
+    }
+}
+
+/// A point in time to reconstruct the state of advisories as of, or the current state.
+///
+/// Advisories are considered from the time they got ingested, until the time they got deleted.
+/// Deprecation gets re-evaluated based on the advisories known at that time, the same way it is
+/// evaluated when ingesting or deleting advisories: only the most recently modified advisory of
+/// an identifier is not deprecated.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct AsOf(pub Option<OffsetDateTime>);
+
+impl AsOf {
+    /// The deprecation to evaluate using the current deprecation marker of advisories.
+    ///
+    /// For a point in time, the current marker doesn't apply, and deprecation gets evaluated by
+    /// [`Self::condition`] instead.
+    pub fn deprecation(&self, deprecation: Deprecation) -> Deprecation {
+        match self.0 {
+            Some(_) => Deprecation::Consider,
+            None => deprecation,
+        }
+    }
+
+    /// A condition on the advisory table, ruling out deleted advisories.
+    ///
+    /// For a point in time, advisories deleted afterwards are kept, as [`Self::condition`] already
+    /// rules out the ones deleted before.
+    pub fn not_deleted(&self) -> Condition {
+        match self.0 {
+            Some(_) => Condition::all(),
+            None => Condition::all().add(advisory::Column::DeletedAt.is_null()),
+        }
+    }
+
+    /// A condition on a column referencing an advisory, ruling out advisories not known at the
+    /// point in time, as well as advisories deprecated at that time, if requested to.
+    pub fn condition(&self, advisory_id: impl ColumnTrait, deprecation: Deprecation) -> Condition {
+        let Some(as_of) = self.0 else {
+            return Condition::all();
+        };
+
+        let mut known = Query::select()
+            .column((advisory::Entity, advisory::Column::Id))
+            .from(advisory::Entity)
+            .inner_join(
+                source_document::Entity,
+                Expr::col((source_document::Entity, source_document::Column::Id))
+                    .equals((advisory::Entity, advisory::Column::SourceDocumentId)),
+            )
+            .and_where(Expr::cust_with_values(
+                r#""source_document"."ingested" <= $1 AND ("advisory"."deleted_at" IS NULL OR "advisory"."deleted_at" > $1)"#,
+                [as_of],
+            ))
+            .to_owned();
+
+        if deprecation == Deprecation::Ignore {
+            // a more recent advisory with the same identifier, known at that time
+            known.and_where(Expr::cust_with_values(
+                r#"NOT EXISTS (
+                    SELECT 1 FROM "advisory" AS "newer"
+                    JOIN "source_document" AS "newer_document" ON "newer_document"."id" = "newer"."source_document_id"
+                    WHERE "newer"."identifier" = "advisory"."identifier"
+                    AND "newer"."modified" > "advisory"."modified"
+                    AND "newer_document"."ingested" <= $1
+                    AND ("newer"."deleted_at" IS NULL OR "newer"."deleted_at" > $1)
+                )"#,
+                [as_of],
+            ));
+        }
+
+        Condition::all().add(advisory_id.in_subquery(known))
+    }
+}
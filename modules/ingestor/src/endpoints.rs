@@ -1,14 +1,27 @@
 use crate::{
-    graph::Graph,
-    service::{Error, IngestorService},
+    graph::{Graph, organization::OrganizationAliases},
+    model::IngestionJob,
+    service::{
+        Error, IngestorService, LabelTemplates,
+        audit::{Actor, Upload},
+        enricher::Enrichers,
+        listener::IngestListeners,
+        oci::OciImageIngestResult,
+        tenant::Tenant,
+    },
+};
+use actix_web::{HttpResponse, Responder, get, post, web};
+use trustify_auth::{CreateSbom, ReadJob, UploadDataset, authorizer::Require};
+use trustify_common::{
+    db::Database, decompress::Limits, digest::VerifiedBytes, error::ErrorInformation,
+    model::BinaryData,
 };
-use actix_web::{HttpResponse, Responder, post, web};
-use trustify_auth::{UploadDataset, authorizer::Require};
-use trustify_common::{db::Database, model::BinaryData};
 use trustify_entity::labels::Labels;
 use trustify_module_analysis::service::AnalysisService;
+use trustify_module_settings::{model::Settings, service::SettingsService};
 use trustify_module_storage::service::dispatch::DispatchBackend;
 use utoipa::IntoParams;
+use uuid::Uuid;
 
 /// mount the "ingestor" module
 pub fn configure(
@@ -18,17 +31,56 @@ pub fn configure(
     storage: impl Into<DispatchBackend>,
     analysis: Option<AnalysisService>,
 ) {
-    let ingestor_service = IngestorService::new(Graph::new(db), storage, analysis);
+    let settings_service = SettingsService::new(db.clone());
+    let graph = Graph::new(db).with_organization_aliases(config.organization_aliases.clone());
+    let ingestor_service = IngestorService::new(graph, storage, analysis)
+        .with_label_templates(config.label_templates.clone())
+        .with_enrichers(config.enrichers.clone())
+        .with_listeners(config.listeners.clone())
+        .with_dataset_concurrency(config.dataset_concurrency);
 
     svc.app_data(web::Data::new(ingestor_service))
+        .app_data(web::Data::new(settings_service))
         .app_data(web::Data::new(config))
-        .service(upload_dataset);
+        .service(upload_dataset)
+        .service(upload_oci_image)
+        .service(get_job);
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 pub struct Config {
     /// Limit of a single content entry (after decompression).
     pub dataset_entry_limit: usize,
+    /// Limit of the uploaded dataset archive itself. Zero meaning "unlimited".
+    pub dataset_upload_limit: usize,
+    /// Maximum ratio between the decompressed and compressed size of an entry. Zero meaning
+    /// "unlimited".
+    pub compression_ratio_limit: usize,
+    /// Number of documents of a dataset, ingested in parallel. Zero is treated like one.
+    pub dataset_concurrency: usize,
+    /// Templates for labels, evaluated against the content of uploaded documents.
+    pub label_templates: LabelTemplates,
+    /// Stages, enriching documents after they got ingested.
+    pub enrichers: Enrichers,
+    /// Listeners, notified about documents after they got ingested.
+    pub listeners: IngestListeners,
+    /// Aliases, applied to the names of ingested organizations.
+    pub organization_aliases: OrganizationAliases,
+}
+
+impl Config {
+    /// The configuration, with the runtime settings applied.
+    pub fn apply(&self, settings: &Settings) -> Self {
+        Self {
+            dataset_entry_limit: settings
+                .dataset_entry_limit
+                .unwrap_or(self.dataset_entry_limit),
+            dataset_upload_limit: settings
+                .dataset_upload_limit
+                .unwrap_or(self.dataset_upload_limit),
+            ..self.clone()
+        }
+    }
 }
 
 #[derive(
@@ -46,10 +98,14 @@ struct UploadParams {
     tag = "dataset",
     operation_id = "uploadDataset",
     request_body = inline(BinaryData),
-    params(UploadParams),
+    params(
+        UploadParams,
+        ("digest" = Option<String>, Header, description = "Digests of the body, like `sha-256=<base64>`, rejecting the upload if it doesn't match"),
+    ),
     responses(
         (status = 201, description = "Uploaded the dataset"),
-        (status = 400, description = "The file could not be parsed as an dataset"),
+        (status = 400, description = "The file could not be parsed as an dataset", body = ErrorInformation),
+        (status = 413, description = "The dataset, or one of its entries, exceeds the size limit", body = ErrorInformation),
     )
 )]
 #[post("/v2/dataset")]
@@ -57,12 +113,101 @@ struct UploadParams {
 pub async fn upload_dataset(
     service: web::Data<IngestorService>,
     config: web::Data<Config>,
+    settings: web::Data<SettingsService>,
     web::Query(UploadParams { labels }): web::Query<UploadParams>,
-    bytes: web::Bytes,
+    VerifiedBytes(bytes): VerifiedBytes,
+    actor: Actor,
+    tenant: Tenant,
     _: Require<UploadDataset>,
 ) -> Result<impl Responder, Error> {
+    let labels = tenant.stamp(labels);
+    let config = config.apply(&settings.current().await?);
+    if config.dataset_upload_limit > 0 && bytes.len() > config.dataset_upload_limit {
+        return Err(Error::PayloadTooLarge {
+            limit: config.dataset_upload_limit,
+        });
+    }
+
     let result = service
-        .ingest_dataset(&bytes, labels, config.dataset_entry_limit)
+        .ingest_dataset(
+            &bytes,
+            labels,
+            Limits::new(config.dataset_entry_limit, config.compression_ratio_limit),
+            Some(&Upload::new(actor)),
+        )
         .await?;
     Ok(HttpResponse::Created().json(result))
 }
+
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "uploadOciImage",
+    request_body = inline(BinaryData),
+    params(
+        UploadParams,
+        ("digest" = Option<String>, Header, description = "Digests of the body, like `sha-256=<base64>`, rejecting the upload if it doesn't match"),
+    ),
+    responses(
+        (status = 201, description = "Ingested the SBOMs contained in the image", body = OciImageIngestResult),
+        (status = 400, description = "The file could not be parsed as an image", body = ErrorInformation),
+        (status = 413, description = "The image, or one of its SBOMs, exceeds the size limit", body = ErrorInformation),
+    )
+)]
+#[post("/v2/oci-image")]
+/// Upload a container image, ingesting the SBOMs it contains
+///
+/// The image is a tarball, as created by `docker save`, or an OCI image layout. The size limits of
+/// datasets apply to images as well.
+pub async fn upload_oci_image(
+    service: web::Data<IngestorService>,
+    config: web::Data<Config>,
+    settings: web::Data<SettingsService>,
+    web::Query(UploadParams { labels }): web::Query<UploadParams>,
+    VerifiedBytes(bytes): VerifiedBytes,
+    actor: Actor,
+    tenant: Tenant,
+    _: Require<CreateSbom>,
+) -> Result<impl Responder, Error> {
+    let labels = tenant.stamp(labels);
+    let config = config.apply(&settings.current().await?);
+    if config.dataset_upload_limit > 0 && bytes.len() > config.dataset_upload_limit {
+        return Err(Error::PayloadTooLarge {
+            limit: config.dataset_upload_limit,
+        });
+    }
+
+    let result = service
+        .ingest_oci_image(
+            bytes,
+            labels,
+            config.dataset_entry_limit,
+            Some(&Upload::new(actor)),
+        )
+        .await?;
+    Ok(HttpResponse::Created().json(result))
+}
+
+#[utoipa::path(
+    tag = "job",
+    operation_id = "getJob",
+    params(
+        ("id" = Uuid, Path, description = "The ID of the ingestion job"),
+    ),
+    responses(
+        (status = 200, description = "The state of the ingestion job", body = IngestionJob),
+        (status = 404, description = "The ingestion job could not be found", body = ErrorInformation),
+    )
+)]
+#[get("/v2/job/{id}")]
+/// Get the state, and result, of an asynchronous ingestion job
+pub async fn get_job(
+    service: web::Data<IngestorService>,
+    id: web::Path<Uuid>,
+    tenant: Tenant,
+    _: Require<ReadJob>,
+) -> Result<impl Responder, Error> {
+    Ok(match service.fetch_job(id.into_inner(), &tenant).await? {
+        Some(job) => HttpResponse::Ok().json(job),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
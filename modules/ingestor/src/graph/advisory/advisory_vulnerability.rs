@@ -2,7 +2,7 @@ use crate::graph::{advisory::AdvisoryContext, error::Error, vulnerability::Vulne
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, NotSet, QueryFilter, Set,
 };
-use sea_query::IntoCondition;
+use sea_query::{IntoCondition, OnConflict};
 use tracing::instrument;
 use trustify_common::{cpe::Cpe, purl::Purl};
 use trustify_cvss::cvss3::Cvss3Base;
@@ -162,6 +162,45 @@ impl AdvisoryVulnerabilityContext<'_> {
 
         Ok(model.insert(connection).await?.into())
     }
+
+    /// Record other identifiers of the vulnerability, as stated by the advisory.
+    #[instrument(skip(self, aliases, connection), err)]
+    pub async fn ingest_aliases<C: ConnectionTrait>(
+        &self,
+        aliases: impl IntoIterator<Item = impl Into<String>>,
+        connection: &C,
+    ) -> Result<(), Error> {
+        let aliases = aliases
+            .into_iter()
+            .map(Into::into)
+            .filter(|alias| *alias != self.advisory_vulnerability.vulnerability_id)
+            .map(|alias| entity::vulnerability_alias::ActiveModel {
+                advisory_id: Set(self.advisory_vulnerability.advisory_id),
+                vulnerability_id: Set(self.advisory_vulnerability.vulnerability_id.clone()),
+                alias: Set(alias),
+            })
+            .collect::<Vec<_>>();
+
+        if aliases.is_empty() {
+            return Ok(());
+        }
+
+        entity::vulnerability_alias::Entity::insert_many(aliases)
+            .on_conflict(
+                OnConflict::columns([
+                    entity::vulnerability_alias::Column::AdvisoryId,
+                    entity::vulnerability_alias::Column::VulnerabilityId,
+                    entity::vulnerability_alias::Column::Alias,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .do_nothing()
+            .exec(connection)
+            .await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
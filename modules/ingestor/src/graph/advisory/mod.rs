@@ -5,19 +5,25 @@ use crate::{
     graph::{
         CreateOutcome, Graph, Outcome,
         advisory::advisory_vulnerability::AdvisoryVulnerabilityContext, error::Error,
+        source_documents_by_digest, tenant_condition,
     },
 };
 use sea_orm::{
     ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel,
     ModelTrait, QueryFilter, QuerySelect, RelationTrait, TransactionTrait,
 };
-use sea_query::{Condition, JoinType, OnConflict};
+use sea_query::{Condition, Expr, JoinType, OnConflict};
 use semver::Version;
 use std::fmt::{Debug, Formatter};
 use time::OffsetDateTime;
 use tracing::instrument;
-use trustify_common::{db::UpdateDeprecatedAdvisory, hashing::Digests};
-use trustify_entity::{self as entity, advisory, labels::Labels, source_document};
+use trustify_auth::tenant::TENANT_LABEL;
+use trustify_common::{
+    cwe,
+    db::{RecomputeProductStatusSummary, UpdateDeprecatedAdvisory},
+    hashing::Digests,
+};
+use trustify_entity::{self as entity, advisory, labels::Labels, product_status, source_document};
 use uuid::Uuid;
 
 pub mod advisory_vulnerability;
@@ -79,6 +85,7 @@ impl Graph {
     pub async fn get_advisory_by_digest<C: ConnectionTrait>(
         &self,
         digest: &str,
+        tenant: Option<&str>,
         connection: &C,
     ) -> Result<Option<AdvisoryContext>, Error> {
         Ok(advisory::Entity::find()
@@ -89,11 +96,70 @@ impl Graph {
                     .add(source_document::Column::Sha384.eq(digest.to_string()))
                     .add(source_document::Column::Sha512.eq(digest.to_string())),
             )
+            .filter(advisory::Column::DeletedAt.is_null())
+            .filter(tenant_condition(
+                (advisory::Entity, advisory::Column::Labels),
+                tenant,
+            ))
             .one(connection)
             .await?
             .map(|advisory| AdvisoryContext::new(self, advisory)))
     }
 
+    /// Get the advisory with the digest, restoring it in case it was marked as deleted.
+    ///
+    /// Ingesting a deleted document again means it is wanted again. As it still holds on to its
+    /// source document, it can't be ingested as a new one anyway.
+    #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
+    pub async fn get_or_restore_advisory_by_digest<C: ConnectionTrait>(
+        &self,
+        digest: &str,
+        tenant: Option<&str>,
+        connection: &C,
+    ) -> Result<Option<AdvisoryContext>, Error> {
+        let restored = advisory::Entity::update_many()
+            .col_expr(
+                advisory::Column::DeletedAt,
+                Expr::value(None::<OffsetDateTime>),
+            )
+            .filter(
+                advisory::Column::SourceDocumentId.in_subquery(source_documents_by_digest(digest)),
+            )
+            .filter(advisory::Column::DeletedAt.is_not_null())
+            .filter(tenant_condition(
+                (advisory::Entity, advisory::Column::Labels),
+                tenant,
+            ))
+            .exec_with_returning(connection)
+            .await?;
+
+        let Some(advisory) = restored.into_iter().next() else {
+            return self
+                .get_advisory_by_digest(digest, tenant, connection)
+                .await;
+        };
+
+        log::info!("Restored deleted advisory: {}", advisory.id);
+
+        // the advisory takes part in deciding deprecations and product statuses again
+
+        UpdateDeprecatedAdvisory::execute(connection, &advisory.identifier).await?;
+
+        let vulnerabilities: Vec<String> = product_status::Entity::find()
+            .select_only()
+            .distinct()
+            .column(product_status::Column::VulnerabilityId)
+            .filter(product_status::Column::AdvisoryId.eq(advisory.id))
+            .into_tuple()
+            .all(connection)
+            .await?;
+        for vulnerability in &vulnerabilities {
+            RecomputeProductStatusSummary::execute(connection, Some(vulnerability), None).await?;
+        }
+
+        Ok(Some(AdvisoryContext::new(self, advisory)))
+    }
+
     pub async fn get_advisories<C: ConnectionTrait>(
         &self,
         deprecation: Deprecation,
@@ -122,6 +188,7 @@ impl Graph {
     {
         let identifier = identifier.into();
         let labels = labels.into();
+        let tenant = labels.get(TENANT_LABEL).map(String::as_str);
 
         let AdvisoryInformation {
             id,
@@ -135,7 +202,8 @@ impl Graph {
 
         let new_id = match self
             .create_doc(digests, connection, async |sha256| {
-                self.get_advisory_by_digest(&sha256, connection).await
+                self.get_or_restore_advisory_by_digest(&sha256, tenant, connection)
+                    .await
             })
             .await?
         {
@@ -165,6 +233,8 @@ impl Graph {
             withdrawn: Set(withdrawn),
             labels: Set(labels),
             source_document_id: Set(Some(new_id)),
+            external_id: Set(None),
+            deleted_at: Set(None),
         };
 
         let result = model.insert(connection).await?;
@@ -247,6 +317,38 @@ impl<'g> AdvisoryContext<'g> {
         self.advisory.withdrawn
     }
 
+    /// Record the identifiers of advisories superseded by this one.
+    #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
+    pub async fn set_supersedes<C: ConnectionTrait>(
+        &self,
+        identifiers: &[String],
+        connection: &C,
+    ) -> Result<(), Error> {
+        if identifiers.is_empty() {
+            return Ok(());
+        }
+
+        entity::advisory_supersedence::Entity::insert_many(identifiers.iter().map(|identifier| {
+            entity::advisory_supersedence::ActiveModel {
+                advisory_id: Set(self.advisory.id),
+                supersedes: Set(identifier.clone()),
+            }
+        }))
+        .on_conflict(
+            OnConflict::columns([
+                entity::advisory_supersedence::Column::AdvisoryId,
+                entity::advisory_supersedence::Column::Supersedes,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .do_nothing()
+        .exec(connection)
+        .await?;
+
+        Ok(())
+    }
+
     #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
     pub async fn get_vulnerability<C: ConnectionTrait>(
         &self,
@@ -280,7 +382,10 @@ impl<'g> AdvisoryContext<'g> {
             reserved_date: Set(information.as_ref().and_then(|info| info.reserved_date)),
             discovery_date: Set(information.as_ref().and_then(|info| info.discovery_date)),
             release_date: Set(information.as_ref().and_then(|info| info.release_date)),
-            cwes: Set(information.as_ref().and_then(|info| info.cwes.clone())),
+            cwes: Set(information
+                .as_ref()
+                .and_then(|info| info.cwes.as_ref())
+                .and_then(|cwes| cwe::normalize_all(cwes.iter().map(String::as_str)))),
         };
 
         // do an upsert, updating field on a conflict
@@ -10,8 +10,13 @@ pub mod vulnerability;
 
 use db_context::DbContext;
 use hex::ToHex;
+use organization::OrganizationAliases;
 use sea_orm::{
-    ActiveValue::Set, ConnectionTrait, DbErr, EntityTrait, TransactionError, TransactionTrait,
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter,
+    TransactionError, TransactionTrait,
+};
+use sea_query::{
+    Condition, Expr, IntoColumnRef, Query, SelectStatement, SimpleExpr, extension::postgres::PgExpr,
 };
 use std::{
     fmt::Debug,
@@ -21,6 +26,7 @@ use std::{
 use time::OffsetDateTime;
 use tokio::sync::Mutex;
 use tracing::instrument;
+use trustify_auth::tenant::TENANT_LABEL;
 use trustify_common::hashing::Digests;
 use trustify_entity::source_document;
 use uuid::Uuid;
@@ -29,6 +35,7 @@ use uuid::Uuid;
 pub struct Graph {
     pub(crate) db: trustify_common::db::Database,
     pub(crate) db_context: Arc<Mutex<DbContext>>,
+    pub(crate) organization_aliases: Arc<OrganizationAliases>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -44,9 +51,16 @@ impl Graph {
         Self {
             db,
             db_context: Arc::new(Mutex::new(DbContext::new())),
+            organization_aliases: Default::default(),
         }
     }
 
+    /// Set the aliases, applied to the names of ingested organizations.
+    pub fn with_organization_aliases(mut self, aliases: impl Into<OrganizationAliases>) -> Self {
+        self.organization_aliases = Arc::new(aliases.into());
+        self
+    }
+
     /// Create a new source document, or return an existing sha256 digest if a document with that
     /// already sha256 digest already exists.
     #[instrument(skip(self, connection, f), err(level=tracing::Level::INFO))]
@@ -90,10 +104,21 @@ impl Graph {
                 match f(digests.sha256.encode_hex()).await? {
                     // and return it
                     Some(doc) => Ok(CreateOutcome::Exists(doc)),
-                    // and report that it vanished
-                    None => Err(error::Error::Database(DbErr::Custom(
-                        "document vanished".to_string(),
-                    ))),
+                    // the source document is shared with a document of another tenant
+                    None => match source_document::Entity::find()
+                        .filter(
+                            source_document::Column::Sha256
+                                .eq(digests.sha256.encode_hex::<String>()),
+                        )
+                        .one(connection)
+                        .await?
+                    {
+                        Some(doc) => Ok(CreateOutcome::Created(doc.id)),
+                        // or report that it vanished
+                        None => Err(error::Error::Database(DbErr::Custom(
+                            "document vanished".to_string(),
+                        ))),
+                    },
                 }
             }
             Err(TransactionError::Transaction(err)) => Err(err.into()),
@@ -102,6 +127,32 @@ impl Graph {
     }
 }
 
+/// Select the IDs of the source documents with the digest, using any of the supported algorithms.
+fn source_documents_by_digest(digest: &str) -> SelectStatement {
+    Query::select()
+        .column(source_document::Column::Id)
+        .from(source_document::Entity)
+        .cond_where(
+            Condition::any()
+                .add(source_document::Column::Sha256.eq(digest))
+                .add(source_document::Column::Sha384.eq(digest))
+                .add(source_document::Column::Sha512.eq(digest)),
+        )
+        .to_owned()
+}
+
+/// Limit documents to the ones of the tenant, or to the ones without a tenant.
+///
+/// Documents are only deduplicated within a tenant, as finding the document of another tenant
+/// would reveal it.
+fn tenant_condition(labels: impl IntoColumnRef, tenant: Option<&str>) -> SimpleExpr {
+    let label = Expr::expr(Expr::col(labels).cast_json_field(TENANT_LABEL));
+    match tenant {
+        Some(tenant) => label.eq(tenant),
+        None => label.is_null(),
+    }
+}
+
 #[derive(Debug)]
 enum CreateOutcome<T> {
     Created(Uuid),
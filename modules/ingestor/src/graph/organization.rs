@@ -1,5 +1,8 @@
-use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
-use std::fmt::Debug;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use sea_query::{Expr, Func};
+use std::{collections::HashMap, fmt::Debug, str::FromStr};
 use tracing::instrument;
 use trustify_entity::organization;
 
@@ -26,6 +29,75 @@ impl From<()> for OrganizationInformation {
     }
 }
 
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum OrganizationAliasError {
+    #[error("missing '=' between alias and organization name")]
+    MissingSeparator,
+    #[error("alias and organization name must not be empty")]
+    Empty,
+}
+
+/// An alternative name of an organization, in the form of `<alias>=<name>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrganizationAlias {
+    alias: String,
+    name: String,
+}
+
+impl FromStr for OrganizationAlias {
+    type Err = OrganizationAliasError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (alias, name) = s
+            .split_once('=')
+            .ok_or(OrganizationAliasError::MissingSeparator)?;
+
+        let (alias, name) = (normalize(alias), normalize(name));
+        if alias.is_empty() || name.is_empty() {
+            return Err(OrganizationAliasError::Empty);
+        }
+
+        Ok(Self { alias, name })
+    }
+}
+
+/// Aliases of organizations, applied when ingesting organizations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OrganizationAliases(HashMap<String, String>);
+
+impl From<Vec<OrganizationAlias>> for OrganizationAliases {
+    fn from(value: Vec<OrganizationAlias>) -> Self {
+        Self(
+            value
+                .into_iter()
+                .map(|OrganizationAlias { alias, name }| (alias.to_lowercase(), name))
+                .collect(),
+        )
+    }
+}
+
+impl OrganizationAliases {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Normalize the name of an organization, replacing it with the name it is an alias of.
+    ///
+    /// Aliases are matched ignoring the case.
+    pub fn resolve(&self, name: &str) -> String {
+        let name = normalize(name);
+        match self.0.get(&name.to_lowercase()) {
+            Some(name) => name.clone(),
+            None => name,
+        }
+    }
+}
+
+/// Trim the name and collapse whitespace inside it.
+fn normalize(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 pub struct OrganizationContext<'g> {
     graph: &'g Graph,
     pub organization: organization::Model,
@@ -74,10 +146,21 @@ impl Graph {
         information: impl Into<OrganizationInformation> + Debug,
         connection: &C,
     ) -> Result<OrganizationContext, Error> {
-        let name = name.into();
+        let name = self.organization_aliases.resolve(&name.into());
         let information = information.into();
 
-        if let Some(found) = self.get_organization_by_name(&name, connection).await? {
+        // trivially different names, only differing in case, refer to the same organization
+        let found = organization::Entity::find()
+            .filter(
+                Expr::expr(Func::lower(Expr::col(organization::Column::Name)))
+                    .eq(name.to_lowercase()),
+            )
+            .order_by_asc(organization::Column::Id)
+            .one(connection)
+            .await?
+            .map(|organization| OrganizationContext::new(self, organization));
+
+        if let Some(found) = found {
             if information.has_data() {
                 let mut entity = organization::ActiveModel::from(found.organization);
                 entity.website = Set(information.website);
@@ -102,3 +185,53 @@ impl Graph {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_test_context::TrustifyContext;
+
+    #[test]
+    fn resolve_aliases() {
+        let aliases = OrganizationAliases::from(vec![
+            OrganizationAlias::from_str("Red Hat Product Security = Red Hat").unwrap(),
+        ]);
+
+        assert_eq!(aliases.resolve("  Red   Hat  "), "Red Hat");
+        assert_eq!(aliases.resolve("red hat product  security"), "Red Hat");
+        assert_eq!(aliases.resolve("Acme"), "Acme");
+
+        assert_eq!(
+            OrganizationAlias::from_str("Red Hat"),
+            Err(OrganizationAliasError::MissingSeparator)
+        );
+        assert_eq!(
+            OrganizationAlias::from_str(" =Red Hat"),
+            Err(OrganizationAliasError::Empty)
+        );
+    }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn ingest_normalized(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        let graph = Graph::new(ctx.db.clone()).with_organization_aliases(vec![
+            OrganizationAlias::from_str("Red Hat Product Security=Red Hat")?,
+        ]);
+
+        let org = graph.ingest_organization("Red Hat", (), &ctx.db).await?;
+        let same = graph.ingest_organization(" red  HAT ", (), &ctx.db).await?;
+        let alias = graph
+            .ingest_organization("Red Hat Product Security", (), &ctx.db)
+            .await?;
+        let other = graph.ingest_organization("Acme", (), &ctx.db).await?;
+
+        assert_eq!(org.organization.id, same.organization.id);
+        assert_eq!(org.organization.id, alias.organization.id);
+        assert_ne!(org.organization.id, other.organization.id);
+        assert_eq!(graph.get_organizations(&ctx.db).await?.len(), 2);
+
+        Ok(())
+    }
+}
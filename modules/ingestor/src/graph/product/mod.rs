@@ -54,6 +54,9 @@ impl<'g> ProductContext<'g> {
                 product_id: Set(self.product.id),
                 sbom_id: Set(None),
                 version: Set(version.clone()),
+                ga_date: Set(None),
+                eol_date: Set(None),
+                support_status: Set(None),
             };
 
             let product_version = ProductVersionContext::new(self, model.insert(connection).await?);
@@ -190,6 +193,7 @@ impl Graph {
                     name: Set(name),
                     cpe_key: Set(cpe_key),
                     vendor_id: Set(Some(org.organization.id)),
+                    parent_id: Set(None),
                 }
             }
         } else {
@@ -199,6 +203,7 @@ impl Graph {
                 name: Set(name),
                 vendor_id: Set(None),
                 cpe_key: Set(cpe_key),
+                parent_id: Set(None),
             }
         };
 
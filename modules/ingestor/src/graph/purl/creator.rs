@@ -1,16 +1,52 @@
 use crate::graph::error::Error;
-use sea_orm::{ActiveValue::Set, ConnectionTrait, EntityTrait};
-use sea_query::OnConflict;
+use sea_orm::ConnectionTrait;
 use std::collections::{BTreeMap, HashSet};
 use tracing::instrument;
-use trustify_common::{db::chunk::EntityChunkedIter, purl::Purl};
-use trustify_entity::{
-    base_purl,
-    qualified_purl::{self, Qualifiers},
-    versioned_purl,
-};
+use trustify_common::{db::batch::insert_rows, purl::Purl};
+use trustify_entity::qualified_purl::{CanonicalPurl, Qualifiers};
 use uuid::Uuid;
 
+const INSERT_BASE_PURLS: &str = r#"
+INSERT INTO base_purl (id, "type", namespace, name)
+SELECT * FROM jsonb_to_recordset($1) AS t(id uuid, "type" text, namespace text, name text)
+ON CONFLICT (id) DO NOTHING
+"#;
+
+const INSERT_VERSIONED_PURLS: &str = r#"
+INSERT INTO versioned_purl (id, base_purl_id, version)
+SELECT * FROM jsonb_to_recordset($1) AS t(id uuid, base_purl_id uuid, version text)
+ON CONFLICT (id) DO NOTHING
+"#;
+
+const INSERT_QUALIFIED_PURLS: &str = r#"
+INSERT INTO qualified_purl (id, versioned_purl_id, qualifiers, purl)
+SELECT * FROM jsonb_to_recordset($1) AS t(id uuid, versioned_purl_id uuid, qualifiers jsonb, purl jsonb)
+ON CONFLICT (id) DO NOTHING
+"#;
+
+#[derive(serde::Serialize)]
+struct BasePurlRow {
+    id: Uuid,
+    r#type: String,
+    namespace: Option<String>,
+    name: String,
+}
+
+#[derive(serde::Serialize)]
+struct VersionedPurlRow {
+    id: Uuid,
+    base_purl_id: Uuid,
+    version: String,
+}
+
+#[derive(serde::Serialize)]
+struct QualifiedPurlRow {
+    id: Uuid,
+    versioned_purl_id: Uuid,
+    qualifiers: Qualifiers,
+    purl: CanonicalPurl,
+}
+
 /// Creator of PURLs.
 #[derive(Default)]
 pub struct PurlCreator {
@@ -22,8 +58,9 @@ impl PurlCreator {
         Self::default()
     }
 
+    /// Add a PURL, which gets stored in its canonical form.
     pub fn add(&mut self, purl: Purl) {
-        self.purls.insert(purl);
+        self.purls.insert(purl.canonical());
     }
 
     #[instrument(skip_all, fields(num = self.purls.len()), err(level=tracing::Level::INFO))]
@@ -35,7 +72,7 @@ impl PurlCreator {
             return Ok(());
         }
 
-        // insert all packages
+        // collect all packages
 
         let mut packages = BTreeMap::new();
         let mut versions = BTreeMap::new();
@@ -44,74 +81,40 @@ impl PurlCreator {
         for purl in self.purls {
             let cp = purl.clone().into();
             let (package, version, qualified) = purl.uuids();
-            packages
-                .entry(package)
-                .or_insert_with(|| base_purl::ActiveModel {
-                    id: Set(package),
-                    r#type: Set(purl.ty),
-                    namespace: Set(purl.namespace),
-                    name: Set(purl.name),
-                });
-
-            versions
-                .entry(version)
-                .or_insert_with(|| versioned_purl::ActiveModel {
-                    id: Set(version),
-                    base_purl_id: Set(package),
-                    version: Set(purl.version.unwrap_or_default()),
-                });
+            packages.entry(package).or_insert_with(|| BasePurlRow {
+                id: package,
+                r#type: purl.ty,
+                namespace: purl.namespace,
+                name: purl.name,
+            });
+
+            versions.entry(version).or_insert_with(|| VersionedPurlRow {
+                id: version,
+                base_purl_id: package,
+                version: purl.version.unwrap_or_default(),
+            });
 
             qualifieds
                 .entry(qualified)
-                .or_insert_with(|| qualified_purl::ActiveModel {
-                    id: Set(qualified),
-                    versioned_purl_id: Set(version),
-                    qualifiers: Set(Qualifiers(purl.qualifiers)),
-                    purl: Set(cp),
+                .or_insert_with(|| QualifiedPurlRow {
+                    id: qualified,
+                    versioned_purl_id: version,
+                    qualifiers: Qualifiers(purl.qualifiers),
+                    purl: cp,
                 });
         }
 
         // insert packages
 
-        for batch in &packages.into_values().chunked() {
-            base_purl::Entity::insert_many(batch)
-                .on_conflict(
-                    OnConflict::columns([base_purl::Column::Id])
-                        .do_nothing()
-                        .to_owned(),
-                )
-                .do_nothing()
-                .exec(db)
-                .await?;
-        }
+        insert_rows(db, INSERT_BASE_PURLS, packages.into_values()).await?;
 
         // insert all package versions
 
-        for batch in &versions.into_values().chunked() {
-            versioned_purl::Entity::insert_many(batch)
-                .on_conflict(
-                    OnConflict::columns([versioned_purl::Column::Id])
-                        .do_nothing()
-                        .to_owned(),
-                )
-                .do_nothing()
-                .exec(db)
-                .await?;
-        }
+        insert_rows(db, INSERT_VERSIONED_PURLS, versions.into_values()).await?;
 
         // insert all qualified packages
 
-        for batch in &qualifieds.into_values().chunked() {
-            qualified_purl::Entity::insert_many(batch)
-                .on_conflict(
-                    OnConflict::columns([qualified_purl::Column::Id])
-                        .do_nothing()
-                        .to_owned(),
-                )
-                .do_nothing()
-                .exec(db)
-                .await?;
-        }
+        insert_rows(db, INSERT_QUALIFIED_PURLS, qualifieds.into_values()).await?;
 
         // return
 
@@ -126,3 +129,99 @@ impl PurlCreator {
             .map(|purl| purl.to_string())
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use sea_orm::EntityTrait;
+    use std::str::FromStr;
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_entity::{base_purl, qualified_purl, versioned_purl};
+    use trustify_test_context::TrustifyContext;
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn create(ctx: &TrustifyContext) -> anyhow::Result<()> {
+        let with_qualifiers = Purl::from_str("pkg:maven/io.quarkus/quarkus-core@3.0.0?type=jar")?;
+        let without_namespace = Purl::from_str("pkg:cargo/serde@1.0.0")?;
+
+        let mut creator = PurlCreator::new();
+        creator.add(with_qualifiers.clone());
+        creator.add(without_namespace.clone());
+        creator.create(&ctx.db).await?;
+
+        // creating it again must not fail
+
+        let mut creator = PurlCreator::new();
+        creator.add(with_qualifiers.clone());
+        creator.add(without_namespace.clone());
+        creator.create(&ctx.db).await?;
+
+        assert_eq!(base_purl::Entity::find().all(&ctx.db).await?.len(), 2);
+        assert_eq!(versioned_purl::Entity::find().all(&ctx.db).await?.len(), 2);
+        assert_eq!(qualified_purl::Entity::find().all(&ctx.db).await?.len(), 2);
+
+        let (package, version, qualified) = without_namespace.uuids();
+        let base = base_purl::Entity::find_by_id(package)
+            .one(&ctx.db)
+            .await?
+            .unwrap();
+        assert_eq!(base.r#type, "cargo");
+        assert_eq!(base.namespace, None);
+        assert_eq!(base.name, "serde");
+        let versioned = versioned_purl::Entity::find_by_id(version)
+            .one(&ctx.db)
+            .await?
+            .unwrap();
+        assert_eq!(versioned.base_purl_id, package);
+        assert_eq!(versioned.version, "1.0.0");
+        let qualified = qualified_purl::Entity::find_by_id(qualified)
+            .one(&ctx.db)
+            .await?
+            .unwrap();
+        assert_eq!(qualified.versioned_purl_id, version);
+        assert!(qualified.qualifiers.0.is_empty());
+
+        let (_, _, qualified) = with_qualifiers.uuids();
+        let qualified = qualified_purl::Entity::find_by_id(qualified)
+            .one(&ctx.db)
+            .await?
+            .unwrap();
+        assert_eq!(
+            qualified.qualifiers.0.get("type").map(String::as_str),
+            Some("jar")
+        );
+        assert_eq!(Purl::from(qualified.purl), with_qualifiers);
+
+        Ok(())
+    }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn canonical(ctx: &TrustifyContext) -> anyhow::Result<()> {
+        let upper = Purl {
+            ty: "NPM".into(),
+            namespace: None,
+            name: "Lodash".into(),
+            version: Some("1.0".into()),
+            qualifiers: Default::default(),
+        };
+        let lower = Purl::from_str("pkg:npm/lodash@1.0")?;
+
+        let mut creator = PurlCreator::new();
+        creator.add(upper.clone());
+        creator.add(lower.clone());
+        creator.create(&ctx.db).await?;
+
+        assert_eq!(base_purl::Entity::find().all(&ctx.db).await?.len(), 1);
+        assert_eq!(versioned_purl::Entity::find().all(&ctx.db).await?.len(), 1);
+        let qualified = qualified_purl::Entity::find().all(&ctx.db).await?;
+        assert_eq!(qualified.len(), 1);
+        assert_eq!(qualified[0].id, upper.qualifier_uuid());
+        assert_eq!(Purl::from(qualified[0].purl.clone()), lower);
+
+        Ok(())
+    }
+}
@@ -8,7 +8,8 @@ use crate::graph::{Graph, error::Error};
 use package_version::PackageVersionContext;
 use qualified_package::QualifiedPackageContext;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set, prelude::Uuid,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, NotSet, QueryFilter, Set,
+    prelude::Uuid,
 };
 use sea_query::SelectStatement;
 use std::fmt::{Debug, Formatter};
@@ -64,6 +65,7 @@ impl Graph {
         purl: &Purl,
         connection: &C,
     ) -> Result<PackageContext, Error> {
+        let purl = &purl.canonical();
         if let Some(found) = self.get_package(purl, connection).await? {
             Ok(found)
         } else {
@@ -72,6 +74,7 @@ impl Graph {
                 r#type: Set(purl.ty.clone()),
                 namespace: Set(purl.namespace.clone()),
                 name: Set(purl.name.clone()),
+                ecosystem: NotSet,
             };
 
             Ok(PackageContext::new(self, model.insert(connection).await?))
@@ -194,6 +197,7 @@ impl Graph {
         purl: &Purl,
         connection: &C,
     ) -> Result<Option<PackageContext>, Error> {
+        let purl = &purl.canonical();
         Ok(entity::base_purl::Entity::find()
             .filter(entity::base_purl::Column::Type.eq(&purl.ty))
             .filter(if let Some(ns) = &purl.namespace {
@@ -454,6 +458,7 @@ mod tests {
                 Paginated {
                     offset: 50,
                     limit: 50,
+                    ..Default::default()
                 },
                 &ctx.db,
             )
@@ -467,6 +472,7 @@ mod tests {
                 Paginated {
                     offset: 100,
                     limit: 50,
+                    ..Default::default()
                 },
                 &ctx.db,
             )
@@ -35,6 +35,7 @@ impl<'g> PackageVersionContext<'g> {
         purl: &Purl,
         connection: &C,
     ) -> Result<QualifiedPackageContext<'g>, Error> {
+        let purl = &purl.canonical();
         if let Some(found) = self.get_qualified_package(purl, connection).await? {
             return Ok(found);
         }
@@ -57,6 +58,7 @@ impl<'g> PackageVersionContext<'g> {
         purl: &Purl,
         connection: &C,
     ) -> Result<Option<QualifiedPackageContext<'g>>, Error> {
+        let purl = &purl.canonical();
         let found = entity::qualified_purl::Entity::find()
             .filter(entity::qualified_purl::Column::VersionedPurlId.eq(self.package_version.id))
             .filter(
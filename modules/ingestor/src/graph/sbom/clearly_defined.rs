@@ -1,12 +1,21 @@
 use crate::graph::purl::creator::PurlCreator;
-use crate::graph::sbom::{LicenseCreator, LicenseInfo, SbomContext, SbomInformation};
+use crate::graph::sbom::{
+    Checksum, LicenseCreator, LicenseInfo, NodeInfoParam, PackageCreator, PackageLicensenInfo,
+    PackageReference, References, RelationshipCreator, SbomContext, SbomInformation,
+};
 use sea_orm::ConnectionTrait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tracing::instrument;
 use trustify_common::purl::Purl;
+use trustify_entity::{relationship::Relationship, sbom_package_license::LicenseCategory};
 
 impl SbomContext {
+    /// Ingest the curated licenses, as packages of the curation SBOM.
+    ///
+    /// Each curated revision becomes a package, identified by its ClearlyDefined coordinates and
+    /// described by the document. So that curated licenses can be found the same way as the
+    /// licenses declared by any other SBOM.
     #[instrument(skip(db, curation), err)]
     pub async fn ingest_clearly_defined_curation<C: ConnectionTrait>(
         &self,
@@ -15,41 +24,48 @@ impl SbomContext {
     ) -> Result<(), anyhow::Error> {
         let mut purls = PurlCreator::new();
         let mut licenses = LicenseCreator::new();
-
-        // TODO: Since the node id cannot be obtained here, it’s not possible to replace purl_license_assertion with sbom_package_license.
-        // let mut assertions = Vec::new();
-
-        for (purl, license) in curation.iter() {
-            let license_info = LicenseInfo {
-                license: license.clone(),
-            };
-
-            // assertions.push(purl_license_assertion::ActiveModel {
-            //     id: Default::default(),
-            //     license_id: Set(license_info.uuid()),
-            //     versioned_purl_id: Set(purl.version_uuid()),
-            //     sbom_id: Set(self.sbom.sbom_id),
-            // });
+        let mut packages = PackageCreator::new(self.sbom.sbom_id);
+        let mut relationships = RelationshipCreator::new(self.sbom.sbom_id, ());
+
+        let document_id = curation.document_id();
+
+        for (version, purl, license) in curation.iter() {
+            let license_info = LicenseInfo { license };
+            let node_id = curation.coordinates.node_id(version);
+
+            packages.add(
+                NodeInfoParam {
+                    node_id: node_id.clone(),
+                    name: curation.coordinates.name.clone(),
+                    group: curation.coordinates.namespace.clone(),
+                    version: Some(version.to_string()),
+                    package_license_info: vec![PackageLicensenInfo {
+                        license_id: license_info.uuid(),
+                        license_type: LicenseCategory::Declared,
+                    }],
+                },
+                [PackageReference::Purl {
+                    versioned_purl: purl.version_uuid(),
+                    qualified_purl: purl.qualifier_uuid(),
+                }],
+                Vec::<Checksum>::new(),
+            );
+            relationships.relate(document_id.clone(), Relationship::Describes, node_id);
 
             purls.add(purl);
             licenses.add(&license_info);
         }
+
+        relationships.validate(
+            References::new()
+                .add_source(&[document_id.as_str()])
+                .add_source(&packages),
+        )?;
+
         purls.create(db).await?;
         licenses.create(db).await?;
-
-        // purl_license_assertion::Entity::insert_many(assertions)
-        //     .on_conflict(
-        //         OnConflict::columns([
-        //             purl_license_assertion::Column::SbomId,
-        //             purl_license_assertion::Column::LicenseId,
-        //             purl_license_assertion::Column::VersionedPurlId,
-        //         ])
-        //         .do_nothing()
-        //         .to_owned(),
-        //     )
-        //     .do_nothing()
-        //     .exec(db)
-        //     .await?;
+        packages.create(db).await?;
+        relationships.create(db).await?;
 
         Ok(())
     }
@@ -66,11 +82,12 @@ impl Curation {
         self.coordinates.document_id()
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = (Purl, String)> + '_ {
+    /// Iterate over the curated revisions, along with their pURL and declared license.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Purl, String)> + '_ {
         self.revisions.iter().flat_map(|(version, details)| {
             if let Some(licensed) = &details.licensed {
                 let purl = self.coordinates.base_purl().with_version(version);
-                Some((purl, licensed.declared.clone()))
+                Some((version.as_str(), purl, licensed.declared.clone()))
             } else {
                 None
             }
@@ -120,6 +137,11 @@ impl Coordinates {
             self.name
         )
     }
+
+    /// The node ID of a revision, which is its ClearlyDefined coordinates.
+    pub fn node_id(&self, revision: &str) -> String {
+        format!("{}/{revision}", self.document_id())
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug)]
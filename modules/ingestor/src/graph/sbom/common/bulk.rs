@@ -0,0 +1,262 @@
+//! Bulk loading of very large SBOMs.
+//!
+//! For SBOMs with hundreds of thousands of elements, nodes, packages, and relationships get
+//! copied into staging tables using `COPY FROM STDIN`, and moved from there to the actual tables
+//! by the ingesting transaction. Conflicting rows are skipped, the same way the regular inserts
+//! skip them.
+//!
+//! The staging tables are unlogged, and rows are tagged with the ID of the load. They get removed
+//! once moved, no matter if the ingesting transaction succeeds.
+
+use sea_orm::{ActiveEnum, ConnectionTrait, DbBackend, DbErr, Statement};
+use tracing::instrument;
+use trustify_common::db::{
+    Database,
+    copy::{CopyRows, copy_rows},
+};
+use trustify_entity::{package_relates_to_package, sbom_node, sbom_package};
+use uuid::Uuid;
+
+/// The number of elements (packages, files, and relationships) from which on an SBOM gets bulk
+/// loaded.
+pub const BULK_LOAD_THRESHOLD: usize = 100_000;
+
+/// Loads the nodes, packages, and relationships of a single SBOM.
+#[derive(Clone, Debug)]
+pub struct BulkLoader {
+    db: Database,
+    load_id: Uuid,
+}
+
+impl BulkLoader {
+    pub fn new(db: &Database) -> Self {
+        Self {
+            db: db.clone(),
+            load_id: Uuid::now_v7(),
+        }
+    }
+
+    /// Create a loader if the number of elements of an SBOM reaches the [`BULK_LOAD_THRESHOLD`].
+    pub fn for_size(db: &Database, elements: usize) -> Option<Self> {
+        (elements >= BULK_LOAD_THRESHOLD).then(|| {
+            log::info!("Bulk loading SBOM with {elements} elements");
+            Self::new(db)
+        })
+    }
+
+    #[instrument(skip_all, fields(num=nodes.len()), err(level=tracing::Level::INFO))]
+    pub async fn nodes(
+        &self,
+        nodes: &[sbom_node::ActiveModel],
+        db: &impl ConnectionTrait,
+    ) -> Result<(), DbErr> {
+        let load_id = self.load_id.to_string();
+        let mut rows = CopyRows::new();
+        for node in nodes {
+            let sbom_id = node.sbom_id.as_ref().to_string();
+            rows.row([
+                Some(load_id.as_str()),
+                Some(&sbom_id),
+                Some(node.node_id.as_ref()),
+                Some(node.name.as_ref()),
+            ]);
+        }
+
+        self.load(
+            "sbom_node_load",
+            &["load_id", "sbom_id", "node_id", "name"],
+            rows,
+            r#"
+            INSERT INTO sbom_node (sbom_id, node_id, name)
+            SELECT sbom_id, node_id, name FROM sbom_node_load WHERE load_id = $1
+            ON CONFLICT (sbom_id, node_id) DO NOTHING
+            "#,
+            db,
+        )
+        .await
+    }
+
+    #[instrument(skip_all, fields(num=packages.len()), err(level=tracing::Level::INFO))]
+    pub async fn packages(
+        &self,
+        packages: &[sbom_package::ActiveModel],
+        db: &impl ConnectionTrait,
+    ) -> Result<(), DbErr> {
+        let load_id = self.load_id.to_string();
+        let mut rows = CopyRows::new();
+        for package in packages {
+            let sbom_id = package.sbom_id.as_ref().to_string();
+            rows.row([
+                Some(load_id.as_str()),
+                Some(&sbom_id),
+                Some(package.node_id.as_ref()),
+                package.group.as_ref().as_deref(),
+                package.version.as_ref().as_deref(),
+            ]);
+        }
+
+        self.load(
+            "sbom_package_load",
+            &["load_id", "sbom_id", "node_id", r#""group""#, "version"],
+            rows,
+            r#"
+            INSERT INTO sbom_package (sbom_id, node_id, "group", version)
+            SELECT sbom_id, node_id, "group", version FROM sbom_package_load WHERE load_id = $1
+            ON CONFLICT (sbom_id, node_id) DO NOTHING
+            "#,
+            db,
+        )
+        .await
+    }
+
+    #[instrument(skip_all, fields(num=relationships.len()), err(level=tracing::Level::INFO))]
+    pub async fn relationships(
+        &self,
+        relationships: &[package_relates_to_package::ActiveModel],
+        db: &impl ConnectionTrait,
+    ) -> Result<(), DbErr> {
+        let load_id = self.load_id.to_string();
+        let mut rows = CopyRows::new();
+        for relationship in relationships {
+            let sbom_id = relationship.sbom_id.as_ref().to_string();
+            let rel = relationship.relationship.as_ref().to_value().to_string();
+            rows.row([
+                Some(load_id.as_str()),
+                Some(&sbom_id),
+                Some(relationship.left_node_id.as_ref()),
+                Some(&rel),
+                Some(relationship.right_node_id.as_ref()),
+                relationship.original_relationship.as_ref().as_deref(),
+            ]);
+        }
+
+        self.load(
+            "package_relates_to_package_load",
+            &[
+                "load_id",
+                "sbom_id",
+                "left_node_id",
+                "relationship",
+                "right_node_id",
+                "original_relationship",
+            ],
+            rows,
+            r#"
+            INSERT INTO package_relates_to_package (sbom_id, left_node_id, relationship, right_node_id, original_relationship)
+            SELECT sbom_id, left_node_id, relationship, right_node_id, original_relationship
+            FROM package_relates_to_package_load WHERE load_id = $1
+            ON CONFLICT (sbom_id, left_node_id, relationship, right_node_id) DO NOTHING
+            "#,
+            db,
+        )
+        .await
+    }
+
+    /// Copy rows into a staging table, and move them using the transaction.
+    async fn load(
+        &self,
+        table: &str,
+        columns: &[&str],
+        rows: CopyRows,
+        r#move: &str,
+        db: &impl ConnectionTrait,
+    ) -> Result<(), DbErr> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let result = async {
+            copy_rows(self.db.get_postgres_connection_pool(), table, columns, rows).await?;
+            db.execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#move,
+                [self.load_id.into()],
+            ))
+            .await
+        }
+        .await;
+
+        // the transaction has read the rows by now, or failed
+        let cleanup = self
+            .db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!("DELETE FROM {table} WHERE load_id = $1"),
+                [self.load_id.into()],
+            ))
+            .await;
+
+        result?;
+        cleanup?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sea_orm::{
+        ActiveValue::Set, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, TransactionTrait,
+    };
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_common::id::Id;
+    use trustify_test_context::TrustifyContext;
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn bulk_load_nodes(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        let result = ctx
+            .ingest_document("zookeeper-3.9.2-cyclonedx.json")
+            .await?;
+        let Id::Uuid(sbom_id) = result.id else {
+            panic!("unexpected ID: {}", result.id);
+        };
+        let nodes_of_sbom = || {
+            sbom_node::Entity::find()
+                .filter(sbom_node::Column::SbomId.eq(sbom_id))
+                .count(&ctx.db)
+        };
+        let before = nodes_of_sbom().await?;
+
+        let nodes = (0..1_000)
+            .map(|i| sbom_node::ActiveModel {
+                sbom_id: Set(sbom_id),
+                node_id: Set(format!("bulk-{i}")),
+                name: Set(format!("node\t{i}\\")),
+            })
+            .collect::<Vec<_>>();
+
+        let bulk = BulkLoader::new(&ctx.db);
+        let tx = ctx.db.begin().await?;
+        bulk.nodes(&nodes, &tx).await?;
+        // loading them again skips the existing ones
+        bulk.nodes(&nodes, &tx).await?;
+        tx.commit().await?;
+
+        assert_eq!(nodes_of_sbom().await?, before + 1_000);
+
+        let node = sbom_node::Entity::find_by_id((sbom_id, "bulk-42".to_string()))
+            .one(&ctx.db)
+            .await?
+            .expect("must be loaded");
+        assert_eq!(node.name, "node\t42\\");
+
+        // nothing remains staged
+
+        let staged = ctx
+            .db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "SELECT count(*) AS count FROM sbom_node_load WHERE load_id = $1",
+                [bulk.load_id.into()],
+            ))
+            .await?
+            .expect("must return a row")
+            .try_get::<i64>("", "count")?;
+        assert_eq!(staged, 0);
+
+        Ok(())
+    }
+}
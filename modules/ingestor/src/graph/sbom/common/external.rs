@@ -1,6 +1,7 @@
-use crate::graph::sbom::{ExternalReference, ReferenceSource};
-use sea_orm::{ActiveValue::Set, ConnectionTrait, DbErr, EntityTrait};
+use crate::graph::sbom::{ExternalReference, ReferenceSource, SbomContext};
+use sea_orm::{ActiveValue::Set, ConnectionTrait, DbErr, EntityTrait, Statement};
 use sea_query::OnConflict;
+use tracing::instrument;
 use trustify_common::db::chunk::EntityChunkedIter;
 use trustify_entity::{
     sbom_external_node::{self, DiscriminatorType},
@@ -114,3 +115,28 @@ impl<'a> ReferenceSource<'a> for ExternalNodeCreator {
             })
     }
 }
+
+impl SbomContext {
+    /// Resolve external node references between this SBOM and other, already ingested, SBOMs.
+    ///
+    /// This resolves references of this SBOM pointing to other SBOMs, as well as references of
+    /// other SBOMs pointing to this SBOM. References which can't be resolved (yet) are left
+    /// untouched, and will be resolved once the referenced SBOM gets ingested.
+    ///
+    /// Returns the number of resolved references.
+    #[instrument(skip(self, db), fields(sbom_id=%self.sbom.sbom_id), err(level=tracing::Level::INFO))]
+    pub async fn resolve_external_references(
+        &self,
+        db: &impl ConnectionTrait,
+    ) -> Result<u64, DbErr> {
+        let result = db
+            .execute(Statement::from_sql_and_values(
+                db.get_database_backend(),
+                include_str!("resolve_external.sql"),
+                [self.sbom.sbom_id.into()],
+            ))
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
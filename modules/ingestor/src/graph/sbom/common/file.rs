@@ -1,9 +1,11 @@
-use crate::graph::sbom::{Checksum, ReferenceSource, common::node::NodeCreator};
+use crate::graph::sbom::{
+    BulkLoader, Checksum, PackageLicensenInfo, ReferenceSource, common::node::NodeCreator,
+};
 use sea_orm::{ActiveValue::Set, ConnectionTrait, DbErr, EntityTrait};
 use sea_query::OnConflict;
 use tracing::instrument;
 use trustify_common::db::chunk::EntityChunkedIter;
-use trustify_entity::sbom_file;
+use trustify_entity::{sbom_file, sbom_file_license};
 use uuid::Uuid;
 
 // Creator of files and relationships.
@@ -11,6 +13,7 @@ pub struct FileCreator {
     sbom_id: Uuid,
     nodes: NodeCreator,
     files: Vec<sbom_file::ActiveModel>,
+    licenses: Vec<sbom_file_license::ActiveModel>,
 }
 
 impl FileCreator {
@@ -19,6 +22,7 @@ impl FileCreator {
             sbom_id,
             nodes: NodeCreator::new(sbom_id),
             files: Vec::new(),
+            licenses: Vec::new(),
         }
     }
 
@@ -27,25 +31,53 @@ impl FileCreator {
             sbom_id,
             nodes: NodeCreator::with_capacity(sbom_id, capacity_files),
             files: Vec::with_capacity(capacity_files),
+            licenses: Vec::with_capacity(capacity_files),
         }
     }
 
-    pub fn add<I, C>(&mut self, node_id: String, name: String, checksums: I)
-    where
+    pub fn add<I, C>(
+        &mut self,
+        node_id: String,
+        name: String,
+        checksums: I,
+        licenses: impl IntoIterator<Item = PackageLicensenInfo>,
+    ) where
         I: IntoIterator<Item = C>,
         C: Into<Checksum>,
     {
         self.nodes.add(node_id.clone(), name, checksums);
 
+        for license in licenses {
+            self.licenses.push(sbom_file_license::ActiveModel {
+                sbom_id: Set(self.sbom_id),
+                node_id: Set(node_id.clone()),
+                license_id: Set(license.license_id),
+                license_type: Set(license.license_type),
+            });
+        }
+
         self.files.push(sbom_file::ActiveModel {
             sbom_id: Set(self.sbom_id),
             node_id: Set(node_id),
         });
     }
 
-    #[instrument(skip_all, fields(num=self.files.len()), err(level=tracing::Level::INFO))]
     pub async fn create(self, db: &impl ConnectionTrait) -> Result<(), DbErr> {
-        self.nodes.create(db).await?;
+        self.create_using(None, db).await
+    }
+
+    /// Create the files, using the bulk loader for their nodes if provided.
+    #[instrument(
+        skip_all,
+        fields(num=self.files.len(), num_licenses=self.licenses.len()),
+        err(level=tracing::Level::INFO)
+    )]
+    pub async fn create_using(
+        self,
+        bulk: Option<&BulkLoader>,
+        db: &impl ConnectionTrait,
+    ) -> Result<(), DbErr> {
+        self.nodes.create(bulk, db).await?;
 
         for batch in &self.files.into_iter().chunked() {
             sbom_file::Entity::insert_many(batch)
@@ -59,6 +91,23 @@ impl FileCreator {
                 .await?;
         }
 
+        for batch in &self.licenses.into_iter().chunked() {
+            sbom_file_license::Entity::insert_many(batch)
+                .on_conflict(
+                    OnConflict::columns([
+                        sbom_file_license::Column::SbomId,
+                        sbom_file_license::Column::NodeId,
+                        sbom_file_license::Column::LicenseId,
+                        sbom_file_license::Column::LicenseType,
+                    ])
+                    .do_nothing()
+                    .to_owned(),
+                )
+                .do_nothing()
+                .exec(db)
+                .await?;
+        }
+
         Ok(())
     }
 }
@@ -1,10 +1,8 @@
-use sea_orm::{ActiveValue::Set, ConnectionTrait, DbErr, EntityTrait};
-use sea_query::OnConflict;
+use sea_orm::{ConnectionTrait, DbErr};
 use spdx_expression::SpdxExpression;
 use std::collections::BTreeMap;
 use tracing::instrument;
-use trustify_common::db::chunk::EntityChunkedIter;
-use trustify_entity::license;
+use trustify_common::db::batch::insert_rows;
 use uuid::Uuid;
 
 const NAMESPACE: Uuid = Uuid::from_bytes([
@@ -44,13 +42,27 @@ impl LicenseInfo {
     }
 }
 
+const INSERT_LICENSES: &str = r#"
+INSERT INTO license (id, text, spdx_licenses, spdx_license_exceptions)
+SELECT * FROM jsonb_to_recordset($1) AS t(id uuid, text text, spdx_licenses text[], spdx_license_exceptions text[])
+ON CONFLICT (id) DO NOTHING
+"#;
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct LicenseRow {
+    id: Uuid,
+    text: String,
+    spdx_licenses: Option<Vec<String>>,
+    spdx_license_exceptions: Option<Vec<String>>,
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct LicenseCreator {
     /// The licenses to create.
     ///
     /// Uses a [`BTreeMap`] to ensure we have a stable insertion order, avoiding deadlocks on the
     /// database.
-    pub licenses: BTreeMap<Uuid, license::ActiveModel>,
+    licenses: BTreeMap<Uuid, LicenseRow>,
 }
 
 impl LicenseCreator {
@@ -65,19 +77,11 @@ impl LicenseCreator {
 
         let (spdx_licenses, spdx_exceptions) = info.spdx_info();
 
-        self.licenses.entry(uuid).or_insert(license::ActiveModel {
-            id: Set(uuid),
-            text: Set(info.license.clone()),
-            spdx_licenses: if spdx_licenses.is_empty() {
-                Set(None)
-            } else {
-                Set(Some(spdx_licenses))
-            },
-            spdx_license_exceptions: if spdx_exceptions.is_empty() {
-                Set(None)
-            } else {
-                Set(Some(spdx_exceptions))
-            },
+        self.licenses.entry(uuid).or_insert(LicenseRow {
+            id: uuid,
+            text: info.license.clone(),
+            spdx_licenses: (!spdx_licenses.is_empty()).then_some(spdx_licenses),
+            spdx_license_exceptions: (!spdx_exceptions.is_empty()).then_some(spdx_exceptions),
         });
     }
 
@@ -86,25 +90,18 @@ impl LicenseCreator {
     where
         C: ConnectionTrait,
     {
-        for batch in &self.licenses.into_values().chunked() {
-            license::Entity::insert_many(batch)
-                .on_conflict(
-                    OnConflict::columns([license::Column::Id])
-                        .do_nothing()
-                        .to_owned(),
-                )
-                .do_nothing()
-                .exec(db)
-                .await?;
-        }
-
-        Ok(())
+        insert_rows(db, INSERT_LICENSES, self.licenses.into_values()).await
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::graph::sbom::LicenseInfo;
+    use super::*;
+    use sea_orm::EntityTrait;
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_entity::license;
+    use trustify_test_context::TrustifyContext;
 
     #[test]
     fn stable_uuid() {
@@ -125,4 +122,47 @@ mod test {
             assert_eq!(original.uuid(), new.uuid());
         }
     }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn create(ctx: &TrustifyContext) -> anyhow::Result<()> {
+        let spdx = LicenseInfo {
+            license: "Apache-2.0 WITH LLVM-exception OR MIT".to_string(),
+        };
+        let other = LicenseInfo {
+            license: "LicenseRef-custom".to_string(),
+        };
+
+        for _ in 0..2 {
+            let mut creator = LicenseCreator::new();
+            creator.add(&spdx);
+            creator.add(&other);
+            creator.create(&ctx.db).await?;
+        }
+
+        assert_eq!(license::Entity::find().all(&ctx.db).await?.len(), 2);
+
+        let result = license::Entity::find_by_id(spdx.uuid())
+            .one(&ctx.db)
+            .await?
+            .expect("must be found");
+        assert_eq!(result.text, spdx.license);
+        assert_eq!(
+            result.spdx_licenses,
+            Some(vec!["Apache-2.0".to_string(), "MIT".to_string()])
+        );
+        assert_eq!(
+            result.spdx_license_exceptions,
+            Some(vec!["LLVM-exception".to_string()])
+        );
+
+        let result = license::Entity::find_by_id(other.uuid())
+            .one(&ctx.db)
+            .await?
+            .expect("must be found");
+        assert_eq!(result.spdx_licenses, None);
+        assert_eq!(result.spdx_license_exceptions, None);
+
+        Ok(())
+    }
 }
@@ -1,4 +1,4 @@
-use crate::graph::sbom::{Checksum, ReferenceSource};
+use crate::graph::sbom::{BulkLoader, Checksum, ReferenceSource};
 use sea_orm::{ActiveValue::Set, ConnectionTrait, DbErr, EntityTrait};
 use sea_query::OnConflict;
 use tracing::instrument;
@@ -52,18 +52,27 @@ impl NodeCreator {
         });
     }
 
+    /// Create the nodes, using the bulk loader if provided.
     #[instrument(skip_all, fields(num=self.nodes.len()), err(level=tracing::Level::INFO))]
-    pub async fn create(self, db: &impl ConnectionTrait) -> Result<(), DbErr> {
-        for batch in &self.nodes.into_iter().chunked() {
-            sbom_node::Entity::insert_many(batch)
-                .on_conflict(
-                    OnConflict::columns([sbom_node::Column::SbomId, sbom_node::Column::NodeId])
-                        .do_nothing()
-                        .to_owned(),
-                )
-                .do_nothing()
-                .exec(db)
-                .await?;
+    pub async fn create(
+        self,
+        bulk: Option<&BulkLoader>,
+        db: &impl ConnectionTrait,
+    ) -> Result<(), DbErr> {
+        if let Some(bulk) = bulk {
+            bulk.nodes(&self.nodes, db).await?;
+        } else {
+            for batch in &self.nodes.into_iter().chunked() {
+                sbom_node::Entity::insert_many(batch)
+                    .on_conflict(
+                        OnConflict::columns([sbom_node::Column::SbomId, sbom_node::Column::NodeId])
+                            .do_nothing()
+                            .to_owned(),
+                    )
+                    .do_nothing()
+                    .exec(db)
+                    .await?;
+            }
         }
 
         for batch in &self.checksums.into_iter().chunked() {
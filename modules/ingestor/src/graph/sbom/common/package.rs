@@ -1,5 +1,5 @@
 use crate::graph::sbom::common::node::NodeCreator;
-use crate::graph::sbom::{Checksum, ReferenceSource};
+use crate::graph::sbom::{BulkLoader, Checksum, ReferenceSource};
 use sea_orm::{ActiveValue::Set, ConnectionTrait, DbErr, EntityTrait};
 use sea_query::OnConflict;
 use tracing::instrument;
@@ -116,6 +116,11 @@ impl PackageCreator {
         }
     }
 
+    pub async fn create(self, db: &impl ConnectionTrait) -> Result<(), DbErr> {
+        self.create_using(None, db).await
+    }
+
+    /// Create the packages, using the bulk loader if provided.
     #[instrument(
         skip_all,
         fields(
@@ -125,22 +130,30 @@ impl PackageCreator {
         ),
         err(level=tracing::Level::INFO)
     )]
-    pub async fn create(self, db: &impl ConnectionTrait) -> Result<(), DbErr> {
-        self.nodes.create(db).await?;
-
-        for batch in &self.packages.into_iter().chunked() {
-            sbom_package::Entity::insert_many(batch)
-                .on_conflict(
-                    OnConflict::columns([
-                        sbom_package::Column::SbomId,
-                        sbom_package::Column::NodeId,
-                    ])
+    pub async fn create_using(
+        self,
+        bulk: Option<&BulkLoader>,
+        db: &impl ConnectionTrait,
+    ) -> Result<(), DbErr> {
+        self.nodes.create(bulk, db).await?;
+
+        if let Some(bulk) = bulk {
+            bulk.packages(&self.packages, db).await?;
+        } else {
+            for batch in &self.packages.into_iter().chunked() {
+                sbom_package::Entity::insert_many(batch)
+                    .on_conflict(
+                        OnConflict::columns([
+                            sbom_package::Column::SbomId,
+                            sbom_package::Column::NodeId,
+                        ])
+                        .do_nothing()
+                        .to_owned(),
+                    )
                     .do_nothing()
-                    .to_owned(),
-                )
-                .do_nothing()
-                .exec(db)
-                .await?;
+                    .exec(db)
+                    .await?;
+            }
         }
 
         for batch in &self.purl_refs.into_iter().chunked() {
@@ -1,4 +1,4 @@
-use crate::graph::sbom::{Discriminator, ExternalNodeCreator};
+use crate::graph::sbom::{BulkLoader, Discriminator, ExternalNodeCreator};
 use anyhow::bail;
 use sea_orm::{ActiveValue::Set, ConnectionTrait, DbErr, EntityTrait};
 use sea_query::OnConflict;
@@ -127,6 +127,19 @@ impl<ER: ExternalReferenceProcessor> RelationshipCreator<ER> {
     /// It is possible to record invalid relationship targets, which might fail the actual creation
     /// process later on. It is possible to validate relationships using [`Self::validate`].
     pub fn relate(&mut self, left: String, rel: Relationship, right: String) {
+        self.relate_original(left, rel, right, None)
+    }
+
+    /// Record a relationship, along with the name the SBOM used for it.
+    ///
+    /// See [`Self::relate`].
+    pub fn relate_original(
+        &mut self,
+        left: String,
+        rel: Relationship,
+        right: String,
+        original: Option<String>,
+    ) {
         // The idea of `NOASSERTION` is to state that there is a relationship, but the element it
         // relates to is unknown.
         //
@@ -153,9 +166,17 @@ impl<ER: ExternalReferenceProcessor> RelationshipCreator<ER> {
             left_node_id: Set(left),
             relationship: Set(rel),
             right_node_id: Set(right),
+            original_relationship: Set(original),
         });
     }
 
+    /// Check if a node ID refers to a node of an external document.
+    pub fn is_external(&self, node_id: &str) -> bool {
+        self.external_references
+            .eval_external_node(node_id)
+            .is_some()
+    }
+
     fn handle_ext(&mut self, node_id: &str) {
         if let Some(externals) = self.external_references.eval_external_node(node_id) {
             self.externals.add(node_id, externals);
@@ -187,25 +208,38 @@ impl<ER: ExternalReferenceProcessor> RelationshipCreator<ER> {
         Ok(())
     }
 
-    #[instrument(skip_all, fields(num=self.rels.len()), err(level=tracing::Level::INFO))]
     pub async fn create(self, db: &impl ConnectionTrait) -> Result<(), DbErr> {
+        self.create_using(None, db).await
+    }
+
+    /// Create the relationships, using the bulk loader if provided.
+    #[instrument(skip_all, fields(num=self.rels.len()), err(level=tracing::Level::INFO))]
+    pub async fn create_using(
+        self,
+        bulk: Option<&BulkLoader>,
+        db: &impl ConnectionTrait,
+    ) -> Result<(), DbErr> {
         self.externals.create(db).await?;
 
-        for batch in &self.rels.into_iter().chunked() {
-            package_relates_to_package::Entity::insert_many(batch)
-                .on_conflict(
-                    OnConflict::columns([
-                        package_relates_to_package::Column::SbomId,
-                        package_relates_to_package::Column::LeftNodeId,
-                        package_relates_to_package::Column::Relationship,
-                        package_relates_to_package::Column::RightNodeId,
-                    ])
+        if let Some(bulk) = bulk {
+            bulk.relationships(&self.rels, db).await?;
+        } else {
+            for batch in &self.rels.into_iter().chunked() {
+                package_relates_to_package::Entity::insert_many(batch)
+                    .on_conflict(
+                        OnConflict::columns([
+                            package_relates_to_package::Column::SbomId,
+                            package_relates_to_package::Column::LeftNodeId,
+                            package_relates_to_package::Column::Relationship,
+                            package_relates_to_package::Column::RightNodeId,
+                        ])
+                        .do_nothing()
+                        .to_owned(),
+                    )
                     .do_nothing()
-                    .to_owned(),
-                )
-                .do_nothing()
-                .exec(db)
-                .await?;
+                    .exec(db)
+                    .await?;
+            }
         }
 
         Ok(())
@@ -0,0 +1,28 @@
+-- Resolve external node references, in both directions, for the SBOM given by $1.
+--
+-- Outgoing: external nodes of $1 pointing to SBOMs which have already been ingested.
+-- Incoming: external nodes of other SBOMs pointing to $1.
+UPDATE sbom_external_node AS ext
+SET target_sbom_id = target.sbom_id
+FROM sbom AS target
+         INNER JOIN source_document AS doc ON doc.id = target.source_document_id
+WHERE ext.target_sbom_id IS NULL
+  AND ext.sbom_id <> target.sbom_id
+  AND (ext.sbom_id = $1 OR target.sbom_id = $1)
+  AND (
+    -- SPDX: the discriminator is the digest of the referenced document
+    (
+        ext.external_type = 0 AND (
+            (ext.discriminator_type = 0 AND ext.discriminator_value = doc.sha256) OR
+            (ext.discriminator_type = 1 AND ext.discriminator_value = doc.sha384) OR
+            (ext.discriminator_type = 2 AND ext.discriminator_value = doc.sha512)
+        )
+    )
+    OR
+    -- CycloneDX: serial number and version make up the document id
+    (
+        ext.external_type = 1
+        AND ext.discriminator_type = 3
+        AND target.document_id = 'urn:cdx:' || ext.external_doc_ref || '/' || ext.discriminator_value
+    )
+  );
@@ -4,9 +4,9 @@ use crate::{
         product::ProductInformation,
         purl::creator::PurlCreator,
         sbom::{
-            CycloneDx as CycloneDxProcessor, LicenseCreator, LicenseInfo, NodeInfoParam,
-            PackageCreator, PackageLicensenInfo, PackageReference, References, RelationshipCreator,
-            SbomContext, SbomInformation,
+            BulkLoader, CycloneDx as CycloneDxProcessor, FileCreator, LicenseCreator, LicenseInfo,
+            NodeInfoParam, PackageCreator, PackageLicensenInfo, PackageReference, References,
+            RelationshipCreator, SbomContext, SbomInformation,
             processor::{
                 InitContext, PostContext, Processor, RedHatProductComponentRelationships,
                 RunProcessors,
@@ -231,9 +231,19 @@ impl SbomContext {
             }
         }
 
-        // create
+        // create, bulk loading very large SBOMs
+
+        let bulk = BulkLoader::for_size(
+            &self.graph.db,
+            creator.components.len() + creator.relations.len(),
+        );
+        creator
+            .create(bulk.as_ref(), connection, &mut processors)
+            .await?;
+
+        // link external references with other SBOMs
 
-        creator.create(connection, &mut processors).await?;
+        self.resolve_external_references(connection).await?;
 
         // done
 
@@ -264,6 +274,16 @@ impl<'a> Creator<'a> {
 
     pub fn add(&mut self, component: &'a Component) {
         self.components.push(component);
+
+        // files nested in a component are contained by it
+        if let Some(parent) = &component.bom_ref {
+            for file in component.components.iter().flatten() {
+                if let (Some(file), "file") = (&file.bom_ref, file.type_.as_str()) {
+                    self.relate(parent.clone(), Relationship::Contains, file.clone());
+                }
+            }
+        }
+
         self.extend(component.components.iter().flatten());
     }
 
@@ -280,9 +300,10 @@ impl<'a> Creator<'a> {
         self.relations.push((left, rel, right));
     }
 
-    #[instrument(skip(self, db, processors), err(level=tracing::Level::INFO))]
+    #[instrument(skip(self, bulk, db, processors), err(level=tracing::Level::INFO))]
     pub async fn create(
         self,
+        bulk: Option<&BulkLoader>,
         db: &impl ConnectionTrait,
         processors: &mut [Box<dyn Processor>],
     ) -> Result<(), Error> {
@@ -294,6 +315,7 @@ impl<'a> Creator<'a> {
             self.relations.len(),
             CycloneDxProcessor,
         );
+        let mut files = FileCreator::new(self.sbom_id);
         let mut licenses = LicenseCreator::new();
 
         for comp in self.components {
@@ -302,6 +324,7 @@ impl<'a> Creator<'a> {
                 &mut purls,
                 &mut licenses,
                 &mut packages,
+                &mut files,
                 &mut relationships,
             );
             creator.create(comp);
@@ -326,7 +349,8 @@ impl<'a> Creator<'a> {
 
         let sources = References::new()
             .add_source(&[CYCLONEDX_DOC_REF])
-            .add_source(&packages);
+            .add_source(&packages)
+            .add_source(&files);
         relationships
             .validate(sources)
             .map_err(Error::InvalidContent)?;
@@ -336,8 +360,9 @@ impl<'a> Creator<'a> {
         purls.create(db).await?;
         cpes.create(db).await?;
         licenses.create(db).await?;
-        packages.create(db).await?;
-        relationships.create(db).await?;
+        packages.create_using(bulk, db).await?;
+        files.create_using(bulk, db).await?;
+        relationships.create_using(bulk, db).await?;
 
         // done
 
@@ -350,6 +375,7 @@ struct ComponentCreator<'a> {
     purls: &'a mut PurlCreator,
     licenses: &'a mut LicenseCreator,
     packages: &'a mut PackageCreator,
+    files: &'a mut FileCreator,
     relationships: &'a mut RelationshipCreator<CycloneDxProcessor>,
 
     refs: Vec<PackageReference>,
@@ -361,6 +387,7 @@ impl<'a> ComponentCreator<'a> {
         purls: &'a mut PurlCreator,
         licenses: &'a mut LicenseCreator,
         packages: &'a mut PackageCreator,
+        files: &'a mut FileCreator,
         relationships: &'a mut RelationshipCreator<CycloneDxProcessor>,
     ) -> Self {
         Self {
@@ -369,6 +396,7 @@ impl<'a> ComponentCreator<'a> {
             licenses,
             refs: Default::default(),
             packages,
+            files,
             relationships,
         }
     }
@@ -381,6 +409,24 @@ impl<'a> ComponentCreator<'a> {
 
         let licenses_uuid = self.add_license(comp);
 
+        let cyclone_licenses = licenses_uuid
+            .iter()
+            .map(|l| PackageLicensenInfo {
+                license_id: *l,
+                license_type: LicenseCategory::Declared,
+            })
+            .collect::<Vec<_>>();
+
+        if comp.type_ == "file" {
+            self.files.add(
+                node_id,
+                comp.name.to_string(),
+                comp.hashes.clone().into_iter().flatten(),
+                cyclone_licenses,
+            );
+            return;
+        }
+
         if let Some(cpe) = &comp.cpe {
             if let Ok(cpe) = Cpe::from_str(cpe.as_ref()) {
                 self.add_cpe(cpe);
@@ -419,14 +465,6 @@ impl<'a> ComponentCreator<'a> {
             }
         }
 
-        let cyclone_licenses = licenses_uuid
-            .iter()
-            .map(|l| PackageLicensenInfo {
-                license_id: *l,
-                license_type: LicenseCategory::Declared,
-            })
-            .collect::<Vec<_>>();
-
         self.packages.add(
             NodeInfoParam {
                 node_id: node_id.clone(),
@@ -456,6 +494,7 @@ impl<'a> ComponentCreator<'a> {
                 self.purls,
                 self.licenses,
                 self.packages,
+                self.files,
                 self.relationships,
             ));
 
@@ -483,6 +522,7 @@ impl<'a> ComponentCreator<'a> {
                 self.purls,
                 self.licenses,
                 self.packages,
+                self.files,
                 self.relationships,
             ));
 
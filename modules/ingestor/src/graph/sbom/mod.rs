@@ -17,6 +17,7 @@ use crate::{
         cpe::CpeContext,
         product::{ProductContext, product_version::ProductVersionContext},
         purl::{creator::PurlCreator, qualified_package::QualifiedPackageContext},
+        source_documents_by_digest, tenant_condition,
     },
 };
 use cpe::uri::OwnedUri;
@@ -32,6 +33,7 @@ use std::{
 };
 use time::OffsetDateTime;
 use tracing::instrument;
+use trustify_auth::tenant::TENANT_LABEL;
 use trustify_common::{cpe::Cpe, hashing::Digests, purl::Purl, sbom::SbomLocator};
 use trustify_entity::{
     self as entity, labels::Labels, license, relationship::Relationship, sbom, sbom_node,
@@ -76,6 +78,7 @@ impl Graph {
     pub async fn get_sbom_by_digest<C: ConnectionTrait>(
         &self,
         digest: &str,
+        tenant: Option<&str>,
         connection: &C,
     ) -> Result<Option<SbomContext>, Error> {
         Ok(sbom::Entity::find()
@@ -86,11 +89,46 @@ impl Graph {
                     .add(source_document::Column::Sha384.eq(digest.to_string()))
                     .add(source_document::Column::Sha512.eq(digest.to_string())),
             )
+            .filter(sbom::Column::DeletedAt.is_null())
+            .filter(tenant_condition(
+                (sbom::Entity, sbom::Column::Labels),
+                tenant,
+            ))
             .one(connection)
             .await?
             .map(|sbom| SbomContext::new(self, sbom)))
     }
 
+    /// Get the SBOM with the digest, restoring it in case it was marked as deleted.
+    ///
+    /// Ingesting a deleted document again means it is wanted again. As it still holds on to its
+    /// source document, it can't be ingested as a new one anyway.
+    #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
+    pub async fn get_or_restore_sbom_by_digest<C: ConnectionTrait>(
+        &self,
+        digest: &str,
+        tenant: Option<&str>,
+        connection: &C,
+    ) -> Result<Option<SbomContext>, Error> {
+        let restored = sbom::Entity::update_many()
+            .col_expr(sbom::Column::DeletedAt, Expr::value(None::<OffsetDateTime>))
+            .filter(sbom::Column::SourceDocumentId.in_subquery(source_documents_by_digest(digest)))
+            .filter(sbom::Column::DeletedAt.is_not_null())
+            .filter(tenant_condition(
+                (sbom::Entity, sbom::Column::Labels),
+                tenant,
+            ))
+            .exec_with_returning(connection)
+            .await?;
+
+        if let Some(sbom) = restored.into_iter().next() {
+            log::info!("Restored deleted SBOM: {}", sbom.sbom_id);
+            return Ok(Some(SbomContext::new(self, sbom)));
+        }
+
+        self.get_sbom_by_digest(digest, tenant, connection).await
+    }
+
     #[instrument(skip(connection, info), err(level=tracing::Level::INFO))]
     pub async fn ingest_sbom<C>(
         &self,
@@ -111,10 +149,13 @@ impl Graph {
             suppliers,
             data_licenses,
         } = info.into();
+        let labels = labels.into();
+        let tenant = labels.get(TENANT_LABEL).map(String::as_str);
 
         let new_id = match self
             .create_doc(digests, connection, async |sha256| {
-                self.get_sbom_by_digest(&sha256, connection).await
+                self.get_or_restore_sbom_by_digest(&sha256, tenant, connection)
+                    .await
             })
             .await?
         {
@@ -135,7 +176,9 @@ impl Graph {
             suppliers: Set(suppliers),
 
             source_document_id: Set(Some(new_id)),
-            labels: Set(labels.into()),
+            external_id: Set(None),
+            deleted_at: Set(None),
+            labels: Set(labels),
             data_licenses: Set(data_licenses),
         };
 
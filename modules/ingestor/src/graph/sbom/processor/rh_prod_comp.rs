@@ -86,6 +86,7 @@ impl super::Processor for RedHatProductComponentRelationships {
                 left_node_id: Set(left_node_id),
                 relationship: Set(relationship),
                 right_node_id: Set(right_node_id),
+                original_relationship: _,
             } = rel
             else {
                 continue;
@@ -132,6 +133,7 @@ impl super::Processor for RedHatProductComponentRelationships {
                 left_node_id: Set(comp_node_id.to_string()),
                 relationship: Set(Relationship::Package),
                 right_node_id: Set(ext_comp_node_id),
+                original_relationship: Set(None),
             });
         }
 
@@ -165,6 +167,7 @@ fn is_relevant(
                 left_node_id: Set(left_node_id),
                 relationship: Set(relationship),
                 right_node_id: Set(right_node_id),
+                original_relationship: _,
             } = relationship
             else {
                 continue;
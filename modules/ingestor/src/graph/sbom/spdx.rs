@@ -4,9 +4,9 @@ use crate::{
         product::ProductInformation,
         purl::creator::PurlCreator,
         sbom::{
-            FileCreator, LicenseCreator, LicenseInfo, LicensingInfo, LicensingInfoCreator,
-            NodeInfoParam, PackageCreator, PackageLicensenInfo, PackageReference, References,
-            RelationshipCreator, SbomContext, SbomInformation, Spdx,
+            BulkLoader, FileCreator, LicenseCreator, LicenseInfo, LicensingInfo,
+            LicensingInfoCreator, NodeInfoParam, PackageCreator, PackageLicensenInfo,
+            PackageReference, References, RelationshipCreator, SbomContext, SbomInformation, Spdx,
             processor::{
                 InitContext, PostContext, Processor, RedHatProductComponentRelationships,
                 RunProcessors,
@@ -112,6 +112,15 @@ impl SbomContext {
 
         check::spdx::all(warnings, &sbom_data);
 
+        // very large SBOMs get bulk loaded
+
+        let bulk = BulkLoader::for_size(
+            &self.graph.db,
+            sbom_data.package_information.len()
+                + sbom_data.file_information.len()
+                + sbom_data.relationships.len(),
+        );
+
         // processors
 
         // TODO: find a way to dynamically set up processors
@@ -170,14 +179,20 @@ impl SbomContext {
                 .clone(),
         );
 
+        // relationships of types we don't know, recorded once all elements are known
+        let mut uncommon = vec![];
+
         for rel in &sbom_data.relationships {
             log::debug!("Relationship: {rel:?}");
 
+            let original = relationship_type_name(&rel.relationship_type);
+
             let Ok(SpdxRelationship(left, rel, right)) = rel.try_into() else {
+                uncommon.push((rel, original));
                 continue;
             };
 
-            relationships.relate(left.to_string(), rel, right.to_string());
+            relationships.relate_original(left.to_string(), rel, right.to_string(), Some(original));
 
             if rel == Relationship::Describes {
                 product_packages.push(right.to_string());
@@ -315,10 +330,31 @@ impl SbomContext {
             FileCreator::with_capacity(self.sbom.sbom_id, sbom_data.file_information.len());
 
         for file in sbom_data.file_information {
+            // the concluded license, and the licenses found in the file itself
+            let file_licenses = file
+                .concluded_license
+                .iter()
+                .map(|license| (license.to_string(), LicenseCategory::Concluded))
+                .chain(
+                    file.license_information_in_file
+                        .iter()
+                        .map(|license| (license.to_string(), LicenseCategory::Declared)),
+                )
+                .map(|(license, license_type)| {
+                    let license = LicenseInfo { license };
+                    licenses.add(&license);
+                    PackageLicensenInfo {
+                        license_id: license.uuid(),
+                        license_type,
+                    }
+                })
+                .collect::<Vec<_>>();
+
             files.add(
                 file.file_spdx_identifier,
                 file.file_name,
                 file.file_checksum,
+                file_licenses,
             );
         }
 
@@ -350,15 +386,41 @@ impl SbomContext {
             .add_source(&doc_id)
             .add_source(&packages)
             .add_source(&files);
+
+        for (rel, original) in uncommon {
+            let left = rel.spdx_element_id.as_str();
+            let right = rel.related_spdx_element.as_str();
+
+            // unlike the types we know, those are optional, so we don't fail on invalid references
+            if ![left, right]
+                .iter()
+                .all(|id| sources.refs.contains(id) || relationships.is_external(id))
+            {
+                log::debug!("Dropping relationship of type {original}: {left} -> {right}");
+                continue;
+            }
+
+            relationships.relate_original(
+                left.to_string(),
+                Relationship::Undefined,
+                right.to_string(),
+                Some(original),
+            );
+        }
+
         relationships
             .validate(sources)
             .map_err(Error::InvalidContent)?;
 
         // create packages, files, and relationships
 
-        packages.create(db).await?;
-        files.create(db).await?;
-        relationships.create(db).await?;
+        packages.create_using(bulk.as_ref(), db).await?;
+        files.create_using(bulk.as_ref(), db).await?;
+        relationships.create_using(bulk.as_ref(), db).await?;
+
+        // link external references with other SBOMs
+
+        self.resolve_external_references(db).await?;
 
         // done
 
@@ -366,6 +428,14 @@ impl SbomContext {
     }
 }
 
+/// The name of a relationship type, as used in SPDX documents (like `DEPENDS_ON`).
+fn relationship_type_name(rel: &RelationshipType) -> String {
+    match serde_json::to_value(rel) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => format!("{rel:?}"),
+    }
+}
+
 pub struct SpdxRelationship<'spdx>(pub &'spdx str, pub Relationship, pub &'spdx str);
 
 impl<'spdx> TryFrom<(&'spdx str, &'spdx RelationshipType, &'spdx str)> for SpdxRelationship<'spdx> {
@@ -10,7 +10,7 @@ use sea_query::{JoinType, OnConflict};
 use std::fmt::{Debug, Formatter};
 use time::OffsetDateTime;
 use tracing::instrument;
-use trustify_common::db::chunk::EntityChunkedIter;
+use trustify_common::{cwe, db::chunk::EntityChunkedIter};
 use trustify_entity::{advisory, advisory_vulnerability, vulnerability, vulnerability_description};
 use uuid::Uuid;
 
@@ -83,7 +83,10 @@ impl Graph {
             published: Set(information.published),
             modified: Set(information.modified),
             withdrawn: Set(information.withdrawn),
-            cwes: Set(information.cwes),
+            cwes: Set(information
+                .cwes
+                .as_ref()
+                .and_then(|cwes| cwe::normalize_all(cwes.iter().map(String::as_str)))),
         };
 
         let result = vulnerability::Entity::insert(entity)
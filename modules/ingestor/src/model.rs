@@ -1,4 +1,7 @@
+use time::OffsetDateTime;
 use trustify_common::id::Id;
+use trustify_entity::ingestion_job;
+use uuid::Uuid;
 
 /// The result of the ingestion process
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
@@ -10,4 +13,105 @@ pub struct IngestResult {
     /// Warnings that occurred during the import process
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub warnings: Vec<String>,
+    /// Whether the document was already ingested before, resulting in the existing one
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub duplicate: bool,
+}
+
+/// How an uploaded document gets ingested: while handling the request (`sync`), or queued and
+/// ingested in the background (`async`)
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum IngestionMode {
+    #[default]
+    Sync,
+    Async,
+}
+
+/// An accepted request for ingesting a document asynchronously
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct IngestionJobAccepted {
+    /// The ID of the job, processing the document
+    pub job_id: Uuid,
+}
+
+/// The state of an asynchronous ingestion job
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+pub struct IngestionJob {
+    /// The ID of the job
+    pub id: Uuid,
+    /// The current state of the job
+    pub state: JobState,
+    /// The time the document was submitted
+    #[serde(with = "time::serde::rfc3339")]
+    pub submitted: OffsetDateTime,
+    /// The number of times processing the document was started
+    ///
+    /// Jobs get picked up again if processing them was interrupted, until reaching a limit.
+    pub attempts: u32,
+    /// The time processing the document started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub started: Option<OffsetDateTime>,
+    /// The time processing the document finished
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub finished: Option<OffsetDateTime>,
+    /// The result of the ingestion, if it succeeded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<IngestResult>,
+    /// The error of the ingestion, if it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The state of an ingestion job
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, utoipa::ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum JobState {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl From<ingestion_job::State> for JobState {
+    fn from(value: ingestion_job::State) -> Self {
+        match value {
+            ingestion_job::State::Pending => Self::Pending,
+            ingestion_job::State::Running => Self::Running,
+            ingestion_job::State::Succeeded => Self::Succeeded,
+            ingestion_job::State::Failed => Self::Failed,
+        }
+    }
+}
+
+impl From<ingestion_job::Model> for IngestionJob {
+    fn from(value: ingestion_job::Model) -> Self {
+        Self {
+            id: value.id,
+            state: value.state.into(),
+            submitted: value.submitted,
+            attempts: value.attempts.try_into().unwrap_or_default(),
+            started: value.started,
+            finished: value.finished,
+            // the result was stored by us, failing to parse it back means it's not there
+            result: value
+                .result
+                .and_then(|result| serde_json::from_value(result).ok()),
+            error: value.error,
+        }
+    }
 }
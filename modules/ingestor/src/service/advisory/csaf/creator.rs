@@ -17,7 +17,9 @@ use crate::{
     },
 };
 use csaf::{Csaf, definitions::ProductIdT};
-use sea_orm::{ActiveValue::Set, ConnectionTrait, EntityTrait};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, prelude::Expr,
+};
 use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use tracing::instrument;
@@ -96,6 +98,7 @@ impl<'a> StatusCreator<'a> {
 
         let mut org_cache: HashMap<String, organization::Model> = HashMap::new();
         let mut product_models = Vec::new();
+        let mut product_parents = HashMap::new();
         let mut version_ranges = Vec::new();
         let mut product_version_ranges = Vec::new();
 
@@ -148,13 +151,38 @@ impl<'a> StatusCreator<'a> {
 
             let product_id = ProductInformation::create_uuid(org_id, product.product.clone());
 
+            // Create the product families as the ancestors of the product
+            let mut parent_id = None;
+            for family in &product.families {
+                let family_id = ProductInformation::create_uuid(org_id, family.clone());
+                if family_id == product_id || parent_id == Some(family_id) {
+                    continue;
+                }
+
+                product_models.push(product::ActiveModel {
+                    id: Set(family_id),
+                    name: Set(family.clone()),
+                    vendor_id: Set(org_id),
+                    cpe_key: Set(None),
+                    parent_id: Set(None),
+                });
+                if let Some(parent_id) = parent_id {
+                    product_parents.insert(family_id, parent_id);
+                }
+                parent_id = Some(family_id);
+            }
+
             let product_entity = product::ActiveModel {
                 id: Set(product_id),
                 name: Set(product.product.clone()),
                 vendor_id: Set(org_id),
                 cpe_key: Set(product_cpe_key),
+                parent_id: Set(None),
             };
             product_models.push(product_entity.clone());
+            if let Some(parent_id) = parent_id {
+                product_parents.insert(product_id, parent_id);
+            }
 
             if let Some(ref info) = product.version {
                 let range = ProductVersionRange {
@@ -270,6 +298,15 @@ impl<'a> StatusCreator<'a> {
                 .await?;
         }
 
+        // Products might exist already, so link them to their families after creating them
+        for (id, parent_id) in product_parents {
+            product::Entity::update_many()
+                .col_expr(product::Column::ParentId, Expr::value(parent_id))
+                .filter(product::Column::Id.eq(id))
+                .exec(connection)
+                .await?;
+        }
+
         for batch in &version_ranges.chunked() {
             version_range::Entity::insert_many(batch)
                 .on_conflict_do_nothing()
@@ -9,7 +9,11 @@ use crate::{
     model::IngestResult,
     service::{
         Error, Warnings,
-        advisory::csaf::{StatusCreator, util::gen_identifier},
+        advisory::csaf::{
+            StatusCreator,
+            util::{gen_identifier, gen_supersedes},
+        },
+        audit::Upload,
     },
 };
 use csaf::{
@@ -23,9 +27,10 @@ use semver::Version;
 use std::{fmt::Debug, str::FromStr};
 use time::OffsetDateTime;
 use tracing::instrument;
-use trustify_common::{hashing::Digests, id::Id};
+use trustify_auth::tenant::TENANT_LABEL;
+use trustify_common::{db::RecomputeProductStatusSummary, hashing::Digests, id::Id};
 use trustify_cvss::cvss3::Cvss3Base;
-use trustify_entity::labels::Labels;
+use trustify_entity::{document_audit::DocumentKind, labels::Labels};
 
 struct Information<'a>(&'a Csaf);
 
@@ -75,11 +80,21 @@ fn parse_csaf_version(csaf: &Csaf) -> Option<Version> {
 
 pub struct CsafLoader<'g> {
     graph: &'g Graph,
+    upload: Option<&'g Upload>,
 }
 
 impl<'g> CsafLoader<'g> {
     pub fn new(graph: &'g Graph) -> Self {
-        Self { graph }
+        Self {
+            graph,
+            upload: None,
+        }
+    }
+
+    /// Record the upload of the document, as part of ingesting it.
+    pub fn upload(mut self, upload: Option<&'g Upload>) -> Self {
+        self.upload = upload;
+        self
     }
 
     #[instrument(skip(self, csaf), err(level=tracing::Level::INFO))]
@@ -97,12 +112,21 @@ impl<'g> CsafLoader<'g> {
         let labels = labels.into().add("type", "csaf");
 
         let sha256 = digests.sha256.encode_hex::<String>();
-        if let Some(found) = self.graph.get_advisory_by_digest(&sha256, &tx).await? {
+        if let Some(found) = self
+            .graph
+            .get_or_restore_advisory_by_digest(
+                &sha256,
+                labels.get(TENANT_LABEL).map(String::as_str),
+                &tx,
+            )
+            .await?
+        {
             // we already have the exact same document.
             return Ok(IngestResult {
                 id: Id::Uuid(found.advisory.id),
                 document_id: Some(advisory_id),
                 warnings: warnings.into(),
+                duplicate: true,
             });
         }
 
@@ -111,17 +135,26 @@ impl<'g> CsafLoader<'g> {
             .ingest_advisory(&advisory_id, labels, digests, Information(&csaf), &tx)
             .await?;
 
+        advisory.set_supersedes(&gen_supersedes(&csaf), &tx).await?;
+
         for vuln in csaf.vulnerabilities.iter().flatten() {
             self.ingest_vulnerability(&csaf, &advisory, vuln, &warnings, &tx)
                 .await?;
         }
 
+        if let Some(upload) = self.upload {
+            upload
+                .record(DocumentKind::Advisory, advisory.advisory.id, &tx)
+                .await?;
+        }
+
         tx.commit().await?;
 
         Ok(IngestResult {
             id: Id::Uuid(advisory.advisory.id),
             document_id: Some(advisory_id),
             warnings: warnings.into(),
+            duplicate: false,
         })
     }
 
@@ -216,6 +249,18 @@ impl<'g> CsafLoader<'g> {
 
         creator.create(self.graph, connection).await?;
 
+        // the statements might change the effective statuses of the products
+        RecomputeProductStatusSummary::execute(
+            connection,
+            Some(
+                &advisory_vulnerability
+                    .advisory_vulnerability
+                    .vulnerability_id,
+            ),
+            None,
+        )
+        .await?;
+
         Ok(())
     }
 }
@@ -245,7 +290,7 @@ mod test {
         assert!(loaded_vulnerability.is_some());
 
         let loaded_advisory = graph
-            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), &ctx.db)
+            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), None, &ctx.db)
             .await?;
         assert!(loaded_advisory.is_some());
 
@@ -318,7 +363,7 @@ mod test {
         assert!(loaded_vulnerability.is_some());
 
         let loaded_advisory = graph
-            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), &ctx.db)
+            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), None, &ctx.db)
             .await?;
         assert!(loaded_advisory.is_some());
 
@@ -359,7 +404,7 @@ mod test {
         assert!(loaded_vulnerability.is_some());
 
         let loaded_advisory = graph
-            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), &ctx.db)
+            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), None, &ctx.db)
             .await?;
         assert!(loaded_advisory.is_some());
 
@@ -9,6 +9,8 @@ use trustify_entity::version_scheme::VersionScheme;
 pub struct ProductStatus {
     pub vendor: Option<String>,
     pub product: String,
+    /// The product families the product belongs to, outermost first
+    pub families: Vec<String>,
     pub version: Option<VersionInfo>,
     pub cpe: Option<trustify_common::cpe::Cpe>,
     pub status: &'static str,
@@ -25,6 +27,13 @@ impl ProductStatus {
                 self.product = branch.name.clone();
                 self.set_version(branch.product.clone());
             }
+            // Get the product streams the product belongs to
+            BranchCategory::ProductFamily => {
+                self.families.push(branch.name.clone());
+                if let Some(purl) = branch_purl(branch) {
+                    self.purls.push(Purl::from(purl.clone()));
+                }
+            }
             // Get organisation info
             BranchCategory::Vendor => {
                 self.vendor = Some(branch.name.clone());
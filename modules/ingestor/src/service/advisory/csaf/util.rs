@@ -4,7 +4,7 @@ use csaf::{
     product_tree::{ProductTree, Relationship},
 };
 use packageurl::PackageUrl;
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display};
 use tracing::instrument;
 
 #[instrument(skip(cache))]
@@ -153,10 +153,48 @@ pub fn gen_identifier(csaf: &Csaf) -> String {
     // From the spec:
     // > The combination of `/document/publisher/namespace` and `/document/tracking/id` identifies a CSAF document globally unique.
 
-    let mut file_name = String::with_capacity(csaf.document.tracking.id.len());
+    identifier(
+        &csaf.document.publisher.namespace,
+        &csaf.document.tracking.id,
+    )
+}
+
+/// Get the identifiers of the advisories, which are superseded by a CSAF document.
+///
+/// CSAF has no dedicated field for this. Superseded documents are references of the document,
+/// with a summary like `Supersedes RHSA-2023:1234`, naming the tracking ID of a document of the
+/// same publisher.
+pub fn gen_supersedes(csaf: &Csaf) -> Vec<String> {
+    let mut result = vec![];
+
+    for reference in csaf.document.references.iter().flatten() {
+        let summary = reference.summary.trim();
+        let Some(prefix) = summary.get(.."supersedes".len()) else {
+            continue;
+        };
+        if !prefix.eq_ignore_ascii_case("supersedes") {
+            continue;
+        }
+
+        let tracking_id = summary["supersedes".len()..].trim_start_matches(':').trim();
+        if tracking_id.is_empty() || tracking_id.contains(char::is_whitespace) {
+            continue;
+        }
+
+        let identifier = identifier(&csaf.document.publisher.namespace, tracking_id);
+        if identifier != gen_identifier(csaf) && !result.contains(&identifier) {
+            result.push(identifier);
+        }
+    }
+
+    result
+}
+
+pub(crate) fn identifier(namespace: impl Display, tracking_id: &str) -> String {
+    let mut file_name = String::with_capacity(tracking_id.len());
 
     let mut in_sequence = false;
-    for c in csaf.document.tracking.id.chars() {
+    for c in tracking_id.chars() {
         if c.is_ascii_alphanumeric() || c == '+' || c == '-' {
             file_name.push(c);
             in_sequence = false;
@@ -166,5 +204,5 @@ pub fn gen_identifier(csaf: &Csaf) -> String {
         }
     }
 
-    format!("{}#{file_name}", csaf.document.publisher.namespace)
+    format!("{namespace}#{file_name}")
 }
@@ -8,7 +8,7 @@ use crate::{
         vulnerability::VulnerabilityInformation,
     },
     model::IngestResult,
-    service::{Error, advisory::cve::divination::divine_purl},
+    service::{Error, advisory::cve::divination::divine_purl, audit::Upload},
 };
 use cve::{
     Cve, Timestamp,
@@ -19,7 +19,9 @@ use std::fmt::Debug;
 use time::OffsetDateTime;
 use tracing::instrument;
 use trustify_common::{hashing::Digests, id::Id};
-use trustify_entity::{labels::Labels, version_scheme::VersionScheme};
+use trustify_entity::{
+    document_audit::DocumentKind, labels::Labels, version_scheme::VersionScheme,
+};
 
 /// Loader capable of parsing a CVE Record JSON file
 /// and manipulating the Graph to integrate it into
@@ -31,11 +33,21 @@ use trustify_entity::{labels::Labels, version_scheme::VersionScheme};
 /// in the fetch.
 pub struct CveLoader<'g> {
     graph: &'g Graph,
+    upload: Option<&'g Upload>,
 }
 
 impl<'g> CveLoader<'g> {
     pub fn new(graph: &'g Graph) -> Self {
-        Self { graph }
+        Self {
+            graph,
+            upload: None,
+        }
+    }
+
+    /// Record the upload of the document, as part of ingesting it.
+    pub fn upload(mut self, upload: Option<&'g Upload>) -> Self {
+        self.upload = upload;
+        self
     }
 
     #[instrument(skip(self, cve), err(level=tracing::Level::INFO))]
@@ -167,12 +179,19 @@ impl<'g> CveLoader<'g> {
             .add_descriptions(advisory.advisory.id, entries, &tx)
             .await?;
 
+        if let Some(upload) = self.upload {
+            upload
+                .record(DocumentKind::Advisory, advisory.advisory.id, &tx)
+                .await?;
+        }
+
         tx.commit().await?;
 
         Ok(IngestResult {
             id: Id::Uuid(advisory.advisory.id),
             document_id: Some(id.to_string()),
             warnings: vec![],
+            duplicate: false,
         })
     }
 
@@ -312,7 +331,7 @@ mod test {
         assert!(loaded_vulnerability.is_none());
 
         let loaded_advisory = graph
-            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), &ctx.db)
+            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), None, &ctx.db)
             .await?;
         assert!(loaded_advisory.is_none());
 
@@ -330,7 +349,7 @@ mod test {
         );
 
         let loaded_advisory = graph
-            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), &ctx.db)
+            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), None, &ctx.db)
             .await?;
         assert!(loaded_advisory.is_some());
 
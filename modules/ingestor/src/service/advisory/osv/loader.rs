@@ -12,6 +12,7 @@ use crate::{
     service::{
         Error, Warnings,
         advisory::osv::{prefix::get_well_known_prefixes, translate},
+        audit::Upload,
     },
 };
 use osv::schema::{Ecosystem, Event, Range, RangeType, ReferenceType, SeverityType, Vulnerability};
@@ -21,15 +22,27 @@ use std::{fmt::Debug, str::FromStr};
 use tracing::instrument;
 use trustify_common::{hashing::Digests, id::Id, purl::Purl, time::ChronoExt};
 use trustify_cvss::cvss3::Cvss3Base;
-use trustify_entity::{labels::Labels, version_scheme::VersionScheme};
+use trustify_entity::{
+    document_audit::DocumentKind, labels::Labels, version_scheme::VersionScheme,
+};
 
 pub struct OsvLoader<'g> {
     graph: &'g Graph,
+    upload: Option<&'g Upload>,
 }
 
 impl<'g> OsvLoader<'g> {
     pub fn new(graph: &'g Graph) -> Self {
-        Self { graph }
+        Self {
+            graph,
+            upload: None,
+        }
+    }
+
+    /// Record the upload of the document, as part of ingesting it.
+    pub fn upload(mut self, upload: Option<&'g Upload>) -> Self {
+        self.upload = upload;
+        self
     }
 
     #[instrument(skip(self, osv), err(level=tracing::Level::INFO))]
@@ -98,6 +111,14 @@ impl<'g> OsvLoader<'g> {
                 )
                 .await?;
 
+            // the OSV ID and all other aliases, like GHSA or RUSTSEC IDs, identify the same vulnerability
+            advisory_vuln
+                .ingest_aliases(
+                    std::iter::once(&osv.id).chain(osv.aliases.iter().flatten()),
+                    &tx,
+                )
+                .await?;
+
             for severity in osv.severity.iter().flatten() {
                 if matches!(severity.severity_type, SeverityType::CVSSv3) {
                     match Cvss3Base::from_str(&severity.score) {
@@ -277,12 +298,19 @@ impl<'g> OsvLoader<'g> {
 
         purl_creator.create(&tx).await?;
 
+        if let Some(upload) = self.upload {
+            upload
+                .record(DocumentKind::Advisory, advisory.advisory.id, &tx)
+                .await?;
+        }
+
         tx.commit().await?;
 
         Ok(IngestResult {
             id: Id::Uuid(advisory.advisory.id),
             document_id: Some(osv.id),
             warnings: warnings.into(),
+            duplicate: false,
         })
     }
 }
@@ -545,7 +573,7 @@ mod test {
         assert!(loaded_vulnerability.is_none());
 
         let loaded_advisory = graph
-            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), &ctx.db)
+            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), None, &ctx.db)
             .await?;
         assert!(loaded_advisory.is_none());
 
@@ -558,7 +586,7 @@ mod test {
         assert!(loaded_vulnerability.is_some());
 
         let loaded_advisory = graph
-            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), &ctx.db)
+            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), None, &ctx.db)
             .await?;
         assert!(loaded_advisory.is_some());
 
@@ -607,7 +635,7 @@ mod test {
         assert!(loaded_vulnerability.is_none());
 
         let loaded_advisory = graph
-            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), &ctx.db)
+            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), None, &ctx.db)
             .await?;
         assert!(loaded_advisory.is_none());
 
@@ -619,7 +647,7 @@ mod test {
         assert!(loaded_vulnerability.is_some());
 
         let loaded_advisory = graph
-            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), &ctx.db)
+            .get_advisory_by_digest(&digests.sha256.encode_hex::<String>(), None, &ctx.db)
             .await?;
         assert!(loaded_advisory.is_some());
 
@@ -0,0 +1,122 @@
+//! Recording actions on documents in their audit trail.
+//!
+//! Uploads get recorded by the loaders, as part of the transaction ingesting the document. The
+//! trail itself is served by the fundamental module.
+
+use actix_web::{FromRequest, HttpMessage, HttpRequest, dev::Payload, http::header};
+use sea_orm::{ActiveValue::Set, ConnectionTrait, DbErr, EntityTrait};
+use std::future::{Ready, ready};
+use time::OffsetDateTime;
+use trustify_auth::authenticator::user::UserInformation;
+use trustify_entity::document_audit::{self, Action, DocumentKind};
+use uuid::Uuid;
+
+/// The user and client performing a request.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Actor {
+    /// The subject of the validated access token, absent if authentication is disabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// The address of the client, as seen by the server.
+    ///
+    /// Headers like `Forwarded` are ignored, as they can be set by the client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_address: Option<String>,
+    /// The user agent of the client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+}
+
+impl Actor {
+    /// Record an action on a document, as part of the transaction performing it.
+    pub async fn record<C: ConnectionTrait>(
+        &self,
+        action: Action,
+        kind: DocumentKind,
+        document_id: Uuid,
+        details: Option<serde_json::Value>,
+        connection: &C,
+    ) -> Result<(), DbErr> {
+        document_audit::Entity::insert(document_audit::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            timestamp: Set(OffsetDateTime::now_utc()),
+            user_id: Set(self.user.clone()),
+            client_address: Set(self.client_address.clone()),
+            user_agent: Set(self.user_agent.clone()),
+            action: Set(action),
+            document_kind: Set(kind),
+            document_id: Set(document_id),
+            details: Set(details),
+        })
+        .exec_without_returning(connection)
+        .await?;
+
+        Ok(())
+    }
+}
+
+impl FromRequest for Actor {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let user = req
+            .extensions()
+            .get::<UserInformation>()
+            .and_then(|user| user.id().map(ToString::to_string));
+        let client_address = req.peer_addr().map(|addr| addr.ip().to_string());
+        let user_agent = req
+            .headers()
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string);
+
+        ready(Ok(Self {
+            user,
+            client_address,
+            user_agent,
+        }))
+    }
+}
+
+/// The upload of a document, recorded once the document got ingested.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Upload {
+    pub actor: Actor,
+    /// Details of the upload, like the file of a dataset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+impl Upload {
+    pub fn new(actor: Actor) -> Self {
+        Self {
+            actor,
+            details: None,
+        }
+    }
+
+    /// Set the details of the upload.
+    pub fn details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// Record the upload of a newly ingested document.
+    pub(crate) async fn record<C: ConnectionTrait>(
+        &self,
+        kind: DocumentKind,
+        document_id: Uuid,
+        connection: &C,
+    ) -> Result<(), DbErr> {
+        self.actor
+            .record(
+                Action::Uploaded,
+                kind,
+                document_id,
+                self.details.clone(),
+                connection,
+            )
+            .await
+    }
+}
@@ -0,0 +1,175 @@
+//! Manifest of a dataset, declaring expectations about its content.
+//!
+//! The manifest is an optional file in the root of the dataset archive, named `manifest.yaml`
+//! (or `manifest.json`). For example:
+//!
+//! ```yaml
+//! counts:
+//!   spdx: 12
+//!   csaf: 3
+//! files:
+//!   spdx/quarkus-bom-2.13.8.Final-redhat-00004.json.bz2:
+//!     sha256: 4ec2a9ee0e1a4ca2d1ba6c9f0ec31fb0e3bc1cd4a3ee96a3ddd9c3e3d2c1e5d0
+//!   csaf/broken.json:
+//!     outcome: failed
+//! ```
+
+use std::collections::BTreeMap;
+
+/// The names of the manifest file, in the root of the archive.
+pub const MANIFEST_FILE_NAMES: &[&str] = &["manifest.yaml", "manifest.yml", "manifest.json"];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DatasetManifest {
+    /// Expected number of documents, by format (the name of the top level directory).
+    #[serde(default)]
+    pub counts: BTreeMap<String, usize>,
+    /// Expectations for individual files, by their full name in the archive.
+    #[serde(default)]
+    pub files: BTreeMap<String, FileExpectation>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FileExpectation {
+    /// The expected SHA256 digest (hex encoded) of the, uncompressed, document.
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// The expected outcome of ingesting the document.
+    #[serde(default)]
+    pub outcome: Outcome,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Outcome {
+    /// The document must be ingested successfully.
+    #[default]
+    Ingested,
+    /// Ingesting the document must fail.
+    Failed,
+    /// The outcome doesn't matter, but the file must be present.
+    Any,
+}
+
+/// What actually happened to a file of the dataset.
+#[derive(Clone, Debug)]
+pub(crate) struct FileRecord {
+    pub format: String,
+    pub sha256: String,
+    pub ingested: bool,
+}
+
+impl DatasetManifest {
+    /// Verify the records of a dataset run against the manifest, returning all mismatches.
+    pub(crate) fn verify(&self, records: &BTreeMap<String, FileRecord>) -> Vec<String> {
+        let mut mismatches = vec![];
+
+        for (format, expected) in &self.counts {
+            let actual = records.values().filter(|r| &r.format == format).count();
+            if actual != *expected {
+                mismatches.push(format!(
+                    "Expected {expected} document(s) of type '{format}', found {actual}"
+                ));
+            }
+        }
+
+        for (name, expectation) in &self.files {
+            let Some(record) = records.get(name) else {
+                mismatches.push(format!("Missing file: {name}"));
+                continue;
+            };
+
+            match &expectation.sha256 {
+                Some(sha256) if !sha256.eq_ignore_ascii_case(&record.sha256) => {
+                    mismatches.push(format!(
+                        "Digest mismatch for {name}: expected {sha256}, found {}",
+                        record.sha256
+                    ));
+                }
+                _ => {}
+            }
+
+            match (expectation.outcome, record.ingested) {
+                (Outcome::Ingested, false) => {
+                    mismatches.push(format!("Expected {name} to be ingested, but it failed"));
+                }
+                (Outcome::Failed, true) => {
+                    mismatches.push(format!("Expected {name} to fail, but it was ingested"));
+                }
+                _ => {}
+            }
+        }
+
+        mismatches
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+
+    fn record(format: &str, sha256: &str, ingested: bool) -> FileRecord {
+        FileRecord {
+            format: format.into(),
+            sha256: sha256.into(),
+            ingested,
+        }
+    }
+
+    #[test]
+    fn verify() -> anyhow::Result<()> {
+        let manifest: DatasetManifest = serde_yml::from_str(
+            r#"
+counts:
+  spdx: 2
+  csaf: 1
+files:
+  spdx/a.json:
+    sha256: ABCD
+  spdx/b.json:
+    outcome: failed
+  csaf/c.json:
+    outcome: any
+  csaf/d.json: {}
+"#,
+        )?;
+
+        let records = BTreeMap::from_iter([
+            ("spdx/a.json".to_string(), record("spdx", "abcd", true)),
+            ("spdx/b.json".to_string(), record("spdx", "0000", true)),
+            ("csaf/c.json".to_string(), record("csaf", "0000", false)),
+        ]);
+
+        assert_eq!(
+            manifest.verify(&records),
+            vec![
+                "Missing file: csaf/d.json".to_string(),
+                "Expected spdx/b.json to fail, but it was ingested".to_string(),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn verify_counts() -> anyhow::Result<()> {
+        let manifest: DatasetManifest = serde_yml::from_str("counts: { spdx: 1, osv: 1 }")?;
+        let records =
+            BTreeMap::from_iter([("spdx/a.json".to_string(), record("spdx", "0000", false))]);
+
+        assert_eq!(
+            manifest.verify(&records),
+            vec!["Expected 1 document(s) of type 'osv', found 0".to_string()]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_unknown_fields() {
+        assert!(serde_yml::from_str::<DatasetManifest>("count: { spdx: 1 }").is_err());
+    }
+}
@@ -1,41 +1,135 @@
 //! Loader for a full dataset (archive) file
 
+mod manifest;
+
+pub use manifest::*;
+
 use crate::{
     graph::Graph,
     model::IngestResult,
-    service::{Error, Format, Warnings},
+    service::{
+        Error, Format, LabelTemplates, Warnings, audit::Upload, document::Document,
+        enricher::Enrichers, listener::IngestListeners, metrics::IngestMetrics,
+    },
 };
 use anyhow::anyhow;
 use bytes::Bytes;
+use futures_util::{StreamExt, TryStreamExt, stream};
+use hex::ToHex;
 use sbom_walker::common::compression;
 use sbom_walker::common::compression::{DecompressionOptions, Detector};
+use serde_json::json;
 use std::{
     collections::BTreeMap,
     io::{Cursor, Read},
     str::FromStr,
+    time::Instant,
 };
 use tokio::runtime::Handle;
 use tokio_util::io::ReaderStream;
 use tracing::instrument;
-use trustify_common::hashing::Digests;
+use trustify_common::{decompress::Limits, hashing::Digests, id::Id};
 use trustify_entity::labels::Labels;
 use trustify_module_storage::{service::StorageBackend, service::dispatch::DispatchBackend};
 
+/// The stage in which documents of a format get ingested.
+///
+/// Stages are processed one after the other, while documents of the same stage get ingested in
+/// parallel. Vulnerabilities, and their details, get created first, so that advisories and SBOMs
+/// only refer to them. Information about packages comes last.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Stage {
+    Vulnerabilities,
+    Advisories,
+    Sboms,
+    Packages,
+}
+
+impl From<Format> for Stage {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::CVE | Format::CweCatalog => Self::Vulnerabilities,
+            Format::CSAF | Format::OSV | Format::Advisory => Self::Advisories,
+            Format::SPDX | Format::CycloneDX | Format::SBOM | Format::OciImage => Self::Sboms,
+            Format::ClearlyDefined | Format::ClearlyDefinedCuration | Format::Unknown => {
+                Self::Packages
+            }
+        }
+    }
+}
+
+/// An entry of the archive, to be ingested.
+struct Entry {
+    index: usize,
+    full_name: String,
+    loader: String,
+    file_name: String,
+    format: Format,
+}
+
 pub struct DatasetLoader<'g> {
     graph: &'g Graph,
     storage: &'g DispatchBackend,
-    limit: usize,
+    limits: Limits,
+    label_templates: Option<&'g LabelTemplates>,
+    enrichers: Option<&'g Enrichers>,
+    listeners: Option<&'g IngestListeners>,
+    metrics: Option<&'g IngestMetrics>,
+    upload: Option<&'g Upload>,
+    concurrency: usize,
 }
 
 impl<'g> DatasetLoader<'g> {
-    pub fn new(graph: &'g Graph, storage: &'g DispatchBackend, limit: usize) -> Self {
+    pub fn new(graph: &'g Graph, storage: &'g DispatchBackend, limits: Limits) -> Self {
         Self {
             graph,
             storage,
-            limit,
+            limits,
+            label_templates: None,
+            enrichers: None,
+            listeners: None,
+            metrics: None,
+            upload: None,
+            concurrency: 1,
         }
     }
 
+    /// Apply label templates to each document of the dataset.
+    pub fn label_templates(mut self, label_templates: &'g LabelTemplates) -> Self {
+        self.label_templates = Some(label_templates);
+        self
+    }
+
+    /// Run the enrichment stages for each document of the dataset, after it got ingested.
+    pub fn enrichers(mut self, enrichers: &'g Enrichers) -> Self {
+        self.enrichers = Some(enrichers);
+        self
+    }
+
+    /// Notify listeners about each document of the dataset, after it got ingested.
+    pub fn listeners(mut self, listeners: &'g IngestListeners) -> Self {
+        self.listeners = Some(listeners);
+        self
+    }
+
+    /// Record the upload of the dataset for each document, as part of ingesting it.
+    pub fn upload(mut self, upload: Option<&'g Upload>) -> Self {
+        self.upload = upload;
+        self
+    }
+
+    /// Set the number of documents, which get ingested in parallel. Zero is treated like one.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Record metrics for each document of the dataset.
+    pub(crate) fn metrics(mut self, metrics: &'g IngestMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     #[instrument(skip(self, buffer), err(level=tracing::Level::INFO))]
     pub async fn load(&self, labels: Labels, buffer: &[u8]) -> Result<DatasetIngestResult, Error> {
         let warnings = Warnings::default();
@@ -43,8 +137,15 @@ impl<'g> DatasetLoader<'g> {
 
         let mut zip = zip::ZipArchive::new(Cursor::new(buffer))?;
 
-        for i in 0..zip.len() {
-            let mut file = zip.by_index(i)?;
+        let manifest = Self::read_manifest(&mut zip)?;
+        let mut records = BTreeMap::new();
+
+        // collect the entries first, so that we can ingest them stage by stage
+
+        let mut stages = BTreeMap::<Stage, Vec<Entry>>::new();
+
+        for index in 0..zip.len() {
+            let file = zip.by_index(index)?;
 
             log::debug!("archive entry: {}", file.name());
 
@@ -54,6 +155,9 @@ impl<'g> DatasetLoader<'g> {
             if file.name() == ".DS_Store" || file.name().ends_with("/.DS_Store") {
                 continue;
             }
+            if MANIFEST_FILE_NAMES.contains(&file.name()) {
+                continue;
+            }
 
             let Some(name) = file.enclosed_name() else {
                 continue;
@@ -65,8 +169,6 @@ impl<'g> DatasetLoader<'g> {
                 .collect::<Vec<_>>()
                 .as_slice()
             {
-                let full_name = name.display().to_string();
-
                 log::debug!(
                     "Processing entry - loader: {loader}, path: {_path:?}, name: {file_name}"
                 );
@@ -75,57 +177,13 @@ impl<'g> DatasetLoader<'g> {
                         warnings.add(format!("Unknown dataset file type: {loader}"));
                     }
                     Ok(format) => {
-                        let mut data = Vec::with_capacity(file.size() as _);
-                        file.read_to_end(&mut data)?;
-
-                        let file_name = file_name.to_string();
-                        let opts = DecompressionOptions::new().limit(self.limit);
-                        let data = Handle::current()
-                            .spawn_blocking(move || {
-                                let detector = Detector {
-                                    file_name: Some(&file_name),
-                                    ..Detector::default()
-                                };
-                                detector
-                                    .decompress_with(Bytes::from(data), &opts)
-                                    .map_err(|err| match err {
-                                        compression::Error::Io(err)
-                                            if err.kind() == std::io::ErrorKind::WriteZero =>
-                                        {
-                                            Error::PayloadTooLarge
-                                        }
-                                        _ => Error::Generic(anyhow!("{err}")),
-                                    })
-                            })
-                            .await??;
-
-                        let labels = labels.clone().add("datasetFile", &full_name);
-
-                        self.storage
-                            .store(ReaderStream::new(&*data))
-                            .await
-                            .map_err(|err| Error::Storage(anyhow!("{err}")))?;
-
-                        // We need to box it, to work around async recursion limits
-                        let result = Box::pin({
-                            async move {
-                                format
-                                    .load(self.graph, labels, None, &Digests::digest(&data), &data)
-                                    .await
-                            }
-                        })
-                        .await;
-
-                        match result {
-                            Ok(result) => {
-                                results.insert(full_name, result);
-                            }
-                            Err(err) => {
-                                warnings.add(format!(
-                                    "Error loading dataset file ({full_name}): {err}"
-                                ));
-                            }
-                        }
+                        stages.entry(format.into()).or_default().push(Entry {
+                            index,
+                            full_name: name.display().to_string(),
+                            loader: loader.to_string(),
+                            file_name: file_name.to_string(),
+                            format,
+                        });
                     }
                 }
             } else {
@@ -136,15 +194,195 @@ impl<'g> DatasetLoader<'g> {
             }
         }
 
+        for (stage, entries) in stages {
+            log::debug!(
+                "Ingesting {} document(s) of stage {stage:?}, {} at a time",
+                entries.len(),
+                self.concurrency
+            );
+
+            let outcomes: Vec<_> = stream::iter(entries)
+                .map(|entry| self.load_entry(&zip, &labels, entry))
+                .buffer_unordered(self.concurrency)
+                .try_collect()
+                .await?;
+
+            for (full_name, record, result) in outcomes {
+                records.insert(full_name.clone(), record);
+
+                match result {
+                    Ok(result) => {
+                        results.insert(full_name, result);
+                    }
+                    Err(err) => {
+                        warnings.add(format!("Error loading dataset file ({full_name}): {err}"));
+                    }
+                }
+            }
+        }
+
+        let mismatches = manifest
+            .map(|manifest| manifest.verify(&records))
+            .unwrap_or_default();
+
         Ok(DatasetIngestResult {
             files: results,
             warnings: warnings.into(),
+            mismatches,
         })
     }
+
+    /// Load a single entry of the dataset.
+    ///
+    /// Failing to extract the entry fails the whole dataset, while failing to ingest it is
+    /// reported as the result of the entry.
+    async fn load_entry(
+        &self,
+        zip: &zip::ZipArchive<Cursor<&[u8]>>,
+        labels: &Labels,
+        entry: Entry,
+    ) -> Result<(String, FileRecord, Result<IngestResult, Error>), Error> {
+        let Entry {
+            index,
+            full_name,
+            loader,
+            file_name,
+            format,
+        } = entry;
+
+        // the archive is cheap to clone, and each clone can read an entry on its own
+        let (limit, data) = {
+            let mut zip = zip.clone();
+            let mut file = zip.by_index(index)?;
+
+            // the limit is based on the compressed size inside the archive, covering
+            // both the compression of the archive and the compression of the document
+
+            let limit = self.limits.effective(file.compressed_size() as _);
+
+            let mut data = vec![];
+            if limit > 0 {
+                (&mut file).take(limit as u64 + 1).read_to_end(&mut data)?;
+                if data.len() > limit {
+                    return Err(Error::PayloadTooLarge { limit });
+                }
+            } else {
+                file.read_to_end(&mut data)?;
+            }
+
+            (limit, data)
+        };
+
+        let opts = DecompressionOptions::new().limit(limit);
+        let data = Handle::current()
+            .spawn_blocking(move || {
+                let detector = Detector {
+                    file_name: Some(&file_name),
+                    ..Detector::default()
+                };
+                detector
+                    .decompress_with(Bytes::from(data), &opts)
+                    .map_err(|err| match err {
+                        compression::Error::Io(err)
+                            if err.kind() == std::io::ErrorKind::WriteZero =>
+                        {
+                            Error::PayloadTooLarge { limit }
+                        }
+                        _ => Error::Generic(anyhow!("{err}")),
+                    })
+            })
+            .await??;
+
+        let mut labels = labels.clone().add("datasetFile", &full_name);
+        if let Some(label_templates) = self.label_templates {
+            labels = label_templates.apply(format, &data, labels);
+        }
+
+        let digests = Digests::digest(&data);
+        let sha256 = digests.sha256.encode_hex();
+
+        self.storage
+            .store(ReaderStream::new(&*data))
+            .await
+            .map_err(|err| Error::Storage(anyhow!("{err}")))?;
+
+        let upload = self
+            .upload
+            .map(|upload| upload.clone().details(json!({ "datasetFile": &full_name })));
+
+        // We need to box it, to work around async recursion limits
+        let start = Instant::now();
+        let result = Box::pin({
+            let digests = &digests;
+            let upload = upload.as_ref();
+            async move {
+                format
+                    .load(self.graph, labels, None, digests, &data, upload)
+                    .await
+            }
+        })
+        .await;
+
+        if let Some(metrics) = self.metrics {
+            metrics.record(format, start.elapsed(), &result);
+        }
+
+        let result = match (result, self.enrichers) {
+            (Ok(mut result), Some(enrichers)) => enrichers
+                .run(self.graph, format, &digests, &mut result)
+                .await
+                .map(|()| result),
+            (result, _) => result,
+        };
+
+        let result = match (result, Document::from_format(format)) {
+            (Ok(result), Some(document)) => match &result.id {
+                Id::Uuid(id) => document
+                    .set_warnings(self.graph, *id, &result.warnings)
+                    .await
+                    .map(|()| result),
+                _ => Ok(result),
+            },
+            (result, _) => result,
+        };
+
+        if let (Ok(result), Some(listeners)) = (&result, self.listeners) {
+            listeners.notify(format, result).await;
+        }
+
+        let record = FileRecord {
+            format: loader,
+            sha256,
+            ingested: result.is_ok(),
+        };
+
+        Ok((full_name, record, result))
+    }
+
+    /// Read the manifest from the root of the archive, if present.
+    fn read_manifest(
+        zip: &mut zip::ZipArchive<Cursor<&[u8]>>,
+    ) -> Result<Option<DatasetManifest>, Error> {
+        for name in MANIFEST_FILE_NAMES {
+            let file = match zip.by_name(name) {
+                Ok(file) => file,
+                Err(zip::result::ZipError::FileNotFound) => continue,
+                Err(err) => return Err(err.into()),
+            };
+
+            log::debug!("Found dataset manifest: {name}");
+            return Ok(Some(serde_yml::from_reader(file)?));
+        }
+
+        Ok(None)
+    }
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct DatasetIngestResult {
     pub warnings: Vec<String>,
     pub files: BTreeMap<String, IngestResult>,
+    /// Mismatches between the content of the dataset and its manifest
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mismatches: Vec<String>,
 }
@@ -0,0 +1,416 @@
+use super::{Error, Format, identity::Identity};
+use crate::{graph::Graph, model::IngestResult};
+use hex::ToHex;
+use sea_orm::{
+    ActiveValue::Set,
+    ColumnTrait, Condition, EntityTrait, JoinType, QueryFilter, QueryOrder, QuerySelect,
+    QueryTrait, RelationTrait,
+    sea_query::{Expr, OnConflict, Query, SelectStatement, extension::postgres::PgExpr},
+};
+use time::OffsetDateTime;
+use trustify_auth::tenant::TENANT_LABEL;
+use trustify_common::{hashing::Digests, id::Id};
+use trustify_entity::{
+    advisory, document_conflict, document_identity, download_audit::DocumentKind,
+    ingestion_warning, labels::Labels, sbom, source_document,
+};
+use uuid::Uuid;
+
+/// The kind of document an ingested format results in.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Document {
+    Sbom,
+    Advisory,
+}
+
+/// The outcome of storing the identity of a document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Identified {
+    /// The identity got stored, conflicting with the documents of these IDs
+    Stored(Vec<Uuid>),
+    /// An equivalent document, with the ID, already holds the identity
+    Equivalent(Uuid),
+}
+
+impl Document {
+    pub(crate) fn from_format(format: Format) -> Option<Self> {
+        match format {
+            Format::SPDX | Format::CycloneDX => Some(Self::Sbom),
+            Format::CSAF | Format::OSV | Format::CVE => Some(Self::Advisory),
+            _ => None,
+        }
+    }
+
+    /// Find a document, which was already ingested with the same digests.
+    ///
+    /// A document which got deleted in the meantime gets restored. Only documents of the same
+    /// tenant are considered.
+    pub(crate) async fn find_duplicate(
+        &self,
+        graph: &Graph,
+        digests: &Digests,
+        tenant: Option<&str>,
+    ) -> Result<Option<IngestResult>, Error> {
+        let sha256: String = digests.sha256.encode_hex();
+
+        let found = match self {
+            Self::Sbom => graph
+                .get_or_restore_sbom_by_digest(&sha256, tenant, &graph.db)
+                .await?
+                .map(|sbom| (sbom.sbom.sbom_id, sbom.sbom.document_id)),
+            Self::Advisory => graph
+                .get_or_restore_advisory_by_digest(&sha256, tenant, &graph.db)
+                .await?
+                .map(|advisory| (advisory.advisory.id, Some(advisory.advisory.identifier))),
+        };
+
+        Ok(found.map(|(id, document_id)| IngestResult {
+            id: Id::Uuid(id),
+            document_id,
+            warnings: vec![],
+            duplicate: true,
+        }))
+    }
+
+    /// Find a document of the tenant, which was already ingested with the same identity and the
+    /// same canonical content, and wasn't deleted.
+    pub(crate) async fn find_equivalent(
+        &self,
+        graph: &Graph,
+        identity: &Identity,
+        tenant: Option<&str>,
+    ) -> Result<Option<IngestResult>, Error> {
+        let Some(found) = document_identity::Entity::find()
+            .filter(self.same_identity(identity, tenant))
+            .filter(document_identity::Column::CanonicalSha256.eq(&identity.canonical_sha256))
+            .filter(document_identity::Column::DocumentId.in_subquery(self.existing()))
+            .one(&graph.db)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let document_id = match self {
+            Self::Sbom => sbom::Entity::find_by_id(found.document_id)
+                .one(&graph.db)
+                .await?
+                .and_then(|sbom| sbom.document_id),
+            Self::Advisory => advisory::Entity::find_by_id(found.document_id)
+                .one(&graph.db)
+                .await?
+                .map(|advisory| advisory.identifier),
+        };
+
+        Ok(Some(IngestResult {
+            id: Id::Uuid(found.document_id),
+            document_id,
+            warnings: vec![],
+            duplicate: true,
+        }))
+    }
+
+    /// Store the identity of an ingested document.
+    ///
+    /// The identity, along with the content, belongs to a single document of a tenant. If an
+    /// equivalent document got ingested at the same time, that one is reported instead. Otherwise,
+    /// conflicts with documents of the same identity, but different content, get recorded.
+    pub(crate) async fn set_identity(
+        &self,
+        graph: &Graph,
+        id: Uuid,
+        identity: &Identity,
+        tenant: Option<&str>,
+    ) -> Result<Identified, Error> {
+        let format: &'static str = identity.format.into();
+
+        let inserted = document_identity::Entity::insert(document_identity::ActiveModel {
+            document_id: Set(id),
+            document_kind: Set(self.kind()),
+            format: Set(format.to_string()),
+            identifier: Set(identity.identifier.clone()),
+            revision: Set(identity.revision.clone()),
+            canonical_sha256: Set(identity.canonical_sha256.clone()),
+            tenant: Set(tenant.unwrap_or_default().to_string()),
+        })
+        .on_conflict(
+            OnConflict::columns([
+                document_identity::Column::DocumentKind,
+                document_identity::Column::Format,
+                document_identity::Column::Identifier,
+                document_identity::Column::Revision,
+                document_identity::Column::CanonicalSha256,
+                document_identity::Column::Tenant,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(&graph.db)
+        .await?;
+
+        if inserted == 0 {
+            // the identity is held by an equivalent document, which is taken over if that one
+            // got deleted in the meantime
+            let taken = document_identity::Entity::update_many()
+                .col_expr(document_identity::Column::DocumentId, Expr::value(id))
+                .filter(self.same_identity(identity, tenant))
+                .filter(document_identity::Column::CanonicalSha256.eq(&identity.canonical_sha256))
+                .filter(document_identity::Column::DocumentId.not_in_subquery(self.existing()))
+                .exec(&graph.db)
+                .await?;
+
+            if taken.rows_affected == 0 {
+                let existing = document_identity::Entity::find()
+                    .filter(self.same_identity(identity, tenant))
+                    .filter(
+                        document_identity::Column::CanonicalSha256.eq(&identity.canonical_sha256),
+                    )
+                    .one(&graph.db)
+                    .await?;
+                if let Some(existing) = existing {
+                    return Ok(Identified::Equivalent(existing.document_id));
+                }
+            }
+        }
+
+        let conflicting: Vec<Uuid> = document_identity::Entity::find()
+            .select_only()
+            .column(document_identity::Column::DocumentId)
+            .filter(self.same_identity(identity, tenant))
+            .filter(document_identity::Column::CanonicalSha256.ne(&identity.canonical_sha256))
+            .filter(document_identity::Column::DocumentId.in_subquery(self.existing()))
+            .into_tuple()
+            .all(&graph.db)
+            .await?;
+
+        if conflicting.is_empty() {
+            return Ok(Identified::Stored(conflicting));
+        }
+
+        let detected = OffsetDateTime::now_utc();
+        document_conflict::Entity::insert_many(conflicting.iter().map(|existing_id| {
+            document_conflict::ActiveModel {
+                id: Set(Uuid::now_v7()),
+                detected: Set(detected),
+                document_kind: Set(self.kind()),
+                format: Set(format.to_string()),
+                identifier: Set(identity.identifier.clone()),
+                revision: Set(identity.revision.clone()),
+                existing_id: Set(*existing_id),
+                conflicting_id: Set(id),
+            }
+        }))
+        .exec(&graph.db)
+        .await?;
+
+        Ok(Identified::Stored(conflicting))
+    }
+
+    /// List the documents without an identity, which weren't deleted, ordered by their ID.
+    ///
+    /// Returns the ID, the SHA-256 digest of the source document, and the tenant of each
+    /// document, starting after the document with the ID `after`.
+    pub(crate) async fn without_identity(
+        &self,
+        graph: &Graph,
+        after: Option<Uuid>,
+        limit: u64,
+    ) -> Result<Vec<(Uuid, String, Option<String>)>, Error> {
+        let identified = Query::select()
+            .column(document_identity::Column::DocumentId)
+            .from(document_identity::Entity)
+            .and_where(document_identity::Column::DocumentKind.eq(self.kind()))
+            .to_owned();
+
+        Ok(match self {
+            Self::Sbom => {
+                sbom::Entity::find()
+                    .select_only()
+                    .column(sbom::Column::SbomId)
+                    .column(source_document::Column::Sha256)
+                    .column_as(
+                        Expr::col((sbom::Entity, sbom::Column::Labels))
+                            .cast_json_field(TENANT_LABEL),
+                        "tenant",
+                    )
+                    .join(JoinType::Join, sbom::Relation::SourceDocument.def())
+                    .filter(sbom::Column::DeletedAt.is_null())
+                    .filter(sbom::Column::SbomId.not_in_subquery(identified))
+                    .apply_if(after, |select, after| {
+                        select.filter(sbom::Column::SbomId.gt(after))
+                    })
+                    .order_by_asc(sbom::Column::SbomId)
+                    .limit(limit)
+                    .into_tuple()
+                    .all(&graph.db)
+                    .await?
+            }
+            Self::Advisory => {
+                advisory::Entity::find()
+                    .select_only()
+                    .column(advisory::Column::Id)
+                    .column(source_document::Column::Sha256)
+                    .column_as(
+                        Expr::col((advisory::Entity, advisory::Column::Labels))
+                            .cast_json_field(TENANT_LABEL),
+                        "tenant",
+                    )
+                    .join(JoinType::Join, advisory::Relation::SourceDocument.def())
+                    .filter(advisory::Column::DeletedAt.is_null())
+                    .filter(advisory::Column::Id.not_in_subquery(identified))
+                    .apply_if(after, |select, after| {
+                        select.filter(advisory::Column::Id.gt(after))
+                    })
+                    .order_by_asc(advisory::Column::Id)
+                    .limit(limit)
+                    .into_tuple()
+                    .all(&graph.db)
+                    .await?
+            }
+        })
+    }
+
+    pub(crate) async fn labels(&self, graph: &Graph, id: Uuid) -> Result<Option<Labels>, Error> {
+        Ok(match self {
+            Self::Sbom => sbom::Entity::find_by_id(id)
+                .one(&graph.db)
+                .await?
+                .map(|sbom| sbom.labels),
+            Self::Advisory => advisory::Entity::find_by_id(id)
+                .one(&graph.db)
+                .await?
+                .map(|advisory| advisory.labels),
+        })
+    }
+
+    pub(crate) async fn set_labels(
+        &self,
+        graph: &Graph,
+        id: Uuid,
+        labels: Labels,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Sbom => {
+                sbom::Entity::update_many()
+                    .filter(sbom::Column::SbomId.eq(id))
+                    .col_expr(sbom::Column::Labels, Expr::value(labels))
+                    .exec(&graph.db)
+                    .await?;
+            }
+            Self::Advisory => {
+                advisory::Entity::update_many()
+                    .filter(advisory::Column::Id.eq(id))
+                    .col_expr(advisory::Column::Labels, Expr::value(labels))
+                    .exec(&graph.db)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Set the external ID, replacing a previous one.
+    pub(crate) async fn set_external_id(
+        &self,
+        graph: &Graph,
+        id: Uuid,
+        external_id: String,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Sbom => {
+                sbom::Entity::update_many()
+                    .filter(sbom::Column::SbomId.eq(id))
+                    .col_expr(sbom::Column::ExternalId, Expr::value(external_id))
+                    .exec(&graph.db)
+                    .await?;
+            }
+            Self::Advisory => {
+                advisory::Entity::update_many()
+                    .filter(advisory::Column::Id.eq(id))
+                    .col_expr(advisory::Column::ExternalId, Expr::value(external_id))
+                    .exec(&graph.db)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Store the warnings of the ingestion, replacing those of a previous ingestion.
+    pub(crate) async fn set_warnings(
+        &self,
+        graph: &Graph,
+        id: Uuid,
+        warnings: &[String],
+    ) -> Result<(), Error> {
+        let column = match self {
+            Self::Sbom => ingestion_warning::Column::SbomId,
+            Self::Advisory => ingestion_warning::Column::AdvisoryId,
+        };
+
+        ingestion_warning::Entity::delete_many()
+            .filter(column.eq(id))
+            .exec(&graph.db)
+            .await?;
+
+        if warnings.is_empty() {
+            return Ok(());
+        }
+
+        let (sbom_id, advisory_id) = match self {
+            Self::Sbom => (Some(id), None),
+            Self::Advisory => (None, Some(id)),
+        };
+
+        ingestion_warning::Entity::insert_many(warnings.iter().map(|message| {
+            ingestion_warning::ActiveModel {
+                id: Set(Uuid::now_v7()),
+                sbom_id: Set(sbom_id),
+                advisory_id: Set(advisory_id),
+                message: Set(message.clone()),
+            }
+        }))
+        .exec(&graph.db)
+        .await?;
+
+        Ok(())
+    }
+
+    fn kind(&self) -> DocumentKind {
+        match self {
+            Self::Sbom => DocumentKind::Sbom,
+            Self::Advisory => DocumentKind::Advisory,
+        }
+    }
+
+    /// Match identities of this kind of document, with the same identifier and revision, of the
+    /// tenant.
+    fn same_identity(&self, identity: &Identity, tenant: Option<&str>) -> Condition {
+        let format: &'static str = identity.format.into();
+
+        Condition::all()
+            .add(document_identity::Column::DocumentKind.eq(self.kind()))
+            .add(document_identity::Column::Format.eq(format))
+            .add(document_identity::Column::Identifier.eq(&identity.identifier))
+            .add(match &identity.revision {
+                Some(revision) => document_identity::Column::Revision.eq(revision),
+                None => document_identity::Column::Revision.is_null(),
+            })
+            .add(document_identity::Column::Tenant.eq(tenant.unwrap_or_default()))
+    }
+
+    /// Select the IDs of the documents of this kind, which weren't deleted.
+    fn existing(&self) -> SelectStatement {
+        match self {
+            Self::Sbom => Query::select()
+                .column(sbom::Column::SbomId)
+                .from(sbom::Entity)
+                .and_where(sbom::Column::DeletedAt.is_null())
+                .to_owned(),
+            Self::Advisory => Query::select()
+                .column(advisory::Column::Id)
+                .from(advisory::Entity)
+                .and_where(advisory::Column::DeletedAt.is_null())
+                .to_owned(),
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Flagging advisories of known exploited vulnerabilities.
+//!
+//! The catalog of known exploited vulnerabilities (KEV) is downloaded on first use, and refreshed
+//! once a day. If downloading fails, the previous catalog is used, and the download is retried
+//! after a few minutes. Advisories of a vulnerability listed in the catalog get the label
+//! `kev=true`.
+
+use super::{Enricher, EnrichmentContext};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use reqwest::Url;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::Mutex;
+use trustify_common::reqwest::ClientFactory;
+use trustify_entity::{advisory_vulnerability, labels::Labels};
+
+/// The catalog published by CISA.
+pub const DEFAULT_CATALOG_URL: &str =
+    "https://www.cisa.gov/sites/default/files/feeds/known_exploited_vulnerabilities.json";
+
+/// The time after which the catalog gets downloaded again.
+const CATALOG_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The time to wait after a failed download, before trying again.
+const FAILURE_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+#[derive(serde::Deserialize)]
+struct Catalog {
+    vulnerabilities: Vec<CatalogEntry>,
+}
+
+#[derive(serde::Deserialize)]
+struct CatalogEntry {
+    #[serde(rename = "cveID")]
+    cve_id: String,
+}
+
+/// The outcome of the latest downloads.
+#[derive(Default)]
+struct State {
+    /// The IDs of the latest downloaded catalog, and when it got downloaded
+    catalog: Option<(Instant, Arc<HashSet<String>>)>,
+    /// The error of the latest download, if it failed, and when it failed
+    failure: Option<(Instant, String)>,
+}
+
+impl State {
+    /// Get the outcome of the previous downloads, unless the catalog must be downloaded again.
+    ///
+    /// During the backoff of a failed download, an outdated catalog is still used.
+    fn cached(&self) -> Option<anyhow::Result<Arc<HashSet<String>>>> {
+        match (&self.catalog, &self.failure) {
+            (Some((fetched, ids)), _) if fetched.elapsed() < CATALOG_MAX_AGE => {
+                Some(Ok(ids.clone()))
+            }
+            (catalog, Some((failed, err))) if failed.elapsed() < FAILURE_BACKOFF => {
+                Some(match catalog {
+                    Some((_, ids)) => Ok(ids.clone()),
+                    None => Err(anyhow::anyhow!(
+                        "downloading the catalog failed recently: {err}"
+                    )),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+pub struct KevEnricher {
+    url: Url,
+    client: reqwest::Client,
+    state: RwLock<State>,
+    /// Held while downloading, so that only one download runs at a time
+    download: Mutex<()>,
+}
+
+impl KevEnricher {
+    pub fn new(url: Url) -> anyhow::Result<Self> {
+        Ok(Self {
+            url,
+            client: ClientFactory::new().build()?,
+            state: Default::default(),
+            download: Default::default(),
+        })
+    }
+
+    /// Get the IDs of the known exploited vulnerabilities, downloading them if required.
+    ///
+    /// The state is never locked while downloading. While another task downloads the catalog,
+    /// the outdated one is used, if there is one.
+    async fn catalog(&self) -> anyhow::Result<Arc<HashSet<String>>> {
+        let cached = self.state.read().cached();
+        if let Some(cached) = cached {
+            return cached;
+        }
+
+        let _download = match self.download.try_lock() {
+            Ok(download) => download,
+            Err(_) => {
+                let outdated = self.state.read().catalog.clone();
+                if let Some((_, ids)) = outdated {
+                    return Ok(ids);
+                }
+                self.download.lock().await
+            }
+        };
+
+        // another task may have completed the download in the meantime
+        let cached = self.state.read().cached();
+        if let Some(cached) = cached {
+            return cached;
+        }
+
+        match self.download_catalog().await {
+            Ok(ids) => {
+                log::debug!("Fetched {} known exploited vulnerabilities", ids.len());
+                let mut state = self.state.write();
+                state.catalog = Some((Instant::now(), ids.clone()));
+                state.failure = None;
+                Ok(ids)
+            }
+            Err(err) => {
+                log::warn!(
+                    "Failed to download the catalog of known exploited vulnerabilities: {err}"
+                );
+                let mut state = self.state.write();
+                state.failure = Some((Instant::now(), err.to_string()));
+                match &state.catalog {
+                    Some((_, ids)) => Ok(ids.clone()),
+                    None => Err(err),
+                }
+            }
+        }
+    }
+
+    async fn download_catalog(&self) -> anyhow::Result<Arc<HashSet<String>>> {
+        let response: Catalog = self
+            .client
+            .get(self.url.clone())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(Arc::new(
+            response
+                .vulnerabilities
+                .into_iter()
+                .map(|entry| entry.cve_id)
+                .collect(),
+        ))
+    }
+}
+
+#[async_trait]
+impl Enricher for KevEnricher {
+    fn name(&self) -> &str {
+        "kev"
+    }
+
+    async fn enrich(&self, context: &EnrichmentContext<'_>) -> anyhow::Result<Labels> {
+        if context.is_sbom() {
+            return Ok(Labels::new());
+        }
+
+        let vulnerabilities: Vec<String> = advisory_vulnerability::Entity::find()
+            .select_only()
+            .column(advisory_vulnerability::Column::VulnerabilityId)
+            .filter(advisory_vulnerability::Column::AdvisoryId.eq(context.id))
+            .into_tuple()
+            .all(&context.graph.db)
+            .await?;
+
+        if vulnerabilities.is_empty() {
+            return Ok(Labels::new());
+        }
+
+        let catalog = self.catalog().await?;
+
+        Ok(
+            match vulnerabilities.iter().any(|id| catalog.contains(id)) {
+                true => Labels::new().add("kev", "true"),
+                false => Labels::new(),
+            },
+        )
+    }
+}
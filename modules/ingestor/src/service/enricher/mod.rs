@@ -0,0 +1,161 @@
+//! Enrichers, running as ordered stages after a document got ingested.
+//!
+//! Each stage gets access to the graph and the ingested document, and may return labels, which
+//! get applied to the labels of the document. Later stages see the labels of earlier stages.
+//! Failing stages are reported as warnings of the ingestion, unless their failure policy is to
+//! fail it.
+
+pub mod kev;
+pub mod webhook;
+
+use super::{Error, Format, document::Document, registry::Registry};
+use crate::{graph::Graph, model::IngestResult};
+use async_trait::async_trait;
+use std::sync::Arc;
+use trustify_common::{hashing::Digests, id::Id};
+use trustify_entity::labels::Labels;
+use uuid::Uuid;
+
+/// The context of an ingested document, handed to each stage.
+pub struct EnrichmentContext<'a> {
+    pub graph: &'a Graph,
+    /// The format of the document, either an SBOM or an advisory format
+    pub format: Format,
+    /// The internal ID of the SBOM or advisory
+    pub id: Uuid,
+    /// The ID declared by the document
+    pub document_id: Option<&'a str>,
+    pub digests: &'a Digests,
+    /// The labels of the document, including the ones of previous stages
+    pub labels: &'a Labels,
+}
+
+impl EnrichmentContext<'_> {
+    /// Check if the document is an SBOM, otherwise it is an advisory.
+    pub fn is_sbom(&self) -> bool {
+        matches!(Document::from_format(self.format), Some(Document::Sbom))
+    }
+}
+
+/// What to do if a stage fails.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    strum::EnumString,
+    strum::Display,
+    strum::VariantNames,
+)]
+#[strum(serialize_all = "camelCase")]
+pub enum FailurePolicy {
+    /// Keep the ingested document, reporting the failure as a warning of the ingestion.
+    #[default]
+    Warn,
+    /// Report the ingestion as failed. The document remains ingested, with the labels of the
+    /// stages which ran before.
+    Fail,
+}
+
+/// A stage, enriching ingested documents.
+#[async_trait]
+pub trait Enricher: Send + Sync {
+    /// The name of the stage, used when reporting failures.
+    fn name(&self) -> &str;
+
+    /// What to do if the stage fails.
+    fn policy(&self) -> FailurePolicy {
+        FailurePolicy::Warn
+    }
+
+    /// Enrich an ingested document, returning labels to apply to it. Labels with an empty value
+    /// get removed.
+    async fn enrich(&self, context: &EnrichmentContext<'_>) -> anyhow::Result<Labels>;
+}
+
+/// The built-in stages, which can be enabled by configuration.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, strum::EnumString, strum::Display, strum::VariantNames,
+)]
+#[strum(serialize_all = "camelCase")]
+pub enum BuiltinEnricher {
+    /// Flag advisories of vulnerabilities, which are known to be exploited.
+    Kev,
+}
+
+/// A set of stages, run in the order they were added.
+pub type Enrichers = Registry<dyn Enricher>;
+
+impl Enrichers {
+    /// Add a stage.
+    pub fn with(self, enricher: impl Enricher + 'static) -> Self {
+        self.push(Arc::new(enricher))
+    }
+
+    /// Run all stages for an ingested document, applying their failure policies.
+    pub(crate) async fn run(
+        &self,
+        graph: &Graph,
+        format: Format,
+        digests: &Digests,
+        result: &mut IngestResult,
+    ) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        // only SBOMs and advisories carry labels
+        let (Some(document), Id::Uuid(id)) = (Document::from_format(format), &result.id) else {
+            return Ok(());
+        };
+        let id = *id;
+        let Some(original) = document.labels(graph, id).await? else {
+            return Ok(());
+        };
+
+        let mut labels = original.clone();
+        let mut failure = None;
+
+        for enricher in self.iter() {
+            let context = EnrichmentContext {
+                graph,
+                format,
+                id,
+                document_id: result.document_id.as_deref(),
+                digests,
+                labels: &labels,
+            };
+
+            match enricher.enrich(&context).await {
+                Ok(update) => labels = labels.apply(update),
+                Err(err) => {
+                    log::warn!(
+                        "Enricher '{}' failed for document {}: {err}",
+                        enricher.name(),
+                        result.id
+                    );
+                    match enricher.policy() {
+                        FailurePolicy::Warn => result
+                            .warnings
+                            .push(format!("Enricher '{}' failed: {err}", enricher.name())),
+                        FailurePolicy::Fail => {
+                            failure = Some(err);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        if labels != original {
+            document.set_labels(graph, id, labels).await?;
+        }
+
+        match failure {
+            Some(err) => Err(Error::Enrichment(err)),
+            None => Ok(()),
+        }
+    }
+}
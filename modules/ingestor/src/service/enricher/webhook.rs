@@ -0,0 +1,144 @@
+//! Enrichment of ingested documents by an external service.
+//!
+//! The service is called as a stage of the [enrichers](super), before the built-in ones. After an
+//! uploaded document got ingested, a summary of it is sent to the configured service, using a
+//! `POST` request:
+//!
+//! ```json
+//! {
+//!   "id": "urn:uuid:01934e6c-…",
+//!   "documentId": "https://example.com/sbom/1",
+//!   "format": "spdx",
+//!   "sha256": "e3b0c442…",
+//!   "labels": { "type": "spdx", "source": "upload" }
+//! }
+//! ```
+//!
+//! The service may respond with labels, which get applied to the labels of the document. Like
+//! with the label update API, labels with an empty value get removed.
+//!
+//! ```json
+//! { "labels": { "team": "platform" } }
+//! ```
+//!
+//! Only labels are supported, there is no field for annotations or structured metadata. Other
+//! fields of the response are ignored. See `docs/design/enrichment.md` for the full contract.
+
+use super::{Enricher, EnrichmentContext, FailurePolicy};
+use async_trait::async_trait;
+use hex::ToHex;
+use reqwest::Url;
+use std::time::Duration;
+use trustify_common::reqwest::ClientFactory;
+use trustify_entity::labels::Labels;
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EnrichmentRequest<'a> {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    document_id: Option<&'a str>,
+    format: &'static str,
+    sha256: String,
+    labels: &'a Labels,
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+struct EnrichmentResponse {
+    #[serde(default)]
+    labels: Labels,
+}
+
+/// The configuration of the enrichment service.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebhookConfig {
+    /// The URL of the enrichment service.
+    pub url: Url,
+    /// The timeout of calling the service.
+    pub timeout: Duration,
+    /// What to do if calling the service fails.
+    pub policy: FailurePolicy,
+}
+
+/// Calls an external service for each ingested document, attaching the labels it returns.
+pub struct WebhookEnricher {
+    config: WebhookConfig,
+    client: reqwest::Client,
+}
+
+impl WebhookEnricher {
+    pub fn new(config: WebhookConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: ClientFactory::new()
+                .new_builder()?
+                .timeout(config.timeout)
+                .build()?,
+            config,
+        })
+    }
+}
+
+#[async_trait]
+impl Enricher for WebhookEnricher {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    fn policy(&self) -> FailurePolicy {
+        self.config.policy
+    }
+
+    async fn enrich(&self, context: &EnrichmentContext<'_>) -> anyhow::Result<Labels> {
+        let response: EnrichmentResponse = self
+            .client
+            .post(self.config.url.clone())
+            .json(&EnrichmentRequest {
+                id: context.id.urn().to_string(),
+                document_id: context.document_id,
+                format: context.format.into(),
+                sha256: context.digests.sha256.encode_hex(),
+                labels: context.labels,
+            })
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(response.labels)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use test_log::test;
+
+    #[test]
+    fn policy() {
+        assert_eq!("warn".parse::<FailurePolicy>(), Ok(FailurePolicy::Warn));
+        assert_eq!("fail".parse::<FailurePolicy>(), Ok(FailurePolicy::Fail));
+        assert!("ignore".parse::<FailurePolicy>().is_err());
+    }
+
+    #[test]
+    fn response() -> anyhow::Result<()> {
+        let response: EnrichmentResponse = serde_json::from_value(json!({}))?;
+        assert!(response.labels.is_empty());
+
+        // only labels are supported
+        let response: EnrichmentResponse =
+            serde_json::from_value(json!({"annotations": {"owner": {"team": "platform"}}}))?;
+        assert!(response.labels.is_empty());
+
+        let response: EnrichmentResponse =
+            serde_json::from_value(json!({"labels": {"team": "platform", "stale": ""}}))?;
+        assert_eq!(
+            Labels::new().add("stale", "yes").apply(response.labels),
+            Labels::new().add("team", "platform")
+        );
+
+        Ok(())
+    }
+}
@@ -3,12 +3,16 @@ use crate::{
     graph::{Graph, sbom::clearly_defined::Curation},
     model::IngestResult,
     service::{
-        Error,
+        Error, Retry,
         advisory::{csaf::loader::CsafLoader, cve::loader::CveLoader, osv::loader::OsvLoader},
+        audit::Upload,
+        oci::OciArchive,
         sbom::{
-            clearly_defined_curation::ClearlyDefinedCurationLoader, cyclonedx::CyclonedxLoader,
-            spdx::SpdxLoader,
+            clearly_defined_curation::ClearlyDefinedCurationLoader,
+            cyclonedx::{CyclonedxLoader, xml},
+            spdx::{SpdxLoader, tag_value},
         },
+        schema,
         weakness::CweCatalogLoader,
     },
 };
@@ -16,13 +20,24 @@ use csaf::Csaf;
 use cve::Cve;
 use jsn::{Format as JsnFormat, TokenReader, mask::*};
 use quick_xml::{Reader, events::Event};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 use serde_json::Value;
 use std::io::Cursor;
+use std::str::FromStr;
 use tracing::instrument;
 use trustify_common::hashing::Digests;
 use trustify_entity::labels::Labels;
 
-#[derive(Clone, Copy, Debug, strum::EnumString)]
+/// Versions of the CSAF specification supported for ingestion.
+pub const CSAF_VERSIONS: &[&str] = &["2.0"];
+/// Versions of the CVE record format supported for ingestion.
+pub const CVE_VERSIONS: &[&str] = &["5.0", "5.1"];
+/// Versions of the SPDX specification supported for ingestion.
+pub const SPDX_VERSIONS: &[&str] = &["2.2", "2.3"];
+/// Versions of the CycloneDX specification supported for ingestion.
+pub const CYCLONEDX_VERSIONS: &[&str] = &["1.3", "1.4", "1.5", "1.6"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::EnumString, strum::IntoStaticStr)]
 #[strum(serialize_all = "camelCase")]
 pub enum Format {
     OSV,
@@ -33,14 +48,69 @@ pub enum Format {
     ClearlyDefinedCuration,
     ClearlyDefined,
     CweCatalog,
+    /// A container image, containing SBOMs
+    OciImage,
     // These should be resolved to one of the above before loading
     Advisory,
     SBOM,
     Unknown,
 }
 
+/// The detected format of a document, and why it was detected as such.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Detection {
+    pub format: Format,
+    /// The reason for detecting the format, like "it has a top-level `spdxVersion` field".
+    pub reason: &'static str,
+}
+
+impl Detection {
+    /// Check the detected format belongs to the expected generic format.
+    fn expect(self, expected: Format) -> Result<Format, Error> {
+        expected
+            .with_override(Some(self.format))
+            .map_err(|_| Error::FormatMismatch {
+                detected: format!("{:?}", self.format),
+                reason: self.reason,
+                expected: expected.describe(),
+            })
+    }
+}
+
+impl Serialize for Format {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.into())
+    }
+}
+
+impl<'de> Deserialize<'de> for Format {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::from_str(&value).map_err(|_| de::Error::custom(format!("unknown format: {value}")))
+    }
+}
+
 impl Format {
-    #[instrument(skip(self, graph, buffer))]
+    /// The formats which can be ingested, along with the versions of their specification
+    /// supported. An empty list of versions means the format isn't versioned.
+    pub fn supported() -> [(Self, &'static [&'static str]); 8] {
+        [
+            (Self::CSAF, CSAF_VERSIONS),
+            (Self::CVE, CVE_VERSIONS),
+            (Self::OSV, &[]),
+            (Self::SPDX, SPDX_VERSIONS),
+            (Self::CycloneDX, CYCLONEDX_VERSIONS),
+            (Self::ClearlyDefined, &[]),
+            (Self::CweCatalog, &[]),
+            (Self::OciImage, &[]),
+        ]
+    }
+
+    /// Load the document into the graph.
+    ///
+    /// Loading is retried when failing due to a transient database error, like a serialization
+    /// failure caused by concurrently ingested documents.
+    #[instrument(skip(self, graph, buffer, upload))]
     pub async fn load(
         &self,
         graph: &'_ Graph,
@@ -48,33 +118,64 @@ impl Format {
         issuer: Option<String>,
         digests: &Digests,
         buffer: &[u8],
+        upload: Option<&Upload>,
+    ) -> Result<IngestResult, Error> {
+        // reject malformed documents right away, pointing to the offending locations
+        schema::validate(*self, buffer)?;
+
+        Retry::default()
+            .run(|| {
+                self.load_once(
+                    graph,
+                    labels.clone(),
+                    issuer.clone(),
+                    digests,
+                    buffer,
+                    upload,
+                )
+            })
+            .await
+    }
+
+    async fn load_once(
+        &self,
+        graph: &'_ Graph,
+        labels: Labels,
+        issuer: Option<String>,
+        digests: &Digests,
+        buffer: &[u8],
+        upload: Option<&Upload>,
     ) -> Result<IngestResult, Error> {
         match self {
             Format::CSAF => {
                 // issuer is internal as publisher of the document.
-                let loader = CsafLoader::new(graph);
+                let loader = CsafLoader::new(graph).upload(upload);
                 let csaf: Csaf = serde_json::from_slice(buffer)?;
                 loader.load(labels, csaf, digests).await
             }
             Format::OSV => {
                 // issuer is :shrug: sometimes we can tell, sometimes not :shrug:
-                let loader = OsvLoader::new(graph);
+                let loader = OsvLoader::new(graph).upload(upload);
                 let osv = super::advisory::osv::parse(buffer)?;
                 loader.load(labels, osv, digests, issuer).await
             }
             Format::CVE => {
                 // issuer is always CVE Project
-                let loader = CveLoader::new(graph);
+                let loader = CveLoader::new(graph).upload(upload);
                 let cve: Cve = serde_json::from_slice(buffer)?;
                 loader.load(labels, cve, digests).await
             }
             Format::SPDX => {
-                let loader = SpdxLoader::new(graph);
-                let v: Value = serde_json::from_slice(buffer)?;
+                let loader = SpdxLoader::new(graph).upload(upload);
+                let v: Value = if tag_value::is_tag_value(buffer) {
+                    tag_value::to_value(buffer)?
+                } else {
+                    serde_json::from_slice(buffer)?
+                };
                 loader.load(labels, v, digests).await
             }
             Format::CycloneDX => {
-                let loader = CyclonedxLoader::new(graph);
+                let loader = CyclonedxLoader::new(graph).upload(upload);
                 loader.load(labels, buffer, digests).await
             }
             Format::ClearlyDefined => {
@@ -91,53 +192,144 @@ impl Format {
                 let loader = CweCatalogLoader::new(graph);
                 loader.load_bytes(labels, buffer, digests).await
             }
+            Format::OciImage => Err(Error::UnsupportedFormat(
+                "OCI images contain several documents, and must be ingested as an image".into(),
+            )),
             f => Err(Error::UnsupportedFormat(format!(
                 "Must resolve {f:?} to an actual format"
             ))),
         }
     }
 
+    /// The prefix used by label template expressions to refer to documents of this format.
+    pub fn template_prefix(&self) -> Option<&'static str> {
+        match self {
+            Format::CSAF => Some("csaf"),
+            Format::CVE => Some("cve"),
+            Format::OSV => Some("osv"),
+            Format::SPDX => Some("spdx"),
+            Format::CycloneDX => Some("cyclonedx"),
+            _ => None,
+        }
+    }
+
+    /// Narrow a generic format down to an explicitly requested one.
+    ///
+    /// Fails if the requested format doesn't belong to the generic one, like requesting SPDX
+    /// when uploading an advisory.
+    pub fn with_override(self, requested: Option<Format>) -> Result<Self, Error> {
+        let Some(requested) = requested else {
+            return Ok(self);
+        };
+
+        let accepted = match self {
+            Self::Advisory => matches!(requested, Self::CSAF | Self::CVE | Self::OSV),
+            Self::SBOM => matches!(
+                requested,
+                Self::SPDX | Self::CycloneDX | Self::ClearlyDefinedCuration | Self::ClearlyDefined
+            ),
+            Self::Unknown => !matches!(requested, Self::Advisory | Self::SBOM),
+            _ => requested == self,
+        };
+
+        if accepted {
+            Ok(requested)
+        } else {
+            Err(Error::UnsupportedFormat(format!(
+                "{requested:?} can't be uploaded as {}",
+                self.describe()
+            )))
+        }
+    }
+
+    /// A description of what documents of this format are, for use in messages.
+    fn describe(&self) -> &'static str {
+        match self {
+            Self::Advisory | Self::CSAF | Self::CVE | Self::OSV => "an advisory",
+            Self::SBOM
+            | Self::SPDX
+            | Self::CycloneDX
+            | Self::ClearlyDefinedCuration
+            | Self::ClearlyDefined => "an SBOM",
+            Self::CweCatalog => "a CWE catalog",
+            Self::OciImage => "an OCI image",
+            Self::Unknown => "a document",
+        }
+    }
+
+    /// Detect the format of a document, along with the reason for detecting it.
+    ///
+    /// SBOM formats are checked before OSV, as its markers are the least specific ones.
+    #[instrument(skip_all, ret)]
+    pub fn detect(bytes: &[u8]) -> Result<Option<Detection>, Error> {
+        let detected = if OciArchive::is_image(bytes) {
+            Some((
+                Self::OciImage,
+                "it is a tar archive with an image index or manifest",
+            ))
+        } else if Self::is_csaf(bytes)? {
+            Some((Self::CSAF, "it has a `document.csaf_version` field"))
+        } else if Self::is_cve(bytes)? {
+            Some((Self::CVE, "it has a top-level `dataType` field"))
+        } else if Self::is_spdx(bytes)? {
+            Some((Self::SPDX, "it has a top-level `spdxVersion` field"))
+        } else if Self::is_spdx_tag_value(bytes)? {
+            Some((Self::SPDX, "it starts with an `SPDXVersion` tag"))
+        } else if Self::is_cyclonedx(bytes)? {
+            Some((Self::CycloneDX, "it has a top-level `specVersion` field"))
+        } else if Self::is_cyclonedx_xml(bytes)? {
+            Some((
+                Self::CycloneDX,
+                "its root element is in the CycloneDX namespace",
+            ))
+        } else if Self::is_osv_json(bytes)? {
+            Some((Self::OSV, "it has top-level `id` and `modified` fields"))
+        } else if Self::is_osv_yaml(bytes)? {
+            Some((Self::OSV, "it parses as an OSV document in YAML"))
+        } else if Self::is_clearly_defined(bytes)? {
+            Some((
+                Self::ClearlyDefinedCuration,
+                "it has a top-level `coordinates` field",
+            ))
+        } else if Self::is_cwe_catalog(bytes)? {
+            Some((
+                Self::CweCatalog,
+                "its root element references the CWE schema",
+            ))
+        } else {
+            None
+        };
+
+        Ok(detected.map(|(format, reason)| Detection { format, reason }))
+    }
+
     #[instrument(skip_all, ret)]
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
-        match Self::advisory_from_bytes(bytes) {
-            Err(Error::UnsupportedFormat(ea)) => match Self::sbom_from_bytes(bytes) {
-                Err(Error::UnsupportedFormat(es)) => match Self::is_cwe_catalog(bytes) {
-                    Ok(true) => Ok(Self::CweCatalog),
-                    _ => Err(Error::UnsupportedFormat(format!("{ea}\n{es}"))),
-                },
-                x => x,
-            },
-            x => x,
+        match Self::detect(bytes)? {
+            Some(detection) => Ok(detection.format),
+            None => Err(Error::UnsupportedFormat(
+                "Unable to detect the document format; only CSAF, CVE, OSV, SPDX, CycloneDX, and CWE catalogs are supported".into(),
+            )),
         }
     }
 
     #[instrument(skip_all, ret)]
     pub fn advisory_from_bytes(bytes: &[u8]) -> Result<Self, Error> {
-        if Self::is_csaf(bytes)? {
-            Ok(Format::CSAF)
-        } else if Self::is_cve(bytes)? {
-            Ok(Format::CVE)
-        } else if Self::is_osv(bytes)? {
-            Ok(Format::OSV)
-        } else {
-            Err(Error::UnsupportedFormat(
+        match Self::detect(bytes)? {
+            Some(detection) => detection.expect(Self::Advisory),
+            None => Err(Error::UnsupportedFormat(
                 "Unable to detect advisory format; only CSAF, CVE, and OSV are supported".into(),
-            ))
+            )),
         }
     }
 
     #[instrument(skip_all, ret)]
     pub fn sbom_from_bytes(bytes: &[u8]) -> Result<Self, Error> {
-        if Self::is_spdx(bytes)? {
-            Ok(Format::SPDX)
-        } else if Self::is_cyclonedx(bytes)? {
-            Ok(Format::CycloneDX)
-        } else if Self::is_clearly_defined(bytes)? {
-            Ok(Format::ClearlyDefinedCuration)
-        } else {
-            Err(Error::UnsupportedFormat(
+        match Self::detect(bytes)? {
+            Some(detection) => detection.expect(Self::SBOM),
+            None => Err(Error::UnsupportedFormat(
                 "Unable to detect SBOM format; only SPDX and CycloneDX are supported".into(),
-            ))
+            )),
         }
     }
 
@@ -163,9 +355,13 @@ impl Format {
     }
 
     pub fn is_osv_json(bytes: &[u8]) -> Result<bool, Error> {
-        match masked(depth(1).and(key("id")), bytes) {
-            Ok(Some(_)) => Ok(true),
-            Err(_) | Ok(None) => Ok(false),
+        // other formats might have an `id` too, but OSV requires `modified` as well
+        match (
+            masked(depth(1).and(key("id")), bytes),
+            masked(depth(1).and(key("modified")), bytes),
+        ) {
+            (Ok(Some(_)), Ok(Some(_))) => Ok(true),
+            _ => Ok(false),
         }
     }
 
@@ -176,24 +372,83 @@ impl Format {
 
     pub fn is_spdx(bytes: &[u8]) -> Result<bool, Error> {
         match masked(depth(1).and(key("spdxVersion")), bytes) {
-            Ok(Some(x)) if matches!(x.as_str(), "SPDX-2.2" | "SPDX-2.3") => Ok(true),
+            Ok(Some(x))
+                if x.strip_prefix("SPDX-")
+                    .is_some_and(|version| SPDX_VERSIONS.contains(&version)) =>
+            {
+                Ok(true)
+            }
             Ok(Some(x)) => Err(Error::UnsupportedFormat(format!(
-                "SPDX version {x} is unsupported; try 2.2 or 2.3"
+                "SPDX version {x} is unsupported; try {}",
+                SPDX_VERSIONS.join(" or ")
             ))),
             Err(_) | Ok(None) => Ok(false),
         }
     }
 
+    pub fn is_spdx_tag_value(bytes: &[u8]) -> Result<bool, Error> {
+        match tag_value::spdx_version(bytes) {
+            Some(x)
+                if x.strip_prefix("SPDX-")
+                    .is_some_and(|version| SPDX_VERSIONS.contains(&version)) =>
+            {
+                Ok(true)
+            }
+            Some(x) => Err(Error::UnsupportedFormat(format!(
+                "SPDX version {x} is unsupported; try {}",
+                SPDX_VERSIONS.join(" or ")
+            ))),
+            None => Ok(false),
+        }
+    }
+
     pub fn is_cyclonedx(bytes: &[u8]) -> Result<bool, Error> {
         match masked(depth(1).and(key("specVersion")), bytes) {
-            Ok(Some(x)) if matches!(x.as_str(), "1.3" | "1.4" | "1.5" | "1.6") => Ok(true),
+            Ok(Some(x)) if CYCLONEDX_VERSIONS.contains(&x.as_str()) => Ok(true),
             Ok(Some(x)) => Err(Error::UnsupportedFormat(format!(
-                "CycloneDX version {x} is unsupported; try 1.3, 1.4, 1.5, 1.6"
+                "CycloneDX version {x} is unsupported; try {}",
+                CYCLONEDX_VERSIONS.join(", ")
             ))),
             Err(_) | Ok(None) => Ok(false),
         }
     }
 
+    pub fn is_cyclonedx_xml(bytes: &[u8]) -> Result<bool, Error> {
+        let mut reader = Reader::from_reader(Cursor::new(bytes));
+
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(event) | Event::Empty(event)) => {
+                    if event.local_name().into_inner() != b"bom" {
+                        return Ok(false);
+                    }
+
+                    // the namespace carries the version, like `http://cyclonedx.org/schema/bom/1.5`
+                    let version = event.attributes().flatten().find_map(|attr| {
+                        if !attr.key.as_ref().starts_with(b"xmlns") {
+                            return None;
+                        }
+                        String::from_utf8_lossy(&attr.value)
+                            .strip_prefix(xml::NAMESPACE)
+                            .map(ToString::to_string)
+                    });
+
+                    return match version {
+                        Some(version) if CYCLONEDX_VERSIONS.contains(&version.as_str()) => Ok(true),
+                        Some(version) => Err(Error::UnsupportedFormat(format!(
+                            "CycloneDX version {version} is unsupported; try {}",
+                            CYCLONEDX_VERSIONS.join(", ")
+                        ))),
+                        None => Ok(false),
+                    };
+                }
+                Err(_) | Ok(Event::Eof) => return Ok(false),
+                _ => buf.clear(),
+            }
+        }
+    }
+
     pub fn is_clearly_defined(bytes: &[u8]) -> Result<bool, Error> {
         // first just try to get some YAML.
         if let Ok(candidate) = serde_yml::from_slice::<'_, serde_yml::Value>(bytes) {
@@ -294,9 +549,18 @@ mod test {
             Ok(Format::CycloneDX)
         ));
 
+        let cyclone = document_bytes("cyclonedx/maven.cdx.xml").await?;
+        assert!(matches!(
+            Format::from_bytes(&cyclone),
+            Ok(Format::CycloneDX)
+        ));
+
         let spdx = document_bytes("ubi9-9.2-755.1697625012.json").await?;
         assert!(matches!(Format::from_bytes(&spdx), Ok(Format::SPDX)));
 
+        let spdx = document_bytes("spdx/simple.spdx").await?;
+        assert!(matches!(Format::from_bytes(&spdx), Ok(Format::SPDX)));
+
         let indigestable = document_bytes("indigestable.json").await?;
         assert!(Format::from_bytes(&indigestable).is_err());
 
@@ -309,4 +573,41 @@ mod test {
 
         Ok(())
     }
+
+    #[test(tokio::test)]
+    async fn mismatch() -> Result<(), anyhow::Error> {
+        let cyclone = document_bytes("cyclonedx/simple_1dot6.json").await?;
+        match Format::advisory_from_bytes(&cyclone) {
+            Err(Error::FormatMismatch {
+                detected, expected, ..
+            }) => {
+                assert_eq!(detected, "CycloneDX");
+                assert_eq!(expected, "an advisory");
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        let csaf = document_bytes("csaf/CVE-2023-20862.json").await?;
+        assert!(matches!(
+            Format::sbom_from_bytes(&csaf),
+            Err(Error::FormatMismatch { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_override() {
+        assert_eq!(Format::SBOM.with_override(None).ok(), Some(Format::SBOM));
+        assert_eq!(
+            Format::SBOM.with_override(Some(Format::CycloneDX)).ok(),
+            Some(Format::CycloneDX)
+        );
+        assert!(Format::SBOM.with_override(Some(Format::OSV)).is_err());
+        assert_eq!(
+            Format::Advisory.with_override(Some(Format::OSV)).ok(),
+            Some(Format::OSV)
+        );
+        assert!(Format::Advisory.with_override(Some(Format::SBOM)).is_err());
+    }
 }
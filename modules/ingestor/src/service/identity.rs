@@ -0,0 +1,174 @@
+//! The canonical identity of documents.
+//!
+//! Documents are identified by a format specific ID, along with a revision distinguishing
+//! updates of the same document:
+//!
+//! | Format    | ID                                          | Revision              |
+//! |-----------|---------------------------------------------|-----------------------|
+//! | CSAF      | The publisher namespace and the tracking ID | The tracking version  |
+//! | CVE       | The CVE ID                                  | The date of update    |
+//! | OSV       | The ID                                      | The date of update    |
+//! | SPDX      | The document namespace                      | None, it's the ID     |
+//! | CycloneDX | The serial number                           | The version           |
+//!
+//! Documents with the same identity are expected to have the same content. Content is compared
+//! by the digest of its canonical JSON representation, so that copies only differing in their
+//! serialization, like ones served by different mirrors, are considered the same document.
+
+use crate::service::{
+    Format,
+    advisory::csaf::util::identifier,
+    sbom::{cyclonedx::xml, spdx::tag_value},
+};
+use csaf::document::Publisher;
+use hex::ToHex;
+use serde_json::Value;
+use trustify_common::{hashing::Digests, signing::canonical_json};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Identity {
+    pub format: Format,
+    /// The format specific ID of the document
+    pub identifier: String,
+    /// The revision of the document, if the format has one
+    pub revision: Option<String>,
+    /// The hex encoded SHA-256 digest of the canonical JSON representation
+    pub canonical_sha256: String,
+}
+
+impl Identity {
+    /// Evaluate the identity of a document, from its JSON representation.
+    ///
+    /// Returns `None` if the document doesn't carry an identity, like a CycloneDX document
+    /// without a serial number.
+    pub fn of(format: Format, document: &Value) -> Option<Self> {
+        let (identifier, revision) = match format {
+            Format::CSAF => {
+                let publisher: Publisher =
+                    serde_json::from_value(document.pointer("/document/publisher")?.clone())
+                        .ok()?;
+                (
+                    identifier(
+                        &publisher.namespace,
+                        &string(document, "/document/tracking/id")?,
+                    ),
+                    string(document, "/document/tracking/version"),
+                )
+            }
+            Format::CVE => (
+                string(document, "/cveMetadata/cveId")?,
+                string(document, "/cveMetadata/dateUpdated"),
+            ),
+            Format::OSV => (string(document, "/id")?, string(document, "/modified")),
+            Format::SPDX => (string(document, "/documentNamespace")?, None),
+            Format::CycloneDX => (
+                string(document, "/serialNumber")?,
+                document
+                    .get("version")
+                    .and_then(Value::as_i64)
+                    .map(|version| version.to_string()),
+            ),
+            _ => return None,
+        };
+
+        let canonical = canonical_json(document).ok()?;
+
+        Some(Self {
+            format,
+            identifier,
+            revision,
+            canonical_sha256: Digests::digest(&canonical).sha256.encode_hex(),
+        })
+    }
+}
+
+/// Parse a document into its JSON representation, once for evaluating both its identity and
+/// label templates.
+///
+/// Returns `None` for formats without an identity, or if the document can't be parsed.
+pub(crate) fn parse(format: Format, bytes: &[u8]) -> Option<Value> {
+    match format {
+        Format::CycloneDX if xml::is_xml(bytes) => xml::to_value(bytes).ok(),
+        Format::SPDX if tag_value::is_tag_value(bytes) => tag_value::to_value(bytes).ok(),
+        Format::OSV => serde_json::from_slice(bytes)
+            .ok()
+            .or_else(|| serde_yml::from_slice(bytes).ok()),
+        Format::CSAF | Format::CVE | Format::SPDX | Format::CycloneDX => {
+            serde_json::from_slice(bytes).ok()
+        }
+        _ => None,
+    }
+}
+
+fn string(document: &Value, pointer: &str) -> Option<String> {
+    document
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .map(ToString::to_string)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+    use trustify_test_context::document_bytes;
+
+    fn identity(format: Format, data: &[u8]) -> Option<Identity> {
+        Identity::of(format, &parse(format, data)?)
+    }
+
+    #[test(tokio::test)]
+    async fn csaf() -> anyhow::Result<()> {
+        let data = document_bytes("csaf/CVE-2023-20862.json").await?;
+        let identity = identity(Format::CSAF, &data).expect("must have an identity");
+
+        assert_eq!(
+            identity.identifier,
+            "https://www.redhat.com/#CVE-2023-20862"
+        );
+        assert!(identity.revision.is_some());
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn ignores_formatting() -> anyhow::Result<()> {
+        let data = document_bytes("osv/RUSTSEC-2021-0079.json").await?;
+        let reformatted = serde_json::to_vec(&serde_json::from_slice::<Value>(&data)?)?;
+        assert_ne!(data.as_ref(), reformatted.as_slice());
+
+        let identity = identity(Format::OSV, &data).expect("must have an identity");
+        let other = identity(Format::OSV, &reformatted).expect("must have an identity");
+
+        assert_eq!(identity.identifier, "RUSTSEC-2021-0079");
+        assert_eq!(identity, other);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn cyclonedx_xml() -> anyhow::Result<()> {
+        let json = document_bytes("cyclonedx/maven.cdx.json").await?;
+        let xml = document_bytes("cyclonedx/maven.cdx.xml").await?;
+
+        let identity = identity(Format::CycloneDX, &json).expect("must have an identity");
+        let other = identity(Format::CycloneDX, &xml).expect("must have an identity");
+
+        assert_eq!(
+            identity.identifier,
+            "urn:uuid:8d1b8f4a-6f3c-4c8e-9a51-2f0c1c6e4b77"
+        );
+        assert_eq!(identity.revision.as_deref(), Some("1"));
+        assert_eq!(identity, other);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn without_identity() -> anyhow::Result<()> {
+        let data = document_bytes("cyclonedx/simple.json").await?;
+        assert_eq!(identity(Format::CycloneDX, &data), None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,312 @@
+//! Asynchronous ingestion of documents.
+//!
+//! Submitted documents get persisted in the storage and queued as an ingestion job. A pool of
+//! workers, driven by the [`JobRunner`], picks up pending jobs and ingests the documents. Running
+//! jobs regularly update their heartbeat. Jobs of a worker which stopped doing so (e.g. because
+//! the process got terminated) are picked up again by another worker. Jobs which got abandoned
+//! [`MAX_ATTEMPTS`] times are considered failed, as processing them is likely what terminates
+//! the workers.
+
+use super::{Error, Format, IngestorService, audit::Upload, tenant::Tenant};
+use crate::model::{IngestResult, IngestionJob};
+use anyhow::anyhow;
+use futures_util::TryStreamExt;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+    Set, TransactionTrait,
+    sea_query::{Expr, LockBehavior, LockType},
+};
+use std::{fmt::Debug, str::FromStr, time::Duration};
+use time::OffsetDateTime;
+use tokio_util::io::ReaderStream;
+use tracing::instrument;
+use trustify_auth::tenant::TENANT_LABEL;
+use trustify_common::id::Id;
+use trustify_entity::{ingestion_job, labels::Labels};
+use trustify_module_storage::service::{StorageBackend, StorageKey};
+use uuid::Uuid;
+
+/// The interval of updating the heartbeat of a running job.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+/// The time after which a running job, without a heartbeat, is considered abandoned.
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// The interval of checking for new jobs, when there was nothing to do.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// The number of times processing a job gets started, before giving up on it.
+pub const MAX_ATTEMPTS: i32 = 3;
+
+impl IngestorService {
+    /// Submit a document for asynchronous ingestion, returning the ID of the created job.
+    ///
+    /// The external ID, if present, gets attached to the document once it got ingested. The upload,
+    /// if present, gets recorded in the audit trail of the document. The job belongs to the tenant
+    /// the labels got stamped with.
+    #[instrument(skip(self, bytes), err)]
+    pub async fn submit(
+        &self,
+        bytes: &[u8],
+        format: Format,
+        labels: impl Into<Labels> + Debug,
+        external_id: Option<String>,
+        upload: Option<&Upload>,
+    ) -> Result<Uuid, Error> {
+        let result = self
+            .storage
+            .store(ReaderStream::new(bytes))
+            .await
+            .map_err(|err| Error::Storage(anyhow!("{err}")))?;
+
+        let format: &'static str = format.into();
+        let labels = labels.into();
+        let tenant = labels.get(TENANT_LABEL).cloned();
+        let job = ingestion_job::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            state: Set(ingestion_job::State::Pending),
+            format: Set(format.to_string()),
+            labels: Set(labels),
+            document_key: Set(result.key().to_string()),
+            external_id: Set(external_id),
+            upload: Set(upload.map(serde_json::to_value).transpose()?),
+            tenant: Set(tenant),
+            submitted: Set(OffsetDateTime::now_utc()),
+            started: Set(None),
+            heartbeat: Set(None),
+            attempts: Set(0),
+            finished: Set(None),
+            result: Set(None),
+            error: Set(None),
+        }
+        .insert(&self.graph.db)
+        .await?;
+
+        log::debug!("Submitted ingestion job: {}", job.id);
+
+        Ok(job.id)
+    }
+
+    /// Fetch the state of an ingestion job, `None` if it doesn't exist or belongs to another
+    /// tenant.
+    #[instrument(skip(self), err(level=tracing::Level::INFO))]
+    pub async fn fetch_job(
+        &self,
+        id: Uuid,
+        tenant: &Tenant,
+    ) -> Result<Option<IngestionJob>, Error> {
+        Ok(ingestion_job::Entity::find_by_id(id)
+            .filter(tenant.column_condition(ingestion_job::Column::Tenant))
+            .one(&self.graph.db)
+            .await?
+            .map(IngestionJob::from))
+    }
+}
+
+/// Runs a pool of workers, processing submitted ingestion jobs.
+pub struct JobRunner {
+    ingestor: IngestorService,
+    workers: usize,
+}
+
+impl JobRunner {
+    pub fn new(ingestor: IngestorService, workers: usize) -> Self {
+        Self { ingestor, workers }
+    }
+
+    /// Run the workers, never returns.
+    pub async fn run(self) -> anyhow::Result<()> {
+        log::info!("Running {} ingestion worker(s)", self.workers);
+
+        futures_util::future::join_all((0..self.workers).map(|_| self.worker())).await;
+
+        Ok(())
+    }
+
+    async fn worker(&self) {
+        loop {
+            match self.next().await {
+                Ok(true) => {}
+                Ok(false) => tokio::time::sleep(POLL_INTERVAL).await,
+                Err(err) => {
+                    log::warn!("Failed to process ingestion job: {err}");
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    /// Claim and process the next job, returns `false` if there was none.
+    pub async fn next(&self) -> Result<bool, Error> {
+        let Some(job) = self.claim().await? else {
+            return Ok(false);
+        };
+
+        let id = job.id;
+        let process = self.process(job);
+        tokio::pin!(process);
+
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+        // the first tick completes immediately, and we just set the heartbeat when claiming
+        heartbeat.tick().await;
+
+        let result = loop {
+            tokio::select! {
+                result = &mut process => break result,
+                _ = heartbeat.tick() => {
+                    if let Err(err) = self.heartbeat(id).await {
+                        log::warn!("Failed to update heartbeat of ingestion job {id}: {err}");
+                    }
+                }
+            }
+        };
+
+        self.complete(id, result).await?;
+
+        Ok(true)
+    }
+
+    /// Claim the oldest pending, or abandoned, job.
+    async fn claim(&self) -> Result<Option<ingestion_job::Model>, Error> {
+        let now = OffsetDateTime::now_utc();
+        let tx = self.ingestor.graph.db.begin().await?;
+
+        let abandoned = Condition::all()
+            .add(ingestion_job::Column::State.eq(ingestion_job::State::Running))
+            .add(ingestion_job::Column::Heartbeat.lt(now - HEARTBEAT_TIMEOUT));
+
+        // give up on jobs which got abandoned too often
+
+        let failed = ingestion_job::Entity::update_many()
+            .col_expr(
+                ingestion_job::Column::State,
+                Expr::value(ingestion_job::State::Failed),
+            )
+            .col_expr(ingestion_job::Column::Finished, Expr::value(now))
+            .col_expr(
+                ingestion_job::Column::Error,
+                Expr::value(format!(
+                    "Processing the document was interrupted {MAX_ATTEMPTS} times"
+                )),
+            )
+            .filter(abandoned.clone())
+            .filter(ingestion_job::Column::Attempts.gte(MAX_ATTEMPTS))
+            .exec(&tx)
+            .await?;
+
+        if failed.rows_affected > 0 {
+            log::warn!(
+                "Failed {} ingestion job(s), after {MAX_ATTEMPTS} attempts",
+                failed.rows_affected
+            );
+        }
+
+        let Some(job) = ingestion_job::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(ingestion_job::Column::State.eq(ingestion_job::State::Pending))
+                    .add(abandoned),
+            )
+            .filter(ingestion_job::Column::Attempts.lt(MAX_ATTEMPTS))
+            .order_by_asc(ingestion_job::Column::Submitted)
+            // don't wait for jobs currently claimed by other workers
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+            .one(&tx)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        if job.state == ingestion_job::State::Running {
+            log::info!("Picking up abandoned ingestion job: {}", job.id);
+        }
+
+        let job = ingestion_job::ActiveModel {
+            id: Set(job.id),
+            state: Set(ingestion_job::State::Running),
+            started: Set(Some(now)),
+            heartbeat: Set(Some(now)),
+            attempts: Set(job.attempts + 1),
+            ..Default::default()
+        }
+        .update(&tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(job))
+    }
+
+    async fn heartbeat(&self, id: Uuid) -> Result<(), Error> {
+        ingestion_job::ActiveModel {
+            id: Set(id),
+            heartbeat: Set(Some(OffsetDateTime::now_utc())),
+            ..Default::default()
+        }
+        .update(&self.ingestor.graph.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Ingest the document of the job.
+    #[instrument(skip_all, fields(job.id = %job.id), err)]
+    async fn process(&self, job: ingestion_job::Model) -> Result<IngestResult, Error> {
+        let format = Format::from_str(&job.format)
+            .map_err(|_| Error::UnsupportedFormat(job.format.clone()))?;
+
+        let key = StorageKey::try_from(Id::Sha256(job.document_key))
+            .map_err(|err| Error::Storage(err.into()))?;
+        let bytes = self
+            .ingestor
+            .storage
+            .retrieve(key)
+            .await
+            .map_err(Error::Storage)?
+            .ok_or_else(|| Error::Storage(anyhow!("document missing from the storage")))?
+            .map_ok(|chunk| chunk.to_vec())
+            .try_concat()
+            .await
+            .map_err(Error::Storage)?;
+
+        let upload = job
+            .upload
+            .map(serde_json::from_value::<Upload>)
+            .transpose()?;
+
+        self.ingestor
+            .ingest_with_external_id(
+                &bytes,
+                format,
+                job.labels,
+                None,
+                job.external_id,
+                upload.as_ref(),
+            )
+            .await
+    }
+
+    /// Record the outcome of a job.
+    async fn complete(&self, id: Uuid, result: Result<IngestResult, Error>) -> Result<(), Error> {
+        let (state, result, error) = match result {
+            Ok(result) => (
+                ingestion_job::State::Succeeded,
+                Some(serde_json::to_value(result)?),
+                None,
+            ),
+            Err(err) => (ingestion_job::State::Failed, None, Some(err.to_string())),
+        };
+
+        ingestion_job::ActiveModel {
+            id: Set(id),
+            state: Set(state),
+            finished: Set(Some(OffsetDateTime::now_utc())),
+            result: Set(result),
+            error: Set(error),
+            ..Default::default()
+        }
+        .update(&self.ingestor.graph.db)
+        .await?;
+
+        log::debug!("Completed ingestion job {id}: {state:?}");
+
+        Ok(())
+    }
+}
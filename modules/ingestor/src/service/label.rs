@@ -0,0 +1,299 @@
+//! Labels derived from the content of a document.
+//!
+//! A label template has the form of `<key>=<template>`. The template may contain expressions in
+//! the form of `{{<format>.<path>}}`, which get evaluated against the parsed document. The
+//! format prefix must match the format of the document (`csaf`, `cve`, `osv`, `spdx`,
+//! `cyclonedx`), otherwise the template will be skipped. The path is a JSON path, relative to
+//! the root of the document. CycloneDX documents in XML and SPDX documents in tag-value format
+//! are evaluated against their JSON representation.
+//!
+//! For example: `product={{csaf.document.title}}` or `vendor={{spdx.creationInfo.creators[0]}}`.
+
+use crate::service::{
+    Format,
+    sbom::{cyclonedx::xml, spdx::tag_value},
+};
+use jsonpath_rust::JsonPath;
+use serde_json::Value;
+use std::str::FromStr;
+use trustify_entity::labels::Labels;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum LabelTemplateError {
+    #[error("missing '=' between label key and template")]
+    MissingSeparator,
+    #[error("label key must not be empty")]
+    EmptyKey,
+    #[error("unterminated expression in template: {0}")]
+    Unterminated(String),
+    #[error("expression must have the form of '<format>.<path>': {0}")]
+    InvalidExpression(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Expression { prefix: String, path: String },
+}
+
+/// A template for a single label.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LabelTemplate {
+    key: String,
+    segments: Vec<Segment>,
+}
+
+impl FromStr for LabelTemplate {
+    type Err = LabelTemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, template) = s
+            .split_once('=')
+            .ok_or(LabelTemplateError::MissingSeparator)?;
+
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(LabelTemplateError::EmptyKey);
+        }
+
+        let mut segments = vec![];
+        let mut rest = template;
+
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                segments.push(Segment::Text(rest[..start].to_string()));
+            }
+
+            let expression = &rest[start + 2..];
+            let end = expression
+                .find("}}")
+                .ok_or_else(|| LabelTemplateError::Unterminated(template.to_string()))?;
+
+            let (prefix, path) = expression[..end]
+                .trim()
+                .split_once('.')
+                .filter(|(prefix, path)| !prefix.is_empty() && !path.is_empty())
+                .ok_or_else(|| {
+                    LabelTemplateError::InvalidExpression(expression[..end].to_string())
+                })?;
+
+            segments.push(Segment::Expression {
+                prefix: prefix.to_lowercase(),
+                path: format!("$.{path}"),
+            });
+
+            rest = &expression[end + 2..];
+        }
+
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest.to_string()));
+        }
+
+        Ok(Self {
+            key: key.to_string(),
+            segments,
+        })
+    }
+}
+
+impl LabelTemplate {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Evaluate the template against a document.
+    ///
+    /// Returns `None` if the template doesn't apply to the format of the document, or if one
+    /// of the expressions didn't resolve to a scalar value.
+    fn evaluate(&self, prefix: &str, document: &Value) -> Option<String> {
+        let mut result = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => result.push_str(text),
+                Segment::Expression {
+                    prefix: expected,
+                    path,
+                } => {
+                    if expected != prefix {
+                        return None;
+                    }
+
+                    match document.query(path).ok()?.first()? {
+                        Value::String(value) => result.push_str(value),
+                        Value::Number(value) => result.push_str(&value.to_string()),
+                        Value::Bool(value) => result.push_str(&value.to_string()),
+                        _ => return None,
+                    }
+                }
+            }
+        }
+
+        Some(result)
+    }
+}
+
+/// A set of label templates, applied during ingestion.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LabelTemplates(Vec<LabelTemplate>);
+
+impl From<Vec<LabelTemplate>> for LabelTemplates {
+    fn from(value: Vec<LabelTemplate>) -> Self {
+        Self(value)
+    }
+}
+
+impl LabelTemplates {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Apply the templates to a document, adding the evaluated labels.
+    ///
+    /// Labels which are already present, e.g. provided explicitly by the uploader, take
+    /// precedence and will not be overridden.
+    pub fn apply(&self, format: Format, bytes: &[u8], labels: Labels) -> Labels {
+        if self.0.is_empty() || format.template_prefix().is_none() {
+            return labels;
+        }
+
+        let document = match serde_json::from_slice::<Value>(bytes) {
+            Ok(document) => Ok(document),
+            Err(_) if format == Format::CycloneDX && xml::is_xml(bytes) => {
+                xml::to_value(bytes).map_err(|err| err.to_string())
+            }
+            Err(_) if format == Format::SPDX && tag_value::is_tag_value(bytes) => {
+                tag_value::to_value(bytes).map_err(|err| err.to_string())
+            }
+            Err(_) => serde_yml::from_slice::<Value>(bytes).map_err(|err| err.to_string()),
+        };
+
+        match document {
+            Ok(document) => self.apply_document(format, &document, labels),
+            Err(err) => {
+                log::debug!("Unable to parse document for label templates: {err}");
+                labels
+            }
+        }
+    }
+
+    /// Apply the templates to an already parsed document, adding the evaluated labels.
+    pub fn apply_document(&self, format: Format, document: &Value, mut labels: Labels) -> Labels {
+        let Some(prefix) = format.template_prefix() else {
+            return labels;
+        };
+
+        for template in &self.0 {
+            if labels.contains_key(&template.key) {
+                continue;
+            }
+
+            if let Some(value) = template.evaluate(prefix, document) {
+                labels.insert(template.key.clone(), value);
+            }
+        }
+
+        labels
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use test_log::test;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            LabelTemplate::from_str("product={{csaf.document.title}}"),
+            Ok(LabelTemplate {
+                key: "product".into(),
+                segments: vec![Segment::Expression {
+                    prefix: "csaf".into(),
+                    path: "$.document.title".into()
+                }]
+            })
+        );
+        assert_eq!(
+            LabelTemplate::from_str("source=upload-{{ spdx.name }}!"),
+            Ok(LabelTemplate {
+                key: "source".into(),
+                segments: vec![
+                    Segment::Text("upload-".into()),
+                    Segment::Expression {
+                        prefix: "spdx".into(),
+                        path: "$.name".into()
+                    },
+                    Segment::Text("!".into()),
+                ]
+            })
+        );
+
+        assert_eq!(
+            LabelTemplate::from_str("{{csaf.document.title}}"),
+            Err(LabelTemplateError::MissingSeparator)
+        );
+        assert_eq!(
+            LabelTemplate::from_str("=foo"),
+            Err(LabelTemplateError::EmptyKey)
+        );
+        assert!(matches!(
+            LabelTemplate::from_str("product={{csaf.document.title"),
+            Err(LabelTemplateError::Unterminated(_))
+        ));
+        assert!(matches!(
+            LabelTemplate::from_str("product={{csaf}}"),
+            Err(LabelTemplateError::InvalidExpression(_))
+        ));
+    }
+
+    #[test]
+    fn apply() -> Result<(), anyhow::Error> {
+        let templates = LabelTemplates::from(vec![
+            LabelTemplate::from_str("product={{csaf.document.title}}")?,
+            LabelTemplate::from_str("vendor={{spdx.creationInfo.creators[0]}}")?,
+            LabelTemplate::from_str("tracking={{csaf.document.tracking.version}}")?,
+            LabelTemplate::from_str("missing={{csaf.document.foo}}")?,
+            LabelTemplate::from_str("source={{csaf.document.title}}")?,
+        ]);
+
+        let doc = serde_json::to_vec(&json!({
+            "document": {
+                "title": "Some advisory",
+                "tracking": {
+                    "version": 2
+                }
+            }
+        }))?;
+
+        let labels = templates.apply(Format::CSAF, &doc, Labels::new().add("source", "test"));
+
+        assert_eq!(
+            labels,
+            Labels::new()
+                .add("source", "test")
+                .add("product", "Some advisory")
+                .add("tracking", "2")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn apply_cyclonedx_xml() -> Result<(), anyhow::Error> {
+        let templates = LabelTemplates::from(vec![LabelTemplate::from_str(
+            "product={{cyclonedx.metadata.component.name}}",
+        )?]);
+
+        let doc = br#"<bom xmlns="http://cyclonedx.org/schema/bom/1.5" version="1">
+            <metadata><component type="application"><name>demo</name></component></metadata>
+        </bom>"#;
+
+        let labels = templates.apply(Format::CycloneDX, doc, Labels::new());
+
+        assert_eq!(labels, Labels::new().add("product", "demo"));
+
+        Ok(())
+    }
+}
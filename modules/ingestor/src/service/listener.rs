@@ -0,0 +1,32 @@
+//! Listeners, being notified about documents once they got ingested.
+
+use super::{Format, registry::Registry};
+use crate::model::IngestResult;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// A listener, being notified about each document which got ingested successfully.
+///
+/// Listeners are called after the document was fully ingested, and cannot fail the ingestion.
+/// They are expected to handle errors on their own.
+#[async_trait]
+pub trait IngestListener: Send + Sync {
+    async fn ingested(&self, format: Format, result: &IngestResult);
+}
+
+/// A set of listeners, notified in the order they were added.
+pub type IngestListeners = Registry<dyn IngestListener>;
+
+impl IngestListeners {
+    /// Add a listener.
+    pub fn with(self, listener: impl IngestListener + 'static) -> Self {
+        self.push(Arc::new(listener))
+    }
+
+    /// Notify all listeners about an ingested document.
+    pub async fn notify(&self, format: Format, result: &IngestResult) {
+        for listener in self.iter() {
+            listener.ingested(format, result).await;
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    model::IngestResult,
+    service::{Error, Format},
+};
+use opentelemetry::{
+    KeyValue, global,
+    metrics::{Counter, Histogram},
+};
+use std::time::Duration;
+
+/// Metrics of the ingestion process.
+#[derive(Clone)]
+pub(crate) struct IngestMetrics {
+    duration: Histogram<f64>,
+    documents: Counter<u64>,
+    failures: Counter<u64>,
+    warnings: Counter<u64>,
+}
+
+impl IngestMetrics {
+    pub(crate) fn new() -> Self {
+        let meter = global::meter("IngestorService");
+
+        Self {
+            duration: meter
+                .f64_histogram("ingestion_duration")
+                .with_unit("s")
+                .with_description("Time it took to ingest a document")
+                .build(),
+            documents: meter
+                .u64_counter("documents_ingested")
+                .with_description("Number of ingested documents")
+                .build(),
+            failures: meter
+                .u64_counter("ingestion_failures")
+                .with_description("Number of documents which failed to ingest")
+                .build(),
+            warnings: meter
+                .u64_counter("ingestion_warnings")
+                .with_description("Number of warnings reported while ingesting documents")
+                .build(),
+        }
+    }
+
+    /// Record the outcome of loading a single document.
+    pub(crate) fn record(
+        &self,
+        format: Format,
+        duration: Duration,
+        result: &Result<IngestResult, Error>,
+    ) {
+        let format: &'static str = format.into();
+        let attributes = [KeyValue::new("format", format)];
+
+        self.duration.record(duration.as_secs_f64(), &attributes);
+
+        match result {
+            Ok(result) => {
+                self.documents.add(1, &attributes);
+                self.warnings.add(result.warnings.len() as _, &attributes);
+            }
+            Err(_) => self.failures.add(1, &attributes),
+        }
+    }
+}
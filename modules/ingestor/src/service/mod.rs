@@ -1,27 +1,62 @@
 pub mod advisory;
+pub mod audit;
 pub mod dataset;
+pub mod enricher;
+pub mod job;
+pub mod listener;
+pub mod oci;
+pub mod registry;
 pub mod sbom;
+pub mod schema;
+pub mod tenant;
 pub mod weakness;
 
+mod document;
 mod format;
-pub use format::Format;
+mod identity;
+mod label;
+mod metrics;
+mod retry;
+mod update;
 
-use crate::service::dataset::{DatasetIngestResult, DatasetLoader};
+pub use format::{Detection, Format};
+pub use label::*;
+pub use retry::Retry;
+
+use crate::service::{
+    audit::Upload,
+    dataset::{DatasetIngestResult, DatasetLoader},
+    document::{Document, Identified},
+    enricher::Enrichers,
+    identity::Identity,
+    listener::IngestListeners,
+    metrics::IngestMetrics,
+    oci::{ImageSbom, OciArchive, OciImageIngestResult},
+    schema::{SchemaErrorInformation, SchemaViolation},
+};
 use crate::{graph::Graph, model::IngestResult};
 use actix_web::{HttpResponse, ResponseError, body::BoxBody};
 use anyhow::anyhow;
+use bytes::Bytes;
+use futures_util::TryStreamExt;
 use parking_lot::Mutex;
 use sbom_walker::report::ReportSink;
 use sea_orm::error::DbErr;
+use serde_json::json;
 use std::sync::Arc;
-use std::{fmt::Debug, time::Instant};
-use tokio::task::JoinError;
+use std::{collections::BTreeMap, fmt::Debug, time::Instant};
+use tokio::{runtime::Handle, task::JoinError};
 use tokio_util::io::ReaderStream;
 use tracing::instrument;
-use trustify_common::{error::ErrorInformation, id::IdError};
+use trustify_auth::tenant::TENANT_LABEL;
+use trustify_common::{
+    decompress::Limits,
+    error::ErrorInformation,
+    id::{Id, IdError},
+};
 use trustify_entity::labels::Labels;
 use trustify_module_analysis::service::AnalysisService;
-use trustify_module_storage::service::{StorageBackend, dispatch::DispatchBackend};
+use trustify_module_storage::service::{StorageBackend, StorageKey, dispatch::DispatchBackend};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -51,12 +86,25 @@ pub enum Error {
     InvalidContent(#[source] anyhow::Error),
     #[error("invalid format: {0}")]
     UnsupportedFormat(String),
+    #[error("document detected as {detected} because {reason}, but expected {expected}")]
+    FormatMismatch {
+        detected: String,
+        reason: &'static str,
+        expected: &'static str,
+    },
     #[error("failed to await the task: {0}")]
     Join(#[from] JoinError),
     #[error(transparent)]
     Zip(#[from] zip::result::ZipError),
-    #[error("payload too large")]
-    PayloadTooLarge,
+    #[error("payload too large, exceeding the limit of {limit} bytes")]
+    PayloadTooLarge { limit: usize },
+    #[error("failed to enrich the document: {0}")]
+    Enrichment(#[source] anyhow::Error),
+    #[error("document violates the {format:?} schema")]
+    SchemaViolation {
+        format: Format,
+        violations: Vec<SchemaViolation>,
+    },
 }
 
 impl ResponseError for Error {
@@ -127,6 +175,14 @@ impl ResponseError for Error {
                 message: format!("Unsupported document format: {fmt}"),
                 details: None,
             }),
+            Self::FormatMismatch { .. } => HttpResponse::BadRequest().json(ErrorInformation {
+                error: "FormatMismatch".into(),
+                message: self.to_string(),
+                details: Some(
+                    "Upload the document to the matching endpoint, or provide its format explicitly"
+                        .into(),
+                ),
+            }),
             Error::HashKey(inner) => HttpResponse::BadRequest().json(ErrorInformation {
                 error: "Digest key error".into(),
                 message: inner.to_string(),
@@ -137,11 +193,28 @@ impl ResponseError for Error {
                 message: inner.to_string(),
                 details: None,
             }),
-            Self::PayloadTooLarge => HttpResponse::PayloadTooLarge().json(ErrorInformation {
-                error: "PayloadTooLarge".into(),
-                message: self.to_string(),
+            Self::PayloadTooLarge { .. } => {
+                HttpResponse::PayloadTooLarge().json(ErrorInformation {
+                    error: "PayloadTooLarge".into(),
+                    message: self.to_string(),
+                    details: None,
+                })
+            }
+            Self::Enrichment(err) => HttpResponse::BadGateway().json(ErrorInformation {
+                error: "Enrichment".into(),
+                message: err.to_string(),
                 details: None,
             }),
+            Self::SchemaViolation { violations, .. } => {
+                HttpResponse::BadRequest().json(SchemaErrorInformation {
+                    error: ErrorInformation {
+                        error: "SchemaViolation".into(),
+                        message: self.to_string(),
+                        details: None,
+                    },
+                    violations: violations.clone(),
+                })
+            }
         }
     }
 }
@@ -151,6 +224,11 @@ pub struct IngestorService {
     graph: Graph,
     storage: DispatchBackend,
     analysis: Option<AnalysisService>,
+    label_templates: LabelTemplates,
+    enrichers: Enrichers,
+    listeners: IngestListeners,
+    dataset_concurrency: usize,
+    metrics: IngestMetrics,
 }
 
 impl IngestorService {
@@ -163,9 +241,38 @@ impl IngestorService {
             graph,
             storage: storage.into(),
             analysis,
+            label_templates: Default::default(),
+            enrichers: Default::default(),
+            listeners: Default::default(),
+            dataset_concurrency: 1,
+            metrics: IngestMetrics::new(),
         }
     }
 
+    /// Set templates for labels, which get evaluated against the content of ingested documents.
+    pub fn with_label_templates(mut self, label_templates: impl Into<LabelTemplates>) -> Self {
+        self.label_templates = label_templates.into();
+        self
+    }
+
+    /// Set stages, enriching documents after they got ingested.
+    pub fn with_enrichers(mut self, enrichers: Enrichers) -> Self {
+        self.enrichers = enrichers;
+        self
+    }
+
+    /// Set listeners, which get notified about documents after they got ingested.
+    pub fn with_listeners(mut self, listeners: IngestListeners) -> Self {
+        self.listeners = listeners;
+        self
+    }
+
+    /// Set the number of documents of a dataset, which get ingested in parallel.
+    pub fn with_dataset_concurrency(mut self, dataset_concurrency: usize) -> Self {
+        self.dataset_concurrency = dataset_concurrency;
+        self
+    }
+
     pub fn storage(&self) -> &DispatchBackend {
         &self.storage
     }
@@ -178,13 +285,30 @@ impl IngestorService {
         self.graph.db.clone()
     }
 
-    #[instrument(skip(self, bytes), err)]
     pub async fn ingest(
         &self,
         bytes: &[u8],
         format: Format,
         labels: impl Into<Labels> + Debug,
         issuer: Option<String>,
+    ) -> Result<IngestResult, Error> {
+        self.ingest_with_external_id(bytes, format, labels, issuer, None, None)
+            .await
+    }
+
+    /// Ingest a document, attaching an external ID to the resulting SBOM or advisory.
+    ///
+    /// The external ID replaces an existing one, in case the document was already ingested. The
+    /// upload, if present, gets recorded in the audit trail of a newly ingested document.
+    #[instrument(skip(self, bytes), err)]
+    pub async fn ingest_with_external_id(
+        &self,
+        bytes: &[u8],
+        format: Format,
+        labels: impl Into<Labels> + Debug,
+        issuer: Option<String>,
+        external_id: Option<String>,
+        upload: Option<&Upload>,
     ) -> Result<IngestResult, Error> {
         let start = Instant::now();
 
@@ -197,18 +321,115 @@ impl IngestorService {
             Format::Unknown => Format::from_bytes(bytes)?,
             v => v,
         };
+        if fmt == Format::OciImage {
+            return Err(Error::UnsupportedFormat(
+                "OCI images contain several documents, and must be ingested as an image".into(),
+            ));
+        }
+
+        // parse the document once, for evaluating both the label templates and its identity
+        let parsed = identity::parse(fmt, bytes);
+        let labels = match &parsed {
+            Some(parsed) => self
+                .label_templates
+                .apply_document(fmt, parsed, labels.into()),
+            None => self.label_templates.apply(fmt, bytes, labels.into()),
+        };
+
         let stream = ReaderStream::new(bytes);
 
-        let result = self
+        let stored = self
             .storage
             .store(stream)
             .await
             .map_err(|err| Error::Storage(anyhow!("{err}")))?;
 
+        // documents with the same identity, but different content, are conflicting
+        let identity = parsed.and_then(|parsed| Identity::of(fmt, &parsed));
+        let tenant = labels.get(TENANT_LABEL).cloned();
+
+        // report an already ingested document, instead of ingesting it again
+        if let Some(document) = Document::from_format(fmt) {
+            let mut found = document
+                .find_duplicate(&self.graph, &stored.digests, tenant.as_deref())
+                .await?;
+
+            if let (None, Some(identity)) = (&found, &identity) {
+                found = document
+                    .find_equivalent(&self.graph, identity, tenant.as_deref())
+                    .await?;
+            }
+
+            if let Some(result) = found {
+                if let (Some(external_id), Id::Uuid(id)) = (external_id, &result.id) {
+                    document
+                        .set_external_id(&self.graph, *id, external_id)
+                        .await?;
+                }
+                log::debug!("Document already ingested: {}", result.id);
+                return Ok(result);
+            }
+        }
+
+        let load_start = Instant::now();
         let result = fmt
-            .load(&self.graph, labels.into(), issuer, &result.digests, bytes)
+            .load(&self.graph, labels, issuer, &stored.digests, bytes, upload)
+            .await;
+        self.metrics.record(fmt, load_start.elapsed(), &result);
+        let mut result = result?;
+
+        if let Some(external_id) = external_id {
+            match (Document::from_format(fmt), &result.id) {
+                (Some(document), Id::Uuid(id)) => {
+                    document
+                        .set_external_id(&self.graph, *id, external_id)
+                        .await?;
+                }
+                _ => {
+                    result.warnings.push(format!(
+                        "External ID not supported for format {fmt:?}, ignoring it"
+                    ));
+                }
+            }
+        }
+
+        if let (Some(document), Some(identity), Id::Uuid(id), false) = (
+            Document::from_format(fmt),
+            &identity,
+            &result.id,
+            result.duplicate,
+        ) {
+            match document
+                .set_identity(&self.graph, *id, identity, tenant.as_deref())
+                .await?
+            {
+                Identified::Stored(conflicting) => {
+                    for existing in conflicting {
+                        result.warnings.push(format!(
+                            "Conflicts with {existing}, which has the same identity ({}), but different content",
+                            identity.identifier
+                        ));
+                    }
+                }
+                Identified::Equivalent(existing) => {
+                    result.warnings.push(format!(
+                        "Equivalent to {existing}, which got ingested at the same time"
+                    ));
+                }
+            }
+        }
+
+        self.enrichers
+            .run(&self.graph, fmt, &stored.digests, &mut result)
             .await?;
 
+        // keep the warnings, so that they can be reviewed later on
+        if let (Some(document), Id::Uuid(id)) = (Document::from_format(fmt), &result.id) {
+            document
+                .set_warnings(&self.graph, *id, &result.warnings)
+                .await?;
+        }
+
         if let Some(analysis) = &self.analysis {
             match fmt {
                 Format::SPDX | Format::CycloneDX => {
@@ -218,6 +439,7 @@ impl IngestorService {
                                 &self.graph.db,
                                 // TODO: today we chop off 'urn:uuid:' prefix using .split_off on result.id
                                 &vec![result.id.to_string().split_off("urn:uuid:".len())],
+                                None,
                             )
                             .await
                         {
@@ -237,6 +459,8 @@ impl IngestorService {
             };
         }
 
+        self.listeners.notify(fmt, &result).await;
+
         let duration = start.elapsed();
         log::debug!(
             "Ingested: {} ({:?}): took {}",
@@ -249,16 +473,137 @@ impl IngestorService {
     }
 
     /// Ingest a dataset archive
+    ///
+    /// The upload, if present, gets recorded in the audit trail of each newly ingested document.
     #[instrument(skip(self, bytes), err(level=tracing::Level::INFO))]
     pub async fn ingest_dataset(
         &self,
         bytes: &[u8],
         labels: impl Into<Labels> + Debug,
-        limit: usize,
+        limits: impl Into<Limits> + Debug,
+        upload: Option<&Upload>,
     ) -> Result<DatasetIngestResult, Error> {
-        let loader = DatasetLoader::new(self.graph(), self.storage(), limit);
+        let loader = DatasetLoader::new(self.graph(), self.storage(), limits.into())
+            .label_templates(&self.label_templates)
+            .upload(upload)
+            .enrichers(&self.enrichers)
+            .listeners(&self.listeners)
+            .concurrency(self.dataset_concurrency)
+            .metrics(&self.metrics);
         loader.load(labels.into(), bytes).await
     }
+
+    /// Store the identities of documents, which were ingested before identities were tracked,
+    /// returning the number of stored identities.
+    ///
+    /// Documents missing from the storage, or not carrying an identity, are skipped.
+    #[instrument(skip(self), err)]
+    pub async fn backfill_identities(&self) -> Result<u64, Error> {
+        const BATCH: u64 = 100;
+
+        let mut count = 0;
+
+        for document in [Document::Sbom, Document::Advisory] {
+            let mut after = None;
+            loop {
+                let batch = document.without_identity(&self.graph, after, BATCH).await?;
+                let Some((last, _, _)) = batch.last() else {
+                    break;
+                };
+                after = Some(*last);
+
+                for (id, sha256, tenant) in batch {
+                    let key = StorageKey::try_from(Id::Sha256(sha256))
+                        .map_err(|err| Error::Storage(err.into()))?;
+                    let Some(stream) = self.storage.retrieve(key).await.map_err(Error::Storage)?
+                    else {
+                        log::warn!("Document {id} is missing from the storage");
+                        continue;
+                    };
+                    let bytes = stream
+                        .map_ok(|chunk| chunk.to_vec())
+                        .try_concat()
+                        .await
+                        .map_err(Error::Storage)?;
+
+                    let format = match document {
+                        Document::Sbom => Format::sbom_from_bytes(&bytes),
+                        Document::Advisory => Format::advisory_from_bytes(&bytes),
+                    };
+                    let Some(identity) = format
+                        .ok()
+                        .and_then(|format| Identity::of(format, &identity::parse(format, &bytes)?))
+                    else {
+                        continue;
+                    };
+
+                    if let Identified::Stored(conflicting) = document
+                        .set_identity(&self.graph, id, &identity, tenant.as_deref())
+                        .await?
+                    {
+                        count += 1;
+                        for existing in conflicting {
+                            log::info!(
+                                "Document {id} conflicts with {existing}, which has the same identity ({}), but different content",
+                                identity.identifier
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Ingest the SBOMs contained in a container image tarball.
+    ///
+    /// Each SBOM gets labeled with the digest of its image (`ociImage`), and where it was found
+    /// in the image (`ociImageFile`). Failing to ingest an SBOM is reported as a warning. The
+    /// limit applies to each SBOM, zero meaning unlimited. The upload, if present, gets recorded
+    /// for each SBOM.
+    #[instrument(skip(self, bytes, upload), err(level=tracing::Level::INFO))]
+    pub async fn ingest_oci_image(
+        &self,
+        bytes: Bytes,
+        labels: impl Into<Labels> + Debug,
+        limit: usize,
+        upload: Option<&Upload>,
+    ) -> Result<OciImageIngestResult, Error> {
+        let labels = labels.into();
+
+        let (sboms, mut warnings) = Handle::current()
+            .spawn_blocking(move || OciArchive::new(&bytes, limit)?.extract())
+            .await??;
+
+        let mut images = BTreeMap::<String, BTreeMap<_, _>>::new();
+        for ImageSbom { image, path, data } in sboms {
+            let labels = labels
+                .clone()
+                .add("ociImage", &image)
+                .add("ociImageFile", &path);
+
+            let upload = upload.map(|upload| {
+                upload
+                    .clone()
+                    .details(json!({ "ociImage": &image, "ociImageFile": &path }))
+            });
+
+            match self
+                .ingest_with_external_id(&data, Format::SBOM, labels, None, None, upload.as_ref())
+                .await
+            {
+                Ok(result) => {
+                    images.entry(image).or_default().insert(path, result);
+                }
+                Err(err) => {
+                    warnings.push(format!("Error loading SBOM {path} of image {image}: {err}"));
+                }
+            }
+        }
+
+        Ok(OciImageIngestResult { warnings, images })
+    }
 }
 
 /// Capture warnings from the import process
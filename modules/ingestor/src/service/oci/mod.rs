@@ -0,0 +1,441 @@
+//! Extraction of SBOMs embedded in container images.
+//!
+//! Images are uploaded as a tarball, either in the format created by `docker save`, or as an
+//! OCI image layout (like created by `skopeo copy … oci-archive:image.tar`). SBOMs are taken from
+//! two places:
+//!
+//! * Files below one of the [`SBOM_PATHS`], in the file system of the image.
+//! * Artifacts referring to the image, using the `subject` of their manifest. Those are the
+//!   artifacts a registry reports through its referrers API, like SBOMs attached using
+//!   `oras attach` or `cosign attach sbom`, and copied along with the image.
+//!
+//! Each SBOM is labeled with the digest of the image it belongs to. Blobs of an OCI image layout
+//! must match their digest.
+
+use crate::{model::IngestResult, service::Error};
+use anyhow::anyhow;
+use flate2::read::GzDecoder;
+use hex::ToHex;
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::Read,
+    ops::Range,
+};
+use trustify_common::hashing::Digests;
+
+/// Paths, in the file system of an image, containing SBOMs.
+pub const SBOM_PATHS: &[&str] = &["usr/share/sbom/"];
+
+/// Media types of artifacts, or their layers, which are SBOMs.
+const SBOM_MEDIA_TYPES: &[&str] = &[
+    "application/spdx+json",
+    "text/spdx+json",
+    "application/vnd.cyclonedx+json",
+    "application/vnd.cyclonedx",
+];
+
+const OCI_INDEX: &str = "index.json";
+const DOCKER_MANIFEST: &str = "manifest.json";
+
+/// The maximum depth of nested indexes, below the index of the layout.
+const MAX_INDEX_DEPTH: usize = 8;
+
+/// The annotation carrying the file name of a layer.
+const ANNOTATION_TITLE: &str = "org.opencontainers.image.title";
+
+/// An SBOM, found in an image.
+#[derive(Clone, Debug)]
+pub struct ImageSbom {
+    /// The digest of the image, like `sha256:…`.
+    pub image: String,
+    /// Where the SBOM was found: the path in the file system of the image, or the name of the
+    /// referring artifact.
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct OciImageIngestResult {
+    pub warnings: Vec<String>,
+    /// The results of ingesting the SBOMs, by the digest of the image, and the path of the SBOM
+    pub images: BTreeMap<String, BTreeMap<String, IngestResult>>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Descriptor {
+    #[serde(default)]
+    media_type: String,
+    digest: String,
+    #[serde(default)]
+    artifact_type: Option<String>,
+    #[serde(default)]
+    annotations: BTreeMap<String, String>,
+}
+
+/// An OCI image index, or image manifest. Both share the same fields we are interested in.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    #[serde(default)]
+    media_type: Option<String>,
+    #[serde(default)]
+    artifact_type: Option<String>,
+    #[serde(default)]
+    config: Option<Descriptor>,
+    #[serde(default)]
+    subject: Option<Descriptor>,
+    #[serde(default)]
+    manifests: Vec<Descriptor>,
+    #[serde(default)]
+    layers: Vec<Descriptor>,
+}
+
+/// An entry of the `manifest.json` file, created by `docker save`.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerManifest {
+    config: String,
+    #[serde(default)]
+    layers: Vec<String>,
+}
+
+/// An image tarball, providing access to its files without copying them.
+pub struct OciArchive<'a> {
+    data: &'a [u8],
+    files: BTreeMap<String, Range<usize>>,
+    /// The maximum size of an extracted SBOM, zero meaning unlimited.
+    limit: usize,
+    warnings: Vec<String>,
+    /// The manifests already extracted, by their digest.
+    visited: HashSet<String>,
+}
+
+impl<'a> OciArchive<'a> {
+    pub fn new(data: &'a [u8], limit: usize) -> Result<Self, Error> {
+        let mut files = BTreeMap::new();
+
+        let mut archive = tar::Archive::new(data);
+        for entry in archive.entries()? {
+            let entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+
+            let start = entry.raw_file_position() as usize;
+            let end = start + entry.size() as usize;
+            if end > data.len() {
+                return Err(Error::InvalidContent(anyhow!("truncated image archive")));
+            }
+
+            files.insert(normalize(&entry.path()?.to_string_lossy()), start..end);
+        }
+
+        Ok(Self {
+            data,
+            files,
+            limit,
+            warnings: vec![],
+            visited: HashSet::new(),
+        })
+    }
+
+    /// Check if the data is an image tarball, without reading the content of its files.
+    pub fn is_image(data: &[u8]) -> bool {
+        // the magic of a (POSIX or GNU) tar header
+        if data.get(257..262) != Some(b"ustar") {
+            return false;
+        }
+
+        let mut archive = tar::Archive::new(data);
+        let Ok(entries) = archive.entries() else {
+            return false;
+        };
+
+        entries.flatten().any(|entry| {
+            entry.path().is_ok_and(|path| {
+                matches!(
+                    &*normalize(&path.to_string_lossy()),
+                    OCI_INDEX | DOCKER_MANIFEST
+                )
+            })
+        })
+    }
+
+    /// Extract all SBOMs from the images of the archive.
+    pub fn extract(mut self) -> Result<(Vec<ImageSbom>, Vec<String>), Error> {
+        let sboms = if let Some(index) = self.file(OCI_INDEX) {
+            let index: Manifest = serde_json::from_slice(index)?;
+            let mut sboms = vec![];
+            self.extract_index(&index, 0, &mut sboms)?;
+            sboms
+        } else if let Some(manifest) = self.file(DOCKER_MANIFEST) {
+            let manifest: Vec<DockerManifest> = serde_json::from_slice(manifest)?;
+            let mut sboms = vec![];
+            for image in manifest {
+                self.extract_docker(&image, &mut sboms)?;
+            }
+            sboms
+        } else {
+            return Err(Error::InvalidContent(anyhow!(
+                "neither an OCI image layout, nor an archive created by `docker save`"
+            )));
+        };
+
+        Ok((sboms, self.warnings))
+    }
+
+    fn file(&self, name: &str) -> Option<&'a [u8]> {
+        self.files.get(name).map(|range| &self.data[range.clone()])
+    }
+
+    /// Get a blob by its digest, verifying that its content matches the digest.
+    fn blob(&self, digest: &str) -> Result<&'a [u8], Error> {
+        let (algorithm, hex) = digest
+            .split_once(':')
+            .ok_or_else(|| Error::InvalidContent(anyhow!("invalid digest: {digest}")))?;
+
+        let data = self
+            .file(&format!("blobs/{algorithm}/{hex}"))
+            .ok_or_else(|| Error::InvalidContent(anyhow!("missing blob: {digest}")))?;
+
+        let digests = Digests::digest(data);
+        let actual = match algorithm {
+            "sha256" => digests.sha256,
+            "sha512" => digests.sha512,
+            _ => {
+                return Err(Error::InvalidContent(anyhow!(
+                    "unsupported digest algorithm: {digest}"
+                )));
+            }
+        };
+        if !actual.encode_hex::<String>().eq_ignore_ascii_case(hex) {
+            return Err(Error::InvalidContent(anyhow!(
+                "content of blob doesn't match its digest: {digest}"
+            )));
+        }
+
+        Ok(data)
+    }
+
+    /// Extract the SBOMs of the manifests of an index, at some depth of nested indexes.
+    ///
+    /// Manifests listed more than once, possibly by different indexes, are only extracted once.
+    fn extract_index(
+        &mut self,
+        index: &Manifest,
+        depth: usize,
+        sboms: &mut Vec<ImageSbom>,
+    ) -> Result<(), Error> {
+        if depth > MAX_INDEX_DEPTH {
+            return Err(Error::InvalidContent(anyhow!(
+                "indexes nested deeper than {MAX_INDEX_DEPTH} levels"
+            )));
+        }
+
+        for descriptor in &index.manifests {
+            if !self.visited.insert(descriptor.digest.clone()) {
+                log::debug!("Skipping manifest listed again: {}", descriptor.digest);
+                continue;
+            }
+
+            let manifest: Manifest = serde_json::from_slice(self.blob(&descriptor.digest)?)?;
+
+            if !manifest.manifests.is_empty() {
+                // a nested index, like one for multiple platforms
+                self.extract_index(&manifest, depth + 1, sboms)?;
+            } else if let Some(subject) = &manifest.subject {
+                self.extract_referrer(descriptor, &manifest, &subject.digest, sboms)?;
+            } else {
+                let layers = manifest
+                    .layers
+                    .iter()
+                    .map(|layer| Ok((layer.digest.clone(), self.blob(&layer.digest)?)))
+                    .collect::<Result<Vec<_>, Error>>()?;
+                self.extract_layers(&descriptor.digest, layers, sboms)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Extract the SBOMs of an artifact, referring to an image.
+    fn extract_referrer(
+        &self,
+        descriptor: &Descriptor,
+        manifest: &Manifest,
+        image: &str,
+        sboms: &mut Vec<ImageSbom>,
+    ) -> Result<(), Error> {
+        let artifact_type = manifest
+            .artifact_type
+            .as_deref()
+            .or(descriptor.artifact_type.as_deref())
+            .or(manifest
+                .config
+                .as_ref()
+                .map(|config| config.media_type.as_str()));
+        let is_sbom_artifact = artifact_type.is_some_and(is_sbom_media_type);
+
+        for layer in &manifest.layers {
+            if !is_sbom_artifact && !is_sbom_media_type(&layer.media_type) {
+                continue;
+            }
+
+            let data = self.blob(&layer.digest)?;
+            if self.limit > 0 && data.len() > self.limit {
+                return Err(Error::PayloadTooLarge { limit: self.limit });
+            }
+
+            let path = layer
+                .annotations
+                .get(ANNOTATION_TITLE)
+                .cloned()
+                .unwrap_or_else(|| layer.digest.clone());
+
+            sboms.push(ImageSbom {
+                image: image.to_string(),
+                path,
+                data: data.to_vec(),
+            });
+        }
+
+        log::debug!(
+            "Artifact {} ({:?}) refers to {image}",
+            descriptor.digest,
+            manifest.media_type
+        );
+
+        Ok(())
+    }
+
+    fn extract_docker(
+        &mut self,
+        image: &DockerManifest,
+        sboms: &mut Vec<ImageSbom>,
+    ) -> Result<(), Error> {
+        let config = self
+            .file(&normalize(&image.config))
+            .ok_or_else(|| Error::InvalidContent(anyhow!("missing config: {}", image.config)))?;
+
+        // the ID of the image is the digest of its config
+        let digest = format!(
+            "sha256:{}",
+            Digests::digest(config).sha256.encode_hex::<String>()
+        );
+
+        let layers = image
+            .layers
+            .iter()
+            .map(|name| {
+                let layer = self
+                    .file(&normalize(name))
+                    .ok_or_else(|| Error::InvalidContent(anyhow!("missing layer: {name}")))?;
+                Ok((name.clone(), layer))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        self.extract_layers(&digest, layers, sboms)
+    }
+
+    /// Extract the SBOMs of the file system, created by applying the layers in order.
+    ///
+    /// Failing to read a layer is reported as a warning, skipping the rest of that layer. Only
+    /// exceeding the size limit fails the whole image.
+    fn extract_layers(
+        &mut self,
+        image: &str,
+        layers: Vec<(String, &[u8])>,
+        sboms: &mut Vec<ImageSbom>,
+    ) -> Result<(), Error> {
+        let mut files = BTreeMap::<String, Vec<u8>>::new();
+
+        for (name, layer) in layers {
+            match self.apply_layer(layer, &mut files) {
+                Ok(()) => {}
+                Err(err @ Error::PayloadTooLarge { .. }) => return Err(err),
+                Err(err) => self.warnings.push(format!(
+                    "Failed to read layer {name} of image {image}: {err}"
+                )),
+            }
+        }
+
+        sboms.extend(files.into_iter().map(|(path, data)| ImageSbom {
+            image: image.to_string(),
+            path,
+            data,
+        }));
+
+        Ok(())
+    }
+
+    fn apply_layer(
+        &self,
+        layer: &[u8],
+        files: &mut BTreeMap<String, Vec<u8>>,
+    ) -> Result<(), Error> {
+        let reader: Box<dyn Read + '_> = if layer.starts_with(&[0x1f, 0x8b]) {
+            Box::new(GzDecoder::new(layer))
+        } else if layer.get(257..262) == Some(b"ustar") {
+            Box::new(layer)
+        } else {
+            return Err(Error::UnsupportedFormat(
+                "unsupported layer compression; only uncompressed and gzip compressed layers are supported".into(),
+            ));
+        };
+
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = normalize(&entry.path()?.to_string_lossy());
+
+            let Some(dir) = SBOM_PATHS.iter().find(|dir| path.starts_with(**dir)) else {
+                continue;
+            };
+
+            // whiteouts remove files of lower layers
+            let (parent, name) = path.rsplit_once('/').unwrap_or(("", &path));
+            if name == ".wh..wh..opq" {
+                files.retain(|file, _| !file.starts_with(&format!("{parent}/")));
+                continue;
+            }
+            if let Some(removed) = name.strip_prefix(".wh.") {
+                files.remove(&format!("{parent}/{removed}"));
+                continue;
+            }
+
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+
+            log::debug!("Found SBOM in {dir}: {path}");
+
+            let mut data = vec![];
+            if self.limit > 0 {
+                (&mut entry)
+                    .take(self.limit as u64 + 1)
+                    .read_to_end(&mut data)?;
+                if data.len() > self.limit {
+                    return Err(Error::PayloadTooLarge { limit: self.limit });
+                }
+            } else {
+                entry.read_to_end(&mut data)?;
+            }
+
+            files.insert(path, data);
+        }
+
+        Ok(())
+    }
+}
+
+/// Normalize the path of an entry, dropping a leading `./` or `/`.
+fn normalize(path: &str) -> String {
+    path.trim_start_matches("./")
+        .trim_start_matches('/')
+        .to_string()
+}
+
+fn is_sbom_media_type(media_type: &str) -> bool {
+    SBOM_MEDIA_TYPES.contains(&media_type)
+}
@@ -0,0 +1,57 @@
+//! An ordered set of extensions, like listeners or enrichers.
+
+use std::{fmt::Debug, sync::Arc};
+
+/// An ordered set of extensions, sharing the instances when being cloned.
+///
+/// The modules of the extensions provide the functions to add and run them, using the same
+/// order in which they got added.
+pub struct Registry<T: ?Sized>(Vec<Arc<T>>);
+
+impl<T: ?Sized> Registry<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(mut self, item: Arc<T>) -> Self {
+        self.0.push(item);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Arc<T>> {
+        self.0.iter()
+    }
+}
+
+impl<T: ?Sized> Default for Registry<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T: ?Sized> Clone for Registry<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: ?Sized> Debug for Registry<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Registry")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
+/// Registries are equal if they consist of the same instances.
+impl<T: ?Sized> PartialEq for Registry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len() && self.0.iter().zip(&other.0).all(|(a, b)| Arc::ptr_eq(a, b))
+    }
+}
+
+impl<T: ?Sized> Eq for Registry<T> {}
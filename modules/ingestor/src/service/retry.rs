@@ -0,0 +1,173 @@
+use super::Error;
+use rand::Rng;
+use std::time::Duration;
+use trustify_common::db::DatabaseErrors;
+
+/// Retrying an ingestion, which failed due to a transient database error.
+///
+/// Loading documents concurrently, touching the same rows (like PURLs), might fail with a
+/// serialization failure or a deadlock. Running the failed transaction again resolves this.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Retry {
+    /// Maximum number of attempts, including the first one.
+    pub attempts: usize,
+    /// The delay before the first retry, doubled for each following one.
+    pub backoff: Duration,
+    /// The upper bound of the delay between two attempts.
+    pub max_backoff: Duration,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self {
+            attempts: 5,
+            backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+impl Retry {
+    /// Run an operation, running it again for transient errors, until running out of attempts.
+    pub async fn run<F, Fut, T>(&self, mut f: F) -> Result<T, Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match f().await {
+                Err(err) if attempt < self.attempts && err.is_transient() => {
+                    let delay = self.delay(attempt);
+                    log::info!(
+                        "Attempt {attempt} failed with a transient error, retrying in {delay:?}: {err}"
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// The delay before the next attempt, jittered to spread out competing ingestions.
+    fn delay(&self, attempt: usize) -> Duration {
+        let exponent = u32::try_from(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        let delay = self
+            .backoff
+            .saturating_mul(2u32.saturating_pow(exponent))
+            .min(self.max_backoff);
+
+        // somewhere between half and the full delay
+        let half = delay / 2;
+        half + rand::rng().random_range(Duration::ZERO..=half)
+    }
+}
+
+impl Error {
+    /// Check if the error is caused by a serialization failure or deadlock of the database.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Db(err) | Self::Graph(crate::graph::error::Error::Database(err)) => {
+                err.is_transient()
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sea_orm::ConnectionTrait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_test_context::TrustifyContext;
+
+    const RETRY: Retry = Retry {
+        attempts: 3,
+        backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(5),
+    };
+
+    /// Fail with a real error of the database, carrying the provided SQLSTATE.
+    async fn fail(ctx: &TrustifyContext, code: &str) -> Result<(), Error> {
+        ctx.db
+            .execute_unprepared(&format!(
+                "DO $$ BEGIN RAISE EXCEPTION 'failed' USING ERRCODE = '{code}'; END $$"
+            ))
+            .await?;
+        Ok(())
+    }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn retry_transient(ctx: &TrustifyContext) -> anyhow::Result<()> {
+        let calls = AtomicUsize::new(0);
+
+        // a serialization failure and a deadlock, then success
+        RETRY
+            .run(|| async {
+                match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 => fail(ctx, "40001").await,
+                    1 => fail(ctx, "40P01").await,
+                    _ => Ok(()),
+                }
+            })
+            .await?;
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        Ok(())
+    }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn bounded_attempts(ctx: &TrustifyContext) -> anyhow::Result<()> {
+        let calls = AtomicUsize::new(0);
+
+        let result = RETRY
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                fail(ctx, "40001").await
+            })
+            .await;
+        assert!(matches!(result, Err(err) if err.is_transient()));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        Ok(())
+    }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn no_retry_otherwise(ctx: &TrustifyContext) -> anyhow::Result<()> {
+        let calls = AtomicUsize::new(0);
+
+        // a unique violation won't go away by trying again
+        let result = RETRY
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                fail(ctx, "23505").await
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn delay() {
+        let retry = Retry {
+            attempts: 10,
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+        };
+
+        for (attempt, max) in [(1, 100), (2, 200), (3, 400), (4, 800), (5, 1000), (9, 1000)] {
+            let delay = retry.delay(attempt);
+            let max = Duration::from_millis(max);
+            assert!(delay >= max / 2 && delay <= max, "{attempt}: {delay:?}");
+        }
+    }
+}
@@ -38,6 +38,7 @@ impl<'g> ClearlyDefinedLoader<'g> {
                 id: Id::Uuid(previously_found.sbom_id),
                 document_id: previously_found.document_id,
                 warnings: vec![],
+                duplicate: false,
             });
         }
 
@@ -87,6 +88,7 @@ impl<'g> ClearlyDefinedLoader<'g> {
                 id: Id::Uuid(sbom.sbom.sbom_id),
                 document_id: sbom.sbom.document_id,
                 warnings: vec![],
+                duplicate: false,
             })
         } else {
             Err(Error::Generic(anyhow!("No valid information")))
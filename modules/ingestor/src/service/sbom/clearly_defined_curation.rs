@@ -26,7 +26,7 @@ impl<'g> ClearlyDefinedCurationLoader<'g> {
     ) -> Result<IngestResult, Error> {
         let tx = self.graph.db.begin().await?;
 
-        let sbom = match self
+        let (sbom, duplicate) = match self
             .graph
             .ingest_sbom(
                 labels,
@@ -37,7 +37,7 @@ impl<'g> ClearlyDefinedCurationLoader<'g> {
             )
             .await?
         {
-            Outcome::Existed(sbom) => sbom,
+            Outcome::Existed(sbom) => (sbom, true),
             Outcome::Added(sbom) => {
                 sbom.ingest_clearly_defined_curation(curation, &tx)
                     .await
@@ -45,7 +45,7 @@ impl<'g> ClearlyDefinedCurationLoader<'g> {
 
                 tx.commit().await?;
 
-                sbom
+                (sbom, false)
             }
         };
 
@@ -53,6 +53,7 @@ impl<'g> ClearlyDefinedCurationLoader<'g> {
             id: Id::Uuid(sbom.sbom.sbom_id),
             document_id: sbom.sbom.document_id,
             warnings: vec![],
+            duplicate,
         })
     }
 }
@@ -61,8 +62,11 @@ impl<'g> ClearlyDefinedCurationLoader<'g> {
 mod test {
     use crate::graph::Graph;
     use crate::service::{Format, IngestorService};
+    use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
     use test_context::test_context;
     use test_log::test;
+    use trustify_common::id::Id;
+    use trustify_entity::sbom_package_license;
     use trustify_test_context::TrustifyContext;
     use trustify_test_context::document_bytes;
 
@@ -74,7 +78,7 @@ mod test {
 
         let data = document_bytes("clearly-defined/chrono.yaml").await?;
 
-        ingestor
+        let result = ingestor
             .ingest(
                 &data,
                 Format::ClearlyDefinedCuration,
@@ -84,6 +88,22 @@ mod test {
             .await
             .expect("must ingest");
 
+        let Id::Uuid(sbom_id) = result.id else {
+            panic!("must be a UUID");
+        };
+
+        // each curated revision is a package of the SBOM, declaring its license
+        let licenses = sbom_package_license::Entity::find()
+            .filter(sbom_package_license::Column::SbomId.eq(sbom_id))
+            .all(&ctx.db)
+            .await?;
+        assert_eq!(licenses.len(), 7);
+        assert!(
+            licenses
+                .iter()
+                .any(|license| license.node_id == "crate/cratesio/-/chrono/0.4.19")
+        );
+
         Ok(())
     }
 }
@@ -1,20 +1,32 @@
+pub mod xml;
+
 use crate::{
     graph::{Graph, Outcome, sbom::cyclonedx},
     model::IngestResult,
-    service::{Error, Warnings},
+    service::{Error, Warnings, audit::Upload},
 };
 use sea_orm::TransactionTrait;
 use tracing::instrument;
 use trustify_common::{hashing::Digests, id::Id};
-use trustify_entity::labels::Labels;
+use trustify_entity::{document_audit::DocumentKind, labels::Labels};
 
 pub struct CyclonedxLoader<'g> {
     graph: &'g Graph,
+    upload: Option<&'g Upload>,
 }
 
 impl<'g> CyclonedxLoader<'g> {
     pub fn new(graph: &'g Graph) -> Self {
-        Self { graph }
+        Self {
+            graph,
+            upload: None,
+        }
+    }
+
+    /// Record the upload of the document, as part of ingesting it.
+    pub fn upload(mut self, upload: Option<&'g Upload>) -> Self {
+        self.upload = upload;
+        self
     }
 
     #[instrument(skip(self, buffer), err(level=tracing::Level::INFO))]
@@ -26,8 +38,12 @@ impl<'g> CyclonedxLoader<'g> {
     ) -> Result<IngestResult, Error> {
         let warnings = Warnings::default();
 
-        let cdx: Box<serde_cyclonedx::cyclonedx::v_1_6::CycloneDx> = serde_json::from_slice(buffer)
-            .map_err(|err| Error::UnsupportedFormat(format!("Failed to parse: {err}")))?;
+        let cdx: Box<serde_cyclonedx::cyclonedx::v_1_6::CycloneDx> = if xml::is_xml(buffer) {
+            xml::parse(buffer)?
+        } else {
+            serde_json::from_slice(buffer)
+                .map_err(|err| Error::UnsupportedFormat(format!("Failed to parse: {err}")))?
+        };
 
         let labels = labels.add("type", "cyclonedx");
 
@@ -47,7 +63,7 @@ impl<'g> CyclonedxLoader<'g> {
                 cdx.version.map(|v| v.to_string()) // If serial_number is None, just use version
             });
 
-        let ctx = match self
+        let (ctx, duplicate) = match self
             .graph
             .ingest_sbom(
                 labels,
@@ -58,12 +74,17 @@ impl<'g> CyclonedxLoader<'g> {
             )
             .await?
         {
-            Outcome::Existed(sbom) => sbom,
+            Outcome::Existed(sbom) => (sbom, true),
             Outcome::Added(sbom) => {
                 sbom.ingest_cyclonedx(cdx, &warnings, &tx).await?;
+                if let Some(upload) = self.upload {
+                    upload
+                        .record(DocumentKind::Sbom, sbom.sbom.sbom_id, &tx)
+                        .await?;
+                }
                 tx.commit().await?;
 
-                sbom
+                (sbom, false)
             }
         };
 
@@ -71,6 +92,7 @@ impl<'g> CyclonedxLoader<'g> {
             id: Id::Uuid(ctx.sbom.sbom_id),
             document_id,
             warnings: warnings.into(),
+            duplicate,
         })
     }
 }
@@ -99,4 +121,26 @@ mod test {
 
         Ok(())
     }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn ingest_cyclonedx_xml(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        let db = &ctx.db;
+        let graph = Graph::new(db.clone());
+        let data = document_bytes("cyclonedx/maven.cdx.xml").await?;
+
+        let ingestor = IngestorService::new(graph, ctx.storage.clone(), Default::default());
+
+        let result = ingestor
+            .ingest(&data, Format::CycloneDX, ("source", "test"), None)
+            .await
+            .expect("must ingest");
+
+        assert_eq!(
+            result.document_id.as_deref(),
+            Some("urn:uuid:8d1b8f4a-6f3c-4c8e-9a51-2f0c1c6e4b77/1")
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,342 @@
+//! Parsing CycloneDX documents using the XML representation.
+//!
+//! The XML document gets translated into the JSON representation, following the mapping between
+//! the XML and JSON schemas of the specification. The result is then parsed into the same model
+//! as JSON documents, so that both are ingested the same way.
+
+use crate::service::Error;
+use roxmltree::{Document, Node};
+use serde_cyclonedx::cyclonedx::v_1_6::CycloneDx;
+use serde_json::{Map, Number, Value};
+use std::str::from_utf8;
+
+/// The prefix of the namespace of CycloneDX XML documents, followed by the spec version.
+pub const NAMESPACE: &str = "http://cyclonedx.org/schema/bom/";
+
+/// Elements wrapping a list of items.
+const LISTS: &[&str] = &[
+    "advisories",
+    "affects",
+    "aliases",
+    "ancestors",
+    "annotations",
+    "assemblies",
+    "authors",
+    "commits",
+    "components",
+    "compositions",
+    "cwes",
+    "descendants",
+    "endpoints",
+    "externalReferences",
+    "formulation",
+    "frames",
+    "hashes",
+    "individuals",
+    "lifecycles",
+    "methods",
+    "notes",
+    "occurrences",
+    "organizations",
+    "patches",
+    "properties",
+    "ratings",
+    "references",
+    "resolves",
+    "responses",
+    "services",
+    "subjects",
+    "tags",
+    "variants",
+    "versions",
+    "vulnerabilities",
+];
+
+/// Elements which may occur several times, and are always a list in JSON.
+const REPEATED: &[&str] = &["contact", "omniborId", "swhid", "url"];
+
+/// Parents of elements in [`REPEATED`]. Others, like external references, have a single URL.
+const ORGANIZATIONS: &[&str] = &[
+    "manufacture",
+    "manufacturer",
+    "organization",
+    "provider",
+    "supplier",
+];
+
+/// Values which are numbers in JSON.
+const NUMBERS: &[&str] = &[
+    "column",
+    "confidence",
+    "cwe",
+    "line",
+    "offset",
+    "score",
+    "tagVersion",
+];
+
+/// Check if a document looks like XML, rather than JSON.
+pub fn is_xml(bytes: &[u8]) -> bool {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    bytes.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'<')
+}
+
+/// Parse a CycloneDX XML document.
+pub fn parse(buffer: &[u8]) -> Result<Box<CycloneDx>, Error> {
+    serde_json::from_value(to_value(buffer)?)
+        .map_err(|err| Error::UnsupportedFormat(format!("Failed to parse: {err}")))
+}
+
+/// Parse a CycloneDX XML document into its JSON representation, without parsing it into the model.
+pub fn to_value(buffer: &[u8]) -> Result<Value, Error> {
+    let xml = from_utf8(buffer)?;
+    let document = Document::parse(xml)?;
+
+    to_json(&document)
+}
+
+/// Translate a CycloneDX XML document into its JSON representation.
+pub fn to_json(document: &Document) -> Result<Value, Error> {
+    let root = document.root_element();
+
+    let spec_version = root
+        .tag_name()
+        .namespace()
+        .and_then(|ns| ns.strip_prefix(NAMESPACE))
+        .filter(|_| root.tag_name().name() == "bom")
+        .ok_or_else(|| {
+            Error::UnsupportedFormat("Root element is not a CycloneDX 'bom' element".into())
+        })?;
+
+    let mut bom = Map::new();
+    bom.insert("bomFormat".into(), "CycloneDX".into());
+    bom.insert("specVersion".into(), spec_version.into());
+
+    if let Value::Object(content) = element(root) {
+        bom.extend(content);
+    }
+
+    // the only numeric attribute, as versions of components are strings
+    if let Some(Value::String(version)) = bom.get("version") {
+        if let Ok(version) = version.parse::<i64>() {
+            bom.insert("version".into(), version.into());
+        }
+    }
+
+    Ok(Value::Object(bom))
+}
+
+fn element(node: Node) -> Value {
+    let name = node.tag_name().name();
+
+    match name {
+        "licenses" => return licenses(node),
+        // the top-level dependency graph
+        "dependency"
+            if node
+                .parent_element()
+                .and_then(|parent| parent.parent_element())
+                .is_some_and(|bom| bom.tag_name().name() == "bom") =>
+        {
+            return dependency(node);
+        }
+        // metadata tools before 1.5 are a plain list, later ones are split by type
+        "tools" if children(node).any(|child| child.tag_name().name() == "tool") => {
+            return list(node);
+        }
+        _ if LISTS.contains(&name) || name == "dependencies" => return list(node),
+        _ => {}
+    }
+
+    let mut result = attributes(node);
+
+    if children(node).next().is_none() {
+        let text = node.text().unwrap_or_default().trim();
+
+        // references to other elements, like an assembly of a composition
+        if result.len() == 1 && text.is_empty() {
+            if let Some(Value::String(r#ref)) = result.get("ref") {
+                return r#ref.as_str().into();
+            }
+        }
+
+        if result.is_empty() {
+            return scalar(name, text);
+        }
+
+        if !text.is_empty() {
+            let key = match name {
+                "classification" => "classification",
+                "property" => "value",
+                _ => "content",
+            };
+            result.insert(key.into(), text.into());
+        }
+
+        return Value::Object(result);
+    }
+
+    for child in children(node) {
+        let key = child.tag_name().name();
+        let value = element(child);
+
+        if REPEATED.contains(&key) && ORGANIZATIONS.contains(&name) {
+            match result
+                .entry(key)
+                .or_insert_with(|| Value::Array(Vec::new()))
+            {
+                Value::Array(values) => values.push(value),
+                _ => unreachable!("repeated elements are always a list"),
+            }
+            continue;
+        }
+
+        match result.get_mut(key) {
+            None => {
+                result.insert(key.into(), value);
+            }
+            Some(Value::Array(values)) if !LISTS.contains(&key) => values.push(value),
+            Some(existing) => {
+                *existing = Value::Array(vec![existing.take(), value]);
+            }
+        }
+    }
+
+    Value::Object(result)
+}
+
+/// Child elements of the CycloneDX namespace, skipping extensions of other namespaces.
+fn children<'a, 'input>(node: Node<'a, 'input>) -> impl Iterator<Item = Node<'a, 'input>> {
+    let namespace = node.tag_name().namespace();
+    node.children()
+        .filter(move |child| child.is_element() && child.tag_name().namespace() == namespace)
+}
+
+fn attributes(node: Node) -> Map<String, Value> {
+    node.attributes()
+        .filter(|attr| attr.namespace().is_none())
+        .map(|attr| {
+            let key = match attr.name() {
+                "content-type" => "contentType",
+                name => name,
+            };
+            (key.to_string(), scalar(key, attr.value()))
+        })
+        .collect()
+}
+
+fn scalar(name: &str, value: &str) -> Value {
+    if NUMBERS.contains(&name) {
+        if let Some(number) = value
+            .parse::<i64>()
+            .ok()
+            .map(Number::from)
+            .or_else(|| value.parse::<f64>().ok().and_then(Number::from_f64))
+        {
+            return Value::Number(number);
+        }
+    }
+
+    match (name, value) {
+        ("patch", "true") => true.into(),
+        ("patch", "false") => false.into(),
+        _ => value.into(),
+    }
+}
+
+fn list(node: Node) -> Value {
+    Value::Array(children(node).map(element).collect())
+}
+
+/// Licenses are a list of either a license or an expression, wrapped into an object in JSON.
+fn licenses(node: Node) -> Value {
+    Value::Array(
+        children(node)
+            .map(|child| match child.tag_name().name() {
+                "expression" => {
+                    let mut result = attributes(child);
+                    result.insert(
+                        "expression".into(),
+                        child.text().unwrap_or_default().trim().into(),
+                    );
+                    Value::Object(result)
+                }
+                name => {
+                    let mut result = Map::new();
+                    result.insert(name.into(), element(child));
+                    Value::Object(result)
+                }
+            })
+            .collect(),
+    )
+}
+
+/// A dependency of the dependency graph, with its direct dependencies as nested elements.
+fn dependency(node: Node) -> Value {
+    let mut result = attributes(node);
+
+    for child in children(node) {
+        let key = match child.tag_name().name() {
+            "dependency" => "dependsOn",
+            name => name,
+        };
+
+        if let Some(r#ref) = child.attribute("ref") {
+            match result
+                .entry(key)
+                .or_insert_with(|| Value::Array(Vec::new()))
+            {
+                Value::Array(values) => values.push(r#ref.into()),
+                _ => unreachable!("references are always a list"),
+            }
+        }
+    }
+
+    Value::Object(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+    use trustify_test_context::document_bytes;
+
+    /// Parse both representations of the same SBOM, and compare their models.
+    async fn assert_same(xml: &str, json: &str) -> anyhow::Result<()> {
+        let xml = parse(&document_bytes(xml).await?)?;
+        let json: Box<CycloneDx> = serde_json::from_slice(&document_bytes(json).await?)?;
+
+        assert_eq!(serde_json::to_value(xml)?, serde_json::to_value(json)?);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn simple() -> anyhow::Result<()> {
+        assert_same("cyclonedx/simple.xml", "cyclonedx/simple.json").await
+    }
+
+    #[test(tokio::test)]
+    async fn maven() -> anyhow::Result<()> {
+        assert_same("cyclonedx/maven.cdx.xml", "cyclonedx/maven.cdx.json").await
+    }
+
+    #[test]
+    fn detect() {
+        assert!(is_xml(b"  <?xml version=\"1.0\"?><bom/>"));
+        assert!(is_xml(b"\xEF\xBB\xBF<bom/>"));
+        assert!(!is_xml(br#"{"bomFormat": "CycloneDX"}"#));
+        assert!(!is_xml(b""));
+    }
+
+    #[test]
+    fn not_a_bom() {
+        let document = Document::parse(r#"<sbom xmlns="http://cyclonedx.org/schema/bom/1.5"/>"#)
+            .expect("must parse");
+        assert!(to_json(&document).is_err());
+
+        let document =
+            Document::parse(r#"<bom xmlns="http://example.com/bom/1.5"/>"#).expect("must parse");
+        assert!(to_json(&document).is_err());
+    }
+}
@@ -1,24 +1,36 @@
+pub mod tag_value;
+
 use crate::{
     graph::{
         Graph, Outcome,
         sbom::spdx::{self},
     },
     model::IngestResult,
-    service::{Error, Warnings},
+    service::{Error, Warnings, audit::Upload},
 };
 use sea_orm::TransactionTrait;
 use serde_json::Value;
 use tracing::instrument;
 use trustify_common::{hashing::Digests, id::Id, sbom::spdx::parse_spdx};
-use trustify_entity::labels::Labels;
+use trustify_entity::{document_audit::DocumentKind, labels::Labels};
 
 pub struct SpdxLoader<'g> {
     graph: &'g Graph,
+    upload: Option<&'g Upload>,
 }
 
 impl<'g> SpdxLoader<'g> {
     pub fn new(graph: &'g Graph) -> Self {
-        Self { graph }
+        Self {
+            graph,
+            upload: None,
+        }
+    }
+
+    /// Record the upload of the document, as part of ingesting it.
+    pub fn upload(mut self, upload: Option<&'g Upload>) -> Self {
+        self.upload = upload;
+        self
     }
 
     #[instrument(skip(self, json), err(level=tracing::Level::INFO))]
@@ -46,7 +58,7 @@ impl<'g> SpdxLoader<'g> {
             .spdx_document_namespace
             .clone();
 
-        let sbom = match self
+        let (sbom, duplicate) = match self
             .graph
             .ingest_sbom(
                 labels,
@@ -57,11 +69,16 @@ impl<'g> SpdxLoader<'g> {
             )
             .await?
         {
-            Outcome::Existed(sbom) => sbom,
+            Outcome::Existed(sbom) => (sbom, true),
             Outcome::Added(sbom) => {
                 sbom.ingest_spdx(spdx, &warnings, &tx).await?;
+                if let Some(upload) = self.upload {
+                    upload
+                        .record(DocumentKind::Sbom, sbom.sbom.sbom_id, &tx)
+                        .await?;
+                }
                 tx.commit().await?;
-                sbom
+                (sbom, false)
             }
         };
 
@@ -69,6 +86,7 @@ impl<'g> SpdxLoader<'g> {
             id: Id::Uuid(sbom.sbom.sbom_id),
             document_id: Some(document_id),
             warnings: warnings.into(),
+            duplicate,
         })
     }
 }
@@ -96,4 +114,22 @@ mod test {
 
         Ok(())
     }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn ingest_spdx_tag_value(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        let graph = Graph::new(ctx.db.clone());
+        let data = document_bytes("spdx/simple.spdx").await?;
+
+        let ingestor = IngestorService::new(graph, ctx.storage.clone(), Default::default());
+
+        let result = ingestor
+            .ingest(&data, Format::SPDX, ("source", "test"), None)
+            .await
+            .expect("must ingest");
+
+        assert_eq!(result.document_id.as_deref(), Some("uri:just-an-example"));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,77 @@
+//! Parsing SPDX documents using the tag-value format.
+//!
+//! The document gets parsed into the SPDX model, and is then converted into the JSON
+//! representation. That way, it passes through the same fixups as JSON documents before being
+//! ingested.
+
+use crate::service::Error;
+use serde_json::Value;
+use spdx_rs::parsers::spdx_from_tag_value;
+use std::str::from_utf8;
+
+/// Get the SPDX version of a tag-value document, like `SPDX-2.3`.
+///
+/// The version must be the first tag of a document, only preceded by comments and empty lines.
+/// Returns `None` if the document isn't a tag-value document.
+pub fn spdx_version(bytes: &[u8]) -> Option<&str> {
+    let text = from_utf8(bytes).ok()?;
+
+    text.trim_start_matches('\u{feff}')
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))?
+        .strip_prefix("SPDXVersion:")
+        .map(str::trim)
+}
+
+/// Check if a document uses the tag-value format, rather than JSON.
+pub fn is_tag_value(bytes: &[u8]) -> bool {
+    spdx_version(bytes).is_some()
+}
+
+/// Parse a tag-value document into its JSON representation.
+pub fn to_value(buffer: &[u8]) -> Result<Value, Error> {
+    let text = from_utf8(buffer)?;
+
+    let spdx = spdx_from_tag_value(text)
+        .map_err(|err| Error::UnsupportedFormat(format!("Failed to parse: {err}")))?;
+
+    Ok(serde_json::to_value(spdx)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+    use trustify_test_context::document_bytes;
+
+    #[test(tokio::test)]
+    async fn simple() -> anyhow::Result<()> {
+        let json = to_value(&document_bytes("spdx/simple.spdx").await?)?;
+
+        assert_eq!(json["spdxVersion"], "SPDX-2.2");
+        assert_eq!(json["documentNamespace"], "uri:just-an-example");
+        assert_eq!(json["packages"].as_array().map(Vec::len), Some(8));
+        assert_eq!(
+            json["packages"][0]["externalRefs"][0]["referenceLocator"],
+            "pkg:rpm/redhat/A@0.0.0?arch=src"
+        );
+        assert_eq!(json["relationships"].as_array().map(Vec::len), Some(6));
+
+        Ok(())
+    }
+
+    #[test]
+    fn detect() {
+        assert_eq!(
+            spdx_version(b"# comment\n\nSPDXVersion: SPDX-2.3\nDataLicense: CC0-1.0\n"),
+            Some("SPDX-2.3")
+        );
+        assert_eq!(spdx_version(br#"{"spdxVersion": "SPDX-2.3"}"#), None);
+        assert_eq!(
+            spdx_version(b"DataLicense: CC0-1.0\nSPDXVersion: SPDX-2.3\n"),
+            None
+        );
+        assert!(!is_tag_value(b""));
+    }
+}
@@ -0,0 +1,127 @@
+//! Validation of documents against the schema of their format, before ingesting them.
+//!
+//! The schemas cover the structure the loaders rely on, not every constraint of the
+//! specifications. Documents violating them are rejected, reporting each violation with a JSON
+//! pointer to the offending location.
+
+use super::{Error, Format};
+use jsonschema::Validator;
+use serde_json::Value;
+use std::sync::OnceLock;
+use trustify_common::error::ErrorInformation;
+
+/// The maximum number of violations reported for a document.
+const MAX_VIOLATIONS: usize = 100;
+
+/// A location in a document, violating the schema of its format
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct SchemaViolation {
+    /// A JSON pointer to the offending location, empty for the document itself
+    pub pointer: String,
+    /// A description of the violation
+    pub message: String,
+}
+
+/// The body of responses, rejecting a document violating the schema of its format
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct SchemaErrorInformation {
+    #[serde(flatten)]
+    pub error: ErrorInformation,
+    /// The violations, in the order they were found
+    pub violations: Vec<SchemaViolation>,
+}
+
+static CSAF: OnceLock<Validator> = OnceLock::new();
+static OSV: OnceLock<Validator> = OnceLock::new();
+static CYCLONEDX: OnceLock<Validator> = OnceLock::new();
+
+fn validator(format: Format) -> Option<&'static Validator> {
+    let (cell, schema) = match format {
+        Format::CSAF => (&CSAF, include_str!("csaf.json")),
+        Format::OSV => (&OSV, include_str!("osv.json")),
+        Format::CycloneDX => (&CYCLONEDX, include_str!("cyclonedx.json")),
+        _ => return None,
+    };
+
+    Some(cell.get_or_init(|| {
+        let schema: Value = serde_json::from_str(schema).expect("schema must be valid JSON");
+        jsonschema::validator_for(&schema).expect("schema must be valid")
+    }))
+}
+
+/// Validate a document against the schema of its format.
+///
+/// Formats without a schema, and documents which aren't JSON (like OSV in YAML), are not
+/// validated. Parsing them is left to the loader.
+pub fn validate(format: Format, buffer: &[u8]) -> Result<(), Error> {
+    let Some(validator) = validator(format) else {
+        return Ok(());
+    };
+    let Ok(document) = serde_json::from_slice::<Value>(buffer) else {
+        return Ok(());
+    };
+
+    let violations: Vec<_> = validator
+        .iter_errors(&document)
+        .take(MAX_VIOLATIONS)
+        .map(|err| SchemaViolation {
+            pointer: err.instance_path.to_string(),
+            message: err.to_string(),
+        })
+        .collect();
+
+    match violations.is_empty() {
+        true => Ok(()),
+        false => Err(Error::SchemaViolation { format, violations }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pointers() {
+        let document = json!({
+            "document": {
+                "category": "csaf_vex",
+                "csaf_version": "2.0",
+                "publisher": { "category": "vendor", "name": "Example", "namespace": "https://example.com" },
+                "tracking": {
+                    "current_release_date": "2024-01-01T00:00:00Z",
+                    "id": "EX-1",
+                    "initial_release_date": "2024-01-01T00:00:00Z",
+                    "revision_history": [],
+                    "status": "published",
+                    "version": "1"
+                }
+            },
+            "vulnerabilities": [ { "cve": "CVE-1" } ]
+        });
+
+        let Err(Error::SchemaViolation { violations, .. }) =
+            validate(Format::CSAF, &serde_json::to_vec(&document).unwrap())
+        else {
+            panic!("must fail validation");
+        };
+
+        let mut pointers: Vec<_> = violations.iter().map(|v| v.pointer.as_str()).collect();
+        pointers.sort();
+        assert_eq!(
+            pointers,
+            [
+                "/document",
+                "/document/tracking/revision_history",
+                "/document/tracking/status",
+                "/vulnerabilities/0/cve",
+            ]
+        );
+    }
+
+    #[test]
+    fn not_json() {
+        assert!(validate(Format::OSV, b"id: GHSA-1\nmodified: 2024-01-01T00:00:00Z\n").is_ok());
+        assert!(validate(Format::SPDX, b"{}").is_ok());
+    }
+}
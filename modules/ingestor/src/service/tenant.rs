@@ -0,0 +1,57 @@
+//! The tenant of the user performing a request to the ingestor.
+//!
+//! The fundamental module has the full tenant isolation. The ingestor only needs to stamp
+//! uploaded documents, and keep users from seeing the jobs of other tenants.
+
+use actix_web::{FromRequest, HttpRequest, dev::Payload};
+use sea_orm::{ColumnTrait, Condition};
+use std::future::{Ready, ready};
+use trustify_auth::tenant::TENANT_LABEL;
+use trustify_entity::labels::Labels;
+
+/// The tenant whose documents and jobs a request may access.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Tenant {
+    /// Tenant isolation is disabled, all jobs are accessible.
+    Any,
+    /// Only jobs of this tenant are accessible.
+    Of(String),
+}
+
+impl Tenant {
+    /// Stamp the labels of a document with the tenant, replacing any other tenant.
+    pub fn stamp(&self, labels: Labels) -> Labels {
+        match self {
+            Self::Any => labels,
+            Self::Of(tenant) => labels.add(TENANT_LABEL, tenant),
+        }
+    }
+
+    /// A condition on a column storing the tenant of a record, ruling out the records of other
+    /// tenants.
+    pub fn column_condition(&self, tenant: impl ColumnTrait) -> Condition {
+        match self {
+            Self::Any => Condition::all(),
+            Self::Of(name) => Condition::all().add(tenant.eq(name)),
+        }
+    }
+}
+
+/// Extract the tenant of the current user.
+///
+/// With tenant isolation enabled, requests of users without a tenant are rejected.
+impl FromRequest for Tenant {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(
+            trustify_auth::tenant::tenant_of(req)
+                .map(|tenant| match tenant {
+                    Some(tenant) => Self::Of(tenant),
+                    None => Self::Any,
+                })
+                .map_err(Into::into),
+        )
+    }
+}
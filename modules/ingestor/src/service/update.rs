@@ -0,0 +1,124 @@
+//! Updating SBOMs in place.
+//!
+//! An SBOM uploaded as an update supersedes the most recently ingested SBOM with the same
+//! identifier, which is the document namespace of SPDX and the serial number of CycloneDX
+//! documents. The update takes over the labels and the product links of its predecessor, which
+//! is kept, so that both can be compared.
+
+use super::{Error, Format, IngestorService, audit::Upload};
+use crate::model::IngestResult;
+use sea_orm::{
+    ActiveValue::Set,
+    ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait,
+    TransactionTrait,
+    sea_query::{Expr, JoinType, Query},
+};
+use std::fmt::Debug;
+use tracing::instrument;
+use trustify_common::id::Id;
+use trustify_entity::{
+    document_identity, download_audit::DocumentKind, labels::Labels, product_version, sbom,
+    sbom_predecessor, source_document,
+};
+use uuid::Uuid;
+
+impl IngestorService {
+    /// Ingest an SBOM as an update of a previously ingested one.
+    ///
+    /// Without a predecessor, or for a document which was already ingested, this is the same as
+    /// ingesting the SBOM. The upload, if present, gets recorded like for any other upload.
+    #[instrument(skip(self, bytes), err)]
+    pub async fn update_sbom(
+        &self,
+        bytes: &[u8],
+        format: Format,
+        labels: impl Into<Labels> + Debug,
+        external_id: Option<String>,
+        upload: Option<&Upload>,
+    ) -> Result<IngestResult, Error> {
+        let result = self
+            .ingest_with_external_id(bytes, format, labels, None, external_id, upload)
+            .await?;
+
+        let (Id::Uuid(id), false) = (&result.id, result.duplicate) else {
+            return Ok(result);
+        };
+
+        let tx = self.graph.db.begin().await?;
+        if let Some(predecessor) = find_predecessor(*id, &tx).await? {
+            log::info!("SBOM {id} supersedes {}", predecessor.sbom_id);
+            supersede(predecessor, *id, &tx).await?;
+        }
+        tx.commit().await?;
+
+        Ok(result)
+    }
+}
+
+/// Find the most recently ingested SBOM with the same identifier, which wasn't deleted.
+async fn find_predecessor<C: ConnectionTrait>(
+    id: Uuid,
+    connection: &C,
+) -> Result<Option<sbom::Model>, Error> {
+    let Some(identity) = document_identity::Entity::find_by_id(id)
+        .one(connection)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    Ok(sbom::Entity::find()
+        .join(JoinType::InnerJoin, sbom::Relation::SourceDocument.def())
+        .filter(sbom::Column::SbomId.ne(id))
+        .filter(sbom::Column::DeletedAt.is_null())
+        .filter(
+            sbom::Column::SbomId.in_subquery(
+                Query::select()
+                    .column(document_identity::Column::DocumentId)
+                    .from(document_identity::Entity)
+                    .and_where(document_identity::Column::DocumentKind.eq(DocumentKind::Sbom))
+                    .and_where(document_identity::Column::Format.eq(&identity.format))
+                    .and_where(document_identity::Column::Identifier.eq(&identity.identifier))
+                    .and_where(document_identity::Column::Tenant.eq(&identity.tenant))
+                    .to_owned(),
+            ),
+        )
+        .order_by_desc(source_document::Column::Ingested)
+        .one(connection)
+        .await?)
+}
+
+/// Let an SBOM supersede its predecessor, taking over its labels and product links.
+///
+/// Labels of the SBOM take precedence over the ones of the predecessor.
+async fn supersede<C: ConnectionTrait>(
+    predecessor: sbom::Model,
+    id: Uuid,
+    connection: &C,
+) -> Result<(), Error> {
+    if let Some(current) = sbom::Entity::find_by_id(id).one(connection).await? {
+        sbom::Entity::update_many()
+            .filter(sbom::Column::SbomId.eq(id))
+            .col_expr(
+                sbom::Column::Labels,
+                Expr::value(predecessor.labels.extend(current.labels.0)),
+            )
+            .exec(connection)
+            .await?;
+    }
+
+    product_version::Entity::update_many()
+        .filter(product_version::Column::SbomId.eq(predecessor.sbom_id))
+        .col_expr(product_version::Column::SbomId, Expr::value(id))
+        .exec(connection)
+        .await?;
+
+    sbom_predecessor::Entity::insert(sbom_predecessor::ActiveModel {
+        sbom_id: Set(id),
+        predecessor_id: Set(predecessor.sbom_id),
+    })
+    .exec(connection)
+    .await?;
+
+    Ok(())
+}
@@ -108,6 +108,7 @@ impl<'d> CweCatalogLoader<'d> {
 
                             batch.push(weakness::ActiveModel {
                                 id: Set(id),
+                                name: Set(weakness.attribute("Name").map(ToString::to_string)),
                                 description: Set(description),
                                 extended_description: Set(extended_description),
                                 child_of: Set(normalize(child_of)),
@@ -143,6 +144,7 @@ impl<'d> CweCatalogLoader<'d> {
             id: Id::Sha512(digests.sha512.encode_hex()),
             document_id: Some("CWE".to_string()),
             warnings: vec![],
+            duplicate: false,
         })
     }
 }
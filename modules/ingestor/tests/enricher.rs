@@ -0,0 +1,163 @@
+use actix_web::{App, HttpResponse, HttpServer, web};
+use async_trait::async_trait;
+use sea_orm::EntityTrait;
+use serde_json::json;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+use test_context::test_context;
+use test_log::test;
+use trustify_common::id::Id;
+use trustify_entity::{advisory, labels::Labels, sbom};
+use trustify_module_ingestor::{
+    graph::Graph,
+    service::{
+        Format, IngestorService,
+        enricher::{Enricher, Enrichers, EnrichmentContext, kev::KevEnricher},
+    },
+};
+use trustify_test_context::{TrustifyContext, document_bytes};
+
+/// Adds a label, containing the value of a label of the previous stages.
+struct Stage(&'static str);
+
+#[async_trait]
+impl Enricher for Stage {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    async fn enrich(&self, context: &EnrichmentContext<'_>) -> anyhow::Result<Labels> {
+        let previous = context.labels.get("stages").cloned().unwrap_or_default();
+        Ok(Labels::new().add("stages", format!("{previous}{}", self.0)))
+    }
+}
+
+struct Failing;
+
+#[async_trait]
+impl Enricher for Failing {
+    fn name(&self) -> &str {
+        "failing"
+    }
+
+    async fn enrich(&self, _: &EnrichmentContext<'_>) -> anyhow::Result<Labels> {
+        anyhow::bail!("service unavailable")
+    }
+}
+
+fn ingestor(ctx: &TrustifyContext, enrichers: Enrichers) -> IngestorService {
+    IngestorService::new(Graph::new(ctx.db.clone()), ctx.storage.clone(), None)
+        .with_enrichers(enrichers)
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn ordered_stages(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let enrichers = Enrichers::new()
+        .with(Stage("a"))
+        .with(Failing)
+        .with(Stage("b"));
+
+    let bytes = document_bytes("zookeeper-3.9.2-cyclonedx.json").await?;
+    let result = ingestor(ctx, enrichers)
+        .ingest(&bytes, Format::SBOM, (), None)
+        .await?;
+
+    // the failing stage is reported, but doesn't stop the others
+    assert_eq!(
+        result.warnings,
+        ["Enricher 'failing' failed: service unavailable"]
+    );
+
+    let Id::Uuid(id) = result.id else {
+        anyhow::bail!("unexpected ID: {}", result.id);
+    };
+    let sbom = sbom::Entity::find_by_id(id)
+        .one(&ctx.db)
+        .await?
+        .expect("must be found");
+    assert_eq!(sbom.labels.get("stages").map(String::as_str), Some("ab"));
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn known_exploited(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let server = HttpServer::new(|| {
+        App::new().route(
+            "/kev.json",
+            web::get().to(|| async {
+                HttpResponse::Ok().json(json!({
+                    "vulnerabilities": [ { "cveID": "CVE-2023-0044" } ]
+                }))
+            }),
+        )
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))?;
+    let url = format!("http://{}/kev.json", server.addrs()[0]).parse()?;
+    actix_web::rt::spawn(server.run());
+
+    let ingestor = ingestor(ctx, Enrichers::new().with(KevEnricher::new(url)?));
+
+    let bytes = document_bytes("csaf/cve-2023-0044.json").await?;
+    let result = ingestor.ingest(&bytes, Format::CSAF, (), None).await?;
+    assert!(!result.warnings.iter().any(|w| w.starts_with("Enricher")));
+
+    let Id::Uuid(id) = result.id else {
+        anyhow::bail!("unexpected ID: {}", result.id);
+    };
+    let advisory = advisory::Entity::find_by_id(id)
+        .one(&ctx.db)
+        .await?
+        .expect("must be found");
+    assert_eq!(advisory.labels.get("kev").map(String::as_str), Some("true"));
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn known_exploited_unavailable(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let server = HttpServer::new({
+        let requests = requests.clone();
+        move || {
+            let requests = requests.clone();
+            App::new().route(
+                "/kev.json",
+                web::get().to(move || {
+                    requests.fetch_add(1, Ordering::SeqCst);
+                    async { HttpResponse::ServiceUnavailable().finish() }
+                }),
+            )
+        }
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))?;
+    let url = format!("http://{}/kev.json", server.addrs()[0]).parse()?;
+    actix_web::rt::spawn(server.run());
+
+    let ingestor = ingestor(ctx, Enrichers::new().with(KevEnricher::new(url)?));
+
+    for file in ["csaf/cve-2023-0044.json", "csaf/cve-2023-33201.json"] {
+        let bytes = document_bytes(file).await?;
+        let result = ingestor.ingest(&bytes, Format::CSAF, (), None).await?;
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.starts_with("Enricher 'kev' failed")),
+            "{file}: {:?}",
+            result.warnings
+        );
+    }
+
+    // the failure is kept, instead of trying again for each document
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+    Ok(())
+}
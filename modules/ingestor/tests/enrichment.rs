@@ -0,0 +1,130 @@
+use actix_web::{App, HttpResponse, HttpServer, web};
+use sea_orm::{EntityTrait, ModelTrait};
+use serde_json::{Value, json};
+use std::time::Duration;
+use test_context::test_context;
+use test_log::test;
+use trustify_common::id::Id;
+use trustify_entity::{ingestion_warning, sbom};
+use trustify_module_ingestor::{
+    graph::Graph,
+    service::{
+        Format, IngestorService,
+        enricher::{
+            Enrichers, FailurePolicy,
+            webhook::{WebhookConfig, WebhookEnricher},
+        },
+    },
+};
+use trustify_test_context::{TrustifyContext, document_bytes};
+
+/// Start an enrichment service, returning the URL of it.
+fn enrichment_service() -> anyhow::Result<reqwest::Url> {
+    let server = HttpServer::new(|| {
+        App::new().route(
+            "/",
+            web::post().to(|web::Json(request): web::Json<Value>| async move {
+                // echo a label of the request, so we can check it
+                HttpResponse::Ok().json(json!({
+                    "labels": {
+                        "team": "platform",
+                        "source": "",
+                        "enrichedType": request["labels"]["type"],
+                    }
+                }))
+            }),
+        )
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))?;
+
+    let addr = server.addrs()[0];
+    actix_web::rt::spawn(server.run());
+
+    Ok(format!("http://{addr}/").parse()?)
+}
+
+fn ingestor(
+    ctx: &TrustifyContext,
+    url: reqwest::Url,
+    policy: FailurePolicy,
+) -> anyhow::Result<IngestorService> {
+    Ok(
+        IngestorService::new(Graph::new(ctx.db.clone()), ctx.storage.clone(), None).with_enrichers(
+            Enrichers::new().with(WebhookEnricher::new(WebhookConfig {
+                url,
+                timeout: Duration::from_secs(5),
+                policy,
+            })?),
+        ),
+    )
+}
+
+async fn fetch_sbom(ctx: &TrustifyContext, id: &Id) -> anyhow::Result<sbom::Model> {
+    let Id::Uuid(id) = id else {
+        anyhow::bail!("unexpected ID: {id}");
+    };
+    sbom::Entity::find_by_id(*id)
+        .one(&ctx.db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("SBOM not found"))
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn enrich_labels(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let ingestor = ingestor(ctx, enrichment_service()?, FailurePolicy::Fail)?;
+
+    let bytes = document_bytes("zookeeper-3.9.2-cyclonedx.json").await?;
+    let result = ingestor
+        .ingest(&bytes, Format::SBOM, [("source", "test")], None)
+        .await?;
+    assert!(result.warnings.is_empty());
+
+    let sbom = fetch_sbom(ctx, &result.id).await?;
+    assert_eq!(
+        sbom.labels.get("team").map(String::as_str),
+        Some("platform")
+    );
+    assert_eq!(
+        sbom.labels.get("enrichedType").map(String::as_str),
+        Some("cyclonedx")
+    );
+    // removed by the enrichment
+    assert_eq!(sbom.labels.get("source"), None);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn failure_policy(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    // nothing is listening on this port
+    let url: reqwest::Url = {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0))?;
+        format!("http://{}/", listener.local_addr()?).parse()?
+    };
+    let bytes = document_bytes("zookeeper-3.9.2-cyclonedx.json").await?;
+
+    let result = ingestor(ctx, url.clone(), FailurePolicy::Warn)?
+        .ingest(&bytes, Format::SBOM, (), None)
+        .await?;
+    assert_eq!(result.warnings.len(), 1);
+    assert!(result.warnings[0].starts_with("Enricher 'webhook' failed"));
+
+    // the warning is kept with the SBOM
+    let sbom = fetch_sbom(ctx, &result.id).await?;
+    let warnings = sbom
+        .find_related(ingestion_warning::Entity)
+        .all(&ctx.db)
+        .await?;
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].message, result.warnings[0]);
+
+    let result = ingestor(ctx, url, FailurePolicy::Fail)?
+        .ingest(&bytes, Format::SBOM, (), None)
+        .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
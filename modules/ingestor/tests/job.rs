@@ -0,0 +1,198 @@
+#[path = "common.rs"]
+mod common;
+
+use actix_http::StatusCode;
+use actix_web::test::TestRequest;
+use common::caller_with;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use test_context::test_context;
+use test_log::test;
+use time::{Duration, OffsetDateTime};
+use trustify_common::id::Id;
+use trustify_entity::{document_audit, ingestion_job, sbom};
+use trustify_module_ingestor::{
+    model::{IngestionJob, JobState},
+    service::{
+        Format,
+        audit::{Actor, Upload},
+        job::{JobRunner, MAX_ATTEMPTS},
+        tenant::Tenant,
+    },
+};
+use trustify_test_context::{TrustifyContext, call::CallService, document_bytes};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn process_job(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let bytes = document_bytes("zookeeper-3.9.2-cyclonedx.json").await?;
+    let id = ctx
+        .ingestor
+        .submit(
+            &bytes,
+            Format::SBOM,
+            [("source", "test")],
+            Some("build-42".to_string()),
+            Some(&Upload::new(Actor {
+                user: Some("user-a".into()),
+                ..Default::default()
+            })),
+        )
+        .await?;
+
+    let job = ctx
+        .ingestor
+        .fetch_job(id, &Tenant::Any)
+        .await?
+        .expect("must be found");
+    assert_eq!(job.state, JobState::Pending);
+    assert!(job.started.is_none());
+
+    let runner = JobRunner::new(ctx.ingestor.clone(), 1);
+    assert!(runner.next().await?);
+    // nothing left to do
+    assert!(!runner.next().await?);
+
+    let job = ctx
+        .ingestor
+        .fetch_job(id, &Tenant::Any)
+        .await?
+        .expect("must be found");
+    assert_eq!(job.state, JobState::Succeeded);
+    assert_eq!(job.attempts, 1);
+    assert!(job.started.is_some());
+    assert!(job.finished.is_some());
+    assert!(job.error.is_none());
+    let result = job.result.expect("must have a result");
+    let Id::Uuid(sbom_id) = result.id else {
+        panic!("unexpected ID: {}", result.id);
+    };
+
+    let sbom = sbom::Entity::find_by_id(sbom_id)
+        .one(&ctx.db)
+        .await?
+        .expect("must be found");
+    assert_eq!(sbom.external_id.as_deref(), Some("build-42"));
+
+    // the upload got recorded along with the document
+
+    let audit = document_audit::Entity::find()
+        .filter(document_audit::Column::DocumentId.eq(sbom_id))
+        .all(&ctx.db)
+        .await?;
+    assert_eq!(audit.len(), 1);
+    assert_eq!(audit[0].action, document_audit::Action::Uploaded);
+    assert_eq!(audit[0].document_kind, document_audit::DocumentKind::Sbom);
+    assert_eq!(audit[0].user_id.as_deref(), Some("user-a"));
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn process_failing_job(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let id = ctx
+        .ingestor
+        .submit(b"this is not an SBOM", Format::SBOM, (), None, None)
+        .await?;
+
+    let runner = JobRunner::new(ctx.ingestor.clone(), 1);
+    assert!(runner.next().await?);
+
+    let job = ctx
+        .ingestor
+        .fetch_job(id, &Tenant::Any)
+        .await?
+        .expect("must be found");
+    assert_eq!(job.state, JobState::Failed);
+    assert!(job.result.is_none());
+    assert!(job.error.is_some());
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn process_abandoned_job(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let bytes = document_bytes("zookeeper-3.9.2-cyclonedx.json").await?;
+    let runner = JobRunner::new(ctx.ingestor.clone(), 1);
+
+    // make a job look like its worker got terminated
+    let abandon = async |id, attempts| {
+        ingestion_job::ActiveModel {
+            id: Set(id),
+            state: Set(ingestion_job::State::Running),
+            heartbeat: Set(Some(OffsetDateTime::now_utc() - Duration::hours(1))),
+            attempts: Set(attempts),
+            ..Default::default()
+        }
+        .update(&ctx.db)
+        .await
+    };
+
+    // picked up again, as long as there are attempts left
+
+    let id = ctx
+        .ingestor
+        .submit(&bytes, Format::SBOM, (), None, None)
+        .await?;
+    abandon(id, MAX_ATTEMPTS - 1).await?;
+
+    assert!(runner.next().await?);
+
+    let job = ctx
+        .ingestor
+        .fetch_job(id, &Tenant::Any)
+        .await?
+        .expect("must be found");
+    assert_eq!(job.state, JobState::Succeeded);
+    assert_eq!(job.attempts, MAX_ATTEMPTS as u32);
+
+    // failed, once there are none
+
+    let id = ctx
+        .ingestor
+        .submit(&bytes, Format::SBOM, (), None, None)
+        .await?;
+    abandon(id, MAX_ATTEMPTS).await?;
+
+    assert!(!runner.next().await?);
+
+    let job = ctx
+        .ingestor
+        .fetch_job(id, &Tenant::Any)
+        .await?
+        .expect("must be found");
+    assert_eq!(job.state, JobState::Failed);
+    assert_eq!(job.attempts, MAX_ATTEMPTS as u32);
+    assert!(job.finished.is_some());
+    assert!(job.error.is_some());
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn get_job(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_with(ctx, Default::default()).await?;
+
+    let bytes = document_bytes("zookeeper-3.9.2-cyclonedx.json").await?;
+    let id = ctx
+        .ingestor
+        .submit(&bytes, Format::SBOM, (), None, None)
+        .await?;
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/job/{id}"))
+        .to_request();
+    let job: IngestionJob = app.call_and_read_body_json(request).await;
+    assert_eq!(job.id, id);
+    assert_eq!(job.state, JobState::Pending);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/job/{}", uuid::Uuid::now_v7()))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
@@ -0,0 +1,225 @@
+#[path = "common.rs"]
+mod common;
+
+use actix_http::StatusCode;
+use actix_web::test::{TestRequest, read_body_json};
+use common::caller_with;
+use hex::ToHex;
+use serde_json::{Value, json};
+use test_context::test_context;
+use test_log::test;
+use trustify_common::hashing::Digests;
+use trustify_module_ingestor::endpoints::Config;
+use trustify_test_context::{TrustifyContext, call::CallService, document_bytes};
+
+fn tar(files: &[(&str, &[u8])]) -> anyhow::Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(vec![]);
+    for (path, data) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as _);
+        header.set_mode(0o644);
+        builder.append_data(&mut header, path, *data)?;
+    }
+    Ok(builder.into_inner()?)
+}
+
+fn digest(data: &[u8]) -> String {
+    format!(
+        "sha256:{}",
+        Digests::digest(data).sha256.encode_hex::<String>()
+    )
+}
+
+fn blob(data: &[u8]) -> String {
+    format!("blobs/sha256/{}", &digest(data)["sha256:".len()..])
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn upload_docker_archive(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_with(ctx, Config::default()).await?;
+
+    let spdx = document_bytes("spdx/simple.json").await?;
+    let cyclonedx = document_bytes("cyclonedx/simple.json").await?;
+
+    let config = br#"{"architecture":"amd64","os":"linux"}"#;
+    let base = tar(&[
+        ("usr/share/sbom/simple.spdx.json", &spdx[..]),
+        ("usr/share/sbom/simple.cdx.json", &cyclonedx[..]),
+        ("etc/hostname", &b"example"[..]),
+    ])?;
+    // the second layer removes the CycloneDX SBOM again
+    let top = tar(&[("usr/share/sbom/.wh.simple.cdx.json", &b""[..])])?;
+    let manifest = serde_json::to_vec(&json!([{
+        "Config": "config.json",
+        "RepoTags": ["example:latest"],
+        "Layers": ["base/layer.tar", "top/layer.tar"],
+    }]))?;
+
+    let image = tar(&[
+        ("manifest.json", &manifest[..]),
+        ("config.json", &config[..]),
+        ("base/layer.tar", &base[..]),
+        ("top/layer.tar", &top[..]),
+    ])?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/oci-image?labels.source=test")
+        .set_payload(image)
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let result: Value = read_body_json(response).await;
+    log::debug!("{result:#?}");
+
+    assert_eq!(result["warnings"], json!([]));
+    let sboms = result["images"][digest(config)]
+        .as_object()
+        .expect("must contain the image");
+    assert_eq!(
+        sboms.keys().collect::<Vec<_>>(),
+        vec!["usr/share/sbom/simple.spdx.json"]
+    );
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn upload_oci_layout_with_referrer(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_with(ctx, Config::default()).await?;
+
+    let cyclonedx = document_bytes("cyclonedx/simple.json").await?;
+
+    let config = br#"{"architecture":"amd64","os":"linux"}"#;
+    let layer = tar(&[("etc/hostname", &b"example"[..])])?;
+    let manifest = serde_json::to_vec(&json!({
+        "schemaVersion": 2,
+        "mediaType": "application/vnd.oci.image.manifest.v1+json",
+        "config": {
+            "mediaType": "application/vnd.oci.image.config.v1+json",
+            "digest": digest(config),
+            "size": config.len(),
+        },
+        "layers": [{
+            "mediaType": "application/vnd.oci.image.layer.v1.tar",
+            "digest": digest(&layer),
+            "size": layer.len(),
+        }],
+    }))?;
+
+    // an SBOM attached to the image, as reported by the referrers API
+    let empty = b"{}";
+    let artifact = serde_json::to_vec(&json!({
+        "schemaVersion": 2,
+        "mediaType": "application/vnd.oci.image.manifest.v1+json",
+        "artifactType": "application/vnd.cyclonedx+json",
+        "config": {
+            "mediaType": "application/vnd.oci.empty.v1+json",
+            "digest": digest(empty),
+            "size": empty.len(),
+        },
+        "layers": [{
+            "mediaType": "application/vnd.cyclonedx+json",
+            "digest": digest(&cyclonedx),
+            "size": cyclonedx.len(),
+            "annotations": {
+                "org.opencontainers.image.title": "sbom.cdx.json",
+            },
+        }],
+        "subject": {
+            "mediaType": "application/vnd.oci.image.manifest.v1+json",
+            "digest": digest(&manifest),
+            "size": manifest.len(),
+        },
+    }))?;
+
+    let index = serde_json::to_vec(&json!({
+        "schemaVersion": 2,
+        "mediaType": "application/vnd.oci.image.index.v1+json",
+        "manifests": [
+            {
+                "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                "digest": digest(&manifest),
+                "size": manifest.len(),
+            },
+            {
+                "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                "artifactType": "application/vnd.cyclonedx+json",
+                "digest": digest(&artifact),
+                "size": artifact.len(),
+            },
+        ],
+    }))?;
+
+    let image = tar(&[
+        ("oci-layout", &br#"{"imageLayoutVersion":"1.0.0"}"#[..]),
+        ("index.json", &index[..]),
+        (blob(config).as_str(), &config[..]),
+        (blob(&layer).as_str(), &layer[..]),
+        (blob(&manifest).as_str(), &manifest[..]),
+        (blob(empty).as_str(), &empty[..]),
+        (blob(&cyclonedx).as_str(), &cyclonedx[..]),
+        (blob(&artifact).as_str(), &artifact[..]),
+    ])?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/oci-image")
+        .set_payload(image)
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let result: Value = read_body_json(response).await;
+    log::debug!("{result:#?}");
+
+    assert_eq!(result["warnings"], json!([]));
+    assert!(result["images"][digest(&manifest)]["sbom.cdx.json"]["id"].is_string());
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn upload_self_referencing_index(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_with(ctx, Config::default()).await?;
+
+    // an index listing itself can only claim a digest which doesn't match its content
+    let claimed = digest(b"nested index");
+    let nested = serde_json::to_vec(&json!({
+        "schemaVersion": 2,
+        "mediaType": "application/vnd.oci.image.index.v1+json",
+        "manifests": [
+            {
+                "mediaType": "application/vnd.oci.image.index.v1+json",
+                "digest": claimed,
+                "size": 0,
+            },
+        ],
+    }))?;
+
+    let image = tar(&[
+        ("oci-layout", &br#"{"imageLayoutVersion":"1.0.0"}"#[..]),
+        ("index.json", &nested[..]),
+        (
+            format!("blobs/sha256/{}", &claimed["sha256:".len()..]).as_str(),
+            &nested[..],
+        ),
+    ])?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/oci-image")
+        .set_payload(image)
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let result: Value = read_body_json(response).await;
+    assert_eq!(result["error"], "InvalidContent");
+
+    Ok(())
+}
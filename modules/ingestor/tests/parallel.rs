@@ -142,6 +142,46 @@ async fn csaf_parallel(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+/// Ingest x OSV advisories in parallel, all of them affecting the same PURLs
+#[test_context(TrustifyContext)]
+#[instrument]
+#[test(tokio::test(flavor = "multi_thread", worker_threads = 4))]
+async fn osv_parallel(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    const NUM: usize = 25;
+
+    let data = document_bytes("osv/GHSA-2ccf-ffrj-m4qw.json").await?;
+    let osv: Value = serde_json::from_slice(&data)?;
+
+    // turn into different advisories, and begin ingesting
+
+    let mut tasks = vec![];
+    for _ in 0..NUM {
+        let mut next = osv.clone();
+        next["id"] = Value::String(format!("GHSA-{}", Uuid::new_v4()));
+        let next = serde_json::to_vec(&next)?;
+
+        let service = ctx.ingestor.clone();
+
+        tasks.push(async move {
+            service.ingest(&next, Format::OSV, (), None).await?;
+
+            Ok::<_, anyhow::Error>(())
+        });
+    }
+
+    // progress ingestion tasks
+
+    let result = futures::future::join_all(tasks).await;
+
+    // now test
+
+    assert_all_ok(NUM, result);
+
+    // done
+
+    Ok(())
+}
+
 /// Ingest x * y PURLs in parallel
 #[test_context(TrustifyContext)]
 #[instrument]
@@ -0,0 +1,109 @@
+use actix_http::StatusCode;
+use actix_web::{test::TestRequest, web};
+use sea_orm::EntityTrait;
+use std::io::{Cursor, Write};
+use test_context::test_context;
+use test_log::test;
+use trustify_auth::{
+    authenticator::user::UserDetails,
+    tenant::{TENANT_LABEL, TenantIsolation},
+};
+use trustify_entity::sbom;
+use trustify_module_analysis::{config::AnalysisConfig, service::AnalysisService};
+use trustify_module_ingestor::{
+    endpoints::{Config, configure},
+    service::Format,
+};
+use trustify_test_context::{
+    TrustifyContext,
+    auth::TestAuthentication,
+    call::{self, CallService},
+    document_bytes,
+};
+use zip::write::FileOptions;
+
+/// A caller isolating tenants, as the fundamental module does for the whole API.
+async fn caller_isolating(ctx: &TrustifyContext) -> anyhow::Result<impl CallService + '_> {
+    let analysis = AnalysisService::new(AnalysisConfig::default());
+    call::caller(|svc| {
+        svc.app_data(web::Data::new(TenantIsolation(true)));
+        configure(
+            svc,
+            Config::default(),
+            ctx.db.clone(),
+            ctx.storage.clone(),
+            Some(analysis),
+        )
+    })
+    .await
+}
+
+fn tenant_user(tenant: &str) -> UserDetails {
+    UserDetails {
+        id: format!("user-{tenant}"),
+        permissions: vec![],
+        tenant: Some(tenant.into()),
+    }
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn upload_dataset_stamps_own_tenant(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_isolating(ctx).await?;
+
+    let mut data = vec![];
+    let mut dataset = zip::write::ZipWriter::new(Cursor::new(&mut data));
+    dataset.add_directory("spdx", FileOptions::<()>::default())?;
+    dataset.start_file("spdx/simple.json", FileOptions::<()>::default())?;
+    dataset.write_all(&document_bytes("spdx/simple.json").await?)?;
+    dataset.finish()?;
+
+    // the label claiming another tenant gets replaced
+
+    let request = TestRequest::post()
+        .uri("/api/v2/dataset?labels.tenant=b")
+        .set_payload(data)
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let sboms = sbom::Entity::find().all(&ctx.db).await?;
+    assert_eq!(sboms.len(), 1);
+    assert_eq!(
+        sboms[0].labels.get(TENANT_LABEL).map(String::as_str),
+        Some("a")
+    );
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn get_job_of_other_tenant(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_isolating(ctx).await?;
+
+    let bytes = document_bytes("zookeeper-3.9.2-cyclonedx.json").await?;
+    let id = ctx
+        .ingestor
+        .submit(&bytes, Format::SBOM, [(TENANT_LABEL, "a")], None, None)
+        .await?;
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/job/{id}"))
+        .to_request()
+        .test_auth_details(tenant_user("a"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // the jobs of other tenants are not found
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/job/{id}"))
+        .to_request()
+        .test_auth_details(tenant_user("b"));
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
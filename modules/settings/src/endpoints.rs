@@ -0,0 +1,118 @@
+use crate::{
+    model::{Settings, SettingsChange},
+    service::{Error, SettingsService},
+};
+use actix_web::{
+    HttpResponse, Responder, get,
+    http::header::{self, ETag, EntityTag, IfMatch},
+    put, web,
+};
+use trustify_auth::{
+    ReadSettings, UpdateSettings, authenticator::user::UserInformation, authorizer::Require,
+};
+use trustify_common::{
+    db::Database,
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults, Revisioned},
+};
+
+/// mount the "settings" module
+pub fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    svc.app_data(web::Data::new(SettingsService::new(db)))
+        .service(get)
+        .service(set)
+        .service(history);
+}
+
+#[utoipa::path(
+    tag = "settings",
+    operation_id = "getSettings",
+    responses(
+        (
+            status = 200,
+            description = "The settings, overriding the configuration of the instance",
+            body = Settings,
+            headers(
+                ("etag" = String, description = "Revision ID, absent if the settings never got changed")
+            )
+        ),
+    )
+)]
+#[get("/v2/settings")]
+/// Get the runtime settings
+async fn get(
+    service: web::Data<SettingsService>,
+    _: Require<ReadSettings>,
+) -> Result<impl Responder, Error> {
+    Ok(match service.get().await? {
+        Some(Revisioned { value, revision }) => HttpResponse::Ok()
+            .append_header((header::ETAG, ETag(EntityTag::new_strong(revision))))
+            .json(value),
+        None => HttpResponse::Ok().json(Settings::default()),
+    })
+}
+
+#[utoipa::path(
+    tag = "settings",
+    operation_id = "setSettings",
+    request_body = Settings,
+    params(
+        ("if-match" = Option<String>, Header, description = "The revision to update"),
+    ),
+    responses(
+        (
+            status = 204,
+            description = "Stored the settings",
+            headers(
+                ("etag" = String, description = "Revision ID")
+            )
+        ),
+        (status = 400, description = "The settings are invalid", body = ErrorInformation),
+        (status = 412, description = "The provided If-Match revision did not match the actual revision", body = ErrorInformation)
+    )
+)]
+#[put("/v2/settings")]
+/// Replace the runtime settings
+async fn set(
+    service: web::Data<SettingsService>,
+    user: UserInformation,
+    web::Header(if_match): web::Header<IfMatch>,
+    web::Json(settings): web::Json<Settings>,
+    _: Require<UpdateSettings>,
+) -> Result<impl Responder, Error> {
+    let revision = match &if_match {
+        IfMatch::Any => None,
+        IfMatch::Items(items) => items.first().map(|etag| etag.tag()),
+    };
+
+    let Revisioned {
+        value: (),
+        revision,
+    } = service
+        .set(settings, revision, user.id().map(ToString::to_string))
+        .await?;
+
+    Ok(HttpResponse::NoContent()
+        .append_header((header::ETAG, ETag(EntityTag::new_strong(revision))))
+        .finish())
+}
+
+#[utoipa::path(
+    tag = "settings",
+    operation_id = "listSettingsHistory",
+    params(
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "The changes of the settings, newest first", body = PaginatedResults<SettingsChange>),
+    )
+)]
+#[get("/v2/settings/history")]
+/// Get the changes of the runtime settings
+async fn history(
+    service: web::Data<SettingsService>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadSettings>,
+) -> Result<impl Responder, Error> {
+    Ok(web::Json(service.history(paginated).await?))
+}
@@ -0,0 +1,8 @@
+//! Settings, which can be changed at runtime.
+//!
+//! Settings override the values configured when starting an instance, without the need of
+//! restarting it. Each change is recorded, together with the user performing it.
+
+pub mod endpoints;
+pub mod model;
+pub mod service;
@@ -0,0 +1,94 @@
+use time::OffsetDateTime;
+use trustify_entity::settings_history;
+use utoipa::ToSchema;
+
+/// The upper bound of [`Settings::retention_days`].
+pub const MAX_RETENTION_DAYS: u32 = 3650;
+
+/// Settings, which can be changed at runtime.
+///
+/// A missing setting falls back to the value configured when starting the instance.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Settings {
+    /// Size limit of SBOMs, after decompression. Zero meaning "unlimited".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sbom_upload_limit: Option<usize>,
+    /// Size limit of advisories, after decompression. Zero meaning "unlimited".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub advisory_upload_limit: Option<usize>,
+    /// Size limit of a dataset archive, as uploaded. Zero meaning "unlimited".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dataset_upload_limit: Option<usize>,
+    /// Size limit of documents in a dataset, after decompression. Zero meaning "unlimited".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dataset_entry_limit: Option<usize>,
+    /// Maximum number of items of collections embedded in detail responses. Zero meaning
+    /// "unlimited".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedded_item_limit: Option<usize>,
+    /// Look up purls without local vulnerability information using the OSV API. Only has an
+    /// effect if an OSV API is configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub osv_fallback: Option<bool>,
+    /// The precedence of statuses, highest first, deciding the effective status of a product
+    /// with statements of several advisories. Changing it requires recomputing the statuses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_precedence: Option<Vec<String>>,
+    /// Days after which events, which could not be published, get dropped. Between 1 and 3650,
+    /// defaults to the retention configured when starting the instance (7 days, unless changed).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retention_days: Option<u32>,
+}
+
+impl Settings {
+    /// Check that the settings are in range, returning a description of the first invalid one.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(days) = self.retention_days {
+            if !(1..=MAX_RETENTION_DAYS).contains(&days) {
+                return Err(format!(
+                    "retentionDays must be between 1 and {MAX_RETENTION_DAYS}, but is {days}"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A change of the settings.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsChange {
+    /// The revision the change resulted in
+    pub revision: String,
+    /// The time of the change
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified: OffsetDateTime,
+    /// The ID of the user performing the change, absent if authentication is disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// The settings, as stored by the change
+    #[schema(value_type = Settings)]
+    pub settings: serde_json::Value,
+}
+
+impl From<settings_history::Model> for SettingsChange {
+    fn from(value: settings_history::Model) -> Self {
+        let settings_history::Model {
+            id: _,
+            key: _,
+            revision,
+            modified,
+            user_id,
+            data,
+        } = value;
+
+        Self {
+            revision: revision.to_string(),
+            modified,
+            user: user_id,
+            settings: data,
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use crate::model::{Settings, SettingsChange};
+use actix_web::{HttpResponse, ResponseError, body::BoxBody};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, TransactionTrait, prelude::Uuid,
+};
+use sea_query::{Alias, Expr, OnConflict};
+use std::sync::{Arc, RwLock};
+use time::OffsetDateTime;
+use trustify_common::{
+    db::{Database, limiter::LimiterTrait},
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults, Revisioned},
+};
+use trustify_entity::{settings, settings_history};
+
+/// The key the runtime settings are stored under.
+const KEY: &str = "runtime";
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("mid air collision")]
+    MidAirCollision,
+    #[error("invalid settings: {0}")]
+    Invalid(String),
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl ResponseError for Error {
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        match self {
+            Error::MidAirCollision => HttpResponse::PreconditionFailed().json(ErrorInformation {
+                error: "MidAirCollision".into(),
+                message: self.to_string(),
+                details: None,
+            }),
+            Error::Invalid(_) => HttpResponse::BadRequest().json(ErrorInformation {
+                error: "InvalidSettings".into(),
+                message: self.to_string(),
+                details: None,
+            }),
+            _ => HttpResponse::InternalServerError().json(ErrorInformation {
+                error: "Internal".into(),
+                message: self.to_string(),
+                details: None,
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SettingsService {
+    db: Database,
+    /// The settings currently in effect, along with their revision.
+    current: Arc<RwLock<Option<(Uuid, Settings)>>>,
+}
+
+impl SettingsService {
+    pub fn new(db: Database) -> Self {
+        Self {
+            db,
+            current: Default::default(),
+        }
+    }
+
+    /// Get the stored settings, `None` if they never got changed.
+    pub async fn get(&self) -> Result<Option<Revisioned<Settings>>, Error> {
+        let Some(result) = settings::Entity::find_by_id(KEY).one(&self.db).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Revisioned {
+            value: serde_json::from_value(result.data)?,
+            revision: result.revision.to_string(),
+        }))
+    }
+
+    /// Get the settings currently in effect.
+    ///
+    /// Settings which can't be read are reported, and ignored. Otherwise, a single broken value
+    /// would fail each request depending on the settings.
+    ///
+    /// The settings are cached, only their revision is checked for changes, which might have been
+    /// made by other instances.
+    pub async fn current(&self) -> Result<Settings, DbErr> {
+        let Some(revision) = settings::Entity::find_by_id(KEY)
+            .select_only()
+            .column(settings::Column::Revision)
+            .into_tuple::<Uuid>()
+            .one(&self.db)
+            .await?
+        else {
+            return Ok(Settings::default());
+        };
+
+        if let Ok(current) = self.current.read() {
+            if let Some((_, settings)) = current.as_ref().filter(|(r, _)| *r == revision) {
+                return Ok(settings.clone());
+            }
+        }
+
+        let Some(result) = settings::Entity::find_by_id(KEY).one(&self.db).await? else {
+            return Ok(Settings::default());
+        };
+
+        let settings = serde_json::from_value(result.data).unwrap_or_else(|err| {
+            log::warn!("Failed to read settings, using defaults: {err}");
+            Settings::default()
+        });
+        self.cache(result.revision, &settings);
+
+        Ok(settings)
+    }
+
+    /// Remember the settings of a revision.
+    fn cache(&self, revision: Uuid, settings: &Settings) {
+        if let Ok(mut current) = self.current.write() {
+            *current = Some((revision, settings.clone()));
+        }
+    }
+
+    /// Replace the settings, recording the change. Fails if a setting is out of range.
+    pub async fn set(
+        &self,
+        settings: Settings,
+        expected_revision: Option<&str>,
+        user_id: Option<String>,
+    ) -> Result<Revisioned<()>, Error> {
+        settings.validate().map_err(Error::Invalid)?;
+
+        let next = Uuid::new_v4();
+        let modified = OffsetDateTime::now_utc();
+        let data = serde_json::to_value(&settings)?;
+
+        let tx = self.db.begin().await?;
+
+        match expected_revision {
+            Some(expected_revision) => {
+                // if we expect a revision, just update
+                let result = settings::Entity::update_many()
+                    .col_expr(settings::Column::Data, Expr::value(data.clone()))
+                    .col_expr(settings::Column::Revision, Expr::value(next))
+                    .col_expr(settings::Column::Modified, Expr::value(modified))
+                    .filter(settings::Column::Key.eq(KEY))
+                    .filter(
+                        settings::Column::Revision
+                            .into_expr()
+                            .cast_as(Alias::new("text"))
+                            .eq(expected_revision),
+                    )
+                    .exec(&tx)
+                    .await?;
+
+                if result.rows_affected == 0 {
+                    // we expected a revision, but didn't find one, we don't update it, but fail
+                    return Err(Error::MidAirCollision);
+                }
+            }
+            None => {
+                let on_conflict = OnConflict::column(settings::Column::Key)
+                    .values([
+                        (settings::Column::Revision, next.into()),
+                        (settings::Column::Modified, modified.into()),
+                        (settings::Column::Data, data.clone().into()),
+                    ])
+                    .to_owned();
+
+                settings::Entity::insert(settings::ActiveModel {
+                    key: Set(KEY.to_string()),
+                    revision: Set(next),
+                    modified: Set(modified),
+                    data: Set(data.clone()),
+                })
+                .on_conflict(on_conflict)
+                .exec_without_returning(&tx)
+                .await?;
+            }
+        }
+
+        record(&tx, next, modified, user_id, data).await?;
+
+        tx.commit().await?;
+
+        self.cache(next, &settings);
+
+        Ok(Revisioned {
+            value: (),
+            revision: next.to_string(),
+        })
+    }
+
+    /// Get the changes of the settings, newest first.
+    pub async fn history(
+        &self,
+        paginated: Paginated,
+    ) -> Result<PaginatedResults<SettingsChange>, Error> {
+        let limiting = settings_history::Entity::find()
+            .filter(settings_history::Column::Key.eq(KEY))
+            .order_by_desc(settings_history::Column::Modified)
+            .limiting(&self.db, paginated.offset, paginated.limit)
+            .counting(paginated.count);
+
+        Ok(PaginatedResults {
+            total: limiting.total().await?,
+            items: limiting
+                .fetch()
+                .await?
+                .into_iter()
+                .map(SettingsChange::from)
+                .collect(),
+        })
+    }
+}
+
+/// Record a change in the history of the settings.
+async fn record(
+    db: &impl ConnectionTrait,
+    revision: Uuid,
+    modified: OffsetDateTime,
+    user_id: Option<String>,
+    data: serde_json::Value,
+) -> Result<(), DbErr> {
+    settings_history::Entity::insert(settings_history::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        key: Set(KEY.to_string()),
+        revision: Set(revision),
+        modified: Set(modified),
+        user_id: Set(user_id),
+        data: Set(data),
+    })
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use actix_http::{StatusCode, header};
+use actix_web::test::TestRequest;
+use serde_json::json;
+use test_context::test_context;
+use test_log::test;
+use trustify_common::model::{Paginated, Revisioned};
+use trustify_module_settings::{
+    endpoints::configure,
+    model::Settings,
+    service::{Error, SettingsService},
+};
+use trustify_test_context::{
+    TrustifyContext,
+    call::{self, CallService},
+};
+
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn set_and_history(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let service = SettingsService::new(ctx.db.clone());
+
+    // initially, nothing is overridden
+
+    assert!(service.get().await?.is_none());
+    assert_eq!(service.current().await?, Settings::default());
+
+    // first change, by some user
+
+    let Revisioned { revision, .. } = service
+        .set(
+            Settings {
+                sbom_upload_limit: Some(1024),
+                ..Default::default()
+            },
+            None,
+            Some("user-a".into()),
+        )
+        .await?;
+
+    // changing an outdated revision must fail
+
+    let result = service
+        .set(Settings::default(), Some("outdated"), None)
+        .await;
+    assert!(matches!(result, Err(Error::MidAirCollision)));
+
+    // second change, based on the current revision
+
+    service
+        .set(
+            Settings {
+                osv_fallback: Some(false),
+                ..Default::default()
+            },
+            Some(&revision),
+            None,
+        )
+        .await?;
+
+    assert_eq!(
+        service.current().await?,
+        Settings {
+            osv_fallback: Some(false),
+            ..Default::default()
+        }
+    );
+
+    // changes by another instance are picked up
+
+    let other = SettingsService::new(ctx.db.clone());
+    other
+        .set(
+            Settings {
+                sbom_upload_limit: Some(2048),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .await?;
+
+    assert_eq!(
+        service.current().await?,
+        Settings {
+            sbom_upload_limit: Some(2048),
+            ..Default::default()
+        }
+    );
+
+    // the history has all changes, newest first
+
+    let history = service.history(Paginated::default()).await?;
+    assert_eq!(history.total, 3);
+    assert_eq!(history.items[0].settings, json!({"sbomUploadLimit": 2048}));
+    assert_eq!(history.items[1].user, None);
+    assert_eq!(history.items[1].settings, json!({"osvFallback": false}));
+    assert_eq!(history.items[2].user.as_deref(), Some("user-a"));
+    assert_eq!(history.items[2].revision, revision);
+    assert_eq!(history.items[2].settings, json!({"sbomUploadLimit": 1024}));
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn endpoints(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = call::caller(|svc| configure(svc, ctx.db.clone())).await?;
+
+    let request = TestRequest::get().uri("/api/v2/settings").to_request();
+    let settings: Settings = app.call_and_read_body_json(request).await;
+    assert_eq!(settings, Settings::default());
+
+    let request = TestRequest::put()
+        .uri("/api/v2/settings")
+        .set_json(json!({"embeddedItemLimit": 10}))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(response.headers().contains_key(header::ETAG));
+
+    let request = TestRequest::get().uri("/api/v2/settings").to_request();
+    let settings: Settings = app.call_and_read_body_json(request).await;
+    assert_eq!(settings.embedded_item_limit, Some(10));
+
+    // unknown settings are rejected
+
+    let request = TestRequest::put()
+        .uri("/api/v2/settings")
+        .set_json(json!({"unknownLimit": 10}))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // as are values out of range
+
+    let request = TestRequest::put()
+        .uri("/api/v2/settings")
+        .set_json(json!({"retentionDays": 0}))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = TestRequest::put()
+        .uri("/api/v2/settings")
+        .set_json(json!({"retentionDays": 30}))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    Ok(())
+}
@@ -51,6 +51,28 @@ impl StorageBackend for DispatchBackend {
                 .map_err(anyhow::Error::from),
         }
     }
+
+    async fn retrieve_range<'a>(
+        &self,
+        key: StorageKey,
+        range: Range<u64>,
+    ) -> Result<Option<impl Stream<Item = Result<Bytes, Self::Error>> + 'a>, Self::Error>
+    where
+        Self: Sized,
+    {
+        match self {
+            Self::Filesystem(backend) => backend
+                .retrieve_range(key, range)
+                .await
+                .map(|stream| stream.map(|stream| stream.map_err(anyhow::Error::from).boxed()))
+                .map_err(anyhow::Error::from),
+            Self::S3(backend) => backend
+                .retrieve_range(key, range)
+                .await
+                .map(|stream| stream.map(|stream| stream.map_err(anyhow::Error::from).boxed()))
+                .map_err(anyhow::Error::from),
+        }
+    }
 }
 
 impl DispatchBackend {
@@ -1,12 +1,14 @@
 use crate::service::{
-    StorageBackend, StorageKey, StorageResult, StoreError, compression::Compression, temp::TempFile,
+    StorageBackend, StorageKey, StorageResult, StoreError, compression::Compression, ranged,
+    temp::TempFile,
 };
 use anyhow::Context;
 use bytes::Bytes;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use std::{
     fmt::Debug,
-    io::ErrorKind,
+    io::{ErrorKind, SeekFrom},
+    ops::Range,
     path::{Path, PathBuf},
     pin::pin,
 };
@@ -14,10 +16,10 @@ use strum::IntoEnumIterator;
 use tempfile::{TempDir, tempdir};
 use tokio::{
     fs::{File, create_dir_all},
-    io::AsyncWriteExt,
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
 };
 use tokio_util::io::ReaderStream;
-use tracing::instrument;
+use tracing::{Span, field::Empty, instrument};
 
 /// A filesystem backed store
 ///
@@ -86,6 +88,26 @@ impl FileSystemBackend {
         })
     }
 
+    /// Open the file of a stored document, trying all compression types.
+    async fn open(&self, hash: &str) -> Result<Option<(File, Compression)>, std::io::Error> {
+        // try all compression types, return the first one we find
+        for compression in &self.read_compressions {
+            let target = level_dir(&self.content, hash, NUM_LEVELS);
+            let mut target = target.join(hash);
+            target.set_extension(compression.extension());
+
+            log::debug!("Opening file: {}", target.display());
+
+            match File::open(&target).await {
+                Ok(file) => return Ok(Some((file, *compression))),
+                Err(err) if err.kind() == ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Create a new storage for testing
     pub async fn for_test() -> anyhow::Result<(Self, TempDir)> {
         let dir = tempdir()?;
@@ -99,7 +121,11 @@ impl FileSystemBackend {
 impl StorageBackend for FileSystemBackend {
     type Error = std::io::Error;
 
-    #[instrument(skip(stream), err(Debug, level=tracing::Level::INFO))]
+    #[instrument(
+        skip(stream),
+        fields(storage.backend = "fs", storage.key = Empty, storage.size = Empty),
+        err(Debug, level=tracing::Level::INFO)
+    )]
     async fn store<E, S>(&self, stream: S) -> Result<StorageResult, StoreError<E, Self::Error>>
     where
         E: Debug,
@@ -112,6 +138,10 @@ impl StorageBackend for FileSystemBackend {
         let result = file.result();
         let key = result.key().to_string();
 
+        let span = Span::current();
+        span.record("storage.key", &key);
+        span.record("storage.size", result.digests.size);
+
         // create the target path
 
         let target = level_dir(&self.content, &key, NUM_LEVELS);
@@ -139,28 +169,45 @@ impl StorageBackend for FileSystemBackend {
         Ok(result)
     }
 
+    #[instrument(
+        skip_all,
+        fields(storage.backend = "fs", storage.key = %hash),
+        err(level=tracing::Level::INFO)
+    )]
     async fn retrieve<'a>(
         &self,
         StorageKey(hash): StorageKey,
     ) -> Result<Option<impl Stream<Item = Result<Bytes, Self::Error>> + 'a>, Self::Error> {
-        // try all compression types, return the first one we find
-        for compression in &self.read_compressions {
-            let target = level_dir(&self.content, &hash, NUM_LEVELS);
-            let mut target = target.join(&hash);
-            target.set_extension(compression.extension());
-
-            log::debug!("Opening file: {}", target.display());
-
-            let file = match File::open(&target).await {
-                Ok(file) => Some(file),
-                Err(err) if err.kind() == ErrorKind::NotFound => continue,
-                Err(err) => return Err(err),
-            };
-
-            return Ok(file.map(|r| ReaderStream::new(compression.reader(r))));
-        }
+        Ok(self
+            .open(&hash)
+            .await?
+            .map(|(file, compression)| ReaderStream::new(compression.reader(file))))
+    }
 
-        Ok(None)
+    #[instrument(
+        skip_all,
+        fields(storage.backend = "fs", storage.key = %hash, storage.range = ?range),
+        err(level=tracing::Level::INFO)
+    )]
+    async fn retrieve_range<'a>(
+        &self,
+        StorageKey(hash): StorageKey,
+        range: Range<u64>,
+    ) -> Result<Option<impl Stream<Item = Result<Bytes, Self::Error>> + 'a>, Self::Error> {
+        let Some((mut file, compression)) = self.open(&hash).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(match compression {
+            // uncompressed files can be accessed directly
+            Compression::None => {
+                file.seek(SeekFrom::Start(range.start)).await?;
+                ReaderStream::new(file.take(range.end.saturating_sub(range.start))).left_stream()
+            }
+            compression => {
+                ranged(ReaderStream::new(compression.reader(file)), range).right_stream()
+            }
+        }))
     }
 }
 
@@ -263,6 +310,37 @@ mod test {
         drop(backend);
     }
 
+    #[test(tokio::test)]
+    #[rstest]
+    #[case(Compression::None)]
+    #[case(Compression::Zstd)]
+    async fn test_store_and_read_range(#[case] compression: Compression) {
+        let dir = tempdir().unwrap();
+        let backend = FileSystemBackend::new(dir.path(), compression)
+            .await
+            .unwrap();
+
+        let digest = backend
+            .store(ReaderStream::new(&b"Hello World"[..]))
+            .await
+            .expect("store must succeed");
+
+        let mut stream = backend
+            .retrieve_range(digest.key(), 3..8)
+            .await
+            .expect("retrieve must succeed")
+            .expect("must be found");
+
+        let mut content = BytesMut::new();
+        while let Some(data) = stream.next().await {
+            content.extend(&data.expect("read must succeed"));
+        }
+
+        assert_eq!(content.as_ref(), b"lo Wo");
+
+        drop(backend);
+    }
+
     /// This test should ensure that we can also read compression algorithm other than the
     /// configured write format.
     #[test(tokio::test)]
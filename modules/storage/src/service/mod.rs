@@ -9,10 +9,11 @@ pub use compression::Compression;
 
 use crate::service::fs::FileSystemBackend;
 use bytes::Bytes;
-use futures::Stream;
+use futures::{Stream, StreamExt};
 use hex::ToHex;
 use std::fmt::{Debug, Display, Formatter};
 use std::future::Future;
+use std::ops::Range;
 use trustify_common::hashing::Digests;
 use trustify_common::id::Id;
 
@@ -100,4 +101,93 @@ pub trait StorageBackend {
     ) -> impl Future<
         Output = Result<Option<impl Stream<Item = Result<Bytes, Self::Error>> + 'a>, Self::Error>,
     >;
+
+    /// Retrieve a range of the content as an async reader
+    ///
+    /// The range is applied to the original (uncompressed) content. The default implementation
+    /// retrieves the full content, skipping everything outside the range.
+    fn retrieve_range<'a>(
+        &self,
+        key: StorageKey,
+        range: Range<u64>,
+    ) -> impl Future<
+        Output = Result<Option<impl Stream<Item = Result<Bytes, Self::Error>> + 'a>, Self::Error>,
+    >
+    where
+        Self::Error: 'a,
+    {
+        async move {
+            Ok(self
+                .retrieve(key)
+                .await?
+                .map(|stream| ranged(stream, range)))
+        }
+    }
+}
+
+/// Limit a stream of bytes to a range.
+///
+/// Chunks before the range are skipped, and the stream ends as soon as the end of the range
+/// was reached, without consuming the remainder of the source stream.
+pub fn ranged<'a, S, E>(stream: S, range: Range<u64>) -> impl Stream<Item = Result<Bytes, E>> + 'a
+where
+    S: Stream<Item = Result<Bytes, E>> + 'a,
+    E: 'a,
+{
+    futures::stream::unfold((Box::pin(stream), 0u64), move |(mut stream, mut offset)| {
+        let range = range.clone();
+        async move {
+            while offset < range.end {
+                let chunk = match stream.next().await? {
+                    Ok(chunk) => chunk,
+                    // end the stream after reporting the error
+                    Err(err) => return Some((Err(err), (stream, range.end))),
+                };
+
+                let start = offset;
+                offset += chunk.len() as u64;
+
+                if offset <= range.start {
+                    continue;
+                }
+
+                let from = range.start.saturating_sub(start) as usize;
+                let to = (range.end.min(offset) - start) as usize;
+
+                return Some((Ok(chunk.slice(from..to)), (stream, offset)));
+            }
+
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::TryStreamExt;
+    use test_log::test;
+
+    async fn collect(range: Range<u64>) -> Vec<u8> {
+        let chunks = ["Hello", " ", "World", "!"]
+            .map(|chunk| Ok::<_, std::io::Error>(Bytes::from_static(chunk.as_bytes())));
+
+        ranged(futures::stream::iter(chunks), range)
+            .try_concat()
+            .await
+            .expect("must not fail")
+            .to_vec()
+    }
+
+    #[test(tokio::test)]
+    async fn test_ranged() {
+        assert_eq!(collect(0..12).await, b"Hello World!");
+        assert_eq!(collect(0..100).await, b"Hello World!");
+        assert_eq!(collect(0..5).await, b"Hello");
+        assert_eq!(collect(3..8).await, b"lo Wo");
+        assert_eq!(collect(6..11).await, b"World");
+        assert_eq!(collect(11..12).await, b"!");
+        assert_eq!(collect(12..20).await, b"");
+        assert_eq!(collect(4..4).await, b"");
+    }
 }
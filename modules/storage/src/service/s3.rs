@@ -11,7 +11,7 @@ use http::{HeaderMap, HeaderValue, header::CONTENT_ENCODING};
 use s3::{Bucket, creds::Credentials, error::S3Error};
 use std::{fmt::Debug, io, pin::pin, str::FromStr};
 use tokio_util::io::{ReaderStream, StreamReader};
-use tracing::instrument;
+use tracing::{Span, field::Empty, instrument};
 
 #[derive(Clone, Debug)]
 pub struct S3Backend {
@@ -51,7 +51,11 @@ impl S3Backend {
 impl StorageBackend for S3Backend {
     type Error = Error;
 
-    #[instrument(skip(self, stream), err(Debug, level=tracing::Level::INFO))]
+    #[instrument(
+        skip(self, stream),
+        fields(storage.backend = "s3", storage.key = Empty, storage.size = Empty),
+        err(Debug, level=tracing::Level::INFO)
+    )]
     async fn store<E, S>(&self, stream: S) -> Result<StorageResult, StoreError<E, Self::Error>>
     where
         E: Debug,
@@ -65,6 +69,10 @@ impl StorageBackend for S3Backend {
             .await;
         let result = file.result();
 
+        let span = Span::current();
+        span.record("storage.key", result.key().to_string());
+        span.record("storage.size", result.digests.size);
+
         self.bucket
             .put_object_stream(&mut source, result.key().to_string())
             .await
@@ -73,6 +81,11 @@ impl StorageBackend for S3Backend {
         Ok(result)
     }
 
+    #[instrument(
+        skip_all,
+        fields(storage.backend = "s3", storage.key = %key),
+        err(level=tracing::Level::INFO)
+    )]
     async fn retrieve<'a>(
         &self,
         StorageKey(key): StorageKey,
@@ -0,0 +1,113 @@
+use actix_web::{
+    Error,
+    body::MessageBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform, forward_ready},
+};
+use futures_util::future::{LocalBoxFuture, Ready, ready};
+use parking_lot::Mutex;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Count API calls, by their method and path pattern.
+///
+/// Only the pattern of the matched resource gets recorded (like `/api/v2/sbom/{id}`), never the
+/// actual path. Requests not matching any resource are not counted.
+#[derive(Clone, Debug, Default)]
+pub struct ApiCalls {
+    calls: Arc<Mutex<BTreeMap<String, u64>>>,
+}
+
+impl ApiCalls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&self, operation: String) {
+        *self.calls.lock().entry(operation).or_default() += 1;
+    }
+
+    /// The current number of calls, by operation.
+    pub fn snapshot(&self) -> BTreeMap<String, u64> {
+        self.calls.lock().clone()
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ApiCalls
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = ApiCallsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ApiCallsMiddleware {
+            service,
+            calls: self.clone(),
+        }))
+    }
+}
+
+pub struct ApiCallsMiddleware<S> {
+    service: S,
+    calls: ApiCalls,
+}
+
+impl<S, B> Service<ServiceRequest> for ApiCallsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if let Some(pattern) = req.match_pattern() {
+            self.calls.record(format!("{} {pattern}", req.method()));
+        }
+
+        Box::pin(self.service.call(req))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::{App, HttpResponse, test, web};
+
+    #[actix_web::test]
+    async fn count_by_pattern() {
+        let calls = ApiCalls::new();
+        let app = test::init_service(
+            App::new()
+                .wrap(calls.clone())
+                .route("/item/{id}", web::get().to(HttpResponse::Ok))
+                .route("/item/{id}", web::delete().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        for req in [
+            test::TestRequest::get().uri("/item/1"),
+            test::TestRequest::get().uri("/item/2"),
+            test::TestRequest::delete().uri("/item/1"),
+            test::TestRequest::get().uri("/unknown"),
+        ] {
+            test::call_service(&app, req.to_request()).await;
+        }
+
+        assert_eq!(
+            calls.snapshot(),
+            BTreeMap::from_iter([
+                ("DELETE /item/{id}".to_string(), 1),
+                ("GET /item/{id}".to_string(), 2),
+            ])
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+use url::Url;
+
+#[derive(clap::Args, Debug, Clone)]
+#[command(next_help_heading = "Telemetry")]
+pub struct TelemetryConfig {
+    /// Enable sending anonymous, aggregated usage reports. Disabled by default.
+    #[arg(
+        id = "telemetry",
+        long = "telemetry",
+        env = "TRUSTD_TELEMETRY",
+        default_value_t = false
+    )]
+    pub enabled: bool,
+
+    /// The endpoint receiving the usage reports. Required when reporting is enabled.
+    #[arg(id = "telemetry-endpoint", long, env = "TRUSTD_TELEMETRY_ENDPOINT")]
+    pub endpoint: Option<Url>,
+
+    /// The interval of sending usage reports.
+    #[arg(
+        id = "telemetry-interval",
+        long,
+        env = "TRUSTD_TELEMETRY_INTERVAL",
+        default_value = "24h"
+    )]
+    pub interval: humantime::Duration,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: None,
+            interval: Duration::from_secs(24 * 60 * 60).into(),
+        }
+    }
+}
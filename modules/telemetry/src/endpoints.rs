@@ -0,0 +1,30 @@
+use crate::{
+    model::TelemetryPreview,
+    service::{Error, TelemetryService},
+};
+use actix_web::{HttpResponse, Responder, get, web};
+use trustify_auth::{ReadTelemetry, authorizer::Require};
+
+/// mount the "telemetry" module
+pub fn configure(
+    svc: &mut utoipa_actix_web::service_config::ServiceConfig,
+    service: TelemetryService,
+) {
+    svc.app_data(web::Data::new(service)).service(preview);
+}
+
+#[utoipa::path(
+    tag = "telemetry",
+    operation_id = "previewTelemetry",
+    responses(
+        (status = 200, description = "The usage report, exactly as it would be sent", body = TelemetryPreview),
+    )
+)]
+#[get("/v2/telemetry/preview")]
+/// Preview the anonymous usage report
+async fn preview(
+    service: web::Data<TelemetryService>,
+    _: Require<ReadTelemetry>,
+) -> Result<impl Responder, Error> {
+    Ok(HttpResponse::Ok().json(service.preview().await?))
+}
@@ -0,0 +1,12 @@
+//! Opt-in reporting of anonymous, aggregated usage data.
+//!
+//! When enabled, a report is periodically sent to a configured endpoint. The report only contains
+//! aggregated numbers: the number of documents by format, the enabled features, and the number of
+//! API calls by operation. The exact content can be inspected using the preview endpoint, no
+//! matter if reporting is enabled or not.
+
+pub mod calls;
+pub mod config;
+pub mod endpoints;
+pub mod model;
+pub mod service;
@@ -0,0 +1,29 @@
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// An anonymous report of aggregated usage data
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryReport {
+    /// The version of the instance
+    pub version: String,
+    /// The number of documents, by format
+    pub documents: BTreeMap<String, u64>,
+    /// The features, and if they are enabled
+    pub features: BTreeMap<String, bool>,
+    /// The number of API calls since the start of the instance, by method and path pattern
+    pub api_calls: BTreeMap<String, u64>,
+}
+
+/// The usage report, exactly as it would be sent
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryPreview {
+    /// If sending reports is enabled
+    pub enabled: bool,
+    /// The endpoint receiving the reports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// The report
+    pub report: TelemetryReport,
+}
@@ -0,0 +1,145 @@
+use crate::{
+    calls::ApiCalls,
+    config::TelemetryConfig,
+    model::{TelemetryPreview, TelemetryReport},
+};
+use actix_web::{HttpResponse, ResponseError, body::BoxBody};
+use sea_orm::{ConnectionTrait, DbErr, Statement};
+use std::collections::BTreeMap;
+use tokio::time::{Instant, interval_at};
+use trustify_common::{db::Database, error::ErrorInformation, reqwest::ClientFactory};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+}
+
+impl ResponseError for Error {
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        HttpResponse::InternalServerError().json(ErrorInformation {
+            error: "Internal".into(),
+            message: self.to_string(),
+            details: None,
+        })
+    }
+}
+
+/// The tables of documents, which get counted by the format stored in their `type` label.
+const DOCUMENT_TABLES: &[&str] = &["sbom", "advisory"];
+
+#[derive(Clone, Debug)]
+pub struct TelemetryService {
+    db: Database,
+    config: TelemetryConfig,
+    features: BTreeMap<String, bool>,
+    calls: ApiCalls,
+}
+
+impl TelemetryService {
+    pub fn new<I, K>(db: Database, config: TelemetryConfig, features: I) -> Self
+    where
+        I: IntoIterator<Item = (K, bool)>,
+        K: Into<String>,
+    {
+        Self {
+            db,
+            config,
+            features: features
+                .into_iter()
+                .map(|(name, enabled)| (name.into(), enabled))
+                .collect(),
+            calls: ApiCalls::new(),
+        }
+    }
+
+    pub fn config(&self) -> &TelemetryConfig {
+        &self.config
+    }
+
+    /// The middleware, counting the API calls for the report.
+    pub fn api_calls(&self) -> ApiCalls {
+        self.calls.clone()
+    }
+
+    /// Create the report from the current state.
+    pub async fn report(&self) -> Result<TelemetryReport, Error> {
+        let mut documents = BTreeMap::new();
+
+        for table in DOCUMENT_TABLES {
+            let rows = self
+                .db
+                .query_all(Statement::from_string(
+                    self.db.get_database_backend(),
+                    format!(
+                        r#"SELECT labels->>'type' AS format, COUNT(*) AS count FROM "{table}" GROUP BY 1"#
+                    ),
+                ))
+                .await?;
+
+            for row in rows {
+                let format = row
+                    .try_get::<Option<String>>("", "format")?
+                    .unwrap_or_else(|| format!("{table}:unknown"));
+                let count: i64 = row.try_get("", "count")?;
+                *documents.entry(format).or_default() += count as u64;
+            }
+        }
+
+        Ok(TelemetryReport {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            documents,
+            features: self.features.clone(),
+            api_calls: self.calls.snapshot(),
+        })
+    }
+
+    /// Preview the report, as it would be sent.
+    pub async fn preview(&self) -> Result<TelemetryPreview, Error> {
+        Ok(TelemetryPreview {
+            enabled: self.config.enabled,
+            endpoint: self.config.endpoint.as_ref().map(|url| url.to_string()),
+            report: self.report().await?,
+        })
+    }
+
+    /// Periodically send the report to the configured endpoint, never returns.
+    pub async fn run(self) -> anyhow::Result<()> {
+        let Some(endpoint) = self.config.endpoint.clone() else {
+            anyhow::bail!("Telemetry is enabled, but no endpoint is configured");
+        };
+
+        log::info!(
+            "Sending usage reports to {endpoint}, every {}",
+            self.config.interval
+        );
+
+        let client = ClientFactory::new().build()?;
+        let period = *self.config.interval;
+        // don't report right away, the instance might only be started briefly
+        let mut interval = interval_at(Instant::now() + period, period);
+
+        loop {
+            interval.tick().await;
+
+            let report = match self.report().await {
+                Ok(report) => report,
+                Err(err) => {
+                    log::warn!("Failed to create usage report: {err}");
+                    continue;
+                }
+            };
+
+            match client
+                .post(endpoint.clone())
+                .json(&report)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+            {
+                Ok(_) => log::debug!("Sent usage report"),
+                Err(err) => log::warn!("Failed to send usage report: {err}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use test_context::test_context;
+use test_log::test;
+use trustify_module_telemetry::service::TelemetryService;
+use trustify_test_context::TrustifyContext;
+
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn document_counts(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_documents([
+        "zookeeper-3.9.2-cyclonedx.json",
+        "spdx/quarkus-bom-3.2.11.Final-redhat-00001.json",
+        "csaf/cve-2023-0044.json",
+    ])
+    .await?;
+
+    let service = TelemetryService::new(ctx.db.clone(), Default::default(), [("graphql", false)]);
+    let report = service.report().await?;
+
+    assert_eq!(report.documents.get("cyclonedx"), Some(&1));
+    assert_eq!(report.documents.get("spdx"), Some(&1));
+    assert_eq!(report.documents.get("csaf"), Some(&1));
+    assert_eq!(report.features.get("graphql"), Some(&false));
+    assert!(report.api_calls.is_empty());
+
+    let preview = service.preview().await?;
+    assert!(!preview.enabled);
+    assert!(preview.endpoint.is_none());
+    assert_eq!(preview.report, report);
+
+    Ok(())
+}
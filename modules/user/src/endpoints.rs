@@ -5,7 +5,7 @@ use actix_web::{
     put, web,
 };
 use trustify_auth::authenticator::user::UserDetails;
-use trustify_common::{db::Database, model::Revisioned};
+use trustify_common::{db::Database, error::ErrorInformation, model::Revisioned};
 
 /// mount the "user" module
 pub fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
@@ -30,7 +30,7 @@ pub fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfig, db:
                 ("etag" = String, description = "Revision ID")
             )
         ),
-        (status = 404, description = "Unknown user preference key"),
+        (status = 404, description = "Unknown user preference key", body = ErrorInformation),
     )
 )]
 #[get("/v2/userPreference/{key}")]
@@ -64,7 +64,7 @@ async fn get(
                 ("etag" = String, description = "Revision ID")
             )
         ),
-        (status = 412, description = "The provided If-Match revision did not match the actual revision")
+        (status = 412, description = "The provided If-Match revision did not match the actual revision", body = ErrorInformation)
     )
 )]
 #[put("/v2/userPreference/{key}")]
@@ -103,7 +103,7 @@ async fn set(
     ),
     responses(
         (status = 201, description = "User preferences are deleted"),
-        (status = 412, description = "The provided If-Match revision did not match the actual revision")
+        (status = 412, description = "The provided If-Match revision did not match the actual revision", body = ErrorInformation)
     )
 )]
 #[delete("/v2/userPreference/{key}")]
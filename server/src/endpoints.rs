@@ -1,27 +1,45 @@
 use actix_web::{
-    HttpRequest, HttpResponse, get,
+    HttpRequest, HttpResponse, Responder, get,
     http::header::AUTHORIZATION,
     web::{self},
 };
 use build_info::BuildInfo;
 use std::sync::Arc;
 use trustify_auth::authenticator::{Authenticator, user::UserInformation};
+use trustify_common::{db::Database, signing::ResponseSigner};
 use trustify_infrastructure::app::new_auth;
+use trustify_module_ingestor::service::Format;
+use trustify_module_settings::{
+    model::Settings,
+    service::{Error as SettingsError, SettingsService},
+};
 use utoipa::OpenApi;
 use utoipa_actix_web::service_config::ServiceConfig;
 
-pub fn configure(svc: &mut ServiceConfig, auth: Option<Arc<Authenticator>>) {
-    let mut scope = utoipa_actix_web::scope("/.well-known/trustify");
+pub fn configure(
+    svc: &mut ServiceConfig,
+    auth: Option<Arc<Authenticator>>,
+    signer: ResponseSigner,
+) {
+    let mut scope =
+        utoipa_actix_web::scope("/.well-known/trustify").app_data(web::Data::new(signer));
 
     if let Some(auth) = auth {
         scope = scope.app_data(web::Data::from(auth));
     }
 
-    svc.service(scope.service(info));
+    svc.service(scope.service(info).service(jwks));
+}
+
+/// Register the endpoint reporting the capabilities of the server, as part of the API.
+pub fn configure_api(svc: &mut ServiceConfig, db: Database, capabilities: Capabilities) {
+    svc.app_data(web::Data::new(SettingsService::new(db)))
+        .app_data(web::Data::new(capabilities))
+        .service(server_info);
 }
 
 #[derive(OpenApi)]
-#[openapi(paths(info), tags())]
+#[openapi(paths(info, jwks), tags())]
 pub struct ApiDoc;
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, utoipa::ToSchema)]
@@ -65,3 +83,111 @@ pub async fn info(req: HttpRequest, auth: Option<web::Data<Authenticator>>) -> H
         build: details.then(build_info),
     })
 }
+
+#[utoipa::path(
+    responses(
+        (status = 200, description = "The keys used for signing responses, as JWK set. Empty if signing is disabled.", body = serde_json::Value),
+    ),
+)]
+#[get("/jwks.json")]
+pub async fn jwks(signer: web::Data<ResponseSigner>) -> HttpResponse {
+    HttpResponse::Ok().json(signer.jwks())
+}
+
+/// The capabilities of the server, as configured when starting it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    pub ai: bool,
+    pub graphql: bool,
+    /// The limits, before applying the runtime settings.
+    pub limits: Limits,
+}
+
+/// Size limits of uploads. Zero meaning "unlimited".
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, utoipa::ToSchema)]
+pub struct Limits {
+    /// Size limit of SBOMs, after decompression
+    pub sbom_upload: usize,
+    /// Size limit of advisories, after decompression
+    pub advisory_upload: usize,
+    /// Size limit of a dataset archive, as uploaded
+    pub dataset_upload: usize,
+    /// Size limit of documents in a dataset, after decompression
+    pub dataset_entry: usize,
+}
+
+impl Limits {
+    /// The limits, with the runtime settings applied.
+    fn apply(&self, settings: &Settings) -> Self {
+        Self {
+            sbom_upload: settings.sbom_upload_limit.unwrap_or(self.sbom_upload),
+            advisory_upload: settings
+                .advisory_upload_limit
+                .unwrap_or(self.advisory_upload),
+            dataset_upload: settings.dataset_upload_limit.unwrap_or(self.dataset_upload),
+            dataset_entry: settings.dataset_entry_limit.unwrap_or(self.dataset_entry),
+        }
+    }
+}
+
+/// Information about the server, allowing clients to adapt to its capabilities.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, utoipa::ToSchema)]
+pub struct ServerInfo {
+    /// The version of the server
+    pub version: &'static str,
+    /// The document formats which can be ingested
+    pub formats: Vec<FormatInfo>,
+    pub features: Features,
+    /// The upload limits currently in effect
+    pub limits: Limits,
+}
+
+/// A document format which can be ingested.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, utoipa::ToSchema)]
+pub struct FormatInfo {
+    /// The name of the format, as accepted when explicitly providing the format of a document
+    pub format: &'static str,
+    /// The versions of the specification supported, empty if the format isn't versioned
+    pub versions: Vec<&'static str>,
+}
+
+/// Optional features, and if they are enabled.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, utoipa::ToSchema)]
+pub struct Features {
+    /// The AI assistant
+    pub ai: bool,
+    /// The GraphQL API
+    pub graphql: bool,
+}
+
+#[utoipa::path(
+    tag = "info",
+    operation_id = "getInfo",
+    responses(
+        (status = 200, description = "Information about the server", body = ServerInfo),
+    ),
+)]
+#[get("/v2/info")]
+/// Get the version, the supported formats, and the enabled features of the server
+pub async fn server_info(
+    capabilities: web::Data<Capabilities>,
+    settings: web::Data<SettingsService>,
+) -> actix_web::Result<impl Responder> {
+    let settings = settings.current().await.map_err(SettingsError::from)?;
+
+    Ok(HttpResponse::Ok().json(ServerInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        formats: Format::supported()
+            .into_iter()
+            .map(|(format, versions)| FormatInfo {
+                format: format.into(),
+                versions: versions.to_vec(),
+            })
+            .collect(),
+        features: Features {
+            ai: capabilities.ai,
+            graphql: capabilities.graphql,
+        },
+        limits: capabilities.limits.apply(&settings),
+    }))
+}
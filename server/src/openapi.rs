@@ -4,6 +4,7 @@ use trustify_common::{config::Database, db};
 use trustify_module_analysis::config::AnalysisConfig;
 use trustify_module_analysis::service::AnalysisService;
 use trustify_module_storage::service::{dispatch::DispatchBackend, fs::FileSystemBackend};
+use trustify_module_telemetry::service::TelemetryService;
 use utoipa::{
     Modify, OpenApi,
     openapi::security::{OpenIdConnect, SecurityScheme},
@@ -14,6 +15,7 @@ pub async fn create_openapi() -> anyhow::Result<utoipa::openapi::OpenApi> {
     let (db, postgresql) = db::embedded::create().await?;
     let (storage, _temp) = FileSystemBackend::for_test().await?;
     let analysis = AnalysisService::new(AnalysisConfig::default());
+    let telemetry = TelemetryService::new(db.clone(), Default::default(), [("graphql", true)]);
 
     let (_, mut openapi) = App::new()
         .into_utoipa_app()
@@ -26,6 +28,7 @@ pub async fn create_openapi() -> anyhow::Result<utoipa::openapi::OpenApi> {
                     storage: storage.into(),
                     auth: None,
                     analysis,
+                    telemetry,
                     with_graphql: true,
                 },
             );
@@ -1,7 +1,10 @@
 #[cfg(feature = "garage-door")]
 use crate::embedded_oidc;
 
-use crate::{endpoints, sample_data};
+use crate::{
+    endpoints::{self, Capabilities, Limits},
+    sample_data,
+};
 use actix_web::{
     HttpRequest, HttpResponse, Responder, Result,
     body::MessageBody,
@@ -24,7 +27,12 @@ use trustify_auth::{
     devmode::{FRONTEND_CLIENT_ID, ISSUER_URL, PUBLIC_CLIENT_IDS},
     swagger_ui::{SwaggerUiOidc, SwaggerUiOidcConfig, swagger_ui_with_auth},
 };
-use trustify_common::{config::Database, db, model::BinaryByteSize};
+use trustify_common::{
+    config::Database,
+    db,
+    model::BinaryByteSize,
+    signing::{ResponseSigner, SigningKey},
+};
 use trustify_infrastructure::{
     Infrastructure, InfrastructureConfig, InitContext, Metrics,
     app::{
@@ -36,13 +44,38 @@ use trustify_infrastructure::{
     otel::{Metrics as OtelMetrics, Tracing},
 };
 use trustify_module_analysis::{config::AnalysisConfig, service::AnalysisService};
+use trustify_module_events::{config::EventsConfig, publisher, service::EventRelay};
+#[cfg(feature = "ai")]
+use trustify_module_fundamental::ai::service::backend::{Llm, LlmBackend, LlmConfig};
+use trustify_module_fundamental::{
+    advisory::service::DeletionService,
+    stats::service::StatsService,
+    subscription::service::{SubscriptionListener, WebhookAllowlist},
+    tenant::TenantIsolation,
+};
 use trustify_module_graphql::RootQuery;
 use trustify_module_importer::server::importer;
-use trustify_module_ingestor::graph::Graph;
+use trustify_module_ingestor::{
+    graph::{
+        Graph,
+        organization::{OrganizationAlias, OrganizationAliases},
+    },
+    service::{
+        IngestorService, LabelTemplate, LabelTemplates,
+        enricher::{
+            BuiltinEnricher, Enrichers, FailurePolicy,
+            kev::{self, KevEnricher},
+            webhook::{WebhookConfig, WebhookEnricher},
+        },
+        job::JobRunner,
+        listener::IngestListeners,
+    },
+};
 use trustify_module_storage::{
     config::{StorageConfig, StorageStrategy},
     service::{dispatch::DispatchBackend, fs::FileSystemBackend, s3::S3Backend},
 };
+use trustify_module_telemetry::{config::TelemetryConfig, service::TelemetryService};
 use trustify_module_ui::{UI, endpoints::UiResources};
 use utoipa::{
     OpenApi,
@@ -93,6 +126,144 @@ pub struct Run {
     )]
     pub dataset_entry_limit: BinaryByteSize,
 
+    /// The size limit of a dataset archive, as uploaded.
+    #[arg(
+        long,
+        env = "TRUSTD_DATASET_UPLOAD_LIMIT",
+        default_value_t = default::dataset_upload_limit()
+    )]
+    pub dataset_upload_limit: BinaryByteSize,
+
+    /// The number of documents of a dataset, which get ingested in parallel.
+    #[arg(
+        long,
+        env = "TRUSTD_DATASET_CONCURRENCY",
+        default_value_t = default::dataset_concurrency()
+    )]
+    pub dataset_concurrency: usize,
+
+    /// The maximum ratio between the decompressed and the compressed size of an uploaded
+    /// document. Zero meaning "unlimited".
+    #[arg(
+        long,
+        env = "TRUSTD_COMPRESSION_RATIO_LIMIT",
+        default_value_t = default::compression_ratio_limit()
+    )]
+    pub compression_ratio_limit: usize,
+
+    /// Templates for labels, applied to uploaded documents.
+    ///
+    /// In the form of `<key>=<template>`, where the template may contain expressions like
+    /// `{{csaf.document.title}}`, which get evaluated against the content of the document.
+    #[arg(
+        long = "label-template",
+        env = "TRUSTD_LABEL_TEMPLATES",
+        value_delimiter = ','
+    )]
+    pub label_templates: Vec<LabelTemplate>,
+
+    /// Aliases of organizations, applied to the names of issuers and vendors of ingested
+    /// documents.
+    ///
+    /// In the form of `<alias>=<name>`, like `Red Hat Product Security=Red Hat`. Aliases are
+    /// matched ignoring the case.
+    #[arg(
+        long = "organization-alias",
+        env = "TRUSTD_ORGANIZATION_ALIASES",
+        value_delimiter = ','
+    )]
+    pub organization_aliases: Vec<OrganizationAlias>,
+
+    /// Look up purls without local vulnerability information using an OSV API, like
+    /// `https://api.osv.dev`. Found vulnerabilities get ingested as advisories.
+    #[arg(long, env = "TRUSTD_OSV_FALLBACK_URL")]
+    pub osv_fallback_url: Option<url::Url>,
+
+    /// The maximum number of items of collections embedded in detail responses, like the
+    /// affected packages of an advisory status. Exceeding items are available through a
+    /// paginated sub-resource. Zero meaning "unlimited".
+    #[arg(
+        long,
+        env = "TRUSTD_EMBEDDED_ITEM_LIMIT",
+        default_value_t = default::embedded_item_limit()
+    )]
+    pub embedded_item_limit: usize,
+
+    /// The number of workers, processing documents uploaded for asynchronous ingestion. Zero
+    /// disables processing them on this instance.
+    #[arg(
+        long,
+        env = "TRUSTD_INGESTION_WORKERS",
+        default_value_t = default::ingestion_workers()
+    )]
+    pub ingestion_workers: usize,
+
+    /// The URL of a service, called after a document got ingested. The service can respond with
+    /// labels, which get attached to the document.
+    #[arg(long, env = "TRUSTD_ENRICHMENT_URL")]
+    pub enrichment_url: Option<url::Url>,
+
+    /// The timeout of calling the enrichment service.
+    #[arg(long, env = "TRUSTD_ENRICHMENT_TIMEOUT", default_value = "10s")]
+    pub enrichment_timeout: humantime::Duration,
+
+    /// What to do if calling the enrichment service fails: keep the document and report a
+    /// warning (`warn`), or report the ingestion as failed (`fail`).
+    #[arg(
+        long,
+        env = "TRUSTD_ENRICHMENT_FAILURE_POLICY",
+        default_value_t = FailurePolicy::Warn
+    )]
+    pub enrichment_failure_policy: FailurePolicy,
+
+    /// Built-in stages, enriching documents after they got ingested. The stages run in the given
+    /// order, after the enrichment service. Failing stages are reported as warnings.
+    #[arg(long = "enricher", env = "TRUSTD_ENRICHERS", value_delimiter = ',')]
+    pub enrichers: Vec<BuiltinEnricher>,
+
+    /// The catalog of known exploited vulnerabilities, used by the `kev` enricher.
+    #[arg(long, env = "TRUSTD_KEV_CATALOG_URL", default_value = kev::DEFAULT_CATALOG_URL)]
+    pub kev_catalog_url: url::Url,
+
+    /// A PEM encoded PKCS#8 Ed25519 key. If set, selected responses get signed using this key,
+    /// attaching a detached JWS to them.
+    #[arg(long, env = "TRUSTD_RESPONSE_SIGNING_KEY")]
+    pub response_signing_key: Option<PathBuf>,
+
+    /// Only mark SBOMs and advisories as deleted when deleting them, instead of removing them.
+    /// Deleted documents get hidden, but can be restored later on.
+    #[arg(long, env = "TRUSTD_SOFT_DELETE", default_value_t = false)]
+    pub soft_delete: bool,
+
+    /// The secret signing the tokens, which confirm deleting all advisories matching a query. If
+    /// unset, a random secret is used, and tokens are only valid for the instance issuing them.
+    /// Required when running more than one replica.
+    #[arg(long, env = "TRUSTD_DELETION_SECRET")]
+    pub deletion_secret: Option<String>,
+
+    /// The number of replicas of this instance, serving the same database. With more than one,
+    /// state which must be shared between the replicas must be configured.
+    #[arg(long, env = "TRUSTD_REPLICAS", default_value_t = 1)]
+    pub replicas: usize,
+
+    /// Isolate tenants from each other. The tenant of a user is taken from their access token,
+    /// using the tenant selector of the authenticator.
+    #[arg(long, env = "TRUSTD_TENANT_ISOLATION", default_value_t = false)]
+    pub tenant_isolation: bool,
+
+    /// Hosts which webhooks of subscriptions may reach, even though they are loopback, private, or
+    /// link-local addresses, or resolve to such.
+    #[arg(
+        long = "webhook-allowed-host",
+        env = "TRUSTD_WEBHOOK_ALLOWED_HOSTS",
+        value_delimiter = ','
+    )]
+    pub webhook_allowed_hosts: Vec<String>,
+
+    /// Interval of refreshing the summary counts, served by the stats endpoint
+    #[arg(long, env = "TRUSTD_STATS_REFRESH_INTERVAL", default_value = "5m")]
+    pub stats_refresh_interval: humantime::Duration,
+
     // flattened commands must go last
     //
     /// Analysis configuration
@@ -119,8 +290,18 @@ pub struct Run {
     #[command(flatten)]
     pub swagger_ui_oidc: SwaggerUiOidcConfig,
 
+    #[command(flatten)]
+    pub telemetry: TelemetryConfig,
+
+    #[command(flatten)]
+    pub events: EventsConfig,
+
     #[command(flatten)]
     pub ui: UiConfig,
+
+    #[cfg(feature = "ai")]
+    #[command(flatten)]
+    pub ai: AiConfig,
 }
 
 mod default {
@@ -138,6 +319,26 @@ mod default {
     pub const fn dataset_entry_limit() -> BinaryByteSize {
         BinaryByteSize(ByteSize::gib(1))
     }
+
+    pub const fn dataset_upload_limit() -> BinaryByteSize {
+        BinaryByteSize(ByteSize::gib(1))
+    }
+
+    pub const fn dataset_concurrency() -> usize {
+        4
+    }
+
+    pub const fn compression_ratio_limit() -> usize {
+        1000
+    }
+
+    pub const fn embedded_item_limit() -> usize {
+        1000
+    }
+
+    pub const fn ingestion_workers() -> usize {
+        1
+    }
 }
 
 #[derive(clap::Args, Debug, Clone)]
@@ -158,6 +359,70 @@ pub struct UiConfig {
     pub analytics_write_key: Option<String>,
 }
 
+#[cfg(feature = "ai")]
+#[derive(clap::Args, Debug, Clone)]
+#[command(next_help_heading = "AI")]
+#[group(id = "ai")]
+pub struct AiConfig {
+    /// The backend providing the LLM of the AI assistant: `openai` (any OpenAI compatible API),
+    /// `ollama`, `azure` (Azure OpenAI), or `noop` (only for testing)
+    #[arg(id = "ai-backend", long, env = "TRUSTD_AI_BACKEND", default_value_t = LlmBackend::OpenAi)]
+    pub backend: LlmBackend,
+    /// Base URL of the API, defaults depending on the backend
+    #[arg(id = "ai-api-base", long, env = "OPENAI_API_BASE")]
+    pub api_base: Option<String>,
+    /// API key, required for the `openai` and `azure` backends
+    #[arg(id = "ai-api-key", long, env = "OPENAI_API_KEY")]
+    pub api_key: Option<String>,
+    /// The model to use, or the name of the deployment for the `azure` backend
+    #[arg(id = "ai-model", long, env = "OPENAI_MODEL")]
+    pub model: Option<String>,
+    /// Version of the API, only used by the `azure` backend
+    #[arg(id = "ai-api-version", long, env = "TRUSTD_AI_API_VERSION")]
+    pub api_version: Option<String>,
+    /// The sampling temperature
+    #[arg(
+        id = "ai-temperature",
+        long,
+        env = "TRUSTD_AI_TEMPERATURE",
+        default_value_t = 0.0
+    )]
+    pub temperature: f32,
+    /// The maximum number of tokens generated per completion
+    #[arg(
+        id = "ai-max-tokens",
+        long,
+        env = "TRUSTD_AI_MAX_TOKENS",
+        default_value_t = 1000
+    )]
+    pub max_tokens: u32,
+}
+
+#[cfg(feature = "ai")]
+impl From<AiConfig> for LlmConfig {
+    fn from(config: AiConfig) -> Self {
+        let AiConfig {
+            backend,
+            api_base,
+            api_key,
+            model,
+            api_version,
+            temperature,
+            max_tokens,
+        } = config;
+
+        Self {
+            backend,
+            api_base,
+            api_key,
+            model,
+            api_version,
+            temperature,
+            max_tokens,
+        }
+    }
+}
+
 const SERVICE_ID: &str = "trustify";
 
 struct InitData {
@@ -175,6 +440,10 @@ struct InitData {
     with_graphql: bool,
     config: ModuleConfig,
     analysis: AnalysisService,
+    ingestion_workers: usize,
+    stats_refresh_interval: Duration,
+    telemetry: TelemetryService,
+    events: EventRelay,
 }
 
 /// Groups all module configurations.
@@ -231,6 +500,17 @@ impl InitData {
             false => None,
         };
 
+        if run.telemetry.enabled && run.telemetry.endpoint.is_none() {
+            anyhow::bail!("Telemetry is enabled, but no endpoint is configured");
+        }
+
+        if run.replicas > 1 && run.deletion_secret.is_none() {
+            anyhow::bail!(
+                "Running {} replicas, but no deletion secret is configured",
+                run.replicas
+            );
+        }
+
         let db = db::Database::new(&run.database).await?;
 
         if run.devmode {
@@ -241,6 +521,8 @@ impl InitData {
             sample_data(db.clone()).await?;
         }
 
+        DeletionService::fail_interrupted(&db, run.replicas <= 1).await?;
+
         let check = Local::spawn_periodic("no database connection", Duration::from_secs(1), {
             let db = db.clone();
             move || {
@@ -274,6 +556,26 @@ impl InitData {
             ),
         };
 
+        // only report if features are used, never their configuration
+        let telemetry = TelemetryService::new(
+            db.clone(),
+            run.telemetry,
+            [
+                ("authentication", authenticator.is_some()),
+                ("graphql", run.with_graphql),
+                ("s3Storage", matches!(storage, DispatchBackend::S3(_))),
+                ("labelTemplates", !run.label_templates.is_empty()),
+                ("organizationAliases", !run.organization_aliases.is_empty()),
+                ("osvFallback", run.osv_fallback_url.is_some()),
+                ("asyncIngestion", run.ingestion_workers > 0),
+                ("enrichment", run.enrichment_url.is_some()),
+                ("enrichers", !run.enrichers.is_empty()),
+                ("responseSigning", run.response_signing_key.is_some()),
+                ("events", run.events.broker.is_some()),
+                ("uiAnalytics", run.ui.analytics_write_key.is_some()),
+            ],
+        );
+
         let ui = UI {
             version: env!("CARGO_PKG_VERSION").to_string(),
             auth_required: authenticator.is_some().to_string(),
@@ -284,13 +586,76 @@ impl InitData {
             analytics_write_key: run.ui.analytics_write_key.unwrap_or_default(),
         };
 
+        let label_templates = LabelTemplates::from(run.label_templates);
+        let organization_aliases = OrganizationAliases::from(run.organization_aliases);
+
+        let mut enrichers = Enrichers::new();
+        if let Some(url) = run.enrichment_url {
+            enrichers = enrichers.with(WebhookEnricher::new(WebhookConfig {
+                url,
+                timeout: run.enrichment_timeout.into(),
+                policy: run.enrichment_failure_policy,
+            })?);
+        }
+        for enricher in &run.enrichers {
+            enrichers = match enricher {
+                BuiltinEnricher::Kev => {
+                    enrichers.with(KevEnricher::new(run.kev_catalog_url.clone())?)
+                }
+            };
+        }
+
+        let signer = ResponseSigner::new(
+            run.response_signing_key
+                .map(SigningKey::from_file)
+                .transpose()?,
+        );
+        if let Some(kid) = signer.kid() {
+            log::info!("Signing responses using key: {kid}");
+        }
+
+        // publish the events recorded in the outbox, or only drop expired ones if disabled
+        let events = EventRelay::new(
+            db.clone(),
+            publisher::from_config(&run.events).await?,
+            run.events,
+        );
+
+        // notify subscribers of saved searches about newly ingested documents
+        let webhook_allowlist = WebhookAllowlist(run.webhook_allowed_hosts);
+        let listeners = IngestListeners::new().with(SubscriptionListener::new(
+            db.clone(),
+            webhook_allowlist.clone(),
+        )?);
+
         let config = ModuleConfig {
             fundamental: trustify_module_fundamental::endpoints::Config {
                 sbom_upload_limit: run.sbom_upload_limit.into(),
                 advisory_upload_limit: run.advisory_upload_limit.into(),
+                compression_ratio_limit: run.compression_ratio_limit,
+                label_templates: label_templates.clone(),
+                osv_fallback_url: run.osv_fallback_url,
+                embedded_item_limit: run.embedded_item_limit,
+                enrichers: enrichers.clone(),
+                listeners: listeners.clone(),
+                organization_aliases: organization_aliases.clone(),
+                signer,
+                soft_delete: run.soft_delete,
+                deletion_secret: run.deletion_secret,
+                tenant_isolation: run.tenant_isolation,
+                webhook_allowlist,
+                #[cfg(feature = "ai")]
+                ai: run.ai.into(),
             },
             ingestor: trustify_module_ingestor::endpoints::Config {
                 dataset_entry_limit: run.dataset_entry_limit.into(),
+                dataset_upload_limit: run.dataset_upload_limit.into(),
+                compression_ratio_limit: run.compression_ratio_limit,
+                dataset_concurrency: run.dataset_concurrency,
+                label_templates,
+                enrichers,
+                listeners,
+                organization_aliases,
             },
         };
 
@@ -309,6 +674,10 @@ impl InitData {
             embedded_oidc,
             ui,
             with_graphql: run.with_graphql,
+            ingestion_workers: run.ingestion_workers,
+            stats_refresh_interval: run.stats_refresh_interval.into(),
+            telemetry,
+            events,
         })
     }
 
@@ -316,6 +685,21 @@ impl InitData {
         let ui = Arc::new(UiResources::new(&self.ui)?);
         let db = self.db.clone();
         let storage = self.storage.clone();
+        let telemetry = self.telemetry.clone();
+
+        // process documents uploaded for asynchronous ingestion
+        let jobs = (self.ingestion_workers > 0).then(|| {
+            let ingestor = IngestorService::new(
+                Graph::new(db.clone())
+                    .with_organization_aliases(self.config.ingestor.organization_aliases.clone()),
+                storage.clone(),
+                Some(self.analysis.clone()),
+            )
+            .with_label_templates(self.config.ingestor.label_templates.clone())
+            .with_enrichers(self.config.ingestor.enrichers.clone())
+            .with_listeners(self.config.ingestor.listeners.clone());
+            JobRunner::new(ingestor, self.ingestion_workers)
+        });
 
         let http = {
             HttpServerBuilder::try_from(self.http)?
@@ -333,6 +717,7 @@ impl InitData {
                             storage: self.storage.clone(),
                             auth: self.authenticator.clone(),
                             analysis: self.analysis.clone(),
+                            telemetry: self.telemetry.clone(),
 
                             with_graphql: self.with_graphql,
                         },
@@ -356,6 +741,22 @@ impl InitData {
             );
         }
 
+        if let Some(jobs) = jobs {
+            tasks.push(jobs.run().boxed_local());
+        }
+
+        tasks.push(
+            StatsService::new(db.clone())
+                .run(self.stats_refresh_interval)
+                .boxed_local(),
+        );
+
+        tasks.push(self.events.run().boxed_local());
+
+        if telemetry.config().enabled {
+            tasks.push(telemetry.run().boxed_local());
+        }
+
         let (result, _, _) = futures::future::select_all(tasks).await;
 
         log::info!("one of the server tasks returned, exiting: {result:?}");
@@ -380,6 +781,7 @@ pub(crate) struct Config {
     pub(crate) db: db::Database,
     pub(crate) storage: DispatchBackend,
     pub(crate) analysis: AnalysisService,
+    pub(crate) telemetry: TelemetryService,
     pub(crate) auth: Option<Arc<Authenticator>>,
     pub(crate) with_graphql: bool,
 }
@@ -394,11 +796,26 @@ pub(crate) fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfi
         storage,
         auth,
         analysis,
+        telemetry,
 
         with_graphql,
     } = config;
 
     let graph = Graph::new(db.clone());
+    let signer = fundamental.signer.clone();
+    let capabilities = Capabilities {
+        #[cfg(feature = "ai")]
+        ai: Llm::new(&fundamental.ai).is_some(),
+        #[cfg(not(feature = "ai"))]
+        ai: false,
+        graphql: with_graphql,
+        limits: Limits {
+            sbom_upload: fundamental.sbom_upload_limit,
+            advisory_upload: fundamental.advisory_upload_limit,
+            dataset_upload: ingestor.dataset_upload_limit,
+            dataset_entry: ingestor.dataset_entry_limit,
+        },
+    };
 
     // set global request limits
 
@@ -417,7 +834,11 @@ pub(crate) fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfi
                     .wrap(new_auth(auth.clone()))
                 })
                 .configure(|svc| {
-                    trustify_module_graphql::endpoints::configure(svc, db.clone());
+                    trustify_module_graphql::endpoints::configure(
+                        svc,
+                        db.clone(),
+                        TenantIsolation(fundamental.tenant_isolation),
+                    );
                     trustify_module_graphql::endpoints::configure_graphiql(svc);
                 }),
         );
@@ -427,11 +848,11 @@ pub(crate) fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfi
 
     svc.app_data(graph)
         .configure(|svc| {
-            endpoints::configure(svc, auth.clone());
+            endpoints::configure(svc, auth.clone(), signer);
         })
         .service(
             utoipa_actix_web::scope("/api")
-                .map(|svc| svc.wrap(new_auth(auth)))
+                .map(|svc| svc.wrap(new_auth(auth)).wrap(telemetry.api_calls()))
                 .configure(|svc| {
                     trustify_module_importer::endpoints::configure(svc, db.clone());
                     trustify_module_ingestor::endpoints::configure(
@@ -450,6 +871,9 @@ pub(crate) fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfi
                     );
                     trustify_module_analysis::endpoints::configure(svc, db.clone(), analysis);
                     trustify_module_user::endpoints::configure(svc, db.clone());
+                    trustify_module_settings::endpoints::configure(svc, db.clone());
+                    trustify_module_telemetry::endpoints::configure(svc, telemetry);
+                    endpoints::configure_api(svc, db.clone(), capabilities);
                 }),
         );
 }
@@ -508,6 +932,7 @@ mod test {
         let (storage, _) = FileSystemBackend::for_test().await?;
         let ui = Arc::new(UiResources::new(&UI::default())?);
         let analysis = AnalysisService::new(AnalysisConfig::default());
+        let telemetry = TelemetryService::new(db.clone(), Default::default(), [("graphql", true)]);
         let app = actix_web::test::init_service(
             App::new()
                 .into_utoipa_app()
@@ -521,6 +946,7 @@ mod test {
                             storage: DispatchBackend::Filesystem(storage),
                             auth: None,
                             analysis,
+                            telemetry,
                             with_graphql: true,
                         },
                     );
@@ -582,6 +1008,17 @@ mod test {
         let text = std::str::from_utf8(&body)?;
         assert!(text.contains("items"));
 
+        let req = TestRequest::get().uri("/api/v2/info").to_request();
+        let body = call_and_read_body(&app, req).await;
+        let info: serde_json::Value = serde_json::from_slice(&body)?;
+        assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(info["features"]["graphql"], true);
+        assert!(
+            info["formats"]
+                .as_array()
+                .is_some_and(|formats| formats.iter().any(|f| f["format"] == "spdx"))
+        );
+
         Ok(())
     }
 }
@@ -32,9 +32,10 @@ use trustify_infrastructure::{
     health::checks::{Local, Probe},
     otel::Tracing,
 };
+use trustify_module_fundamental::subscription::service::{SubscriptionListener, WebhookAllowlist};
 use trustify_module_graphql::RootQuery;
 use trustify_module_importer::server::importer;
-use trustify_module_ingestor::graph::Graph;
+use trustify_module_ingestor::{graph::Graph, service::listener::IngestListeners};
 use trustify_module_storage::{
     config::{StorageConfig, StorageStrategy},
     service::{dispatch::DispatchBackend, fs::FileSystemBackend, s3::S3Backend},
@@ -61,6 +62,15 @@ pub struct Run {
     )]
     pub concurrency: usize,
 
+    /// Hosts which webhooks of subscriptions may reach, even though they are loopback, private, or
+    /// link-local addresses, or resolve to such.
+    #[arg(
+        long = "webhook-allowed-host",
+        env = "IMPORTER_WEBHOOK_ALLOWED_HOSTS",
+        value_delimiter = ','
+    )]
+    pub webhook_allowed_hosts: Vec<String>,
+
     // flattened commands must go last
     //
     /// Database configuration
@@ -83,6 +93,7 @@ struct InitData {
     tracing: Tracing,
     working_dir: Option<PathBuf>,
     concurrency: usize,
+    webhook_allowlist: WebhookAllowlist,
 }
 
 impl Run {
@@ -137,12 +148,17 @@ impl InitData {
             storage,
             working_dir: run.working_dir,
             concurrency: run.concurrency,
+            webhook_allowlist: WebhookAllowlist(run.webhook_allowed_hosts),
         })
     }
 
     async fn run(mut self) -> anyhow::Result<()> {
         let db = self.db;
         let storage = self.storage;
+        let listeners = IngestListeners::new().with(SubscriptionListener::new(
+            db.clone(),
+            self.webhook_allowlist,
+        )?);
 
         let importer = async {
             importer(
@@ -150,6 +166,7 @@ impl InitData {
                 storage,
                 self.working_dir,
                 None, // Running the importer, we don't need an analysis graph update
+                listeners,
                 self.concurrency,
             )
             .await
@@ -25,7 +25,7 @@ pub enum Command {
 
 impl Run {
     pub async fn run(self) -> anyhow::Result<ExitCode> {
-        init_tracing("db-run", Tracing::Disabled);
+        init_tracing("db-run", Tracing::Disabled, None);
         use Command::*;
         match self.command {
             Create => self.create().await,
@@ -60,7 +60,7 @@ impl Run {
     }
 
     pub async fn start(&mut self) -> anyhow::Result<PostgreSQL> {
-        init_tracing("db-start", Tracing::Disabled);
+        init_tracing("db-start", Tracing::Disabled, None);
         log::warn!("Setting up managed DB; not suitable for production use!");
 
         let current_dir = env::current_dir()?;